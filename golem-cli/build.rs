@@ -0,0 +1,9 @@
+use std::env::var;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Compiled components are stored per target triple by the worker executor
+    println!("cargo:rustc-env=GOLEM_TARGET_TRIPLE={}", var("TARGET")?);
+    println!("cargo:rerun-if-changed=build.rs");
+
+    Ok(())
+}
@@ -32,6 +32,34 @@ pub trait ComponentClient {
         &self,
         component_urn: &ComponentUrn,
     ) -> Result<Component, GolemError>;
+    async fn get_metadata_by_tag(
+        &self,
+        component_urn: &ComponentUrn,
+        tag: &str,
+    ) -> Result<Component, GolemError>;
+    async fn tag(
+        &self,
+        component_urn: &ComponentUrn,
+        version: u64,
+        tag: &str,
+    ) -> Result<Component, GolemError>;
+    async fn rename(
+        &self,
+        component_urn: &ComponentUrn,
+        name: &ComponentName,
+    ) -> Result<Component, GolemError>;
+    async fn download(
+        &self,
+        component_urn: &ComponentUrn,
+        version: u64,
+    ) -> Result<Vec<u8>, GolemError>;
+    /// Downloads the content of an initial file of the component version, None if it is missing
+    async fn download_file(
+        &self,
+        component_urn: &ComponentUrn,
+        version: u64,
+        key: &str,
+    ) -> Result<Option<Vec<u8>>, GolemError>;
     async fn find(
         &self,
         name: Option<ComponentName>,
@@ -51,6 +79,7 @@ pub trait ComponentClient {
         urn: ComponentUrn,
         file: PathBufOrStdin,
         component_type: Option<ComponentType>,
+        expected_current_version: Option<u64>,
         files_archive: Option<&Path>,
         files_permissions: Option<&ComponentFilePathWithPermissionsList>,
     ) -> Result<Component, GolemError>;
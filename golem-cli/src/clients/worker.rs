@@ -18,7 +18,9 @@ use crate::model::{
     WorkersMetadataResponse,
 };
 use async_trait::async_trait;
-use golem_client::model::{InvokeParameters, InvokeResult, ScanCursor, WorkerFilter, WorkerId};
+use golem_client::model::{
+    ExecutorCompileStatus, InvokeParameters, InvokeResult, ScanCursor, WorkerFilter, WorkerId,
+};
 use golem_common::model::public_oplog::PublicOplogEntry;
 use golem_common::uri::oss::urn::{ComponentUrn, WorkerUrn};
 
@@ -106,6 +108,19 @@ pub trait WorkerClient {
         worker_urn: WorkerUrn,
         query: String,
     ) -> Result<Vec<(u64, PublicOplogEntry)>, GolemError>;
+
+    async fn get_compile_status(
+        &self,
+        component_urn: ComponentUrn,
+        version: u64,
+    ) -> Result<Vec<ExecutorCompileStatus>, GolemError>;
+
+    async fn warm_component(
+        &self,
+        component_urn: ComponentUrn,
+        version: u64,
+        force_recompile: bool,
+    ) -> Result<Vec<ExecutorCompileStatus>, GolemError>;
 }
 
 pub fn worker_name_required(urn: &WorkerUrn) -> Result<String, GolemError> {
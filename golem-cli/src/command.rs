@@ -12,9 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::model::ComponentUriArg;
+use crate::model::{ComponentUriArg, GolemError};
 use crate::oss::model::OssContext;
+use crate::service::component::ComponentService;
+use crate::service::project::ProjectResolver;
 use golem_common::uri::oss::uri::ComponentUri;
+use golem_common::uri::oss::urn::ComponentUrn;
 
 pub mod api_definition;
 pub mod api_deployment;
@@ -31,3 +34,26 @@ impl ComponentRefSplit<OssContext> for ComponentUriArg {
         (self.uri, None)
     }
 }
+
+/// Resolves the component reference of a subcommand to the component and its project. All the
+/// subcommands taking a component go through here, so that they accept the same references:
+/// a name, a URN or URL, or the plain component ID
+pub async fn resolve_component_ref<ProjectRef: Send + Sync + 'static, ProjectContext>(
+    component_ref: impl ComponentRefSplit<ProjectRef>,
+    projects: &(dyn ProjectResolver<ProjectRef, ProjectContext> + Send + Sync),
+) -> Result<(ComponentUri, Option<ProjectContext>), GolemError> {
+    let (component_uri, project_ref) = component_ref.split();
+    let project = projects.resolve_id_or_default_opt(project_ref).await?;
+    Ok((component_uri, project))
+}
+
+/// Like `resolve_component_ref`, also looking up the ID of a component referenced by its name
+pub async fn resolve_component_urn<ProjectRef: Send + Sync + 'static, ProjectContext>(
+    component_ref: impl ComponentRefSplit<ProjectRef>,
+    projects: &(dyn ProjectResolver<ProjectRef, ProjectContext> + Send + Sync),
+    service: &(dyn ComponentService<ProjectContext = ProjectContext> + Send + Sync),
+) -> Result<(ComponentUrn, Option<ProjectContext>), GolemError> {
+    let (component_uri, project) = resolve_component_ref(component_ref, projects).await?;
+    let component_urn = service.resolve_uri(component_uri, &project).await?;
+    Ok((component_urn, project))
+}
@@ -12,19 +12,39 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::command::ComponentRefSplit;
-use crate::model::application_manifest::load_app;
+use crate::command::{resolve_component_ref, resolve_component_urn, ComponentRefSplit};
+use crate::compat::process_check_compat;
+use crate::compile::{process_compile, EngineProfile};
+use crate::download_files::process_download_files;
+use crate::export_manifest::process_export_manifest;
+use crate::import_manifest::process_import_manifest;
+use crate::inspect::process_print;
+use crate::load_local::process_load_local;
+use crate::model::application_manifest::{load_app, DownloadableFile, InitialComponentFile};
+use crate::model::component::{ComponentField, ComponentTimings, VersionSelector};
 use crate::model::{
     ComponentName, Format, GolemError, GolemResult, PathBufOrStdin, WorkerUpdateMode,
 };
 use crate::service::component::ComponentService;
 use crate::service::deploy::DeployService;
 use crate::service::project::ProjectResolver;
+use crate::service::worker::WorkerService;
+use crate::store_check::process_store_check;
+use crate::validate_app::process_validate_app;
+use crate::verify::process_verify_all;
 use clap::Subcommand;
 use golem_client::model::ComponentType;
+use golem_common::model::{
+    ComponentFilePath, ComponentFilePathWithPermissions, ComponentFilePermissions,
+};
+use golem_common::uri::oss::uri::ComponentUri;
+use golem_common::uri::oss::url::ComponentUrl;
 use golem_wasm_rpc_stubgen::commands::declarative::ApplicationResolveMode;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
+use url::Url;
+use uuid::Uuid;
 
 #[derive(Subcommand, Debug)]
 #[command()]
@@ -68,9 +88,23 @@ pub enum ComponentSubCommand<ProjectRef: clap::Args, ComponentRef: clap::Args> {
         #[arg(long, short, conflicts_with_all = vec!["component_file", "component-type-flag"], verbatim_doc_comment)]
         app: Vec<PathBuf>,
 
+        /// Initial file of the component, as `path:target:permissions`. Can be specified multiple times.
+        /// The local path must exist, the permissions are `ro` (read-only) or `rw` (read-write).
+        ///
+        /// Requires the `component-file` argument.
+        #[arg(long = "file", value_name = "path:target:permissions", value_parser = parse_initial_file, requires = "component_file", verbatim_doc_comment)]
+        files: Vec<InitialComponentFile>,
+
         /// Do not ask for confirmation for performing an update in case the component already exists
         #[arg(short = 'y', long)]
         non_interactive: bool,
+
+        /// Print how long reading, uploading and the server's processing of the component took
+        #[arg(long)]
+        timings: bool,
+
+        #[command(flatten)]
+        wait_compiled: WaitCompiledArg,
     },
     /// Updates an existing component by uploading a new version of its WASM
     ///
@@ -104,6 +138,13 @@ pub enum ComponentSubCommand<ProjectRef: clap::Args, ComponentRef: clap::Args> {
         #[arg(long, short)]
         app: Vec<PathBuf>,
 
+        /// Initial file of the component, as `path:target:permissions`. Can be specified multiple times.
+        /// The local path must exist, the permissions are `ro` (read-only) or `rw` (read-write).
+        ///
+        /// Requires the `component-file` argument.
+        #[arg(long = "file", value_name = "path:target:permissions", value_parser = parse_initial_file, requires = "component_file", verbatim_doc_comment)]
+        files: Vec<InitialComponentFile>,
+
         /// Try to automatically update all existing workers to the new version
         #[arg(long, default_value_t = false)]
         try_update_workers: bool,
@@ -115,6 +156,15 @@ pub enum ComponentSubCommand<ProjectRef: clap::Args, ComponentRef: clap::Args> {
         /// Do not ask for confirmation for creating a new component in case it does not exist
         #[arg(short = 'y', long)]
         non_interactive: bool,
+
+        /// Only update the component if its latest version is this one, so that a concurrent
+        /// update is not overwritten. The component is never created when this is set.
+        #[arg(long)]
+        expected_current_version: Option<u64>,
+
+        /// Print how long reading, uploading and the server's processing of the component took
+        #[arg(long)]
+        timings: bool,
     },
     /// Lists the existing components
     #[command()]
@@ -134,9 +184,56 @@ pub enum ComponentSubCommand<ProjectRef: clap::Args, ComponentRef: clap::Args> {
         #[command(flatten)]
         component_name_or_uri: ComponentRef,
 
-        /// The version of the component
+        /// The version of the component, or a tag pointing to it
         #[arg(short = 't', long)]
-        version: Option<u64>,
+        version: Option<VersionSelector>,
+
+        /// Only output the given comma separated fields, such as `version,size,component_type`
+        #[arg(long, value_delimiter = ',')]
+        fields: Vec<ComponentField>,
+    },
+    /// Tag a version of a component with a human-friendly name, such as `stable`
+    #[command()]
+    Tag {
+        /// The Golem component
+        #[command(flatten)]
+        component_name_or_uri: ComponentRef,
+
+        /// The version of the component to tag
+        #[arg(short = 't', long)]
+        version: u64,
+
+        /// The tag to set. If it already exists, it is moved to the given version
+        #[arg(long)]
+        tag: String,
+    },
+    /// Rename a component, keeping all of its versions and workers
+    #[command()]
+    Rename {
+        /// The component to rename
+        #[command(flatten)]
+        component_name_or_uri: ComponentRef,
+
+        /// The new name of the component. It must not be taken by another component
+        #[arg(long)]
+        new_name: ComponentName,
+
+        /// Do not ask for confirmation
+        #[arg(short = 'y', long)]
+        non_interactive: bool,
+    },
+    /// Download a version of a component and print it
+    ///
+    /// The component is printed as WAT in text format, and as the list of its sections in JSON and YAML format.
+    #[command(alias = "open", verbatim_doc_comment)]
+    Print {
+        /// The Golem component
+        #[command(flatten)]
+        component_name_or_uri: ComponentRef,
+
+        /// The version of the component, or a tag pointing to it. Defaults to the latest version
+        #[arg(short = 't', long)]
+        version: Option<VersionSelector>,
     },
     /// Try to automatically update all existing workers to the latest version
     #[command()]
@@ -160,27 +257,279 @@ pub enum ComponentSubCommand<ProjectRef: clap::Args, ComponentRef: clap::Args> {
         #[arg(short = 'y', long)]
         non_interactive: bool,
     },
+    /// Compiles a component WASM ahead of time, without connecting to Golem
+    ///
+    /// The compiled artifact is written into the compilation cache of a local file system blob storage,
+    /// where the worker executor picks it up instead of compiling the component itself.
+    #[command(verbatim_doc_comment)]
+    Compile {
+        /// The WASM file to compile
+        #[arg(value_name = "component-file", value_hint = clap::ValueHint::FilePath)]
+        component_file: PathBuf,
+
+        /// ID of the component the WASM file belongs to
+        #[arg(long)]
+        component_id: Uuid,
+
+        /// The version of the component
+        #[arg(short = 't', long)]
+        version: u64,
+
+        /// The engine configuration to compile for
+        #[arg(long, value_enum, default_value = "worker-executor")]
+        engine_profile: EngineProfile,
+
+        /// Root directory of the blob storage
+        #[arg(short, long, value_hint = clap::ValueHint::DirPath)]
+        output_dir: PathBuf,
+    },
+    /// Loads a component WASM directly into the local store of a running worker executor
+    ///
+    /// Only works with a worker executor using the local file system component service, and does not need a component service.
+    /// The component gets the next version in the local store, and the next invocation of a worker picks it up.
+    #[command(verbatim_doc_comment)]
+    LoadLocal {
+        /// The WASM file to load, or `-` to read it from stdin
+        #[arg(long, value_name = "component-file", value_hint = clap::ValueHint::FilePath)]
+        from: PathBufOrStdin,
+
+        /// ID of the component to load a new version of. Defaults to a new component
+        #[arg(long)]
+        component_id: Option<Uuid>,
+
+        #[command(flatten)]
+        component_type: ComponentTypeArg,
+
+        /// URL of the worker executor's HTTP interface
+        #[arg(long, default_value = "http://localhost:8082")]
+        executor_url: Url,
+    },
+    /// Checks whether a local WASM is API-compatible with the latest deployed version of a component
+    ///
+    /// Removed exports and exports with a changed signature are reported as breaking changes,
+    /// and the command fails if there are any, unless `allow-breaking` is set.
+    #[command(verbatim_doc_comment)]
+    CheckCompat {
+        /// The deployed component to compare with
+        #[command(flatten)]
+        component_name_or_uri: ComponentRef,
+
+        /// The local WASM file to check
+        #[arg(value_name = "component-file", value_hint = clap::ValueHint::FilePath)]
+        component_file: PathBuf,
+
+        /// Do not fail if there are breaking changes
+        #[arg(long, default_value_t = false)]
+        allow_breaking: bool,
+    },
+    /// Checks that the latest version of every component in a project compiles
+    ///
+    /// Each component is downloaded and compiled with the same engine configuration as the worker executor,
+    /// reporting the result and the compilation time per component. The command fails if any of them does not compile.
+    #[command(verbatim_doc_comment)]
+    VerifyAll {
+        /// The project to verify the components of
+        #[command(flatten)]
+        project_ref: ProjectRef,
+
+        /// The engine configuration to compile with
+        #[arg(long, value_enum, default_value = "worker-executor")]
+        engine_profile: EngineProfile,
+    },
+    /// Shows the result of compiling a version of a component on each worker executor
+    ///
+    /// For every worker executor, prints whether the version compiled, how long the compilation took and the error if it failed.
+    /// Executors which did not compile the version since it got evicted from their caches show it as not compiled.
+    #[command(verbatim_doc_comment)]
+    CompileStatus {
+        /// The Golem component
+        #[command(flatten)]
+        component_name_or_uri: ComponentRef,
+
+        /// The version of the component. Defaults to the latest version
+        #[arg(short = 't', long)]
+        version: Option<u64>,
+    },
+    /// Loads and compiles a version of a component on every worker executor
+    ///
+    /// Useful for compiling a component ahead of its first invocation, for example after an upgrade of the executors.
+    /// With --force-recompile the cached compilation results are discarded and the component is compiled again.
+    /// Prints the compilation result of each worker executor.
+    #[command(verbatim_doc_comment)]
+    Warm {
+        /// The Golem component
+        #[command(flatten)]
+        component_name_or_uri: ComponentRef,
+
+        /// The version of the component. Defaults to the latest version
+        #[arg(short = 't', long)]
+        version: Option<u64>,
+
+        /// Discard the cached compilation results and compile the component again
+        #[arg(long, default_value_t = false)]
+        force_recompile: bool,
+    },
+    /// Downloads all initial files of a version of a component into a local directory
+    ///
+    /// The files are written in the same layout as in the filesystem of the workers, and read-only files are made read-only.
+    /// Files already downloaded with the same content are kept, and files whose content is missing on the server are reported.
+    #[command(verbatim_doc_comment)]
+    DownloadFiles {
+        /// The Golem component
+        #[command(flatten)]
+        component_name_or_uri: ComponentRef,
+
+        /// The version of the component, or a tag pointing to it. Defaults to the latest version
+        #[arg(short = 't', long)]
+        version: Option<VersionSelector>,
+
+        /// The directory to write the files into
+        #[arg(short, long, value_hint = clap::ValueHint::DirPath)]
+        output_dir: PathBuf,
+    },
+    /// Exports the metadata of every version of every component in a project as a manifest
+    ///
+    /// The manifest contains the ids, versions, types, sizes, WASM hashes, initial files and metadata of the components.
+    /// It is written as JSON if the output file has the `.json` extension, and as YAML otherwise.
+    #[command(verbatim_doc_comment)]
+    ExportManifest {
+        /// The project to export the components of
+        #[command(flatten)]
+        project_ref: ProjectRef,
+
+        /// The file to write the manifest to
+        #[arg(short, long, value_hint = clap::ValueHint::FilePath)]
+        output: PathBuf,
+    },
+    /// Creates the components and versions of a manifest written by `export-manifest`
+    ///
+    /// Components are matched by name, and versions which already exist are skipped.
+    /// The `wasm` location of each version to import, and the `source` of its initial files, have to be added to the manifest,
+    /// as http(s) URLs or paths relative to the manifest. The WASM files are checked against the hashes in the manifest.
+    #[command(verbatim_doc_comment)]
+    ImportManifest {
+        /// The manifest file, in JSON if it has the `.json` extension, and in YAML otherwise
+        #[arg(value_name = "manifest", value_hint = clap::ValueHint::FilePath)]
+        manifest: PathBuf,
+
+        /// The project to import the components into
+        #[command(flatten)]
+        project_ref: ProjectRef,
+
+        /// Only report what would be imported, without creating anything
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+    },
+    /// Validates the app manifest without building or deploying anything
+    ///
+    /// Checks that the manifest is valid, and that the output WASM of every component in it exists and is a valid component,
+    /// along with the local initial files of the components. All problems are reported at once, and the command fails if there are any.
+    #[command(verbatim_doc_comment)]
+    ValidateApp {
+        /// Application manifest to validate. Can be specified multiple times.
+        /// If not specified, the manifest is looked for in the current directory and all parent directories.
+        #[arg(long, short, verbatim_doc_comment)]
+        app: Vec<PathBuf>,
+    },
+    /// Checks the consistency of the component store of a worker executor using the local file system component source
+    ///
+    /// Every WASM or patch file must have a properties file which parses and matches it, no two files may resolve to the same version,
+    /// every properties file must belong to a stored version, and the initial files of the components must exist in the blob storage.
+    /// All problems are reported at once, and the command fails if any of them remains.
+    #[command(verbatim_doc_comment)]
+    StoreCheck {
+        /// The directory of the component store
+        #[arg(value_name = "root", value_hint = clap::ValueHint::DirPath)]
+        root: PathBuf,
+
+        /// The directory of the properties files, if they are not stored next to the WASMs
+        #[arg(long, value_hint = clap::ValueHint::DirPath)]
+        props_root: Option<PathBuf>,
+
+        /// The root of the file system blob storage, to check that the initial files of the components exist in it
+        #[arg(long, value_hint = clap::ValueHint::DirPath)]
+        blob_root: Option<PathBuf>,
+
+        /// Regenerate missing properties files of full WASMs by analysing them
+        #[arg(long, default_value_t = false)]
+        fix: bool,
+    },
 }
 
 #[derive(clap::Args, Debug, Clone)]
 #[group(required = false, multiple = false)]
 pub struct ComponentTypeArg {
-    /// Create an Ephemeral component. If not specified, the command creates a Durable component.
+    /// Create an Ephemeral component. If neither flag is specified, the profile's default component type is used, which is Durable unless configured otherwise.
     #[arg(long, group = "component-type-flag")]
     ephemeral: bool,
 
-    /// Create a Durable component. This is the default.
+    /// Create a Durable component.
     #[arg(long, group = "component-type-flag")]
     durable: bool,
 }
 
 impl ComponentTypeArg {
-    pub fn component_type(&self) -> ComponentType {
+    pub fn component_type(&self, default: ComponentType) -> ComponentType {
         if self.ephemeral {
             ComponentType::Ephemeral
-        } else {
+        } else if self.durable {
             ComponentType::Durable
+        } else {
+            default
+        }
+    }
+}
+
+#[derive(clap::Args, Debug, Clone)]
+pub struct WaitCompiledArg {
+    /// Wait until the worker executors compiled the new version of the component
+    ///
+    /// Asks every worker executor to compile the version, then waits until a quorum of them report it compiled.
+    /// Fails if the quorum is not reached within the timeout, or as soon as too many executors failed to compile it.
+    #[arg(long, verbatim_doc_comment)]
+    wait_compiled: bool,
+
+    /// The number of worker executors which have to compile the component. Defaults to a majority of them
+    #[arg(long, value_name = "executors", requires = "wait_compiled")]
+    compiled_quorum: Option<usize>,
+
+    /// How long to wait for the worker executors to compile the component, in seconds
+    #[arg(
+        long,
+        value_name = "seconds",
+        default_value_t = 60,
+        requires = "wait_compiled"
+    )]
+    wait_compiled_timeout: u64,
+}
+
+impl WaitCompiledArg {
+    /// Waits for the given version of the component if requested, adding the compile status of
+    /// the executors to the result
+    async fn wait<ProjectContext: Send + Sync>(
+        &self,
+        result: GolemResult,
+        worker_service: &(dyn WorkerService<ProjectContext = ProjectContext> + Send + Sync),
+        component_name: ComponentName,
+        version: u64,
+        project: Option<ProjectContext>,
+    ) -> Result<GolemResult, GolemError> {
+        if !self.wait_compiled {
+            return Ok(result);
         }
+
+        let status = worker_service
+            .wait_compiled(
+                ComponentUri::URL(ComponentUrl {
+                    name: component_name.0,
+                }),
+                Some(version),
+                self.compiled_quorum,
+                Duration::from_secs(self.wait_compiled_timeout),
+                project,
+            )
+            .await?;
+        Ok(result.merge(status))
     }
 }
 
@@ -216,8 +565,10 @@ impl<
     pub async fn handle<ProjectContext: Clone + Send + Sync>(
         self,
         format: Format,
+        default_component_type: ComponentType,
         service: Arc<dyn ComponentService<ProjectContext = ProjectContext> + Send + Sync>,
         deploy_service: Arc<dyn DeployService<ProjectContext = ProjectContext> + Send + Sync>,
+        worker_service: Arc<dyn WorkerService<ProjectContext = ProjectContext> + Send + Sync>,
         projects: &(dyn ProjectResolver<ProjectRef, ProjectContext> + Send + Sync),
     ) -> Result<GolemResult, GolemError> {
         match self {
@@ -227,21 +578,34 @@ impl<
                 component_file: Some(component_file),
                 component_type,
                 app: _,
+                files,
                 non_interactive,
+                timings: show_timings,
+                wait_compiled,
             } => {
                 let project_id = projects.resolve_id_or_default(project_ref).await?;
 
-                service
+                let upload = service
                     .add(
-                        component_name,
+                        component_name.clone(),
                         component_file,
-                        component_type.component_type(),
-                        Some(project_id),
+                        component_type.component_type(default_component_type),
+                        Some(project_id.clone()),
                         non_interactive,
                         format,
-                        vec![],
+                        files,
                     )
-                    .await
+                    .await?;
+                let result = wait_compiled
+                    .wait(
+                        upload.result,
+                        worker_service.as_ref(),
+                        component_name,
+                        upload.version,
+                        Some(project_id),
+                    )
+                    .await?;
+                Ok(with_timings(result, upload.timings, show_timings))
             }
             ComponentSubCommand::Add {
                 project_ref,
@@ -249,7 +613,10 @@ impl<
                 component_file: None,
                 component_type: _,
                 app,
+                files: _,
                 non_interactive,
+                timings: show_timings,
+                wait_compiled,
             } => {
                 let project_id = projects.resolve_id_or_default(project_ref).await?;
 
@@ -274,48 +641,63 @@ impl<
                 let component_file =
                     PathBufOrStdin::Path(app.component_output_wasm(&component_name.0));
 
-                service
+                let upload = service
                     .add(
-                        component_name,
+                        component_name.clone(),
                         component_file,
                         component.component_type,
-                        Some(project_id),
+                        Some(project_id.clone()),
                         non_interactive,
                         format,
                         component.files.clone(),
                     )
-                    .await
+                    .await?;
+                let result = wait_compiled
+                    .wait(
+                        upload.result,
+                        worker_service.as_ref(),
+                        component_name,
+                        upload.version,
+                        Some(project_id),
+                    )
+                    .await?;
+                Ok(with_timings(result, upload.timings, show_timings))
             }
             ComponentSubCommand::Update {
                 component_name_or_uri,
                 component_file: Some(component_file),
                 component_type,
                 app: _,
+                files,
                 try_update_workers,
                 update_mode,
                 non_interactive,
+                expected_current_version,
+                timings: show_timings,
             } => {
-                let (component_name_or_uri, project_ref) = component_name_or_uri.split();
-                let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
-                let mut result = service
+                let (component_name_or_uri, project_id) =
+                    resolve_component_ref(component_name_or_uri, projects).await?;
+                let upload = service
                     .update(
                         component_name_or_uri.clone(),
                         component_file,
                         component_type.optional_component_type(),
+                        expected_current_version,
                         project_id.clone(),
                         non_interactive,
                         format,
-                        vec![],
+                        files,
                     )
                     .await?;
 
+                let mut result = upload.result;
                 if try_update_workers {
                     let deploy_result = deploy_service
                         .try_update_all_workers(component_name_or_uri, project_id, update_mode)
                         .await?;
                     result = result.merge(deploy_result);
                 }
-                Ok(result)
+                Ok(with_timings(result, upload.timings, show_timings))
             }
             ComponentSubCommand::Update {
                 component_name_or_uri,
@@ -323,17 +705,19 @@ impl<
                 component_file: None,
                 component_type: _,
                 app,
+                files: _,
                 try_update_workers,
                 update_mode,
+                expected_current_version,
+                timings: show_timings,
             } => {
-                let (component_name_or_uri, project_ref) = component_name_or_uri.split();
+                let (component_name_or_uri, project_id) =
+                    resolve_component_ref(component_name_or_uri, projects).await?;
 
                 let component_name = service
                     .resolve_component_name(&component_name_or_uri)
                     .await?;
 
-                let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
-
                 let app_resolve_mode = if app.is_empty() {
                     ApplicationResolveMode::Automatic
                 } else {
@@ -355,11 +739,12 @@ impl<
                 let component_file =
                     PathBufOrStdin::Path(app.component_output_wasm(&component_name));
 
-                let mut result = service
+                let upload = service
                     .update(
                         component_name_or_uri.clone(),
                         component_file,
                         Some(component.component_type),
+                        expected_current_version,
                         project_id.clone(),
                         non_interactive,
                         format,
@@ -367,13 +752,14 @@ impl<
                     )
                     .await?;
 
+                let mut result = upload.result;
                 if try_update_workers {
                     let deploy_result = deploy_service
                         .try_update_all_workers(component_name_or_uri, project_id, update_mode)
                         .await?;
                     result = result.merge(deploy_result);
                 }
-                Ok(result)
+                Ok(with_timings(result, upload.timings, show_timings))
             }
             ComponentSubCommand::List {
                 project_ref,
@@ -385,19 +771,68 @@ impl<
             ComponentSubCommand::Get {
                 component_name_or_uri,
                 version,
+                fields,
             } => {
-                let (component_name_or_uri, project_ref) = component_name_or_uri.split();
-                let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
-                service
+                let (component_name_or_uri, project_id) =
+                    resolve_component_ref(component_name_or_uri, projects).await?;
+                let result = service
                     .get(component_name_or_uri, version, project_id)
+                    .await?;
+                if fields.is_empty() {
+                    Ok(result)
+                } else {
+                    Ok(GolemResult::Json(ComponentField::select(
+                        &fields,
+                        &result.as_json_value(),
+                    )))
+                }
+            }
+            ComponentSubCommand::Tag {
+                component_name_or_uri,
+                version,
+                tag,
+            } => {
+                let (component_name_or_uri, project_id) =
+                    resolve_component_ref(component_name_or_uri, projects).await?;
+                service
+                    .tag(component_name_or_uri, version, tag, project_id)
                     .await
             }
+            ComponentSubCommand::Rename {
+                component_name_or_uri,
+                new_name,
+                non_interactive,
+            } => {
+                let (component_name_or_uri, project_id) =
+                    resolve_component_ref(component_name_or_uri, projects).await?;
+                service
+                    .rename(
+                        component_name_or_uri,
+                        new_name,
+                        project_id,
+                        non_interactive,
+                        format,
+                    )
+                    .await
+            }
+            ComponentSubCommand::Print {
+                component_name_or_uri,
+                version,
+            } => {
+                let (component_name_or_uri, project_id) =
+                    resolve_component_ref(component_name_or_uri, projects).await?;
+                let bytes = service
+                    .download(component_name_or_uri, version, project_id)
+                    .await?;
+
+                process_print(&bytes, format)
+            }
             ComponentSubCommand::TryUpdateWorkers {
                 component_name_or_uri,
                 update_mode,
             } => {
-                let (component_name_or_uri, project_ref) = component_name_or_uri.split();
-                let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let (component_name_or_uri, project_id) =
+                    resolve_component_ref(component_name_or_uri, projects).await?;
                 deploy_service
                     .try_update_all_workers(component_name_or_uri, project_id, update_mode)
                     .await
@@ -406,12 +841,431 @@ impl<
                 component_name_or_uri,
                 non_interactive,
             } => {
-                let (component_name_or_uri, project_ref) = component_name_or_uri.split();
-                let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let (component_name_or_uri, project_id) =
+                    resolve_component_ref(component_name_or_uri, projects).await?;
                 deploy_service
                     .redeploy(component_name_or_uri, project_id, non_interactive, format)
                     .await
             }
+            ComponentSubCommand::Compile {
+                component_file,
+                component_id,
+                version,
+                engine_profile,
+                output_dir,
+            } => {
+                process_compile(
+                    component_file,
+                    component_id,
+                    version,
+                    engine_profile,
+                    output_dir,
+                )
+                .await
+            }
+            ComponentSubCommand::LoadLocal {
+                from,
+                component_id,
+                component_type,
+                executor_url,
+            } => {
+                process_load_local(
+                    from,
+                    component_id,
+                    component_type.component_type(default_component_type),
+                    executor_url,
+                )
+                .await
+            }
+            ComponentSubCommand::CheckCompat {
+                component_name_or_uri,
+                component_file,
+                allow_breaking,
+            } => {
+                let (urn, _) =
+                    resolve_component_urn(component_name_or_uri, projects, service.as_ref())
+                        .await?;
+                let deployed = service.get_latest_metadata(&urn).await?;
+
+                process_check_compat(component_file, deployed, allow_breaking).await
+            }
+            ComponentSubCommand::VerifyAll {
+                project_ref,
+                engine_profile,
+            } => {
+                let project_id = projects.resolve_id_or_default(project_ref).await?;
+                process_verify_all(service.as_ref(), Some(project_id), engine_profile).await
+            }
+            ComponentSubCommand::CompileStatus {
+                component_name_or_uri,
+                version,
+            } => {
+                let (component_name_or_uri, project_id) =
+                    resolve_component_ref(component_name_or_uri, projects).await?;
+                worker_service
+                    .compile_status(component_name_or_uri, version, project_id)
+                    .await
+            }
+            ComponentSubCommand::Warm {
+                component_name_or_uri,
+                version,
+                force_recompile,
+            } => {
+                let (component_name_or_uri, project_id) =
+                    resolve_component_ref(component_name_or_uri, projects).await?;
+                worker_service
+                    .warm(component_name_or_uri, version, force_recompile, project_id)
+                    .await
+            }
+            ComponentSubCommand::DownloadFiles {
+                component_name_or_uri,
+                version,
+                output_dir,
+            } => {
+                let (component_name_or_uri, project_id) =
+                    resolve_component_ref(component_name_or_uri, projects).await?;
+                process_download_files(
+                    service.as_ref(),
+                    component_name_or_uri,
+                    version,
+                    project_id,
+                    output_dir,
+                )
+                .await
+            }
+            ComponentSubCommand::ExportManifest {
+                project_ref,
+                output,
+            } => {
+                let project_id = projects.resolve_id_or_default(project_ref).await?;
+                process_export_manifest(service.as_ref(), Some(project_id), output).await
+            }
+            ComponentSubCommand::ImportManifest {
+                manifest,
+                project_ref,
+                dry_run,
+            } => {
+                let project_id = projects.resolve_id_or_default(project_ref).await?;
+                process_import_manifest(
+                    service.as_ref(),
+                    Some(project_id),
+                    manifest,
+                    dry_run,
+                    format,
+                )
+                .await
+            }
+            ComponentSubCommand::ValidateApp { app } => process_validate_app(app).await,
+            ComponentSubCommand::StoreCheck {
+                root,
+                props_root,
+                blob_root,
+                fix,
+            } => process_store_check(root, props_root, blob_root, fix).await,
+        }
+    }
+}
+
+fn with_timings(result: GolemResult, timings: ComponentTimings, show: bool) -> GolemResult {
+    if show {
+        result.merge(GolemResult::Ok(Box::new(timings)))
+    } else {
+        result
+    }
+}
+
+// Parses `path:target:permissions`, splitting from the right so that the local path can contain colons
+fn parse_initial_file(
+    s: &str,
+) -> Result<InitialComponentFile, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let mut parts = s.rsplitn(3, ':');
+    let (Some(permissions), Some(target), Some(path)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(format!("invalid path:target:permissions: `{s}`").into());
+    };
+
+    let permissions = match permissions {
+        "ro" | "read-only" => ComponentFilePermissions::ReadOnly,
+        "rw" | "read-write" => ComponentFilePermissions::ReadWrite,
+        _ => {
+            return Err(format!(
+                "invalid permissions `{permissions}`: expected ro, rw, read-only or read-write"
+            )
+            .into())
+        }
+    };
+
+    Ok(InitialComponentFile {
+        source_path: DownloadableFile::from_local_path(Path::new(path))?,
+        target: ComponentFilePathWithPermissions {
+            path: ComponentFilePath::from_either_str(target)?,
+            permissions,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::command::component::{parse_initial_file, ComponentSubCommand};
+    use crate::command::profile::OssProfileAdd;
+    use crate::command::resolve_component_ref;
+    use crate::model::ComponentUriArg;
+    use crate::oss::command::{GolemOssCommand, OssCommand};
+    use crate::oss::model::OssContext;
+    use crate::service::project::ProjectResolverOss;
+    use clap::Parser;
+    use golem_common::model::{ComponentFilePath, ComponentFilePermissions, ComponentId};
+    use golem_common::uri::oss::uri::ComponentUri;
+    use golem_common::uri::oss::url::ComponentUrl;
+    use golem_common::uri::oss::urn::ComponentUrn;
+    use std::io::Write;
+    use uuid::Uuid;
+
+    // Every subcommand taking a component, with its other required arguments
+    const COMPONENT_SUBCOMMANDS: [&[&str]; 11] = [
+        &["update", "component.wasm"],
+        &["get"],
+        &["tag", "-t", "1", "--tag", "stable"],
+        &["rename", "--new-name", "renamed"],
+        &["print"],
+        &["try-update-workers"],
+        &["redeploy"],
+        &["check-compat", "component.wasm"],
+        &["compile-status"],
+        &["warm"],
+        &["download-files", "-o", "files"],
+    ];
+
+    fn component_ref(subcommand: &[&str], component: &[&str]) -> ComponentUriArg {
+        let args = ["golem-cli", "component"]
+            .into_iter()
+            .chain(subcommand.iter().copied())
+            .chain(component.iter().copied());
+
+        let command = GolemOssCommand::<OssProfileAdd>::try_parse_from(args)
+            .unwrap_or_else(|err| panic!("Failed to parse {subcommand:?} {component:?}: {err}"));
+
+        let OssCommand::Component { subcommand } = command.command else {
+            panic!("Expected a component command")
+        };
+        match subcommand {
+            ComponentSubCommand::Update {
+                component_name_or_uri,
+                ..
+            }
+            | ComponentSubCommand::Get {
+                component_name_or_uri,
+                ..
+            }
+            | ComponentSubCommand::Tag {
+                component_name_or_uri,
+                ..
+            }
+            | ComponentSubCommand::Rename {
+                component_name_or_uri,
+                ..
+            }
+            | ComponentSubCommand::Print {
+                component_name_or_uri,
+                ..
+            }
+            | ComponentSubCommand::TryUpdateWorkers {
+                component_name_or_uri,
+                ..
+            }
+            | ComponentSubCommand::Redeploy {
+                component_name_or_uri,
+                ..
+            }
+            | ComponentSubCommand::CheckCompat {
+                component_name_or_uri,
+                ..
+            }
+            | ComponentSubCommand::CompileStatus {
+                component_name_or_uri,
+                ..
+            }
+            | ComponentSubCommand::Warm {
+                component_name_or_uri,
+                ..
+            }
+            | ComponentSubCommand::DownloadFiles {
+                component_name_or_uri,
+                ..
+            } => component_name_or_uri,
+            other => panic!("{other:?} does not take a component"),
+        }
+    }
+
+    async fn resolve(subcommand: &[&str], component: &[&str]) -> ComponentUri {
+        let (component_uri, project) = resolve_component_ref::<OssContext, OssContext>(
+            component_ref(subcommand, component),
+            &ProjectResolverOss::DUMMY,
+        )
+        .await
+        .unwrap();
+        assert!(project.is_none());
+        component_uri
+    }
+
+    #[test]
+    async fn component_ids_are_accepted_by_every_subcommand() {
+        let id = Uuid::new_v4();
+        let expected = ComponentUri::URN(ComponentUrn {
+            id: ComponentId(id),
+        });
+
+        for subcommand in COMPONENT_SUBCOMMANDS {
+            let urn = format!("urn:component:{id}");
+            let plain_id = id.to_string();
+            assert_eq!(resolve(subcommand, &["-C", &urn]).await, expected);
+            assert_eq!(resolve(subcommand, &["-C", &plain_id]).await, expected);
+        }
+    }
+
+    #[test]
+    async fn component_names_are_accepted_by_every_subcommand() {
+        let expected = ComponentUri::URL(ComponentUrl {
+            name: "shopping-cart".to_string(),
+        });
+
+        for subcommand in COMPONENT_SUBCOMMANDS {
+            assert_eq!(
+                resolve(subcommand, &["-c", "shopping-cart"]).await,
+                expected
+            );
+            assert_eq!(
+                resolve(subcommand, &["-C", "component:///shopping-cart"]).await,
+                expected
+            );
         }
     }
+
+    #[test]
+    fn initial_files_are_parsed_from_the_command_line() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(b"key=value").unwrap();
+        let path = file.path().to_string_lossy().to_string();
+
+        let parsed = parse_initial_file(&format!("{path}:/config/app.conf:rw")).unwrap();
+        assert_eq!(
+            parsed.source_path.as_url().to_file_path().unwrap(),
+            file.path().canonicalize().unwrap()
+        );
+        assert_eq!(
+            parsed.target.path,
+            ComponentFilePath::from_abs_str("/config/app.conf").unwrap()
+        );
+        assert_eq!(
+            parsed.target.permissions,
+            ComponentFilePermissions::ReadWrite
+        );
+
+        assert!(parse_initial_file(&format!("{path}:/config/app.conf:rx")).is_err());
+        assert!(parse_initial_file(&format!("{path}:/config/app.conf")).is_err());
+        assert!(parse_initial_file("/does/not/exist:/config/app.conf:ro").is_err());
+    }
+
+    #[test]
+    fn get_parses_selected_fields() {
+        let parse = |fields: &str| {
+            GolemOssCommand::<OssProfileAdd>::try_parse_from([
+                "golem-cli",
+                "component",
+                "get",
+                "-c",
+                "shopping-cart",
+                "--fields",
+                fields,
+            ])
+        };
+
+        let command = parse("version,size,component_type").unwrap();
+        let OssCommand::Component {
+            subcommand: ComponentSubCommand::Get { fields, .. },
+        } = command.command
+        else {
+            panic!("Expected a component get command")
+        };
+        assert_eq!(
+            fields
+                .iter()
+                .map(|field| field.to_string())
+                .collect::<Vec<_>>(),
+            vec!["version", "size", "type"]
+        );
+
+        let err = parse("version,owner").unwrap_err().to_string();
+        assert!(err.contains("Unknown field owner, valid fields are: urn, name, version"));
+    }
+
+    #[test]
+    fn warm_parses_force_recompile() {
+        let parse = |extra: &[&str]| {
+            let args = ["golem-cli", "component", "warm", "-c", "shopping-cart"]
+                .into_iter()
+                .chain(extra.iter().copied());
+            let command = GolemOssCommand::<OssProfileAdd>::try_parse_from(args).unwrap();
+            let OssCommand::Component {
+                subcommand:
+                    ComponentSubCommand::Warm {
+                        version,
+                        force_recompile,
+                        ..
+                    },
+            } = command.command
+            else {
+                panic!("Expected a component warm command")
+            };
+            (version, force_recompile)
+        };
+
+        assert_eq!(parse(&[]), (None, false));
+        assert_eq!(parse(&["-t", "3", "--force-recompile"]), (Some(3), true));
+    }
+
+    #[test]
+    fn add_parses_wait_compiled() {
+        let parse = |extra: &[&str]| {
+            let args = [
+                "golem-cli",
+                "component",
+                "add",
+                "-c",
+                "shopping-cart",
+                "a.wasm",
+            ]
+            .into_iter()
+            .chain(extra.iter().copied());
+            GolemOssCommand::<OssProfileAdd>::try_parse_from(args).map(|command| {
+                let OssCommand::Component {
+                    subcommand: ComponentSubCommand::Add { wait_compiled, .. },
+                } = command.command
+                else {
+                    panic!("Expected a component add command")
+                };
+                (
+                    wait_compiled.wait_compiled,
+                    wait_compiled.compiled_quorum,
+                    wait_compiled.wait_compiled_timeout,
+                )
+            })
+        };
+
+        assert_eq!(parse(&[]).unwrap(), (false, None, 60));
+        assert_eq!(
+            parse(&[
+                "--wait-compiled",
+                "--compiled-quorum",
+                "2",
+                "--wait-compiled-timeout",
+                "5"
+            ])
+            .unwrap(),
+            (true, Some(2), 5)
+        );
+        assert!(parse(&["--compiled-quorum", "2"]).is_err());
+    }
 }
@@ -18,6 +18,7 @@ use crate::config::{
 use crate::init::{CliKind, ProfileAuth};
 use crate::model::{Format, GolemError, GolemResult};
 use clap::Subcommand;
+use golem_client::model::ComponentType;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
@@ -55,6 +56,14 @@ pub enum ProfileConfigSubCommand {
         #[arg(value_name = "default-format")]
         default_format: Format,
     },
+
+    /// Default type of new components, used by `component add` without `--ephemeral` or `--durable`
+    #[command()]
+    ComponentType {
+        /// Default component type: Durable or Ephemeral
+        #[arg(value_name = "default-component-type")]
+        default_component_type: ComponentType,
+    },
 }
 
 #[derive(clap::Args, Debug)]
@@ -245,6 +254,31 @@ impl ProfileConfigSubCommand {
 
                 Ok(GolemResult::Str("Default format updated".to_string()))
             }
+            ProfileConfigSubCommand::ComponentType {
+                default_component_type,
+            } => {
+                let NamedProfile { name, mut profile } = match profile_name {
+                    None => Config::get_active_profile(cli_kind, config_dir).ok_or(GolemError(
+                        "No active profile. Please run `golem-cli init`".to_string(),
+                    ))?,
+                    Some(profile_name) => {
+                        let profile = Config::get_profile(&profile_name, config_dir)
+                            .ok_or(GolemError(format!("Can't find profile {profile_name}")))?;
+                        NamedProfile {
+                            name: profile_name,
+                            profile,
+                        }
+                    }
+                };
+
+                profile.get_config_mut().default_component_type = Some(default_component_type);
+
+                Config::set_profile(name, profile, config_dir)?;
+
+                Ok(GolemResult::Str(format!(
+                    "Default component type updated to {default_component_type}"
+                )))
+            }
         }
     }
 }
@@ -271,7 +305,10 @@ impl UniversalProfileAddSubCommand {
                     url: component_url,
                     worker_url,
                     allow_insecure,
-                    config: ProfileConfig { default_format },
+                    config: ProfileConfig {
+                        default_format,
+                        ..ProfileConfig::default()
+                    },
                 });
 
                 Config::set_profile(name.clone(), profile, config_dir)?;
@@ -298,7 +335,10 @@ impl UniversalProfileAddSubCommand {
                     custom_cloud_url: dev_cloud_url,
                     custom_worker_url: dev_worker_url,
                     allow_insecure: dev_allow_insecure,
-                    config: ProfileConfig { default_format },
+                    config: ProfileConfig {
+                        default_format,
+                        ..ProfileConfig::default()
+                    },
                     auth: None,
                 });
 
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::command::ComponentRefSplit;
+use crate::command::{resolve_component_ref, ComponentRefSplit};
 use clap::builder::ValueParser;
 use clap::{ArgMatches, Args, Error, FromArgMatches, Subcommand};
 use golem_client::model::ScanCursor;
@@ -494,8 +494,8 @@ impl<ComponentRef: clap::Args, WorkerRef: clap::Args> WorkerSubcommand<Component
                 env,
                 args,
             } => {
-                let (component_name_or_uri, project_ref) = component_name_or_uri.split();
-                let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let (component_name_or_uri, project_id) =
+                    resolve_component_ref(component_name_or_uri, projects.as_ref()).await?;
                 service
                     .add(component_name_or_uri, worker_name, env, args, project_id)
                     .await
@@ -627,8 +627,8 @@ impl<ComponentRef: clap::Args, WorkerRef: clap::Args> WorkerSubcommand<Component
                 cursor,
                 precise,
             } => {
-                let (component_name_or_uri, project_ref) = component_name_or_uri.split();
-                let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let (component_name_or_uri, project_id) =
+                    resolve_component_ref(component_name_or_uri, projects.as_ref()).await?;
                 service
                     .list(
                         component_name_or_uri,
@@ -657,8 +657,8 @@ impl<ComponentRef: clap::Args, WorkerRef: clap::Args> WorkerSubcommand<Component
                 mode,
                 target_version,
             } => {
-                let (component_name_or_uri, project_ref) = component_name_or_uri.split();
-                let project_id = projects.resolve_id_or_default_opt(project_ref).await?;
+                let (component_name_or_uri, project_id) =
+                    resolve_component_ref(component_name_or_uri, projects.as_ref()).await?;
                 service
                     .update_many(
                         component_name_or_uri,
@@ -0,0 +1,217 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use golem_common::model::component_metadata::{
+    is_core_wasm_module, RawComponentMetadata, CORE_WASM_MODULE_ERROR,
+};
+use golem_wasm_ast::analysis::{AnalysedExport, AnalysedFunction, AnalysedInstance};
+
+use crate::model::component::{format_function_name, show_exported_function, Component};
+use crate::model::{GolemError, GolemResult};
+
+/// Differences between the exports of a deployed component version and a local WASM.
+#[derive(Debug, Default, PartialEq)]
+pub struct ExportsDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    /// Pairs of the deployed and the local signature
+    pub changed: Vec<(String, String)>,
+}
+
+impl ExportsDiff {
+    pub fn is_breaking(&self) -> bool {
+        !self.removed.is_empty() || !self.changed.is_empty()
+    }
+
+    fn report(&self) -> String {
+        let mut lines = Vec::new();
+
+        for removed in &self.removed {
+            lines.push(format!("BREAKING removed: {removed}"));
+        }
+        for (deployed, local) in &self.changed {
+            lines.push(format!("BREAKING changed: {deployed} -> {local}"));
+        }
+        for added in &self.added {
+            lines.push(format!("added: {added}"));
+        }
+
+        if lines.is_empty() {
+            lines.push("No changes in exports".to_string());
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// Exported functions by their fully qualified name, with their rendered signature
+fn exported_functions(exports: &[AnalysedExport]) -> BTreeMap<String, (AnalysedFunction, String)> {
+    exports
+        .iter()
+        .flat_map(|export| match export {
+            AnalysedExport::Instance(AnalysedInstance { name, functions }) => functions
+                .iter()
+                .map(|f| {
+                    (
+                        format_function_name(Some(name), &f.name),
+                        (f.clone(), show_exported_function(Some(name), f)),
+                    )
+                })
+                .collect::<Vec<_>>(),
+            AnalysedExport::Function(f) => vec![(
+                format_function_name(None, &f.name),
+                (f.clone(), show_exported_function(None, f)),
+            )],
+        })
+        .collect()
+}
+
+pub fn diff_exports(deployed: &[AnalysedExport], local: &[AnalysedExport]) -> ExportsDiff {
+    let deployed = exported_functions(deployed);
+    let local = exported_functions(local);
+
+    let mut diff = ExportsDiff::default();
+
+    for (name, (deployed_function, deployed_signature)) in &deployed {
+        match local.get(name) {
+            None => diff.removed.push(deployed_signature.clone()),
+            Some((local_function, local_signature)) => {
+                if local_function != deployed_function {
+                    diff.changed
+                        .push((deployed_signature.clone(), local_signature.clone()));
+                }
+            }
+        }
+    }
+
+    for (name, (_, local_signature)) in &local {
+        if !deployed.contains_key(name) {
+            diff.added.push(local_signature.clone());
+        }
+    }
+
+    diff
+}
+
+pub async fn process_check_compat(
+    component_file: PathBuf,
+    deployed: Component,
+    allow_breaking: bool,
+) -> Result<GolemResult, GolemError> {
+    let bytes = tokio::fs::read(&component_file).await.map_err(|err| {
+        GolemError(format!(
+            "Failed to read component file {}: {err}",
+            component_file.display()
+        ))
+    })?;
+
+    if is_core_wasm_module(&bytes) {
+        return Err(GolemError(format!(
+            "Failed to analyse {}: {CORE_WASM_MODULE_ERROR}",
+            component_file.display()
+        )));
+    }
+
+    let local = RawComponentMetadata::analyse_component(&bytes).map_err(|err| {
+        GolemError(format!(
+            "Failed to analyse {}: {err}",
+            component_file.display()
+        ))
+    })?;
+
+    let diff = diff_exports(&deployed.metadata.exports, &local.exports);
+    let mut report = format!(
+        "Comparing {} with {} version {}\n{}",
+        component_file.display(),
+        deployed.component_name,
+        deployed.versioned_component_id.version,
+        diff.report()
+    );
+    for warning in &local.warnings {
+        report.push_str(&format!("\nwarning: {warning}"));
+    }
+
+    if diff.is_breaking() && !allow_breaking {
+        Err(GolemError(report))
+    } else {
+        Ok(GolemResult::Str(report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use golem_wasm_ast::analysis::analysed_type::{str, u32};
+    use golem_wasm_ast::analysis::{
+        AnalysedExport, AnalysedFunction, AnalysedFunctionParameter, AnalysedFunctionResult,
+        AnalysedInstance, AnalysedType,
+    };
+
+    use crate::compat::{diff_exports, ExportsDiff};
+
+    fn function(name: &str, param: AnalysedType) -> AnalysedFunction {
+        AnalysedFunction {
+            name: name.to_string(),
+            parameters: vec![AnalysedFunctionParameter {
+                name: "x".to_string(),
+                typ: param,
+            }],
+            results: vec![AnalysedFunctionResult {
+                name: None,
+                typ: u32(),
+            }],
+        }
+    }
+
+    fn instance(functions: Vec<AnalysedFunction>) -> Vec<AnalysedExport> {
+        vec![AnalysedExport::Instance(AnalysedInstance {
+            name: "golem:it/api".to_string(),
+            functions,
+        })]
+    }
+
+    #[test]
+    fn identical_exports_are_compatible() {
+        let exports = instance(vec![function("get", u32())]);
+
+        assert_eq!(diff_exports(&exports, &exports), ExportsDiff::default());
+    }
+
+    #[test]
+    fn added_exports_are_not_breaking() {
+        let deployed = instance(vec![function("get", u32())]);
+        let local = instance(vec![function("get", u32()), function("set", u32())]);
+
+        let diff = diff_exports(&deployed, &local);
+
+        assert_eq!(diff.added.len(), 1);
+        assert!(!diff.is_breaking());
+    }
+
+    #[test]
+    fn removed_and_changed_exports_are_breaking() {
+        let deployed = instance(vec![function("get", u32()), function("set", u32())]);
+        let local = instance(vec![function("get", str())]);
+
+        let diff = diff_exports(&deployed, &local);
+
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.changed.len(), 1);
+        assert!(diff.is_breaking());
+    }
+}
@@ -0,0 +1,159 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use clap::ValueEnum;
+use golem_common::engine::create_wasmtime_config;
+use golem_common::metrics::compilation::record_compilation_time;
+use golem_common::model::component_metadata::{is_core_wasm_module, CORE_WASM_MODULE_ERROR};
+use uuid::Uuid;
+use wasmtime::component::Component;
+use wasmtime::{Config, Engine};
+
+use crate::model::{GolemError, GolemResult};
+
+/// The wasmtime engine configuration to compile for. A precompiled component can only be loaded
+/// by an engine with the same configuration.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum EngineProfile {
+    /// The engine used by the worker executor and the component compilation service
+    WorkerExecutor,
+}
+
+impl EngineProfile {
+    fn wasmtime_config(&self) -> Config {
+        match self {
+            EngineProfile::WorkerExecutor => create_wasmtime_config(),
+        }
+    }
+
+    pub fn engine(&self) -> Result<Engine, GolemError> {
+        Engine::new(&self.wasmtime_config())
+            .map_err(|err| GolemError(format!("Failed to create engine: {err}")))
+    }
+}
+
+/// Compiles a component WASM on a blocking thread, returning it with the time compilation took
+pub async fn compile_component(
+    engine: &Engine,
+    bytes: Vec<u8>,
+) -> Result<(Component, Duration), GolemError> {
+    if is_core_wasm_module(&bytes) {
+        return Err(GolemError(CORE_WASM_MODULE_ERROR.to_string()));
+    }
+
+    let engine = engine.clone();
+    let start = Instant::now();
+    let component = tokio::task::spawn_blocking(move || Component::from_binary(&engine, &bytes))
+        .await
+        .map_err(|err| GolemError(format!("Compilation task failed: {err}")))?
+        .map_err(|err| GolemError(format!("Failed to compile component: {err}")))?;
+
+    Ok((component, start.elapsed()))
+}
+
+/// The target triple components are compiled for, which is the one the CLI was built for
+const HOST_TARGET: &str = env!("GOLEM_TARGET_TRIPLE");
+
+/// Path of a compiled component relative to the root of a local file system blob storage,
+/// matching where the worker executor's compiled component service looks for it.
+fn compiled_component_path(
+    output_dir: &Path,
+    component_id: &Uuid,
+    version: u64,
+    target: &str,
+) -> PathBuf {
+    output_dir
+        .join("compilation_cache")
+        .join(component_id.to_string())
+        .join(target)
+        .join(format!("{version}.cwasm"))
+}
+
+pub async fn process_compile(
+    component_file: PathBuf,
+    component_id: Uuid,
+    version: u64,
+    engine_profile: EngineProfile,
+    output_dir: PathBuf,
+) -> Result<GolemResult, GolemError> {
+    let bytes = tokio::fs::read(&component_file).await.map_err(|err| {
+        GolemError(format!(
+            "Failed to read component file {}: {err}",
+            component_file.display()
+        ))
+    })?;
+
+    let size = bytes.len() as u64;
+    let engine = engine_profile.engine()?;
+    // Core WASM modules are rejected by compile_component, the error only gets the file name
+    let (component, compilation_time) = compile_component(&engine, bytes)
+        .await
+        .map_err(|err| GolemError(format!("{}: {}", component_file.display(), err.0)))?;
+    record_compilation_time(compilation_time, size);
+
+    let serialized = component
+        .serialize()
+        .map_err(|err| GolemError(format!("Failed to serialize compiled component: {err}")))?;
+
+    let target = compiled_component_path(&output_dir, &component_id, version, HOST_TARGET);
+    if let Some(parent) = target.parent() {
+        tokio::fs::create_dir_all(parent).await.map_err(|err| {
+            GolemError(format!(
+                "Failed to create directory {}: {err}",
+                parent.display()
+            ))
+        })?;
+    }
+    tokio::fs::write(&target, serialized).await.map_err(|err| {
+        GolemError(format!(
+            "Failed to write compiled component to {}: {err}",
+            target.display()
+        ))
+    })?;
+
+    Ok(GolemResult::Str(format!(
+        "Compiled {} in {}ms to {}",
+        component_file.display(),
+        compilation_time.as_millis(),
+        target.display()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::path::Path;
+    use uuid::Uuid;
+
+    use crate::compile::compiled_component_path;
+
+    #[test]
+    fn compiled_component_path_matches_compilation_cache_layout() {
+        let component_id = Uuid::parse_str("c0e0a5ab-0ad5-4e1b-a6d4-4f9b6b5e6b4d").unwrap();
+
+        assert_eq!(
+            compiled_component_path(
+                Path::new("/data/blobs"),
+                &component_id,
+                3,
+                "x86_64-unknown-linux-gnu"
+            ),
+            Path::new("/data/blobs/compilation_cache/c0e0a5ab-0ad5-4e1b-a6d4-4f9b6b5e6b4d/x86_64-unknown-linux-gnu/3.cwasm")
+        );
+    }
+}
@@ -16,6 +16,7 @@ use crate::cloud::CloudAuthenticationConfig;
 use crate::init::CliKind;
 use crate::model::{Format, GolemError, HasFormatConfig};
 use derive_more::FromStr;
+use golem_client::model::ComponentType;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -141,6 +142,16 @@ impl HasFormatConfig for OssProfile {
 pub struct ProfileConfig {
     #[serde(default)]
     pub default_format: Format,
+    /// Type of the components created without `--ephemeral` or `--durable`
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub default_component_type: Option<ComponentType>,
+}
+
+impl ProfileConfig {
+    pub fn default_component_type(&self) -> ComponentType {
+        self.default_component_type
+            .unwrap_or(ComponentType::Durable)
+    }
 }
 
 impl Config {
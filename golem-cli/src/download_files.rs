@@ -0,0 +1,311 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::{Path, PathBuf};
+
+use golem_client::model::ComponentFilePermissions;
+use golem_common::model::ComponentFilePath;
+use golem_common::uri::oss::uri::ComponentUri;
+use serde::{Deserialize, Serialize};
+
+use crate::export_manifest::sha256;
+use crate::model::component::VersionSelector;
+use crate::model::{GolemError, GolemResult};
+use crate::service::component::ComponentService;
+
+/// What happened to one initial file of the component
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FileDownload {
+    Downloaded,
+    /// The local file already has the content of the key, it was not downloaded again
+    Cached,
+    /// The content of the key is missing from the blob storage
+    Missing,
+    Failed(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDownloadView {
+    pub path: String,
+    pub key: String,
+    pub result: FileDownload,
+}
+
+impl FileDownloadView {
+    fn report_line(&self) -> String {
+        match &self.result {
+            FileDownload::Downloaded => format!("Downloaded {}", self.path),
+            FileDownload::Cached => format!("Up to date {}", self.path),
+            FileDownload::Missing => {
+                format!(
+                    "MISSING {}: no content stored for key {}",
+                    self.path, self.key
+                )
+            }
+            FileDownload::Failed(err) => format!("FAIL {}: {err}", self.path),
+        }
+    }
+}
+
+/// The initial files of a component version downloaded into `output_dir`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadFilesView {
+    pub output_dir: PathBuf,
+    pub files: Vec<FileDownloadView>,
+}
+
+impl DownloadFilesView {
+    fn failed(&self) -> usize {
+        self.files
+            .iter()
+            .filter(|file| !matches!(file.result, FileDownload::Downloaded | FileDownload::Cached))
+            .count()
+    }
+
+    pub fn report(&self) -> String {
+        let mut lines = self
+            .files
+            .iter()
+            .map(FileDownloadView::report_line)
+            .collect::<Vec<_>>();
+        lines.push(format!(
+            "{} of {} files in {}",
+            self.files.len() - self.failed(),
+            self.files.len(),
+            self.output_dir.display()
+        ));
+        lines.join("\n")
+    }
+}
+
+/// Downloads all the initial files of a component version into `output_dir`, laid out as in
+/// the filesystem of the workers, with read-only files made read-only locally as well. Files
+/// already present with the same content are kept. Every file is reported, failing if any of
+/// them could not be downloaded.
+pub async fn process_download_files<ProjectContext: Send + Sync>(
+    service: &(dyn ComponentService<ProjectContext = ProjectContext> + Send + Sync),
+    component_uri: ComponentUri,
+    version: Option<VersionSelector>,
+    project: Option<ProjectContext>,
+    output_dir: PathBuf,
+) -> Result<GolemResult, GolemError> {
+    let urn = service.resolve_uri(component_uri, &project).await?;
+    let component = service.get_selected_metadata(&urn, version).await?;
+    let version = component.versioned_component_id.version;
+
+    let mut downloads = Vec::new();
+    for file in &component.files {
+        let download = match local_path(&output_dir, &file.path) {
+            Ok(path) if is_cached(&path, &file.key).await => FileDownload::Cached,
+            Ok(path) => match service.download_file(&urn, version, &file.key).await? {
+                Some(content) => match write_file(&path, &content, &file.permissions).await {
+                    Ok(()) => FileDownload::Downloaded,
+                    Err(err) => FileDownload::Failed(err),
+                },
+                None => FileDownload::Missing,
+            },
+            Err(err) => FileDownload::Failed(err),
+        };
+        downloads.push(FileDownloadView {
+            path: file.path.clone(),
+            key: file.key.clone(),
+            result: download,
+        });
+    }
+
+    download_report(DownloadFilesView {
+        output_dir,
+        files: downloads,
+    })
+}
+
+fn local_path(output_dir: &Path, path: &str) -> Result<PathBuf, String> {
+    let path = ComponentFilePath::from_abs_str(path)
+        .map_err(|err| format!("invalid path {path}: {err}"))?;
+    Ok(output_dir.join(path.to_rel_string()))
+}
+
+async fn is_cached(path: &Path, key: &str) -> bool {
+    match tokio::fs::read(path).await {
+        Ok(content) => sha256(&content) == key,
+        Err(_) => false,
+    }
+}
+
+async fn write_file(
+    path: &Path,
+    content: &[u8],
+    permissions: &ComponentFilePermissions,
+) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|err| format!("failed to create {}: {err}", parent.display()))?;
+    }
+
+    // A read-only file left by a previous download is replaced
+    if let Ok(metadata) = tokio::fs::metadata(path).await {
+        if metadata.permissions().readonly() {
+            set_readonly(path, false).await?;
+        }
+    }
+
+    tokio::fs::write(path, content)
+        .await
+        .map_err(|err| format!("failed to write {}: {err}", path.display()))?;
+
+    set_readonly(
+        path,
+        matches!(permissions, ComponentFilePermissions::ReadOnly),
+    )
+    .await
+}
+
+async fn set_readonly(path: &Path, readonly: bool) -> Result<(), String> {
+    let mut permissions = tokio::fs::metadata(path)
+        .await
+        .map_err(|err| format!("failed to read {}: {err}", path.display()))?
+        .permissions();
+    #[allow(clippy::permissions_set_readonly_false)]
+    permissions.set_readonly(readonly);
+    tokio::fs::set_permissions(path, permissions)
+        .await
+        .map_err(|err| format!("failed to set permissions of {}: {err}", path.display()))
+}
+
+fn download_report(view: DownloadFilesView) -> Result<GolemResult, GolemError> {
+    if view.failed() == 0 {
+        Ok(GolemResult::Ok(Box::new(view)))
+    } else {
+        Err(GolemError(view.report()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::path::{Path, PathBuf};
+
+    use golem_client::model::ComponentFilePermissions;
+
+    use crate::download_files::{
+        download_report, is_cached, local_path, write_file, DownloadFilesView, FileDownload,
+        FileDownloadView,
+    };
+    use crate::export_manifest::sha256;
+    use crate::model::GolemResult;
+
+    fn file(path: &str, result: FileDownload) -> FileDownloadView {
+        FileDownloadView {
+            key: sha256(path.as_bytes()),
+            path: path.to_string(),
+            result,
+        }
+    }
+
+    #[test]
+    fn local_paths_stay_in_the_output_dir() {
+        let output_dir = Path::new("files");
+        assert_eq!(
+            local_path(output_dir, "/etc/app.conf").unwrap(),
+            PathBuf::from("files/etc/app.conf")
+        );
+        assert_eq!(
+            local_path(output_dir, "/../../etc/passwd").unwrap(),
+            PathBuf::from("files/etc/passwd")
+        );
+        assert!(local_path(output_dir, "etc/app.conf").is_err());
+    }
+
+    #[test]
+    async fn files_are_written_with_their_permissions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("etc/app.conf");
+
+        write_file(&path, b"first", &ComponentFilePermissions::ReadOnly)
+            .await
+            .unwrap();
+        assert!(std::fs::metadata(&path).unwrap().permissions().readonly());
+        assert!(is_cached(&path, &sha256(b"first")).await);
+        assert!(!is_cached(&path, &sha256(b"second")).await);
+
+        // Downloading again replaces the read-only file
+        write_file(&path, b"second", &ComponentFilePermissions::ReadWrite)
+            .await
+            .unwrap();
+        assert!(!std::fs::metadata(&path).unwrap().permissions().readonly());
+        assert_eq!(std::fs::read(&path).unwrap(), b"second");
+
+        assert!(!is_cached(&dir.path().join("missing"), &sha256(b"")).await);
+    }
+
+    #[test]
+    fn report_lists_missing_files() {
+        let report = download_report(DownloadFilesView {
+            output_dir: PathBuf::from("files"),
+            files: vec![
+                file("/etc/app.conf", FileDownload::Downloaded),
+                file("/data/cache.db", FileDownload::Cached),
+                file("/data/index.db", FileDownload::Missing),
+            ],
+        })
+        .unwrap_err();
+
+        assert_eq!(
+            report.0,
+            format!(
+                "Downloaded /etc/app.conf\n\
+                 Up to date /data/cache.db\n\
+                 MISSING /data/index.db: no content stored for key {}\n\
+                 2 of 3 files in files",
+                sha256(b"/data/index.db")
+            )
+        );
+    }
+
+    #[test]
+    fn successful_downloads_are_structured() {
+        let view = DownloadFilesView {
+            output_dir: PathBuf::from("files"),
+            files: vec![
+                file("/etc/app.conf", FileDownload::Downloaded),
+                file("/data/cache.db", FileDownload::Cached),
+            ],
+        };
+        assert_eq!(
+            view.report(),
+            "Downloaded /etc/app.conf\nUp to date /data/cache.db\n2 of 2 files in files"
+        );
+
+        let yaml = serde_yaml::to_string(&view).unwrap();
+        assert!(yaml.contains("outputDir: files\n"));
+        assert!(yaml.contains("  path: /data/cache.db\n"));
+        assert!(yaml.contains("  result: cached\n"));
+        assert_eq!(
+            serde_yaml::from_str::<DownloadFilesView>(&yaml).unwrap(),
+            view
+        );
+
+        match download_report(view.clone()) {
+            Ok(GolemResult::Ok(result)) => {
+                assert_eq!(result.as_json_value(), serde_json::to_value(&view).unwrap())
+            }
+            _ => panic!("Expected a successful result"),
+        }
+    }
+}
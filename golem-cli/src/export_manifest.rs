@@ -0,0 +1,294 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use golem_common::model::component_metadata::ComponentMetadata;
+use golem_common::model::{ComponentFilePermissions, ComponentId, ComponentType};
+use golem_common::uri::oss::uri::ComponentUri;
+use golem_common::uri::oss::urn::ComponentUrn;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::model::component::{Component, VersionSelector};
+use crate::model::{GolemError, GolemResult};
+use crate::service::component::ComponentService;
+
+/// Version of the manifest format, to be checked when importing a manifest
+pub const COMPONENTS_MANIFEST_VERSION: u32 = 1;
+
+/// Every version of every component of a project, as written by `component export-manifest`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentsManifest {
+    pub manifest_version: u32,
+    pub components: Vec<ComponentManifestEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentManifestEntry {
+    pub component_id: Uuid,
+    pub component_name: String,
+    /// Ordered by version number
+    pub versions: Vec<ComponentVersionManifest>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentVersionManifest {
+    pub version: u64,
+    pub component_type: ComponentType,
+    pub component_size: u64,
+    /// Hex encoded SHA-256 of the component WASM
+    pub sha256: String,
+    pub created_at: Option<DateTime<Utc>>,
+    /// Location of the component WASM for importing the manifest: an http(s) URL, or a path
+    /// relative to the manifest. Not written by the export, which only identifies it by its hash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wasm: Option<String>,
+    pub files: Vec<ManifestComponentFile>,
+    pub metadata: ComponentMetadata,
+}
+
+impl ComponentVersionManifest {
+    fn new(component: Component, wasm: &[u8]) -> Self {
+        ComponentVersionManifest {
+            version: component.versioned_component_id.version,
+            component_type: component.component_type,
+            component_size: component.component_size,
+            sha256: sha256(wasm),
+            created_at: component.created_at,
+            wasm: None,
+            files: component
+                .files
+                .into_iter()
+                .map(ManifestComponentFile::from)
+                .collect(),
+            metadata: component.metadata,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestComponentFile {
+    /// Key of the content of the file, the same for all files with the same content
+    pub key: String,
+    pub path: String,
+    pub permissions: ComponentFilePermissions,
+    /// Location of the content for importing the manifest, in the same form as `wasm`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+impl From<golem_client::model::InitialComponentFile> for ManifestComponentFile {
+    fn from(value: golem_client::model::InitialComponentFile) -> Self {
+        ManifestComponentFile {
+            key: value.key,
+            path: value.path,
+            permissions: match value.permissions {
+                golem_client::model::ComponentFilePermissions::ReadOnly => {
+                    ComponentFilePermissions::ReadOnly
+                }
+                golem_client::model::ComponentFilePermissions::ReadWrite => {
+                    ComponentFilePermissions::ReadWrite
+                }
+            },
+            source: None,
+        }
+    }
+}
+
+pub(crate) fn sha256(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ManifestFormat {
+    Json,
+    Yaml,
+}
+
+impl ManifestFormat {
+    // JSON if the manifest file has the .json extension, YAML otherwise
+    pub(crate) fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => ManifestFormat::Json,
+            _ => ManifestFormat::Yaml,
+        }
+    }
+
+    fn serialize(&self, manifest: &ComponentsManifest) -> Result<String, GolemError> {
+        match self {
+            ManifestFormat::Json => serde_json::to_string_pretty(manifest)
+                .map_err(|err| GolemError(format!("Failed to serialize the manifest: {err}"))),
+            ManifestFormat::Yaml => serde_yaml::to_string(manifest)
+                .map_err(|err| GolemError(format!("Failed to serialize the manifest: {err}"))),
+        }
+    }
+
+    pub(crate) fn deserialize(&self, content: &str) -> Result<ComponentsManifest, GolemError> {
+        match self {
+            ManifestFormat::Json => serde_json::from_str(content)
+                .map_err(|err| GolemError(format!("Failed to parse the manifest: {err}"))),
+            ManifestFormat::Yaml => serde_yaml::from_str(content)
+                .map_err(|err| GolemError(format!("Failed to parse the manifest: {err}"))),
+        }
+    }
+}
+
+/// Fetches the metadata of every version of every component of a project, downloads each version
+/// to hash it, and writes all of it as a manifest to `output`
+pub async fn process_export_manifest<ProjectContext: Clone + Send + Sync>(
+    service: &(dyn ComponentService<ProjectContext = ProjectContext> + Send + Sync),
+    project: Option<ProjectContext>,
+    output: PathBuf,
+) -> Result<GolemResult, GolemError> {
+    let mut components = Vec::new();
+    for latest in service.list_latest(project.clone()).await? {
+        let component_urn = ComponentUrn {
+            id: ComponentId(latest.versioned_component_id.component_id),
+        };
+
+        let mut versions = Vec::new();
+        for version in 0..=latest.versioned_component_id.version {
+            let component = if version == latest.versioned_component_id.version {
+                latest.clone()
+            } else {
+                service.get_metadata(&component_urn, version).await?
+            };
+            let wasm = service
+                .download(
+                    ComponentUri::URN(component_urn.clone()),
+                    Some(VersionSelector::Version(version)),
+                    project.clone(),
+                )
+                .await?;
+
+            versions.push(ComponentVersionManifest::new(component, &wasm));
+        }
+
+        components.push(ComponentManifestEntry {
+            component_id: latest.versioned_component_id.component_id,
+            component_name: latest.component_name,
+            versions,
+        });
+    }
+
+    let manifest = ComponentsManifest {
+        manifest_version: COMPONENTS_MANIFEST_VERSION,
+        components,
+    };
+
+    let content = ManifestFormat::from_path(&output).serialize(&manifest)?;
+    tokio::fs::write(&output, content).await.map_err(|err| {
+        GolemError(format!(
+            "Failed to write the manifest to {}: {err}",
+            output.display()
+        ))
+    })?;
+
+    let version_count: usize = manifest
+        .components
+        .iter()
+        .map(|component| component.versions.len())
+        .sum();
+    Ok(GolemResult::Str(format!(
+        "Exported {} versions of {} components to {}",
+        version_count,
+        manifest.components.len(),
+        output.display()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::BTreeMap;
+    use std::path::Path;
+
+    use golem_common::model::component_metadata::ComponentMetadata;
+    use golem_common::model::{ComponentFilePermissions, ComponentType};
+    use uuid::Uuid;
+
+    use crate::export_manifest::{
+        ComponentManifestEntry, ComponentVersionManifest, ComponentsManifest,
+        ManifestComponentFile, ManifestFormat, COMPONENTS_MANIFEST_VERSION,
+    };
+
+    fn manifest() -> ComponentsManifest {
+        ComponentsManifest {
+            manifest_version: COMPONENTS_MANIFEST_VERSION,
+            components: vec![ComponentManifestEntry {
+                component_id: Uuid::new_v4(),
+                component_name: "shopping-cart".to_string(),
+                versions: vec![ComponentVersionManifest {
+                    version: 0,
+                    component_type: ComponentType::Durable,
+                    component_size: 1234,
+                    sha256: "ab".repeat(32),
+                    created_at: None,
+                    wasm: None,
+                    files: vec![ManifestComponentFile {
+                        key: "cd".repeat(32),
+                        path: "/config/app.conf".to_string(),
+                        permissions: ComponentFilePermissions::ReadOnly,
+                        source: None,
+                    }],
+                    metadata: ComponentMetadata {
+                        exports: vec![],
+                        producers: vec![],
+                        memories: vec![],
+                        world: None,
+                        analysis_warnings: vec![],
+                        annotations: BTreeMap::new(),
+                    },
+                }],
+            }],
+        }
+    }
+
+    #[test]
+    fn format_is_selected_by_extension() {
+        assert_eq!(
+            ManifestFormat::from_path(Path::new("components.json")),
+            ManifestFormat::Json
+        );
+        assert_eq!(
+            ManifestFormat::from_path(Path::new("components.yaml")),
+            ManifestFormat::Yaml
+        );
+        assert_eq!(
+            ManifestFormat::from_path(Path::new("components")),
+            ManifestFormat::Yaml
+        );
+    }
+
+    #[test]
+    fn manifest_can_be_read_back() {
+        let manifest = manifest();
+
+        let json = ManifestFormat::Json.serialize(&manifest).unwrap();
+        assert_eq!(ManifestFormat::Json.deserialize(&json).unwrap(), manifest);
+
+        let yaml = ManifestFormat::Yaml.serialize(&manifest).unwrap();
+        assert!(yaml.contains("componentName: shopping-cart"));
+        assert!(!yaml.contains("wasm:"));
+        assert_eq!(ManifestFormat::Yaml.deserialize(&yaml).unwrap(), manifest);
+    }
+}
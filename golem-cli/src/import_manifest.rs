@@ -0,0 +1,465 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use golem_common::model::{ComponentFilePath, ComponentFilePathWithPermissions};
+use golem_common::uri::oss::uri::ComponentUri;
+use golem_common::uri::oss::url::ComponentUrl;
+use itertools::Itertools;
+
+use crate::export_manifest::{
+    sha256, ComponentVersionManifest, ManifestFormat, COMPONENTS_MANIFEST_VERSION,
+};
+use crate::model::application_manifest::{DownloadableFile, InitialComponentFile};
+use crate::model::{ComponentName, Format, GolemError, GolemResult, PathBufOrStdin};
+use crate::service::component::ComponentService;
+
+/// Outcome of importing the versions of one component of a manifest
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentImport {
+    pub component_name: String,
+    /// Versions of the manifest which were created, or would be created in a dry run
+    pub created: Vec<u64>,
+    /// Versions of the manifest which already exist
+    pub skipped: Vec<u64>,
+    /// Why the remaining versions could not be imported
+    pub error: Option<String>,
+}
+
+impl ComponentImport {
+    fn report_line(&self, dry_run: bool) -> String {
+        let versions = |versions: &[u64]| versions.iter().join(", ");
+
+        let mut parts = Vec::new();
+        if !self.created.is_empty() {
+            let verb = if dry_run { "would create" } else { "created" };
+            parts.push(format!("{verb} versions {}", versions(&self.created)));
+        }
+        if !self.skipped.is_empty() {
+            parts.push(format!(
+                "skipped existing versions {}",
+                versions(&self.skipped)
+            ));
+        }
+        if parts.is_empty() {
+            parts.push("nothing to import".to_string());
+        }
+
+        match &self.error {
+            Some(error) => format!(
+                "FAIL {}: {}, {error}",
+                self.component_name,
+                parts.join(", ")
+            ),
+            None => format!("OK {}: {}", self.component_name, parts.join(", ")),
+        }
+    }
+}
+
+// The WASM and initial files of a component version, resolved relative to the manifest
+struct ResolvedVersion {
+    wasm: DownloadableFile,
+    files: Vec<InitialComponentFile>,
+}
+
+fn resolve_version(
+    manifest_path: &Path,
+    version: &ComponentVersionManifest,
+) -> Result<ResolvedVersion, GolemError> {
+    let wasm = version
+        .wasm
+        .as_ref()
+        .ok_or_else(|| GolemError("missing the location of the WASM".to_string()))?;
+    let wasm = DownloadableFile::make(wasm, manifest_path).map_err(GolemError)?;
+
+    let files = version
+        .files
+        .iter()
+        .map(|file| {
+            let source = file.source.as_ref().ok_or_else(|| {
+                GolemError(format!(
+                    "missing the location of the initial file {}",
+                    file.path
+                ))
+            })?;
+            Ok(InitialComponentFile {
+                source_path: DownloadableFile::make(source, manifest_path).map_err(GolemError)?,
+                target: ComponentFilePathWithPermissions {
+                    path: ComponentFilePath::from_abs_str(&file.path).map_err(GolemError)?,
+                    permissions: file.permissions,
+                },
+            })
+        })
+        .collect::<Result<Vec<_>, GolemError>>()?;
+
+    Ok(ResolvedVersion { wasm, files })
+}
+
+/// Creates the versions of the components of a manifest written by `component export-manifest`
+/// which do not exist yet. Components are matched by name, and a version is considered to exist
+/// if the component's latest version is at least the version in the manifest.
+///
+/// The WASM of each version is fetched and checked against its hash, also in a dry run. A
+/// component's import stops at the first version the server does not (or would not) assign the
+/// version of the manifest to, as all its later versions would be off as well
+pub async fn process_import_manifest<ProjectContext: Clone + Send + Sync>(
+    service: &(dyn ComponentService<ProjectContext = ProjectContext> + Send + Sync),
+    project: Option<ProjectContext>,
+    manifest_path: PathBuf,
+    dry_run: bool,
+    format: Format,
+) -> Result<GolemResult, GolemError> {
+    let content = tokio::fs::read_to_string(&manifest_path)
+        .await
+        .map_err(|err| {
+            GolemError(format!(
+                "Failed to read the manifest {}: {err}",
+                manifest_path.display()
+            ))
+        })?;
+    let manifest = ManifestFormat::from_path(&manifest_path).deserialize(&content)?;
+    if manifest.manifest_version != COMPONENTS_MANIFEST_VERSION {
+        return Err(GolemError(format!(
+            "Unsupported manifest version {}, expected {}",
+            manifest.manifest_version, COMPONENTS_MANIFEST_VERSION
+        )));
+    }
+
+    let existing: HashMap<String, u64> = service
+        .list_latest(project.clone())
+        .await?
+        .into_iter()
+        .map(|component| {
+            (
+                component.component_name,
+                component.versioned_component_id.version,
+            )
+        })
+        .collect();
+
+    let mut imports = Vec::new();
+    for entry in manifest.components {
+        let mut import = ComponentImport {
+            component_name: entry.component_name.clone(),
+            created: Vec::new(),
+            skipped: Vec::new(),
+            error: None,
+        };
+
+        let mut latest = existing.get(&entry.component_name).copied();
+        for version in entry
+            .versions
+            .iter()
+            .sorted_by_key(|version| version.version)
+        {
+            if latest.is_some_and(|latest| version.version <= latest) {
+                import.skipped.push(version.version);
+                continue;
+            }
+
+            let result = match resolve_version(&manifest_path, version) {
+                Ok(resolved) if dry_run => fetch_wasm(service, &resolved, version)
+                    .await
+                    .map(|_| latest.map_or(0, |latest| latest + 1)),
+                Ok(resolved) => {
+                    import_version(
+                        service,
+                        project.clone(),
+                        &entry.component_name,
+                        latest,
+                        version,
+                        resolved,
+                        format,
+                    )
+                    .await
+                }
+                Err(err) => Err(err),
+            };
+
+            let result = result.and_then(|assigned| {
+                latest = Some(assigned);
+                check_assigned_version(version.version, assigned, dry_run)
+            });
+
+            match result {
+                Ok(()) => import.created.push(version.version),
+                Err(err) => {
+                    import.error = Some(format!("version {}: {}", version.version, err.0));
+                    break;
+                }
+            }
+        }
+
+        imports.push(import);
+    }
+
+    import_report(&imports, dry_run)
+}
+
+fn check_assigned_version(expected: u64, assigned: u64, dry_run: bool) -> Result<(), GolemError> {
+    if assigned == expected {
+        Ok(())
+    } else if dry_run {
+        Err(GolemError(format!(
+            "would be created as version {assigned}"
+        )))
+    } else {
+        Err(GolemError(format!("was created as version {assigned}")))
+    }
+}
+
+// Fetches the WASM of a version, checking it against the hash of the manifest
+async fn fetch_wasm<ProjectContext: Clone + Send + Sync>(
+    service: &(dyn ComponentService<ProjectContext = ProjectContext> + Send + Sync),
+    resolved: &ResolvedVersion,
+    version: &ComponentVersionManifest,
+) -> Result<Vec<u8>, GolemError> {
+    let wasm = service.fetch_file(&resolved.wasm).await?;
+    let hash = sha256(&wasm);
+    if hash != version.sha256 {
+        return Err(GolemError(format!(
+            "the WASM at {} has hash {hash}, expected {}",
+            resolved.wasm.as_url(),
+            version.sha256
+        )));
+    }
+    Ok(wasm)
+}
+
+// Creates a version, returning the version the server assigned to it
+async fn import_version<ProjectContext: Clone + Send + Sync>(
+    service: &(dyn ComponentService<ProjectContext = ProjectContext> + Send + Sync),
+    project: Option<ProjectContext>,
+    component_name: &str,
+    latest: Option<u64>,
+    version: &ComponentVersionManifest,
+    resolved: ResolvedVersion,
+    format: Format,
+) -> Result<u64, GolemError> {
+    let wasm = fetch_wasm(service, &resolved, version).await?;
+
+    let mut wasm_file = tempfile::NamedTempFile::new()
+        .map_err(|err| GolemError(format!("Failed to create a temporary file: {err}")))?;
+    wasm_file
+        .write_all(&wasm)
+        .map_err(|err| GolemError(format!("Failed to write a temporary file: {err}")))?;
+    let component_file = PathBufOrStdin::Path(wasm_file.path().to_path_buf());
+
+    let upload = match latest {
+        None => {
+            service
+                .add(
+                    ComponentName(component_name.to_string()),
+                    component_file,
+                    version.component_type,
+                    project,
+                    true,
+                    format,
+                    resolved.files,
+                )
+                .await?
+        }
+        Some(latest) => {
+            service
+                .update(
+                    ComponentUri::URL(ComponentUrl {
+                        name: component_name.to_string(),
+                    }),
+                    component_file,
+                    Some(version.component_type),
+                    Some(latest),
+                    project,
+                    true,
+                    format,
+                    resolved.files,
+                )
+                .await?
+        }
+    };
+
+    Ok(upload.version)
+}
+
+fn import_report(imports: &[ComponentImport], dry_run: bool) -> Result<GolemResult, GolemError> {
+    let failed = imports
+        .iter()
+        .filter(|import| import.error.is_some())
+        .count();
+
+    let mut lines: Vec<String> = imports
+        .iter()
+        .map(|import| import.report_line(dry_run))
+        .collect();
+    lines.push(format!(
+        "{} of {} components imported{}",
+        imports.len() - failed,
+        imports.len(),
+        if dry_run { " (dry run)" } else { "" }
+    ));
+    let report = lines.join("\n");
+
+    if failed > 0 {
+        Err(GolemError(report))
+    } else {
+        Ok(GolemResult::Str(report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::BTreeMap;
+
+    use golem_common::model::component_metadata::ComponentMetadata;
+    use golem_common::model::{ComponentFilePermissions, ComponentType};
+
+    use crate::export_manifest::{ComponentVersionManifest, ManifestComponentFile};
+    use crate::import_manifest::{
+        check_assigned_version, import_report, resolve_version, ComponentImport,
+    };
+    use crate::model::GolemResult;
+
+    fn version(wasm: Option<&str>, file_source: Option<&str>) -> ComponentVersionManifest {
+        ComponentVersionManifest {
+            version: 1,
+            component_type: ComponentType::Durable,
+            component_size: 8,
+            sha256: "ab".repeat(32),
+            created_at: None,
+            wasm: wasm.map(|wasm| wasm.to_string()),
+            files: vec![ManifestComponentFile {
+                key: "cd".repeat(32),
+                path: "/config/app.conf".to_string(),
+                permissions: ComponentFilePermissions::ReadWrite,
+                source: file_source.map(|source| source.to_string()),
+            }],
+            metadata: ComponentMetadata {
+                exports: vec![],
+                producers: vec![],
+                memories: vec![],
+                world: None,
+                analysis_warnings: vec![],
+                annotations: BTreeMap::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn sources_are_resolved_relative_to_the_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("components.yaml");
+
+        let resolved = resolve_version(
+            &manifest_path,
+            &version(
+                Some("wasm/shopping-cart.wasm"),
+                Some("https://example.com/app.conf"),
+            ),
+        )
+        .unwrap();
+
+        assert_eq!(
+            resolved.wasm.as_url().to_file_path().unwrap(),
+            dir.path()
+                .canonicalize()
+                .unwrap()
+                .join("wasm/shopping-cart.wasm")
+        );
+        assert_eq!(
+            resolved.files[0].source_path.as_url().as_str(),
+            "https://example.com/app.conf"
+        );
+        assert_eq!(
+            resolved.files[0].target.permissions,
+            ComponentFilePermissions::ReadWrite
+        );
+    }
+
+    #[test]
+    fn missing_sources_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("components.yaml");
+
+        assert!(resolve_version(&manifest_path, &version(None, Some("app.conf"))).is_err());
+        assert!(resolve_version(&manifest_path, &version(Some("a.wasm"), None)).is_err());
+    }
+
+    #[test]
+    fn report_lists_created_and_skipped_versions() {
+        let report = import_report(
+            &[
+                ComponentImport {
+                    component_name: "first".to_string(),
+                    created: vec![2, 3],
+                    skipped: vec![0, 1],
+                    error: None,
+                },
+                ComponentImport {
+                    component_name: "second".to_string(),
+                    created: vec![],
+                    skipped: vec![0],
+                    error: None,
+                },
+            ],
+            true,
+        );
+
+        match report {
+            Ok(GolemResult::Str(report)) => {
+                assert!(report.contains(
+                    "OK first: would create versions 2, 3, skipped existing versions 0, 1"
+                ));
+                assert!(report.contains("OK second: skipped existing versions 0"));
+                assert!(report.ends_with("2 of 2 components imported (dry run)"));
+            }
+            _ => panic!("Expected a successful report"),
+        }
+    }
+
+    #[test]
+    fn report_fails_when_any_component_fails() {
+        let report = import_report(
+            &[ComponentImport {
+                component_name: "first".to_string(),
+                created: vec![0],
+                skipped: vec![],
+                error: Some("version 1: missing the location of the WASM".to_string()),
+            }],
+            false,
+        )
+        .unwrap_err();
+
+        assert!(report.0.contains(
+            "FAIL first: created versions 0, version 1: missing the location of the WASM"
+        ));
+        assert!(report.0.ends_with("0 of 1 components imported"));
+    }
+
+    #[test]
+    fn versions_assigned_differently_are_reported() {
+        assert!(check_assigned_version(3, 3, false).is_ok());
+        assert_eq!(
+            check_assigned_version(5, 3, true).unwrap_err().0,
+            "would be created as version 3"
+        );
+        assert_eq!(
+            check_assigned_version(5, 3, false).unwrap_err().0,
+            "was created as version 3"
+        );
+    }
+}
@@ -361,7 +361,10 @@ fn make_profile_config() -> Result<ProfileConfig, GolemError> {
         .map_err(|err| GolemError(format!("Unexpected error: {err}")))?
         .into();
 
-    Ok(ProfileConfig { default_format })
+    Ok(ProfileConfig {
+        default_format,
+        ..ProfileConfig::default()
+    })
 }
 
 fn make_cloud_profile() -> Result<Profile, GolemError> {
@@ -0,0 +1,139 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+use wasmparser::{Parser, Payload};
+
+use crate::model::{Format, GolemError, GolemResult};
+
+/// A section of a WASM component, or of one of the modules and components nested in it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WasmSection {
+    /// Nesting level, 0 for the sections of the top level component
+    pub depth: usize,
+    pub name: String,
+    pub offset: usize,
+    pub size: usize,
+}
+
+/// Prints a component WASM as WAT in text format, and as the list of its sections otherwise
+pub fn process_print(bytes: &[u8], format: Format) -> Result<GolemResult, GolemError> {
+    match format {
+        Format::Text => {
+            let wat = wasmprinter::print_bytes(bytes)
+                .map_err(|err| GolemError(format!("Failed to print component: {err}")))?;
+            Ok(GolemResult::Str(wat))
+        }
+        Format::Json | Format::Yaml => {
+            let sections = wasm_sections(bytes)?;
+            Ok(GolemResult::Json(serde_json::to_value(sections).map_err(
+                |err| GolemError(format!("Failed to serialize sections: {err}")),
+            )?))
+        }
+    }
+}
+
+pub fn wasm_sections(bytes: &[u8]) -> Result<Vec<WasmSection>, GolemError> {
+    let mut sections = Vec::new();
+    let mut open = 0usize;
+
+    for payload in Parser::new(0).parse_all(bytes) {
+        let payload = payload.map_err(|err| GolemError(format!("Invalid WASM: {err}")))?;
+        match &payload {
+            Payload::Version { .. } => open += 1,
+            Payload::End(_) => open = open.saturating_sub(1),
+            _ => {
+                if let (Some(name), Some((_, range))) =
+                    (section_name(&payload), payload.as_section())
+                {
+                    sections.push(WasmSection {
+                        depth: open.saturating_sub(1),
+                        name,
+                        offset: range.start,
+                        size: range.len(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(sections)
+}
+
+fn section_name(payload: &Payload) -> Option<String> {
+    let name = match payload {
+        Payload::TypeSection(_) => "type",
+        Payload::ImportSection(_) => "import",
+        Payload::FunctionSection(_) => "function",
+        Payload::TableSection(_) => "table",
+        Payload::MemorySection(_) => "memory",
+        Payload::TagSection(_) => "tag",
+        Payload::GlobalSection(_) => "global",
+        Payload::ExportSection(_) => "export",
+        Payload::StartSection { .. } => "start",
+        Payload::ElementSection(_) => "element",
+        Payload::DataCountSection { .. } => "data-count",
+        Payload::DataSection(_) => "data",
+        Payload::CodeSectionStart { .. } => "code",
+        Payload::ModuleSection { .. } => "module",
+        Payload::InstanceSection(_) => "instance",
+        Payload::CoreTypeSection(_) => "core-type",
+        Payload::ComponentSection { .. } => "component",
+        Payload::ComponentInstanceSection(_) => "component-instance",
+        Payload::ComponentAliasSection(_) => "alias",
+        Payload::ComponentTypeSection(_) => "component-type",
+        Payload::ComponentCanonicalSection(_) => "canonical",
+        Payload::ComponentStartSection { .. } => "component-start",
+        Payload::ComponentImportSection(_) => "component-import",
+        Payload::ComponentExportSection(_) => "component-export",
+        Payload::CustomSection(reader) => return Some(format!("custom:{}", reader.name())),
+        Payload::UnknownSection { .. } => "unknown",
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::inspect::{process_print, wasm_sections};
+    use crate::model::{Format, GolemResult};
+
+    // An empty core module with a single custom section named `test`
+    const MODULE: &[u8] = b"\0asm\x01\0\0\0\x00\x06\x04testx";
+
+    #[test]
+    fn lists_sections() {
+        let sections = wasm_sections(MODULE).unwrap();
+
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].depth, 0);
+        assert_eq!(sections[0].name, "custom:test");
+    }
+
+    #[test]
+    fn prints_wat() {
+        match process_print(MODULE, Format::Text).unwrap() {
+            GolemResult::Str(wat) => assert!(wat.starts_with("(module")),
+            _ => panic!("Expected the WAT as a string"),
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_wasm() {
+        assert!(wasm_sections(b"not wasm").is_err());
+    }
+}
@@ -32,17 +32,27 @@ use tracing_subscriber::FmtSubscriber;
 pub mod clients;
 pub mod cloud;
 pub mod command;
+pub mod compat;
+pub mod compile;
 pub mod completion;
 pub mod config;
 pub mod connect_output;
 pub mod diagnose;
+pub mod download_files;
 pub mod examples;
+pub mod export_manifest;
 pub mod factory;
+pub mod import_manifest;
 pub mod init;
+pub mod inspect;
+pub mod load_local;
 pub mod model;
 pub mod oss;
 pub mod service;
+pub mod store_check;
 pub mod stubgen;
+pub mod validate_app;
+pub mod verify;
 
 #[cfg(test)]
 test_r::enable!();
@@ -0,0 +1,124 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Read;
+
+use golem_client::model::ComponentType;
+use serde::Deserialize;
+use url::Url;
+use uuid::Uuid;
+
+use crate::model::{GolemError, GolemResult, PathBufOrStdin};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LoadComponentResponse {
+    version: u64,
+}
+
+/// URL of the worker executor's admin endpoint loading a component into its local store
+fn load_url(
+    executor_url: &Url,
+    component_id: &Uuid,
+    component_type: ComponentType,
+) -> Result<Url, GolemError> {
+    let mut url = executor_url
+        .join(&format!("admin/components/{component_id}"))
+        .map_err(|err| GolemError(format!("Invalid executor URL {executor_url}: {err}")))?;
+    let component_type = match component_type {
+        ComponentType::Durable => "Durable",
+        ComponentType::Ephemeral => "Ephemeral",
+    };
+    url.query_pairs_mut()
+        .append_pair("componentType", component_type);
+    Ok(url)
+}
+
+pub async fn process_load_local(
+    from: PathBufOrStdin,
+    component_id: Option<Uuid>,
+    component_type: ComponentType,
+    executor_url: Url,
+) -> Result<GolemResult, GolemError> {
+    let bytes = match from {
+        PathBufOrStdin::Path(path) => tokio::fs::read(&path).await.map_err(|err| {
+            GolemError(format!(
+                "Failed to read component file {}: {err}",
+                path.display()
+            ))
+        })?,
+        PathBufOrStdin::Stdin => {
+            let mut bytes = Vec::new();
+            std::io::stdin()
+                .read_to_end(&mut bytes)
+                .map_err(|err| GolemError(format!("Failed to read stdin: {err}")))?;
+            bytes
+        }
+    };
+
+    let component_id = component_id.unwrap_or_else(Uuid::new_v4);
+    let url = load_url(&executor_url, &component_id, component_type)?;
+
+    let response = reqwest::Client::new()
+        .post(url)
+        .body(bytes)
+        .send()
+        .await
+        .map_err(|err| GolemError(format!("Failed to connect to the worker executor: {err}")))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(GolemError(format!(
+            "Failed to load component into the worker executor ({status}): {body}"
+        )));
+    }
+
+    let loaded: LoadComponentResponse = response
+        .json()
+        .await
+        .map_err(|err| GolemError(format!("Invalid response from the worker executor: {err}")))?;
+
+    Ok(GolemResult::Str(format!(
+        "Loaded component {component_id} as version {} into the worker executor's local store",
+        loaded.version
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use golem_client::model::ComponentType;
+    use url::Url;
+    use uuid::Uuid;
+
+    use crate::load_local::load_url;
+
+    #[test]
+    fn load_url_points_to_the_admin_endpoint() {
+        let component_id = Uuid::parse_str("c0e0a5ab-0ad5-4e1b-a6d4-4f9b6b5e6b4d").unwrap();
+
+        assert_eq!(
+            load_url(
+                &Url::parse("http://localhost:8082").unwrap(),
+                &component_id,
+                ComponentType::Ephemeral
+            )
+            .unwrap()
+            .as_str(),
+            "http://localhost:8082/admin/components/c0e0a5ab-0ad5-4e1b-a6d4-4f9b6b5e6b4d?componentType=Ephemeral"
+        );
+    }
+}
@@ -35,9 +35,10 @@ use clap_verbosity_flag::Verbosity;
 use derive_more::{Display, FromStr};
 use golem_client::model::{ApiDefinitionInfo, ApiSite, ScanCursor};
 use golem_common::model::trim_date::TrimDateTime;
+use golem_common::model::ComponentId;
 use golem_common::uri::oss::uri::ComponentUri;
 use golem_common::uri::oss::url::ComponentUrl;
-use golem_common::uri::oss::urn::WorkerUrn;
+use golem_common::uri::oss::urn::{ComponentUrn, WorkerUrn};
 use golem_examples::model::{Example, ExampleName, GuestLanguage, GuestLanguageTier};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
@@ -271,13 +272,14 @@ impl clap::Args for ComponentUriArg {
 #[derive(clap::Args, Debug, Clone)]
 #[group(required = true, multiple = false)]
 struct ComponentUriOrNameArgs {
-    /// Component URI. Either URN or URL.
+    /// Component URI. Either URN, URL or the plain component ID.
     #[arg(
         short = 'C',
         long,
         group = "component_group",
         required = true,
-        value_name = "URI"
+        value_name = "URI",
+        value_parser = parse_component_uri
     )]
     component: Option<ComponentUri>,
 
@@ -286,6 +288,16 @@ struct ComponentUriOrNameArgs {
     component_name: Option<String>,
 }
 
+// A plain UUID is taken as the ID of the component, the same as its URN
+pub fn parse_component_uri(s: &str) -> Result<ComponentUri, String> {
+    match Uuid::parse_str(s) {
+        Ok(id) => Ok(ComponentUri::URN(ComponentUrn {
+            id: ComponentId(id),
+        })),
+        Err(_) => ComponentUri::from_str(s).map_err(|err| err.to_string()),
+    }
+}
+
 impl From<&ComponentUriOrNameArgs> for ComponentUriArg {
     fn from(value: &ComponentUriOrNameArgs) -> ComponentUriArg {
         if let Some(uri) = &value.component {
@@ -55,7 +55,7 @@ pub fn load_app(
     }
 }
 
-fn load_app_validated(
+pub(crate) fn load_app_validated(
     app_resolve_mode: &ApplicationResolveMode,
 ) -> ValidatedResult<ApplicationManifest> {
     let sources = collect_sources(app_resolve_mode);
@@ -533,29 +533,59 @@ impl ApplicationManifest {
         validation: &mut ValidationBuilder,
         components: Vec<WasmComponent>,
     ) -> BTreeMap<String, WasmComponent> {
-        let (wasm_components_by_name, sources) = {
-            let mut wasm_components_by_name = BTreeMap::<String, WasmComponent>::new();
-            let mut sources = BTreeMap::<String, Vec<String>>::new();
-            for component in components {
-                sources
-                    .entry(component.name.clone())
-                    .and_modify(|sources| sources.push(component.source_as_string()))
-                    .or_insert_with(|| vec![component.source_as_string()]);
-                wasm_components_by_name.insert(component.name.clone(), component);
+        let mut components_by_name = BTreeMap::<String, Vec<WasmComponent>>::new();
+        for component in components {
+            components_by_name
+                .entry(component.name.clone())
+                .or_default()
+                .push(component);
+        }
+
+        // The same component can be defined in multiple sources as long as the definitions
+        // are the same, otherwise the conflicting properties are reported for each source
+        let mut wasm_components_by_name = BTreeMap::<String, WasmComponent>::new();
+        for (component_name, components) in components_by_name {
+            let mut duplicates = components;
+            let component = duplicates.remove(0);
+
+            if !duplicates.is_empty() {
+                validation.push_context("component name", component_name.clone());
+
+                let conflicts = duplicates
+                    .iter()
+                    .filter_map(|duplicate| {
+                        let properties = component.conflicting_properties(duplicate);
+                        (!properties.is_empty()).then(|| {
+                            format!(
+                                "{} and {} differ in: {}",
+                                component.source_as_string(),
+                                duplicate.source_as_string(),
+                                properties.join(", ")
+                            )
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
+                if conflicts.is_empty() {
+                    validation.add_warn(format!(
+                        "Component is specified multiple times with the same definition in sources: {}",
+                        std::iter::once(&component)
+                            .chain(duplicates.iter())
+                            .map(|component| component.source_as_string())
+                            .join(", ")
+                    ));
+                } else {
+                    validation.add_error(format!(
+                        "Component is specified with conflicting definitions in sources: {}",
+                        conflicts.join("; ")
+                    ));
+                }
+
+                validation.pop_context();
             }
-            (wasm_components_by_name, sources)
-        };
 
-        let non_unique_components = sources.into_iter().filter(|(_, sources)| sources.len() > 1);
-        validation.add_errors(non_unique_components, |(component_name, sources)| {
-            Some((
-                vec![("component name", component_name)],
-                format!(
-                    "Component is specified multiple times in sources: {}",
-                    sources.join(", ")
-                ),
-            ))
-        });
+            wasm_components_by_name.insert(component_name, component);
+        }
 
         for (component_name, component) in &wasm_components_by_name {
             validation.push_context("source", component.source_as_string());
@@ -721,6 +751,45 @@ impl WasmComponent {
             .parent()
             .expect("Failed to get parent for source")
     }
+
+    // The manifest properties in which the two definitions of the component differ. Paths are
+    // compared after resolving them relative to the sources, and build steps also differ when
+    // they run in different directories
+    pub fn conflicting_properties(&self, other: &WasmComponent) -> Vec<&'static str> {
+        let mut properties = Vec::new();
+
+        if self.source_dir().join(&self.wit) != other.source_dir().join(&other.wit) {
+            properties.push("wit");
+        }
+        if self.build_steps != other.build_steps
+            || (!self.build_steps.is_empty() && self.source_dir() != other.source_dir())
+        {
+            properties.push("build");
+        }
+        if self.source_dir().join(&self.input_wasm) != other.source_dir().join(&other.input_wasm) {
+            properties.push("inputWasm");
+        }
+        if self.source_dir().join(&self.output_wasm) != other.source_dir().join(&other.output_wasm)
+        {
+            properties.push("outputWasm");
+        }
+        if self.component_type != other.component_type {
+            properties.push("componentType");
+        }
+        if !self
+            .files
+            .iter()
+            .map(InitialComponentFile::key)
+            .eq(other.files.iter().map(InitialComponentFile::key))
+        {
+            properties.push("files");
+        }
+        if self.wasm_rpc_dependencies != other.wasm_rpc_dependencies {
+            properties.push("wasm-rpc dependencies");
+        }
+
+        properties
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -792,6 +861,16 @@ impl DownloadableFile {
         Ok(DownloadableFile(url))
     }
 
+    pub fn from_local_path(path: &Path) -> Result<Self, String> {
+        let canonical_path = path
+            .canonicalize()
+            .map_err(|err| format!("Failed to find local file {}: {}", path.display(), err))?;
+
+        Url::from_file_path(&canonical_path)
+            .map(DownloadableFile)
+            .map_err(|_| "Failed to convert path to URL".to_string())
+    }
+
     pub fn as_url(&self) -> &Url {
         &self.0
     }
@@ -801,7 +880,7 @@ impl DownloadableFile {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct BuildStep {
     pub command: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -818,6 +897,16 @@ pub struct InitialComponentFile {
     pub target: ComponentFilePathWithPermissions,
 }
 
+impl InitialComponentFile {
+    fn key(&self) -> (&Url, &ComponentFilePath, ComponentFilePermissions) {
+        (
+            self.source_path.as_url(),
+            &self.target.path,
+            self.target.permissions,
+        )
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 enum RawComponentType {
@@ -942,3 +1031,79 @@ impl HasUnknownProperties for ComponentStubBuildProperties {
         &self.unknown_properties
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::model::application_manifest::{ApplicationManifest, BuildStep, WasmComponent};
+    use golem_common::model::ComponentType;
+    use golem_wasm_rpc_stubgen::model::validation::{ValidatedResult, ValidationBuilder};
+    use std::path::PathBuf;
+
+    fn component(source: &str, name: &str) -> WasmComponent {
+        WasmComponent {
+            name: name.to_string(),
+            source: PathBuf::from(source),
+            build_steps: vec![BuildStep {
+                command: "cargo component build".to_string(),
+                dir: None,
+                inputs: vec![],
+                outputs: vec![],
+            }],
+            wit: PathBuf::from("wit"),
+            input_wasm: PathBuf::from("target/component.wasm"),
+            output_wasm: PathBuf::from("target/component-composed.wasm"),
+            wasm_rpc_dependencies: vec![],
+            component_type: ComponentType::Durable,
+            files: vec![],
+        }
+    }
+
+    #[test]
+    fn components_of_multiple_manifests_are_merged() {
+        let mut validation = ValidationBuilder::new();
+        let components = ApplicationManifest::validate_components(
+            &mut validation,
+            vec![
+                component("app/a/golem.yaml", "a"),
+                component("app/b/golem.yaml", "b"),
+                component("app/a/golem.yaml", "a"),
+            ],
+        );
+
+        assert_eq!(
+            components.keys().cloned().collect::<Vec<_>>(),
+            vec!["a".to_string(), "b".to_string()]
+        );
+        match validation.build(()) {
+            ValidatedResult::OkWithWarns((), warns) => {
+                assert_eq!(warns.len(), 1);
+                assert!(warns[0].contains("same definition"));
+            }
+            _ => panic!("Unexpected validation result"),
+        }
+    }
+
+    #[test]
+    fn conflicting_component_definitions_are_reported() {
+        let mut ephemeral = component("app/b/golem.yaml", "a");
+        ephemeral.component_type = ComponentType::Ephemeral;
+
+        let mut validation = ValidationBuilder::new();
+        ApplicationManifest::validate_components(
+            &mut validation,
+            vec![component("app/a/golem.yaml", "a"), ephemeral],
+        );
+
+        match validation.build(()) {
+            ValidatedResult::WarnsAndErrors(_, errors) => {
+                assert_eq!(errors.len(), 1);
+                assert!(errors[0].contains(
+                    "app/a/golem.yaml and app/b/golem.yaml differ in: wit, build, inputWasm, outputWasm, componentType"
+                ));
+            }
+            _ => panic!("Unexpected validation result"),
+        }
+    }
+}
@@ -14,13 +14,14 @@
 
 use crate::cloud::ProjectId;
 use crate::model::wave::function_wave_compatible;
-use crate::model::GolemError;
+use crate::model::{GolemError, GolemResult};
 use chrono::{DateTime, Utc};
 use golem_client::model::{
-    AnalysedType, ComponentMetadata, ComponentType, InitialComponentFile, VersionedComponentId,
+    AnalysedType, ComponentCompileResult, ComponentMetadata, ComponentType, ExecutorCompileStatus,
+    InitialComponentFile, VersionedComponentId,
 };
 use golem_common::model::trim_date::TrimDateTime;
-use golem_common::model::ComponentId;
+use golem_common::model::{ComponentFilePermissions, ComponentId};
 use golem_common::uri::oss::urn::ComponentUrn;
 use golem_wasm_ast::analysis::wave::DisplayNamedFunc;
 use golem_wasm_ast::analysis::{
@@ -28,8 +29,15 @@ use golem_wasm_ast::analysis::{
     AnalysedResourceMode, NameOptionTypePair, NameTypePair, TypeEnum, TypeFlags, TypeRecord,
     TypeTuple, TypeVariant,
 };
+use itertools::Itertools;
 use rib::{ParsedFunctionName, ParsedFunctionSite};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::future::Future;
+use std::str::FromStr;
+use std::time::Instant;
 use tracing::info;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -69,6 +77,104 @@ impl From<golem_client::model::Component> for Component {
     }
 }
 
+/// Selects a component version either by its number or by a tag pointing to it.
+///
+/// Tags can never be numbers, so any numeric value is parsed as a version.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum VersionSelector {
+    Version(u64),
+    Tag(String),
+}
+
+impl FromStr for VersionSelector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            Err("Version or tag must not be empty".to_string())
+        } else if let Ok(version) = s.parse::<u64>() {
+            Ok(VersionSelector::Version(version))
+        } else {
+            Ok(VersionSelector::Tag(s.to_string()))
+        }
+    }
+}
+
+impl Display for VersionSelector {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VersionSelector::Version(version) => write!(f, "{version}"),
+            VersionSelector::Tag(tag) => write!(f, "{tag}"),
+        }
+    }
+}
+
+/// A field of the component view, selected by its snake case name, where the `component_`
+/// prefix is optional: both `version` and `component_version` select the version.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ComponentField {
+    name: &'static str,
+    key: &'static str,
+}
+
+impl ComponentField {
+    /// The names and the keys in the serialized component view of all fields
+    const ALL: [(&'static str, &'static str); 11] = [
+        ("urn", "componentUrn"),
+        ("name", "componentName"),
+        ("version", "componentVersion"),
+        ("type", "componentType"),
+        ("size", "componentSize"),
+        ("created_at", "createdAt"),
+        ("project_id", "projectId"),
+        ("exports", "exports"),
+        ("analysis_warnings", "analysisWarnings"),
+        ("annotations", "annotations"),
+        ("files", "files"),
+    ];
+
+    /// Keeps only the given fields of a serialized component view, under the same keys. Fields
+    /// missing from the view because they are empty are selected as null.
+    pub fn select(fields: &[ComponentField], view: &Value) -> Value {
+        Value::Object(
+            fields
+                .iter()
+                .map(|field| {
+                    (
+                        field.key.to_string(),
+                        view.get(field.key).cloned().unwrap_or(Value::Null),
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
+impl FromStr for ComponentField {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+        let name = name.strip_prefix("component_").unwrap_or(name);
+        Self::ALL
+            .iter()
+            .find(|(field, _)| *field == name)
+            .map(|(name, key)| ComponentField { name, key })
+            .ok_or_else(|| {
+                format!(
+                    "Unknown field {s}, valid fields are: {}",
+                    Self::ALL.iter().map(|(name, _)| *name).join(", ")
+                )
+            })
+    }
+}
+
+impl Display for ComponentField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ComponentView {
@@ -76,11 +182,47 @@ pub struct ComponentView {
     pub component_version: u64,
     pub component_name: String,
     pub component_size: u64,
+    pub component_type: ComponentType,
     pub created_at: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub project_id: Option<ProjectId>,
     pub exports: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub analysis_warnings: Vec<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default)]
+    pub files: Vec<ComponentFileView>,
+}
+
+/// An initial file of a component version
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentFileView {
+    pub path: String,
+    pub permissions: ComponentFilePermissions,
+    pub key: String,
+}
+
+impl From<&InitialComponentFile> for ComponentFileView {
+    fn from(value: &InitialComponentFile) -> Self {
+        ComponentFileView {
+            path: value.path.clone(),
+            permissions: match value.permissions {
+                golem_client::model::ComponentFilePermissions::ReadOnly => {
+                    ComponentFilePermissions::ReadOnly
+                }
+                golem_client::model::ComponentFilePermissions::ReadWrite => {
+                    ComponentFilePermissions::ReadWrite
+                }
+            },
+            key: value.key.clone(),
+        }
+    }
 }
 
 impl TrimDateTime for ComponentView {
@@ -107,6 +249,7 @@ impl From<&Component> for ComponentView {
             component_version: value.versioned_component_id.version,
             component_name: value.component_name.to_string(),
             component_size: value.component_size,
+            component_type: value.component_type,
             created_at: value.created_at,
             project_id: value.project_id,
             exports: value
@@ -126,10 +269,108 @@ impl From<&Component> for ComponentView {
                     }
                 })
                 .collect(),
+            analysis_warnings: value.metadata.analysis_warnings.clone(),
+            annotations: value.metadata.annotations.clone(),
+            files: value.files.iter().map(ComponentFileView::from).collect(),
         }
     }
 }
 
+/// Duration of a phase of adding or updating a component
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentPhaseTiming {
+    pub phase: String,
+    pub duration_ms: u64,
+}
+
+/// Durations of the phases of adding or updating a component, in the order they ran.
+///
+/// The phases are `resolve` (looking up the component to update), `read` (reading the initial
+/// files of the component into an archive) and `upload` (sending the component and its files,
+/// up to the server acknowledging the new version, which includes the server's analysis of the
+/// component).
+#[derive(Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentTimings {
+    pub phases: Vec<ComponentPhaseTiming>,
+}
+
+impl ComponentTimings {
+    pub async fn measure<T>(&mut self, phase: &str, f: impl Future<Output = T>) -> T {
+        let start = Instant::now();
+        let result = f.await;
+        self.phases.push(ComponentPhaseTiming {
+            phase: phase.to_string(),
+            duration_ms: start.elapsed().as_millis() as u64,
+        });
+        result
+    }
+
+    pub fn total_ms(&self) -> u64 {
+        self.phases.iter().map(|phase| phase.duration_ms).sum()
+    }
+}
+
+/// The outcome of adding or updating a component: the result to print, the timings of its
+/// phases, and the version the component service created
+pub struct ComponentUpload {
+    pub result: GolemResult,
+    pub timings: ComponentTimings,
+    pub version: u64,
+}
+
+/// The result of the last compilation of a component version on each worker executor
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentCompileStatusView {
+    pub component_urn: ComponentUrn,
+    pub component_version: u64,
+    pub executors: Vec<ExecutorCompileStatus>,
+}
+
+/// Where waiting for a quorum of worker executors to compile a component version stands
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CompileQuorum {
+    Reached,
+    Pending,
+    /// Too many executors failed to compile the version for the quorum to be reached
+    Unreachable,
+}
+
+impl ComponentCompileStatusView {
+    pub fn compiled(&self) -> usize {
+        self.count(|result| result.success)
+    }
+
+    pub fn failed(&self) -> usize {
+        self.count(|result| !result.success)
+    }
+
+    /// The quorum defaults to a majority of the executors
+    pub fn required_quorum(&self, quorum: Option<usize>) -> usize {
+        quorum.unwrap_or(self.executors.len() / 2 + 1)
+    }
+
+    pub fn compile_quorum(&self, quorum: Option<usize>) -> CompileQuorum {
+        let quorum = self.required_quorum(quorum);
+        if self.compiled() >= quorum {
+            CompileQuorum::Reached
+        } else if self.executors.len() - self.failed() < quorum {
+            CompileQuorum::Unreachable
+        } else {
+            CompileQuorum::Pending
+        }
+    }
+
+    fn count(&self, f: impl Fn(&ComponentCompileResult) -> bool) -> usize {
+        self.executors
+            .iter()
+            .filter(|executor| executor.result.as_ref().is_some_and(&f))
+            .count()
+    }
+}
+
 fn render_type(typ: &AnalysedType) -> String {
     match typ {
         AnalysedType::Variant(TypeVariant { cases }) => {
@@ -309,7 +550,20 @@ pub fn function_params_types<'t>(
 mod tests {
     use test_r::test;
 
-    use crate::model::component::show_exported_function;
+    use std::collections::BTreeMap;
+
+    use crate::model::component::{
+        show_exported_function, CompileQuorum, Component, ComponentCompileStatusView,
+        ComponentField, ComponentTimings, ComponentView, VersionSelector,
+    };
+    use crate::model::Format;
+    use golem_client::model::{
+        ComponentCompileResult, ComponentFilePermissions, ExecutorCompileStatus,
+        InitialComponentFile, VersionedComponentId,
+    };
+    use golem_common::model::component_metadata::ComponentMetadata;
+    use golem_common::model::{ComponentId, ComponentType};
+    use golem_common::uri::oss::urn::ComponentUrn;
     use golem_wasm_ast::analysis::analysed_type::{
         bool, case, chr, f32, f64, field, flags, handle, list, option, r#enum, record, result,
         result_err, result_ok, s16, s32, s64, s8, str, tuple, u16, u32, u64, u8, unit_case,
@@ -320,6 +574,108 @@ mod tests {
         AnalysedResourceMode, AnalysedType,
     };
 
+    #[test]
+    fn component_fields_select_from_the_view() {
+        let view = serde_json::json!({
+            "componentName": "shopping-cart",
+            "componentVersion": 2,
+            "componentType": "Durable",
+            "exports": ["golem:it/api.{add-item}(item: string)"],
+        });
+        let fields = ["component_version", "type", "project_id"]
+            .iter()
+            .map(|field| field.parse::<ComponentField>().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            ComponentField::select(&fields, &view),
+            serde_json::json!({
+                "componentVersion": 2,
+                "componentType": "Durable",
+                "projectId": null,
+            })
+        );
+        assert!("owner"
+            .parse::<ComponentField>()
+            .unwrap_err()
+            .ends_with("valid fields are: urn, name, version, type, size, created_at, project_id, exports, analysis_warnings, annotations, files"));
+    }
+
+    #[test]
+    fn version_selector_from_str() {
+        assert_eq!("3".parse(), Ok(VersionSelector::Version(3)));
+        assert_eq!(
+            "stable".parse(),
+            Ok(VersionSelector::Tag("stable".to_string()))
+        );
+        assert!("".parse::<VersionSelector>().is_err());
+    }
+
+    #[test]
+    fn component_views_serialize_to_yaml() {
+        let component = Component {
+            versioned_component_id: VersionedComponentId {
+                component_id: uuid::Uuid::nil(),
+                version: 2,
+            },
+            component_name: "shopping-cart".to_string(),
+            component_size: 1234,
+            component_type: ComponentType::Durable,
+            metadata: ComponentMetadata {
+                exports: vec![],
+                producers: vec![],
+                memories: vec![],
+                world: None,
+                analysis_warnings: vec![],
+                annotations: BTreeMap::new(),
+            },
+            project_id: None,
+            created_at: None,
+            files: vec![InitialComponentFile {
+                key: "ab".repeat(32),
+                path: "/config/app.conf".to_string(),
+                permissions: ComponentFilePermissions::ReadOnly,
+            }],
+        };
+        let view: ComponentView = component.into();
+
+        let yaml = serde_yaml::to_string(&vec![view.clone()]).unwrap();
+        assert!(
+            yaml.contains("- componentUrn: urn:component:00000000-0000-0000-0000-000000000000\n")
+        );
+        assert!(yaml.contains("  componentVersion: 2\n"));
+        assert!(yaml.contains("  - path: /config/app.conf\n    permissions: read-only\n"));
+        assert_eq!(
+            serde_yaml::from_str::<Vec<ComponentView>>(&yaml).unwrap(),
+            vec![view]
+        );
+
+        assert_eq!("yaml".parse(), Ok(Format::Yaml));
+        assert_eq!(Format::default(), Format::Text);
+    }
+
+    #[test]
+    async fn component_timings_keep_phase_order() {
+        let mut timings = ComponentTimings::default();
+
+        let archive = timings.measure("read", async { "archive" }).await;
+        timings
+            .measure(
+                "upload",
+                tokio::time::sleep(std::time::Duration::from_millis(5)),
+            )
+            .await;
+
+        assert_eq!(archive, "archive");
+        let phases: Vec<_> = timings.phases.iter().map(|t| t.phase.as_str()).collect();
+        assert_eq!(phases, vec!["read", "upload"]);
+        assert!(timings.phases[1].duration_ms >= 5);
+        assert_eq!(
+            timings.total_ms(),
+            timings.phases[0].duration_ms + timings.phases[1].duration_ms
+        );
+    }
+
     #[test]
     fn show_exported_function_handles_type_handle() {
         let f = AnalysedFunction {
@@ -610,4 +966,37 @@ mod tests {
     fn same_export_for_bool() {
         ensure_same_export(bool(), "bool")
     }
+
+    #[test]
+    fn compile_quorum_defaults_to_a_majority() {
+        let executor = |success: Option<bool>| ExecutorCompileStatus {
+            executor: "localhost:9000".to_string(),
+            result: success.map(|success| ComponentCompileResult {
+                success,
+                error: (!success).then(|| "invalid".to_string()),
+                duration_millis: 10,
+            }),
+        };
+        let view = |results: &[Option<bool>]| ComponentCompileStatusView {
+            component_urn: ComponentUrn {
+                id: ComponentId(uuid::Uuid::nil()),
+            },
+            component_version: 0,
+            executors: results.iter().copied().map(executor).collect(),
+        };
+
+        let pending = view(&[Some(true), None, None]);
+        assert_eq!((pending.compiled(), pending.failed()), (1, 0));
+        assert_eq!(pending.required_quorum(None), 2);
+        assert_eq!(pending.compile_quorum(None), CompileQuorum::Pending);
+        assert_eq!(pending.compile_quorum(Some(1)), CompileQuorum::Reached);
+
+        let reached = view(&[Some(true), Some(false), Some(true)]);
+        assert_eq!(reached.compile_quorum(None), CompileQuorum::Reached);
+        assert_eq!(reached.compile_quorum(Some(3)), CompileQuorum::Unreachable);
+
+        let failing = view(&[Some(false), Some(false), None]);
+        assert_eq!(failing.compile_quorum(None), CompileQuorum::Unreachable);
+        assert_eq!(view(&[]).compile_quorum(None), CompileQuorum::Unreachable);
+    }
 }
@@ -168,6 +168,9 @@ mod tests {
                     results: func_res,
                 })],
                 memories: vec![],
+                world: None,
+                analysis_warnings: vec![],
+                annotations: Default::default(),
             },
             project_id: None,
             created_at: Some(Utc::now()),
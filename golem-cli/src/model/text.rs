@@ -475,9 +475,12 @@ pub mod api_deployment {
 }
 
 pub mod component {
-    use crate::model::component::ComponentView;
+    use crate::download_files::DownloadFilesView;
+    use crate::model::component::{ComponentCompileStatusView, ComponentTimings, ComponentView};
     use crate::model::text::fmt::*;
     use cli_table::{format::Justify, print_stdout, Table, WithTitle};
+    use golem_client::model::ExecutorCompileStatus;
+    use itertools::Itertools;
     use serde::{Deserialize, Serialize};
 
     #[derive(Table)]
@@ -525,10 +528,32 @@ pub mod component {
             .fmt_field("Component URN", &view.component_urn, format_main_id)
             .fmt_field("Component name", &view.component_name, format_id)
             .fmt_field("Component version", &view.component_version, format_id)
+            .fmt_field("Component type", &view.component_type, format_id)
             .fmt_field_option("Project ID", &view.project_id, format_id)
             .fmt_field("Component size", &view.component_size, format_binary_size)
             .fmt_field_option("Created at", &view.created_at, |d| d.to_string())
-            .fmt_field("Exports", &view.exports, |e| format_exports(e.as_slice()));
+            .fmt_field("Exports", &view.exports, |e| format_exports(e.as_slice()))
+            .fmt_field_optional(
+                "Analysis warnings",
+                &view.analysis_warnings,
+                !view.analysis_warnings.is_empty(),
+                |w| w.iter().map(format_warn).join("\n"),
+            )
+            .fmt_field_optional(
+                "Annotations",
+                &view.annotations,
+                !view.annotations.is_empty(),
+                |a| {
+                    a.iter()
+                        .map(|(key, value)| format!("{key}: {value}"))
+                        .join("\n")
+                },
+            )
+            .fmt_field_optional("Initial files", &view.files, !view.files.is_empty(), |f| {
+                f.iter()
+                    .map(|file| format!("{} ({})", file.path, file.permissions.as_compact_str()))
+                    .join("\n")
+            });
 
         fields.build()
     }
@@ -566,6 +591,98 @@ pub mod component {
         }
     }
 
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct ComponentRenameView(pub ComponentView);
+
+    impl MessageWithFields for ComponentRenameView {
+        fn message(&self) -> String {
+            format!(
+                "Renamed component to {}",
+                format_message_highlight(&self.0.component_name)
+            )
+        }
+
+        fn fields(&self) -> Vec<(&'static str, String)> {
+            component_view_fields(&self.0)
+        }
+    }
+
+    impl TextFormat for ComponentTimings {
+        fn print(&self) {
+            println!("Timings:");
+
+            let max_phase_len = self
+                .phases
+                .iter()
+                .map(|timing| timing.phase.len())
+                .max()
+                .unwrap_or(0)
+                .max("total".len())
+                + 1;
+
+            for timing in &self.phases {
+                println!(
+                    "  {: <max_phase_len$} {}ms",
+                    format!("{}:", timing.phase),
+                    timing.duration_ms
+                );
+            }
+            println!(
+                "  {: <max_phase_len$} {}",
+                "total:",
+                format_id(&format!("{}ms", self.total_ms()))
+            );
+        }
+    }
+
+    #[derive(Table)]
+    struct ExecutorCompileStatusTableView {
+        #[table(title = "Executor")]
+        pub executor: String,
+        #[table(title = "Status")]
+        pub status: String,
+        #[table(title = "Duration", justify = "Justify::Right")]
+        pub duration: String,
+        #[table(title = "Error")]
+        pub error: String,
+    }
+
+    impl From<&ExecutorCompileStatus> for ExecutorCompileStatusTableView {
+        fn from(value: &ExecutorCompileStatus) -> Self {
+            let (status, duration, error) = match &value.result {
+                Some(result) => (
+                    if result.success { "compiled" } else { "failed" },
+                    format!("{}ms", result.duration_millis),
+                    result.error.clone().unwrap_or_default(),
+                ),
+                None => ("not compiled", String::new(), String::new()),
+            };
+            Self {
+                executor: value.executor.clone(),
+                status: status.to_string(),
+                duration,
+                error,
+            }
+        }
+    }
+
+    impl TextFormat for DownloadFilesView {
+        fn print(&self) {
+            println!("{}", self.report())
+        }
+    }
+
+    impl TextFormat for ComponentCompileStatusView {
+        fn print(&self) {
+            println!(
+                "Compile status of component {} version {}:",
+                format_main_id(&self.component_urn),
+                format_id(&self.component_version)
+            );
+            print_table::<_, ExecutorCompileStatusTableView>(&self.executors);
+        }
+    }
+
     #[derive(Debug, Clone, Serialize, Deserialize)]
     pub struct ComponentGetView(pub ComponentView);
 
@@ -692,6 +809,10 @@ pub mod profile {
             println!(
                 "Default output format: {}",
                 format_message_highlight(&self.default_format)
+            );
+            println!(
+                "Default component type: {}",
+                format_message_highlight(&self.default_component_type())
             )
         }
     }
@@ -15,7 +15,9 @@
 use std::io::Read;
 
 use async_trait::async_trait;
-use golem_client::model::ComponentFilePathWithPermissionsList;
+use futures_util::StreamExt;
+use golem_client::api::ComponentError;
+use golem_client::model::{ComponentFilePathWithPermissionsList, RenameComponentRequest};
 
 use crate::clients::component::ComponentClient;
 use golem_common::uri::oss::urn::ComponentUrn;
@@ -65,6 +67,99 @@ impl<C: golem_client::api::ComponentClient + Sync + Send> ComponentClient
             .into())
     }
 
+    async fn get_metadata_by_tag(
+        &self,
+        component_urn: &ComponentUrn,
+        tag: &str,
+    ) -> Result<Component, GolemError> {
+        info!("Getting component version by tag");
+
+        Ok(self
+            .client
+            .get_component_metadata_by_tag(&component_urn.id.0, tag)
+            .await?
+            .into())
+    }
+
+    async fn tag(
+        &self,
+        component_urn: &ComponentUrn,
+        version: u64,
+        tag: &str,
+    ) -> Result<Component, GolemError> {
+        info!("Tagging component version");
+
+        Ok(self
+            .client
+            .tag_component_version(&component_urn.id.0, &version.to_string(), tag)
+            .await?
+            .into())
+    }
+
+    async fn rename(
+        &self,
+        component_urn: &ComponentUrn,
+        name: &ComponentName,
+    ) -> Result<Component, GolemError> {
+        info!("Renaming component");
+
+        Ok(self
+            .client
+            .rename_component(
+                &component_urn.id.0,
+                &RenameComponentRequest {
+                    name: name.0.clone(),
+                },
+            )
+            .await?
+            .into())
+    }
+
+    async fn download(
+        &self,
+        component_urn: &ComponentUrn,
+        version: u64,
+    ) -> Result<Vec<u8>, GolemError> {
+        info!("Downloading component version");
+
+        let mut stream = self
+            .client
+            .download_component(&component_urn.id.0, Some(version))
+            .await?;
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            bytes.extend_from_slice(&chunk?);
+        }
+        Ok(bytes)
+    }
+
+    async fn download_file(
+        &self,
+        component_urn: &ComponentUrn,
+        version: u64,
+        key: &str,
+    ) -> Result<Option<Vec<u8>>, GolemError> {
+        info!("Downloading initial component file {key}");
+
+        let result = self
+            .client
+            .download_component_file(&component_urn.id.0, key, Some(version))
+            .await;
+
+        let mut stream = match result {
+            Ok(stream) => stream,
+            Err(golem_client::Error::Item(ComponentError::Error404(_))) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            bytes.extend_from_slice(&chunk?);
+        }
+        Ok(Some(bytes))
+    }
+
     async fn find(
         &self,
         name: Option<ComponentName>,
@@ -142,6 +237,7 @@ impl<C: golem_client::api::ComponentClient + Sync + Send> ComponentClient
         urn: ComponentUrn,
         path: PathBufOrStdin,
         component_type: Option<golem_client::model::ComponentType>,
+        expected_current_version: Option<u64>,
         files_archive: Option<&Path>,
         files_permissions: Option<&ComponentFilePathWithPermissionsList>,
     ) -> Result<Component, GolemError> {
@@ -166,6 +262,7 @@ impl<C: golem_client::api::ComponentClient + Sync + Send> ComponentClient
                 self.client
                     .update_component(
                         &urn.id.0,
+                        expected_current_version,
                         component_type.as_ref(),
                         file,
                         files_permissions,
@@ -183,6 +280,7 @@ impl<C: golem_client::api::ComponentClient + Sync + Send> ComponentClient
                 self.client
                     .update_component(
                         &urn.id.0,
+                        expected_current_version,
                         component_type.as_ref(),
                         bytes,
                         files_permissions,
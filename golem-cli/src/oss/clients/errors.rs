@@ -18,13 +18,14 @@ use golem_client::api::{
 };
 use golem_client::model::{
     GolemError, GolemErrorComponentDownloadFailed, GolemErrorComponentParseFailed,
-    GolemErrorFailedToResumeWorker, GolemErrorFileSystemError,
+    GolemErrorComponentSignatureInvalid, GolemErrorFailedToResumeWorker, GolemErrorFileSystemError,
     GolemErrorGetLatestVersionOfComponentFailed, GolemErrorInitialComponentFileDownloadFailed,
     GolemErrorInterrupted, GolemErrorInvalidRequest, GolemErrorInvalidShardId,
-    GolemErrorPromiseAlreadyCompleted, GolemErrorPromiseDropped, GolemErrorPromiseNotFound,
-    GolemErrorRuntimeError, GolemErrorUnexpectedOplogEntry, GolemErrorUnknown,
-    GolemErrorValueMismatch, GolemErrorWorkerAlreadyExists, GolemErrorWorkerCreationFailed,
-    GolemErrorWorkerNotFound, PromiseId, WorkerId, WorkerServiceErrorsBody,
+    GolemErrorMissingInitialFile, GolemErrorPromiseAlreadyCompleted, GolemErrorPromiseDropped,
+    GolemErrorPromiseNotFound, GolemErrorRuntimeError, GolemErrorUnexpectedOplogEntry,
+    GolemErrorUnknown, GolemErrorValueMismatch, GolemErrorWorkerAlreadyExists,
+    GolemErrorWorkerCreationFailed, GolemErrorWorkerNotFound, PromiseId, WorkerId,
+    WorkerServiceErrorsBody,
 };
 use itertools::Itertools;
 
@@ -202,6 +203,22 @@ fn display_golem_error(error: GolemError) -> String {
         GolemError::FileSystemError(GolemErrorFileSystemError { path, reason, .. }) => {
             format!("Error working with file {}: {}", path, reason)
         }
+        GolemError::ComponentSignatureInvalid(GolemErrorComponentSignatureInvalid {
+            component_id,
+            reason,
+        }) => {
+            format!(
+                "Invalid signature of component {}#{}: {}",
+                component_id.component_id, component_id.version, reason
+            )
+        }
+        GolemError::ShuttingDown(_) => "Worker executor is shutting down".to_string(),
+        GolemError::MissingInitialFile(GolemErrorMissingInitialFile { key, path }) => {
+            format!(
+                "Initial file {} is missing from the blob storage: {}",
+                path, key
+            )
+        }
     }
 }
 
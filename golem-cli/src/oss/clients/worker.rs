@@ -25,8 +25,8 @@ use async_trait::async_trait;
 use futures_util::{future, pin_mut, SinkExt, StreamExt};
 use golem_client::api::WorkerError;
 use golem_client::model::{
-    InvokeParameters, InvokeResult, ScanCursor, UpdateWorkerRequest, WorkerCreationRequest,
-    WorkerFilter, WorkerId, WorkersMetadataRequest,
+    ExecutorCompileStatus, InvokeParameters, InvokeResult, ScanCursor, UpdateWorkerRequest,
+    WorkerCreationRequest, WorkerFilter, WorkerId, WorkersMetadataRequest,
 };
 use golem_client::{Context, Error};
 use golem_common::model::public_oplog::{OplogCursor, PublicOplogEntry};
@@ -556,6 +556,35 @@ impl<C: golem_client::api::WorkerClient + Sync + Send> WorkerClient for WorkerCl
 
         Ok(entries)
     }
+
+    async fn get_compile_status(
+        &self,
+        component_urn: ComponentUrn,
+        version: u64,
+    ) -> Result<Vec<ExecutorCompileStatus>, GolemError> {
+        info!("Getting compile status of {component_urn} version {version}");
+
+        Ok(self
+            .client
+            .get_component_compile_status(&component_urn.id.0, version)
+            .await?
+            .executors)
+    }
+
+    async fn warm_component(
+        &self,
+        component_urn: ComponentUrn,
+        version: u64,
+        force_recompile: bool,
+    ) -> Result<Vec<ExecutorCompileStatus>, GolemError> {
+        info!("Warming {component_urn} version {version}, force recompile: {force_recompile}");
+
+        Ok(self
+            .client
+            .warm_component(&component_urn.id.0, version, Some(force_recompile))
+            .await?
+            .executors)
+    }
 }
 
 fn get_worker_golem_error(status: u16, body: Vec<u8>) -> GolemError {
@@ -55,8 +55,10 @@ pub async fn async_main<ProfileAdd: Into<UniversalProfileAdd> + clap::Args>(
             subcommand
                 .handle(
                     format,
+                    profile.config.default_component_type(),
                     factory.component_service(),
                     factory.deploy_service(),
+                    factory.worker_service(),
                     factory.project_resolver().as_ref(),
                 )
                 .await
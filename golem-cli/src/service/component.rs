@@ -14,9 +14,13 @@
 
 use crate::clients::component::ComponentClient;
 use crate::clients::file_download::FileDownloadClient;
-use crate::model::application_manifest::InitialComponentFile;
-use crate::model::component::{Component, ComponentView};
-use crate::model::text::component::{ComponentAddView, ComponentGetView, ComponentUpdateView};
+use crate::model::application_manifest::{DownloadableFile, InitialComponentFile};
+use crate::model::component::{
+    Component, ComponentTimings, ComponentUpload, ComponentView, VersionSelector,
+};
+use crate::model::text::component::{
+    ComponentAddView, ComponentGetView, ComponentRenameView, ComponentUpdateView,
+};
 use crate::model::{ComponentName, Format, GolemError, GolemResult, PathBufOrStdin};
 use async_trait::async_trait;
 use async_zip::base::write::ZipFileWriter;
@@ -51,17 +55,18 @@ pub trait ComponentService {
         non_interactive: bool,
         format: Format,
         files: Vec<InitialComponentFile>,
-    ) -> Result<GolemResult, GolemError>;
+    ) -> Result<ComponentUpload, GolemError>;
     async fn update(
         &self,
         component_uri: ComponentUri,
         component_file: PathBufOrStdin,
         component_type: Option<ComponentType>,
+        expected_current_version: Option<u64>,
         project: Option<Self::ProjectContext>,
         non_interactive: bool,
         format: Format,
         files: Vec<InitialComponentFile>,
-    ) -> Result<GolemResult, GolemError>;
+    ) -> Result<ComponentUpload, GolemError>;
     async fn list(
         &self,
         component_name: Option<ComponentName>,
@@ -70,9 +75,30 @@ pub trait ComponentService {
     async fn get(
         &self,
         component_uri: ComponentUri,
-        version: Option<u64>,
+        version: Option<VersionSelector>,
         project: Option<Self::ProjectContext>,
     ) -> Result<GolemResult, GolemError>;
+    async fn tag(
+        &self,
+        component_uri: ComponentUri,
+        version: u64,
+        tag: String,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<GolemResult, GolemError>;
+    async fn rename(
+        &self,
+        component_uri: ComponentUri,
+        new_name: ComponentName,
+        project: Option<Self::ProjectContext>,
+        non_interactive: bool,
+        format: Format,
+    ) -> Result<GolemResult, GolemError>;
+    async fn download(
+        &self,
+        component_uri: ComponentUri,
+        version: Option<VersionSelector>,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<Vec<u8>, GolemError>;
     async fn resolve_uri(
         &self,
         uri: ComponentUri,
@@ -87,6 +113,26 @@ pub trait ComponentService {
         &self,
         component_urn: &ComponentUrn,
     ) -> Result<Component, GolemError>;
+    /// The version picked by the selector, the latest one if there is none
+    async fn get_selected_metadata(
+        &self,
+        urn: &ComponentUrn,
+        version: Option<VersionSelector>,
+    ) -> Result<Component, GolemError>;
+    /// The content of an initial file of the component version, None if it is missing
+    async fn download_file(
+        &self,
+        urn: &ComponentUrn,
+        version: u64,
+        key: &str,
+    ) -> Result<Option<Vec<u8>>, GolemError>;
+    /// The latest version of every component, ordered by name
+    async fn list_latest(
+        &self,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<Vec<Component>, GolemError>;
+    /// Reads a local file, or downloads a remote one
+    async fn fetch_file(&self, file: &DownloadableFile) -> Result<Vec<u8>, GolemError>;
     async fn resolve_component_name(&self, uri: &ComponentUri) -> Result<String, GolemError> {
         match uri {
             ComponentUri::URN(urn) => {
@@ -256,9 +302,15 @@ impl<ProjectContext: Display + Send + Sync> ComponentService
         non_interactive: bool,
         format: Format,
         files: Vec<InitialComponentFile>,
-    ) -> Result<GolemResult, GolemError> {
+    ) -> Result<ComponentUpload, GolemError> {
+        let mut timings = ComponentTimings::default();
+
         let files_archive = if !files.is_empty() {
-            Some(self.build_files_archive(files).await?)
+            Some(
+                timings
+                    .measure("read", self.build_files_archive(files))
+                    .await?,
+            )
         } else {
             None
         };
@@ -266,20 +318,22 @@ impl<ProjectContext: Display + Send + Sync> ComponentService
         let files_archive_path = files_archive.as_ref().map(|fa| fa.archive_path.as_path());
         let files_archive_properties = files_archive.as_ref().map(|fa| &fa.properties);
 
-        let result = self
-            .client
-            .add(
-                component_name.clone(),
-                component_file.clone(),
-                &project,
-                component_type,
-                files_archive_path,
-                files_archive_properties,
+        let result = timings
+            .measure(
+                "upload",
+                self.client.add(
+                    component_name.clone(),
+                    component_file.clone(),
+                    &project,
+                    component_type,
+                    files_archive_path,
+                    files_archive_properties,
+                ),
             )
             .await;
 
         let can_fallback = format == Format::Text;
-        let result = match result {
+        let (result, version) = match result {
             Err(GolemError(message))
                 if message.starts_with("Component already exists") && can_fallback =>
             {
@@ -299,14 +353,18 @@ impl<ProjectContext: Display + Send + Sync> ComponentService
                         let component_uri = ComponentUri::URL(ComponentUrl {
                             name: component_name.0.clone(),
                         });
-                        let urn = self.resolve_uri(component_uri, &project).await?;
-                        self.client.update(
+                        let urn = timings.measure("resolve", self.resolve_uri(component_uri, &project)).await?;
+                        timings.measure("upload", self.client.update(
                             urn,
                             component_file,
                             Some(component_type),
+                            None,
                             files_archive_path,
                             files_archive_properties
-                        ).await.map(|component| GolemResult::Ok(Box::new(ComponentUpdateView(component.into()))))
+                        )).await.map(|component| {
+                            let version = component.versioned_component_id.version;
+                            (GolemResult::Ok(Box::new(ComponentUpdateView(component.into()))), version)
+                        })
 
                     }
                     Ok(false) => Err(GolemError(message)),
@@ -314,15 +372,23 @@ impl<ProjectContext: Display + Send + Sync> ComponentService
                 }
             }
             Err(other) => Err(other),
-            Ok(component) => Ok(GolemResult::Ok(Box::new(ComponentAddView(
-                component.into(),
-            )))),
+            Ok(component) => {
+                let version = component.versioned_component_id.version;
+                Ok((
+                    GolemResult::Ok(Box::new(ComponentAddView(component.into()))),
+                    version,
+                ))
+            }
         }?;
 
         // We need to keep the files archive open until the client is done uploading it
         drop(files_archive);
 
-        Ok(result)
+        Ok(ComponentUpload {
+            result,
+            timings,
+            version,
+        })
     }
 
     async fn update(
@@ -330,15 +396,24 @@ impl<ProjectContext: Display + Send + Sync> ComponentService
         component_uri: ComponentUri,
         component_file: PathBufOrStdin,
         component_type: Option<ComponentType>,
+        expected_current_version: Option<u64>,
         project: Option<Self::ProjectContext>,
         non_interactive: bool,
         format: Format,
         files: Vec<InitialComponentFile>,
-    ) -> Result<GolemResult, GolemError> {
-        let result = self.resolve_uri(component_uri.clone(), &project).await;
+    ) -> Result<ComponentUpload, GolemError> {
+        let mut timings = ComponentTimings::default();
+
+        let result = timings
+            .measure("resolve", self.resolve_uri(component_uri.clone(), &project))
+            .await;
 
         let files_archive = if !files.is_empty() {
-            Some(self.build_files_archive(files).await?)
+            Some(
+                timings
+                    .measure("read", self.build_files_archive(files))
+                    .await?,
+            )
         } else {
             None
         };
@@ -346,9 +421,10 @@ impl<ProjectContext: Display + Send + Sync> ComponentService
         let files_archive_path = files_archive.as_ref().map(|fa| fa.archive_path.as_path());
         let files_archive_properties = files_archive.as_ref().map(|fa| &fa.properties);
 
-        let can_fallback =
-            format == Format::Text && matches!(component_uri, ComponentUri::URL { .. });
-        let result = match result {
+        let can_fallback = format == Format::Text
+            && matches!(component_uri, ComponentUri::URL { .. })
+            && expected_current_version.is_none();
+        let (result, version) = match result {
             Err(GolemError(message))
                 if message.starts_with("Can't find component") && can_fallback =>
             {
@@ -369,8 +445,9 @@ impl<ProjectContext: Display + Send + Sync> ComponentService
                                 ComponentUri::URL(ComponentUrl { name }) => ComponentName(name.clone()),
                                 _ => unreachable!(),
                             };
-                            self.client.add(component_name, component_file, &project, component_type.unwrap_or(ComponentType::Durable), files_archive_path, files_archive_properties).await.map(|component| {
-                                GolemResult::Ok(Box::new(ComponentAddView(component.into())))
+                            timings.measure("upload", self.client.add(component_name, component_file, &project, component_type.unwrap_or(ComponentType::Durable), files_archive_path, files_archive_properties)).await.map(|component| {
+                                let version = component.versioned_component_id.version;
+                                (GolemResult::Ok(Box::new(ComponentAddView(component.into()))), version)
                             })
 
                         }
@@ -379,23 +456,36 @@ impl<ProjectContext: Display + Send + Sync> ComponentService
                     }
             }
             Err(other) => Err(other),
-            Ok(urn) => self
-                .client
-                .update(
-                    urn,
-                    component_file.clone(),
-                    component_type,
-                    files_archive_path,
-                    files_archive_properties,
+            Ok(urn) => timings
+                .measure(
+                    "upload",
+                    self.client.update(
+                        urn,
+                        component_file.clone(),
+                        component_type,
+                        expected_current_version,
+                        files_archive_path,
+                        files_archive_properties,
+                    ),
                 )
                 .await
-                .map(|component| GolemResult::Ok(Box::new(ComponentUpdateView(component.into())))),
+                .map(|component| {
+                    let version = component.versioned_component_id.version;
+                    (
+                        GolemResult::Ok(Box::new(ComponentUpdateView(component.into()))),
+                        version,
+                    )
+                }),
         }?;
 
         // We need to keep the files archive open until the client is done uploading it
         drop(files_archive);
 
-        Ok(result)
+        Ok(ComponentUpload {
+            result,
+            timings,
+            version,
+        })
     }
 
     async fn list(
@@ -412,18 +502,90 @@ impl<ProjectContext: Display + Send + Sync> ComponentService
     async fn get(
         &self,
         component_uri: ComponentUri,
-        version: Option<u64>,
+        version: Option<VersionSelector>,
         project: Option<Self::ProjectContext>,
     ) -> Result<GolemResult, GolemError> {
         let urn = self.resolve_uri(component_uri, &project).await?;
-        let component = match version {
-            Some(v) => self.get_metadata(&urn, v).await?,
-            None => self.get_latest_metadata(&urn).await?,
-        };
+        let component = self.get_selected_metadata(&urn, version).await?;
+        let view: ComponentView = component.into();
+        Ok(GolemResult::Ok(Box::new(ComponentGetView(view))))
+    }
+
+    async fn download(
+        &self,
+        component_uri: ComponentUri,
+        version: Option<VersionSelector>,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<Vec<u8>, GolemError> {
+        let urn = self.resolve_uri(component_uri, &project).await?;
+        let component = self.get_selected_metadata(&urn, version).await?;
+        self.client
+            .download(&urn, component.versioned_component_id.version)
+            .await
+    }
+
+    async fn tag(
+        &self,
+        component_uri: ComponentUri,
+        version: u64,
+        tag: String,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<GolemResult, GolemError> {
+        let urn = self.resolve_uri(component_uri, &project).await?;
+        let component = self.client.tag(&urn, version, &tag).await?;
         let view: ComponentView = component.into();
         Ok(GolemResult::Ok(Box::new(ComponentGetView(view))))
     }
 
+    async fn rename(
+        &self,
+        component_uri: ComponentUri,
+        new_name: ComponentName,
+        project: Option<Self::ProjectContext>,
+        non_interactive: bool,
+        format: Format,
+    ) -> Result<GolemResult, GolemError> {
+        let urn = self.resolve_uri(component_uri, &project).await?;
+        let component = self.client.get_latest_metadata(&urn).await?;
+
+        let taken_by = self
+            .client
+            .find(Some(new_name.clone()), &project)
+            .await?
+            .into_iter()
+            .find(|c| c.versioned_component_id.component_id != urn.id.0);
+        if let Some(other) = taken_by {
+            return Err(GolemError(format!(
+                "Component name {new_name} is already taken by component {}",
+                other.versioned_component_id.component_id
+            )));
+        }
+
+        if format == Format::Text && !non_interactive {
+            let answer = inquire::Confirm::new(&format!(
+                "Do you want to rename component {} to {new_name}?",
+                component.component_name
+            ))
+            .with_default(false)
+            .prompt();
+
+            match answer {
+                Ok(true) => {}
+                Ok(false) => return Ok(GolemResult::Str("Operation canceled by the user".to_string())),
+                Err(error) => return Err(GolemError(format!("Error while asking for confirmation: {}; Use the --non-interactive (-y) flag to bypass it.", error))),
+            }
+        } else if !non_interactive {
+            return Err(GolemError(
+                "Pass the --non-interactive (-y) flag or use text format for manual confirmation"
+                    .to_string(),
+            ));
+        }
+
+        let component = self.client.rename(&urn, &new_name).await?;
+        let view: ComponentView = component.into();
+        Ok(GolemResult::Ok(Box::new(ComponentRenameView(view))))
+    }
+
     async fn resolve_uri(
         &self,
         uri: ComponentUri,
@@ -487,6 +649,56 @@ impl<ProjectContext: Display + Send + Sync> ComponentService
     async fn get_latest_metadata(&self, urn: &ComponentUrn) -> Result<Component, GolemError> {
         self.client.get_latest_metadata(urn).await
     }
+
+    async fn get_selected_metadata(
+        &self,
+        urn: &ComponentUrn,
+        version: Option<VersionSelector>,
+    ) -> Result<Component, GolemError> {
+        match version {
+            Some(VersionSelector::Version(v)) => self.client.get_metadata(urn, v).await,
+            Some(VersionSelector::Tag(tag)) => self.client.get_metadata_by_tag(urn, &tag).await,
+            None => self.client.get_latest_metadata(urn).await,
+        }
+    }
+
+    async fn download_file(
+        &self,
+        urn: &ComponentUrn,
+        version: u64,
+        key: &str,
+    ) -> Result<Option<Vec<u8>>, GolemError> {
+        self.client.download_file(urn, version, key).await
+    }
+
+    async fn list_latest(
+        &self,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<Vec<Component>, GolemError> {
+        let components = self.client.find(None, &project).await?;
+        Ok(components
+            .into_iter()
+            .into_group_map_by(|c| c.versioned_component_id.component_id)
+            .into_values()
+            .filter_map(|group| {
+                group
+                    .into_iter()
+                    .max_by_key(|c| c.versioned_component_id.version)
+            })
+            .sorted_by(|a, b| a.component_name.cmp(&b.component_name))
+            .collect())
+    }
+
+    async fn fetch_file(&self, file: &DownloadableFile) -> Result<Vec<u8>, GolemError> {
+        let url = file.as_url();
+        match url.scheme() {
+            "file" | "" => tokio::fs::read(url.path())
+                .await
+                .map_err(|err| GolemError(format!("Error reading file {}: {err}", url.path()))),
+            "http" | "https" => self.file_download_client.download_file(url.clone()).await,
+            scheme => Err(GolemError(format!("Unsupported scheme: {}", scheme))),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -15,7 +15,8 @@
 use crate::clients::worker::WorkerClient;
 use crate::command::worker::WorkerConnectOptions;
 use crate::model::component::{
-    format_function_name, function_params_types, show_exported_function, Component,
+    format_function_name, function_params_types, show_exported_function, CompileQuorum, Component,
+    ComponentCompileStatusView,
 };
 use crate::model::deploy::TryUpdateAllWorkersResult;
 use crate::model::invoke_result_view::InvokeResultView;
@@ -38,8 +39,10 @@ use golem_wasm_rpc::type_annotated_value_from_str;
 use itertools::Itertools;
 use serde_json::Value;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::task::JoinHandle;
-use tracing::{error, info};
+use tokio::time::{sleep, timeout_at, Instant};
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
 #[async_trait]
@@ -204,6 +207,37 @@ pub trait WorkerService {
         query: String,
         project: Option<Self::ProjectContext>,
     ) -> Result<GolemResult, GolemError>;
+
+    /// Gets the result of the last compilation of a component version on each worker executor.
+    /// Defaults to the latest version of the component.
+    async fn compile_status(
+        &self,
+        component_uri: ComponentUri,
+        version: Option<u64>,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<GolemResult, GolemError>;
+
+    /// Makes every worker executor load and compile a version of a component, and reports the results.
+    /// Defaults to the latest version of the component.
+    async fn warm(
+        &self,
+        component_uri: ComponentUri,
+        version: Option<u64>,
+        force_recompile: bool,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<GolemResult, GolemError>;
+
+    /// Makes every worker executor compile a version of a component, then waits until at least
+    /// `quorum` of them, by default a majority, report it compiled.
+    /// Fails once `timeout` passed, or as soon as too many executors failed to compile it.
+    async fn wait_compiled(
+        &self,
+        component_uri: ComponentUri,
+        version: Option<u64>,
+        quorum: Option<usize>,
+        timeout: Duration,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<GolemResult, GolemError>;
 }
 
 pub struct WorkerServiceLive<ProjectContext: Send + Sync> {
@@ -255,6 +289,26 @@ async fn resolve_worker_component_version<ProjectContext: Send + Sync>(
     }
 }
 
+async fn resolve_component_version<ProjectContext: Send + Sync>(
+    components: &(dyn ComponentService<ProjectContext = ProjectContext> + Send + Sync),
+    component_uri: ComponentUri,
+    version: Option<u64>,
+    project: &Option<ProjectContext>,
+) -> Result<(ComponentUrn, u64), GolemError> {
+    let component_urn = components.resolve_uri(component_uri, project).await?;
+    let component_version = match version {
+        Some(version) => version,
+        None => {
+            components
+                .get_latest_metadata(&component_urn)
+                .await?
+                .versioned_component_id
+                .version
+        }
+    };
+    Ok((component_urn, component_version))
+}
+
 fn parse_parameter(wave: &str, typ: &AnalysedType) -> Result<TypeAnnotatedValue, GolemError> {
     // Avoid converting from typ to AnalysedType
     match type_annotated_value_from_str(typ, wave) {
@@ -859,4 +913,131 @@ impl<ProjectContext: Send + Sync + 'static> WorkerService for WorkerServiceLive<
         let entries = self.client.search_oplog(worker_urn, query).await?;
         Ok(GolemResult::Ok(Box::new(entries)))
     }
+
+    async fn compile_status(
+        &self,
+        component_uri: ComponentUri,
+        version: Option<u64>,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<GolemResult, GolemError> {
+        let (component_urn, component_version) =
+            resolve_component_version(self.components.as_ref(), component_uri, version, &project)
+                .await?;
+
+        let executors = self
+            .client
+            .get_compile_status(component_urn.clone(), component_version)
+            .await?;
+
+        Ok(GolemResult::Ok(Box::new(ComponentCompileStatusView {
+            component_urn,
+            component_version,
+            executors,
+        })))
+    }
+
+    async fn warm(
+        &self,
+        component_uri: ComponentUri,
+        version: Option<u64>,
+        force_recompile: bool,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<GolemResult, GolemError> {
+        let (component_urn, component_version) =
+            resolve_component_version(self.components.as_ref(), component_uri, version, &project)
+                .await?;
+
+        let executors = self
+            .client
+            .warm_component(component_urn.clone(), component_version, force_recompile)
+            .await?;
+
+        Ok(GolemResult::Ok(Box::new(ComponentCompileStatusView {
+            component_urn,
+            component_version,
+            executors,
+        })))
+    }
+
+    async fn wait_compiled(
+        &self,
+        component_uri: ComponentUri,
+        version: Option<u64>,
+        quorum: Option<usize>,
+        timeout: Duration,
+        project: Option<Self::ProjectContext>,
+    ) -> Result<GolemResult, GolemError> {
+        let (component_urn, component_version) =
+            resolve_component_version(self.components.as_ref(), component_uri, version, &project)
+                .await?;
+        let deadline = Instant::now() + timeout;
+
+        // Warming compiles the version on the executors before responding, but the executors
+        // which could not be reached are polled the same way as the ones still compiling
+        let warmed = timeout_at(
+            deadline,
+            self.client
+                .warm_component(component_urn.clone(), component_version, false),
+        )
+        .await;
+        let mut executors = match warmed {
+            Ok(Ok(executors)) => executors,
+            Ok(Err(err)) => {
+                warn!("Failed to warm {component_urn} version {component_version}: {err}");
+                Vec::new()
+            }
+            Err(_) => Vec::new(),
+        };
+
+        loop {
+            let status = ComponentCompileStatusView {
+                component_urn: component_urn.clone(),
+                component_version,
+                executors,
+            };
+            let required = status.required_quorum(quorum);
+            let report = || {
+                format!(
+                    "{} of {} worker executors compiled {component_urn} version {component_version}, {} failed, {required} required",
+                    status.compiled(),
+                    status.executors.len(),
+                    status.failed()
+                )
+            };
+
+            match status.compile_quorum(quorum) {
+                CompileQuorum::Reached => return Ok(GolemResult::Ok(Box::new(status))),
+                CompileQuorum::Unreachable if !status.executors.is_empty() => {
+                    return Err(GolemError(report()))
+                }
+                _ if Instant::now() >= deadline => {
+                    return Err(GolemError(format!(
+                        "Timed out after {}s: {}",
+                        timeout.as_secs(),
+                        report()
+                    )))
+                }
+                _ => info!("Waiting for the compilation: {}", report()),
+            }
+
+            sleep(Duration::from_secs(1).min(deadline.saturating_duration_since(Instant::now())))
+                .await;
+            // A failed request counts as no executor having compiled it yet, and is retried
+            // until the deadline
+            let polled = timeout_at(
+                deadline,
+                self.client
+                    .get_compile_status(component_urn.clone(), component_version),
+            )
+            .await;
+            executors = match polled {
+                Ok(Ok(executors)) => executors,
+                Ok(Err(err)) => {
+                    warn!("Failed to get the compile status of {component_urn} version {component_version}: {err}");
+                    Vec::new()
+                }
+                Err(_) => Vec::new(),
+            };
+        }
+    }
 }
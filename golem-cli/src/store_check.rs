@@ -0,0 +1,465 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use golem_common::model::component_metadata::LocalComponentProps;
+use golem_common::model::ComponentType;
+use uuid::Uuid;
+
+use crate::model::{GolemError, GolemResult};
+
+const WASM_SUFFIX: &str = ".wasm";
+const PATCH_SUFFIX: &str = ".patch";
+const PROPS_SUFFIX: &str = ".json";
+
+/// A WASM (or patch) file of the store, named `<component id>-<version>.wasm`
+#[derive(Debug, Clone, PartialEq)]
+struct StoredVersion {
+    component_id: Uuid,
+    version: u64,
+    path: PathBuf,
+    props_path: PathBuf,
+}
+
+impl StoredVersion {
+    fn is_patch(&self) -> bool {
+        self.path.to_string_lossy().ends_with(PATCH_SUFFIX)
+    }
+}
+
+/// Problems found with one component version of the store, and the ones fixed
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionCheck {
+    pub component_id: Uuid,
+    pub version: u64,
+    pub errors: Vec<String>,
+    pub fixes: Vec<String>,
+}
+
+impl VersionCheck {
+    fn report_lines(&self) -> Vec<String> {
+        let name = format!("{} version {}", self.component_id, self.version);
+        let mut lines: Vec<String> = self
+            .fixes
+            .iter()
+            .map(|fix| format!("FIXED {name}: {fix}"))
+            .collect();
+        if self.errors.is_empty() {
+            lines.push(format!("OK {name}"));
+        } else {
+            lines.extend(
+                self.errors
+                    .iter()
+                    .map(|error| format!("FAIL {name}: {error}")),
+            );
+        }
+        lines
+    }
+}
+
+/// The result of checking a component store
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct StoreCheck {
+    pub versions: Vec<VersionCheck>,
+    /// Problems not belonging to a component version, such as orphaned properties files
+    pub errors: Vec<String>,
+}
+
+/// Checks that the store of the worker executor's local file system component source is
+/// consistent: every WASM or patch file has a properties file which parses and matches it, no
+/// two files resolve to the same version, every properties file belongs to a stored version, and
+/// the initial files of the components exist in the blob storage (if its root is given).
+///
+/// With `fix`, missing properties files of full WASMs are regenerated by analysing them, like the
+/// worker executor does when recovering them. The command fails if any problem remains.
+pub async fn process_store_check(
+    root: PathBuf,
+    props_root: Option<PathBuf>,
+    blob_root: Option<PathBuf>,
+    fix: bool,
+) -> Result<GolemResult, GolemError> {
+    let props_root = props_root.unwrap_or_else(|| root.clone());
+    let check = check_store(&root, &props_root, blob_root.as_deref(), fix).await?;
+    check_report(&check)
+}
+
+async fn check_store(
+    root: &Path,
+    props_root: &Path,
+    blob_root: Option<&Path>,
+    fix: bool,
+) -> Result<StoreCheck, GolemError> {
+    let mut check = StoreCheck::default();
+
+    let mut stored: BTreeMap<(Uuid, u64), Vec<StoredVersion>> = BTreeMap::new();
+    for file_name in list_file_names(root).await? {
+        let base_name = file_name
+            .strip_suffix(WASM_SUFFIX)
+            .or_else(|| file_name.strip_suffix(PATCH_SUFFIX));
+        let Some(base_name) = base_name else {
+            continue;
+        };
+        match parse_base_name(base_name) {
+            Some((component_id, version)) => stored
+                .entry((component_id, version))
+                .or_default()
+                .push(StoredVersion {
+                    component_id,
+                    version,
+                    path: root.join(&file_name),
+                    props_path: props_root.join(format!("{base_name}{PROPS_SUFFIX}")),
+                }),
+            None => check.errors.push(format!(
+                "{} is not named <component id>-<version>",
+                root.join(&file_name).display()
+            )),
+        }
+    }
+
+    let props_paths: BTreeSet<PathBuf> = stored
+        .values()
+        .flatten()
+        .map(|stored| stored.props_path.clone())
+        .collect();
+    for file_name in list_file_names(props_root).await? {
+        let path = props_root.join(&file_name);
+        let is_props = file_name
+            .strip_suffix(PROPS_SUFFIX)
+            .and_then(parse_base_name)
+            .is_some();
+        if is_props && !props_paths.contains(&path) {
+            check.errors.push(format!(
+                "orphaned properties file {}, there is no WASM for it",
+                path.display()
+            ));
+        }
+    }
+
+    for ((component_id, version), files) in &stored {
+        let mut version_check = VersionCheck {
+            component_id: *component_id,
+            version: *version,
+            errors: Vec::new(),
+            fixes: Vec::new(),
+        };
+
+        if files.len() > 1 {
+            version_check.errors.push(format!(
+                "multiple files resolve to the version: {}",
+                files
+                    .iter()
+                    .map(|stored| stored.path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        for stored_version in files {
+            check_version(stored_version, &stored, blob_root, fix, &mut version_check).await;
+        }
+
+        check.versions.push(version_check);
+    }
+
+    Ok(check)
+}
+
+async fn check_version(
+    stored_version: &StoredVersion,
+    stored: &BTreeMap<(Uuid, u64), Vec<StoredVersion>>,
+    blob_root: Option<&Path>,
+    fix: bool,
+    version_check: &mut VersionCheck,
+) {
+    let props_path = &stored_version.props_path;
+
+    let props = match tokio::fs::read_to_string(props_path).await {
+        Ok(data) => match serde_json::from_str::<LocalComponentProps>(&data) {
+            Ok(props) => props,
+            Err(err) => {
+                version_check.errors.push(format!(
+                    "invalid properties file {}: {err}",
+                    props_path.display()
+                ));
+                return;
+            }
+        },
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            if fix && !stored_version.is_patch() {
+                match regenerate_props(stored_version).await {
+                    Ok(()) => version_check.fixes.push(format!(
+                        "regenerated the missing properties file {}",
+                        props_path.display()
+                    )),
+                    Err(err) => version_check.errors.push(format!(
+                        "properties file {} not found, and it could not be regenerated: {err}",
+                        props_path.display()
+                    )),
+                }
+            } else {
+                version_check.errors.push(format!(
+                    "properties file {} not found",
+                    props_path.display()
+                ));
+            }
+            return;
+        }
+        Err(err) => {
+            version_check.errors.push(format!(
+                "failed to read properties file {}: {err}",
+                props_path.display()
+            ));
+            return;
+        }
+    };
+
+    if props.version != stored_version.version {
+        version_check.errors.push(format!(
+            "properties file {} is of version {}",
+            props_path.display(),
+            props.version
+        ));
+    }
+
+    if stored_version.is_patch() {
+        match props.base_version {
+            None => version_check
+                .errors
+                .push("patch without a base version in its properties".to_string()),
+            Some(base_version)
+                if !stored.contains_key(&(stored_version.component_id, base_version)) =>
+            {
+                version_check.errors.push(format!(
+                    "base version {base_version} of the patch not found"
+                ))
+            }
+            Some(_) => {}
+        }
+    } else if let Ok(metadata) = tokio::fs::metadata(&stored_version.path).await {
+        if metadata.len() != props.size {
+            version_check.errors.push(format!(
+                "WASM is {} bytes, but its properties give {}",
+                metadata.len(),
+                props.size
+            ));
+        }
+    }
+
+    if let Some(blob_root) = blob_root {
+        for file in &props.files {
+            if !initial_file_exists(blob_root, &file.key.0).await {
+                version_check.errors.push(format!(
+                    "initial file {} not found in the blob storage (key {})",
+                    file.path, file.key.0
+                ));
+            }
+        }
+    }
+}
+
+/// Writes the properties file of a full WASM by analysing it
+async fn regenerate_props(stored_version: &StoredVersion) -> Result<(), String> {
+    let bytes = tokio::fs::read(&stored_version.path)
+        .await
+        .map_err(|err| err.to_string())?;
+    let props =
+        LocalComponentProps::from_wasm(stored_version.version, ComponentType::Durable, &bytes)?;
+    props
+        .write(&stored_version.props_path)
+        .await
+        .map_err(|err| err.to_string())
+}
+
+/// Whether an initial file exists in the file system blob storage rooted at `blob_root`, for
+/// any account
+async fn initial_file_exists(blob_root: &Path, key: &str) -> bool {
+    let Ok(mut accounts) = tokio::fs::read_dir(blob_root.join("initial_component_files")).await
+    else {
+        return false;
+    };
+    while let Ok(Some(account)) = accounts.next_entry().await {
+        if tokio::fs::try_exists(account.path().join(key))
+            .await
+            .unwrap_or(false)
+        {
+            return true;
+        }
+    }
+    false
+}
+
+/// Parses a `<component id>-<version>` file name without its extension
+fn parse_base_name(base_name: &str) -> Option<(Uuid, u64)> {
+    let (component_id, version) = base_name.rsplit_once('-')?;
+    Some((
+        Uuid::parse_str(component_id).ok()?,
+        version.parse::<u64>().ok()?,
+    ))
+}
+
+async fn list_file_names(dir: &Path) -> Result<Vec<String>, GolemError> {
+    let read_error =
+        |err: std::io::Error| GolemError(format!("Failed to read {}: {err}", dir.display()));
+
+    let mut reader = tokio::fs::read_dir(dir).await.map_err(read_error)?;
+    let mut file_names = Vec::new();
+    while let Some(entry) = reader.next_entry().await.map_err(read_error)? {
+        if let Ok(file_name) = entry.file_name().into_string() {
+            file_names.push(file_name);
+        }
+    }
+    file_names.sort();
+    Ok(file_names)
+}
+
+fn check_report(check: &StoreCheck) -> Result<GolemResult, GolemError> {
+    let mut lines: Vec<String> = check
+        .errors
+        .iter()
+        .map(|error| format!("error: {error}"))
+        .collect();
+    lines.extend(check.versions.iter().flat_map(|v| v.report_lines()));
+
+    let failed = check
+        .versions
+        .iter()
+        .filter(|v| !v.errors.is_empty())
+        .count();
+    lines.push(format!(
+        "{} of {} component versions valid",
+        check.versions.len() - failed,
+        check.versions.len()
+    ));
+    let report = lines.join("\n");
+
+    if check.errors.is_empty() && failed == 0 {
+        Ok(GolemResult::Str(report))
+    } else {
+        Err(GolemError(report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::path::Path;
+
+    use golem_common::model::component_metadata::EMPTY_COMPONENT;
+    use uuid::Uuid;
+
+    use crate::model::GolemResult;
+    use crate::store_check::{check_report, check_store};
+
+    fn props(version: u64, size: usize) -> String {
+        format!(
+            r#"{{"version":{version},"size":{size},"memories":[],"exports":[],"componentType":"Durable","files":[]}}"#
+        )
+    }
+
+    fn store(dir: &Path, component_id: &Uuid, version: u64, with_props: bool) {
+        std::fs::write(
+            dir.join(format!("{component_id}-{version}.wasm")),
+            EMPTY_COMPONENT,
+        )
+        .unwrap();
+        if with_props {
+            std::fs::write(
+                dir.join(format!("{component_id}-{version}.json")),
+                props(version, EMPTY_COMPONENT.len()),
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    async fn consistent_store_passes() {
+        let dir = tempfile::tempdir().unwrap();
+        let component_id = Uuid::new_v4();
+        store(dir.path(), &component_id, 0, true);
+        store(dir.path(), &component_id, 1, true);
+
+        let check = check_store(dir.path(), dir.path(), None, false)
+            .await
+            .unwrap();
+
+        assert!(check.errors.is_empty());
+        match check_report(&check) {
+            Ok(GolemResult::Str(report)) => {
+                assert!(report.ends_with("2 of 2 component versions valid"))
+            }
+            _ => panic!("Expected a successful report"),
+        }
+    }
+
+    #[test]
+    async fn orphans_and_mismatches_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let component_id = Uuid::new_v4();
+        store(dir.path(), &component_id, 0, true);
+        std::fs::write(
+            dir.path().join(format!("{component_id}-1.wasm")),
+            EMPTY_COMPONENT,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join(format!("{component_id}-1.json")),
+            props(2, 1),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join(format!("{component_id}-5.json")),
+            props(5, 8),
+        )
+        .unwrap();
+
+        let check = check_store(dir.path(), dir.path(), None, false)
+            .await
+            .unwrap();
+        let report = check_report(&check).unwrap_err().0;
+
+        assert!(report.contains("orphaned properties file"));
+        assert!(report.contains(&format!("{component_id}-5.json")));
+        assert!(report.contains(&format!("OK {component_id} version 0")));
+        assert!(report.contains("is of version 2"));
+        assert!(report.contains("WASM is 8 bytes, but its properties give 1"));
+        assert!(report.ends_with("1 of 2 component versions valid"));
+    }
+
+    #[test]
+    async fn missing_props_are_regenerated_with_fix() {
+        let dir = tempfile::tempdir().unwrap();
+        let component_id = Uuid::new_v4();
+        store(dir.path(), &component_id, 0, false);
+
+        let check = check_store(dir.path(), dir.path(), None, false)
+            .await
+            .unwrap();
+        assert!(check_report(&check).unwrap_err().0.contains("not found"));
+
+        let check = check_store(dir.path(), dir.path(), None, true)
+            .await
+            .unwrap();
+        assert_eq!(check.versions[0].errors, Vec::<String>::new());
+        assert_eq!(check.versions[0].fixes.len(), 1);
+
+        // The regenerated properties file is consistent with the WASM
+        let check = check_store(dir.path(), dir.path(), None, false)
+            .await
+            .unwrap();
+        assert!(check_report(&check).is_ok());
+    }
+}
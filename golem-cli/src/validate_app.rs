@@ -0,0 +1,244 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::{Path, PathBuf};
+
+use golem_common::model::component_metadata::{
+    is_core_wasm_module, RawComponentMetadata, CORE_WASM_MODULE_ERROR,
+};
+use golem_wasm_rpc_stubgen::commands::declarative::ApplicationResolveMode;
+use golem_wasm_rpc_stubgen::model::validation::ValidatedResult;
+
+use crate::model::application_manifest::{load_app_validated, ApplicationManifest};
+use crate::model::{GolemError, GolemResult};
+
+/// Problems found with one component of the app manifest
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentValidation {
+    pub component_name: String,
+    pub output_wasm: PathBuf,
+    pub errors: Vec<String>,
+}
+
+impl ComponentValidation {
+    fn report_lines(&self) -> Vec<String> {
+        if self.errors.is_empty() {
+            vec![format!(
+                "OK {}: {}",
+                self.component_name,
+                self.output_wasm.display()
+            )]
+        } else {
+            self.errors
+                .iter()
+                .map(|error| format!("FAIL {}: {error}", self.component_name))
+                .collect()
+        }
+    }
+}
+
+/// Loads the app manifest and checks that the output WASM of every component exists and is a
+/// valid component, and that its local initial files exist, without building or deploying
+/// anything. All the problems are reported at once, failing if there are any.
+pub async fn process_validate_app(app: Vec<PathBuf>) -> Result<GolemResult, GolemError> {
+    let app_resolve_mode = if app.is_empty() {
+        ApplicationResolveMode::Automatic
+    } else {
+        ApplicationResolveMode::Explicit(app)
+    };
+
+    let (app, warns, errors) = match load_app_validated(&app_resolve_mode) {
+        ValidatedResult::Ok(app) => (Some(app), vec![], vec![]),
+        ValidatedResult::OkWithWarns(app, warns) => (Some(app), warns, vec![]),
+        ValidatedResult::WarnsAndErrors(warns, errors) => (None, warns, errors),
+    };
+
+    let validations = match &app {
+        Some(app) => validate_components(app).await,
+        None => vec![],
+    };
+
+    validation_report(&warns, &errors, &validations)
+}
+
+async fn validate_components(app: &ApplicationManifest) -> Vec<ComponentValidation> {
+    let mut validations = Vec::new();
+    for (name, component) in &app.wasm_components_by_name {
+        let output_wasm = app.component_output_wasm(name);
+        let mut errors = Vec::new();
+
+        if let Err(error) = validate_wasm(&output_wasm).await {
+            errors.push(error);
+        }
+        for file in &component.files {
+            let url = file.source_path.as_url();
+            if url.scheme() != "file" {
+                continue;
+            }
+            match url.to_file_path() {
+                Ok(path) if path.exists() => {}
+                _ => errors.push(format!(
+                    "initial file {} not found at {url}",
+                    file.target.path
+                )),
+            }
+        }
+
+        validations.push(ComponentValidation {
+            component_name: name.clone(),
+            output_wasm,
+            errors,
+        });
+    }
+    validations
+}
+
+async fn validate_wasm(path: &Path) -> Result<(), String> {
+    let bytes = tokio::fs::read(path).await.map_err(|err| {
+        if err.kind() == std::io::ErrorKind::NotFound {
+            format!("output WASM {} not found, is it built?", path.display())
+        } else {
+            format!("failed to read output WASM {}: {err}", path.display())
+        }
+    })?;
+
+    if is_core_wasm_module(&bytes) {
+        return Err(format!(
+            "output WASM {} is invalid: {CORE_WASM_MODULE_ERROR}",
+            path.display()
+        ));
+    }
+    RawComponentMetadata::analyse_component(&bytes)
+        .map(|_| ())
+        .map_err(|err| format!("output WASM {} is invalid: {err}", path.display()))
+}
+
+fn validation_report(
+    warns: &[String],
+    errors: &[String],
+    validations: &[ComponentValidation],
+) -> Result<GolemResult, GolemError> {
+    let mut lines = Vec::new();
+    lines.extend(warns.iter().map(|warn| format!("warning: {warn}")));
+    lines.extend(errors.iter().map(|error| format!("error: {error}")));
+    lines.extend(validations.iter().flat_map(|v| v.report_lines()));
+
+    let failed = validations.iter().filter(|v| !v.errors.is_empty()).count();
+    if errors.is_empty() {
+        lines.push(format!(
+            "{} of {} components valid",
+            validations.len() - failed,
+            validations.len()
+        ));
+    } else {
+        lines.push("The app manifest is invalid".to_string());
+    }
+    let report = lines.join("\n");
+
+    if errors.is_empty() && failed == 0 {
+        Ok(GolemResult::Str(report))
+    } else {
+        Err(GolemError(report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::path::PathBuf;
+
+    use golem_common::model::component_metadata::EMPTY_COMPONENT;
+
+    use crate::model::GolemResult;
+    use crate::validate_app::{validate_wasm, validation_report, ComponentValidation};
+
+    fn validation(name: &str, errors: Vec<&str>) -> ComponentValidation {
+        ComponentValidation {
+            component_name: name.to_string(),
+            output_wasm: PathBuf::from(format!("target/{name}.wasm")),
+            errors: errors.into_iter().map(|e| e.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    async fn missing_and_invalid_wasm_are_reported() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let missing = dir.path().join("missing.wasm");
+        assert!(validate_wasm(&missing)
+            .await
+            .unwrap_err()
+            .contains("not found"));
+
+        let invalid = dir.path().join("invalid.wasm");
+        std::fs::write(&invalid, b"not wasm").unwrap();
+        assert!(validate_wasm(&invalid)
+            .await
+            .unwrap_err()
+            .contains("is invalid"));
+
+        let valid = dir.path().join("valid.wasm");
+        std::fs::write(&valid, EMPTY_COMPONENT).unwrap();
+        assert_eq!(validate_wasm(&valid).await, Ok(()));
+    }
+
+    #[test]
+    fn report_lists_every_problem() {
+        let report = validation_report(
+            &["Source added multiple times".to_string()],
+            &[],
+            &[
+                validation("first", vec![]),
+                validation(
+                    "second",
+                    vec![
+                        "output WASM target/second.wasm not found, is it built?",
+                        "initial file /app.conf not found at file:///app.conf",
+                    ],
+                ),
+            ],
+        )
+        .unwrap_err();
+
+        assert!(report
+            .0
+            .contains("warning: Source added multiple times\nOK first: target/first.wasm"));
+        assert!(report.0.contains(
+            "FAIL second: output WASM target/second.wasm not found, is it built?\n\
+             FAIL second: initial file /app.conf not found at file:///app.conf"
+        ));
+        assert!(report.0.ends_with("1 of 2 components valid"));
+    }
+
+    #[test]
+    fn report_fails_on_manifest_errors() {
+        let report =
+            validation_report(&[], &["Missing field: outputWasm".to_string()], &[]).unwrap_err();
+        assert_eq!(
+            report.0,
+            "error: Missing field: outputWasm\nThe app manifest is invalid"
+        );
+
+        match validation_report(&[], &[], &[validation("first", vec![])]) {
+            Ok(GolemResult::Str(report)) => {
+                assert_eq!(
+                    report,
+                    "OK first: target/first.wasm\n1 of 1 components valid"
+                )
+            }
+            _ => panic!("Expected a successful report"),
+        }
+    }
+}
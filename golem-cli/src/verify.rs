@@ -0,0 +1,183 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use golem_common::model::ComponentId;
+use golem_common::uri::oss::uri::ComponentUri;
+use golem_common::uri::oss::urn::ComponentUrn;
+
+use crate::compile::{compile_component, EngineProfile};
+use crate::model::component::VersionSelector;
+use crate::model::{GolemError, GolemResult};
+use crate::service::component::ComponentService;
+
+/// Outcome of compiling the latest version of a deployed component
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentVerification {
+    pub component_name: String,
+    pub component_urn: ComponentUrn,
+    pub version: u64,
+    /// The compilation time, or why the component could not be downloaded or compiled
+    pub result: Result<Duration, String>,
+}
+
+impl ComponentVerification {
+    fn report_line(&self) -> String {
+        let component = format!(
+            "{} ({}) version {}",
+            self.component_name, self.component_urn, self.version
+        );
+        match &self.result {
+            Ok(time) => format!("PASS {component} in {}ms", time.as_millis()),
+            Err(error) => format!("FAIL {component}: {error}"),
+        }
+    }
+}
+
+/// Downloads the latest version of every component of a project and compiles it with the
+/// engine of `engine_profile`, failing if any of them does not compile
+pub async fn process_verify_all<ProjectContext: Clone + Send + Sync>(
+    service: &(dyn ComponentService<ProjectContext = ProjectContext> + Send + Sync),
+    project: Option<ProjectContext>,
+    engine_profile: EngineProfile,
+) -> Result<GolemResult, GolemError> {
+    let engine = engine_profile.engine()?;
+
+    let mut verifications = Vec::new();
+    for component in service.list_latest(project.clone()).await? {
+        let component_urn = ComponentUrn {
+            id: ComponentId(component.versioned_component_id.component_id),
+        };
+        let version = component.versioned_component_id.version;
+
+        let result = match service
+            .download(
+                ComponentUri::URN(component_urn.clone()),
+                Some(VersionSelector::Version(version)),
+                project.clone(),
+            )
+            .await
+        {
+            Ok(bytes) => compile_component(&engine, bytes)
+                .await
+                .map(|(_, time)| time)
+                .map_err(|err| err.0),
+            Err(err) => Err(format!("Failed to download component: {err}")),
+        };
+
+        verifications.push(ComponentVerification {
+            component_name: component.component_name,
+            component_urn,
+            version,
+            result,
+        });
+    }
+
+    verification_report(&verifications)
+}
+
+fn verification_report(verifications: &[ComponentVerification]) -> Result<GolemResult, GolemError> {
+    let failed = verifications
+        .iter()
+        .filter(|verification| verification.result.is_err())
+        .count();
+
+    let mut lines: Vec<String> = verifications
+        .iter()
+        .map(ComponentVerification::report_line)
+        .collect();
+    lines.push(format!(
+        "{} of {} components compiled",
+        verifications.len() - failed,
+        verifications.len()
+    ));
+    let report = lines.join("\n");
+
+    if failed > 0 {
+        Err(GolemError(report))
+    } else {
+        Ok(GolemResult::Str(report))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::time::Duration;
+
+    use golem_common::model::component_metadata::EMPTY_COMPONENT;
+    use golem_common::model::ComponentId;
+    use golem_common::uri::oss::urn::ComponentUrn;
+    use uuid::Uuid;
+
+    use crate::compile::{compile_component, EngineProfile};
+    use crate::model::GolemResult;
+    use crate::verify::{verification_report, ComponentVerification};
+
+    fn verification(name: &str, result: Result<Duration, String>) -> ComponentVerification {
+        ComponentVerification {
+            component_name: name.to_string(),
+            component_urn: ComponentUrn {
+                id: ComponentId(Uuid::new_v4()),
+            },
+            version: 2,
+            result,
+        }
+    }
+
+    #[test]
+    fn report_passes_when_all_components_compile() {
+        let report = verification_report(&[
+            verification("first", Ok(Duration::from_millis(12))),
+            verification("second", Ok(Duration::from_millis(34))),
+        ]);
+
+        match report {
+            Ok(GolemResult::Str(report)) => {
+                assert!(report.contains("PASS first"));
+                assert!(report.contains("in 34ms"));
+                assert!(report.ends_with("2 of 2 components compiled"));
+            }
+            _ => panic!("Expected a successful report"),
+        }
+    }
+
+    #[test]
+    fn report_fails_when_any_component_fails() {
+        let report = verification_report(&[
+            verification("first", Ok(Duration::from_millis(12))),
+            verification("second", Err("Invalid WASM".to_string())),
+        ])
+        .unwrap_err();
+
+        assert!(report.0.contains("PASS first"));
+        assert!(report.0.contains("FAIL second"));
+        assert!(report.0.contains("Invalid WASM"));
+        assert!(report.0.ends_with("1 of 2 components compiled"));
+    }
+
+    #[test]
+    async fn compiles_with_the_worker_executor_engine() {
+        let engine = EngineProfile::WorkerExecutor.engine().unwrap();
+
+        assert!(compile_component(&engine, EMPTY_COMPONENT.to_vec())
+            .await
+            .is_ok());
+        assert!(compile_component(&engine, b"not wasm".to_vec())
+            .await
+            .is_err());
+    }
+}
@@ -15,7 +15,9 @@
 use crate::cli::{Cli, CliLive};
 use crate::Tracing;
 use assert2::assert;
+use golem_cli::inspect::WasmSection;
 use golem_cli::model::component::ComponentView;
+use golem_cli::model::Format;
 use golem_common::uri::oss::url::ComponentUrl;
 use golem_test_framework::config::{EnvBasedTestDependencies, TestDependencies};
 use itertools::Itertools;
@@ -78,6 +80,22 @@ fn make(r: &mut DynamicTestRegistration, suffix: &'static str, name: &'static st
             component_add_and_get_url((deps, name.to_string(), cli.with_args(short)))
         }
     );
+    add_test!(
+        r,
+        format!("component_tag_and_get{suffix}"),
+        TestType::IntegrationTest,
+        move |deps: &EnvBasedTestDependencies, cli: &CliLive, _tracing: &Tracing| {
+            component_tag_and_get((deps, name.to_string(), cli.with_args(short)))
+        }
+    );
+    add_test!(
+        r,
+        format!("component_print{suffix}"),
+        TestType::IntegrationTest,
+        move |deps: &EnvBasedTestDependencies, cli: &CliLive, _tracing: &Tracing| {
+            component_print((deps, name.to_string(), cli.with_args(short)))
+        }
+    );
     add_test!(
         r,
         format!("component_add_from_project_file{suffix}"),
@@ -110,6 +128,22 @@ fn make(r: &mut DynamicTestRegistration, suffix: &'static str, name: &'static st
             component_update_url((deps, name.to_string(), cli.with_args(short)))
         }
     );
+    add_test!(
+        r,
+        format!("component_rename{suffix}"),
+        TestType::IntegrationTest,
+        move |deps: &EnvBasedTestDependencies, cli: &CliLive, _tracing: &Tracing| {
+            component_rename((deps, name.to_string(), cli.with_args(short)))
+        }
+    );
+    add_test!(
+        r,
+        format!("component_update_expected_version{suffix}"),
+        TestType::IntegrationTest,
+        move |deps: &EnvBasedTestDependencies, cli: &CliLive, _tracing: &Tracing| {
+            component_update_expected_version((deps, name.to_string(), cli.with_args(short)))
+        }
+    );
     add_test!(
         r,
         format!("component_update_from_project_file{suffix}"),
@@ -358,6 +392,182 @@ fn component_add_and_get_urn(
     Ok(())
 }
 
+fn component_tag_and_get(
+    (deps, name, cli): (&EnvBasedTestDependencies, String, CliLive),
+) -> Result<(), anyhow::Error> {
+    let component_name = format!("{name} component tag and get");
+    let env_service = deps.component_directory().join("environment-service.wasm");
+    let cfg = &cli.config;
+    let component: ComponentView = cli.run_trimmed(&[
+        "component",
+        "add",
+        &cfg.arg('c', "component-name"),
+        &component_name,
+        env_service.to_str().unwrap(),
+    ])?;
+    let tagged: ComponentView = cli.run_trimmed(&[
+        "component",
+        "tag",
+        &cfg.arg('c', "component-name"),
+        &component_name,
+        &cfg.arg('t', "version"),
+        &component.component_version.to_string(),
+        "--tag",
+        "stable",
+    ])?;
+    assert_eq!(tagged, component, "{tagged:?} = ({component:?})");
+
+    let _: ComponentView = cli.run_trimmed(&[
+        "component",
+        "update",
+        &cfg.arg('c', "component-name"),
+        &component_name,
+        env_service.to_str().unwrap(),
+    ])?;
+
+    let res: ComponentView = cli.run_trimmed(&[
+        "component",
+        "get",
+        &cfg.arg('c', "component-name"),
+        &component_name,
+        &cfg.arg('t', "version"),
+        "stable",
+    ])?;
+    assert_eq!(res, component, "{res:?} = ({component:?})");
+    Ok(())
+}
+
+fn component_update_expected_version(
+    (deps, name, cli): (&EnvBasedTestDependencies, String, CliLive),
+) -> Result<(), anyhow::Error> {
+    let component_name = format!("{name} component update expected version");
+    let env_service = deps.component_directory().join("environment-service.wasm");
+    let cfg = &cli.config;
+    let _: ComponentView = cli.run_trimmed(&[
+        "component",
+        "add",
+        &cfg.arg('c', "component-name"),
+        &component_name,
+        env_service.to_str().unwrap(),
+    ])?;
+
+    let component: ComponentView = cli.run_trimmed(&[
+        "component",
+        "update",
+        &cfg.arg('c', "component-name"),
+        &component_name,
+        "--expected-current-version",
+        "0",
+        env_service.to_str().unwrap(),
+    ])?;
+    assert_eq!(component.component_version, 1);
+
+    let result: Result<ComponentView, _> = cli.run_trimmed(&[
+        "component",
+        "update",
+        &cfg.arg('c', "component-name"),
+        &component_name,
+        "--expected-current-version",
+        "0",
+        env_service.to_str().unwrap(),
+    ]);
+    assert!(result.is_err());
+    Ok(())
+}
+
+fn component_rename(
+    (deps, name, cli): (&EnvBasedTestDependencies, String, CliLive),
+) -> Result<(), anyhow::Error> {
+    let component_name = format!("{name} component rename");
+    let new_name = format!("{name} component renamed");
+    let taken_name = format!("{name} component rename taken");
+    let env_service = deps.component_directory().join("environment-service.wasm");
+    let cfg = &cli.config;
+    let component: ComponentView = cli.run_trimmed(&[
+        "component",
+        "add",
+        &cfg.arg('c', "component-name"),
+        &component_name,
+        env_service.to_str().unwrap(),
+    ])?;
+    let _: ComponentView = cli.run_trimmed(&[
+        "component",
+        "add",
+        &cfg.arg('c', "component-name"),
+        &taken_name,
+        env_service.to_str().unwrap(),
+    ])?;
+
+    let result: Result<ComponentView, _> = cli.run_trimmed(&[
+        "component",
+        "rename",
+        &cfg.arg('c', "component-name"),
+        &component_name,
+        "--new-name",
+        &taken_name,
+        &cfg.arg('y', "non-interactive"),
+    ]);
+    assert!(result.is_err());
+
+    let renamed: ComponentView = cli.run_trimmed(&[
+        "component",
+        "rename",
+        &cfg.arg('c', "component-name"),
+        &component_name,
+        "--new-name",
+        &new_name,
+        &cfg.arg('y', "non-interactive"),
+    ])?;
+    assert_eq!(renamed.component_urn, component.component_urn);
+    assert_eq!(renamed.component_name, new_name);
+
+    let res: ComponentView = cli.run_trimmed(&[
+        "component",
+        "get",
+        &cfg.arg('c', "component-name"),
+        &new_name,
+    ])?;
+    assert_eq!(res, renamed, "{res:?} = ({renamed:?})");
+    Ok(())
+}
+
+fn component_print(
+    (deps, name, cli): (&EnvBasedTestDependencies, String, CliLive),
+) -> Result<(), anyhow::Error> {
+    let component_name = format!("{name} component print");
+    let env_service = deps.component_directory().join("environment-service.wasm");
+    let cfg = &cli.config;
+    let _: ComponentView = cli.run_trimmed(&[
+        "component",
+        "add",
+        &cfg.arg('c', "component-name"),
+        &component_name,
+        env_service.to_str().unwrap(),
+    ])?;
+
+    let sections: Vec<WasmSection> = cli.run(&[
+        "component",
+        "print",
+        &cfg.arg('c', "component-name"),
+        &component_name,
+    ])?;
+    assert!(sections
+        .iter()
+        .any(|section| section.depth == 0 && section.name == "component-export"));
+    assert!(sections
+        .iter()
+        .any(|section| section.depth > 0 && section.name == "code"));
+
+    let wat = cli.with_format(Format::Text).run_string(&[
+        "component",
+        "print",
+        &cfg.arg('c', "component-name"),
+        &component_name,
+    ])?;
+    assert!(wat.starts_with("(component"));
+    Ok(())
+}
+
 fn component_add_and_get_url(
     (deps, name, cli): (&EnvBasedTestDependencies, String, CliLive),
 ) -> Result<(), anyhow::Error> {
@@ -20,6 +20,7 @@ use assert2::assert;
 use golem_cli::command::profile::{ProfileType, ProfileView};
 use golem_cli::config::{ProfileConfig, ProfileName};
 use golem_cli::model::Format;
+use golem_common::model::ComponentType;
 use golem_test_framework::config::EnvBasedTestDependencies;
 use std::fmt::{Display, Formatter};
 use std::sync::Arc;
@@ -109,6 +110,7 @@ fn profile_add_get_list_switch_delete(
         authenticated: None,
         config: ProfileConfig {
             default_format: Format::Yaml,
+            default_component_type: None,
         },
     };
 
@@ -241,6 +243,31 @@ fn profile_config(
 
     let expected = ProfileConfig {
         default_format: Format::Json,
+        default_component_type: None,
+    };
+
+    assert_eq!(config, expected);
+
+    cli.run_unit(&[
+        "profile",
+        "config",
+        &cfg.arg('p', "profile"),
+        "p_config",
+        "component-type",
+        "Ephemeral",
+    ])?;
+
+    let config: ProfileConfig = cli.run(&[
+        "profile",
+        "config",
+        &cfg.arg('p', "profile"),
+        "p_config",
+        "show",
+    ])?;
+
+    let expected = ProfileConfig {
+        default_format: Format::Json,
+        default_component_type: Some(ComponentType::Ephemeral),
     };
 
     assert_eq!(config, expected);
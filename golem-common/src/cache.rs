@@ -168,6 +168,12 @@ impl<
         }
     }
 
+    /// Checks whether a value is cached or pending for the given key, without counting it as an
+    /// access
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.state.items.contains_key(key)
+    }
+
     /// Gets a cached value for the given key. If the value is pending, it awaits it.
     /// If the pending value fails, it returns None.
     #[allow(unused)]
@@ -0,0 +1,31 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use wasmtime::{Config, WasmBacktraceDetails};
+
+/// The wasmtime configuration of the worker executor. A precompiled component can only be loaded
+/// by an engine with the same configuration, so everything compiling components for the
+/// executor (the compilation service, the CLI) must use this one too.
+pub fn create_wasmtime_config() -> Config {
+    let mut config = Config::default();
+
+    config.wasm_multi_value(true);
+    config.async_support(true);
+    config.wasm_component_model(true);
+    config.epoch_interruption(true);
+    config.consume_fuel(true);
+    config.wasm_backtrace_details(WasmBacktraceDetails::Enable);
+
+    config
+}
@@ -18,6 +18,8 @@ use std::fmt::{Display, Formatter};
 pub mod cache;
 pub mod client;
 pub mod config;
+#[cfg(feature = "wasmtime")]
+pub mod engine;
 
 pub mod golem_version;
 pub mod grpc;
@@ -104,6 +104,50 @@ pub mod external_calls {
     }
 }
 
+pub mod compilation {
+    use std::time::Duration;
+
+    use lazy_static::lazy_static;
+    use prometheus::*;
+
+    use crate::metrics::DEFAULT_TIME_BUCKETS;
+
+    lazy_static! {
+        static ref COMPILATION_TIME_SECONDS: Histogram = register_histogram!(
+            "compilation_time_seconds",
+            "Time to compile a WASM component to native code",
+            DEFAULT_TIME_BUCKETS.to_vec()
+        )
+        .unwrap();
+        static ref COMPILATION_TIME_SECONDS_BY_SIZE: HistogramVec = register_histogram_vec!(
+            "compilation_time_seconds_by_size",
+            "Time to compile a WASM component to native code, by the size range of the component (lt_1mb, 1mb_10mb, 10mb_100mb or gte_100mb)",
+            &["size"],
+            DEFAULT_TIME_BUCKETS.to_vec()
+        )
+        .unwrap();
+    }
+
+    /// Records the compilation time of a component of `size` bytes, both in total and in the
+    /// histogram of its size range
+    pub fn record_compilation_time(duration: Duration, size: u64) {
+        COMPILATION_TIME_SECONDS.observe(duration.as_secs_f64());
+        COMPILATION_TIME_SECONDS_BY_SIZE
+            .with_label_values(&[component_size_range(size)])
+            .observe(duration.as_secs_f64());
+    }
+
+    fn component_size_range(size: u64) -> &'static str {
+        const MB: u64 = 1024 * 1024;
+        match size {
+            size if size < MB => "lt_1mb",
+            size if size < 10 * MB => "1mb_10mb",
+            size if size < 100 * MB => "10mb_100mb",
+            _ => "gte_100mb",
+        }
+    }
+}
+
 pub mod redis {
     use std::time::Duration;
 
@@ -13,14 +13,20 @@
 // limitations under the License.
 
 use bincode::{Decode, Encode};
+use std::collections::{BTreeMap, HashSet};
 use std::fmt::{self, Display, Formatter};
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::path::Path;
 
+use crate::model::{ComponentType, InitialComponentFile};
 use crate::SafeDisplay;
 use golem_wasm_ast::analysis::AnalysedFunctionParameter;
 use golem_wasm_ast::core::Mem;
 use golem_wasm_ast::metadata::Producers as WasmAstProducers;
 use golem_wasm_ast::{
-    analysis::{AnalysedExport, AnalysedFunction, AnalysisContext, AnalysisFailure},
+    analysis::{
+        AnalysedExport, AnalysedFunction, AnalysedInstance, AnalysisContext, AnalysisFailure,
+    },
     component::Component,
     IgnoreAllButMetadata,
 };
@@ -32,6 +38,20 @@ pub struct ComponentMetadata {
     pub exports: Vec<AnalysedExport>,
     pub producers: Vec<Producers>,
     pub memories: Vec<LinearMemory>,
+    /// The exports grouped by the interfaces of the component's world, derived from `exports`.
+    /// Missing when the metadata comes from a server that does not provide it yet.
+    #[serde(default)]
+    pub world: Option<AnalysedWorld>,
+    /// Oddities found while analysing the component which did not make the analysis fail,
+    /// but may cause surprises at runtime
+    #[serde(default)]
+    #[oai(default)]
+    pub analysis_warnings: Vec<String>,
+    /// Arbitrary key/value annotations of the component, such as its owner team or cost center.
+    /// Not interpreted by Golem, only passed through to the tools reading the metadata
+    #[serde(default)]
+    #[oai(default)]
+    pub annotations: BTreeMap<String, String>,
 }
 
 impl ComponentMetadata {
@@ -41,6 +61,95 @@ impl ComponentMetadata {
     }
 }
 
+/// The exported world of a component, with the exported interfaces and the functions exported
+/// directly by the world separated
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object, Encode, Decode)]
+pub struct AnalysedWorld {
+    pub interfaces: Vec<AnalysedInstance>,
+    pub functions: Vec<AnalysedFunction>,
+}
+
+impl AnalysedWorld {
+    /// Groups the exports by interface, keeping the order in which the interfaces are first
+    /// exported and merging the functions of an interface exported more than once
+    pub fn from_exports(exports: &[AnalysedExport]) -> Self {
+        let mut interfaces: Vec<AnalysedInstance> = Vec::new();
+        let mut functions = Vec::new();
+
+        for export in exports {
+            match export {
+                AnalysedExport::Instance(instance) => {
+                    match interfaces
+                        .iter_mut()
+                        .find(|interface| interface.name == instance.name)
+                    {
+                        Some(interface) => interface
+                            .functions
+                            .extend(instance.functions.iter().cloned()),
+                        None => interfaces.push(instance.clone()),
+                    }
+                }
+                AnalysedExport::Function(function) => functions.push(function.clone()),
+            }
+        }
+
+        Self {
+            interfaces,
+            functions,
+        }
+    }
+}
+
+/// Checks the exports of an analysed component for oddities which do not make the analysis
+/// fail, such as interfaces exported more than once or without any functions
+pub fn analysis_warnings(exports: &[AnalysedExport]) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    if exports.is_empty() {
+        warnings.push("The component does not export any functions".to_string());
+    }
+
+    let mut interface_names = HashSet::new();
+    let mut function_names = HashSet::new();
+    for export in exports {
+        match export {
+            AnalysedExport::Instance(instance) => {
+                if !interface_names.insert(instance.name.as_str()) {
+                    warnings.push(format!(
+                        "Interface {} is exported more than once",
+                        instance.name
+                    ));
+                }
+                if instance.functions.is_empty() {
+                    warnings.push(format!(
+                        "Interface {} does not export any functions",
+                        instance.name
+                    ));
+                }
+                let mut names = HashSet::new();
+                for function in &instance.functions {
+                    if !names.insert(function.name.as_str()) {
+                        warnings.push(format!(
+                            "Function {} is exported more than once by interface {}",
+                            function.name, instance.name
+                        ));
+                    }
+                }
+            }
+            AnalysedExport::Function(function) => {
+                if !function_names.insert(function.name.as_str()) {
+                    warnings.push(format!(
+                        "Function {} is exported more than once",
+                        function.name
+                    ));
+                }
+            }
+        }
+    }
+
+    warnings
+}
+
 #[derive(
     Debug,
     Clone,
@@ -259,10 +368,15 @@ impl From<RawComponentMetadata> for ComponentMetadata {
 
         let memories = value.memories.into_iter().map(LinearMemory::from).collect();
 
+        let world = Some(AnalysedWorld::from_exports(&exports));
+
         ComponentMetadata {
             exports,
             producers,
             memories,
+            world,
+            analysis_warnings: value.warnings,
+            annotations: BTreeMap::new(),
         }
     }
 }
@@ -273,12 +387,16 @@ impl TryFrom<golem_api_grpc::proto::golem::component::ComponentMetadata> for Com
     fn try_from(
         value: golem_api_grpc::proto::golem::component::ComponentMetadata,
     ) -> Result<Self, Self::Error> {
+        let exports: Vec<AnalysedExport> = value
+            .exports
+            .into_iter()
+            .map(|export| export.try_into())
+            .collect::<Result<_, _>>()?;
+        let world = Some(AnalysedWorld::from_exports(&exports));
+        let analysis_warnings = analysis_warnings(&exports);
+
         Ok(Self {
-            exports: value
-                .exports
-                .into_iter()
-                .map(|export| export.try_into())
-                .collect::<Result<_, _>>()?,
+            exports,
             producers: value
                 .producers
                 .into_iter()
@@ -289,6 +407,9 @@ impl TryFrom<golem_api_grpc::proto::golem::component::ComponentMetadata> for Com
                 .into_iter()
                 .map(|memory| memory.into())
                 .collect(),
+            world,
+            analysis_warnings,
+            annotations: value.annotations.into_iter().collect(),
         })
     }
 }
@@ -311,6 +432,7 @@ impl From<ComponentMetadata> for golem_api_grpc::proto::golem::component::Compon
                 .into_iter()
                 .map(|memory| memory.into())
                 .collect(),
+            annotations: value.annotations.into_iter().collect(),
         }
     }
 }
@@ -320,12 +442,19 @@ pub struct RawComponentMetadata {
     pub exports: Vec<AnalysedExport>,
     pub producers: Vec<WasmAstProducers>,
     pub memories: Vec<Mem>,
+    pub warnings: Vec<String>,
 }
 
 impl RawComponentMetadata {
     pub fn analyse_component(
         data: &[u8],
     ) -> Result<RawComponentMetadata, ComponentProcessingError> {
+        if is_core_wasm_module(data) {
+            return Err(ComponentProcessingError::Parsing(
+                CORE_WASM_MODULE_ERROR.to_string(),
+            ));
+        }
+
         let component = Component::<IgnoreAllButMetadata>::from_bytes(data)
             .map_err(ComponentProcessingError::Parsing)?;
 
@@ -343,6 +472,7 @@ impl RawComponentMetadata {
         add_resource_drops(&mut exports);
 
         let exports = exports.into_iter().collect::<Vec<_>>();
+        let warnings = analysis_warnings(&exports);
 
         let memories: Vec<Mem> = state
             .get_all_memories()
@@ -354,6 +484,7 @@ impl RawComponentMetadata {
             exports,
             producers,
             memories,
+            warnings,
         })
     }
 }
@@ -385,6 +516,185 @@ impl Display for ComponentProcessingError {
     }
 }
 
+pub const CORE_WASM_MODULE_ERROR: &str = "this file is a core WASM module, not a component - did you forget to componentize it (for example with `wasm-tools component new`)?";
+
+/// Checks whether the binary is a core WebAssembly module instead of a component, based on
+/// the layer field of the preamble following the magic number.
+pub fn is_core_wasm_module(data: &[u8]) -> bool {
+    data.len() >= 8 && data[0..4] == *b"\0asm" && data[6..8] == [0, 0]
+}
+
+/// The preamble of a component without any sections, the smallest valid component.
+pub const EMPTY_COMPONENT: &[u8] = b"\0asm\x0d\0\x01\0";
+
+/// Name of the custom section of a component that embeds its initial files manifest, as a JSON
+/// array of initial component files.
+pub const INITIAL_FILES_CUSTOM_SECTION: &str = "golem:initial-files";
+
+/// Reads the initial files manifest from the top level `golem:initial-files` custom section
+/// of a component, if it has one.
+pub fn initial_files_from_custom_section(
+    data: &[u8],
+) -> Result<Option<Vec<InitialComponentFile>>, String> {
+    initial_files_from_custom_section_reader(&mut Cursor::new(data))
+}
+
+/// Reads the initial files manifest like [`initial_files_from_custom_section`], seeking over the
+/// other sections so only the section headers and the manifest itself are read from the source.
+pub fn initial_files_from_custom_section_reader<R: Read + Seek>(
+    reader: &mut R,
+) -> Result<Option<Vec<InitialComponentFile>>, String> {
+    match find_custom_section(reader, INITIAL_FILES_CUSTOM_SECTION)? {
+        Some(payload) => serde_json::from_slice(&payload)
+            .map(Some)
+            .map_err(|err| format!("Invalid {INITIAL_FILES_CUSTOM_SECTION} custom section: {err}")),
+        None => Ok(None),
+    }
+}
+
+/// The properties file of a component version in the store of the worker executor's local file
+/// system component source, next to its WASM or patch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalComponentProps {
+    pub version: u64,
+    pub size: u64,
+    pub memories: Vec<LinearMemory>,
+    pub exports: Vec<AnalysedExport>,
+    pub component_type: ComponentType,
+    pub files: Vec<InitialComponentFile>,
+    /// The version this one is stored as a binary patch of, if it is not stored in full
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_version: Option<u64>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+}
+
+impl LocalComponentProps {
+    /// Builds the properties of a version stored as a full WASM by analysing it, with the initial
+    /// files of its `golem:initial-files` custom section
+    pub fn from_wasm(
+        version: u64,
+        component_type: ComponentType,
+        data: &[u8],
+    ) -> Result<LocalComponentProps, String> {
+        let analysed = ComponentMetadata::analyse_component(data).map_err(|err| err.to_string())?;
+        let files = initial_files_from_custom_section(data)?.unwrap_or_default();
+        Ok(LocalComponentProps {
+            version,
+            size: data.len() as u64,
+            memories: analysed.memories,
+            exports: analysed.exports,
+            component_type,
+            files,
+            base_version: None,
+            annotations: BTreeMap::new(),
+        })
+    }
+
+    /// Writes the properties file, see [`write_atomically`]
+    pub async fn write(&self, path: &Path) -> std::io::Result<()> {
+        let data = serde_json::to_vec(self)?;
+        write_atomically(path, data).await
+    }
+}
+
+/// Writes a file of the local component store through a temporary file in the same directory
+/// renamed into place, so that the file is either missing or complete. The temporary file starts
+/// with a dot, so it is never taken for a component version.
+pub async fn write_atomically(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp_path = path.with_file_name(format!(".{file_name}.{}.tmp", uuid::Uuid::new_v4()));
+    tokio::fs::write(&temp_path, contents).await?;
+    if let Err(err) = tokio::fs::rename(&temp_path, path).await {
+        let _ = tokio::fs::remove_file(&temp_path).await;
+        return Err(err);
+    }
+    Ok(())
+}
+
+fn find_custom_section<R: Read + Seek>(
+    reader: &mut R,
+    name: &str,
+) -> Result<Option<Vec<u8>>, String> {
+    let len = reader
+        .seek(SeekFrom::End(0))
+        .map_err(|err| err.to_string())?;
+    reader
+        .seek(SeekFrom::Start(0))
+        .map_err(|err| err.to_string())?;
+
+    let mut preamble = [0u8; 8];
+    if len < 8 || reader.read_exact(&mut preamble).is_err() || preamble[0..4] != *b"\0asm" {
+        return Err("Not a WebAssembly binary".to_string());
+    }
+
+    let mut offset = 8;
+    while offset < len {
+        let id = read_u8(reader)?;
+        let size = read_leb128_u32(reader)? as u64;
+        let start = reader.stream_position().map_err(|err| err.to_string())?;
+        let end = start
+            .checked_add(size)
+            .filter(|end| *end <= len)
+            .ok_or_else(|| "Section exceeds the end of the binary".to_string())?;
+
+        // Custom sections have the same id in core modules and components
+        if id == 0 {
+            let name_len = read_leb128_u32(reader)? as u64;
+            let name_end = reader
+                .stream_position()
+                .map_err(|err| err.to_string())?
+                .checked_add(name_len)
+                .filter(|name_end| *name_end <= end)
+                .ok_or_else(|| "Custom section name exceeds the section".to_string())?;
+
+            let mut section_name = vec![0u8; name_len as usize];
+            reader
+                .read_exact(&mut section_name)
+                .map_err(|_| "Unexpected end of the binary".to_string())?;
+
+            if section_name == name.as_bytes() {
+                let mut payload = vec![0u8; (end - name_end) as usize];
+                reader
+                    .read_exact(&mut payload)
+                    .map_err(|_| "Unexpected end of the binary".to_string())?;
+                return Ok(Some(payload));
+            }
+        }
+
+        reader
+            .seek(SeekFrom::Start(end))
+            .map_err(|err| err.to_string())?;
+        offset = end;
+    }
+
+    Ok(None)
+}
+
+fn read_u8<R: Read>(reader: &mut R) -> Result<u8, String> {
+    let mut byte = [0u8; 1];
+    reader
+        .read_exact(&mut byte)
+        .map_err(|_| "Unexpected end of the binary".to_string())?;
+    Ok(byte[0])
+}
+
+fn read_leb128_u32<R: Read>(reader: &mut R) -> Result<u32, String> {
+    let mut result: u32 = 0;
+    for shift in (0..35).step_by(7) {
+        let byte = read_u8(reader)?;
+        result |= ((byte & 0x7f) as u32) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+    }
+    Err("Invalid LEB128 encoded integer".to_string())
+}
+
 fn add_resource_drops(exports: &mut Vec<AnalysedExport>) {
     // Components are not exporting explicit drop functions for exported resources, but
     // worker executor does. So we keep golem-wasm-ast as a universal library and extend
@@ -429,3 +739,199 @@ fn drop_from_constructor(constructor: &AnalysedFunction) -> AnalysedFunction {
         results: vec![],
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use golem_wasm_ast::analysis::{AnalysedExport, AnalysedFunction, AnalysedInstance};
+
+    use crate::model::component_metadata::{
+        analysis_warnings, initial_files_from_custom_section,
+        initial_files_from_custom_section_reader, is_core_wasm_module, AnalysedWorld,
+        LocalComponentProps, EMPTY_COMPONENT, INITIAL_FILES_CUSTOM_SECTION,
+    };
+    use crate::model::{ComponentFilePermissions, ComponentType, InitialComponentFileKey};
+
+    fn function(name: &str) -> AnalysedFunction {
+        AnalysedFunction {
+            name: name.to_string(),
+            parameters: vec![],
+            results: vec![],
+        }
+    }
+
+    fn custom_section(name: &str, payload: &[u8]) -> Vec<u8> {
+        let mut content = vec![name.len() as u8];
+        content.extend_from_slice(name.as_bytes());
+        content.extend_from_slice(payload);
+
+        let mut section = vec![0, content.len() as u8];
+        section.extend(content);
+        section
+    }
+
+    #[test]
+    fn detects_core_wasm_module() {
+        assert!(is_core_wasm_module(b"\0asm\x01\0\0\0"));
+    }
+
+    #[test]
+    fn does_not_detect_component_as_core_wasm_module() {
+        assert!(!is_core_wasm_module(EMPTY_COMPONENT));
+        assert!(!is_core_wasm_module(b"\0asm"));
+    }
+
+    #[test]
+    fn reads_initial_files_from_custom_section() {
+        let manifest =
+            br#"[{"key":"abc","path":"/config/settings.json","permissions":"read-only"}]"#;
+
+        let mut data = EMPTY_COMPONENT.to_vec();
+        data.extend(custom_section("other", b"ignored"));
+        data.extend(custom_section(INITIAL_FILES_CUSTOM_SECTION, manifest));
+
+        let files = initial_files_from_custom_section(&data).unwrap().unwrap();
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].key, InitialComponentFileKey("abc".to_string()));
+        assert_eq!(files[0].path.to_string(), "/config/settings.json");
+        assert_eq!(files[0].permissions, ComponentFilePermissions::ReadOnly);
+    }
+
+    #[test]
+    fn missing_initial_files_custom_section() {
+        let mut data = EMPTY_COMPONENT.to_vec();
+        data.extend(custom_section("other", b"ignored"));
+
+        assert_eq!(initial_files_from_custom_section(&data).unwrap(), None);
+    }
+
+    #[test]
+    fn reads_initial_files_from_custom_section_reader() {
+        let manifest =
+            br#"[{"key":"abc","path":"/config/settings.json","permissions":"read-write"}]"#;
+
+        let mut data = EMPTY_COMPONENT.to_vec();
+        data.extend(custom_section("other", &[0xff; 100]));
+        data.extend(custom_section(INITIAL_FILES_CUSTOM_SECTION, manifest));
+
+        let files = initial_files_from_custom_section_reader(&mut std::io::Cursor::new(data))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].permissions, ComponentFilePermissions::ReadWrite);
+    }
+
+    #[test]
+    fn rejects_section_exceeding_the_binary() {
+        let mut data = EMPTY_COMPONENT.to_vec();
+        data.extend(custom_section("other", b"ignored"));
+        data.truncate(data.len() - 2);
+
+        assert!(initial_files_from_custom_section(&data).is_err());
+    }
+
+    #[test]
+    fn world_groups_functions_under_their_interfaces() {
+        let exports = vec![
+            AnalysedExport::Instance(AnalysedInstance {
+                name: "golem:it/api".to_string(),
+                functions: vec![function("add")],
+            }),
+            AnalysedExport::Function(function("run")),
+            AnalysedExport::Instance(AnalysedInstance {
+                name: "golem:it/other".to_string(),
+                functions: vec![function("get")],
+            }),
+            AnalysedExport::Instance(AnalysedInstance {
+                name: "golem:it/api".to_string(),
+                functions: vec![function("remove")],
+            }),
+        ];
+
+        assert_eq!(
+            AnalysedWorld::from_exports(&exports),
+            AnalysedWorld {
+                interfaces: vec![
+                    AnalysedInstance {
+                        name: "golem:it/api".to_string(),
+                        functions: vec![function("add"), function("remove")],
+                    },
+                    AnalysedInstance {
+                        name: "golem:it/other".to_string(),
+                        functions: vec![function("get")],
+                    },
+                ],
+                functions: vec![function("run")],
+            }
+        );
+    }
+
+    #[test]
+    fn analysis_warnings_of_odd_exports() {
+        let exports = vec![
+            AnalysedExport::Instance(AnalysedInstance {
+                name: "golem:it/api".to_string(),
+                functions: vec![function("add"), function("add")],
+            }),
+            AnalysedExport::Instance(AnalysedInstance {
+                name: "golem:it/empty".to_string(),
+                functions: vec![],
+            }),
+            AnalysedExport::Instance(AnalysedInstance {
+                name: "golem:it/api".to_string(),
+                functions: vec![function("remove")],
+            }),
+            AnalysedExport::Function(function("run")),
+            AnalysedExport::Function(function("run")),
+        ];
+
+        assert_eq!(
+            analysis_warnings(&exports),
+            vec![
+                "Function add is exported more than once by interface golem:it/api".to_string(),
+                "Interface golem:it/empty does not export any functions".to_string(),
+                "Interface golem:it/api is exported more than once".to_string(),
+                "Function run is exported more than once".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn no_analysis_warnings_of_regular_exports() {
+        let exports = vec![
+            AnalysedExport::Instance(AnalysedInstance {
+                name: "golem:it/api".to_string(),
+                functions: vec![function("add"), function("remove")],
+            }),
+            AnalysedExport::Function(function("run")),
+        ];
+
+        assert!(analysis_warnings(&exports).is_empty());
+        assert_eq!(
+            analysis_warnings(&[]),
+            vec!["The component does not export any functions".to_string()]
+        );
+    }
+
+    #[test]
+    async fn local_component_props_are_written_for_a_wasm() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("props.json");
+
+        let props =
+            LocalComponentProps::from_wasm(3, ComponentType::Ephemeral, EMPTY_COMPONENT).unwrap();
+        assert_eq!(props.version, 3);
+        assert_eq!(props.size, EMPTY_COMPONENT.len() as u64);
+        assert!(props.files.is_empty());
+
+        props.write(&path).await.unwrap();
+        let written: LocalComponentProps =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(written, props);
+        // Only the properties file is left, not the temporary one it was written to
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
@@ -19,6 +19,7 @@ use std::{
 
 use config::ServerConfig;
 use golem_api_grpc::proto::golem::componentcompilation::v1::component_compilation_service_server::ComponentCompilationServiceServer;
+use golem_common::engine::create_wasmtime_config;
 use golem_common::tracing::init_tracing_with_default_env_filter;
 use golem_service_base::config::BlobStorageConfig;
 use golem_service_base::storage::blob::s3::S3BlobStorage;
@@ -35,7 +36,6 @@ use crate::config::make_config_loader;
 use crate::service::compile_service::ComponentCompilationServiceImpl;
 use golem_service_base::storage::blob::sqlite::SqliteBlobStorage;
 use golem_service_base::storage::sqlite::SqlitePool;
-use wasmtime::WasmBacktraceDetails;
 
 mod config;
 mod grpc;
@@ -99,7 +99,8 @@ async fn run(config: ServerConfig, prometheus: Registry) -> Result<(), Box<dyn s
         }
     };
     let compiled_component =
-        compiled_component::configured(&config.compiled_component_service, blob_storage.clone());
+        compiled_component::configured(&config.compiled_component_service, blob_storage.clone())
+            .await;
     let engine = wasmtime::Engine::new(&create_wasmtime_config()).expect("Failed to create engine");
 
     // Start metrics and healthcheck server.
@@ -152,19 +153,6 @@ async fn start_grpc_server(
         .await
 }
 
-fn create_wasmtime_config() -> wasmtime::Config {
-    let mut config = wasmtime::Config::default();
-
-    config.wasm_multi_value(true);
-    config.async_support(true);
-    config.wasm_component_model(true);
-    config.epoch_interruption(true);
-    config.consume_fuel(true);
-    config.wasm_backtrace_details(WasmBacktraceDetails::Enable);
-
-    config
-}
-
 pub trait UriBackConversion {
     fn as_http_02(&self) -> http_02::Uri;
 }
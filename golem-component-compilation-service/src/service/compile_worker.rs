@@ -160,7 +160,7 @@ impl CompileWorker {
 
         let compilation_time = end.duration_since(start);
 
-        record_compilation_time(compilation_time);
+        record_compilation_time(compilation_time, bytes.len() as u64);
 
         tracing::debug!(
             "Compiled {component_with_version:?} in {}ms",
@@ -198,6 +198,7 @@ async fn download_via_grpc(
                             DownloadComponentRequest {
                                 component_id: Some(component_id.clone().into()),
                                 version: Some(component_version),
+                                base_version: None,
                             },
                             &access_token,
                         );
@@ -24,7 +24,7 @@ use golem_common::model::{
 };
 use golem_service_base::model::{ComponentName, VersionedComponentId};
 use golem_service_base::repo::RepoError;
-use sqlx::{Database, Pool, Row};
+use sqlx::{Database, Pool, Row, Transaction};
 use std::fmt::Display;
 use std::ops::Deref;
 use std::result::Result;
@@ -200,6 +200,15 @@ impl TryFrom<FileRecord> for InitialComponentFile {
 pub trait ComponentRepo {
     async fn create(&self, component: &ComponentRecord) -> Result<(), RepoError>;
 
+    /// Creates the next version of an existing component if its latest version is still
+    /// `current_version`, returning whether it was created. The latest version is checked and
+    /// moved in a single statement, so of concurrent updates only one can succeed
+    async fn create_next_version(
+        &self,
+        component: &ComponentRecord,
+        current_version: u64,
+    ) -> Result<bool, RepoError>;
+
     async fn get(&self, component_id: &Uuid) -> Result<Vec<ComponentRecord>, RepoError>;
 
     async fn get_all(&self, namespace: &str) -> Result<Vec<ComponentRecord>, RepoError>;
@@ -221,10 +230,26 @@ pub trait ComponentRepo {
         name: &str,
     ) -> Result<Vec<ComponentRecord>, RepoError>;
 
+    async fn get_by_tag(
+        &self,
+        component_id: &Uuid,
+        tag: &str,
+    ) -> Result<Option<ComponentRecord>, RepoError>;
+
+    /// Points the tag to the given version, moving it if it already exists
+    async fn tag(&self, component_id: &Uuid, version: u64, tag: &str) -> Result<(), RepoError>;
+
     async fn get_id_by_name(&self, namespace: &str, name: &str) -> Result<Option<Uuid>, RepoError>;
 
     async fn get_namespace(&self, component_id: &Uuid) -> Result<Option<String>, RepoError>;
 
+    async fn rename(
+        &self,
+        namespace: &str,
+        component_id: &Uuid,
+        name: &str,
+    ) -> Result<(), RepoError>;
+
     async fn delete(&self, namespace: &str, component_id: &Uuid) -> Result<(), RepoError>;
 
     async fn create_or_update_constraint(
@@ -289,6 +314,48 @@ impl DbComponentRepo<sqlx::Postgres> {
 
         try_join_all(result).await
     }
+
+    async fn insert_version(
+        transaction: &mut Transaction<'_, sqlx::Postgres>,
+        component: &ComponentRecord,
+    ) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"
+              INSERT INTO component_versions
+                (component_id, version, size, metadata, created_at, component_type)
+              VALUES
+                ($1, $2, $3, $4, $5, $6)
+               "#,
+        )
+        .bind(component.component_id)
+        .bind(component.version)
+        .bind(component.size)
+        .bind(component.metadata.clone())
+        .bind(component.created_at)
+        .bind(component.component_type)
+        .execute(&mut **transaction)
+        .await?;
+
+        for file in &component.files {
+            sqlx::query(
+                r#"
+                  INSERT INTO component_files
+                    (component_id, version, file_path, file_key, file_permissions)
+                  VALUES
+                    ($1, $2, $3, $4, $5)
+                "#,
+            )
+            .bind(component.component_id)
+            .bind(component.version)
+            .bind(file.file_path.clone())
+            .bind(file.file_key.clone())
+            .bind(file.file_permissions.clone())
+            .execute(&mut **transaction)
+            .await?;
+        }
+
+        Ok(())
+    }
 }
 
 pub struct LoggedComponentRepo<Repo: ComponentRepo> {
@@ -332,6 +399,18 @@ impl<Repo: ComponentRepo + Send + Sync> ComponentRepo for LoggedComponentRepo<Re
         Self::logged_with_id("create", &component.component_id, result)
     }
 
+    async fn create_next_version(
+        &self,
+        component: &ComponentRecord,
+        current_version: u64,
+    ) -> Result<bool, RepoError> {
+        let result = self
+            .repo
+            .create_next_version(component, current_version)
+            .await;
+        Self::logged_with_id("create_next_version", &component.component_id, result)
+    }
+
     async fn get(&self, component_id: &Uuid) -> Result<Vec<ComponentRecord>, RepoError> {
         let result = self.repo.get(component_id).await;
         Self::logged_with_id("get", component_id, result)
@@ -368,6 +447,20 @@ impl<Repo: ComponentRepo + Send + Sync> ComponentRepo for LoggedComponentRepo<Re
         Self::logged("get_by_name", result)
     }
 
+    async fn get_by_tag(
+        &self,
+        component_id: &Uuid,
+        tag: &str,
+    ) -> Result<Option<ComponentRecord>, RepoError> {
+        let result = self.repo.get_by_tag(component_id, tag).await;
+        Self::logged_with_id("get_by_tag", component_id, result)
+    }
+
+    async fn tag(&self, component_id: &Uuid, version: u64, tag: &str) -> Result<(), RepoError> {
+        let result = self.repo.tag(component_id, version, tag).await;
+        Self::logged_with_id("tag", component_id, result)
+    }
+
     async fn get_id_by_name(&self, namespace: &str, name: &str) -> Result<Option<Uuid>, RepoError> {
         let result = self.repo.get_id_by_name(namespace, name).await;
         Self::logged("get_id_by_name", result)
@@ -378,6 +471,16 @@ impl<Repo: ComponentRepo + Send + Sync> ComponentRepo for LoggedComponentRepo<Re
         Self::logged_with_id("get_namespace", component_id, result)
     }
 
+    async fn rename(
+        &self,
+        namespace: &str,
+        component_id: &Uuid,
+        name: &str,
+    ) -> Result<(), RepoError> {
+        let result = self.repo.rename(namespace, component_id, name).await;
+        Self::logged_with_id("rename", component_id, result)
+    }
+
     async fn delete(&self, namespace: &str, component_id: &Uuid) -> Result<(), RepoError> {
         let result = self.repo.delete(namespace, component_id).await;
         Self::logged_with_id("delete", component_id, result)
@@ -423,59 +526,64 @@ impl ComponentRepo for DbComponentRepo<sqlx::Postgres> {
                     "Component namespace and name invalid".to_string(),
                 ));
             }
+
+            sqlx::query("UPDATE components SET latest_version = $2 WHERE component_id = $1")
+                .bind(component.component_id)
+                .bind(component.version)
+                .execute(&mut *transaction)
+                .await?;
         } else {
             sqlx::query(
                 r#"
                   INSERT INTO components
-                    (namespace, component_id, name)
+                    (namespace, component_id, name, latest_version)
                   VALUES
-                    ($1, $2, $3)
+                    ($1, $2, $3, $4)
                    "#,
             )
             .bind(component.namespace.clone())
             .bind(component.component_id)
             .bind(component.name.clone())
+            .bind(component.version)
             .execute(&mut *transaction)
             .await?;
         }
 
-        sqlx::query(
+        Self::insert_version(&mut transaction, component).await?;
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    async fn create_next_version(
+        &self,
+        component: &ComponentRecord,
+        current_version: u64,
+    ) -> Result<bool, RepoError> {
+        let mut transaction = self.db_pool.begin().await?;
+
+        let result = sqlx::query(
             r#"
-              INSERT INTO component_versions
-                (component_id, version, size, metadata, created_at, component_type)
-              VALUES
-                ($1, $2, $3, $4, $5, $6)
-               "#,
+              UPDATE components
+              SET latest_version = $4
+              WHERE namespace = $1 AND component_id = $2 AND latest_version = $3
+            "#,
         )
+        .bind(component.namespace.clone())
         .bind(component.component_id)
+        .bind(current_version as i64)
         .bind(component.version)
-        .bind(component.size)
-        .bind(component.metadata.clone())
-        .bind(component.created_at)
-        .bind(component.component_type)
         .execute(&mut *transaction)
         .await?;
 
-        for file in &component.files {
-            sqlx::query(
-                r#"
-                  INSERT INTO component_files
-                    (component_id, version, file_path, file_key, file_permissions)
-                  VALUES
-                    ($1, $2, $3, $4, $5)
-                "#,
-            )
-            .bind(component.component_id)
-            .bind(component.version)
-            .bind(file.file_path.clone())
-            .bind(file.file_key.clone())
-            .bind(file.file_permissions.clone())
-            .execute(&mut *transaction)
-            .await?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
         }
 
+        Self::insert_version(&mut transaction, component).await?;
+
         transaction.commit().await?;
-        Ok(())
+        Ok(true)
     }
 
     #[when(sqlx::Postgres -> get)]
@@ -766,6 +874,89 @@ impl ComponentRepo for DbComponentRepo<sqlx::Postgres> {
         self.add_files(components).await
     }
 
+    #[when(sqlx::Postgres -> get_by_tag)]
+    async fn get_by_tag_postgres(
+        &self,
+        component_id: &Uuid,
+        tag: &str,
+    ) -> Result<Option<ComponentRecord>, RepoError> {
+        let component = sqlx::query_as::<_, ComponentRecord>(
+            r#"
+                SELECT
+                    c.namespace AS namespace,
+                    c.name AS name,
+                    c.component_id AS component_id,
+                    cv.version AS version,
+                    cv.size AS size,
+                    cv.metadata AS metadata,
+                    cv.created_at::timestamptz AS created_at,
+                    cv.component_type AS component_type
+                FROM components c
+                    JOIN component_versions cv ON c.component_id = cv.component_id
+                    JOIN component_tags ct ON cv.component_id = ct.component_id AND cv.version = ct.version
+                WHERE c.component_id = $1 AND ct.tag = $2
+                "#,
+        )
+        .bind(component_id)
+        .bind(tag)
+        .fetch_optional(self.db_pool.deref())
+        .await
+        .map_err::<RepoError, _>(|e| e.into())?;
+
+        Ok(self.add_files(component).await?.pop())
+    }
+
+    #[when(sqlx::Sqlite -> get_by_tag)]
+    async fn get_by_tag_sqlite(
+        &self,
+        component_id: &Uuid,
+        tag: &str,
+    ) -> Result<Option<ComponentRecord>, RepoError> {
+        let component = sqlx::query_as::<_, ComponentRecord>(
+            r#"
+                SELECT
+                    c.namespace AS namespace,
+                    c.name AS name,
+                    c.component_id AS component_id,
+                    cv.version AS version,
+                    cv.size AS size,
+                    cv.metadata AS metadata,
+                    cv.created_at AS created_at,
+                    cv.component_type AS component_type
+                FROM components c
+                    JOIN component_versions cv ON c.component_id = cv.component_id
+                    JOIN component_tags ct ON cv.component_id = ct.component_id AND cv.version = ct.version
+                WHERE c.component_id = $1 AND ct.tag = $2
+                "#,
+        )
+        .bind(component_id)
+        .bind(tag)
+        .fetch_optional(self.db_pool.deref())
+        .await
+        .map_err::<RepoError, _>(|e| e.into())?;
+
+        Ok(self.add_files(component).await?.pop())
+    }
+
+    async fn tag(&self, component_id: &Uuid, version: u64, tag: &str) -> Result<(), RepoError> {
+        sqlx::query(
+            r#"
+              INSERT INTO component_tags
+                (component_id, tag, version)
+              VALUES
+                ($1, $2, $3)
+              ON CONFLICT (component_id, tag) DO UPDATE SET version = excluded.version
+               "#,
+        )
+        .bind(component_id)
+        .bind(tag)
+        .bind(version as i64)
+        .execute(self.db_pool.deref())
+        .await?;
+
+        Ok(())
+    }
+
     async fn get_id_by_name(&self, namespace: &str, name: &str) -> Result<Option<Uuid>, RepoError> {
         let result =
             sqlx::query("SELECT component_id FROM components WHERE namespace = $1 AND name = $2")
@@ -786,8 +977,35 @@ impl ComponentRepo for DbComponentRepo<sqlx::Postgres> {
         Ok(result.map(|x| x.get("namespace")))
     }
 
+    async fn rename(
+        &self,
+        namespace: &str,
+        component_id: &Uuid,
+        name: &str,
+    ) -> Result<(), RepoError> {
+        sqlx::query("UPDATE components SET name = $3 WHERE namespace = $1 AND component_id = $2")
+            .bind(namespace)
+            .bind(component_id)
+            .bind(name)
+            .execute(self.db_pool.deref())
+            .await?;
+
+        Ok(())
+    }
+
     async fn delete(&self, namespace: &str, component_id: &Uuid) -> Result<(), RepoError> {
         let mut transaction = self.db_pool.begin().await?;
+        sqlx::query(
+            r#"
+                DELETE FROM component_tags
+                WHERE component_id IN (SELECT component_id FROM components WHERE namespace = $1 AND component_id = $2)
+            "#
+        )
+            .bind(namespace)
+            .bind(component_id)
+            .execute(&mut *transaction)
+            .await?;
+
         sqlx::query(
             r#"
                 DELETE FROM component_versions
@@ -77,6 +77,19 @@ pub enum ComponentError {
     InitialComponentFileUploadError { message: String, error: String },
     #[error("Provided component file not found: {path} (key: {key})")]
     InitialComponentFileNotFound { path: String, key: String },
+    #[error("Invalid component tag {tag}: {reason}")]
+    InvalidComponentTag { tag: String, reason: String },
+    #[error("Component {component_id} has been updated concurrently: expected current version {expected_version}, but it is {current_version}")]
+    ConcurrentUpdate {
+        component_id: ComponentId,
+        expected_version: u64,
+        current_version: u64,
+    },
+    #[error("Component name {name} is already taken by component {component_id}")]
+    ComponentNameAlreadyTaken {
+        name: ComponentName,
+        component_id: ComponentId,
+    },
 }
 
 impl ComponentError {
@@ -147,6 +160,9 @@ impl SafeDisplay for ComponentError {
             ComponentError::MalformedComponentArchiveError { .. } => self.to_string(),
             ComponentError::InitialComponentFileUploadError { .. } => self.to_string(),
             ComponentError::InitialComponentFileNotFound { .. } => self.to_string(),
+            ComponentError::InvalidComponentTag { .. } => self.to_string(),
+            ComponentError::ConcurrentUpdate { .. } => self.to_string(),
+            ComponentError::ComponentNameAlreadyTaken { .. } => self.to_string(),
         }
     }
 }
@@ -214,11 +230,50 @@ impl From<ComponentError> for golem_api_grpc::proto::golem::component::v1::Compo
                     error: value.to_safe_string(),
                 })
             }
+            ComponentError::InvalidComponentTag { .. } => {
+                component_error::Error::BadRequest(ErrorsBody {
+                    errors: vec![value.to_safe_string()],
+                })
+            }
+            ComponentError::ConcurrentUpdate { .. } => {
+                component_error::Error::AlreadyExists(ErrorBody {
+                    error: value.to_safe_string(),
+                })
+            }
+            ComponentError::ComponentNameAlreadyTaken { .. } => {
+                component_error::Error::AlreadyExists(ErrorBody {
+                    error: value.to_safe_string(),
+                })
+            }
         };
         Self { error: Some(error) }
     }
 }
 
+/// Tags are human-friendly aliases of component versions, such as `stable` or `canary`. They
+/// can't be numbers, so they are never ambiguous with versions where either is accepted.
+pub fn validate_component_tag(tag: &str) -> Result<(), ComponentError> {
+    let invalid = |reason: &str| ComponentError::InvalidComponentTag {
+        tag: tag.to_string(),
+        reason: reason.to_string(),
+    };
+
+    if tag.is_empty() || tag.len() > 128 {
+        Err(invalid("must be between 1 and 128 characters long"))
+    } else if !tag
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+    {
+        Err(invalid(
+            "must only contain ASCII letters, digits, '-', '_' and '.'",
+        ))
+    } else if tag.chars().all(|c| c.is_ascii_digit()) {
+        Err(invalid("must not be a number"))
+    } else {
+        Ok(())
+    }
+}
+
 pub fn create_new_versioned_component_id(component_id: &ComponentId) -> VersionedComponentId {
     VersionedComponentId {
         component_id: component_id.clone(),
@@ -255,6 +310,7 @@ pub trait ComponentService<Namespace> {
         data: Vec<u8>,
         component_type: Option<ComponentType>,
         files: Option<InitialComponentFilesArchiveAndPermissions>,
+        expected_current_version: Option<u64>,
         namespace: &Namespace,
     ) -> Result<Component<Namespace>, ComponentError>;
 
@@ -266,6 +322,8 @@ pub trait ComponentService<Namespace> {
         component_type: Option<ComponentType>,
         // None signals that files should be reused from the previous version
         files: Option<Vec<InitialComponentFile>>,
+        // When set, the update is rejected if the latest version of the component is a different one
+        expected_current_version: Option<u64>,
         namespace: &Namespace,
     ) -> Result<Component<Namespace>, ComponentError>;
 
@@ -293,6 +351,17 @@ pub trait ComponentService<Namespace> {
         namespace: &Namespace,
     ) -> Result<Option<Vec<u8>>, ComponentError>;
 
+    /// Gets the content of one of the initial files of a version of the component. Returns None
+    /// if the version has no file with the given key, or if its content is missing from the
+    /// blob storage
+    async fn get_initial_component_file(
+        &self,
+        component_id: &ComponentId,
+        version: Option<u64>,
+        key: &InitialComponentFileKey,
+        namespace: &Namespace,
+    ) -> Result<Option<Bytes>, ComponentError>;
+
     async fn find_by_name(
         &self,
         component_name: Option<ComponentName>,
@@ -317,6 +386,30 @@ pub trait ComponentService<Namespace> {
         namespace: &Namespace,
     ) -> Result<Option<Component<Namespace>>, ComponentError>;
 
+    async fn get_by_tag(
+        &self,
+        component_id: &ComponentId,
+        tag: &str,
+        namespace: &Namespace,
+    ) -> Result<Option<Component<Namespace>>, ComponentError>;
+
+    /// Tags an existing version of the component, moving the tag if it already points to
+    /// another version
+    async fn tag(
+        &self,
+        component_id: &VersionedComponentId,
+        tag: &str,
+        namespace: &Namespace,
+    ) -> Result<Component<Namespace>, ComponentError>;
+
+    /// Renames the component, keeping all of its versions, tags and workers
+    async fn rename(
+        &self,
+        component_id: &ComponentId,
+        new_name: &ComponentName,
+        namespace: &Namespace,
+    ) -> Result<Component<Namespace>, ComponentError>;
+
     async fn get(
         &self,
         component_id: &ComponentId,
@@ -478,6 +571,7 @@ impl ComponentServiceDefault {
         data: Vec<u8>,
         component_type: Option<ComponentType>,
         files: Option<Vec<InitialComponentFile>>,
+        expected_current_version: Option<u64>,
         namespace: &Namespace,
     ) -> Result<Component<Namespace>, ComponentError>
     where
@@ -503,7 +597,7 @@ impl ComponentServiceDefault {
             }
         }
 
-        let next_component = self
+        let current_component: Component<Namespace> = self
             .component_repo
             .get_latest_version(&component_id.0)
             .await?
@@ -512,8 +606,20 @@ impl ComponentServiceDefault {
             .and_then(|c| {
                 c.try_into()
                     .map_err(|e| ComponentError::conversion_error("record", e))
-            })
-            .map(Component::next_version)?;
+            })?;
+
+        let current_version = current_component.versioned_component_id.version;
+        if let Some(expected_version) = expected_current_version {
+            if current_version != expected_version {
+                return Err(ComponentError::ConcurrentUpdate {
+                    component_id: component_id.clone(),
+                    expected_version,
+                    current_version,
+                });
+            }
+        }
+
+        let next_component = current_component.next_version();
 
         // Fallback to the files from the previous version if no files are provided.
         let files_to_use = files.unwrap_or_else(|| next_component.files.clone());
@@ -541,7 +647,24 @@ impl ComponentServiceDefault {
             .try_into()
             .map_err(|e| ComponentError::conversion_error("record", e))?;
 
-        self.component_repo.create(&record).await?;
+        // Another update may have created a version since the latest one was read above
+        if !self
+            .component_repo
+            .create_next_version(&record, current_version)
+            .await?
+        {
+            let latest_version = self
+                .component_repo
+                .get_latest_version(&component_id.0)
+                .await?
+                .map(|record| record.version as u64)
+                .unwrap_or(current_version);
+            return Err(ComponentError::ConcurrentUpdate {
+                component_id: component_id.clone(),
+                expected_version: expected_current_version.unwrap_or(current_version),
+                current_version: latest_version,
+            });
+        }
 
         self.component_compilation
             .enqueue_compilation(component_id, component.versioned_component_id.version)
@@ -720,6 +843,7 @@ where
         data: Vec<u8>,
         component_type: Option<ComponentType>,
         files: Option<InitialComponentFilesArchiveAndPermissions>,
+        expected_current_version: Option<u64>,
         namespace: &Namespace,
     ) -> Result<Component<Namespace>, ComponentError> {
         info!(namespace = %namespace, "Update component");
@@ -737,6 +861,7 @@ where
             data,
             component_type,
             uploaded_files,
+            expected_current_version,
             namespace,
         )
         .await
@@ -748,6 +873,7 @@ where
         data: Vec<u8>,
         component_type: Option<ComponentType>,
         files: Option<Vec<InitialComponentFile>>,
+        expected_current_version: Option<u64>,
         namespace: &Namespace,
     ) -> Result<Component<Namespace>, ComponentError> {
         info!(namespace = %namespace, "Update component");
@@ -771,8 +897,15 @@ where
             }
         }
 
-        self.update_unchecked(component_id, data, component_type, files, namespace)
-            .await
+        self.update_unchecked(
+            component_id,
+            data,
+            component_type,
+            files,
+            expected_current_version,
+            namespace,
+        )
+        .await
     }
 
     async fn download(
@@ -849,6 +982,43 @@ where
         }
     }
 
+    async fn get_initial_component_file(
+        &self,
+        component_id: &ComponentId,
+        version: Option<u64>,
+        key: &InitialComponentFileKey,
+        namespace: &Namespace,
+    ) -> Result<Option<Bytes>, ComponentError> {
+        info!(namespace = %namespace, "Get initial component file");
+
+        let versioned_component_id = self
+            .get_versioned_component_id(component_id, version, namespace)
+            .await?
+            .ok_or(ComponentError::UnknownComponentId(component_id.clone()))?;
+
+        let component: Option<Component<Namespace>> = self
+            .get_by_version(&versioned_component_id, namespace)
+            .await?;
+        let belongs_to_component = component
+            .map(|component| component.files.iter().any(|file| &file.key == key))
+            .unwrap_or(false);
+
+        if !belongs_to_component {
+            return Ok(None);
+        }
+
+        self.initial_component_files_service
+            .get(&namespace.account_id(), key)
+            .await
+            .tap_err(|e| error!(namespace = %namespace, "Error getting initial component file - error: {}", e))
+            .map_err(|e| {
+                ComponentError::component_store_error(
+                    "Error retrieving initial component file",
+                    anyhow::Error::msg(e),
+                )
+            })
+    }
+
     async fn find_by_name(
         &self,
         component_name: Option<ComponentName>,
@@ -936,6 +1106,98 @@ where
         }
     }
 
+    async fn get_by_tag(
+        &self,
+        component_id: &ComponentId,
+        tag: &str,
+        namespace: &Namespace,
+    ) -> Result<Option<Component<Namespace>>, ComponentError> {
+        info!(namespace = %namespace, "Get component by tag");
+
+        let result = self.component_repo.get_by_tag(&component_id.0, tag).await?;
+
+        match result {
+            Some(c) if c.namespace == namespace.to_string() => {
+                let value = c
+                    .try_into()
+                    .map_err(|e| ComponentError::conversion_error("record", e))?;
+                Ok(Some(value))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    async fn tag(
+        &self,
+        component_id: &VersionedComponentId,
+        tag: &str,
+        namespace: &Namespace,
+    ) -> Result<Component<Namespace>, ComponentError> {
+        info!(namespace = %namespace, "Tag component");
+
+        validate_component_tag(tag)?;
+
+        let component = self
+            .get_by_version(component_id, namespace)
+            .await?
+            .ok_or_else(|| ComponentError::UnknownVersionedComponentId(component_id.clone()))?;
+
+        self.component_repo
+            .tag(&component_id.component_id.0, component_id.version, tag)
+            .await?;
+
+        Ok(component)
+    }
+
+    async fn rename(
+        &self,
+        component_id: &ComponentId,
+        new_name: &ComponentName,
+        namespace: &Namespace,
+    ) -> Result<Component<Namespace>, ComponentError> {
+        info!(namespace = %namespace, "Rename component");
+
+        let component = self
+            .get_latest_version(component_id, namespace)
+            .await?
+            .ok_or_else(|| ComponentError::UnknownComponentId(component_id.clone()))?;
+
+        if component.component_name == *new_name {
+            return Ok(component);
+        }
+
+        let name_taken = |id: ComponentId| ComponentError::ComponentNameAlreadyTaken {
+            name: new_name.clone(),
+            component_id: id,
+        };
+
+        if let Some(id) = self.find_id_by_name(new_name, namespace).await? {
+            return Err(name_taken(id));
+        }
+
+        // The name can still be taken concurrently, which the unique index of the names catches.
+        // The component taking it is looked up again to report it, unless it is gone already
+        match self
+            .component_repo
+            .rename(&namespace.to_string(), &component_id.0, &new_name.0)
+            .await
+        {
+            Ok(()) => {}
+            Err(error @ RepoError::UniqueViolation(_)) => {
+                return Err(match self.find_id_by_name(new_name, namespace).await? {
+                    Some(id) => name_taken(id),
+                    None => error.into(),
+                });
+            }
+            Err(error) => return Err(error.into()),
+        }
+
+        Ok(Component {
+            component_name: new_name.clone(),
+            ..component
+        })
+    }
+
     async fn get(
         &self,
         component_id: &ComponentId,
@@ -1270,10 +1532,20 @@ mod internal {
 mod tests {
     use test_r::test;
 
-    use crate::service::component::ComponentError;
+    use crate::service::component::{validate_component_tag, ComponentError};
     use golem_common::SafeDisplay;
     use golem_service_base::repo::RepoError;
 
+    #[test]
+    pub fn test_validate_component_tag() {
+        assert!(validate_component_tag("stable").is_ok());
+        assert!(validate_component_tag("canary-2024.10_rc").is_ok());
+        assert!(validate_component_tag("").is_err());
+        assert!(validate_component_tag("42").is_err());
+        assert!(validate_component_tag("with space").is_err());
+        assert!(validate_component_tag(&"a".repeat(129)).is_err());
+    }
+
     #[test]
     pub fn test_repo_error_to_service_error() {
         let repo_err = RepoError::Internal("some sql error".to_string());
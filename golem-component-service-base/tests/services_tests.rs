@@ -202,6 +202,7 @@ async fn test_component_constraint_incompatible_updates(
             get_component_data("shopping-cart"),
             None,
             None,
+            None,
             &DefaultNamespace::default(),
         )
         .await
@@ -257,6 +258,8 @@ async fn test_services(component_repo: Arc<dyn ComponentRepo + Sync + Send>) {
         ));
 
     test_complex_component_service_flow(component_service.clone()).await;
+    test_conditional_update(component_service.clone()).await;
+    test_rename(component_service.clone()).await;
     test_initial_component_file_upload(component_service.clone()).await;
     test_initial_component_file_data_sharing(component_service.clone()).await;
 }
@@ -374,6 +377,7 @@ async fn test_complex_component_service_flow(
             get_component_data("shopping-cart"),
             None,
             None,
+            None,
             &DefaultNamespace::default(),
         )
         .await
@@ -535,6 +539,139 @@ async fn test_complex_component_service_flow(
     assert!(component1_result.is_none());
 }
 
+async fn test_conditional_update(
+    component_service: Arc<dyn ComponentService<DefaultNamespace> + Sync + Send>,
+) {
+    let component_id = ComponentId::new_v4();
+    component_service
+        .create(
+            &component_id,
+            &ComponentName("shopping-cart-conditional-update".to_string()),
+            ComponentType::Durable,
+            get_component_data("shopping-cart"),
+            None,
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap();
+
+    let component_v1 = component_service
+        .update(
+            &component_id,
+            get_component_data("shopping-cart"),
+            None,
+            None,
+            Some(0),
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(component_v1.versioned_component_id.version, 1);
+
+    let error = component_service
+        .update(
+            &component_id,
+            get_component_data("shopping-cart"),
+            None,
+            None,
+            Some(0),
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        ComponentError::ConcurrentUpdate {
+            expected_version: 0,
+            current_version: 1,
+            ..
+        }
+    ));
+
+    let latest = component_service
+        .get_latest_version(&component_id, &DefaultNamespace::default())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(latest, component_v1);
+}
+
+async fn test_rename(component_service: Arc<dyn ComponentService<DefaultNamespace> + Sync + Send>) {
+    let component_id = ComponentId::new_v4();
+    component_service
+        .create(
+            &component_id,
+            &ComponentName("shopping-cart-rename".to_string()),
+            ComponentType::Durable,
+            get_component_data("shopping-cart"),
+            None,
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap();
+    component_service
+        .update(
+            &component_id,
+            get_component_data("shopping-cart"),
+            None,
+            None,
+            None,
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap();
+
+    let other_component_id = ComponentId::new_v4();
+    component_service
+        .create(
+            &other_component_id,
+            &ComponentName("shopping-cart-rename-taken".to_string()),
+            ComponentType::Durable,
+            get_component_data("shopping-cart"),
+            None,
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap();
+
+    let error = component_service
+        .rename(
+            &component_id,
+            &ComponentName("shopping-cart-rename-taken".to_string()),
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        ComponentError::ComponentNameAlreadyTaken { component_id: taken_by, .. } if taken_by == other_component_id
+    ));
+
+    let new_name = ComponentName("shopping-cart-renamed".to_string());
+    let renamed = component_service
+        .rename(&component_id, &new_name, &DefaultNamespace::default())
+        .await
+        .unwrap();
+    assert_eq!(renamed.component_name, new_name);
+    assert_eq!(renamed.versioned_component_id.version, 1);
+
+    let versions = component_service
+        .get(&component_id, &DefaultNamespace::default())
+        .await
+        .unwrap();
+    assert_eq!(versions.len(), 2);
+    assert!(versions.iter().all(|c| c.component_name == new_name));
+
+    let old_name_id = component_service
+        .find_id_by_name(
+            &ComponentName("shopping-cart-rename".to_string()),
+            &DefaultNamespace::default(),
+        )
+        .await
+        .unwrap();
+    assert!(old_name_id.is_none());
+}
+
 async fn test_initial_component_file_upload(
     component_service: Arc<dyn ComponentService<DefaultNamespace> + Sync + Send>,
 ) {
@@ -621,6 +758,7 @@ async fn test_initial_component_file_data_sharing(
                     permissions: ComponentFilePermissions::ReadWrite,
                 }],
             }),
+            None,
             &DefaultNamespace::default(),
         )
         .await
@@ -648,6 +786,8 @@ async fn test_repo(component_repo: Arc<dyn ComponentRepo + Sync + Send>) {
     test_repo_component_name_unique_in_namespace(component_repo.clone()).await;
     test_repo_component_delete(component_repo.clone()).await;
     test_repo_component_constraints(component_repo.clone()).await;
+    test_repo_component_tags(component_repo.clone()).await;
+    test_repo_component_create_next_version(component_repo.clone()).await;
 }
 
 async fn test_repo_component_id_unique(component_repo: Arc<dyn ComponentRepo + Sync + Send>) {
@@ -782,6 +922,99 @@ async fn test_repo_component_delete(component_repo: Arc<dyn ComponentRepo + Sync
     assert!(result4.unwrap().is_empty());
 }
 
+async fn test_repo_component_create_next_version(
+    component_repo: Arc<dyn ComponentRepo + Sync + Send>,
+) {
+    let namespace1 = Uuid::new_v4().to_string();
+
+    let component_name1 = ComponentName("shopping-cart1".to_string());
+    let data = get_component_data("shopping-cart");
+
+    let component1 = Component::new(
+        &ComponentId::new_v4(),
+        &component_name1,
+        ComponentType::Durable,
+        &data,
+        &namespace1,
+        vec![],
+    )
+    .unwrap();
+    let component1v2 = component1.clone().next_version();
+    let component1v3 = component1v2.clone().next_version();
+    let component_id = component1.versioned_component_id.component_id.0;
+
+    let result1 = component_repo
+        .create(&component1.clone().try_into().unwrap())
+        .await;
+    let result2 = component_repo
+        .create_next_version(&component1v2.clone().try_into().unwrap(), 0)
+        .await;
+    // A concurrent update of the same version, which has already been replaced
+    let result3 = component_repo
+        .create_next_version(&component1v2.clone().try_into().unwrap(), 0)
+        .await;
+    let result4 = component_repo
+        .create_next_version(&component1v3.clone().try_into().unwrap(), 0)
+        .await;
+    let result5 = component_repo.get_latest_version(&component_id).await;
+
+    let result6 = component_repo.delete(&namespace1, &component_id).await;
+
+    assert!(result1.is_ok());
+    assert!(result2.unwrap());
+    assert!(!result3.unwrap());
+    assert!(!result4.unwrap());
+    assert_eq!(result5.unwrap().map(|c| c.version), Some(1));
+    assert!(result6.is_ok());
+}
+
+async fn test_repo_component_tags(component_repo: Arc<dyn ComponentRepo + Sync + Send>) {
+    let namespace1 = Uuid::new_v4().to_string();
+
+    let component_name1 = ComponentName("shopping-cart1".to_string());
+    let data = get_component_data("shopping-cart");
+
+    let component1 = Component::new(
+        &ComponentId::new_v4(),
+        &component_name1,
+        ComponentType::Durable,
+        &data,
+        &namespace1,
+        vec![],
+    )
+    .unwrap();
+    let component1v2 = component1.clone().next_version();
+    let component_id = component1.versioned_component_id.component_id.0;
+
+    let result1 = component_repo
+        .create(&component1.clone().try_into().unwrap())
+        .await;
+    let result2 = component_repo
+        .create(&component1v2.clone().try_into().unwrap())
+        .await;
+
+    let result3 = component_repo.tag(&component_id, 0, "stable").await;
+    let result4 = component_repo.get_by_tag(&component_id, "stable").await;
+
+    let result5 = component_repo.tag(&component_id, 1, "stable").await;
+    let result6 = component_repo.get_by_tag(&component_id, "stable").await;
+
+    let result7 = component_repo.tag(&component_id, 2, "canary").await;
+    let result8 = component_repo.get_by_tag(&component_id, "canary").await;
+
+    let result9 = component_repo.delete(&namespace1, &component_id).await;
+
+    assert!(result1.is_ok());
+    assert!(result2.is_ok());
+    assert!(result3.is_ok());
+    assert_eq!(result4.unwrap().map(|c| c.version), Some(0));
+    assert!(result5.is_ok());
+    assert_eq!(result6.unwrap().map(|c| c.version), Some(1));
+    assert!(result7.is_err());
+    assert!(result8.unwrap().is_none());
+    assert!(result9.is_ok());
+}
+
 async fn test_repo_component_constraints(component_repo: Arc<dyn ComponentRepo + Sync + Send>) {
     let namespace1 = Uuid::new_v4().to_string();
 
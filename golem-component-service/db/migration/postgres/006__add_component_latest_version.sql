@@ -0,0 +1,8 @@
+ALTER TABLE components ADD COLUMN latest_version bigint NOT NULL DEFAULT 0;
+
+UPDATE components
+SET latest_version = (
+    SELECT COALESCE(MAX(version), 0)
+    FROM component_versions
+    WHERE component_versions.component_id = components.component_id
+);
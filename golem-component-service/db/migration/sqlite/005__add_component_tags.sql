@@ -0,0 +1,8 @@
+CREATE TABLE component_tags
+(
+    component_id uuid NOT NULL,
+    tag VARCHAR(255) NOT NULL,
+    version bigint NOT NULL,
+    PRIMARY KEY (component_id, tag),
+    FOREIGN KEY (component_id, version) REFERENCES component_versions (component_id, version)
+);
@@ -13,7 +13,9 @@
 // limitations under the License.
 
 use futures_util::TryStreamExt;
-use golem_common::model::{ComponentFilePathWithPermissionsList, ComponentId, ComponentType};
+use golem_common::model::{
+    ComponentFilePathWithPermissionsList, ComponentId, ComponentType, InitialComponentFileKey,
+};
 use golem_component_service_base::model::InitialComponentFilesArchiveAndPermissions;
 use golem_component_service_base::service::component::{
     ComponentError as ComponentServiceError, ComponentService,
@@ -82,6 +84,12 @@ pub struct UpdatePayload {
     files: Option<Upload>,
 }
 
+#[derive(Object, Debug, Clone)]
+#[oai(rename_all = "camelCase")]
+pub struct RenameComponentRequest {
+    pub name: ComponentName,
+}
+
 type Result<T> = std::result::Result<T, ComponentError>;
 
 impl From<ComponentServiceError> for ComponentError {
@@ -143,6 +151,21 @@ impl From<ComponentServiceError> for ComponentError {
                     error: error.to_safe_string(),
                 }))
             }
+            ComponentServiceError::InvalidComponentTag { .. } => {
+                ComponentError::BadRequest(Json(ErrorsBody {
+                    errors: vec![error.to_safe_string()],
+                }))
+            }
+            ComponentServiceError::ConcurrentUpdate { .. } => {
+                ComponentError::AlreadyExists(Json(ErrorBody {
+                    error: error.to_safe_string(),
+                }))
+            }
+            ComponentServiceError::ComponentNameAlreadyTaken { .. } => {
+                ComponentError::AlreadyExists(Json(ErrorBody {
+                    error: error.to_safe_string(),
+                }))
+            }
         }
     }
 }
@@ -240,6 +263,7 @@ impl ComponentApi {
                     data,
                     component_type.0,
                     None,
+                    None,
                     &DefaultNamespace::default(),
                 )
                 .instrument(record.span.clone())
@@ -251,6 +275,10 @@ impl ComponentApi {
     }
 
     /// Update a component
+    ///
+    /// When `expected-current-version` is set, the update is rejected with a conflict if the latest
+    /// version of the component is a different one, for example because it has been updated
+    /// concurrently.
     #[oai(
         path = "/:component_id/updates",
         method = "post",
@@ -259,6 +287,7 @@ impl ComponentApi {
     async fn update_component(
         &self,
         component_id: Path<ComponentId>,
+        #[oai(name = "expected-current-version")] expected_current_version: Query<Option<u64>>,
         payload: UpdatePayload,
     ) -> Result<Json<Component>> {
         let record = recorded_http_api_request!(
@@ -284,6 +313,7 @@ impl ComponentApi {
                     data,
                     payload.component_type,
                     files,
+                    expected_current_version.0,
                     &DefaultNamespace::default(),
                 )
                 .instrument(record.span.clone())
@@ -326,6 +356,46 @@ impl ComponentApi {
         record.result(response)
     }
 
+    /// Download an initial file of a component
+    ///
+    /// Downloads the content of one of the initial files of a specific version of the component, identified by its key.
+    #[oai(
+        path = "/:component_id/files/:key",
+        method = "get",
+        operation_id = "download_component_file"
+    )]
+    async fn download_component_file(
+        &self,
+        component_id: Path<ComponentId>,
+        key: Path<String>,
+        version: Query<Option<u64>>,
+    ) -> Result<Binary<Body>> {
+        let record = recorded_http_api_request!(
+            "download_component_file",
+            component_id = component_id.0.to_string(),
+            version = version.0.map(|v| v.to_string()),
+            key = key.0.clone()
+        );
+        let response = self
+            .component_service
+            .get_initial_component_file(
+                &component_id.0,
+                version.0,
+                &InitialComponentFileKey(key.0.clone()),
+                &DefaultNamespace::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .and_then(|content| match content {
+                Some(content) => Ok(Binary(Body::from_bytes(content))),
+                None => Err(ComponentError::NotFound(Json(ErrorBody {
+                    error: format!("Initial component file not found: {}", key.0),
+                }))),
+            });
+        record.result(response)
+    }
+
     /// Get the metadata for all component versions
     ///
     /// Each component can have multiple versions. Every time a new WASM is uploaded for a given component id, that creates a new version.
@@ -442,6 +512,123 @@ impl ComponentApi {
         record.result(response)
     }
 
+    /// Tag a version of a given component
+    ///
+    /// Points the tag to the given version of the component. If the tag already exists, it is moved to this version.
+    #[oai(
+        path = "/:component_id/versions/:version/tags/:tag",
+        method = "put",
+        operation_id = "tag_component_version"
+    )]
+    async fn tag_component_version(
+        &self,
+        #[oai(name = "component_id")] component_id: Path<ComponentId>,
+        #[oai(name = "version")] version: Path<String>,
+        #[oai(name = "tag")] tag: Path<String>,
+    ) -> Result<Json<Component>> {
+        let record = recorded_http_api_request!(
+            "tag_component_version",
+            component_id = component_id.0.to_string(),
+            version = version.0,
+            tag = tag.0,
+        );
+
+        let response = {
+            let version_int = version.0.parse::<u64>().map_err(|_| {
+                ComponentError::BadRequest(Json(ErrorsBody {
+                    errors: vec!["Invalid version".to_string()],
+                }))
+            })?;
+
+            let versioned_component_id = VersionedComponentId {
+                component_id: component_id.0,
+                version: version_int,
+            };
+
+            self.component_service
+                .tag(
+                    &versioned_component_id,
+                    &tag.0,
+                    &DefaultNamespace::default(),
+                )
+                .instrument(record.span.clone())
+                .await
+                .map_err(|e| e.into())
+                .map(|component| Json(component.into()))
+        };
+
+        record.result(response)
+    }
+
+    /// Get the version of a given component by tag
+    ///
+    /// Gets the version of a component the given tag points to.
+    #[oai(
+        path = "/:component_id/tags/:tag",
+        method = "get",
+        operation_id = "get_component_metadata_by_tag"
+    )]
+    async fn get_component_metadata_by_tag(
+        &self,
+        #[oai(name = "component_id")] component_id: Path<ComponentId>,
+        #[oai(name = "tag")] tag: Path<String>,
+    ) -> Result<Json<Component>> {
+        let record = recorded_http_api_request!(
+            "get_component_metadata_by_tag",
+            component_id = component_id.0.to_string(),
+            tag = tag.0,
+        );
+
+        let response = self
+            .component_service
+            .get_by_tag(&component_id.0, &tag.0, &DefaultNamespace::default())
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .and_then(|response| match response {
+                Some(component) => Ok(Json(component.into())),
+                None => Err(ComponentError::NotFound(Json(ErrorBody {
+                    error: "Component tag not found".to_string(),
+                }))),
+            });
+
+        record.result(response)
+    }
+
+    /// Rename a component
+    ///
+    /// Renames the component, keeping all of its versions and workers. Fails if the new name is already taken by another component.
+    #[oai(
+        path = "/:component_id/name",
+        method = "put",
+        operation_id = "rename_component"
+    )]
+    async fn rename_component(
+        &self,
+        #[oai(name = "component_id")] component_id: Path<ComponentId>,
+        request: Json<RenameComponentRequest>,
+    ) -> Result<Json<Component>> {
+        let record = recorded_http_api_request!(
+            "rename_component",
+            component_id = component_id.0.to_string(),
+            component_name = request.0.name.0,
+        );
+
+        let response = self
+            .component_service
+            .rename(
+                &component_id.0,
+                &request.0.name,
+                &DefaultNamespace::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|component| Json(component.into()));
+
+        record.result(response)
+    }
+
     /// Get all components
     ///
     /// Gets all components, optionally filtered by component name.
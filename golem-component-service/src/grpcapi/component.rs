@@ -14,7 +14,7 @@
 
 use std::pin::Pin;
 use std::sync::Arc;
-use tracing::Instrument;
+use tracing::{warn, Instrument};
 
 use futures_util::stream::BoxStream;
 use futures_util::StreamExt;
@@ -65,6 +65,21 @@ fn internal_error(error: &str) -> ComponentError {
     }
 }
 
+/// Size of the chunks a patch is sent in, as the WASM is sent in the chunks of the object store
+const PATCH_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// A bsdiff patch rebuilding `target` from `base`, if it is smaller than `target` itself
+fn component_patch(base: &[u8], target: &[u8]) -> Option<Vec<u8>> {
+    let mut patch = Vec::new();
+    match bsdiff::diff(base, target, &mut patch) {
+        Ok(()) => (patch.len() < target.len()).then_some(patch),
+        Err(err) => {
+            warn!("Failed to create a component patch: {err}");
+            None
+        }
+    }
+}
+
 pub struct ComponentGrpcApi {
     pub component_service: Arc<dyn component::ComponentService<DefaultNamespace> + Sync + Send>,
 }
@@ -141,11 +156,17 @@ impl ComponentGrpcApi {
         }
     }
 
+    /// Streams the WASM of the requested version, or a bsdiff patch against the base version of
+    /// the request if there is one and the patch is smaller. Returns the base version of the
+    /// patch if a patch is sent.
     async fn download(
         &self,
         request: DownloadComponentRequest,
     ) -> Result<
-        Pin<Box<dyn Stream<Item = Result<Vec<u8>, anyhow::Error>> + Send + Sync>>,
+        (
+            Pin<Box<dyn Stream<Item = Result<Vec<u8>, anyhow::Error>> + Send + Sync>>,
+            Option<u64>,
+        ),
         ComponentError,
     > {
         let id: ComponentId = request
@@ -153,11 +174,59 @@ impl ComponentGrpcApi {
             .and_then(|id| id.try_into().ok())
             .ok_or_else(|| bad_request_error("Missing component id"))?;
         let version = request.version;
+
+        if let (Some(version), Some(base_version)) = (version, request.base_version) {
+            if base_version < version {
+                if let Some(patch) = self.download_patch(&id, version, base_version).await? {
+                    let chunks = patch
+                        .chunks(PATCH_CHUNK_SIZE)
+                        .map(|chunk| Ok(chunk.to_vec()))
+                        .collect::<Vec<_>>();
+                    return Ok((Box::pin(tokio_stream::iter(chunks)), Some(base_version)));
+                }
+            }
+        }
+
         let result = self
             .component_service
             .download_stream(&id, version, &DefaultNamespace::default())
             .await?;
-        Ok(result)
+        Ok((result, None))
+    }
+
+    /// Creates a patch of `version` against `base_version`, or returns None if the full WASM
+    /// should be sent instead, because the base version can not be read or the patch is not
+    /// smaller
+    async fn download_patch(
+        &self,
+        id: &ComponentId,
+        version: u64,
+        base_version: u64,
+    ) -> Result<Option<Vec<u8>>, ComponentError> {
+        let namespace = DefaultNamespace::default();
+        let base = match self
+            .component_service
+            .download(id, Some(base_version), &namespace)
+            .await
+        {
+            Ok(base) => base,
+            Err(err) => {
+                warn!("Sending the full WASM of {id} version {version}, as base version {base_version} can not be read: {err}");
+                return Ok(None);
+            }
+        };
+        let target = self
+            .component_service
+            .download(id, Some(version), &namespace)
+            .await?;
+
+        match tokio::task::spawn_blocking(move || component_patch(&base, &target)).await {
+            Ok(patch) => Ok(patch),
+            Err(err) => {
+                warn!("Failed to create a patch of {id} version {version}: {err}");
+                Ok(None)
+            }
+        }
     }
 
     async fn create(
@@ -224,6 +293,7 @@ impl ComponentGrpcApi {
                 data,
                 component_type,
                 files,
+                request.expected_current_version,
                 &DefaultNamespace::default(),
             )
             .await?;
@@ -339,18 +409,22 @@ impl ComponentService for ComponentGrpcApi {
         );
         let stream: Self::DownloadComponentStream =
             match self.download(request).instrument(record.span.clone()).await {
-                Ok(response) => {
-                    let stream = response.map(|content| {
+                Ok((response, patch_base_version)) => {
+                    let stream = response.map(move |content| {
                         let res = match content {
                             Ok(content) => DownloadComponentResponse {
                                 result: Some(download_component_response::Result::SuccessChunk(
                                     content,
                                 )),
+                                signature: None,
+                                patch_base_version,
                             },
                             Err(_) => DownloadComponentResponse {
                                 result: Some(download_component_response::Result::Error(
                                     internal_error("Internal error"),
                                 )),
+                                signature: None,
+                                patch_base_version,
                             },
                         };
                         Ok(res)
@@ -361,6 +435,8 @@ impl ComponentService for ComponentGrpcApi {
                 Err(err) => {
                     let res = DownloadComponentResponse {
                         result: Some(download_component_response::Result::Error(err.clone())),
+                        signature: None,
+                        patch_base_version: None,
                     };
 
                     let stream: Self::DownloadComponentStream =
@@ -610,3 +686,24 @@ impl ComponentService for ComponentGrpcApi {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::component_patch;
+
+    #[test]
+    fn patches_are_only_used_when_smaller() {
+        let base = [b"a large common base shared by the versions".repeat(64)].concat();
+        let mut target = base.clone();
+        target.extend_from_slice(b" and a small change");
+
+        let patch = component_patch(&base, &target).unwrap();
+        let mut rebuilt = Vec::new();
+        bsdiff::patch(&base, &mut patch.as_slice(), &mut rebuilt).unwrap();
+        assert_eq!(rebuilt, target);
+
+        assert!(component_patch(b"unrelated", b"tiny").is_none());
+    }
+}
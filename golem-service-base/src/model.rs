@@ -373,7 +373,7 @@ impl TryFrom<golem_api_grpc::proto::golem::worker::v1::ComponentDownloadFailed>
                     .try_into()?,
                 version: value.component_version,
             },
-            reason: value.reason,
+            reason: reason_with_attempts(value.reason, value.attempts, &value.recent_errors),
         })
     }
 }
@@ -390,10 +390,26 @@ impl From<GolemErrorComponentDownloadFailed>
             component_id: Some(component_id),
             component_version,
             reason: value.reason,
+            attempts: 0,
+            recent_errors: Vec::new(),
         }
     }
 }
 
+// The REST API only has the reason of a failed component operation, so the attempts it made
+// are described at its end, the same way as by the worker executor
+fn reason_with_attempts(reason: String, attempts: u32, recent_errors: &[String]) -> String {
+    match attempts {
+        0 => reason,
+        1 => format!("{reason} (after 1 attempt)"),
+        attempts if recent_errors.is_empty() => format!("{reason} (after {attempts} attempts)"),
+        attempts => format!(
+            "{reason} (after {attempts} attempts, last errors: {})",
+            recent_errors.join("; ")
+        ),
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object, thiserror::Error)]
 #[serde(rename_all = "camelCase")]
 #[oai(rename_all = "camelCase")]
@@ -474,7 +490,7 @@ impl TryFrom<golem_api_grpc::proto::golem::worker::v1::GetLatestVersionOfCompone
                 .component_id
                 .ok_or("Missing field: component_id")?
                 .try_into()?,
-            reason: value.reason,
+            reason: reason_with_attempts(value.reason, value.attempts, &value.recent_errors),
         })
     }
 }
@@ -486,6 +502,8 @@ impl From<GolemErrorGetLatestVersionOfComponentFailed>
         Self {
             component_id: Some(value.component_id.into()),
             reason: value.reason,
+            attempts: 0,
+            recent_errors: Vec::new(),
         }
     }
 }
@@ -1022,6 +1040,58 @@ impl From<GolemErrorFileSystemError> for golem_api_grpc::proto::golem::worker::v
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object, thiserror::Error)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+#[error("Invalid signature of component {component_id}: {reason}")]
+pub struct GolemErrorComponentSignatureInvalid {
+    pub component_id: VersionedComponentId,
+    pub reason: String,
+}
+
+impl SafeDisplay for GolemErrorComponentSignatureInvalid {
+    fn to_safe_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::worker::v1::ComponentSignatureInvalid>
+    for GolemErrorComponentSignatureInvalid
+{
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::worker::v1::ComponentSignatureInvalid,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            component_id: VersionedComponentId {
+                component_id: value
+                    .component_id
+                    .ok_or("Missing field: component_id")?
+                    .try_into()?,
+                version: value.component_version,
+            },
+            reason: value.reason,
+        })
+    }
+}
+
+impl From<GolemErrorComponentSignatureInvalid>
+    for golem_api_grpc::proto::golem::worker::v1::ComponentSignatureInvalid
+{
+    fn from(value: GolemErrorComponentSignatureInvalid) -> Self {
+        let component_version = value.component_id.version;
+        let component_id = golem_api_grpc::proto::golem::component::ComponentId {
+            value: Some(value.component_id.component_id.0.into()),
+        };
+        Self {
+            component_id: Some(component_id),
+            component_version,
+            reason: value.reason,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object, thiserror::Error)]
 #[error("Invalid account")]
 pub struct GolemErrorShardingNotReady {}
@@ -1048,6 +1118,63 @@ impl From<crate::model::GolemErrorShardingNotReady>
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object, thiserror::Error)]
+#[error("Worker executor is shutting down")]
+pub struct GolemErrorShuttingDown {}
+
+impl SafeDisplay for GolemErrorShuttingDown {
+    fn to_safe_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl From<golem_api_grpc::proto::golem::worker::v1::ShuttingDown> for GolemErrorShuttingDown {
+    fn from(_value: golem_api_grpc::proto::golem::worker::v1::ShuttingDown) -> Self {
+        Self {}
+    }
+}
+
+impl From<GolemErrorShuttingDown> for golem_api_grpc::proto::golem::worker::v1::ShuttingDown {
+    fn from(_value: GolemErrorShuttingDown) -> Self {
+        Self {}
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object, thiserror::Error)]
+#[error("Initial file {path} is missing from the blob storage: {key}")]
+pub struct GolemErrorMissingInitialFile {
+    pub key: String,
+    pub path: String,
+}
+
+impl SafeDisplay for GolemErrorMissingInitialFile {
+    fn to_safe_string(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl From<golem_api_grpc::proto::golem::worker::v1::MissingInitialFile>
+    for GolemErrorMissingInitialFile
+{
+    fn from(value: golem_api_grpc::proto::golem::worker::v1::MissingInitialFile) -> Self {
+        Self {
+            key: value.key,
+            path: value.path,
+        }
+    }
+}
+
+impl From<GolemErrorMissingInitialFile>
+    for golem_api_grpc::proto::golem::worker::v1::MissingInitialFile
+{
+    fn from(value: GolemErrorMissingInitialFile) -> Self {
+        Self {
+            key: value.key,
+            path: value.path,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
 pub struct InvokeParameters {
     pub params: Vec<TypeAnnotatedValue>,
@@ -1215,6 +1342,46 @@ pub struct WorkersMetadataResponse {
     pub cursor: Option<ScanCursor>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct ComponentCompileStatusResponse {
+    pub executors: Vec<ExecutorCompileStatus>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct ExecutorCompileStatus {
+    pub executor: String,
+    // Not set if the component version was not compiled on the executor, or its result got
+    // evicted from the executor's caches
+    pub result: Option<ComponentCompileResult>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ComponentCompileResult {
+    pub success: bool,
+    pub error: Option<String>,
+    pub duration_millis: u64,
+}
+
+impl
+    From<golem_api_grpc::proto::golem::workerexecutor::v1::GetComponentCompileStatusSuccessResponse>
+    for ExecutorCompileStatus
+{
+    fn from(
+        value: golem_api_grpc::proto::golem::workerexecutor::v1::GetComponentCompileStatusSuccessResponse,
+    ) -> Self {
+        Self {
+            executor: value.executor,
+            result: value.result.map(|result| ComponentCompileResult {
+                success: result.error.is_none(),
+                error: result.error,
+                duration_millis: result.duration_millis,
+            }),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Object)]
 #[serde(rename_all = "camelCase")]
 #[oai(rename_all = "camelCase")]
@@ -1514,6 +1681,12 @@ pub enum GolemError {
     InitialComponentFileDownloadFailed(GolemErrorInitialComponentFileDownloadFailed),
     #[error(transparent)]
     FileSystemError(GolemErrorFileSystemError),
+    #[error(transparent)]
+    ComponentSignatureInvalid(GolemErrorComponentSignatureInvalid),
+    #[error(transparent)]
+    ShuttingDown(GolemErrorShuttingDown),
+    #[error(transparent)]
+    MissingInitialFile(GolemErrorMissingInitialFile),
 }
 
 impl SafeDisplay for GolemError {
@@ -1544,6 +1717,9 @@ impl SafeDisplay for GolemError {
             GolemError::ShardingNotReady(inner) => inner.to_safe_string(),
             GolemError::InitialComponentFileDownloadFailed(inner) => inner.to_safe_string(),
             GolemError::FileSystemError(inner) => inner.to_safe_string(),
+            GolemError::ComponentSignatureInvalid(inner) => inner.to_safe_string(),
+            GolemError::ShuttingDown(inner) => inner.to_safe_string(),
+            GolemError::MissingInitialFile(inner) => inner.to_safe_string(),
         }
     }
 }
@@ -1634,6 +1810,15 @@ impl TryFrom<golem_api_grpc::proto::golem::worker::v1::WorkerExecutionError> for
             Some(golem_api_grpc::proto::golem::worker::v1::worker_execution_error::Error::FileSystemError(err)) => {
                 Ok(GolemError::FileSystemError(err.into()))
             }
+            Some(golem_api_grpc::proto::golem::worker::v1::worker_execution_error::Error::ComponentSignatureInvalid(err)) => {
+                Ok(GolemError::ComponentSignatureInvalid(err.try_into()?))
+            }
+            Some(golem_api_grpc::proto::golem::worker::v1::worker_execution_error::Error::ShuttingDown(err)) => {
+                Ok(GolemError::ShuttingDown(err.into()))
+            }
+            Some(golem_api_grpc::proto::golem::worker::v1::worker_execution_error::Error::MissingInitialFile(err)) => {
+                Ok(GolemError::MissingInitialFile(err.into()))
+            }
             None => Err("Missing field: error".to_string()),
         }
     }
@@ -1725,6 +1910,15 @@ impl From<GolemError> for golem_api_grpc::proto::golem::worker::v1::worker_execu
             GolemError::FileSystemError(err) => {
                 golem_api_grpc::proto::golem::worker::v1::worker_execution_error::Error::FileSystemError(err.into())
             }
+            GolemError::ComponentSignatureInvalid(err) => {
+                golem_api_grpc::proto::golem::worker::v1::worker_execution_error::Error::ComponentSignatureInvalid(err.into())
+            }
+            GolemError::ShuttingDown(err) => {
+                golem_api_grpc::proto::golem::worker::v1::worker_execution_error::Error::ShuttingDown(err.into())
+            }
+            GolemError::MissingInitialFile(err) => {
+                golem_api_grpc::proto::golem::worker::v1::worker_execution_error::Error::MissingInitialFile(err.into())
+            }
         }
     }
 }
@@ -296,6 +296,7 @@ pub trait ComponentService {
                     component_type: Some(component_type as i32),
                     update_files,
                     files,
+                    expected_current_version: None,
                 },
             )),
         }];
@@ -1191,6 +1191,15 @@ pub fn worker_error_message(error: &Error) -> String {
                 worker_execution_error::Error::FileSystemError(error) => {
                     format!("File system error: {}", error.reason)
                 }
+                worker_execution_error::Error::ComponentSignatureInvalid(error) => {
+                    format!("Invalid component signature: {}", error.reason)
+                }
+                worker_execution_error::Error::ShuttingDown(_error) => {
+                    "Worker executor shutting down".to_string()
+                }
+                worker_execution_error::Error::MissingInitialFile(error) => {
+                    format!("Missing initial file: {}", error.path)
+                }
             },
         },
     }
@@ -1,5 +1,5 @@
 use cargo_metadata::MetadataCommand;
-use std::env::var_os;
+use std::env::{var, var_os};
 use std::path::Path;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -9,6 +9,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     std::fs::write(target_file.clone(), preview2_mod_gen(&golem_wit_root)).unwrap();
 
+    // Compiled components are only loadable on the target they were compiled for
+    println!("cargo:rustc-env=GOLEM_TARGET_TRIPLE={}", var("TARGET")?);
+
     println!("cargo:rerun-if-changed=build.rs");
     println!("cargo:rerun-if-changed=Cargo.toml");
 
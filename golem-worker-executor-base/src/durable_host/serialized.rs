@@ -666,9 +666,9 @@ mod tests {
             workerid_strat().prop_map(|worker_id| GolemError::WorkerNotFound { worker_id }),
             (workerid_strat(), ".*").prop_map(|(worker_id, details)| GolemError::WorkerCreationFailed { worker_id, details }),
             (workerid_strat(), ".*").prop_map(|(worker_id, reason)| GolemError::FailedToResumeWorker { worker_id, reason: Box::new(GolemError::unknown(reason)) }),
-            (componentid_strat(), any::<u64>(), ".*").prop_map(|(component_id, component_version, reason)| GolemError::ComponentDownloadFailed { component_id, component_version, reason }),
+            (componentid_strat(), any::<u64>(), ".*").prop_map(|(component_id, component_version, reason)| GolemError::component_download_failed(component_id, component_version, reason)),
             (componentid_strat(), any::<u64>(), ".*").prop_map(|(component_id, component_version, reason)| GolemError::ComponentParseFailed { component_id, component_version, reason }),
-            (componentid_strat(), ".*").prop_map(|(component_id, reason)| GolemError::GetLatestVersionOfComponentFailed { component_id, reason }),
+            (componentid_strat(), ".*").prop_map(|(component_id, reason)| GolemError::get_latest_version_of_component_failed(component_id, reason)),
             promiseid_strat().prop_map(|promise_id| GolemError::PromiseNotFound { promise_id }),
             promiseid_strat().prop_map(|promise_id| GolemError::PromiseDropped { promise_id }),
             promiseid_strat().prop_map(|promise_id| GolemError::PromiseAlreadyCompleted { promise_id }),
@@ -685,6 +685,9 @@ mod tests {
             Just(GolemError::PreviousInvocationExited),
             ".*".prop_map(|details| GolemError::Unknown { details }),
             (".*", ".*").prop_map(|(path, reason)| GolemError::InitialComponentFileDownloadFailed { path, reason }),
+            (componentid_strat(), any::<u64>(), ".*").prop_map(|(component_id, component_version, reason)| GolemError::ComponentSignatureInvalid { component_id, component_version, reason }),
+            Just(GolemError::ShuttingDown),
+            (".*", ".*").prop_map(|(key, path)| GolemError::MissingInitialFile { key, path }),
         }
     }
 
@@ -16,7 +16,10 @@ use std::collections::HashSet;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 
-use bincode::{Decode, Encode};
+use bincode::de::{BorrowDecoder, Decoder};
+use bincode::enc::Encoder;
+use bincode::error::{DecodeError, EncodeError};
+use bincode::{BorrowDecode, Decode, Encode};
 use golem_api_grpc::proto::golem;
 use golem_common::metrics::api::TraceErrorKind;
 use golem_common::model::{ComponentId, PromiseId, ShardId, WorkerId};
@@ -48,6 +51,7 @@ pub enum GolemError {
         component_id: ComponentId,
         component_version: u64,
         reason: String,
+        attempts: ComponentAttempts,
     },
     ComponentParseFailed {
         component_id: ComponentId,
@@ -57,6 +61,7 @@ pub enum GolemError {
     GetLatestVersionOfComponentFailed {
         component_id: ComponentId,
         reason: String,
+        attempts: ComponentAttempts,
     },
     PromiseNotFound {
         promise_id: PromiseId,
@@ -105,6 +110,16 @@ pub enum GolemError {
         path: String,
         reason: String,
     },
+    ComponentSignatureInvalid {
+        component_id: ComponentId,
+        component_version: u64,
+        reason: String,
+    },
+    ShuttingDown,
+    MissingInitialFile {
+        key: String,
+        path: String,
+    },
 }
 
 impl GolemError {
@@ -139,6 +154,78 @@ impl GolemError {
             component_id,
             component_version,
             reason: reason.into(),
+            attempts: ComponentAttempts::default(),
+        }
+    }
+
+    pub fn get_latest_version_of_component_failed(
+        component_id: ComponentId,
+        reason: impl Into<String>,
+    ) -> Self {
+        GolemError::GetLatestVersionOfComponentFailed {
+            component_id,
+            reason: reason.into(),
+            attempts: ComponentAttempts::default(),
+        }
+    }
+
+    /// Attaches the attempts made by a retried component operation to the errors reporting its
+    /// failure, leaving other errors unchanged
+    pub fn with_attempts(self, attempts: ComponentAttempts) -> Self {
+        match self {
+            GolemError::ComponentDownloadFailed {
+                component_id,
+                component_version,
+                reason,
+                ..
+            } => GolemError::ComponentDownloadFailed {
+                component_id,
+                component_version,
+                reason,
+                attempts,
+            },
+            GolemError::GetLatestVersionOfComponentFailed {
+                component_id,
+                reason,
+                ..
+            } => GolemError::GetLatestVersionOfComponentFailed {
+                component_id,
+                reason,
+                attempts,
+            },
+            other => other,
+        }
+    }
+
+    /// Reports a failed lookup of a component as a failed download of the given version, for
+    /// the lookups done as part of downloading it (such as resolving the manifest of the version
+    /// in an OCI registry), so that their timeouts and failures are not taken for a missing
+    /// latest version
+    pub fn as_download_failure(self, component_version: u64) -> Self {
+        match self {
+            GolemError::GetLatestVersionOfComponentFailed {
+                component_id,
+                reason,
+                attempts,
+            } => GolemError::ComponentDownloadFailed {
+                component_id,
+                component_version,
+                reason,
+                attempts,
+            },
+            other => other,
+        }
+    }
+
+    pub fn component_signature_invalid(
+        component_id: ComponentId,
+        component_version: u64,
+        reason: impl Into<String>,
+    ) -> Self {
+        GolemError::ComponentSignatureInvalid {
+            component_id,
+            component_version,
+            reason: reason.into(),
         }
     }
 
@@ -146,6 +233,13 @@ impl GolemError {
         GolemError::InitialComponentFileDownloadFailed { path, reason }
     }
 
+    pub fn missing_initial_file(key: impl Into<String>, path: impl Into<String>) -> Self {
+        GolemError::MissingInitialFile {
+            key: key.into(),
+            path: path.into(),
+        }
+    }
+
     pub fn invalid_request(details: impl Into<String>) -> Self {
         GolemError::InvalidRequest {
             details: details.into(),
@@ -201,10 +295,11 @@ impl Display for GolemError {
                 component_id,
                 component_version,
                 reason,
+                attempts,
             } => {
                 write!(
                     f,
-                    "Failed to download component: {component_id}#{component_version}: {reason}"
+                    "Failed to download component: {component_id}#{component_version}: {reason}{attempts}"
                 )
             }
             GolemError::ComponentParseFailed {
@@ -220,10 +315,11 @@ impl Display for GolemError {
             GolemError::GetLatestVersionOfComponentFailed {
                 component_id,
                 reason,
+                attempts,
             } => {
                 write!(
                     f,
-                    "Failed to get latest version of component {component_id}: {reason}"
+                    "Failed to get latest version of component {component_id}: {reason}{attempts}"
                 )
             }
             GolemError::InitialComponentFileDownloadFailed { path, reason } => {
@@ -286,6 +382,25 @@ impl Display for GolemError {
                     "Failed to access file in worker filesystem {path}: {reason}"
                 )
             }
+            GolemError::ComponentSignatureInvalid {
+                component_id,
+                component_version,
+                reason,
+            } => {
+                write!(
+                    f,
+                    "Invalid signature of component: {component_id}#{component_version}: {reason}"
+                )
+            }
+            GolemError::ShuttingDown => {
+                write!(f, "Worker executor is shutting down")
+            }
+            GolemError::MissingInitialFile { key, path } => {
+                write!(
+                    f,
+                    "Initial file {path} of the component is missing from the blob storage: {key}"
+                )
+            }
         }
     }
 }
@@ -322,6 +437,9 @@ impl Error for GolemError {
             GolemError::Unknown { .. } => "Unknown error",
             GolemError::ShardingNotReady => "Sharding not ready",
             GolemError::FileSystemError { .. } => "File system error",
+            GolemError::ComponentSignatureInvalid { .. } => "Invalid signature of component",
+            GolemError::ShuttingDown => "Worker executor is shutting down",
+            GolemError::MissingInitialFile { .. } => "Missing initial file",
         }
     }
 }
@@ -358,6 +476,9 @@ impl TraceErrorKind for GolemError {
             GolemError::Unknown { .. } => "Unknown",
             GolemError::ShardingNotReady => "ShardingNotReady",
             GolemError::FileSystemError { .. } => "FileSystemError",
+            GolemError::ComponentSignatureInvalid { .. } => "ComponentSignatureInvalid",
+            GolemError::ShuttingDown => "ShuttingDown",
+            GolemError::MissingInitialFile { .. } => "MissingInitialFile",
         }
     }
 }
@@ -460,6 +581,7 @@ impl From<GolemError> for golem::worker::v1::WorkerExecutionError {
                 component_id,
                 component_version,
                 reason,
+                attempts,
             } => golem::worker::v1::WorkerExecutionError {
                 error: Some(
                     golem::worker::v1::worker_execution_error::Error::ComponentDownloadFailed(
@@ -467,6 +589,8 @@ impl From<GolemError> for golem::worker::v1::WorkerExecutionError {
                             component_id: Some(component_id.into()),
                             component_version,
                             reason,
+                            attempts: attempts.attempts,
+                            recent_errors: attempts.recent_errors,
                         },
                     ),
                 ),
@@ -489,12 +613,15 @@ impl From<GolemError> for golem::worker::v1::WorkerExecutionError {
             GolemError::GetLatestVersionOfComponentFailed {
                 component_id,
                 reason,
+                attempts,
             } => golem::worker::v1::WorkerExecutionError {
                 error: Some(
                     golem::worker::v1::worker_execution_error::Error::GetLatestVersionOfComponentFailed(
                         golem::worker::v1::GetLatestVersionOfComponentFailed {
                             component_id: Some(component_id.into()),
                             reason,
+                            attempts: attempts.attempts,
+                            recent_errors: attempts.recent_errors,
                         },
                     ),
                 ),
@@ -635,6 +762,35 @@ impl From<GolemError> for golem::worker::v1::WorkerExecutionError {
                     ),
                 ),
             },
+            GolemError::ComponentSignatureInvalid {
+                component_id,
+                component_version,
+                reason,
+            } => golem::worker::v1::WorkerExecutionError {
+                error: Some(
+                    golem::worker::v1::worker_execution_error::Error::ComponentSignatureInvalid(
+                        golem::worker::v1::ComponentSignatureInvalid {
+                            component_id: Some(component_id.into()),
+                            component_version,
+                            reason,
+                        },
+                    ),
+                ),
+            },
+            GolemError::ShuttingDown => golem::worker::v1::WorkerExecutionError {
+                error: Some(
+                    golem::worker::v1::worker_execution_error::Error::ShuttingDown(
+                        golem::worker::v1::ShuttingDown {},
+                    ),
+                ),
+            },
+            GolemError::MissingInitialFile { key, path } => golem::worker::v1::WorkerExecutionError {
+                error: Some(
+                    golem::worker::v1::worker_execution_error::Error::MissingInitialFile(
+                        golem::worker::v1::MissingInitialFile { key, path },
+                    ),
+                ),
+            },
         }
     }
 }
@@ -695,6 +851,10 @@ impl TryFrom<golem::worker::v1::WorkerExecutionError> for GolemError {
                     .try_into()?,
                 component_version: component_download_failed.component_version,
                 reason: component_download_failed.reason,
+                attempts: ComponentAttempts {
+                    attempts: component_download_failed.attempts,
+                    recent_errors: component_download_failed.recent_errors,
+                },
             }),
             Some(golem::worker::v1::worker_execution_error::Error::ComponentParseFailed(
                 component_parse_failed,
@@ -716,6 +876,10 @@ impl TryFrom<golem::worker::v1::WorkerExecutionError> for GolemError {
                     .ok_or("Missing component_id")?
                     .try_into()?,
                 reason: get_latest_version_of_component_failed.reason,
+                attempts: ComponentAttempts {
+                    attempts: get_latest_version_of_component_failed.attempts,
+                    recent_errors: get_latest_version_of_component_failed.recent_errors,
+                },
             }),
             Some(golem::worker::v1::worker_execution_error::Error::PromiseNotFound(
                 promise_not_found,
@@ -815,6 +979,25 @@ impl TryFrom<golem::worker::v1::WorkerExecutionError> for GolemError {
                 path: file_system_error.path,
                 reason: file_system_error.reason,
             }),
+            Some(golem::worker::v1::worker_execution_error::Error::ComponentSignatureInvalid(
+                component_signature_invalid,
+            )) => Ok(GolemError::ComponentSignatureInvalid {
+                component_id: component_signature_invalid
+                    .component_id
+                    .ok_or("Missing component_id")?
+                    .try_into()?,
+                component_version: component_signature_invalid.component_version,
+                reason: component_signature_invalid.reason,
+            }),
+            Some(golem::worker::v1::worker_execution_error::Error::ShuttingDown(_)) => {
+                Ok(GolemError::ShuttingDown)
+            }
+            Some(golem::worker::v1::worker_execution_error::Error::MissingInitialFile(
+                missing_initial_file,
+            )) => Ok(GolemError::MissingInitialFile {
+                key: missing_initial_file.key,
+                path: missing_initial_file.path,
+            }),
         }
     }
 }
@@ -831,6 +1014,63 @@ impl From<EncodingError> for GolemError {
     }
 }
 
+/// The attempts a retried component operation made before it failed, with the errors of the last
+/// few of them. Only kept for reporting the failure: it is not encoded when the error is persisted
+/// in the oplog, and decodes as no attempts, so the binary format of `GolemError` is unchanged
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ComponentAttempts {
+    pub attempts: u32,
+    /// Oldest first, at most `MAX_RECENT_ERRORS`
+    pub recent_errors: Vec<String>,
+}
+
+impl ComponentAttempts {
+    pub const MAX_RECENT_ERRORS: usize = 3;
+
+    pub fn record_failure(&mut self, error: impl Display) {
+        self.attempts += 1;
+        if self.recent_errors.len() == Self::MAX_RECENT_ERRORS {
+            self.recent_errors.remove(0);
+        }
+        self.recent_errors.push(error.to_string());
+    }
+}
+
+// Written after the reason of the error, so it is empty if the attempts are unknown
+impl Display for ComponentAttempts {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.attempts {
+            0 => Ok(()),
+            1 => write!(f, " (after 1 attempt)"),
+            attempts => {
+                write!(f, " (after {attempts} attempts")?;
+                if !self.recent_errors.is_empty() {
+                    write!(f, ", last errors: {}", self.recent_errors.join("; "))?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+impl Encode for ComponentAttempts {
+    fn encode<E: Encoder>(&self, _encoder: &mut E) -> Result<(), EncodeError> {
+        Ok(())
+    }
+}
+
+impl Decode for ComponentAttempts {
+    fn decode<D: Decoder>(_decoder: &mut D) -> Result<Self, DecodeError> {
+        Ok(ComponentAttempts::default())
+    }
+}
+
+impl<'de> BorrowDecode<'de> for ComponentAttempts {
+    fn borrow_decode<D: BorrowDecoder<'de>>(_decoder: &mut D) -> Result<Self, DecodeError> {
+        Ok(ComponentAttempts::default())
+    }
+}
+
 #[derive(Debug, Clone, PartialOrd, PartialEq, Eq, Hash)]
 pub struct WorkerOutOfMemory;
 
@@ -841,3 +1081,96 @@ impl Display for WorkerOutOfMemory {
 }
 
 impl Error for WorkerOutOfMemory {}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use golem_common::model::ComponentId;
+    use golem_common::serialization::{deserialize, serialize};
+    use uuid::Uuid;
+
+    use crate::error::{ComponentAttempts, GolemError};
+
+    fn attempts(errors: &[&str]) -> ComponentAttempts {
+        let mut attempts = ComponentAttempts::default();
+        for error in errors {
+            attempts.record_failure(error);
+        }
+        attempts
+    }
+
+    #[test]
+    fn only_the_last_errors_are_kept() {
+        let attempts = attempts(&["a", "b", "c", "d", "e"]);
+
+        assert_eq!(attempts.attempts, 5);
+        assert_eq!(attempts.recent_errors, vec!["c", "d", "e"]);
+    }
+
+    #[test]
+    fn attempts_are_displayed_after_the_reason() {
+        let component_id = ComponentId(Uuid::nil());
+        let error = GolemError::component_download_failed(component_id.clone(), 1, "unavailable");
+
+        assert_eq!(
+            error.to_string(),
+            format!("Failed to download component: {component_id}#1: unavailable")
+        );
+        assert_eq!(
+            error
+                .clone()
+                .with_attempts(attempts(&["refused"]))
+                .to_string(),
+            format!(
+                "Failed to download component: {component_id}#1: unavailable (after 1 attempt)"
+            )
+        );
+        assert_eq!(
+            error
+                .with_attempts(attempts(&["refused", "timeout", "unavailable"]))
+                .to_string(),
+            format!(
+                "Failed to download component: {component_id}#1: unavailable (after 3 attempts, last errors: refused; timeout; unavailable)"
+            )
+        );
+    }
+
+    #[test]
+    fn attempts_are_not_persisted() {
+        let error = GolemError::get_latest_version_of_component_failed(
+            ComponentId(Uuid::new_v4()),
+            "timeout",
+        );
+        let with_attempts = error
+            .clone()
+            .with_attempts(attempts(&["timeout", "timeout"]));
+
+        let bytes = serialize(&with_attempts).unwrap();
+        assert_eq!(bytes, serialize(&error).unwrap());
+        assert_eq!(deserialize::<GolemError>(&bytes).unwrap(), error);
+    }
+
+    #[test]
+    fn failed_lookups_of_a_download_are_reported_as_download_failures() {
+        let component_id = ComponentId(Uuid::nil());
+        let error = GolemError::get_latest_version_of_component_failed(
+            component_id.clone(),
+            "Timed out after 10s of retrying",
+        )
+        .as_download_failure(3);
+
+        assert_eq!(
+            error,
+            GolemError::component_download_failed(
+                component_id,
+                3,
+                "Timed out after 10s of retrying"
+            )
+        );
+        assert_eq!(
+            GolemError::unknown("other").as_download_failure(3),
+            GolemError::unknown("other")
+        );
+    }
+}
@@ -21,12 +21,14 @@ use golem_api_grpc::proto::golem::common::ResourceLimits as GrpcResourceLimits;
 use golem_api_grpc::proto::golem::worker::{Cursor, ResourceMetadata, UpdateMode};
 use golem_api_grpc::proto::golem::workerexecutor::v1::worker_executor_server::WorkerExecutor;
 use golem_api_grpc::proto::golem::workerexecutor::v1::{
-    ConnectWorkerRequest, DeleteWorkerRequest, GetFileContentsRequest, GetFileContentsResponse,
-    GetOplogRequest, GetOplogResponse, GetRunningWorkersMetadataRequest,
-    GetRunningWorkersMetadataResponse, GetWorkersMetadataRequest, GetWorkersMetadataResponse,
-    InvokeAndAwaitWorkerRequest, InvokeAndAwaitWorkerResponseTyped, InvokeAndAwaitWorkerSuccess,
-    ListDirectoryRequest, ListDirectoryResponse, SearchOplogRequest, SearchOplogResponse,
-    UpdateWorkerRequest, UpdateWorkerResponse,
+    ConnectWorkerRequest, DeleteWorkerRequest, GetComponentCompileStatusRequest,
+    GetComponentCompileStatusResponse, GetComponentProtoRequest, GetComponentProtoResponse,
+    GetFileContentsRequest, GetFileContentsResponse, GetOplogRequest, GetOplogResponse,
+    GetRunningWorkersMetadataRequest, GetRunningWorkersMetadataResponse, GetWorkersMetadataRequest,
+    GetWorkersMetadataResponse, InvokeAndAwaitWorkerRequest, InvokeAndAwaitWorkerResponseTyped,
+    InvokeAndAwaitWorkerSuccess, ListDirectoryRequest, ListDirectoryResponse, SearchOplogRequest,
+    SearchOplogResponse, UpdateWorkerRequest, UpdateWorkerResponse, WarmComponentRequest,
+    WarmComponentResponse,
 };
 use golem_common::grpc::{
     proto_account_id_string, proto_component_id_string, proto_idempotency_key_string,
@@ -130,6 +132,12 @@ pub fn is_grpc_retriable<E>(error: &GrpcError<E>) -> bool {
     }
 }
 
+/// Same as `is_grpc_retriable`, but also retries the statuses with one of the `additional_codes`
+pub fn is_grpc_retriable_with<E>(error: &GrpcError<E>, additional_codes: &[tonic::Code]) -> bool {
+    is_grpc_retriable(error)
+        || matches!(error, GrpcError::Status(status) if additional_codes.contains(&status.code()))
+}
+
 /// This is the implementation of the Worker Executor gRPC API
 pub struct WorkerExecutorImpl<
     Ctx: WorkerCtx,
@@ -793,6 +801,75 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         Ok(result)
     }
 
+    async fn get_component_compile_status_internal(
+        &self,
+        request: GetComponentCompileStatusRequest,
+    ) -> Result<golem::workerexecutor::v1::GetComponentCompileStatusSuccessResponse, GolemError>
+    {
+        let component_id: ComponentId = request
+            .component_id
+            .and_then(|t| t.try_into().ok())
+            .ok_or(GolemError::invalid_request("Invalid component id"))?;
+
+        let result = self
+            .component_service()
+            .compile_result(&component_id, request.component_version)
+            .map(|result| golem::workerexecutor::v1::ComponentCompileResult {
+                error: result.error,
+                duration_millis: result.duration.as_millis() as u64,
+            });
+
+        Ok(
+            golem::workerexecutor::v1::GetComponentCompileStatusSuccessResponse {
+                executor: gethostname().to_string_lossy().to_string(),
+                result,
+            },
+        )
+    }
+
+    /// Loads the component version into the executor's caches, compiling it if needed, and
+    /// returns the result of its last compilation here
+    async fn warm_component_internal(
+        &self,
+        request: WarmComponentRequest,
+    ) -> Result<golem::workerexecutor::v1::GetComponentCompileStatusSuccessResponse, GolemError>
+    {
+        let component_id: ComponentId = request
+            .component_id
+            .clone()
+            .and_then(|t| t.try_into().ok())
+            .ok_or(GolemError::invalid_request("Invalid component id"))?;
+
+        self.component_service()
+            .ensure_cached(
+                &self.engine(),
+                &component_id,
+                request.component_version,
+                request.force_recompile,
+            )
+            .await?;
+
+        self.get_component_compile_status_internal(GetComponentCompileStatusRequest {
+            component_id: request.component_id,
+            component_version: request.component_version,
+        })
+        .await
+    }
+
+    async fn get_component_proto_internal(
+        &self,
+        request: GetComponentProtoRequest,
+    ) -> Result<golem::component::Component, GolemError> {
+        let component_id: ComponentId = request
+            .component_id
+            .and_then(|t| t.try_into().ok())
+            .ok_or(GolemError::invalid_request("Invalid component id"))?;
+
+        self.component_service()
+            .get_proto_component(&component_id, request.component_version)
+            .await
+    }
+
     async fn get_workers_metadata_internal(
         &self,
         request: GetWorkersMetadataRequest,
@@ -2135,6 +2212,113 @@ impl<Ctx: WorkerCtx, Svcs: HasAll<Ctx> + UsesAllDeps<Ctx = Ctx> + Send + Sync +
         };
         Ok(Response::new(stream))
     }
+
+    async fn get_component_compile_status(
+        &self,
+        request: Request<GetComponentCompileStatusRequest>,
+    ) -> Result<Response<GetComponentCompileStatusResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "get_component_compile_status",
+            component_id = proto_component_id_string(&request.component_id),
+            component_version = request.component_version,
+        );
+
+        let result = self
+            .get_component_compile_status_internal(request)
+            .instrument(record.span.clone())
+            .await;
+        match result {
+            Ok(status) => record.succeed(Ok(Response::new(GetComponentCompileStatusResponse {
+                result: Some(
+                    golem::workerexecutor::v1::get_component_compile_status_response::Result::Success(
+                        status,
+                    ),
+                ),
+            }))),
+            Err(err) => record.fail(
+                Ok(Response::new(GetComponentCompileStatusResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_component_compile_status_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                })),
+                &err,
+            ),
+        }
+    }
+
+    async fn get_component_proto(
+        &self,
+        request: Request<GetComponentProtoRequest>,
+    ) -> Result<Response<GetComponentProtoResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "get_component_proto",
+            component_id = proto_component_id_string(&request.component_id),
+            component_version = request.component_version,
+        );
+
+        let result = self
+            .get_component_proto_internal(request)
+            .instrument(record.span.clone())
+            .await;
+        match result {
+            Ok(component) => record.succeed(Ok(Response::new(GetComponentProtoResponse {
+                result: Some(
+                    golem::workerexecutor::v1::get_component_proto_response::Result::Success(
+                        component,
+                    ),
+                ),
+            }))),
+            Err(err) => record.fail(
+                Ok(Response::new(GetComponentProtoResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::get_component_proto_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                })),
+                &err,
+            ),
+        }
+    }
+
+    async fn warm_component(
+        &self,
+        request: Request<WarmComponentRequest>,
+    ) -> Result<Response<WarmComponentResponse>, Status> {
+        let request = request.into_inner();
+        let record = recorded_grpc_api_request!(
+            "warm_component",
+            component_id = proto_component_id_string(&request.component_id),
+            component_version = request.component_version,
+            force_recompile = request.force_recompile,
+        );
+
+        let result = self
+            .warm_component_internal(request)
+            .instrument(record.span.clone())
+            .await;
+        match result {
+            Ok(status) => record.succeed(Ok(Response::new(WarmComponentResponse {
+                result: Some(
+                    golem::workerexecutor::v1::warm_component_response::Result::Success(status),
+                ),
+            }))),
+            Err(err) => record.fail(
+                Ok(Response::new(WarmComponentResponse {
+                    result: Some(
+                        golem::workerexecutor::v1::warm_component_response::Result::Failure(
+                            err.clone().into(),
+                        ),
+                    ),
+                })),
+                &err,
+            ),
+        }
+    }
 }
 
 trait CanStartWorker {
@@ -14,15 +14,28 @@
 
 use std::fmt::Display;
 use std::net::SocketAddr;
+use std::sync::Arc;
 
+use bytes::Bytes;
+use golem_common::model::{ComponentId, ComponentType};
 use http_02::{Response, StatusCode};
 use prometheus::{Encoder, Registry, TextEncoder};
+use serde::{Deserialize, Serialize};
 use tokio::task::JoinHandle;
-use tracing::info;
+use tracing::{info, warn};
+use uuid::Uuid;
 use warp::hyper::Body;
 use warp::Filter;
 
-/// The worker executor's HTTP interface provides Prometheus metrics and a healthcheck endpoint
+use crate::error::GolemError;
+use crate::services::component::LocalComponentStore;
+
+/// The worker executor's HTTP interface provides Prometheus metrics and a healthcheck endpoint.
+///
+/// With a local file system component store, it also provides an admin endpoint for loading
+/// components directly into the store: `POST /admin/components/{component_id}` with the WASM as
+/// the body, and optionally `?componentType=Ephemeral`. The endpoint only accepts requests from
+/// the loopback interface, with a body of at most [MAX_LOADED_COMPONENT_SIZE] bytes.
 pub struct HttpServerImpl {
     handle: JoinHandle<()>,
 }
@@ -33,11 +46,38 @@ impl HttpServerImpl {
         registry: Registry,
         body_message: &'static str,
     ) -> HttpServerImpl {
-        let handle = tokio::spawn(server(addr, registry, body_message));
+        let handle = tokio::spawn(server(addr, registry, body_message, None));
+        HttpServerImpl { handle }
+    }
+
+    pub fn with_local_components(
+        addr: impl Into<SocketAddr> + Display + Send + 'static,
+        registry: Registry,
+        body_message: &'static str,
+        local_components: Arc<LocalComponentStore>,
+    ) -> HttpServerImpl {
+        let handle = tokio::spawn(server(addr, registry, body_message, Some(local_components)));
         HttpServerImpl { handle }
     }
 }
 
+/// The largest component accepted by the admin endpoint, the same as the default maximum component
+/// size of the gRPC component service
+pub const MAX_LOADED_COMPONENT_SIZE: u64 = 50 * 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LoadComponentQuery {
+    component_type: Option<ComponentType>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LoadComponentResponse {
+    component_id: ComponentId,
+    version: u64,
+}
+
 impl Drop for HttpServerImpl {
     fn drop(&mut self) {
         info!("Stopping Http server...");
@@ -49,6 +89,7 @@ async fn server(
     addr: impl Into<SocketAddr> + Display + Send,
     registry: Registry,
     body_message: &'static str,
+    local_components: Option<Arc<LocalComponentStore>>,
 ) {
     let healthcheck = warp::path!("healthcheck").map(move || {
         Response::builder()
@@ -59,8 +100,87 @@ async fn server(
 
     let metrics = warp::path!("metrics").map(move || prometheus_metrics(registry.clone()));
 
+    let load_component = warp::post()
+        .and(warp::path!("admin" / "components" / Uuid))
+        .and(warp::addr::remote())
+        .and(warp::query::<LoadComponentQuery>())
+        .and(warp::body::content_length_limit(MAX_LOADED_COMPONENT_SIZE))
+        .and(warp::body::bytes())
+        .then(
+            move |component_id: Uuid,
+                  remote: Option<SocketAddr>,
+                  query: LoadComponentQuery,
+                  body: Bytes| {
+                load_component(
+                    local_components.clone(),
+                    ComponentId(component_id),
+                    remote,
+                    query.component_type.unwrap_or(ComponentType::Durable),
+                    body,
+                )
+            },
+        );
+
     info!("Http server started on {addr}");
-    warp::serve(healthcheck.or(metrics)).run(addr).await;
+    warp::serve(healthcheck.or(metrics).or(load_component))
+        .run(addr)
+        .await;
+}
+
+async fn load_component(
+    local_components: Option<Arc<LocalComponentStore>>,
+    component_id: ComponentId,
+    remote: Option<SocketAddr>,
+    component_type: ComponentType,
+    body: Bytes,
+) -> Response<Body> {
+    if !remote.is_some_and(|remote| remote.ip().is_loopback()) {
+        return text_response(
+            StatusCode::FORBIDDEN,
+            "Components can only be loaded from the loopback interface".to_string(),
+        );
+    }
+
+    let Some(store) = local_components
+        .as_ref()
+        .and_then(|local_components| local_components.get())
+    else {
+        return text_response(
+            StatusCode::NOT_FOUND,
+            "Loading components is only supported with the local component service".to_string(),
+        );
+    };
+
+    match store
+        .store(&component_id, component_type, body.to_vec())
+        .await
+    {
+        Ok(version) => {
+            let response = LoadComponentResponse {
+                component_id,
+                version,
+            };
+            Response::builder()
+                .status(StatusCode::CREATED)
+                .header("Content-Type", "application/json")
+                .body(Body::from(serde_json::to_vec(&response).unwrap()))
+                .unwrap()
+        }
+        Err(err @ GolemError::ComponentParseFailed { .. }) => {
+            text_response(StatusCode::BAD_REQUEST, err.to_string())
+        }
+        Err(err) => {
+            warn!("Failed to load component {component_id}: {err}");
+            text_response(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+        }
+    }
+}
+
+fn text_response(status: StatusCode, body: String) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .body(Body::from(body))
+        .unwrap()
 }
 
 fn prometheus_metrics(registry: Registry) -> Response<Body> {
@@ -35,7 +35,7 @@ use crate::grpc::WorkerExecutorImpl;
 use crate::http_server::HttpServerImpl;
 use crate::services::active_workers::ActiveWorkers;
 use crate::services::blob_store::{BlobStoreService, DefaultBlobStoreService};
-use crate::services::component::ComponentService;
+use crate::services::component::{ComponentService, LocalComponentStore, MetadataTransformer};
 use crate::services::events::Events;
 use crate::services::golem_config::{GolemConfig, IndexedStorageConfig, KeyValueStorageConfig};
 use crate::services::key_value::{DefaultKeyValueService, KeyValueService};
@@ -86,7 +86,7 @@ use tonic::transport::Server;
 use tracing::info;
 use uuid::Uuid;
 use wasmtime::component::Linker;
-use wasmtime::{Config, Engine, WasmBacktraceDetails};
+use wasmtime::{Config, Engine};
 
 const VERSION: &str = golem_version!();
 
@@ -126,18 +126,15 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
         file_loader: Arc<FileLoader>,
     ) -> anyhow::Result<All<Ctx>>;
 
+    /// Can be overridden to enrich or normalize the metadata of components (for example by
+    /// adding environment specific tags) before it gets cached by the component service
+    fn create_component_metadata_transformer(&self) -> Option<MetadataTransformer> {
+        None
+    }
+
     /// Can be overridden to customize the wasmtime configuration
     fn create_wasmtime_config(&self) -> Config {
-        let mut config = Config::default();
-
-        config.wasm_multi_value(true);
-        config.async_support(true);
-        config.wasm_component_model(true);
-        config.epoch_interruption(true);
-        config.consume_fuel(true);
-        config.wasm_backtrace_details(WasmBacktraceDetails::Enable);
-
-        config
+        golem_common::engine::create_wasmtime_config()
     }
 
     /// This method is responsible for linking all the host function implementations the worker
@@ -152,6 +149,7 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
         runtime: Handle,
     ) -> anyhow::Result<()> {
         info!("Golem Worker Executor starting up...");
+        golem_config.validate()?;
 
         let total_system_memory = golem_config.memory.total_system_memory();
         let system_memory = golem_config.memory.system_memory();
@@ -172,10 +170,12 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
             .register_encoded_file_descriptor_set(proto::FILE_DESCRIPTOR_SET)
             .build()?;
 
-        let http_server = HttpServerImpl::new(
+        let local_components = Arc::new(LocalComponentStore::new());
+        let http_server = HttpServerImpl::with_local_components(
             golem_config.http_addr()?,
             prometheus_registry,
             "Worker executor is running",
+            local_components.clone(),
         );
 
         let (redis, sqlite, key_value_storage): (
@@ -305,6 +305,9 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
             &golem_config.component_cache,
             &golem_config.compiled_component_service,
             blob_storage.clone(),
+            self.create_component_metadata_transformer(),
+            &local_components,
+            file_loader.clone(),
         )
         .await;
 
@@ -425,7 +428,7 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
                 engine,
                 linker,
                 runtime.clone(),
-                component_service,
+                component_service.clone(),
                 shard_manager_service,
                 worker_service,
                 worker_enumeration_service,
@@ -454,15 +457,42 @@ pub trait Bootstrap<Ctx: WorkerCtx> {
             .send_compressed(CompressionEncoding::Gzip);
 
         info!("Starting gRPC server on port {}", addr.port());
-        Server::builder()
+        let server = Server::builder()
             .max_concurrent_streams(Some(golem_config.limits.max_concurrent_streams))
             .add_service(reflection_service)
             .add_service(service)
             .add_service(health_service)
-            .serve(addr)
-            .await?;
+            .serve(addr);
+
+        tokio::select! {
+            result = server => result?,
+            _ = shutdown_signal() => {
+                info!("Golem Worker Executor shutting down...");
+                component_service
+                    .shutdown(golem_config.component_cache.shutdown_grace_period)
+                    .await;
+            }
+        }
 
         drop(http_server); // explicitly keeping it alive until the end
         Ok(())
     }
 }
+
+/// Completes when the process is asked to stop, either by Ctrl+C or by SIGTERM
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        let mut terminate =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+                .expect("Failed to install the SIGTERM handler");
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {},
+            _ = terminate.recv() => {},
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
@@ -62,19 +62,108 @@ pub mod component {
     use lazy_static::lazy_static;
     use prometheus::*;
 
-    use golem_common::metrics::DEFAULT_TIME_BUCKETS;
+    use golem_common::metrics::{DEFAULT_SIZE_BUCKETS, DEFAULT_TIME_BUCKETS};
+
+    pub use golem_common::metrics::compilation::record_compilation_time;
 
     lazy_static! {
-        pub static ref COMPILATION_TIME_SECONDS: Histogram = register_histogram!(
-            "compilation_time_seconds",
-            "Time to compile a WASM component to native code",
+        static ref COMPONENT_DOWNLOAD_SECONDS: HistogramVec = register_histogram_vec!(
+            "component_download_seconds",
+            "Time to download a component WASM",
+            &["source"],
             DEFAULT_TIME_BUCKETS.to_vec()
         )
         .unwrap();
+        static ref COMPONENT_DOWNLOAD_SIZE_BYTES: HistogramVec = register_histogram_vec!(
+            "component_download_size_bytes",
+            "Size of downloaded component WASMs",
+            &["source"],
+            DEFAULT_SIZE_BUCKETS.to_vec()
+        )
+        .unwrap();
+        static ref COMPONENT_DOWNLOAD_THROUGHPUT_MB_PER_SECOND: GaugeVec = register_gauge_vec!(
+            "component_download_throughput_mb_per_second",
+            "Throughput of the last component WASM download",
+            &["source"]
+        )
+        .unwrap();
+        static ref COMPONENT_LOAD_TOTAL: CounterVec = register_counter_vec!(
+            "component_load_total",
+            "Number of components loaded, by whether they were served from the in-memory cache (memory_cache), taken from the compiled component store (compiled_store) or compiled (compiled)",
+            &["source", "result"]
+        )
+        .unwrap();
+        static ref COMPILED_COMPONENT_LOAD_FAILURES_TOTAL: CounterVec = register_counter_vec!(
+            "compiled_component_load_failures_total",
+            "Number of compiled components which could not be taken from the compiled component store, by whether they were incompatible or corrupted (incompatible) or the store was unreachable (store_unavailable)",
+            &["reason"]
+        )
+        .unwrap();
+        static ref COMPONENT_CACHE_ADMISSION_TOTAL: CounterVec = register_counter_vec!(
+            "component_cache_admission_total",
+            "Number of loaded components, by whether they were admitted to the main in-memory cache (main), to the pool of large components (large), or not cached in memory because of their size (bypass)",
+            &["pool"]
+        )
+        .unwrap();
+        static ref COMPONENT_METADATA_CACHE_ENTRIES: Gauge = register_gauge!(
+            "component_metadata_cache_entries",
+            "Number of component versions in the component metadata cache of the executor"
+        )
+        .unwrap();
+        static ref COMPONENT_METADATA_CACHE_COMPONENTS: Gauge = register_gauge!(
+            "component_metadata_cache_components",
+            "Number of components with at least one version in the component metadata cache of the executor"
+        )
+        .unwrap();
+        static ref COMPONENT_METADATA_CACHE_MAX_VERSIONS_PER_COMPONENT: Gauge = register_gauge!(
+            "component_metadata_cache_max_versions_per_component",
+            "Highest number of versions of a single component in the component metadata cache of the executor"
+        )
+        .unwrap();
+    }
+
+    pub fn record_component_load(source: &'static str, result: &'static str) {
+        COMPONENT_LOAD_TOTAL
+            .with_label_values(&[source, result])
+            .inc();
+    }
+
+    pub fn record_compiled_component_load_failure(reason: &'static str) {
+        COMPILED_COMPONENT_LOAD_FAILURES_TOTAL
+            .with_label_values(&[reason])
+            .inc();
+    }
+
+    pub fn record_component_cache_admission(pool: &'static str) {
+        COMPONENT_CACHE_ADMISSION_TOTAL
+            .with_label_values(&[pool])
+            .inc();
     }
 
-    pub fn record_compilation_time(duration: Duration) {
-        COMPILATION_TIME_SECONDS.observe(duration.as_secs_f64());
+    pub fn record_component_metadata_cache_stats(
+        entries: usize,
+        components: usize,
+        max_versions_per_component: usize,
+    ) {
+        COMPONENT_METADATA_CACHE_ENTRIES.set(entries as f64);
+        COMPONENT_METADATA_CACHE_COMPONENTS.set(components as f64);
+        COMPONENT_METADATA_CACHE_MAX_VERSIONS_PER_COMPONENT.set(max_versions_per_component as f64);
+    }
+
+    pub fn record_component_download(source: &'static str, size: usize, duration: Duration) {
+        COMPONENT_DOWNLOAD_SECONDS
+            .with_label_values(&[source])
+            .observe(duration.as_secs_f64());
+        COMPONENT_DOWNLOAD_SIZE_BYTES
+            .with_label_values(&[source])
+            .observe(size as f64);
+
+        let seconds = duration.as_secs_f64();
+        if seconds > 0.0 {
+            COMPONENT_DOWNLOAD_THROUGHPUT_MB_PER_SECOND
+                .with_label_values(&[source])
+                .set(size as f64 / 1_000_000.0 / seconds);
+        }
     }
 }
 
@@ -1721,6 +1721,7 @@ impl IntoValue for GolemError {
                     component_id,
                     component_version,
                     reason,
+                    ..
                 } => Value::Variant {
                     case_idx: 5,
                     case_value: Some(Box::new(Value::Record(vec![
@@ -1744,6 +1745,7 @@ impl IntoValue for GolemError {
                 GolemError::GetLatestVersionOfComponentFailed {
                     component_id,
                     reason,
+                    ..
                 } => Value::Variant {
                     case_idx: 7,
                     case_value: Some(Box::new(Value::Record(vec![
@@ -1834,6 +1836,29 @@ impl IntoValue for GolemError {
                         reason.into_value(),
                     ]))),
                 },
+                GolemError::ComponentSignatureInvalid {
+                    component_id,
+                    component_version,
+                    reason,
+                } => Value::Variant {
+                    case_idx: 25,
+                    case_value: Some(Box::new(Value::Record(vec![
+                        component_id.into_value(),
+                        component_version.into_value(),
+                        reason.into_value(),
+                    ]))),
+                },
+                GolemError::ShuttingDown => Value::Variant {
+                    case_idx: 26,
+                    case_value: None,
+                },
+                GolemError::MissingInitialFile { key, path } => Value::Variant {
+                    case_idx: 27,
+                    case_value: Some(Box::new(Value::Record(vec![
+                        key.into_value(),
+                        path.into_value(),
+                    ]))),
+                },
             }
         }
         into_value(self, true)
@@ -1931,6 +1956,23 @@ impl IntoValue for GolemError {
                     "InitialComponentFileDownloadFailed",
                     record(vec![field("path", str()), field("reason", str())]),
                 ),
+                case(
+                    "FileSystemError",
+                    record(vec![field("path", str()), field("reason", str())]),
+                ),
+                case(
+                    "ComponentSignatureInvalid",
+                    record(vec![
+                        field("component_id", ComponentId::get_type()),
+                        field("component_version", u64()),
+                        field("reason", str()),
+                    ]),
+                ),
+                unit_case("ShuttingDown"),
+                case(
+                    "MissingInitialFile",
+                    record(vec![field("key", str()), field("path", str())]),
+                ),
             ])
         }
         get_type(true)
@@ -12,30 +12,72 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use async_trait::async_trait;
 use tokio::time::Instant;
-use tracing::debug;
+use tracing::{debug, info, warn};
 use wasmtime::component::Component;
 
 use golem_common::model::ComponentId;
 
 use crate::error::GolemError;
-use crate::services::golem_config::CompiledComponentServiceConfig;
+use crate::services::golem_config::{
+    CompiledComponentServiceConfig, CompiledComponentServiceEnabledConfig,
+};
 use crate::Engine;
+use golem_service_base::storage::blob::fs::FileSystemBlobStorage;
 use golem_service_base::storage::blob::{BlobStorage, BlobStorageNamespace};
 
+/// The target triple this executor was built for, such as `x86_64-unknown-linux-gnu`
+pub const HOST_TARGET: &str = env!("GOLEM_TARGET_TRIPLE");
+
+/// Why a compiled component could not be taken from the store
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CompiledComponentError {
+    /// The stored compiled component could not be decompressed or deserialized, because it was
+    /// compiled by an incompatible engine or got corrupted. Compiling the component again
+    /// replaces it in the store.
+    #[error("Compiled component {component_id}#{component_version} is incompatible or corrupted: {reason}")]
+    Incompatible {
+        component_id: ComponentId,
+        component_version: u64,
+        reason: String,
+    },
+    /// The store could not be reached, the compiled component may or may not be there
+    #[error(
+        "Compiled component store is unavailable for {component_id}#{component_version}: {reason}"
+    )]
+    StoreUnavailable {
+        component_id: ComponentId,
+        component_version: u64,
+        reason: String,
+    },
+}
+
+impl CompiledComponentError {
+    /// Label of the error in the metrics
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CompiledComponentError::Incompatible { .. } => "incompatible",
+            CompiledComponentError::StoreUnavailable { .. } => "store_unavailable",
+        }
+    }
+}
+
 /// Service for storing compiled native binaries of WebAssembly components
 #[async_trait]
 pub trait CompiledComponentService {
+    /// Gets and deserializes a compiled component, None if it is not stored
     async fn get(
         &self,
         component_id: &ComponentId,
         component_version: u64,
         engine: &Engine,
-    ) -> Result<Option<Component>, GolemError>;
+    ) -> Result<Option<Component>, CompiledComponentError>;
     async fn put(
         &self,
         component_id: &ComponentId,
@@ -44,48 +86,115 @@ pub trait CompiledComponentService {
     ) -> Result<(), GolemError>;
 }
 
+/// Compiled components are stored per target triple, so executors running on different
+/// architectures can share a store without overwriting each other's native binaries.
+///
+/// Compressed compiled components are recognized by their zstd header, so they are read
+/// regardless of whether compression is currently enabled.
 pub struct DefaultCompiledComponentService {
     blob_storage: Arc<dyn BlobStorage + Send + Sync>,
+    circuit: DeserializationCircuit,
+    target: String,
+    compress: bool,
 }
 
 impl DefaultCompiledComponentService {
-    pub fn new(blob_storage: Arc<dyn BlobStorage + Send + Sync>) -> Self {
-        Self { blob_storage }
+    /// `store` names the store in the logs
+    pub fn new(
+        blob_storage: Arc<dyn BlobStorage + Send + Sync>,
+        store: impl Into<String>,
+        max_deserialization_failures: u32,
+        deserialization_failure_cooldown: Duration,
+    ) -> Self {
+        Self {
+            blob_storage,
+            circuit: DeserializationCircuit::new(
+                store.into(),
+                max_deserialization_failures,
+                deserialization_failure_cooldown,
+            ),
+            target: HOST_TARGET.to_string(),
+            compress: false,
+        }
     }
 
-    fn key(component_id: &ComponentId, component_version: u64) -> PathBuf {
-        Path::new(&component_id.to_string()).join(format!("{component_version}.cwasm"))
+    /// Compresses the compiled components when storing them, trading the time of decompressing
+    /// them on every load for disk space
+    pub fn with_compression(mut self, compress: bool) -> Self {
+        self.compress = compress;
+        self
     }
-}
 
-#[async_trait]
-impl CompiledComponentService for DefaultCompiledComponentService {
-    async fn get(
+    /// Overrides the target triple the compiled components are stored under, which is
+    /// the one of the running executor by default
+    pub fn with_target(mut self, target: impl Into<String>) -> Self {
+        self.target = target.into();
+        self
+    }
+
+    fn key(&self, component_id: &ComponentId, component_version: u64) -> PathBuf {
+        Path::new(&component_id.to_string())
+            .join(&self.target)
+            .join(format!("{component_version}.cwasm"))
+    }
+
+    async fn load(
         &self,
         component_id: &ComponentId,
         component_version: u64,
         engine: &Engine,
-    ) -> Result<Option<Component>, GolemError> {
+    ) -> Result<Option<Component>, CompiledComponentError> {
+        let incompatible = |reason: String| CompiledComponentError::Incompatible {
+            component_id: component_id.clone(),
+            component_version,
+            reason,
+        };
+
         match self
             .blob_storage
             .get_raw(
                 "compiled_component",
                 "get",
                 BlobStorageNamespace::CompilationCache,
-                &Self::key(component_id, component_version),
+                &self.key(component_id, component_version),
             )
             .await
         {
             Ok(None) => Ok(None),
             Ok(Some(bytes)) => {
+                let bytes = if is_compressed(&bytes) {
+                    let start = Instant::now();
+                    let compressed_size = bytes.len();
+                    // A failed decompression task says nothing about the stored bytes, so it
+                    // does not count towards skipping the store
+                    let bytes = tokio::task::spawn_blocking(move || zstd::decode_all(&*bytes))
+                        .await
+                        .map_err(|err| CompiledComponentError::StoreUnavailable {
+                            component_id: component_id.clone(),
+                            component_version,
+                            reason: format!(
+                                "Could not run the decompression of the compiled component: {err}"
+                            ),
+                        })?
+                        .map_err(|err| {
+                            incompatible(format!("Could not decompress compiled component: {err}"))
+                        })?;
+                    debug!(
+                        "Decompressed precompiled image for {} from {} to {} bytes in {}ms",
+                        component_id,
+                        compressed_size,
+                        bytes.len(),
+                        start.elapsed().as_millis(),
+                    );
+                    bytes.into()
+                } else {
+                    bytes
+                };
+
                 let start = Instant::now();
                 let component = unsafe {
                     Component::deserialize(engine, &bytes).map_err(|err| {
-                        GolemError::component_download_failed(
-                            component_id.clone(),
-                            component_version,
-                            format!("Could not deserialize compiled component: {}", err),
-                        )
+                        incompatible(format!("Could not deserialize compiled component: {err}"))
                     })?
                 };
                 let end = Instant::now();
@@ -99,13 +208,37 @@ impl CompiledComponentService for DefaultCompiledComponentService {
 
                 Ok(Some(component))
             }
-            Err(err) => Err(GolemError::component_download_failed(
-                component_id.clone(),
+            Err(err) => Err(CompiledComponentError::StoreUnavailable {
+                component_id: component_id.clone(),
                 component_version,
-                format!("Could not download compiled component: {err}"),
-            )),
+                reason: format!("Could not download compiled component: {err}"),
+            }),
         }
     }
+}
+
+#[async_trait]
+impl CompiledComponentService for DefaultCompiledComponentService {
+    async fn get(
+        &self,
+        component_id: &ComponentId,
+        component_version: u64,
+        engine: &Engine,
+    ) -> Result<Option<Component>, CompiledComponentError> {
+        if self.circuit.is_open() {
+            return Ok(None);
+        }
+
+        let result = self.load(component_id, component_version, engine).await;
+        // Only incompatible compiled components point to a corrupted store, an unreachable one is
+        // not skipped
+        match &result {
+            Ok(Some(_)) => self.circuit.record_success(),
+            Err(CompiledComponentError::Incompatible { .. }) => self.circuit.record_failure(),
+            Ok(None) | Err(CompiledComponentError::StoreUnavailable { .. }) => {}
+        }
+        result
+    }
 
     async fn put(
         &self,
@@ -113,15 +246,36 @@ impl CompiledComponentService for DefaultCompiledComponentService {
         component_version: u64,
         component: &Component,
     ) -> Result<(), GolemError> {
-        let bytes = component
+        let mut bytes = component
             .serialize()
             .expect("Could not serialize component");
+        if self.compress {
+            let uncompressed_size = bytes.len();
+            bytes = tokio::task::spawn_blocking(move || {
+                zstd::encode_all(&*bytes, COMPILED_COMPONENT_ZSTD_LEVEL)
+            })
+            .await
+            .map_err(|err| GolemError::unknown(err.to_string()))?
+            .map_err(|err| {
+                GolemError::component_download_failed(
+                    component_id.clone(),
+                    component_version,
+                    format!("Could not compress compiled component: {err}"),
+                )
+            })?;
+            debug!(
+                "Compressed precompiled image for {} from {} to {} bytes",
+                component_id,
+                uncompressed_size,
+                bytes.len(),
+            );
+        }
         self.blob_storage
             .put_raw(
                 "compiled_component",
                 "put",
                 BlobStorageNamespace::CompilationCache,
-                &Self::key(component_id, component_version),
+                &self.key(component_id, component_version),
                 &bytes,
             )
             .await
@@ -135,13 +289,172 @@ impl CompiledComponentService for DefaultCompiledComponentService {
     }
 }
 
-pub fn configured(
+const COMPILED_COMPONENT_ZSTD_LEVEL: i32 = 3;
+
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+fn is_compressed(bytes: &[u8]) -> bool {
+    bytes.starts_with(&ZSTD_MAGIC)
+}
+
+/// Skips a compiled component store for a cooldown period once a number of compiled components
+/// in a row failed to deserialize from it. Such a store is likely corrupted, and reading from it
+/// only delays the recompilation the components end up with anyway.
+struct DeserializationCircuit {
+    store: String,
+    max_failures: u32,
+    cooldown: Duration,
+    state: Mutex<CircuitState>,
+}
+
+#[derive(Default)]
+struct CircuitState {
+    consecutive_failures: u32,
+    skipped_until: Option<Instant>,
+}
+
+impl DeserializationCircuit {
+    fn new(store: String, max_failures: u32, cooldown: Duration) -> Self {
+        Self {
+            store,
+            max_failures,
+            cooldown,
+            state: Mutex::new(CircuitState::default()),
+        }
+    }
+
+    /// Whether the store is currently skipped. Re-enables the store once the cooldown is over.
+    fn is_open(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        match state.skipped_until {
+            Some(until) if Instant::now() < until => true,
+            Some(_) => {
+                state.skipped_until = None;
+                info!(
+                    "Re-enabling compiled component store {} after a cooldown of {:?}",
+                    self.store, self.cooldown
+                );
+                false
+            }
+            None => false,
+        }
+    }
+
+    fn record_success(&self) {
+        self.state.lock().unwrap().consecutive_failures = 0;
+    }
+
+    fn record_failure(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.consecutive_failures += 1;
+        if self.max_failures > 0 && state.consecutive_failures >= self.max_failures {
+            warn!(
+                "Skipping compiled component store {} for {:?}, after {} compiled components in a row failed to deserialize",
+                self.store, self.cooldown, state.consecutive_failures
+            );
+            state.consecutive_failures = 0;
+            state.skipped_until = Some(Instant::now() + self.cooldown);
+        }
+    }
+}
+
+/// Routes compiled components of selected components to their own store, and everything else
+/// to the default one
+pub struct RoutedCompiledComponentService {
+    default: Arc<dyn CompiledComponentService + Send + Sync>,
+    routes: HashMap<ComponentId, Arc<dyn CompiledComponentService + Send + Sync>>,
+}
+
+impl RoutedCompiledComponentService {
+    pub fn new(
+        default: Arc<dyn CompiledComponentService + Send + Sync>,
+        routes: HashMap<ComponentId, Arc<dyn CompiledComponentService + Send + Sync>>,
+    ) -> Self {
+        Self { default, routes }
+    }
+
+    fn backend(
+        &self,
+        component_id: &ComponentId,
+    ) -> &Arc<dyn CompiledComponentService + Send + Sync> {
+        self.routes.get(component_id).unwrap_or(&self.default)
+    }
+}
+
+#[async_trait]
+impl CompiledComponentService for RoutedCompiledComponentService {
+    async fn get(
+        &self,
+        component_id: &ComponentId,
+        component_version: u64,
+        engine: &Engine,
+    ) -> Result<Option<Component>, CompiledComponentError> {
+        self.backend(component_id)
+            .get(component_id, component_version, engine)
+            .await
+    }
+
+    async fn put(
+        &self,
+        component_id: &ComponentId,
+        component_version: u64,
+        component: &Component,
+    ) -> Result<(), GolemError> {
+        self.backend(component_id)
+            .put(component_id, component_version, component)
+            .await
+    }
+}
+
+pub async fn configured(
     config: &CompiledComponentServiceConfig,
     blob_storage: Arc<dyn BlobStorage + Send + Sync>,
 ) -> Arc<dyn CompiledComponentService + Send + Sync> {
+    let default_store = |config: &CompiledComponentServiceEnabledConfig| {
+        Arc::new(
+            DefaultCompiledComponentService::new(
+                blob_storage.clone(),
+                "blob storage",
+                config.max_deserialization_failures,
+                config.deserialization_failure_cooldown,
+            )
+            .with_compression(config.compress_compiled),
+        )
+    };
+
     match config {
-        CompiledComponentServiceConfig::Enabled(_) => {
-            Arc::new(DefaultCompiledComponentService::new(blob_storage))
+        CompiledComponentServiceConfig::Enabled(config) if config.overrides.is_empty() => {
+            default_store(config)
+        }
+        CompiledComponentServiceConfig::Enabled(config) => {
+            let mut routes: HashMap<ComponentId, Arc<dyn CompiledComponentService + Send + Sync>> =
+                HashMap::new();
+            for store_override in &config.overrides {
+                info!(
+                    "Using local file system at {:?} for compiled components of {} component(s)",
+                    store_override.root,
+                    store_override.component_ids.len()
+                );
+                let storage = FileSystemBlobStorage::new(&store_override.root)
+                    .await
+                    .expect("Failed to create file system blob storage");
+                let service: Arc<dyn CompiledComponentService + Send + Sync> = Arc::new(
+                    DefaultCompiledComponentService::new(
+                        Arc::new(storage),
+                        store_override.root.display().to_string(),
+                        config.max_deserialization_failures,
+                        config.deserialization_failure_cooldown,
+                    )
+                    .with_compression(config.compress_compiled),
+                );
+                for component_id in &store_override.component_ids {
+                    routes.insert(component_id.clone(), service.clone());
+                }
+            }
+            Arc::new(RoutedCompiledComponentService::new(
+                default_store(config),
+                routes,
+            ))
         }
         CompiledComponentServiceConfig::Disabled(_) => {
             Arc::new(CompiledComponentServiceDisabled::new())
@@ -170,7 +483,7 @@ impl CompiledComponentService for CompiledComponentServiceDisabled {
         _component_id: &ComponentId,
         _component_version: u64,
         _engine: &Engine,
-    ) -> Result<Option<Component>, GolemError> {
+    ) -> Result<Option<Component>, CompiledComponentError> {
         Ok(None)
     }
 
@@ -183,3 +496,193 @@ impl CompiledComponentService for CompiledComponentServiceDisabled {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::path::Path;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use golem_common::model::component_metadata::EMPTY_COMPONENT;
+    use golem_common::model::ComponentId;
+    use golem_service_base::storage::blob::memory::InMemoryBlobStorage;
+    use golem_service_base::storage::blob::{BlobStorage, BlobStorageNamespace};
+    use wasmtime::component::Component;
+    use wasmtime::Engine;
+
+    use super::{
+        is_compressed, CompiledComponentError, CompiledComponentService,
+        DefaultCompiledComponentService, DeserializationCircuit,
+    };
+
+    #[test]
+    async fn targets_do_not_overwrite_each_others_components() {
+        let blob_storage: Arc<dyn BlobStorage + Send + Sync> = Arc::new(InMemoryBlobStorage::new());
+        let store = |target: &str| {
+            DefaultCompiledComponentService::new(
+                blob_storage.clone(),
+                "test",
+                3,
+                Duration::from_secs(60),
+            )
+            .with_target(target)
+        };
+        let x86_64 = store("x86_64-unknown-linux-gnu");
+        let aarch64 = store("aarch64-unknown-linux-gnu");
+
+        let engine = Engine::default();
+        let component = Component::new(&engine, EMPTY_COMPONENT).unwrap();
+        let component_id = ComponentId::new_v4();
+
+        x86_64.put(&component_id, 1, &component).await.unwrap();
+        assert!(aarch64
+            .get(&component_id, 1, &engine)
+            .await
+            .unwrap()
+            .is_none());
+
+        aarch64.put(&component_id, 1, &component).await.unwrap();
+        for target in ["x86_64-unknown-linux-gnu", "aarch64-unknown-linux-gnu"] {
+            let key = Path::new(&component_id.to_string())
+                .join(target)
+                .join("1.cwasm");
+            let stored = blob_storage
+                .get_raw(
+                    "compiled_component",
+                    "get",
+                    BlobStorageNamespace::CompilationCache,
+                    &key,
+                )
+                .await
+                .unwrap();
+            assert!(
+                stored.is_some(),
+                "No compiled component stored for {target}"
+            );
+        }
+        assert!(x86_64
+            .get(&component_id, 1, &engine)
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    async fn compressed_components_are_read_regardless_of_the_setting() {
+        let blob_storage: Arc<dyn BlobStorage + Send + Sync> = Arc::new(InMemoryBlobStorage::new());
+        let store = |compress: bool| {
+            DefaultCompiledComponentService::new(
+                blob_storage.clone(),
+                "test",
+                3,
+                Duration::from_secs(60),
+            )
+            .with_compression(compress)
+        };
+        let compressed = store(true);
+        let uncompressed = store(false);
+
+        let engine = Engine::default();
+        let component = Component::new(&engine, EMPTY_COMPONENT).unwrap();
+        let component_id = ComponentId::new_v4();
+
+        compressed.put(&component_id, 1, &component).await.unwrap();
+        let stored = blob_storage
+            .get_raw(
+                "compiled_component",
+                "get",
+                BlobStorageNamespace::CompilationCache,
+                &compressed.key(&component_id, 1),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(is_compressed(&stored));
+
+        uncompressed
+            .put(&component_id, 2, &component)
+            .await
+            .unwrap();
+
+        for store in [&compressed, &uncompressed] {
+            for version in [1, 2] {
+                assert!(store
+                    .get(&component_id, version, &engine)
+                    .await
+                    .unwrap()
+                    .is_some());
+            }
+        }
+    }
+
+    #[test]
+    async fn corrupted_components_are_incompatible() {
+        let blob_storage: Arc<dyn BlobStorage + Send + Sync> = Arc::new(InMemoryBlobStorage::new());
+        let store = DefaultCompiledComponentService::new(
+            blob_storage.clone(),
+            "test",
+            2,
+            Duration::from_secs(60),
+        );
+
+        let engine = Engine::default();
+        let component_id = ComponentId::new_v4();
+        for version in [1, 2] {
+            blob_storage
+                .put_raw(
+                    "compiled_component",
+                    "put",
+                    BlobStorageNamespace::CompilationCache,
+                    &store.key(&component_id, version),
+                    b"not a compiled component",
+                )
+                .await
+                .unwrap();
+        }
+
+        let err = store.get(&component_id, 1, &engine).await.err().unwrap();
+        assert!(matches!(err, CompiledComponentError::Incompatible { .. }));
+        assert_eq!(err.kind(), "incompatible");
+
+        // Incompatible components count towards skipping the store
+        assert!(store.get(&component_id, 2, &engine).await.is_err());
+        assert!(store
+            .get(&component_id, 2, &engine)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn skips_store_after_consecutive_failures() {
+        let circuit = DeserializationCircuit::new("test".to_string(), 3, Duration::from_secs(60));
+
+        circuit.record_failure();
+        circuit.record_failure();
+        assert!(!circuit.is_open());
+
+        circuit.record_failure();
+        assert!(circuit.is_open());
+    }
+
+    #[test]
+    fn success_resets_failure_count() {
+        let circuit = DeserializationCircuit::new("test".to_string(), 2, Duration::from_secs(60));
+
+        circuit.record_failure();
+        circuit.record_success();
+        circuit.record_failure();
+        assert!(!circuit.is_open());
+    }
+
+    #[test]
+    fn re_enables_store_after_cooldown() {
+        let circuit = DeserializationCircuit::new("test".to_string(), 1, Duration::ZERO);
+
+        circuit.record_failure();
+        assert!(!circuit.is_open());
+        assert!(!circuit.is_open());
+    }
+}
@@ -12,41 +12,67 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::io::BufReader;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
 use std::time::{Duration, Instant};
 
-use crate::error::GolemError;
-use crate::grpc::{authorised_grpc_request, is_grpc_retriable, GrpcError, UriBackConversion};
-use crate::metrics::component::record_compilation_time;
+use crate::error::{ComponentAttempts, GolemError};
+use crate::grpc::{authorised_grpc_request, is_grpc_retriable_with, GrpcError, UriBackConversion};
+use crate::metrics::component::{
+    record_compilation_time, record_compiled_component_load_failure,
+    record_component_cache_admission, record_component_download, record_component_load,
+};
 use crate::services::compiled_component;
-use crate::services::compiled_component::CompiledComponentService;
+use crate::services::compiled_component::{CompiledComponentError, CompiledComponentService};
+use crate::services::component_metadata_cache;
+use crate::services::component_metadata_cache::ComponentMetadataCache;
+use crate::services::download_throttle::{throttle, DownloadThrottle};
+use crate::services::file_loader::{FileLoader, PrefetchedFiles};
 use crate::services::golem_config::{
-    CompiledComponentServiceConfig, ComponentCacheConfig, ComponentServiceConfig,
+    CompiledComponentServiceConfig, ComponentCacheConfig, ComponentServiceConfig, OciAuthConfig,
+    PublicKey,
 };
+use crate::services::oci::{OciError, OciManifest, OciRegistryClient};
 use async_trait::async_trait;
-use futures_util::TryStreamExt;
+use futures_util::stream::BoxStream;
+use futures_util::{Stream, StreamExt};
 use golem_api_grpc::proto::golem::component::v1::component_service_client::ComponentServiceClient;
 use golem_api_grpc::proto::golem::component::v1::{
-    download_component_response, get_component_metadata_response, ComponentError,
-    DownloadComponentRequest, GetLatestComponentRequest, GetVersionedComponentRequest,
+    download_component_response, get_component_metadata_all_versions_response,
+    get_component_metadata_response, ComponentError, DownloadComponentRequest, GetComponentRequest,
+    GetLatestComponentRequest, GetVersionedComponentRequest,
 };
 use golem_common::cache::{BackgroundEvictionMode, Cache, FullCacheEvictionMode, SimpleCache};
 use golem_common::client::{GrpcClient, GrpcClientConfig};
 use golem_common::config::RetryConfig;
 use golem_common::metrics::external_calls::record_external_call_response_size_bytes;
-use golem_common::model::component_metadata::LinearMemory;
-use golem_common::model::{ComponentId, ComponentType, ComponentVersion, InitialComponentFile};
+use golem_common::model::component_metadata::{
+    initial_files_from_custom_section, initial_files_from_custom_section_reader,
+    is_core_wasm_module, write_atomically, ComponentMetadata as AnalysedComponentMetadata,
+    LinearMemory, LocalComponentProps, RawComponentMetadata, CORE_WASM_MODULE_ERROR,
+};
+use golem_common::model::{
+    AccountId, ComponentId, ComponentType, ComponentVersion, InitialComponentFile,
+};
 use golem_common::retries::with_retries;
 use golem_service_base::storage::blob::BlobStorage;
 use golem_wasm_ast::analysis::AnalysedExport;
 use http::Uri;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use prost::Message;
+use ring::signature::{UnparsedPublicKey, ED25519};
 use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, watch, Notify, OwnedSemaphorePermit, Semaphore};
 use tokio::task::spawn_blocking;
 use tonic::codec::CompressionEncoding;
 use tonic::transport::Channel;
 use tracing::{debug, info, warn};
+use url::Url;
 use uuid::Uuid;
 use wasmtime::component::Component;
 use wasmtime::Engine;
@@ -60,6 +86,57 @@ pub struct ComponentMetadata {
     pub exports: Vec<AnalysedExport>,
     pub component_type: ComponentType,
     pub files: Vec<InitialComponentFile>,
+    /// The version this one is stored as a binary patch of, if it is not stored in full
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_version: Option<ComponentVersion>,
+    /// Arbitrary key/value annotations of the component, passed through from the component
+    /// service or the properties file of a local component
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+}
+
+impl From<LocalComponentProps> for ComponentMetadata {
+    fn from(props: LocalComponentProps) -> Self {
+        ComponentMetadata {
+            version: props.version,
+            size: props.size,
+            memories: props.memories,
+            exports: props.exports,
+            component_type: props.component_type,
+            files: props.files,
+            base_version: props.base_version,
+            annotations: props.annotations,
+        }
+    }
+}
+
+/// Transforms the metadata of components before it gets cached, allowing deployments to
+/// enrich or normalize it
+pub type MetadataTransformer = Arc<dyn Fn(ComponentMetadata) -> ComponentMetadata + Send + Sync>;
+
+/// Transforms the loaded metadata of a component before it gets cached. With
+/// `reject_empty_exports` set, components without any exports are rejected, as these are usually
+/// broken builds rather than components only running side effects
+fn prepare_metadata(
+    metadata_transformer: &Option<MetadataTransformer>,
+    reject_empty_exports: bool,
+    component_id: &ComponentId,
+    metadata: ComponentMetadata,
+) -> Result<ComponentMetadata, GolemError> {
+    let metadata = match metadata_transformer {
+        Some(metadata_transformer) => metadata_transformer(metadata),
+        None => metadata,
+    };
+
+    if reject_empty_exports && metadata.exports.is_empty() {
+        Err(GolemError::ComponentParseFailed {
+            component_id: component_id.clone(),
+            component_version: metadata.version,
+            reason: "The component has no exports, and reject_empty_exports is enabled".to_string(),
+        })
+    } else {
+        Ok(metadata)
+    }
 }
 
 /// Service for downloading a specific Golem component from the Golem Component API
@@ -72,20 +149,202 @@ pub trait ComponentService {
         component_version: ComponentVersion,
     ) -> Result<(Component, ComponentMetadata), GolemError>;
 
+    /// Gets the version of a component whose WASM has the given SHA-256 hash (hex encoded,
+    /// optionally prefixed with `sha256:`), for deployments pinned to the content of a component
+    /// instead of a mutable version number. Fails if no version of the component matches.
+    ///
+    /// Finding the version may require downloading every version to hash it, so the version
+    /// found for each hash is cached.
+    async fn get_by_hash(
+        &self,
+        engine: &Engine,
+        component_id: &ComponentId,
+        sha256: &str,
+    ) -> Result<(Component, ComponentMetadata), GolemError>;
+
+    /// Downloads, compiles and caches the component the same way as `get`, without returning
+    /// it or its metadata, such as when warming up the executor.
+    ///
+    /// With `force_recompile`, neither the in-memory cache nor the compiled component store is
+    /// used: the component is compiled from its WASM, and the compiled artifact in the store gets
+    /// overwritten. This replaces an artifact suspected to be corrupt.
+    async fn ensure_cached(
+        &self,
+        engine: &Engine,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+        force_recompile: bool,
+    ) -> Result<(), GolemError>;
+
+    /// Gets the metadata of a component version, or of the latest version if none is forced.
+    /// Never compiles the component, so callers which only need the metadata must use this
+    /// instead of `get`, which pays the cost of the compilation on a cache miss.
     async fn get_metadata(
         &self,
         component_id: &ComponentId,
         forced_version: Option<ComponentVersion>,
     ) -> Result<ComponentMetadata, GolemError>;
+
+    /// The full analysis of a component version, with its producers and memories in terms of
+    /// the WASM AST. Only the local file system source analyses the component itself. The other
+    /// sources only have the metadata reported by the component service or registry, so they
+    /// return a subset of it: the exports, without any producers, memories or warnings.
+    async fn get_raw_metadata(
+        &self,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+    ) -> Result<RawComponentMetadata, GolemError> {
+        let metadata = self
+            .get_metadata(component_id, Some(component_version))
+            .await?;
+        Ok(RawComponentMetadata {
+            exports: metadata.exports,
+            producers: vec![],
+            memories: vec![],
+            warnings: vec![],
+        })
+    }
+
+    /// The component version as the `Component` message of the component service, for consumers
+    /// which need more than the `ComponentMetadata` projection of it. The gRPC source returns the
+    /// message of the component service as is. The other sources synthesize it from the metadata,
+    /// without the name, project, creation time and producers of the component.
+    async fn get_proto_component(
+        &self,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+    ) -> Result<golem_api_grpc::proto::golem::component::Component, GolemError> {
+        let metadata = self
+            .get_metadata(component_id, Some(component_version))
+            .await?;
+        Ok(proto_component_from_metadata(component_id, metadata))
+    }
+
+    /// Whether `version` is the latest version of the component. The latest version is resolved
+    /// through the latest version cache when enabled, so a newly published version may only be
+    /// seen after the cache entry expires.
+    async fn is_latest(
+        &self,
+        component_id: &ComponentId,
+        version: ComponentVersion,
+    ) -> Result<bool, GolemError>;
+
+    /// Downloads the initial files of a component version into the executor's file cache, a
+    /// bounded number at a time, so that a starting worker finds them there instead of waiting
+    /// for each download. The files stay cached while the returned `PrefetchedFiles` is kept.
+    ///
+    /// The files are stored per account, so the account of the worker is needed to find them.
+    async fn prefetch_files(
+        &self,
+        account_id: &AccountId,
+        metadata: &ComponentMetadata,
+    ) -> Result<PrefetchedFiles, GolemError>;
+
+    /// Stops accepting new `get` calls, which fail with `GolemError::ShuttingDown` from now on,
+    /// and waits at most `grace_period` for the in-flight downloads and compilations to finish
+    /// and store their compiled component. A load only counts as finished once the `put` of
+    /// its compiled component returned, as there is no separate flush-to-disk step to run after
+    async fn shutdown(&self, grace_period: Duration);
+
+    /// The result of the last compilation of the component version on this executor, or `None`
+    /// if it was not compiled here since it got evicted from the caches
+    fn compile_result(
+        &self,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+    ) -> Option<ComponentCompileResult>;
+
+    /// Watches the latest version of a component, by looking it up every `poll_interval`
+    /// bypassing the latest version cache. Yields the metadata of the current latest version
+    /// first, then of every new latest version. Failed lookups are yielded as errors, and the
+    /// watch goes on with the next poll. The local file system backend looks it up whenever the
+    /// files of the component change instead, and only polls if they cannot be watched.
+    fn watch_metadata<'a>(
+        &'a self,
+        component_id: &'a ComponentId,
+        poll_interval: Duration,
+    ) -> BoxStream<'a, Result<ComponentMetadata, GolemError>>;
+}
+
+/// Tracks the component loads in progress, so that on shutdown new loads get rejected while
+/// the in-flight ones can still finish
+pub struct ComponentLoadTracker {
+    shutting_down: AtomicBool,
+    in_flight: watch::Sender<usize>,
+}
+
+impl ComponentLoadTracker {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            shutting_down: AtomicBool::new(false),
+            in_flight: watch::Sender::new(0),
+        })
+    }
+
+    /// Registers a new component load, which is in flight until the returned guard is dropped
+    pub fn start(self: &Arc<Self>) -> Result<InFlightComponentLoad, GolemError> {
+        // Registering before checking the flag guarantees that `shutdown` either waits for
+        // this load, or the load observes the flag and gets rejected
+        self.in_flight.send_modify(|count| *count += 1);
+        let in_flight = InFlightComponentLoad {
+            tracker: self.clone(),
+        };
+        if self.shutting_down.load(Ordering::SeqCst) {
+            Err(GolemError::ShuttingDown)
+        } else {
+            Ok(in_flight)
+        }
+    }
+
+    /// Rejects new loads and waits at most `grace_period` for the in-flight ones. Returns
+    /// whether all of them finished in time.
+    pub async fn shutdown(&self, grace_period: Duration) -> bool {
+        self.shutting_down.store(true, Ordering::SeqCst);
+        let mut in_flight = self.in_flight.subscribe();
+        let drained = tokio::time::timeout(grace_period, in_flight.wait_for(|count| *count == 0))
+            .await
+            .is_ok();
+
+        if drained {
+            info!("All in-flight component loads finished");
+        } else {
+            warn!(
+                "{} component loads did not finish within {:?}, abandoning them",
+                *self.in_flight.borrow(),
+                grace_period
+            );
+        }
+        drained
+    }
+}
+
+pub struct InFlightComponentLoad {
+    tracker: Arc<ComponentLoadTracker>,
 }
 
+impl Drop for InFlightComponentLoad {
+    fn drop(&mut self) {
+        self.tracker.in_flight.send_modify(|count| *count -= 1);
+    }
+}
+
+/// Set to the local file system component service once it is configured, giving the executor's
+/// HTTP interface access to it for loading components directly into the local store
+pub type LocalComponentStore = OnceLock<Arc<ComponentServiceLocalFileSystem>>;
+
 pub async fn configured(
     config: &ComponentServiceConfig,
     cache_config: &ComponentCacheConfig,
     compiled_config: &CompiledComponentServiceConfig,
     blob_storage: Arc<dyn BlobStorage + Send + Sync>,
+    metadata_transformer: Option<MetadataTransformer>,
+    local_store: &LocalComponentStore,
+    file_loader: Arc<FileLoader>,
 ) -> Arc<dyn ComponentService + Send + Sync> {
-    let compiled_component_service = compiled_component::configured(compiled_config, blob_storage);
+    let compiled_component_service =
+        compiled_component::configured(compiled_config, blob_storage).await;
+    let cache_options = ComponentCacheOptions::from(cache_config);
+    let metadata_cache = component_metadata_cache::configured(cache_config).await;
     match config {
         ComponentServiceConfig::Grpc(config) => {
             info!("Using component API at {}", config.url());
@@ -95,24 +354,113 @@ pub async fn configured(
                     .access_token
                     .parse::<Uuid>()
                     .expect("Access token must be an UUID"),
-                cache_config.max_capacity,
-                cache_config.max_metadata_capacity,
-                cache_config.time_to_idle,
-                config.retries.clone(),
+                cache_options,
+                metadata_cache.clone(),
+                ComponentDownloadOptions {
+                    retry_config: config.retries.clone(),
+                    download_throttle: DownloadThrottle::configured(
+                        config.max_download_bytes_per_second,
+                    ),
+                    verification_key: config.verification_key.clone(),
+                },
+                config.max_elapsed,
                 compiled_component_service,
                 config.max_component_size,
+                config
+                    .retriable_status_codes
+                    .iter()
+                    .map(|code| code.0)
+                    .collect(),
+                config.lenient_metadata,
+                config.verify_memories,
+                metadata_transformer,
+                file_loader.clone(),
             ))
         }
-        ComponentServiceConfig::Local(config) => Arc::new(ComponentServiceLocalFileSystem::new(
-            &config.root,
-            cache_config.max_capacity,
-            cache_config.max_metadata_capacity,
-            cache_config.time_to_idle,
-            compiled_component_service,
-        )),
+        ComponentServiceConfig::Local(config) => {
+            let service = Arc::new(ComponentServiceLocalFileSystem::new(
+                &config.root,
+                config.props_root.as_deref(),
+                cache_options,
+                metadata_cache.clone(),
+                compiled_component_service,
+                config.verification_key.clone(),
+                metadata_transformer,
+                file_loader.clone(),
+                config.recover_missing_props,
+            ));
+            let _ = local_store.set(service.clone());
+            service
+        }
+        ComponentServiceConfig::Oci(config) => {
+            info!("Using OCI registry at {} for components", config.registry);
+            Arc::new(ComponentServiceOci::new(
+                config.registry.clone(),
+                &config.repository,
+                config.auth.clone(),
+                ComponentDownloadOptions {
+                    retry_config: config.retries.clone(),
+                    download_throttle: DownloadThrottle::configured(
+                        config.max_download_bytes_per_second,
+                    ),
+                    verification_key: config.verification_key.clone(),
+                },
+                config.max_elapsed,
+                config.max_component_size,
+                cache_options,
+                metadata_cache.clone(),
+                compiled_component_service,
+                metadata_transformer,
+                file_loader.clone(),
+            ))
+        }
+    }
+}
+
+/// The caching and compilation settings of a component service, taken from its
+/// `ComponentCacheConfig`
+#[derive(Clone, Debug)]
+pub struct ComponentCacheOptions {
+    pub max_capacity: usize,
+    pub max_metadata_capacity: usize,
+    pub time_to_idle: Duration,
+    pub latest_version_ttl: Option<Duration>,
+    pub latest_version_soft_ttl: Option<Duration>,
+    pub max_concurrent_compiles: usize,
+    pub max_concurrent_file_prefetches: usize,
+    pub reject_empty_exports: bool,
+    pub large_component_threshold: Option<u64>,
+    pub large_component_capacity: usize,
+    pub max_downloaded_bytes_cache_size: Option<u64>,
+}
+
+impl From<&ComponentCacheConfig> for ComponentCacheOptions {
+    fn from(config: &ComponentCacheConfig) -> Self {
+        Self {
+            max_capacity: config.max_capacity,
+            max_metadata_capacity: config.max_metadata_capacity,
+            time_to_idle: config.time_to_idle,
+            latest_version_ttl: config.latest_version_ttl,
+            latest_version_soft_ttl: config.latest_version_soft_ttl,
+            max_concurrent_compiles: config.concurrent_compile_limit(),
+            max_concurrent_file_prefetches: config.max_concurrent_file_prefetches,
+            reject_empty_exports: config.reject_empty_exports,
+            large_component_threshold: config.large_component_threshold,
+            large_component_capacity: config.large_component_capacity,
+            max_downloaded_bytes_cache_size: config.max_downloaded_bytes_cache_size,
+        }
     }
 }
 
+/// How a component service downloads components from a remote source
+#[derive(Clone)]
+pub struct ComponentDownloadOptions {
+    pub retry_config: RetryConfig,
+    pub download_throttle: Option<Arc<DownloadThrottle>>,
+    /// When set, the downloaded components must have a valid signature made with this key
+    pub verification_key: Option<PublicKey>,
+}
+
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 struct ComponentKey {
     component_id: ComponentId,
@@ -120,34 +468,76 @@ struct ComponentKey {
 }
 
 pub struct ComponentServiceGrpc {
-    component_cache: Cache<ComponentKey, (), Component, GolemError>,
-    component_metadata_cache: Cache<ComponentKey, (), ComponentMetadata, GolemError>,
+    component_cache: ComponentCache,
+    downloaded_bytes_cache: Option<Arc<DownloadedBytesCache>>,
+    component_metadata_cache: Option<Arc<dyn ComponentMetadataCache + Send + Sync>>,
+    latest_version_cache: Option<LatestVersionCache>,
     access_token: Uuid,
     retry_config: RetryConfig,
+    max_elapsed: Option<Duration>,
     compiled_component_service: Arc<dyn CompiledComponentService + Send + Sync>,
+    verification_key: Option<PublicKey>,
+    retriable_status_codes: Arc<[tonic::Code]>,
+    lenient_metadata: bool,
+    verify_memories: bool,
+    download_throttle: Option<Arc<DownloadThrottle>>,
+    metadata_transformer: Option<MetadataTransformer>,
     client: GrpcClient<ComponentServiceClient<Channel>>,
+    loads: Arc<ComponentLoadTracker>,
+    compile_permits: Arc<Semaphore>,
+    compile_results: CompileResults,
+    file_loader: Arc<FileLoader>,
+    max_concurrent_file_prefetches: usize,
+    reject_empty_exports: bool,
+    versions_by_hash: VersionsByHash,
+    max_component_size: usize,
 }
 
 impl ComponentServiceGrpc {
     pub fn new(
         endpoint: Uri,
         access_token: Uuid,
-        max_capacity: usize,
-        max_metadata_capacity: usize,
-        time_to_idle: Duration,
-        retry_config: RetryConfig,
+        cache: ComponentCacheOptions,
+        metadata_cache: Option<Arc<dyn ComponentMetadataCache + Send + Sync>>,
+        download: ComponentDownloadOptions,
+        max_elapsed: Option<Duration>,
         compiled_component_service: Arc<dyn CompiledComponentService + Send + Sync>,
         max_component_size: usize,
+        retriable_status_codes: Vec<tonic::Code>,
+        lenient_metadata: bool,
+        verify_memories: bool,
+        metadata_transformer: Option<MetadataTransformer>,
+        file_loader: Arc<FileLoader>,
     ) -> Self {
+        let retry_config = download.retry_config;
         Self {
-            component_cache: create_component_cache(max_capacity, time_to_idle),
-            component_metadata_cache: create_component_metadata_cache(
-                max_metadata_capacity,
-                time_to_idle,
+            component_cache: ComponentCache::new(
+                cache.max_capacity,
+                cache.time_to_idle,
+                cache.large_component_threshold,
+                cache.large_component_capacity,
+            ),
+            downloaded_bytes_cache: DownloadedBytesCache::configured(
+                cache.max_downloaded_bytes_cache_size,
             ),
+            component_metadata_cache: metadata_cache,
+            latest_version_cache: cache.latest_version_ttl.map(|ttl| {
+                LatestVersionCache::new(
+                    cache.max_metadata_capacity,
+                    ttl,
+                    cache.latest_version_soft_ttl,
+                )
+            }),
             access_token,
             retry_config: retry_config.clone(),
+            max_elapsed,
             compiled_component_service,
+            verification_key: download.verification_key,
+            retriable_status_codes: retriable_status_codes.into(),
+            lenient_metadata,
+            verify_memories,
+            download_throttle: download.download_throttle,
+            metadata_transformer,
             client: GrpcClient::new(
                 "component_service",
                 move |channel| {
@@ -162,100 +552,395 @@ impl ComponentServiceGrpc {
                     ..Default::default() // TODO
                 },
             ),
+            loads: ComponentLoadTracker::new(),
+            compile_permits: Arc::new(Semaphore::new(cache.max_concurrent_compiles)),
+            compile_results: CompileResults::new(cache.max_capacity, cache.time_to_idle),
+            file_loader,
+            max_concurrent_file_prefetches: cache.max_concurrent_file_prefetches,
+            reject_empty_exports: cache.reject_empty_exports,
+            versions_by_hash: VersionsByHash::new(cache.max_metadata_capacity, cache.time_to_idle),
+            max_component_size,
         }
     }
-}
 
-#[async_trait]
-impl ComponentService for ComponentServiceGrpc {
-    async fn get(
+    /// The latest version of the component in the latest version cache. A background refresh
+    /// also caches the metadata it fetched, as the refreshed version is looked up right after.
+    fn cached_latest_version(&self, component_id: &ComponentId) -> Option<ComponentVersion> {
+        self.latest_version_cache.as_ref()?.get(component_id, || {
+            let client = self.client.clone();
+            let access_token = self.access_token;
+            let retry_config = self.retry_config.clone();
+            let max_elapsed = self.max_elapsed;
+            let retriable_status_codes = self.retriable_status_codes.clone();
+            let lenient_metadata = self.lenient_metadata;
+            let metadata_transformer = self.metadata_transformer.clone();
+            let reject_empty_exports = self.reject_empty_exports;
+            let component_metadata_cache = self.component_metadata_cache.clone();
+            let component_id = component_id.clone();
+            Box::pin(async move {
+                let metadata = get_metadata_via_grpc(
+                    &client,
+                    &access_token,
+                    &retry_config,
+                    max_elapsed,
+                    &retriable_status_codes,
+                    lenient_metadata,
+                    &component_id,
+                    None,
+                )
+                .await?;
+
+                let version = metadata.version;
+                let metadata = prepare_metadata(
+                    &metadata_transformer,
+                    reject_empty_exports,
+                    &component_id,
+                    metadata,
+                )?;
+                get_or_insert_metadata(
+                    &component_metadata_cache,
+                    &ComponentKey {
+                        component_id: component_id.clone(),
+                        component_version: version,
+                    },
+                    || Box::pin(async move { Ok(metadata) }),
+                )
+                .await?;
+
+                Ok(version)
+            })
+        })
+    }
+
+    /// Finds the version with the given hash by downloading the versions from the latest one down
+    async fn find_version_by_hash(
+        &self,
+        component_id: &ComponentId,
+        sha256: String,
+    ) -> Result<Option<ComponentVersion>, GolemError> {
+        let latest = self.get_metadata(component_id, None).await?.version;
+        for version in (0..=latest).rev() {
+            let metadata = self.get_metadata(component_id, Some(version)).await?;
+            let (bytes, _) = download_via_grpc(
+                &self.client,
+                &self.access_token,
+                &self.retry_config,
+                self.max_elapsed,
+                &self.retriable_status_codes,
+                &self.download_throttle,
+                component_id,
+                version,
+                None,
+                metadata.size,
+                self.max_component_size,
+            )
+            .await?;
+            if sha256_hex(&bytes) == sha256 {
+                return Ok(Some(version));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Lists the metadata of all the versions of a component, the latest version first. The
+    /// metadata of each version is also put in the metadata cache. Versions whose metadata cannot
+    /// be decoded or is rejected (see `prepare_metadata`) are left out with a warning, so that one
+    /// broken version does not hide the others.
+    pub async fn list_versions(
+        &self,
+        component_id: &ComponentId,
+    ) -> Result<Vec<ComponentMetadata>, GolemError> {
+        let components = get_all_components_via_grpc(
+            &self.client,
+            &self.access_token,
+            &self.retry_config,
+            self.max_elapsed,
+            &self.retriable_status_codes,
+            component_id,
+        )
+        .await?;
+
+        let mut versions = Vec::with_capacity(components.len());
+        for metadata in versions_from_grpc(
+            component_id,
+            components,
+            self.lenient_metadata,
+            &self.metadata_transformer,
+            self.reject_empty_exports,
+        ) {
+            let metadata = get_or_insert_metadata(
+                &self.component_metadata_cache,
+                &ComponentKey {
+                    component_id: component_id.clone(),
+                    component_version: metadata.version,
+                },
+                || Box::pin(async move { Ok(metadata) }),
+            )
+            .await?;
+            versions.push(metadata);
+        }
+
+        Ok(versions)
+    }
+
+    /// Gets the component from the in-memory cache, or from the compiled component store, or
+    /// downloads and compiles it, storing it in both
+    async fn load_component(
         &self,
         engine: &Engine,
         component_id: &ComponentId,
         component_version: ComponentVersion,
-    ) -> Result<(Component, ComponentMetadata), GolemError> {
+        metadata: &ComponentMetadata,
+        force_recompile: bool,
+        in_flight: InFlightComponentLoad,
+    ) -> Result<Component, GolemError> {
         let key = ComponentKey {
             component_id: component_id.clone(),
             component_version,
         };
+        if force_recompile {
+            self.component_cache.remove(&key);
+        }
+        let expected_size = metadata.size;
+        let reported_memories = self.verify_memories.then(|| metadata.memories.clone());
         let client_clone = self.client.clone();
         let component_id_clone = component_id.clone();
         let engine = engine.clone();
         let access_token = self.access_token;
         let retry_config_clone = self.retry_config.clone();
+        let max_elapsed = self.max_elapsed;
+        let retriable_status_codes = self.retriable_status_codes.clone();
+        let download_throttle = self.download_throttle.clone();
         let compiled_component_service = self.compiled_component_service.clone();
-        let component = self
-            .component_cache
-            .get_or_insert_simple(&key.clone(), || {
-                Box::pin(async move {
-                    let result = compiled_component_service
-                        .get(&component_id_clone, component_version, &engine)
-                        .await;
-
-                    let component = match result {
-                        Ok(component) => component,
-                        Err(err) => {
-                            warn!("Failed to download compiled component {:?}: {}", key, err);
-                            None
+        let compile_permits = self.compile_permits.clone();
+        let compile_results = self.compile_results.clone();
+        let verification_key = self.verification_key.clone();
+        let downloaded_bytes_cache = self.downloaded_bytes_cache.clone();
+        let max_component_size = self.max_component_size;
+        get_or_load_component(
+            &self.component_cache,
+            &key.clone(),
+            expected_size,
+            "grpc",
+            in_flight,
+            || {
+                Box::pin(load_uncached_component(
+                    "grpc",
+                    key.clone(),
+                    expected_size,
+                    engine,
+                    force_recompile,
+                    compiled_component_service,
+                    compile_permits,
+                    compile_results,
+                    move || async move {
+                        let cached_bytes = downloaded_bytes_cache
+                            .as_ref()
+                            .and_then(|cache| cache.get(&key));
+                        if let Some(bytes) = cached_bytes {
+                            debug!("Recompiling {:?} from its cached WASM", key);
+                            return Ok(bytes);
                         }
-                    };
 
-                    match component {
-                        Some(component) => Ok(component),
-                        None => {
-                            let bytes = download_via_grpc(
-                                &client_clone,
-                                &access_token,
-                                &retry_config_clone,
+                        // An earlier version still in the cache lets the component service send
+                        // just a patch against it
+                        let base = downloaded_bytes_cache
+                            .as_ref()
+                            .and_then(|cache| cache.latest_before(&key));
+                        let (bytes, signature) = download_via_grpc(
+                            &client_clone,
+                            &access_token,
+                            &retry_config_clone,
+                            max_elapsed,
+                            &retriable_status_codes,
+                            &download_throttle,
+                            &component_id_clone,
+                            component_version,
+                            base,
+                            expected_size,
+                            max_component_size,
+                        )
+                        .await?;
+
+                        if let Some(verification_key) = &verification_key {
+                            verify_signature(
+                                verification_key,
+                                signature.as_deref(),
+                                &bytes,
                                 &component_id_clone,
                                 component_version,
-                            )
-                            .await?;
+                            )?;
+                        }
+
+                        reject_core_wasm_module(&bytes, &component_id_clone, component_version)?;
 
-                            let start = Instant::now();
-                            let component_id_clone2 = component_id_clone.clone();
-                            let component = spawn_blocking(move || {
-                                Component::from_binary(&engine, &bytes).map_err(|e| {
-                                    GolemError::ComponentParseFailed {
-                                        component_id: component_id_clone2,
-                                        component_version,
-                                        reason: format!("{}", e),
-                                    }
-                                })
-                            })
-                            .await
-                            .map_err(|join_err| GolemError::unknown(join_err.to_string()))??;
-                            let end = Instant::now();
-
-                            let compilation_time = end.duration_since(start);
-                            record_compilation_time(compilation_time);
-                            debug!(
-                                "Compiled {} in {}ms",
-                                component_id_clone,
-                                compilation_time.as_millis(),
+                        if let Some(reported_memories) = &reported_memories {
+                            check_reported_memories(
+                                &component_id_clone,
+                                component_version,
+                                reported_memories,
+                                &bytes,
                             );
+                        }
 
-                            let result = compiled_component_service
-                                .put(&component_id_clone, component_version, &component)
-                                .await;
-
-                            match result {
-                                Ok(_) => Ok(component),
-                                Err(err) => {
-                                    warn!("Failed to upload compiled component {:?}: {}", key, err);
-                                    Ok(component)
-                                }
-                            }
+                        let bytes = Arc::new(bytes);
+                        if let Some(cache) = &downloaded_bytes_cache {
+                            cache.put(key.clone(), bytes.clone());
                         }
-                    }
-                })
+                        Ok(bytes)
+                    },
+                ))
+            },
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl ComponentService for ComponentServiceGrpc {
+    async fn get(
+        &self,
+        engine: &Engine,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+    ) -> Result<(Component, ComponentMetadata), GolemError> {
+        let in_flight = self.loads.start()?;
+        let metadata = self
+            .get_metadata(component_id, Some(component_version))
+            .await?;
+        let component = self
+            .load_component(
+                engine,
+                component_id,
+                component_version,
+                &metadata,
+                false,
+                in_flight,
+            )
+            .await?;
+
+        Ok((component, metadata))
+    }
+
+    async fn get_by_hash(
+        &self,
+        engine: &Engine,
+        component_id: &ComponentId,
+        sha256: &str,
+    ) -> Result<(Component, ComponentMetadata), GolemError> {
+        let version = self
+            .versions_by_hash
+            .resolve(component_id, sha256, |sha256| {
+                self.find_version_by_hash(component_id, sha256)
             })
             .await?;
+        self.get(engine, component_id, version).await
+    }
+
+    async fn ensure_cached(
+        &self,
+        engine: &Engine,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+        force_recompile: bool,
+    ) -> Result<(), GolemError> {
+        let in_flight = self.loads.start()?;
         let metadata = self
             .get_metadata(component_id, Some(component_version))
             .await?;
+        self.load_component(
+            engine,
+            component_id,
+            component_version,
+            &metadata,
+            force_recompile,
+            in_flight,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn prefetch_files(
+        &self,
+        account_id: &AccountId,
+        metadata: &ComponentMetadata,
+    ) -> Result<PrefetchedFiles, GolemError> {
+        self.file_loader
+            .prefetch(
+                account_id,
+                &metadata.files,
+                self.max_concurrent_file_prefetches,
+            )
+            .await
+    }
+
+    async fn shutdown(&self, grace_period: Duration) {
+        self.loads.shutdown(grace_period).await;
+    }
+
+    fn compile_result(
+        &self,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+    ) -> Option<ComponentCompileResult> {
+        self.compile_results.get(component_id, component_version)
+    }
+
+    fn watch_metadata<'a>(
+        &'a self,
+        component_id: &'a ComponentId,
+        poll_interval: Duration,
+    ) -> BoxStream<'a, Result<ComponentMetadata, GolemError>> {
+        watch_latest_metadata(poll_interval, move || async move {
+            let metadata = get_metadata_via_grpc(
+                &self.client,
+                &self.access_token,
+                &self.retry_config,
+                self.max_elapsed,
+                &self.retriable_status_codes,
+                self.lenient_metadata,
+                component_id,
+                None,
+            )
+            .await?;
+            prepare_metadata(
+                &self.metadata_transformer,
+                self.reject_empty_exports,
+                component_id,
+                metadata,
+            )
+        })
+    }
 
-        Ok((component, metadata))
+    async fn get_proto_component(
+        &self,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+    ) -> Result<golem_api_grpc::proto::golem::component::Component, GolemError> {
+        get_component_via_grpc(
+            &self.client,
+            &self.access_token,
+            &self.retry_config,
+            self.max_elapsed,
+            &self.retriable_status_codes,
+            component_id,
+            Some(component_version),
+        )
+        .await
+    }
+
+    async fn is_latest(
+        &self,
+        component_id: &ComponentId,
+        version: ComponentVersion,
+    ) -> Result<bool, GolemError> {
+        let cached = self.cached_latest_version(component_id);
+        let latest = match cached {
+            Some(latest) => latest,
+            // Also fills the latest version cache
+            None => self.get_metadata(component_id, None).await?.version,
+        };
+        Ok(latest == version)
     }
 
     async fn get_metadata(
@@ -268,48 +953,80 @@ impl ComponentService for ComponentServiceGrpc {
                 let client = self.client.clone();
                 let access_token = self.access_token;
                 let retry_config = self.retry_config.clone();
+                let max_elapsed = self.max_elapsed;
+                let retriable_status_codes = self.retriable_status_codes.clone();
+                let lenient_metadata = self.lenient_metadata;
+                let metadata_transformer = self.metadata_transformer.clone();
+                let reject_empty_exports = self.reject_empty_exports;
                 let component_id = component_id.clone();
-                self.component_metadata_cache
-                    .get_or_insert_simple(
-                        &ComponentKey {
-                            component_id: component_id.clone(),
-                            component_version: version,
-                        },
-                        || {
-                            Box::pin(async move {
-                                get_metadata_via_grpc(
-                                    &client,
-                                    &access_token,
-                                    &retry_config,
-                                    &component_id,
-                                    forced_version,
-                                )
-                                .await
-                            })
-                        },
-                    )
-                    .await
+                get_or_insert_metadata(
+                    &self.component_metadata_cache,
+                    &ComponentKey {
+                        component_id: component_id.clone(),
+                        component_version: version,
+                    },
+                    || {
+                        Box::pin(async move {
+                            let metadata = get_metadata_via_grpc(
+                                &client,
+                                &access_token,
+                                &retry_config,
+                                max_elapsed,
+                                &retriable_status_codes,
+                                lenient_metadata,
+                                &component_id,
+                                forced_version,
+                            )
+                            .await?;
+                            prepare_metadata(
+                                &metadata_transformer,
+                                reject_empty_exports,
+                                &component_id,
+                                metadata,
+                            )
+                        })
+                    },
+                )
+                .await
             }
             None => {
+                if let Some(version) = self.cached_latest_version(component_id) {
+                    return self.get_metadata(component_id, Some(version)).await;
+                }
+
                 let metadata = get_metadata_via_grpc(
                     &self.client,
                     &self.access_token,
                     &self.retry_config,
+                    self.max_elapsed,
+                    &self.retriable_status_codes,
+                    self.lenient_metadata,
                     component_id,
                     None,
                 )
                 .await?;
 
-                let metadata = self
-                    .component_metadata_cache
-                    .get_or_insert_simple(
-                        &ComponentKey {
-                            component_id: component_id.clone(),
-                            component_version: metadata.version,
-                        },
-                        || Box::pin(async move { Ok(metadata) }),
-                    )
-                    .await?;
+                let version = metadata.version;
+
+                if let Some(latest_version_cache) = &self.latest_version_cache {
+                    latest_version_cache.put(component_id, version).await;
+                }
+
+                let metadata = prepare_metadata(
+                    &self.metadata_transformer,
+                    self.reject_empty_exports,
+                    component_id,
+                    metadata,
+                )?;
+                let metadata = get_or_insert_metadata(
+                    &self.component_metadata_cache,
+                    &ComponentKey {
+                        component_id: component_id.clone(),
+                        component_version: version,
+                    },
+                    || Box::pin(async move { Ok(metadata) }),
+                )
+                .await?;
 
                 Ok(metadata)
             }
@@ -321,27 +1038,39 @@ async fn download_via_grpc(
     client: &GrpcClient<ComponentServiceClient<Channel>>,
     access_token: &Uuid,
     retry_config: &RetryConfig,
+    max_elapsed: Option<Duration>,
+    retriable_status_codes: &[tonic::Code],
+    download_throttle: &Option<Arc<DownloadThrottle>>,
     component_id: &ComponentId,
     component_version: ComponentVersion,
-) -> Result<Vec<u8>, GolemError> {
-    with_retries(
+    base: Option<(ComponentVersion, Arc<Vec<u8>>)>,
+    expected_size: u64,
+    max_component_size: usize,
+) -> Result<(Vec<u8>, Option<Vec<u8>>), GolemError> {
+    let base_version = base.as_ref().map(|(base_version, _)| *base_version);
+    let attempts = Mutex::new(ComponentAttempts::default());
+    let input = (
+        client.clone(),
+        component_id.clone(),
+        access_token.to_owned(),
+        &attempts,
+        download_throttle,
+    );
+    let download = with_retries(
         "components",
         "download",
         Some(component_id.to_string()),
         retry_config,
-        &(
-            client.clone(),
-            component_id.clone(),
-            access_token.to_owned(),
-        ),
-        |(client, component_id, access_token)| {
-            Box::pin(async move {
-                let response = client
+        &input,
+        |(client, component_id, access_token, attempts, download_throttle)| {
+            recording_attempts(attempts, async move {
+                let mut response = client
                     .call("download_component", move |client| {
                         let request = authorised_grpc_request(
                             DownloadComponentRequest {
                                 component_id: Some(component_id.clone().into()),
                                 version: Some(component_version),
+                                base_version,
                             },
                             access_token,
                         );
@@ -350,59 +1079,365 @@ async fn download_via_grpc(
                     .await?
                     .into_inner();
 
-                let chunks = response.into_stream().try_collect::<Vec<_>>().await?;
-                let bytes = chunks
-                    .into_iter()
-                    .map(|chunk| match chunk.result {
-                        None => Err("Empty response".to_string().into()),
-                        Some(download_component_response::Result::SuccessChunk(chunk)) => Ok(chunk),
-                        Some(download_component_response::Result::Error(error)) => {
-                            Err(GrpcError::Domain(error))
-                        }
-                    })
-                    .collect::<Result<Vec<Vec<u8>>, GrpcError<ComponentError>>>()?;
+                let start = Instant::now();
+                let downloaded = collect_download_chunks(&mut response, download_throttle).await?;
+                let download_time = start.elapsed();
 
-                let bytes: Vec<u8> = bytes.into_iter().flatten().collect();
+                let bytes = concat_chunks(downloaded.chunks, expected_size, max_component_size);
 
                 record_external_call_response_size_bytes("components", "download", bytes.len());
+                record_component_download("grpc", bytes.len(), download_time);
 
-                Ok(bytes)
+                Ok((bytes, downloaded.signature, downloaded.patch_base_version))
             })
         },
-        is_grpc_retriable::<ComponentError>,
-    )
+        |error: &GrpcError<ComponentError>| is_grpc_retriable_with(error, retriable_status_codes),
+    );
+
+    let result = with_max_elapsed(max_elapsed, download, |max_elapsed| {
+        GolemError::component_download_failed(
+            component_id.clone(),
+            component_version,
+            format!("Timed out after {max_elapsed:?} of retrying"),
+        )
+    })
     .await
-    .map_err(|error| grpc_component_download_error(error, component_id, component_version))
+    .and_then(|result| {
+        result
+            .map_err(|error| grpc_component_download_error(error, component_id, component_version))
+    });
+    let (bytes, signature, patch_base_version) =
+        result.map_err(|error| error.with_attempts(attempts.into_inner().unwrap()))?;
+
+    match (patch_base_version, base) {
+        (None, _) => Ok((bytes, signature)),
+        (Some(patch_base_version), Some((base_version, base)))
+            if patch_base_version == base_version =>
+        {
+            let bytes =
+                patched_component(&base, &bytes, component_id, component_version, base_version)?;
+            Ok((bytes, signature))
+        }
+        (Some(patch_base_version), _) => Err(GolemError::ComponentParseFailed {
+            component_id: component_id.clone(),
+            component_version,
+            reason: format!(
+                "Received a patch against version {patch_base_version}, which was not requested"
+            ),
+        }),
+    }
 }
 
-async fn get_metadata_via_grpc(
-    client: &GrpcClient<ComponentServiceClient<Channel>>,
-    access_token: &Uuid,
-    retry_config: &RetryConfig,
-    component_id: &ComponentId,
-    component_version: Option<ComponentVersion>,
-) -> Result<ComponentMetadata, GolemError> {
-    let desc = format!("Getting component metadata of {component_id}");
-    debug!("{}", &desc);
-    with_retries(
-        "components",
-        "get_metadata",
-        Some(component_id.to_string()),
-        retry_config,
-        &(
-            client.clone(),
-            component_id.clone(),
-            access_token.to_owned(),
-        ),
-        |(client, component_id, access_token)| {
-            Box::pin(async move {
-                let response = match component_version {
-                    Some(component_version) => client
-                        .call("get_component_metadata", move |client| {
-                            let request = authorised_grpc_request(
-                                GetVersionedComponentRequest {
-                                    component_id: Some(component_id.clone().into()),
-                                    version: component_version,
+/// The chunks of a downloaded component, with its signature. If `patch_base_version` is set,
+/// the chunks form a bsdiff patch against that version instead of the full WASM.
+struct DownloadedChunks {
+    chunks: Vec<Vec<u8>>,
+    signature: Option<Vec<u8>>,
+    patch_base_version: Option<ComponentVersion>,
+}
+
+/// Collects the WASM chunks and the signature of a `download_component` response stream. An
+/// error chunk ends the download right away, without receiving (and buffering) the rest of the
+/// stream, and is returned as the domain error sent by the component service.
+async fn collect_download_chunks<S>(
+    response: &mut S,
+    download_throttle: &Option<Arc<DownloadThrottle>>,
+) -> Result<DownloadedChunks, GrpcError<ComponentError>>
+where
+    S: Stream<Item = Result<DownloadComponentResponse, tonic::Status>> + Unpin,
+{
+    let mut chunks = Vec::new();
+    let mut signature = None;
+    let mut patch_base_version = None;
+    while let Some(chunk) = response.next().await {
+        let chunk = chunk?;
+        throttle(download_throttle, chunk.encoded_len()).await;
+        if signature.is_none() {
+            signature = chunk.signature;
+        }
+        if patch_base_version.is_none() {
+            patch_base_version = chunk.patch_base_version;
+        }
+        match chunk.result {
+            None => return Err("Empty response".to_string().into()),
+            Some(download_component_response::Result::SuccessChunk(chunk)) => chunks.push(chunk),
+            Some(download_component_response::Result::Error(error)) => {
+                debug!(
+                    "Component download failed after {} chunks: {error:?}",
+                    chunks.len()
+                );
+                return Err(GrpcError::Domain(error));
+            }
+        }
+    }
+    Ok(DownloadedChunks {
+        chunks,
+        signature,
+        patch_base_version,
+    })
+}
+
+/// Gets a component from the in-memory cache, or loads it with `load` if it is not there yet. Hits
+/// of the in-memory cache are recorded here, `load` records whether the component was compiled or
+/// taken from the compiled component store. Whether and where the loaded component is cached
+/// depends on its `size`, see `ComponentCache`.
+async fn get_or_load_component<F>(
+    cache: &ComponentCache,
+    key: &ComponentKey,
+    size: u64,
+    source: &'static str,
+    in_flight: InFlightComponentLoad,
+    load: F,
+) -> Result<Component, GolemError>
+where
+    F: FnOnce() -> Pin<Box<dyn Future<Output = Result<Component, GolemError>> + Send>> + Send,
+{
+    let admission = cache.admission(size);
+    let cache = match admission {
+        ComponentCacheAdmission::Main => cache.main.as_ref(),
+        ComponentCacheAdmission::Large => cache.large.as_ref(),
+        ComponentCacheAdmission::Bypass => None,
+    };
+    let Some(cache) = cache else {
+        record_component_cache_admission(ComponentCacheAdmission::Bypass.label());
+        return spawn_component_load(load(), in_flight).await;
+    };
+
+    let loaded = Arc::new(AtomicBool::new(false));
+    let loaded_clone = loaded.clone();
+    let result = cache
+        .get_or_insert_simple(key, move || {
+            loaded_clone.store(true, Ordering::Relaxed);
+            record_component_cache_admission(admission.label());
+            Box::pin(spawn_component_load(load(), in_flight))
+        })
+        .await;
+
+    if result.is_ok() && !loaded.load(Ordering::Relaxed) {
+        record_component_load(source, "memory_cache");
+    }
+    result
+}
+
+/// Runs a component load in its own task, so that it keeps the load in flight even if the caller
+/// gets dropped, until it finishes and stores the compiled component. The shutdown of the
+/// component service relies on this to drain the in-flight loads.
+async fn spawn_component_load(
+    load: Pin<Box<dyn Future<Output = Result<Component, GolemError>> + Send>>,
+    in_flight: InFlightComponentLoad,
+) -> Result<Component, GolemError> {
+    let task = tokio::spawn(async move {
+        let result = load.await;
+        drop(in_flight);
+        result
+    });
+    task.await
+        .unwrap_or_else(|err| Err(GolemError::unknown(format!("Component load failed: {err}"))))
+}
+
+/// Waits for a free compilation slot, so that cold starts of many components queue up instead
+/// of compiling all of them at once. The slot is released when the returned permit is dropped.
+async fn acquire_compile_permit(
+    compile_permits: &Arc<Semaphore>,
+) -> Result<OwnedSemaphorePermit, GolemError> {
+    compile_permits
+        .clone()
+        .acquire_owned()
+        .await
+        .map_err(|err| GolemError::unknown(err.to_string()))
+}
+
+/// Loads a component missing from the in-memory cache, the same way for all component sources:
+/// takes it from the compiled component store, or compiles the WASM returned by `fetch` and
+/// stores the compiled component there. `source` labels the recorded metrics.
+#[allow(clippy::too_many_arguments)]
+async fn load_uncached_component<F, Fut>(
+    source: &'static str,
+    key: ComponentKey,
+    size: u64,
+    engine: Engine,
+    force_recompile: bool,
+    compiled_component_service: Arc<dyn CompiledComponentService + Send + Sync>,
+    compile_permits: Arc<Semaphore>,
+    compile_results: CompileResults,
+    fetch: F,
+) -> Result<Component, GolemError>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<Arc<Vec<u8>>, GolemError>>,
+{
+    let (component, store_reachable) =
+        get_compiled_component(&compiled_component_service, &key, &engine, force_recompile).await;
+    if let Some(component) = component {
+        record_component_load(source, "compiled_store");
+        return Ok(component);
+    }
+
+    let bytes = fetch().await?;
+
+    let permit = acquire_compile_permit(&compile_permits).await?;
+    let start = Instant::now();
+    let component_id = key.component_id.clone();
+    let component_version = key.component_version;
+    let result = spawn_blocking(move || {
+        let _permit = permit;
+        Component::from_binary(&engine, &bytes).map_err(|e| GolemError::ComponentParseFailed {
+            component_id,
+            component_version,
+            reason: format!("{}", e),
+        })
+    })
+    .await
+    .map_err(|join_err| GolemError::unknown(join_err.to_string()))?;
+    let compilation_time = start.elapsed();
+
+    compile_results
+        .record(
+            &key.component_id,
+            key.component_version,
+            &result,
+            compilation_time,
+        )
+        .await;
+    let component = result?;
+    record_compilation_time(compilation_time, size);
+    record_component_load(source, "compiled");
+    debug!(
+        "Compiled {} in {}ms",
+        key.component_id,
+        compilation_time.as_millis(),
+    );
+
+    if store_reachable {
+        let result = compiled_component_service
+            .put(&key.component_id, key.component_version, &component)
+            .await;
+        if let Err(err) = result {
+            warn!("Failed to upload compiled component {:?}: {}", key, err);
+        }
+    }
+    Ok(component)
+}
+
+/// Fails with a targeted error for core WASM modules, which cannot be compiled as components
+fn reject_core_wasm_module(
+    bytes: &[u8],
+    component_id: &ComponentId,
+    component_version: ComponentVersion,
+) -> Result<(), GolemError> {
+    if is_core_wasm_module(bytes) {
+        Err(GolemError::ComponentParseFailed {
+            component_id: component_id.clone(),
+            component_version,
+            reason: CORE_WASM_MODULE_ERROR.to_string(),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Suffix of the files in the local component store holding a version as a patch of its base
+/// version, instead of its full WASM
+const COMPONENT_PATCH_SUFFIX: &str = ".patch";
+
+fn is_component_patch(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "patch")
+}
+
+/// Rebuilds the WASM of a component version from the WASM of its base version and a bsdiff patch
+fn apply_component_patch(base: &[u8], mut patch: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    bsdiff::patch(base, &mut patch, &mut bytes)?;
+    Ok(bytes)
+}
+
+/// Rebuilds the WASM of a component version downloaded as a patch of `base_version`
+fn patched_component(
+    base: &[u8],
+    patch: &[u8],
+    component_id: &ComponentId,
+    component_version: ComponentVersion,
+    base_version: ComponentVersion,
+) -> Result<Vec<u8>, GolemError> {
+    apply_component_patch(base, patch).map_err(|e| GolemError::ComponentParseFailed {
+        component_id: component_id.clone(),
+        component_version,
+        reason: format!("Failed to apply patch to base version {base_version}: {e}"),
+    })
+}
+
+/// Reassembles the downloaded chunks, reserving the expected size of the component up front
+/// so large components don't get reallocated repeatedly while being concatenated. The size comes
+/// from the metadata, so the reservation is capped at the largest component the client accepts.
+fn concat_chunks(chunks: Vec<Vec<u8>>, expected_size: u64, max_component_size: usize) -> Vec<u8> {
+    let capacity = expected_size.min(max_component_size as u64) as usize;
+    let mut bytes = Vec::with_capacity(capacity);
+    for chunk in chunks {
+        bytes.extend_from_slice(&chunk);
+    }
+    bytes
+}
+
+async fn get_metadata_via_grpc(
+    client: &GrpcClient<ComponentServiceClient<Channel>>,
+    access_token: &Uuid,
+    retry_config: &RetryConfig,
+    max_elapsed: Option<Duration>,
+    retriable_status_codes: &[tonic::Code],
+    lenient_metadata: bool,
+    component_id: &ComponentId,
+    component_version: Option<ComponentVersion>,
+) -> Result<ComponentMetadata, GolemError> {
+    let component = get_component_via_grpc(
+        client,
+        access_token,
+        retry_config,
+        max_elapsed,
+        retriable_status_codes,
+        component_id,
+        component_version,
+    )
+    .await?;
+    metadata_from_grpc(component_id, component, lenient_metadata)
+        .map_err(|error| grpc_get_latest_version_error(error, component_id))
+}
+
+/// Gets the component message of a component version, or of its latest version, as returned by
+/// the component service
+async fn get_component_via_grpc(
+    client: &GrpcClient<ComponentServiceClient<Channel>>,
+    access_token: &Uuid,
+    retry_config: &RetryConfig,
+    max_elapsed: Option<Duration>,
+    retriable_status_codes: &[tonic::Code],
+    component_id: &ComponentId,
+    component_version: Option<ComponentVersion>,
+) -> Result<golem_api_grpc::proto::golem::component::Component, GolemError> {
+    let desc = format!("Getting component metadata of {component_id}");
+    debug!("{}", &desc);
+    let attempts = Mutex::new(ComponentAttempts::default());
+    let input = (
+        client.clone(),
+        component_id.clone(),
+        access_token.to_owned(),
+        &attempts,
+    );
+    let get_metadata = with_retries(
+        "components",
+        "get_metadata",
+        Some(component_id.to_string()),
+        retry_config,
+        &input,
+        |(client, component_id, access_token, attempts)| {
+            recording_attempts(attempts, async move {
+                let response = match component_version {
+                    Some(component_version) => client
+                        .call("get_component_metadata", move |client| {
+                            let request = authorised_grpc_request(
+                                GetVersionedComponentRequest {
+                                    component_id: Some(component_id.clone().into()),
+                                    version: component_version,
                                 },
                                 access_token,
                             );
@@ -436,54 +1471,421 @@ async fn get_metadata_via_grpc(
                     }
                 }?;
 
-                let result = ComponentMetadata {
-                    version: component
-                        .versioned_component_id
-                        .as_ref()
-                        .map(|id| id.version)
-                        .ok_or(GrpcError::Unexpected(
-                            "Undefined component version".to_string(),
-                        ))?,
-                    size: component.component_size,
-                    component_type: component.component_type().into(),
-                    memories: component
-                        .metadata
-                        .as_ref()
-                        .map(|metadata| {
-                            metadata.memories.iter().map(|m| m.clone().into()).collect()
-                        })
-                        .unwrap_or_default(),
-                    exports: component
-                        .metadata
-                        .map(|metadata| {
-                            let export = metadata.exports;
-                            let vec: Vec<Result<AnalysedExport, String>> =
-                                export.into_iter().map(AnalysedExport::try_from).collect();
-                            vec.into_iter().collect()
-                        })
-                        .unwrap_or_else(|| Ok(Vec::new()))
-                        .map_err(|_| {
-                            GrpcError::Unexpected("Failed to get the exports".to_string())
-                        })?,
-                    files: component
-                        .files
-                        .into_iter()
-                        .map(|file| file.try_into())
-                        .collect::<Result<Vec<_>, _>>()
-                        .map_err(|_| {
-                            GrpcError::Unexpected("Failed to get the files".to_string())
-                        })?,
-                };
-
                 record_external_call_response_size_bytes("components", "get_metadata", len);
 
-                Ok(result)
+                Ok(component)
             })
         },
-        is_grpc_retriable::<ComponentError>,
-    )
+        |error: &GrpcError<ComponentError>| is_grpc_retriable_with(error, retriable_status_codes),
+    );
+
+    let result = with_max_elapsed(max_elapsed, get_metadata, |max_elapsed| {
+        GolemError::get_latest_version_of_component_failed(
+            component_id.clone(),
+            format!("Timed out after {max_elapsed:?} of retrying"),
+        )
+    })
+    .await
+    .and_then(|result| result.map_err(|error| grpc_get_latest_version_error(error, component_id)));
+    result.map_err(|error| error.with_attempts(attempts.into_inner().unwrap()))
+}
+
+/// Gets the component messages of all the versions of a component, as returned by the
+/// component service
+async fn get_all_components_via_grpc(
+    client: &GrpcClient<ComponentServiceClient<Channel>>,
+    access_token: &Uuid,
+    retry_config: &RetryConfig,
+    max_elapsed: Option<Duration>,
+    retriable_status_codes: &[tonic::Code],
+    component_id: &ComponentId,
+) -> Result<Vec<golem_api_grpc::proto::golem::component::Component>, GolemError> {
+    let desc = format!("Getting all versions of component {component_id}");
+    debug!("{}", &desc);
+    let attempts = Mutex::new(ComponentAttempts::default());
+    let input = (
+        client.clone(),
+        component_id.clone(),
+        access_token.to_owned(),
+        &attempts,
+    );
+    let list_versions = with_retries(
+        "components",
+        "list_versions",
+        Some(component_id.to_string()),
+        retry_config,
+        &input,
+        |(client, component_id, access_token, attempts)| {
+            recording_attempts(attempts, async move {
+                let response = client
+                    .call("get_component_metadata_all_versions", move |client| {
+                        let request = authorised_grpc_request(
+                            GetComponentRequest {
+                                component_id: Some(component_id.clone().into()),
+                            },
+                            access_token,
+                        );
+                        Box::pin(client.get_component_metadata_all_versions(request))
+                    })
+                    .await?
+                    .into_inner();
+                let len = response.encoded_len();
+                let components = match response.result {
+                    None => Err("Empty response".to_string().into()),
+                    Some(get_component_metadata_all_versions_response::Result::Success(
+                        response,
+                    )) => Ok(response.components),
+                    Some(get_component_metadata_all_versions_response::Result::Error(error)) => {
+                        Err(GrpcError::Domain(error))
+                    }
+                }?;
+
+                record_external_call_response_size_bytes("components", "list_versions", len);
+
+                Ok(components)
+            })
+        },
+        |error: &GrpcError<ComponentError>| is_grpc_retriable_with(error, retriable_status_codes),
+    );
+
+    let result = with_max_elapsed(max_elapsed, list_versions, |max_elapsed| {
+        GolemError::get_latest_version_of_component_failed(
+            component_id.clone(),
+            format!("Timed out after {max_elapsed:?} of retrying"),
+        )
+    })
     .await
-    .map_err(|error| grpc_get_latest_version_error(error, component_id))
+    .and_then(|result| result.map_err(|error| grpc_get_latest_version_error(error, component_id)));
+    result.map_err(|error| error.with_attempts(attempts.into_inner().unwrap()))
+}
+
+/// Wraps one attempt of a retried call of the component service, recording its failure in
+/// `attempts`, so that the error finally returned can tell how the attempts went
+fn recording_attempts<'a, R: Send + 'a>(
+    attempts: &'a Mutex<ComponentAttempts>,
+    attempt: impl Future<Output = Result<R, GrpcError<ComponentError>>> + Send + 'a,
+) -> Pin<Box<dyn Future<Output = Result<R, GrpcError<ComponentError>>> + Send + 'a>> {
+    Box::pin(async move {
+        let result = attempt.await;
+        if let Err(error) = &result {
+            attempts.lock().unwrap().record_failure(error);
+        }
+        result
+    })
+}
+
+/// Parses the properties file of a local component. The reason of a failure names the file and
+/// tells what is wrong with it and where, such as a missing field or a value of the wrong type.
+fn parse_props_file(props_path: &Path, data: &str) -> Result<ComponentMetadata, String> {
+    serde_json::from_str::<LocalComponentProps>(data)
+        .map(ComponentMetadata::from)
+        .map_err(|err| {
+            format!(
+                "Invalid properties file {} of component: {err}",
+                props_path.display()
+            )
+        })
+}
+
+/// Builds the component message of the component service from the metadata of a component
+/// version, leaving the parts it does not have empty
+fn proto_component_from_metadata(
+    component_id: &ComponentId,
+    metadata: ComponentMetadata,
+) -> golem_api_grpc::proto::golem::component::Component {
+    golem_api_grpc::proto::golem::component::Component {
+        versioned_component_id: Some(
+            golem_api_grpc::proto::golem::component::VersionedComponentId {
+                component_id: Some(component_id.clone().into()),
+                version: metadata.version,
+            },
+        ),
+        component_name: String::new(),
+        component_size: metadata.size,
+        metadata: Some(golem_api_grpc::proto::golem::component::ComponentMetadata {
+            exports: metadata
+                .exports
+                .into_iter()
+                .map(|export| export.into())
+                .collect(),
+            producers: vec![],
+            memories: metadata
+                .memories
+                .into_iter()
+                .map(|memory| memory.into())
+                .collect(),
+            annotations: metadata.annotations.into_iter().collect(),
+        }),
+        project_id: None,
+        created_at: None,
+        component_type: Some(
+            golem_api_grpc::proto::golem::component::ComponentType::from(metadata.component_type)
+                as i32,
+        ),
+        files: metadata.files.into_iter().map(|file| file.into()).collect(),
+    }
+}
+
+/// Converts the versions of a component returned by the component service to their prepared
+/// metadata, the latest version first, leaving out the versions which cannot be converted
+fn versions_from_grpc(
+    component_id: &ComponentId,
+    components: Vec<golem_api_grpc::proto::golem::component::Component>,
+    lenient: bool,
+    metadata_transformer: &Option<MetadataTransformer>,
+    reject_empty_exports: bool,
+) -> Vec<ComponentMetadata> {
+    let mut versions: Vec<ComponentMetadata> = components
+        .into_iter()
+        .filter_map(|component| {
+            let metadata = metadata_from_grpc(component_id, component, lenient)
+                .map_err(|error| grpc_get_latest_version_error(error, component_id))
+                .and_then(|metadata| {
+                    prepare_metadata(
+                        metadata_transformer,
+                        reject_empty_exports,
+                        component_id,
+                        metadata,
+                    )
+                });
+            match metadata {
+                Ok(metadata) => Some(metadata),
+                Err(err) => {
+                    warn!(
+                        "Leaving a version of component {component_id} out of its versions: {err}"
+                    );
+                    None
+                }
+            }
+        })
+        .collect();
+    versions.sort_by(|a, b| b.version.cmp(&a.version));
+    versions
+}
+
+/// Converts a component returned by the component service to its metadata. The id, the version
+/// and the size are essential and always required, while with `lenient` set, exports and initial
+/// files that cannot be decoded (for example after the server added new variants to them) are
+/// replaced by empty lists with a warning instead of failing the whole lookup
+fn metadata_from_grpc(
+    component_id: &ComponentId,
+    component: golem_api_grpc::proto::golem::component::Component,
+    lenient: bool,
+) -> Result<ComponentMetadata, GrpcError<ComponentError>> {
+    let versioned_component_id =
+        component
+            .versioned_component_id
+            .as_ref()
+            .ok_or(GrpcError::Unexpected(
+                "Undefined component version".to_string(),
+            ))?;
+    if versioned_component_id.component_id.is_none() {
+        return Err(GrpcError::Unexpected("Undefined component id".to_string()));
+    }
+    let version = versioned_component_id.version;
+
+    let exports = component
+        .metadata
+        .as_ref()
+        .map(|metadata| {
+            metadata
+                .exports
+                .iter()
+                .cloned()
+                .map(AnalysedExport::try_from)
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .unwrap_or_else(|| Ok(Vec::new()));
+    let exports = match exports {
+        Ok(exports) => exports,
+        Err(err) if lenient => {
+            warn!("Ignoring the exports of component {component_id} version {version}: {err}");
+            Vec::new()
+        }
+        Err(_) => {
+            return Err(GrpcError::Unexpected(
+                "Failed to get the exports".to_string(),
+            ))
+        }
+    };
+
+    let files = component
+        .files
+        .iter()
+        .cloned()
+        .map(InitialComponentFile::try_from)
+        .collect::<Result<Vec<_>, _>>();
+    let files = match files {
+        Ok(files) => files,
+        Err(err) if lenient => {
+            warn!("Ignoring the files of component {component_id} version {version}: {err}");
+            Vec::new()
+        }
+        Err(_) => return Err(GrpcError::Unexpected("Failed to get the files".to_string())),
+    };
+
+    Ok(ComponentMetadata {
+        version,
+        size: component.component_size,
+        component_type: component.component_type().into(),
+        memories: component
+            .metadata
+            .as_ref()
+            .map(|metadata| metadata.memories.iter().map(|m| m.clone().into()).collect())
+            .unwrap_or_default(),
+        exports,
+        files,
+        base_version: None,
+        annotations: component
+            .metadata
+            .as_ref()
+            .map(|metadata| metadata.annotations.clone().into_iter().collect())
+            .unwrap_or_default(),
+    })
+}
+
+/// Compares the memories reported in the metadata of a downloaded component with the ones found
+/// by analysing its WASM, and logs a warning if they differ. A component reporting no memories
+/// while needing some would be scheduled with too little memory. Returns whether they match.
+fn check_reported_memories(
+    component_id: &ComponentId,
+    component_version: ComponentVersion,
+    reported: &[LinearMemory],
+    bytes: &[u8],
+) -> bool {
+    match AnalysedComponentMetadata::analyse_component(bytes) {
+        Ok(analysed) if analysed.memories == reported => true,
+        Ok(analysed) => {
+            warn!(
+                "The metadata of component {component_id} version {component_version} reports {} linear memories of {} bytes in total, but the component has {} of {} bytes",
+                reported.len(),
+                reported.iter().map(|memory| memory.initial).sum::<u64>(),
+                analysed.memories.len(),
+                analysed.memories.iter().map(|memory| memory.initial).sum::<u64>(),
+            );
+            false
+        }
+        Err(err) => {
+            warn!(
+                "Failed to analyse component {component_id} version {component_version} to check its memories: {err}"
+            );
+            false
+        }
+    }
+}
+
+/// Polls the metadata of the latest version of a component with `latest`, waiting `poll_interval`
+/// between the polls, and yields it whenever its version differs from the previously yielded one
+fn watch_latest_metadata<'a, F, Fut>(
+    poll_interval: Duration,
+    latest: F,
+) -> BoxStream<'a, Result<ComponentMetadata, GolemError>>
+where
+    F: Fn() -> Fut + Send + 'a,
+    Fut: Future<Output = Result<ComponentMetadata, GolemError>> + Send + 'a,
+{
+    Box::pin(futures_util::stream::unfold(
+        (latest, None, true),
+        move |(latest, last_version, first)| async move {
+            if !first {
+                tokio::time::sleep(poll_interval).await;
+            }
+            loop {
+                match latest().await {
+                    Ok(metadata) if Some(metadata.version) == last_version => {
+                        tokio::time::sleep(poll_interval).await
+                    }
+                    Ok(metadata) => {
+                        let version = Some(metadata.version);
+                        return Some((Ok(metadata), (latest, version, false)));
+                    }
+                    Err(err) => return Some((Err(err), (latest, last_version, false))),
+                }
+            }
+        },
+    ))
+}
+
+/// Yields the metadata of the latest version of a component with `latest` first, then looks it up
+/// again on every signal of `changes` and yields it whenever its version differs from the
+/// previously yielded one. The `watcher` sending the signals is kept alive as long as the stream
+fn watch_latest_metadata_on_changes<'a, W, F, Fut>(
+    watcher: W,
+    changes: mpsc::UnboundedReceiver<()>,
+    latest: F,
+) -> BoxStream<'a, Result<ComponentMetadata, GolemError>>
+where
+    W: Send + 'a,
+    F: Fn() -> Fut + Send + 'a,
+    Fut: Future<Output = Result<ComponentMetadata, GolemError>> + Send + 'a,
+{
+    Box::pin(futures_util::stream::unfold(
+        (watcher, changes, latest, None, true),
+        move |(watcher, mut changes, latest, last_version, mut first)| async move {
+            loop {
+                if !first {
+                    changes.recv().await?;
+                    // Writing a component triggers several changes, one lookup covers all of them
+                    while changes.try_recv().is_ok() {}
+                }
+                first = false;
+
+                match latest().await {
+                    Ok(metadata) if Some(metadata.version) == last_version => {}
+                    Ok(metadata) => {
+                        let version = Some(metadata.version);
+                        return Some((Ok(metadata), (watcher, changes, latest, version, false)));
+                    }
+                    Err(err) => {
+                        return Some((Err(err), (watcher, changes, latest, last_version, false)))
+                    }
+                }
+            }
+        },
+    ))
+}
+
+/// Runs a retried operation with an optional overall wall-clock limit. If the limit is exceeded,
+/// the operation is dropped and the error produced by `on_timeout` is returned, regardless of
+/// how many retry attempts would have been left.
+async fn with_max_elapsed<R>(
+    max_elapsed: Option<Duration>,
+    action: impl Future<Output = R>,
+    on_timeout: impl FnOnce(Duration) -> GolemError,
+) -> Result<R, GolemError> {
+    match max_elapsed {
+        Some(max_elapsed) => tokio::time::timeout(max_elapsed, action)
+            .await
+            .map_err(|_| on_timeout(max_elapsed)),
+        None => Ok(action.await),
+    }
+}
+
+/// Verifies the detached Ed25519 signature of a component's raw bytes. A missing signature is
+/// treated the same way as an invalid one.
+fn verify_signature(
+    verification_key: &PublicKey,
+    signature: Option<&[u8]>,
+    bytes: &[u8],
+    component_id: &ComponentId,
+    component_version: ComponentVersion,
+) -> Result<(), GolemError> {
+    let signature = signature.ok_or_else(|| {
+        GolemError::component_signature_invalid(
+            component_id.clone(),
+            component_version,
+            "Missing signature",
+        )
+    })?;
+    UnparsedPublicKey::new(&ED25519, &verification_key.0)
+        .verify(bytes, signature)
+        .map_err(|_| {
+            GolemError::component_signature_invalid(
+                component_id.clone(),
+                component_version,
+                "Signature does not match the configured verification key",
+            )
+        })
 }
 
 fn grpc_component_download_error(
@@ -491,285 +1893,3496 @@ fn grpc_component_download_error(
     component_id: &ComponentId,
     component_version: ComponentVersion,
 ) -> GolemError {
-    GolemError::ComponentDownloadFailed {
-        component_id: component_id.clone(),
+    GolemError::component_download_failed(
+        component_id.clone(),
         component_version,
-        reason: format!("{}", error),
-    }
+        format!("{}", error),
+    )
 }
 
 fn grpc_get_latest_version_error(
     error: GrpcError<ComponentError>,
     component_id: &ComponentId,
 ) -> GolemError {
-    GolemError::GetLatestVersionOfComponentFailed {
-        component_id: component_id.clone(),
-        reason: format!("{}", error),
-    }
+    GolemError::get_latest_version_of_component_failed(component_id.clone(), format!("{}", error))
 }
 
-fn create_component_cache(
-    max_capacity: usize,
-    time_to_idle: Duration,
-) -> Cache<ComponentKey, (), Component, GolemError> {
-    Cache::new(
-        Some(max_capacity),
-        FullCacheEvictionMode::LeastRecentlyUsed(1),
-        BackgroundEvictionMode::OlderThan {
-            ttl: time_to_idle,
-            period: Duration::from_secs(60),
-        },
-        "component",
-    )
+/// The WASM of the most recently downloaded components, up to `max_size` bytes in total, so that
+/// recompiling a component whose compiled artifact turned out to be unusable (for example after
+/// an engine upgrade) does not download it again. The least recently used components are evicted
+/// first, and a component larger than `max_size` is not kept at all. Only WASMs which passed the
+/// checks done on download (such as the signature verification) are cached.
+struct DownloadedBytesCache {
+    max_size: u64,
+    entries: Mutex<DownloadedBytes>,
 }
 
-fn create_component_metadata_cache(
-    max_capacity: usize,
-    time_to_idle: Duration,
-) -> Cache<ComponentKey, (), ComponentMetadata, GolemError> {
-    Cache::new(
-        Some(max_capacity),
-        FullCacheEvictionMode::LeastRecentlyUsed(1),
-        BackgroundEvictionMode::OlderThan {
-            ttl: time_to_idle,
-            period: Duration::from_secs(60),
-        },
-        "component_metadata",
-    )
+#[derive(Default)]
+struct DownloadedBytes {
+    size: u64,
+    // Least recently used first
+    order: VecDeque<ComponentKey>,
+    bytes: HashMap<ComponentKey, Arc<Vec<u8>>>,
 }
 
-impl From<std::io::Error> for GolemError {
-    fn from(value: std::io::Error) -> Self {
-        GolemError::Unknown {
-            details: format!("{}", value),
+impl DownloadedBytesCache {
+    fn new(max_size: u64) -> Self {
+        Self {
+            max_size,
+            entries: Mutex::new(DownloadedBytes::default()),
+        }
+    }
+
+    fn configured(max_size: Option<u64>) -> Option<Arc<Self>> {
+        max_size
+            .filter(|max_size| *max_size > 0)
+            .map(|max_size| Arc::new(Self::new(max_size)))
+    }
+
+    fn get(&self, key: &ComponentKey) -> Option<Arc<Vec<u8>>> {
+        let mut entries = self.entries.lock().unwrap();
+        let bytes = entries.bytes.get(key).cloned()?;
+        entries.order.retain(|entry| entry != key);
+        entries.order.push_back(key.clone());
+        Some(bytes)
+    }
+
+    /// The cached WASM of the latest version of the component before the one of `key`
+    fn latest_before(&self, key: &ComponentKey) -> Option<(ComponentVersion, Arc<Vec<u8>>)> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .bytes
+            .iter()
+            .filter(|(cached, _)| {
+                cached.component_id == key.component_id
+                    && cached.component_version < key.component_version
+            })
+            .max_by_key(|(cached, _)| cached.component_version)
+            .map(|(cached, bytes)| (cached.component_version, bytes.clone()))
+    }
+
+    fn put(&self, key: ComponentKey, bytes: Arc<Vec<u8>>) {
+        let size = bytes.len() as u64;
+        if size > self.max_size {
+            return;
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+        if entries.bytes.contains_key(&key) {
+            return;
+        }
+        while entries.size + size > self.max_size {
+            let Some(evicted) = entries.order.pop_front() else {
+                break;
+            };
+            if let Some(evicted) = entries.bytes.remove(&evicted) {
+                entries.size -= evicted.len() as u64;
+            }
         }
+        entries.size += size;
+        entries.order.push_back(key.clone());
+        entries.bytes.insert(key, bytes);
     }
 }
 
-pub struct ComponentServiceLocalFileSystem {
-    root: PathBuf,
-    component_cache: Cache<ComponentKey, (), Component, GolemError>,
-    component_metadata_cache: Cache<ComponentKey, (), ComponentMetadata, GolemError>,
-    compiled_component_service: Arc<dyn CompiledComponentService + Send + Sync>,
+/// The in-memory cache of compiled components, with an admission policy based on the size of the
+/// components' WASM. Components above `large_component_threshold` go to a separate pool of
+/// `large_component_capacity` entries, so that a few giant components cannot evict the many small
+/// ones from the main cache. With a capacity of 0 they are not cached in memory at all, and are
+/// loaded from the compiled component store every time. The same goes for all the other
+/// components with a `max_capacity` of 0, disabling the main cache.
+struct ComponentCache {
+    main: Option<Cache<ComponentKey, (), Component, GolemError>>,
+    large: Option<Cache<ComponentKey, (), Component, GolemError>>,
+    large_component_threshold: Option<u64>,
 }
 
-impl ComponentServiceLocalFileSystem {
-    pub fn new(
-        root: &Path,
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ComponentCacheAdmission {
+    Main,
+    Large,
+    Bypass,
+}
+
+impl ComponentCacheAdmission {
+    fn label(&self) -> &'static str {
+        match self {
+            ComponentCacheAdmission::Main => "main",
+            ComponentCacheAdmission::Large => "large",
+            ComponentCacheAdmission::Bypass => "bypass",
+        }
+    }
+}
+
+impl ComponentCache {
+    fn new(
         max_capacity: usize,
-        max_metadata_capacity: usize,
         time_to_idle: Duration,
-        compiled_component_service: Arc<dyn CompiledComponentService + Send + Sync>,
+        large_component_threshold: Option<u64>,
+        large_component_capacity: usize,
     ) -> Self {
-        if !root.exists() {
-            std::fs::create_dir_all(root).expect("Failed to create local component store");
-        }
+        let cache = |capacity: usize, name: &'static str| {
+            Cache::new(
+                Some(capacity),
+                FullCacheEvictionMode::LeastRecentlyUsed(1),
+                BackgroundEvictionMode::OlderThan {
+                    ttl: time_to_idle,
+                    period: Duration::from_secs(60),
+                },
+                name,
+            )
+        };
         Self {
-            root: root.to_path_buf(),
-            component_cache: create_component_cache(max_capacity, time_to_idle),
-            component_metadata_cache: create_component_metadata_cache(
-                max_metadata_capacity,
-                time_to_idle,
-            ),
-            compiled_component_service,
+            main: (max_capacity > 0).then(|| cache(max_capacity, "component")),
+            large: (large_component_threshold.is_some() && large_component_capacity > 0)
+                .then(|| cache(large_component_capacity, "component_large")),
+            large_component_threshold,
         }
     }
 
-    async fn find_component_files(
-        &self,
-        component_id: &ComponentId,
-        forced_version: Option<ComponentVersion>,
-    ) -> Result<(ComponentVersion, PathBuf, PathBuf), GolemError> {
-        let prefix: String = format!("{}-", component_id);
-        let mut reader = tokio::fs::read_dir(&self.root).await?;
-        let mut matching_files = Vec::new();
-        while let Some(entry) = reader.next_entry().await? {
-            if let Ok(file_name) = entry.file_name().into_string() {
-                if file_name.starts_with(&prefix) && file_name.ends_with(".wasm") {
-                    // strip wasm extension
-                    let base_path = file_name[..file_name.len() - 5].to_string();
+    fn admission(&self, size: u64) -> ComponentCacheAdmission {
+        match self.large_component_threshold {
+            Some(threshold) if size > threshold => {
+                if self.large.is_some() {
+                    ComponentCacheAdmission::Large
+                } else {
+                    ComponentCacheAdmission::Bypass
+                }
+            }
+            _ if self.main.is_some() => ComponentCacheAdmission::Main,
+            _ => ComponentCacheAdmission::Bypass,
+        }
+    }
 
-                    if let Some(version) = Self::extract_version(&base_path) {
-                        matching_files.push((
-                            version,
-                            entry.path(),
-                            self.root.join(PathBuf::from(format!("{base_path}.json"))),
-                        ));
-                    };
-                };
-            };
+    fn remove(&self, key: &ComponentKey) {
+        if let Some(main) = &self.main {
+            main.remove(key);
+        }
+        if let Some(large) = &self.large {
+            large.remove(key);
         }
+    }
+}
 
-        match forced_version {
-            Some(forced_version) => matching_files
-                .into_iter()
-                .find(|(version, _, _)| *version == forced_version)
-                .ok_or(GolemError::GetLatestVersionOfComponentFailed {
-                    component_id: component_id.clone(),
-                    reason: "Could not find any component with the given id and version"
-                        .to_string(),
-                }),
+/// Gets the metadata from the cache, inserting it if missing. When metadata caching is
+/// disabled, the metadata is always loaded with `f`.
+async fn get_or_insert_metadata<F>(
+    cache: &Option<Arc<dyn ComponentMetadataCache + Send + Sync>>,
+    key: &ComponentKey,
+    f: F,
+) -> Result<ComponentMetadata, GolemError>
+where
+    F: FnOnce() -> Pin<Box<dyn Future<Output = Result<ComponentMetadata, GolemError>> + Send>>
+        + Send,
+{
+    match cache {
+        Some(cache) => {
+            cache
+                .get_or_insert(&key.component_id, key.component_version, f())
+                .await
+        }
+        None => f().await,
+    }
+}
+
+/// Gets the compiled component from the compiled component store, see
+/// `compiled_component_or_fallback`. With `force_recompile` the store is not read at all, so that
+/// the component gets compiled again, and its artifact in the store replaced.
+async fn get_compiled_component(
+    compiled_component_service: &Arc<dyn CompiledComponentService + Send + Sync>,
+    key: &ComponentKey,
+    engine: &Engine,
+    force_recompile: bool,
+) -> (Option<Component>, bool) {
+    if force_recompile {
+        info!("Recompiling {key:?} bypassing the compiled component store");
+        return (None, true);
+    }
+
+    let result = compiled_component_service
+        .get(&key.component_id, key.component_version, engine)
+        .await;
+    compiled_component_or_fallback(key, result)
+}
+
+/// Takes the result of getting a compiled component from the store, falling back to compiling
+/// the component if it could not be taken. Also returns whether the store was reachable: the
+/// recompiled component replaces an incompatible one in the store, but is not stored when the
+/// store is unreachable, as it would most likely fail again.
+fn compiled_component_or_fallback(
+    key: &ComponentKey,
+    result: Result<Option<Component>, CompiledComponentError>,
+) -> (Option<Component>, bool) {
+    match result {
+        Ok(component) => (component, true),
+        Err(err) => {
+            record_compiled_component_load_failure(err.kind());
+            match err {
+                CompiledComponentError::Incompatible { .. } => {
+                    warn!("Recompiling {:?}: {}", key, err);
+                    (None, true)
+                }
+                CompiledComponentError::StoreUnavailable { .. } => {
+                    warn!(
+                        "Compiling {:?} without the compiled component store: {}",
+                        key, err
+                    );
+                    (None, false)
+                }
+            }
+        }
+    }
+}
+
+/// Caches the latest version of each component for a fixed time after it was resolved.
+///
+/// Unlike the other component caches, entries expire based on when they were resolved and not
+/// on their last access, so a newly published version is picked up at most `ttl` later.
+///
+/// With a `soft_ttl`, entries older than it are still returned until `ttl`, while the latest
+/// version gets resolved again in the background, so that frequently used components do not wait
+/// for the component service every time their entry expires.
+struct LatestVersionCache {
+    cache: Cache<ComponentId, (), (ComponentVersion, Instant), GolemError>,
+    ttl: Duration,
+    soft_ttl: Option<Duration>,
+    refreshing: Arc<Mutex<HashSet<ComponentId>>>,
+    // Notified whenever a background refresh finished
+    refreshed: Arc<Notify>,
+}
+
+type ResolveLatestVersion =
+    Pin<Box<dyn Future<Output = Result<ComponentVersion, GolemError>> + Send>>;
+
+impl LatestVersionCache {
+    fn new(max_capacity: usize, ttl: Duration, soft_ttl: Option<Duration>) -> Self {
+        Self {
+            cache: Cache::new(
+                Some(max_capacity),
+                FullCacheEvictionMode::LeastRecentlyUsed(1),
+                BackgroundEvictionMode::OlderThan {
+                    ttl,
+                    period: Duration::from_secs(60),
+                },
+                "component_latest_version",
+            ),
+            ttl,
+            soft_ttl,
+            refreshing: Arc::new(Mutex::new(HashSet::new())),
+            refreshed: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Returns the cached latest version, if not older than `ttl`. When it is older than
+    /// `soft_ttl`, `resolve` is spawned to refresh it, unless a refresh of the component is
+    /// already running.
+    fn get(
+        &self,
+        component_id: &ComponentId,
+        resolve: impl FnOnce() -> ResolveLatestVersion,
+    ) -> Option<ComponentVersion> {
+        match self.cache.try_get(component_id) {
+            Some((version, resolved_at)) if resolved_at.elapsed() < self.ttl => {
+                if self
+                    .soft_ttl
+                    .is_some_and(|soft_ttl| resolved_at.elapsed() >= soft_ttl)
+                {
+                    self.refresh(component_id, resolve);
+                }
+                Some(version)
+            }
+            Some(_) => {
+                self.cache.remove(component_id);
+                None
+            }
+            None => None,
+        }
+    }
+
+    async fn put(&self, component_id: &ComponentId, version: ComponentVersion) {
+        Self::insert(&self.cache, component_id, version).await
+    }
+
+    fn refresh(&self, component_id: &ComponentId, resolve: impl FnOnce() -> ResolveLatestVersion) {
+        if !self.refreshing.lock().unwrap().insert(component_id.clone()) {
+            return;
+        }
+
+        let cache = self.cache.clone();
+        let refreshing = self.refreshing.clone();
+        let refreshed = self.refreshed.clone();
+        let component_id = component_id.clone();
+        let resolve = resolve();
+        tokio::spawn(async move {
+            match resolve.await {
+                Ok(version) => {
+                    // The stale entry has to be removed first, as inserting keeps existing ones
+                    cache.remove(&component_id);
+                    Self::insert(&cache, &component_id, version).await;
+                }
+                Err(err) => {
+                    warn!("Failed to refresh the latest version of component {component_id}: {err}")
+                }
+            }
+            refreshing.lock().unwrap().remove(&component_id);
+            refreshed.notify_one();
+        });
+    }
+
+    async fn insert(
+        cache: &Cache<ComponentId, (), (ComponentVersion, Instant), GolemError>,
+        component_id: &ComponentId,
+        version: ComponentVersion,
+    ) {
+        let _ = cache
+            .get_or_insert_simple(component_id, || {
+                Box::pin(async move { Ok((version, Instant::now())) })
+            })
+            .await;
+    }
+}
+
+/// Caches the version of a component found for a SHA-256 hash of its WASM. The content of a
+/// version never changes, so the entries only expire to bound the memory used.
+struct VersionsByHash {
+    cache: Cache<(ComponentId, String), (), ComponentVersion, GolemError>,
+}
+
+impl VersionsByHash {
+    fn new(max_capacity: usize, time_to_idle: Duration) -> Self {
+        Self {
+            // Results are kept even when the in-memory component cache is disabled
+            cache: Cache::new(
+                Some(max_capacity.max(1)),
+                FullCacheEvictionMode::LeastRecentlyUsed(1),
+                BackgroundEvictionMode::OlderThan {
+                    ttl: time_to_idle,
+                    period: Duration::from_secs(60),
+                },
+                "component_version_by_hash",
+            ),
+        }
+    }
+
+    /// Gets the cached version with the given hash, or finds it with `find`, which gets the
+    /// normalized hex encoded hash and returns `None` if no version matches
+    async fn resolve<F, Fut>(
+        &self,
+        component_id: &ComponentId,
+        sha256: &str,
+        find: F,
+    ) -> Result<ComponentVersion, GolemError>
+    where
+        F: FnOnce(String) -> Fut,
+        Fut: Future<Output = Result<Option<ComponentVersion>, GolemError>>,
+    {
+        let sha256 = normalize_sha256(sha256)?;
+        let key = (component_id.clone(), sha256.clone());
+        if let Some(version) = self.cache.try_get(&key) {
+            return Ok(version);
+        }
+
+        let version = find(sha256.clone()).await?.ok_or_else(|| {
+            GolemError::get_latest_version_of_component_failed(
+                component_id.clone(),
+                format!("No version of the component has the SHA-256 hash {sha256}"),
+            )
+        })?;
+        let _ = self
+            .cache
+            .get_or_insert_simple(&key, || Box::pin(async move { Ok(version) }))
+            .await;
+        Ok(version)
+    }
+}
+
+/// The outcome of the last compilation of a component version on this executor
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentCompileResult {
+    /// The reason of the failure, or `None` if the component compiled successfully
+    pub error: Option<String>,
+    pub duration: Duration,
+}
+
+/// Keeps the result of the last compilation of each component version, so that compilation
+/// failures can be looked up on each executor. Versions loaded from the compiled component store
+/// or from the in-memory cache are not compiled again, and keep their previous result.
+#[derive(Clone)]
+struct CompileResults {
+    cache: Cache<ComponentKey, (), ComponentCompileResult, ()>,
+}
+
+impl CompileResults {
+    fn new(max_capacity: usize, time_to_idle: Duration) -> Self {
+        Self {
+            // Results are kept even when the in-memory component cache is disabled
+            cache: Cache::new(
+                Some(max_capacity.max(1)),
+                FullCacheEvictionMode::LeastRecentlyUsed(1),
+                BackgroundEvictionMode::OlderThan {
+                    ttl: time_to_idle,
+                    period: Duration::from_secs(60),
+                },
+                "component_compile_result",
+            ),
+        }
+    }
+
+    fn get(
+        &self,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+    ) -> Option<ComponentCompileResult> {
+        self.cache.try_get(&ComponentKey {
+            component_id: component_id.clone(),
+            component_version,
+        })
+    }
+
+    async fn record(
+        &self,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+        result: &Result<Component, GolemError>,
+        duration: Duration,
+    ) {
+        let key = ComponentKey {
+            component_id: component_id.clone(),
+            component_version,
+        };
+        let compile_result = ComponentCompileResult {
+            error: result.as_ref().err().map(|err| err.to_string()),
+            duration,
+        };
+        self.cache.remove(&key);
+        let _ = self
+            .cache
+            .get_or_insert_simple(&key, || Box::pin(async move { Ok(compile_result) }))
+            .await;
+    }
+}
+
+/// Accepts a hex encoded SHA-256 hash, optionally prefixed with `sha256:`, and returns it in
+/// lowercase
+fn normalize_sha256(sha256: &str) -> Result<String, GolemError> {
+    let hex = sha256.strip_prefix("sha256:").unwrap_or(sha256);
+    if hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(hex.to_ascii_lowercase())
+    } else {
+        Err(GolemError::invalid_request(format!(
+            "Invalid SHA-256 hash of a component: {sha256}"
+        )))
+    }
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(ring::digest::digest(&ring::digest::SHA256, bytes))
+}
+
+impl From<std::io::Error> for GolemError {
+    fn from(value: std::io::Error) -> Self {
+        GolemError::Unknown {
+            details: format!("{}", value),
+        }
+    }
+}
+
+pub struct ComponentServiceLocalFileSystem {
+    root: PathBuf,
+    props_root: PathBuf,
+    component_cache: ComponentCache,
+    component_metadata_cache: Option<Arc<dyn ComponentMetadataCache + Send + Sync>>,
+    compiled_component_service: Arc<dyn CompiledComponentService + Send + Sync>,
+    verification_key: Option<PublicKey>,
+    metadata_transformer: Option<MetadataTransformer>,
+    loads: Arc<ComponentLoadTracker>,
+    compile_permits: Arc<Semaphore>,
+    compile_results: CompileResults,
+    file_loader: Arc<FileLoader>,
+    max_concurrent_file_prefetches: usize,
+    reject_empty_exports: bool,
+    recover_missing_props: bool,
+    versions_by_hash: VersionsByHash,
+    /// Serializes `store`, so that components stored at the same time get different versions
+    store_lock: tokio::sync::Mutex<()>,
+}
+
+impl ComponentServiceLocalFileSystem {
+    pub fn new(
+        root: &Path,
+        props_root: Option<&Path>,
+        cache: ComponentCacheOptions,
+        metadata_cache: Option<Arc<dyn ComponentMetadataCache + Send + Sync>>,
+        compiled_component_service: Arc<dyn CompiledComponentService + Send + Sync>,
+        verification_key: Option<PublicKey>,
+        metadata_transformer: Option<MetadataTransformer>,
+        file_loader: Arc<FileLoader>,
+        recover_missing_props: bool,
+    ) -> Self {
+        if !root.exists() {
+            std::fs::create_dir_all(root).expect("Failed to create local component store");
+        }
+        Self {
+            root: root.to_path_buf(),
+            props_root: props_root.unwrap_or(root).to_path_buf(),
+            component_cache: ComponentCache::new(
+                cache.max_capacity,
+                cache.time_to_idle,
+                cache.large_component_threshold,
+                cache.large_component_capacity,
+            ),
+            component_metadata_cache: metadata_cache,
+            compiled_component_service,
+            verification_key,
+            metadata_transformer,
+            loads: ComponentLoadTracker::new(),
+            compile_permits: Arc::new(Semaphore::new(cache.max_concurrent_compiles)),
+            compile_results: CompileResults::new(cache.max_capacity, cache.time_to_idle),
+            file_loader,
+            max_concurrent_file_prefetches: cache.max_concurrent_file_prefetches,
+            reject_empty_exports: cache.reject_empty_exports,
+            recover_missing_props,
+            versions_by_hash: VersionsByHash::new(cache.max_capacity, cache.time_to_idle),
+            store_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Finds the version with the given hash by reading the stored versions from the latest one
+    /// down. Versions missing from the store are skipped.
+    async fn find_version_by_hash(
+        &self,
+        component_id: &ComponentId,
+        sha256: String,
+    ) -> Result<Option<ComponentVersion>, GolemError> {
+        let Some(latest) = Self::latest_version_in(&self.root, component_id).await? else {
+            return Ok(None);
+        };
+        for version in (0..=latest).rev() {
+            let (version, wasm_path, props_path) =
+                match self.find_component_files(component_id, Some(version)).await {
+                    Ok(files) => files,
+                    Err(GolemError::GetLatestVersionOfComponentFailed { .. }) => continue,
+                    Err(err) => return Err(err),
+                };
+            let bytes = Self::read_component_bytes(
+                self.root.clone(),
+                self.props_root.clone(),
+                component_id.clone(),
+                version,
+                wasm_path,
+                props_path,
+            )
+            .await?;
+            if sha256_hex(&bytes) == sha256 {
+                return Ok(Some(version));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn find_component_files(
+        &self,
+        component_id: &ComponentId,
+        forced_version: Option<ComponentVersion>,
+    ) -> Result<(ComponentVersion, PathBuf, PathBuf), GolemError> {
+        Self::find_component_files_in(
+            &self.root,
+            &self.props_root,
+            component_id,
+            forced_version,
+            !self.recover_missing_props,
+        )
+        .await
+    }
+
+    /// Watches the WASM and properties files of a component, signalling every change of them
+    fn watch_component_files(
+        &self,
+        component_id: &ComponentId,
+    ) -> notify::Result<(RecommendedWatcher, mpsc::UnboundedReceiver<()>)> {
+        let prefix = format!("{component_id}-");
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                // After an error of the watcher the component is looked up anyway, as a change may
+                // have been missed
+                let changed = match event {
+                    Ok(event) => event.paths.iter().any(|path| {
+                        path.file_name()
+                            .and_then(|file_name| file_name.to_str())
+                            .is_some_and(|file_name| file_name.starts_with(&prefix))
+                    }),
+                    Err(_) => true,
+                };
+                if changed {
+                    let _ = sender.send(());
+                }
+            })?;
+
+        watcher.watch(&self.root, RecursiveMode::NonRecursive)?;
+        if self.props_root != self.root {
+            watcher.watch(&self.props_root, RecursiveMode::NonRecursive)?;
+        }
+
+        Ok((watcher, receiver))
+    }
+
+    /// Finds the WASM (or patch, see [`Self::read_component_bytes`]) and properties file of a
+    /// component version
+    async fn find_component_files_in(
+        root: &Path,
+        props_root: &Path,
+        component_id: &ComponentId,
+        forced_version: Option<ComponentVersion>,
+        require_props: bool,
+    ) -> Result<(ComponentVersion, PathBuf, PathBuf), GolemError> {
+        let prefix: String = format!("{}-", component_id);
+        let mut reader = tokio::fs::read_dir(root).await?;
+        let mut matching_files = Vec::new();
+        while let Some(entry) = reader.next_entry().await? {
+            if let Ok(file_name) = entry.file_name().into_string() {
+                if !file_name.starts_with(&prefix) {
+                    continue;
+                }
+                // strip wasm or patch extension
+                let base_path = file_name
+                    .strip_suffix(".wasm")
+                    .or_else(|| file_name.strip_suffix(COMPONENT_PATCH_SUFFIX));
+
+                if let Some(base_path) = base_path {
+                    if let Some(version) = Self::extract_version(base_path) {
+                        matching_files.push((
+                            version,
+                            entry.path(),
+                            props_root.join(PathBuf::from(format!("{base_path}.json"))),
+                        ));
+                    };
+                };
+            };
+        }
+
+        Self::check_duplicate_versions(component_id, &matching_files)?;
+
+        let (version, wasm_path, props_path) = match forced_version {
+            Some(forced_version) => matching_files
+                .into_iter()
+                .find(|(version, _, _)| *version == forced_version)
+                .ok_or(GolemError::get_latest_version_of_component_failed(
+                    component_id.clone(),
+                    "Could not find any component with the given id and version",
+                )),
             None => matching_files
                 .into_iter()
                 .max_by_key(|(version, _, _)| *version)
-                .ok_or(GolemError::GetLatestVersionOfComponentFailed {
-                    component_id: component_id.clone(),
-                    reason: "Could not find any component with the given id".to_string(),
-                }),
+                .ok_or(GolemError::get_latest_version_of_component_failed(
+                    component_id.clone(),
+                    "Could not find any component with the given id",
+                )),
+        }?;
+
+        if require_props && !tokio::fs::try_exists(&props_path).await? {
+            return Err(GolemError::get_latest_version_of_component_failed(
+                component_id.clone(),
+                format!(
+                    "Properties file of component version {version} not found at {}",
+                    props_path.display()
+                ),
+            ));
+        }
+
+        Ok((version, wasm_path, props_path))
+    }
+
+    /// Fails if more than one file resolves to the same version (for example `foo-1.wasm` and
+    /// `foo-01.wasm`), instead of serving an arbitrary one of them
+    fn check_duplicate_versions(
+        component_id: &ComponentId,
+        matching_files: &[(ComponentVersion, PathBuf, PathBuf)],
+    ) -> Result<(), GolemError> {
+        let mut files_by_version: BTreeMap<ComponentVersion, Vec<&PathBuf>> = BTreeMap::new();
+        for (version, wasm_path, _) in matching_files {
+            files_by_version
+                .entry(*version)
+                .or_default()
+                .push(wasm_path);
+        }
+
+        let conflicts = files_by_version
+            .into_iter()
+            .filter(|(_, paths)| paths.len() > 1)
+            .map(|(version, mut paths)| {
+                paths.sort();
+                format!(
+                    "version {version}: {}",
+                    paths
+                        .iter()
+                        .map(|path| path.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if conflicts.is_empty() {
+            Ok(())
+        } else {
+            Err(GolemError::get_latest_version_of_component_failed(
+                component_id.clone(),
+                format!(
+                    "Multiple files resolve to the same component version ({})",
+                    conflicts.join("; ")
+                ),
+            ))
         }
     }
 
+    /// Reads the WASM of a component version. Versions stored as a `.patch` file are rebuilt by
+    /// applying the bsdiff patch to the WASM of the base version given in their properties, which
+    /// can itself be stored as a patch.
+    fn read_component_bytes(
+        root: PathBuf,
+        props_root: PathBuf,
+        component_id: ComponentId,
+        component_version: ComponentVersion,
+        wasm_path: PathBuf,
+        props_path: PathBuf,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<u8>, GolemError>> + Send>> {
+        Box::pin(async move {
+            let bytes = tokio::fs::read(&wasm_path).await?;
+            if !is_component_patch(&wasm_path) {
+                return Ok(bytes);
+            }
+
+            let patch_error = |reason: String| GolemError::ComponentParseFailed {
+                component_id: component_id.clone(),
+                component_version,
+                reason,
+            };
+
+            let props = tokio::fs::read_to_string(&props_path).await?;
+            let metadata = parse_props_file(&props_path, &props).map_err(patch_error)?;
+            let base_version = match metadata.base_version {
+                Some(base_version) if base_version < component_version => base_version,
+                Some(base_version) => {
+                    return Err(patch_error(format!(
+                        "Base version {base_version} of the patch must be an earlier version"
+                    )))
+                }
+                None => {
+                    return Err(patch_error(
+                        "Component is stored as a patch, but has no base version".to_string(),
+                    ))
+                }
+            };
+
+            let (_, base_wasm_path, base_props_path) = Self::find_component_files_in(
+                &root,
+                &props_root,
+                &component_id,
+                Some(base_version),
+                true,
+            )
+            .await?;
+            let base = Self::read_component_bytes(
+                root,
+                props_root,
+                component_id.clone(),
+                base_version,
+                base_wasm_path,
+                base_props_path,
+            )
+            .await?;
+
+            patched_component(
+                &base,
+                &bytes,
+                &component_id,
+                component_version,
+                base_version,
+            )
+        })
+    }
+
     async fn get_component_from_path(
         &self,
         wasm_path: &Path,
+        props_path: &Path,
         engine: &Engine,
         component_id: &ComponentId,
         component_version: ComponentVersion,
+        size: u64,
+        force_recompile: bool,
+        in_flight: InFlightComponentLoad,
     ) -> Result<Component, GolemError> {
         let key = ComponentKey {
             component_id: component_id.clone(),
             component_version,
         };
+        if force_recompile {
+            self.component_cache.remove(&key);
+        }
         let component_id = component_id.clone();
         let engine = engine.clone();
         let compiled_component_service = self.compiled_component_service.clone();
+        let compile_permits = self.compile_permits.clone();
+        let compile_results = self.compile_results.clone();
+        let verification_key = self.verification_key.clone();
         let path = wasm_path.to_path_buf();
+        let props_path = props_path.to_path_buf();
+        let root = self.root.clone();
+        let props_root = self.props_root.clone();
 
-        self.component_cache
-            .get_or_insert_simple(&key.clone(), || {
-                Box::pin(async move {
-                    let result = compiled_component_service
-                        .get(&component_id, component_version, &engine)
-                        .await;
-
-                    let component = match result {
-                        Ok(component) => component,
-                        Err(err) => {
-                            warn!("Failed to download compiled component {:?}: {}", key, err);
-                            None
-                        }
-                    };
-
-                    match component {
-                        Some(component) => Ok(component),
-                        None => {
-                            let bytes = tokio::fs::read(path).await?;
-
-                            let start = Instant::now();
-                            let component =
-                                Component::from_binary(&engine, &bytes).map_err(|e| {
-                                    GolemError::ComponentParseFailed {
-                                        component_id: component_id.clone(),
-                                        component_version,
-                                        reason: format!("{}", e),
-                                    }
-                                })?;
-                            let end = Instant::now();
-
-                            let compilation_time = end.duration_since(start);
-                            record_compilation_time(compilation_time);
-                            debug!(
-                                "Compiled {} in {}ms",
-                                component_id,
-                                compilation_time.as_millis(),
-                            );
+        get_or_load_component(
+            &self.component_cache,
+            &key.clone(),
+            size,
+            "local",
+            in_flight,
+            || {
+                Box::pin(load_uncached_component(
+                    "local",
+                    key,
+                    size,
+                    engine,
+                    force_recompile,
+                    compiled_component_service,
+                    compile_permits,
+                    compile_results,
+                    move || async move {
+                        let start = Instant::now();
+                        let bytes = Self::read_component_bytes(
+                            root,
+                            props_root,
+                            component_id.clone(),
+                            component_version,
+                            path.clone(),
+                            props_path,
+                        )
+                        .await?;
+                        record_component_download("local", bytes.len(), start.elapsed());
 
-                            let result = compiled_component_service
-                                .put(&component_id, component_version, &component)
-                                .await;
-
-                            match result {
-                                Ok(_) => Ok(component),
-                                Err(err) => {
-                                    warn!("Failed to upload compiled component {:?}: {}", key, err);
-                                    Ok(component)
-                                }
-                            }
+                        if let Some(verification_key) = &verification_key {
+                            let signature = match tokio::fs::read(path.with_extension("sig")).await
+                            {
+                                Ok(signature) => Some(signature),
+                                Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+                                Err(err) => return Err(err.into()),
+                            };
+                            verify_signature(
+                                verification_key,
+                                signature.as_deref(),
+                                &bytes,
+                                &component_id,
+                                component_version,
+                            )?;
                         }
-                    }
-                })
-            })
-            .await
+
+                        reject_core_wasm_module(&bytes, &component_id, component_version)?;
+                        Ok(Arc::new(bytes))
+                    },
+                ))
+            },
+        )
+        .await
     }
 
+    /// Reads the metadata from the properties file. If the WASM embeds an initial files manifest
+    /// in a custom section, it is used instead of the files listed in the properties file.
+    ///
+    /// With `recover_missing_props` set, a missing properties file is replaced by the metadata
+    /// found by analysing the WASM, as a durable component without annotations.
     async fn get_metadata_from_path(
         &self,
+        wasm_path: &Path,
         props_path: &Path,
         component_id: &ComponentId,
         component_version: ComponentVersion,
     ) -> Result<ComponentMetadata, GolemError> {
         let component_id = component_id.clone();
+        let wasm_path = PathBuf::from(wasm_path);
         let props_path = PathBuf::from(props_path);
+        let metadata_transformer = self.metadata_transformer.clone();
+        let reject_empty_exports = self.reject_empty_exports;
+        let root = self.root.clone();
+        let props_root = self.props_root.clone();
+        let recover_missing_props = self.recover_missing_props;
 
         let key = ComponentKey {
             component_id: component_id.clone(),
             component_version,
         };
 
-        self.component_metadata_cache
-            .get_or_insert_simple(&key, || {
-                Box::pin(async move {
-                    let data = tokio::fs::read_to_string(props_path).await.map_err(|e| {
-                        GolemError::GetLatestVersionOfComponentFailed {
-                            component_id: component_id.clone(),
-                            reason: format!("Failed to read properties of component: {}", e),
-                        }
-                    })?;
+        get_or_insert_metadata(&self.component_metadata_cache, &key, || {
+            Box::pin(async move {
+                if recover_missing_props && !tokio::fs::try_exists(&props_path).await? {
+                    let metadata = Self::recover_metadata(
+                        &component_id,
+                        component_version,
+                        &wasm_path,
+                        &props_path,
+                    )
+                    .await?;
+                    return prepare_metadata(
+                        &metadata_transformer,
+                        reject_empty_exports,
+                        &component_id,
+                        metadata,
+                    );
+                }
 
-                    serde_json::from_str(&data).map_err(|e| {
-                        GolemError::GetLatestVersionOfComponentFailed {
-                            component_id: component_id.clone(),
-                            reason: format!("Failed to read properties of component: {}", e),
-                        }
+                let data = tokio::fs::read_to_string(&props_path).await.map_err(|e| {
+                    GolemError::get_latest_version_of_component_failed(
+                        component_id.clone(),
+                        format!("Failed to read properties of component: {}", e),
+                    )
+                })?;
+
+                let mut metadata = parse_props_file(&props_path, &data).map_err(|reason| {
+                    GolemError::get_latest_version_of_component_failed(component_id.clone(), reason)
+                })?;
+
+                let embedded_files = if is_component_patch(&wasm_path) {
+                    let wasm = Self::read_component_bytes(
+                        root,
+                        props_root,
+                        component_id.clone(),
+                        component_version,
+                        wasm_path,
+                        props_path,
+                    )
+                    .await?;
+                    initial_files_from_custom_section(&wasm)
+                } else {
+                    // Only the section headers of the WASM are read here, the component itself
+                    // is loaded when it actually gets compiled
+                    spawn_blocking(move || {
+                        let mut wasm = BufReader::new(std::fs::File::open(&wasm_path)?);
+                        Ok::<_, std::io::Error>(initial_files_from_custom_section_reader(&mut wasm))
                     })
-                })
-            })
-            .await
-    }
+                    .await
+                    .map_err(|join_err| GolemError::unknown(join_err.to_string()))??
+                }
+                .map_err(|reason| GolemError::ComponentParseFailed {
+                    component_id: component_id.clone(),
+                    component_version,
+                    reason,
+                })?;
+                if let Some(files) = embedded_files {
+                    metadata.files = files;
+                }
 
-    fn extract_version(file_name: &str) -> Option<ComponentVersion> {
-        let version_part = file_name.split('-').last()?;
-        version_part.parse::<u64>().ok()
+                prepare_metadata(
+                    &metadata_transformer,
+                    reject_empty_exports,
+                    &component_id,
+                    metadata,
+                )
+            })
+        })
+        .await
     }
-}
 
-#[async_trait]
-impl ComponentService for ComponentServiceLocalFileSystem {
-    async fn get(
-        &self,
-        engine: &Engine,
+    /// Builds the metadata of a component version whose properties file is missing by analysing
+    /// its WASM. Versions stored as patches cannot be recovered, as their base version is only
+    /// known from the properties file.
+    async fn recover_metadata(
         component_id: &ComponentId,
         component_version: ComponentVersion,
-    ) -> Result<(Component, ComponentMetadata), GolemError> {
-        let (version, wasm_path, props_path) = self
-            .find_component_files(component_id, Some(component_version))
-            .await?;
+        wasm_path: &Path,
+        props_path: &Path,
+    ) -> Result<ComponentMetadata, GolemError> {
+        if is_component_patch(wasm_path) {
+            return Err(GolemError::get_latest_version_of_component_failed(
+                component_id.clone(),
+                format!(
+                    "Properties file of component version {component_version} not found at {}, \
+                     and it cannot be recovered for a patch",
+                    props_path.display()
+                ),
+            ));
+        }
 
-        let component = self
-            .get_component_from_path(&wasm_path, engine, component_id, version)
-            .await?;
-        let metadata = self
-            .get_metadata_from_path(&props_path, component_id, version)
-            .await?;
-        Ok((component, metadata))
+        warn!(
+            "Properties file of component {component_id} version {component_version} not found at {}, recovering the metadata from the WASM",
+            props_path.display()
+        );
+        let bytes = tokio::fs::read(wasm_path).await?;
+        let props = spawn_blocking(move || {
+            LocalComponentProps::from_wasm(component_version, ComponentType::Durable, &bytes)
+        })
+        .await
+        .map_err(|join_err| GolemError::unknown(join_err.to_string()))?
+        .map_err(|reason| GolemError::ComponentParseFailed {
+            component_id: component_id.clone(),
+            component_version,
+            reason,
+        })?;
+
+        Ok(props.into())
     }
 
-    async fn get_metadata(
+    /// Stores the WASM as the next version of a component, with a properties file generated by
+    /// analysing it, and returns the assigned version. Components stored this way don't have
+    /// initial files, unless embedded in the WASM.
+    pub async fn store(
         &self,
         component_id: &ComponentId,
-        forced_version: Option<ComponentVersion>,
-    ) -> Result<ComponentMetadata, GolemError> {
-        let (version, _, props_path) = self
-            .find_component_files(component_id, forced_version)
+        component_type: ComponentType,
+        bytes: Vec<u8>,
+    ) -> Result<ComponentVersion, GolemError> {
+        let _store_guard = self.store_lock.lock().await;
+        let version = match Self::latest_version_in(&self.root, component_id).await? {
+            Some(latest) => latest + 1,
+            None => 0,
+        };
+
+        let props =
+            LocalComponentProps::from_wasm(version, component_type, &bytes).map_err(|reason| {
+                GolemError::ComponentParseFailed {
+                    component_id: component_id.clone(),
+                    component_version: version,
+                    reason,
+                }
+            })?;
+
+        // The properties file is written first, so the version is never found without it, and
+        // both are renamed into place so that no reader sees them partially written
+        let base_path = format!("{component_id}-{version}");
+        tokio::fs::create_dir_all(&self.props_root).await?;
+        props
+            .write(&self.props_root.join(format!("{base_path}.json")))
             .await?;
-        self.get_metadata_from_path(&props_path, component_id, version)
+        write_atomically(&self.root.join(format!("{base_path}.wasm")), bytes).await?;
+
+        let key = ComponentKey {
+            component_id: component_id.clone(),
+            component_version: version,
+        };
+        self.component_cache.remove(&key);
+        if let Some(cache) = &self.component_metadata_cache {
+            cache.remove(component_id, version).await;
+        }
+
+        info!("Stored version {version} of component {component_id} in the local store");
+        Ok(version)
+    }
+
+    async fn latest_version_in(
+        root: &Path,
+        component_id: &ComponentId,
+    ) -> Result<Option<ComponentVersion>, GolemError> {
+        let prefix = format!("{}-", component_id);
+        let mut reader = tokio::fs::read_dir(root).await?;
+        let mut latest = None;
+        while let Some(entry) = reader.next_entry().await? {
+            if let Ok(file_name) = entry.file_name().into_string() {
+                let version = file_name
+                    .strip_prefix(&prefix)
+                    .and_then(|_| {
+                        file_name
+                            .strip_suffix(".wasm")
+                            .or_else(|| file_name.strip_suffix(COMPONENT_PATCH_SUFFIX))
+                    })
+                    .and_then(Self::extract_version);
+                latest = latest.max(version);
+            }
+        }
+        Ok(latest)
+    }
+
+    fn extract_version(file_name: &str) -> Option<ComponentVersion> {
+        let version_part = file_name.split('-').last()?;
+        version_part.parse::<u64>().ok()
+    }
+}
+
+#[async_trait]
+impl ComponentService for ComponentServiceLocalFileSystem {
+    async fn get(
+        &self,
+        engine: &Engine,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+    ) -> Result<(Component, ComponentMetadata), GolemError> {
+        let in_flight = self.loads.start()?;
+        let (version, wasm_path, props_path) = self
+            .find_component_files(component_id, Some(component_version))
+            .await?;
+
+        let metadata = self
+            .get_metadata_from_path(&wasm_path, &props_path, component_id, version)
+            .await?;
+        let component = self
+            .get_component_from_path(
+                &wasm_path,
+                &props_path,
+                engine,
+                component_id,
+                version,
+                metadata.size,
+                false,
+                in_flight,
+            )
+            .await?;
+        Ok((component, metadata))
+    }
+
+    async fn get_by_hash(
+        &self,
+        engine: &Engine,
+        component_id: &ComponentId,
+        sha256: &str,
+    ) -> Result<(Component, ComponentMetadata), GolemError> {
+        let version = self
+            .versions_by_hash
+            .resolve(component_id, sha256, |sha256| {
+                self.find_version_by_hash(component_id, sha256)
+            })
+            .await?;
+        self.get(engine, component_id, version).await
+    }
+
+    async fn ensure_cached(
+        &self,
+        engine: &Engine,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+        force_recompile: bool,
+    ) -> Result<(), GolemError> {
+        let in_flight = self.loads.start()?;
+        let (version, wasm_path, props_path) = self
+            .find_component_files(component_id, Some(component_version))
+            .await?;
+        let metadata = self
+            .get_metadata_from_path(&wasm_path, &props_path, component_id, version)
+            .await?;
+        self.get_component_from_path(
+            &wasm_path,
+            &props_path,
+            engine,
+            component_id,
+            version,
+            metadata.size,
+            force_recompile,
+            in_flight,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn prefetch_files(
+        &self,
+        account_id: &AccountId,
+        metadata: &ComponentMetadata,
+    ) -> Result<PrefetchedFiles, GolemError> {
+        self.file_loader
+            .prefetch(
+                account_id,
+                &metadata.files,
+                self.max_concurrent_file_prefetches,
+            )
+            .await
+    }
+
+    async fn shutdown(&self, grace_period: Duration) {
+        self.loads.shutdown(grace_period).await;
+    }
+
+    fn compile_result(
+        &self,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+    ) -> Option<ComponentCompileResult> {
+        self.compile_results.get(component_id, component_version)
+    }
+
+    fn watch_metadata<'a>(
+        &'a self,
+        component_id: &'a ComponentId,
+        poll_interval: Duration,
+    ) -> BoxStream<'a, Result<ComponentMetadata, GolemError>> {
+        match self.watch_component_files(component_id) {
+            Ok((watcher, changes)) => {
+                watch_latest_metadata_on_changes(watcher, changes, move || {
+                    self.get_metadata(component_id, None)
+                })
+            }
+            Err(err) => {
+                warn!(
+                    "Failed to watch the files of component {component_id}, polling every {poll_interval:?} instead: {err}"
+                );
+                watch_latest_metadata(poll_interval, move || self.get_metadata(component_id, None))
+            }
+        }
+    }
+
+    async fn is_latest(
+        &self,
+        component_id: &ComponentId,
+        version: ComponentVersion,
+    ) -> Result<bool, GolemError> {
+        let (latest, _, _) = self.find_component_files(component_id, None).await?;
+        Ok(latest == version)
+    }
+
+    async fn get_metadata(
+        &self,
+        component_id: &ComponentId,
+        forced_version: Option<ComponentVersion>,
+    ) -> Result<ComponentMetadata, GolemError> {
+        let (version, wasm_path, props_path) = self
+            .find_component_files(component_id, forced_version)
+            .await?;
+        self.get_metadata_from_path(&wasm_path, &props_path, component_id, version)
+            .await
+    }
+
+    async fn get_raw_metadata(
+        &self,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+    ) -> Result<RawComponentMetadata, GolemError> {
+        let (version, wasm_path, props_path) = self
+            .find_component_files(component_id, Some(component_version))
+            .await?;
+        let wasm = Self::read_component_bytes(
+            self.root.clone(),
+            self.props_root.clone(),
+            component_id.clone(),
+            version,
+            wasm_path,
+            props_path,
+        )
+        .await?;
+
+        spawn_blocking(move || RawComponentMetadata::analyse_component(&wasm))
             .await
+            .map_err(|join_err| GolemError::unknown(join_err.to_string()))?
+            .map_err(|err| GolemError::ComponentParseFailed {
+                component_id: component_id.clone(),
+                component_version: version,
+                reason: err.to_string(),
+            })
     }
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct ComponentProperties {
-    pub component_type: ComponentType,
-    pub files: Vec<InitialComponentFile>,
+/// Annotation of the OCI manifests holding the component version a tag refers to
+pub const OCI_COMPONENT_VERSION_ANNOTATION: &str = "cloud.golem.component.version";
+pub const OCI_COMPONENT_LAYER_MEDIA_TYPE: &str = "application/wasm";
+/// Layer of the artifacts holding a version as a bsdiff patch of the base version named in their
+/// metadata, instead of the component layer
+pub const OCI_COMPONENT_PATCH_LAYER_MEDIA_TYPE: &str =
+    "application/vnd.golem.component.patch.v1+bsdiff";
+pub const OCI_METADATA_LAYER_MEDIA_TYPE: &str = "application/vnd.golem.component.metadata.v1+json";
+/// Layer of the artifacts holding the detached Ed25519 signature of the component's WASM
+pub const OCI_SIGNATURE_LAYER_MEDIA_TYPE: &str = "application/vnd.golem.component.signature.v1";
+
+pub struct ComponentServiceOci {
+    client: Arc<OciRegistryClient>,
+    repository: String,
+    retry_config: RetryConfig,
+    verification_key: Option<PublicKey>,
+    manifests: OciManifests,
+    component_cache: ComponentCache,
+    downloaded_bytes_cache: Option<Arc<DownloadedBytesCache>>,
+    component_metadata_cache: Option<Arc<dyn ComponentMetadataCache + Send + Sync>>,
+    latest_version_cache: Option<LatestVersionCache>,
+    compiled_component_service: Arc<dyn CompiledComponentService + Send + Sync>,
+    metadata_transformer: Option<MetadataTransformer>,
+    loads: Arc<ComponentLoadTracker>,
+    compile_permits: Arc<Semaphore>,
+    compile_results: CompileResults,
+    file_loader: Arc<FileLoader>,
+    max_concurrent_file_prefetches: usize,
+    reject_empty_exports: bool,
+    versions_by_hash: VersionsByHash,
+}
+
+impl ComponentServiceOci {
+    pub fn new(
+        registry: Url,
+        repository: &str,
+        auth: Option<OciAuthConfig>,
+        download: ComponentDownloadOptions,
+        max_elapsed: Option<Duration>,
+        max_component_size: usize,
+        cache: ComponentCacheOptions,
+        metadata_cache: Option<Arc<dyn ComponentMetadataCache + Send + Sync>>,
+        compiled_component_service: Arc<dyn CompiledComponentService + Send + Sync>,
+        metadata_transformer: Option<MetadataTransformer>,
+        file_loader: Arc<FileLoader>,
+    ) -> Self {
+        Self {
+            client: Arc::new(OciRegistryClient::new(
+                registry,
+                auth,
+                download.download_throttle,
+                max_component_size,
+                max_elapsed,
+            )),
+            repository: repository.trim_end_matches('/').to_string(),
+            retry_config: download.retry_config,
+            verification_key: download.verification_key,
+            manifests: OciManifests::new(cache.max_metadata_capacity, cache.time_to_idle),
+            component_cache: ComponentCache::new(
+                cache.max_capacity,
+                cache.time_to_idle,
+                cache.large_component_threshold,
+                cache.large_component_capacity,
+            ),
+            downloaded_bytes_cache: DownloadedBytesCache::configured(
+                cache.max_downloaded_bytes_cache_size,
+            ),
+            component_metadata_cache: metadata_cache,
+            latest_version_cache: cache.latest_version_ttl.map(|ttl| {
+                LatestVersionCache::new(
+                    cache.max_metadata_capacity,
+                    ttl,
+                    cache.latest_version_soft_ttl,
+                )
+            }),
+            compiled_component_service,
+            metadata_transformer,
+            loads: ComponentLoadTracker::new(),
+            compile_permits: Arc::new(Semaphore::new(cache.max_concurrent_compiles)),
+            compile_results: CompileResults::new(cache.max_capacity, cache.time_to_idle),
+            file_loader,
+            max_concurrent_file_prefetches: cache.max_concurrent_file_prefetches,
+            reject_empty_exports: cache.reject_empty_exports,
+            versions_by_hash: VersionsByHash::new(cache.max_metadata_capacity, cache.time_to_idle),
+        }
+    }
+
+    fn component_repository(&self, component_id: &ComponentId) -> String {
+        format!("{}/{}", self.repository, component_id)
+    }
+
+    /// The latest version of the component in the latest version cache
+    fn cached_latest_version(&self, component_id: &ComponentId) -> Option<ComponentVersion> {
+        self.latest_version_cache.as_ref()?.get(component_id, || {
+            let client = self.client.clone();
+            let manifests = self.manifests.clone();
+            let retry_config = self.retry_config.clone();
+            let repository = self.component_repository(component_id);
+            let component_id = component_id.clone();
+            Box::pin(async move {
+                let (version, _) = resolve_oci_manifest(
+                    &client,
+                    &manifests,
+                    &retry_config,
+                    &repository,
+                    &component_id,
+                    None,
+                )
+                .await?;
+                Ok(version)
+            })
+        })
+    }
+
+    /// Finds the version with the given hash by the digests of the component layers in the
+    /// manifests, without downloading any of the components
+    async fn find_version_by_hash(
+        &self,
+        component_id: &ComponentId,
+        sha256: String,
+    ) -> Result<Option<ComponentVersion>, GolemError> {
+        let digest = format!("sha256:{sha256}");
+        let manifests = list_oci_manifests(
+            &self.client,
+            &self.retry_config,
+            &self.component_repository(component_id),
+            component_id,
+        )
+        .await?;
+        self.manifests.put(component_id, &manifests).await;
+        Ok(manifests
+            .into_iter()
+            .find(|(_, manifest)| {
+                manifest
+                    .layer(OCI_COMPONENT_LAYER_MEDIA_TYPE)
+                    .is_some_and(|layer| layer.digest == digest)
+            })
+            .map(|(version, _)| version))
+    }
+
+    /// Gets the component from the in-memory cache, or from the compiled component store, or
+    /// downloads and compiles it, storing it in both
+    async fn load_component(
+        &self,
+        engine: &Engine,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+        size: u64,
+        force_recompile: bool,
+        in_flight: InFlightComponentLoad,
+    ) -> Result<Component, GolemError> {
+        let key = ComponentKey {
+            component_id: component_id.clone(),
+            component_version,
+        };
+        if force_recompile {
+            self.component_cache.remove(&key);
+        }
+        let client = self.client.clone();
+        let manifests = self.manifests.clone();
+        let repository = self.component_repository(component_id);
+        let retry_config = self.retry_config.clone();
+        let verification_key = self.verification_key.clone();
+        let component_id_clone = component_id.clone();
+        let engine = engine.clone();
+        let compiled_component_service = self.compiled_component_service.clone();
+        let compile_permits = self.compile_permits.clone();
+        let compile_results = self.compile_results.clone();
+        let downloaded_bytes_cache = self.downloaded_bytes_cache.clone();
+        get_or_load_component(
+            &self.component_cache,
+            &key.clone(),
+            size,
+            "oci",
+            in_flight,
+            || {
+                Box::pin(load_uncached_component(
+                    "oci",
+                    key.clone(),
+                    size,
+                    engine,
+                    force_recompile,
+                    compiled_component_service,
+                    compile_permits,
+                    compile_results,
+                    move || async move {
+                        let cached_bytes = downloaded_bytes_cache
+                            .as_ref()
+                            .and_then(|cache| cache.get(&key));
+                        if let Some(bytes) = cached_bytes {
+                            debug!("Recompiling {:?} from its cached WASM", key);
+                            return Ok(bytes);
+                        }
+
+                        let bytes = download_component_via_oci(
+                            client,
+                            manifests,
+                            retry_config,
+                            repository,
+                            verification_key,
+                            downloaded_bytes_cache.clone(),
+                            component_id_clone.clone(),
+                            component_version,
+                        )
+                        .await?;
+
+                        reject_core_wasm_module(&bytes, &component_id_clone, component_version)?;
+
+                        let bytes = Arc::new(bytes);
+                        if let Some(cache) = &downloaded_bytes_cache {
+                            cache.put(key.clone(), bytes.clone());
+                        }
+                        Ok(bytes)
+                    },
+                ))
+            },
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl ComponentService for ComponentServiceOci {
+    async fn get(
+        &self,
+        engine: &Engine,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+    ) -> Result<(Component, ComponentMetadata), GolemError> {
+        let in_flight = self.loads.start()?;
+        let metadata = self
+            .get_metadata(component_id, Some(component_version))
+            .await?;
+        let component = self
+            .load_component(
+                engine,
+                component_id,
+                component_version,
+                metadata.size,
+                false,
+                in_flight,
+            )
+            .await?;
+
+        Ok((component, metadata))
+    }
+
+    async fn get_by_hash(
+        &self,
+        engine: &Engine,
+        component_id: &ComponentId,
+        sha256: &str,
+    ) -> Result<(Component, ComponentMetadata), GolemError> {
+        let version = self
+            .versions_by_hash
+            .resolve(component_id, sha256, |sha256| {
+                self.find_version_by_hash(component_id, sha256)
+            })
+            .await?;
+        self.get(engine, component_id, version).await
+    }
+
+    async fn ensure_cached(
+        &self,
+        engine: &Engine,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+        force_recompile: bool,
+    ) -> Result<(), GolemError> {
+        let in_flight = self.loads.start()?;
+        let metadata = self
+            .get_metadata(component_id, Some(component_version))
+            .await?;
+        self.load_component(
+            engine,
+            component_id,
+            component_version,
+            metadata.size,
+            force_recompile,
+            in_flight,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn prefetch_files(
+        &self,
+        account_id: &AccountId,
+        metadata: &ComponentMetadata,
+    ) -> Result<PrefetchedFiles, GolemError> {
+        self.file_loader
+            .prefetch(
+                account_id,
+                &metadata.files,
+                self.max_concurrent_file_prefetches,
+            )
+            .await
+    }
+
+    async fn shutdown(&self, grace_period: Duration) {
+        self.loads.shutdown(grace_period).await;
+    }
+
+    fn compile_result(
+        &self,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+    ) -> Option<ComponentCompileResult> {
+        self.compile_results.get(component_id, component_version)
+    }
+
+    fn watch_metadata<'a>(
+        &'a self,
+        component_id: &'a ComponentId,
+        poll_interval: Duration,
+    ) -> BoxStream<'a, Result<ComponentMetadata, GolemError>> {
+        watch_latest_metadata(poll_interval, move || async move {
+            let (version, _) = resolve_oci_manifest(
+                &self.client,
+                &self.manifests,
+                &self.retry_config,
+                &self.component_repository(component_id),
+                component_id,
+                None,
+            )
+            .await?;
+            self.get_metadata(component_id, Some(version)).await
+        })
+    }
+
+    async fn is_latest(
+        &self,
+        component_id: &ComponentId,
+        version: ComponentVersion,
+    ) -> Result<bool, GolemError> {
+        if let Some(latest) = self.cached_latest_version(component_id) {
+            return Ok(latest == version);
+        }
+
+        // Only the manifest is needed to resolve the latest version, not the metadata layer
+        let (latest, _) = resolve_oci_manifest(
+            &self.client,
+            &self.manifests,
+            &self.retry_config,
+            &self.component_repository(component_id),
+            component_id,
+            None,
+        )
+        .await?;
+
+        if let Some(latest_version_cache) = &self.latest_version_cache {
+            latest_version_cache.put(component_id, latest).await;
+        }
+
+        Ok(latest == version)
+    }
+
+    async fn get_metadata(
+        &self,
+        component_id: &ComponentId,
+        forced_version: Option<ComponentVersion>,
+    ) -> Result<ComponentMetadata, GolemError> {
+        match forced_version {
+            Some(version) => {
+                let client = self.client.clone();
+                let manifests = self.manifests.clone();
+                let repository = self.component_repository(component_id);
+                let retry_config = self.retry_config.clone();
+                let metadata_transformer = self.metadata_transformer.clone();
+                let reject_empty_exports = self.reject_empty_exports;
+                let component_id = component_id.clone();
+                get_or_insert_metadata(
+                    &self.component_metadata_cache,
+                    &ComponentKey {
+                        component_id: component_id.clone(),
+                        component_version: version,
+                    },
+                    || {
+                        Box::pin(async move {
+                            let (_, manifest) = resolve_oci_manifest(
+                                &client,
+                                &manifests,
+                                &retry_config,
+                                &repository,
+                                &component_id,
+                                Some(version),
+                            )
+                            .await?;
+                            let metadata = get_metadata_via_oci(
+                                &client,
+                                &retry_config,
+                                &repository,
+                                &manifest,
+                                &component_id,
+                                version,
+                            )
+                            .await?;
+                            prepare_metadata(
+                                &metadata_transformer,
+                                reject_empty_exports,
+                                &component_id,
+                                metadata,
+                            )
+                        })
+                    },
+                )
+                .await
+            }
+            None => {
+                if let Some(version) = self.cached_latest_version(component_id) {
+                    return self.get_metadata(component_id, Some(version)).await;
+                }
+
+                let repository = self.component_repository(component_id);
+                let (version, manifest) = resolve_oci_manifest(
+                    &self.client,
+                    &self.manifests,
+                    &self.retry_config,
+                    &repository,
+                    component_id,
+                    None,
+                )
+                .await?;
+
+                if let Some(latest_version_cache) = &self.latest_version_cache {
+                    latest_version_cache.put(component_id, version).await;
+                }
+
+                let client = self.client.clone();
+                let retry_config = self.retry_config.clone();
+                let metadata_transformer = self.metadata_transformer.clone();
+                let reject_empty_exports = self.reject_empty_exports;
+                let component_id_clone = component_id.clone();
+                get_or_insert_metadata(
+                    &self.component_metadata_cache,
+                    &ComponentKey {
+                        component_id: component_id.clone(),
+                        component_version: version,
+                    },
+                    || {
+                        Box::pin(async move {
+                            let metadata = get_metadata_via_oci(
+                                &client,
+                                &retry_config,
+                                &repository,
+                                &manifest,
+                                &component_id_clone,
+                                version,
+                            )
+                            .await?;
+                            prepare_metadata(
+                                &metadata_transformer,
+                                reject_empty_exports,
+                                &component_id_clone,
+                                metadata,
+                            )
+                        })
+                    },
+                )
+                .await
+            }
+        }
+    }
+}
+
+/// The manifests of the component versions in an OCI registry, so that loading a version does
+/// not list all the tags of its repository and fetch their manifests every time
+#[derive(Clone)]
+struct OciManifests {
+    cache: Cache<ComponentKey, (), OciManifest, GolemError>,
+}
+
+impl OciManifests {
+    fn new(max_capacity: usize, time_to_idle: Duration) -> Self {
+        Self {
+            cache: Cache::new(
+                Some(max_capacity.max(1)),
+                FullCacheEvictionMode::LeastRecentlyUsed(1),
+                BackgroundEvictionMode::OlderThan {
+                    ttl: time_to_idle,
+                    period: Duration::from_secs(60),
+                },
+                "component_oci_manifest",
+            ),
+        }
+    }
+
+    fn get(
+        &self,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+    ) -> Option<OciManifest> {
+        self.cache.try_get(&ComponentKey {
+            component_id: component_id.clone(),
+            component_version,
+        })
+    }
+
+    /// Replaces the cached manifests of the given versions with the listed ones
+    async fn put(&self, component_id: &ComponentId, manifests: &[(ComponentVersion, OciManifest)]) {
+        for (component_version, manifest) in manifests {
+            let key = ComponentKey {
+                component_id: component_id.clone(),
+                component_version: *component_version,
+            };
+            let manifest = manifest.clone();
+            self.cache.remove(&key);
+            let _ = self
+                .cache
+                .get_or_insert_simple(&key, || Box::pin(async move { Ok(manifest) }))
+                .await;
+        }
+    }
+}
+
+/// Finds the manifest of the given version, or of the latest one if no version is forced. The
+/// manifest of a forced version is taken from `cache` if it is there. Otherwise the tags of the
+/// repository are listed, and if some of them are named after versions (`3` or `v3`), only the
+/// manifest of the tag of the wanted version (the highest one for the latest version) is fetched.
+/// Only if that fails, or no tag is named after a version, the manifests of all the tags are
+/// fetched, and all of them are cached.
+async fn resolve_oci_manifest(
+    client: &Arc<OciRegistryClient>,
+    cache: &OciManifests,
+    retry_config: &RetryConfig,
+    repository: &str,
+    component_id: &ComponentId,
+    forced_version: Option<ComponentVersion>,
+) -> Result<(ComponentVersion, OciManifest), GolemError> {
+    if let Some(forced_version) = forced_version {
+        if let Some(manifest) = cache.get(component_id, forced_version) {
+            return Ok((forced_version, manifest));
+        }
+    }
+
+    let tags = list_oci_tags(client, retry_config, repository, component_id).await?;
+
+    if let Some((version, tag)) = oci_version_tag(&tags, forced_version) {
+        let manifest =
+            get_oci_manifest(client, retry_config, repository, component_id, tag).await?;
+        if oci_manifest_version(&manifest) == Some(version) {
+            cache
+                .put(component_id, &[(version, manifest.clone())])
+                .await;
+            return Ok((version, manifest));
+        }
+        debug!(
+            "Tag {tag} of {repository} is not annotated with version {version}, fetching the manifests of all tags"
+        );
+    }
+
+    let manifests =
+        list_oci_manifests(client, retry_config, repository, component_id, tags).await?;
+    cache.put(component_id, &manifests).await;
+
+    match forced_version {
+        Some(forced_version) => manifests
+            .into_iter()
+            .find(|(version, _)| *version == forced_version)
+            .ok_or(GolemError::get_latest_version_of_component_failed(
+                component_id.clone(),
+                "Could not find any component with the given id and version",
+            )),
+        None => manifests
+            .into_iter()
+            .max_by_key(|(version, _)| *version)
+            .ok_or(GolemError::get_latest_version_of_component_failed(
+                component_id.clone(),
+                "Could not find any component with the given id",
+            )),
+    }
+}
+
+/// Picks the tag named after the forced version, or the one named after the highest version if
+/// no version is forced
+fn oci_version_tag(
+    tags: &[String],
+    forced_version: Option<ComponentVersion>,
+) -> Option<(ComponentVersion, &str)> {
+    let mut versions = tags.iter().filter_map(|tag| {
+        tag.strip_prefix('v')
+            .unwrap_or(tag)
+            .parse::<ComponentVersion>()
+            .ok()
+            .map(|version| (version, tag.as_str()))
+    });
+
+    match forced_version {
+        Some(forced_version) => versions.find(|(version, _)| *version == forced_version),
+        None => versions.max_by_key(|(version, _)| *version),
+    }
+}
+
+fn oci_manifest_version(manifest: &OciManifest) -> Option<ComponentVersion> {
+    manifest
+        .annotations
+        .get(OCI_COMPONENT_VERSION_ANNOTATION)
+        .and_then(|version| version.parse::<ComponentVersion>().ok())
+}
+
+async fn list_oci_tags(
+    client: &Arc<OciRegistryClient>,
+    retry_config: &RetryConfig,
+    repository: &str,
+    component_id: &ComponentId,
+) -> Result<Vec<String>, GolemError> {
+    let input = (client.clone(), repository.to_string());
+    let list_tags = with_retries(
+        "components",
+        "oci_list_tags",
+        Some(component_id.to_string()),
+        retry_config,
+        &input,
+        |(client, repository)| Box::pin(async move { client.list_tags(repository).await }),
+        OciError::is_retriable,
+    );
+    with_max_elapsed(client.max_elapsed(), list_tags, |max_elapsed| {
+        GolemError::get_latest_version_of_component_failed(
+            component_id.clone(),
+            format!("Timed out after {max_elapsed:?} of retrying"),
+        )
+    })
+    .await?
+    .map_err(|error| {
+        GolemError::get_latest_version_of_component_failed(component_id.clone(), error.to_string())
+    })
+}
+
+async fn get_oci_manifest(
+    client: &Arc<OciRegistryClient>,
+    retry_config: &RetryConfig,
+    repository: &str,
+    component_id: &ComponentId,
+    tag: &str,
+) -> Result<OciManifest, GolemError> {
+    let input = (client.clone(), repository.to_string(), tag.to_string());
+    let get_manifest = with_retries(
+        "components",
+        "oci_get_manifest",
+        Some(component_id.to_string()),
+        retry_config,
+        &input,
+        |(client, repository, tag)| {
+            Box::pin(async move { client.get_manifest(repository, tag).await })
+        },
+        OciError::is_retriable,
+    );
+    with_max_elapsed(client.max_elapsed(), get_manifest, |max_elapsed| {
+        GolemError::get_latest_version_of_component_failed(
+            component_id.clone(),
+            format!("Timed out after {max_elapsed:?} of retrying"),
+        )
+    })
+    .await?
+    .map_err(|error| {
+        GolemError::get_latest_version_of_component_failed(component_id.clone(), error.to_string())
+    })
+}
+
+/// Gets the manifests of the versions of a component, by reading the version annotation of
+/// every given tag of the component's repository. Tags without the annotation are ignored.
+async fn list_oci_manifests(
+    client: &Arc<OciRegistryClient>,
+    retry_config: &RetryConfig,
+    repository: &str,
+    component_id: &ComponentId,
+    tags: Vec<String>,
+) -> Result<Vec<(ComponentVersion, OciManifest)>, GolemError> {
+    let mut manifests = Vec::new();
+    for tag in tags {
+        let manifest =
+            get_oci_manifest(client, retry_config, repository, component_id, &tag).await?;
+        if let Some(version) = oci_manifest_version(&manifest) {
+            manifests.push((version, manifest));
+        }
+    }
+
+    Ok(manifests)
+}
+
+/// Downloads the WASM of a component version. An artifact with a patch layer instead of the
+/// component layer is rebuilt by applying the patch to the WASM of the base version named in its
+/// metadata, which is taken from the downloaded bytes cache if it is there, and downloaded the
+/// same way otherwise. With a `verification_key`, the (rebuilt) WASM must match the signature
+/// layer of the artifact.
+#[allow(clippy::too_many_arguments)]
+fn download_component_via_oci(
+    client: Arc<OciRegistryClient>,
+    manifests: OciManifests,
+    retry_config: RetryConfig,
+    repository: String,
+    verification_key: Option<PublicKey>,
+    downloaded_bytes_cache: Option<Arc<DownloadedBytesCache>>,
+    component_id: ComponentId,
+    component_version: ComponentVersion,
+) -> Pin<Box<dyn Future<Output = Result<Vec<u8>, GolemError>> + Send>> {
+    Box::pin(async move {
+        let (_, manifest) = resolve_oci_manifest(
+            &client,
+            &manifests,
+            &retry_config,
+            &repository,
+            &component_id,
+            Some(component_version),
+        )
+        .await
+        .map_err(|error| error.as_download_failure(component_version))?;
+
+        let bytes = match manifest.layer(OCI_COMPONENT_PATCH_LAYER_MEDIA_TYPE) {
+            None => {
+                download_via_oci(
+                    &client,
+                    &retry_config,
+                    &repository,
+                    &manifest,
+                    OCI_COMPONENT_LAYER_MEDIA_TYPE,
+                    &component_id,
+                    component_version,
+                )
+                .await?
+            }
+            Some(_) => {
+                let metadata = get_metadata_via_oci(
+                    &client,
+                    &retry_config,
+                    &repository,
+                    &manifest,
+                    &component_id,
+                    component_version,
+                )
+                .await
+                .map_err(|error| error.as_download_failure(component_version))?;
+                let base_version =
+                    match metadata.base_version {
+                        Some(base_version) if base_version < component_version => base_version,
+                        _ => return Err(GolemError::ComponentParseFailed {
+                            component_id,
+                            component_version,
+                            reason:
+                                "Component is stored as a patch, but has no earlier base version"
+                                    .to_string(),
+                        }),
+                    };
+
+                let base_key = ComponentKey {
+                    component_id: component_id.clone(),
+                    component_version: base_version,
+                };
+                let cached_base = downloaded_bytes_cache
+                    .as_ref()
+                    .and_then(|cache| cache.get(&base_key));
+                let base = match cached_base {
+                    Some(base) => base,
+                    None => Arc::new(
+                        download_component_via_oci(
+                            client.clone(),
+                            manifests.clone(),
+                            retry_config.clone(),
+                            repository.clone(),
+                            verification_key.clone(),
+                            downloaded_bytes_cache,
+                            component_id.clone(),
+                            base_version,
+                        )
+                        .await?,
+                    ),
+                };
+
+                let patch = download_via_oci(
+                    &client,
+                    &retry_config,
+                    &repository,
+                    &manifest,
+                    OCI_COMPONENT_PATCH_LAYER_MEDIA_TYPE,
+                    &component_id,
+                    component_version,
+                )
+                .await?;
+                patched_component(
+                    &base,
+                    &patch,
+                    &component_id,
+                    component_version,
+                    base_version,
+                )?
+            }
+        };
+
+        if let Some(verification_key) = &verification_key {
+            let signature = get_signature_via_oci(
+                &client,
+                &retry_config,
+                &repository,
+                &manifest,
+                &component_id,
+                component_version,
+            )
+            .await?;
+            verify_signature(
+                verification_key,
+                signature.as_deref(),
+                &bytes,
+                &component_id,
+                component_version,
+            )?;
+        }
+
+        Ok(bytes)
+    })
+}
+
+/// Reads the signature layer of the artifact, if it has one
+async fn get_signature_via_oci(
+    client: &Arc<OciRegistryClient>,
+    retry_config: &RetryConfig,
+    repository: &str,
+    manifest: &OciManifest,
+    component_id: &ComponentId,
+    component_version: ComponentVersion,
+) -> Result<Option<Vec<u8>>, GolemError> {
+    let Some(layer) = manifest.layer(OCI_SIGNATURE_LAYER_MEDIA_TYPE).cloned() else {
+        return Ok(None);
+    };
+
+    let input = (client.clone(), repository.to_string(), layer);
+    let get_signature = with_retries(
+        "components",
+        "oci_get_signature",
+        Some(component_id.to_string()),
+        retry_config,
+        &input,
+        |(client, repository, layer)| {
+            Box::pin(async move { client.get_blob(repository, layer).await })
+        },
+        OciError::is_retriable,
+    );
+    let signature = with_max_elapsed(client.max_elapsed(), get_signature, |max_elapsed| {
+        GolemError::component_download_failed(
+            component_id.clone(),
+            component_version,
+            format!("Timed out after {max_elapsed:?} of retrying"),
+        )
+    })
+    .await?
+    .map_err(|error| {
+        GolemError::component_download_failed(
+            component_id.clone(),
+            component_version,
+            error.to_string(),
+        )
+    })?;
+
+    Ok(Some(signature))
+}
+
+async fn download_via_oci(
+    client: &Arc<OciRegistryClient>,
+    retry_config: &RetryConfig,
+    repository: &str,
+    manifest: &OciManifest,
+    media_type: &str,
+    component_id: &ComponentId,
+    component_version: ComponentVersion,
+) -> Result<Vec<u8>, GolemError> {
+    let layer = manifest.layer(media_type).cloned().ok_or_else(|| {
+        GolemError::component_download_failed(
+            component_id.clone(),
+            component_version,
+            format!("The artifact has no {media_type} layer"),
+        )
+    })?;
+
+    let input = (client.clone(), repository.to_string(), layer);
+    let start = Instant::now();
+    let download = with_retries(
+        "components",
+        "oci_download",
+        Some(component_id.to_string()),
+        retry_config,
+        &input,
+        |(client, repository, layer)| {
+            Box::pin(async move { client.get_blob(repository, layer).await })
+        },
+        OciError::is_retriable,
+    );
+    let bytes = with_max_elapsed(client.max_elapsed(), download, |max_elapsed| {
+        GolemError::component_download_failed(
+            component_id.clone(),
+            component_version,
+            format!("Timed out after {max_elapsed:?} of retrying"),
+        )
+    })
+    .await?
+    .map_err(|error| {
+        GolemError::component_download_failed(
+            component_id.clone(),
+            component_version,
+            error.to_string(),
+        )
+    })?;
+
+    record_external_call_response_size_bytes("components", "download", bytes.len());
+    record_component_download("oci", bytes.len(), start.elapsed());
+
+    Ok(bytes)
+}
+
+/// Reads the metadata layer of the artifact. The version and size are taken from the manifest,
+/// as those are what the registry serves for the given tag.
+async fn get_metadata_via_oci(
+    client: &Arc<OciRegistryClient>,
+    retry_config: &RetryConfig,
+    repository: &str,
+    manifest: &OciManifest,
+    component_id: &ComponentId,
+    component_version: ComponentVersion,
+) -> Result<ComponentMetadata, GolemError> {
+    let to_error = |reason: String| {
+        GolemError::get_latest_version_of_component_failed(component_id.clone(), reason)
+    };
+
+    let layer = manifest
+        .layer(OCI_METADATA_LAYER_MEDIA_TYPE)
+        .cloned()
+        .ok_or_else(|| {
+            to_error(format!(
+                "The artifact has no {OCI_METADATA_LAYER_MEDIA_TYPE} layer"
+            ))
+        })?;
+
+    let input = (client.clone(), repository.to_string(), layer);
+    let get_metadata = with_retries(
+        "components",
+        "oci_get_metadata",
+        Some(component_id.to_string()),
+        retry_config,
+        &input,
+        |(client, repository, layer)| {
+            Box::pin(async move { client.get_blob(repository, layer).await })
+        },
+        OciError::is_retriable,
+    );
+    let bytes = with_max_elapsed(client.max_elapsed(), get_metadata, |max_elapsed| {
+        to_error(format!("Timed out after {max_elapsed:?} of retrying"))
+    })
+    .await?
+    .map_err(|error| to_error(error.to_string()))?;
+
+    let mut metadata: ComponentMetadata = serde_json::from_slice(&bytes)
+        .map_err(|e| to_error(format!("Failed to read metadata of component: {}", e)))?;
+    metadata.version = component_version;
+    if let Some(layer) = manifest.layer(OCI_COMPONENT_LAYER_MEDIA_TYPE) {
+        metadata.size = layer.size;
+    }
+
+    Ok(metadata)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentProperties {
+    pub component_type: ComponentType,
+    pub files: Vec<InitialComponentFile>,
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::collections::BTreeMap;
+    use std::path::Path;
+    use std::time::Duration;
+
+    use golem_api_grpc::proto::golem::common::ErrorBody;
+    use golem_api_grpc::proto::golem::component::v1::{
+        component_error, download_component_response, ComponentError, DownloadComponentResponse,
+    };
+    use tonic::{Code, Status};
+
+    use futures_util::StreamExt;
+    use golem_common::model::component_metadata::{LinearMemory, EMPTY_COMPONENT};
+    use golem_common::model::{ComponentId, ComponentType};
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+    use golem_wasm_ast::analysis::{AnalysedExport, AnalysedFunction};
+    use ring::rand::SystemRandom;
+    use ring::signature::{Ed25519KeyPair, KeyPair};
+    use wasmtime::component::Component;
+    use wasmtime::Engine;
+
+    use super::{
+        apply_component_patch, check_reported_memories, collect_download_chunks, concat_chunks,
+        metadata_from_grpc, normalize_sha256, oci_version_tag, parse_props_file, prepare_metadata,
+        sha256_hex, spawn_component_load, verify_signature, versions_from_grpc,
+        watch_latest_metadata, with_max_elapsed, CompileResults, ComponentCache,
+        ComponentCacheAdmission, ComponentCacheOptions, ComponentCompileResult, ComponentKey,
+        ComponentLoadTracker, ComponentMetadata, ComponentService, ComponentServiceLocalFileSystem,
+        DownloadedBytesCache, LatestVersionCache, MetadataTransformer, OciManifests,
+        ResolveLatestVersion, OCI_COMPONENT_LAYER_MEDIA_TYPE,
+    };
+    use crate::error::GolemError;
+    use crate::grpc::{is_grpc_retriable_with, GrpcError};
+    use crate::services::compiled_component::{
+        CompiledComponentError, CompiledComponentService, CompiledComponentServiceDisabled,
+    };
+    use crate::services::component_metadata_cache::{
+        ComponentMetadataCache, InMemoryComponentMetadataCache,
+    };
+    use crate::services::file_loader::FileLoader;
+    use crate::services::golem_config::{ComponentCacheConfig, GrpcStatusCode, PublicKey};
+    use crate::services::oci::{OciDescriptor, OciManifest};
+    use golem_service_base::service::initial_component_files::InitialComponentFilesService;
+    use golem_service_base::storage::blob::memory::InMemoryBlobStorage;
+
+    fn cache_options() -> ComponentCacheOptions {
+        ComponentCacheOptions {
+            max_capacity: 16,
+            time_to_idle: Duration::from_secs(60),
+            max_concurrent_compiles: 1,
+            max_concurrent_file_prefetches: 1,
+            ..ComponentCacheOptions::from(&ComponentCacheConfig::default())
+        }
+    }
+
+    fn local_service(
+        root: &Path,
+        metadata_cache: Option<Arc<dyn ComponentMetadataCache + Send + Sync>>,
+        compiled_component_service: Arc<dyn CompiledComponentService + Send + Sync>,
+        recover_missing_props: bool,
+    ) -> ComponentServiceLocalFileSystem {
+        ComponentServiceLocalFileSystem::new(
+            root,
+            None,
+            cache_options(),
+            metadata_cache,
+            compiled_component_service,
+            None,
+            None,
+            Arc::new(
+                FileLoader::new(Arc::new(InitialComponentFilesService::new(Arc::new(
+                    InMemoryBlobStorage::new(),
+                ))))
+                .unwrap(),
+            ),
+            recover_missing_props,
+        )
+    }
+
+    fn download_chunk(
+        result: download_component_response::Result,
+    ) -> Result<DownloadComponentResponse, Status> {
+        Ok(DownloadComponentResponse {
+            result: Some(result),
+            signature: None,
+            patch_base_version: None,
+        })
+    }
+
+    #[test]
+    async fn download_stops_at_the_first_error_chunk() {
+        let error = ComponentError {
+            error: Some(component_error::Error::InternalError(ErrorBody {
+                error: "Blob storage unavailable".to_string(),
+            })),
+        };
+        let mut response = futures_util::stream::iter(vec![
+            download_chunk(download_component_response::Result::SuccessChunk(vec![
+                1, 2,
+            ])),
+            download_chunk(download_component_response::Result::Error(error.clone())),
+            download_chunk(download_component_response::Result::SuccessChunk(vec![
+                3, 4,
+            ])),
+        ]);
+
+        let result = collect_download_chunks(&mut response, &None).await;
+
+        match result {
+            Err(GrpcError::Domain(domain_error)) => assert_eq!(domain_error, error),
+            other => panic!("Expected the error chunk, got {other:?}"),
+        }
+        // The chunk after the error is never received
+        assert!(response.next().await.is_some());
+    }
+
+    #[test]
+    async fn download_collects_chunks_and_signature() {
+        let mut response = futures_util::stream::iter(vec![
+            Ok(DownloadComponentResponse {
+                result: Some(download_component_response::Result::SuccessChunk(vec![
+                    1, 2,
+                ])),
+                signature: Some(vec![9]),
+                patch_base_version: Some(2),
+            }),
+            download_chunk(download_component_response::Result::SuccessChunk(vec![3])),
+        ]);
+
+        let downloaded = collect_download_chunks(&mut response, &None).await.unwrap();
+
+        assert_eq!(downloaded.chunks, vec![vec![1, 2], vec![3]]);
+        assert_eq!(downloaded.signature, Some(vec![9]));
+        assert_eq!(downloaded.patch_base_version, Some(2));
+    }
+
+    fn component_key(component_version: u64) -> ComponentKey {
+        ComponentKey {
+            component_id: ComponentId(uuid::Uuid::nil()),
+            component_version,
+        }
+    }
+
+    #[test]
+    fn downloaded_bytes_cache_evicts_the_least_recently_used_wasms() {
+        let cache = DownloadedBytesCache::new(100);
+        cache.put(component_key(0), Arc::new(vec![0; 40]));
+        cache.put(component_key(1), Arc::new(vec![1; 40]));
+        assert!(cache.get(&component_key(0)).is_some());
+
+        // Does not fit next to both, evicting version 1 which was used less recently
+        cache.put(component_key(2), Arc::new(vec![2; 40]));
+        assert!(cache.get(&component_key(1)).is_none());
+        assert_eq!(cache.get(&component_key(0)).unwrap().len(), 40);
+        assert_eq!(cache.get(&component_key(2)).unwrap()[0], 2);
+
+        // Larger than the whole cache, so it is not kept and does not evict anything
+        cache.put(component_key(3), Arc::new(vec![3; 101]));
+        assert!(cache.get(&component_key(3)).is_none());
+        assert!(cache.get(&component_key(0)).is_some());
+        assert!(cache.get(&component_key(2)).is_some());
+    }
+
+    #[test]
+    fn downloaded_bytes_cache_finds_the_latest_earlier_version() {
+        let cache = DownloadedBytesCache::new(100);
+        cache.put(component_key(1), Arc::new(vec![1; 10]));
+        cache.put(component_key(3), Arc::new(vec![3; 10]));
+        cache.put(component_key(5), Arc::new(vec![5; 10]));
+
+        let (version, bytes) = cache.latest_before(&component_key(5)).unwrap();
+        assert_eq!(version, 3);
+        assert_eq!(bytes[0], 3);
+        assert_eq!(cache.latest_before(&component_key(3)).unwrap().0, 1);
+        assert!(cache.latest_before(&component_key(1)).is_none());
+
+        let other = ComponentKey {
+            component_id: ComponentId(uuid::Uuid::max()),
+            component_version: 5,
+        };
+        assert!(cache.latest_before(&other).is_none());
+    }
+
+    fn oci_manifest(digest: &str) -> OciManifest {
+        OciManifest {
+            layers: vec![OciDescriptor {
+                media_type: OCI_COMPONENT_LAYER_MEDIA_TYPE.to_string(),
+                digest: digest.to_string(),
+                size: 1,
+            }],
+            annotations: Default::default(),
+        }
+    }
+
+    #[test]
+    fn oci_versions_are_resolved_from_tags_named_after_them() {
+        let tags = ["latest", "v2", "10", "v9", "stable-3"]
+            .into_iter()
+            .map(|tag| tag.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(oci_version_tag(&tags, None), Some((10, "10")));
+        assert_eq!(oci_version_tag(&tags, Some(2)), Some((2, "v2")));
+        assert_eq!(oci_version_tag(&tags, Some(3)), None);
+        assert_eq!(oci_version_tag(&["latest".to_string()], None), None);
+    }
+
+    #[test]
+    async fn oci_manifests_are_cached_per_version() {
+        let manifests = OciManifests::new(16, Duration::from_secs(60));
+        let component_id = ComponentId(uuid::Uuid::nil());
+        assert!(manifests.get(&component_id, 1).is_none());
+
+        manifests
+            .put(
+                &component_id,
+                &[
+                    (1, oci_manifest("sha256:aa")),
+                    (2, oci_manifest("sha256:bb")),
+                ],
+            )
+            .await;
+        assert_eq!(
+            manifests.get(&component_id, 2).unwrap().layers[0].digest,
+            "sha256:bb"
+        );
+
+        // Listing the manifests again replaces the cached ones, as a tag can be pushed again
+        manifests
+            .put(&component_id, &[(1, oci_manifest("sha256:cc"))])
+            .await;
+        assert_eq!(
+            manifests.get(&component_id, 1).unwrap().layers[0].digest,
+            "sha256:cc"
+        );
+    }
+
+    #[test]
+    fn concat_chunks_allocates_once_with_expected_size() {
+        let chunks = vec![vec![1u8; 1000]; 64];
+        let bytes = concat_chunks(chunks, 64 * 1000, usize::MAX);
+
+        assert_eq!(bytes.len(), 64 * 1000);
+        // Any reallocation during concatenation would have grown the capacity past the length
+        assert_eq!(bytes.capacity(), 64 * 1000);
+        assert!(bytes.iter().all(|b| *b == 1));
+    }
+
+    #[test]
+    fn concat_chunks_tolerates_inaccurate_expected_size() {
+        let chunks = vec![vec![1u8, 2], vec![3], vec![4, 5, 6]];
+
+        assert_eq!(
+            concat_chunks(chunks.clone(), 0, usize::MAX),
+            vec![1, 2, 3, 4, 5, 6]
+        );
+        assert_eq!(
+            concat_chunks(chunks, 1024, usize::MAX),
+            vec![1, 2, 3, 4, 5, 6]
+        );
+    }
+
+    #[test]
+    fn concat_chunks_caps_reservation_at_max_component_size() {
+        let chunks = vec![vec![1u8, 2], vec![3]];
+        let bytes = concat_chunks(chunks, u64::MAX, 16);
+
+        assert_eq!(bytes, vec![1, 2, 3]);
+        assert_eq!(bytes.capacity(), 16);
+    }
+
+    #[test]
+    fn apply_component_patch_rebuilds_new_version() {
+        let base = [
+            EMPTY_COMPONENT,
+            b" a large common base shared by the versions",
+        ]
+        .concat();
+        let mut new = base.clone();
+        new.extend_from_slice(b" and a small change");
+
+        let mut patch = Vec::new();
+        bsdiff::diff(&base, &new, &mut patch).unwrap();
+
+        assert_eq!(apply_component_patch(&base, &patch).unwrap(), new);
+        assert!(apply_component_patch(&base, b"not a patch").is_err());
+    }
+
+    #[test]
+    async fn shutdown_rejects_new_loads() {
+        let tracker = ComponentLoadTracker::new();
+
+        assert!(tracker.shutdown(Duration::from_secs(1)).await);
+        assert!(matches!(tracker.start(), Err(GolemError::ShuttingDown)));
+    }
+
+    #[test]
+    async fn shutdown_waits_for_in_flight_loads() {
+        let tracker = ComponentLoadTracker::new();
+        let in_flight = tracker.start().unwrap();
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            drop(in_flight);
+        });
+
+        assert!(tracker.shutdown(Duration::from_secs(10)).await);
+    }
+
+    #[test]
+    async fn loads_stay_in_flight_after_the_caller_is_dropped() {
+        let tracker = ComponentLoadTracker::new();
+        let in_flight = tracker.start().unwrap();
+        let finished = Arc::new(AtomicBool::new(false));
+        let finished_clone = finished.clone();
+
+        let load = spawn_component_load(
+            Box::pin(async move {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                finished_clone.store(true, Ordering::Relaxed);
+                Err(GolemError::unknown("not a component"))
+            }),
+            in_flight,
+        );
+        // The caller gives up before the load finishes
+        let _ = tokio::time::timeout(Duration::from_millis(10), load).await;
+
+        assert!(tracker.shutdown(Duration::from_secs(10)).await);
+        assert!(finished.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    async fn shutdown_gives_up_after_the_grace_period() {
+        let tracker = ComponentLoadTracker::new();
+        let _in_flight = tracker.start().unwrap();
+
+        assert!(!tracker.shutdown(Duration::from_millis(100)).await);
+    }
+
+    #[test]
+    async fn retries_are_cut_off_after_the_max_elapsed_time() {
+        let timed_out = |max_elapsed: Duration| GolemError::unknown(format!("{max_elapsed:?}"));
+
+        let result = with_max_elapsed(
+            Some(Duration::from_millis(50)),
+            std::future::pending::<()>(),
+            timed_out,
+        )
+        .await;
+        assert_eq!(result, Err(GolemError::unknown("50ms")));
+
+        let result = with_max_elapsed(Some(Duration::from_secs(60)), async { 1 }, timed_out).await;
+        assert_eq!(result, Ok(1));
+
+        let result = with_max_elapsed(
+            None,
+            async {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                1
+            },
+            timed_out,
+        )
+        .await;
+        assert_eq!(result, Ok(1));
+    }
+
+    #[test]
+    fn additional_retriable_status_codes() {
+        let error = |code| GrpcError::<ComponentError>::Status(Status::new(code, "error"));
+
+        assert!(!is_grpc_retriable_with(
+            &error(Code::ResourceExhausted),
+            &[]
+        ));
+        assert!(is_grpc_retriable_with(
+            &error(Code::ResourceExhausted),
+            &[Code::ResourceExhausted]
+        ));
+        assert!(is_grpc_retriable_with(
+            &error(Code::Unavailable),
+            &[Code::ResourceExhausted]
+        ));
+        assert!(!is_grpc_retriable_with(
+            &error(Code::NotFound),
+            &[Code::ResourceExhausted]
+        ));
+    }
+
+    #[test]
+    fn parse_grpc_status_code() {
+        for name in [
+            "RESOURCE_EXHAUSTED",
+            "ResourceExhausted",
+            "resource_exhausted",
+        ] {
+            assert_eq!(
+                GrpcStatusCode::try_from(name.to_string()),
+                Ok(GrpcStatusCode(Code::ResourceExhausted))
+            );
+        }
+        assert_eq!(
+            String::from(GrpcStatusCode(Code::DeadlineExceeded)),
+            "DeadlineExceeded"
+        );
+        assert!(GrpcStatusCode::try_from("TOO_MANY_REQUESTS".to_string()).is_err());
+    }
+
+    #[test]
+    async fn latest_versions_are_cached_until_the_ttl() {
+        let cache = LatestVersionCache::new(16, Duration::from_millis(100), None);
+        let component_id = ComponentId(uuid::Uuid::new_v4());
+        let resolve = || -> ResolveLatestVersion {
+            panic!("Latest versions are only resolved in the background with a soft TTL")
+        };
+
+        assert_eq!(cache.get(&component_id, resolve), None);
+
+        cache.put(&component_id, 1).await;
+        assert_eq!(cache.get(&component_id, resolve), Some(1));
+
+        // A newer version is only picked up once the cached one expired
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert_eq!(cache.get(&component_id, resolve), None);
+        cache.put(&component_id, 2).await;
+        assert_eq!(cache.get(&component_id, resolve), Some(2));
+    }
+
+    #[test]
+    async fn stale_latest_versions_are_refreshed_in_the_background() {
+        // Every cached version is past the soft TTL, but none of them past the hard one
+        let cache = LatestVersionCache::new(16, Duration::from_secs(60), Some(Duration::ZERO));
+        let component_id = ComponentId(uuid::Uuid::new_v4());
+        let resolved = Arc::new(Mutex::new(0));
+        let release = Arc::new(tokio::sync::Notify::new());
+        let resolve = |version| {
+            let resolved = resolved.clone();
+            let release = release.clone();
+            move || -> ResolveLatestVersion {
+                Box::pin(async move {
+                    release.notified().await;
+                    *resolved.lock().unwrap() += 1;
+                    Ok(version)
+                })
+            }
+        };
+
+        cache.put(&component_id, 1).await;
+
+        // The stale version is returned while it gets refreshed, only once at a time
+        assert_eq!(cache.get(&component_id, resolve(2)), Some(1));
+        assert_eq!(cache.get(&component_id, resolve(3)), Some(1));
+        release.notify_one();
+        cache.refreshed.notified().await;
+        assert_eq!(*resolved.lock().unwrap(), 1);
+
+        assert_eq!(cache.get(&component_id, resolve(3)), Some(2));
+        release.notify_one();
+        cache.refreshed.notified().await;
+        assert_eq!(*resolved.lock().unwrap(), 2);
+
+        assert_eq!(cache.get(&component_id, resolve(4)), Some(3));
+    }
+
+    #[test]
+    async fn watch_yields_new_latest_versions() {
+        let versions = Mutex::new(vec![0, 0, 1, 1, 1, 2].into_iter());
+        let latest = || {
+            let version = versions.lock().unwrap().next();
+            async move {
+                match version {
+                    Some(version) => Ok(ComponentMetadata {
+                        version,
+                        size: 0,
+                        memories: vec![],
+                        exports: vec![],
+                        component_type: ComponentType::Durable,
+                        files: vec![],
+                        base_version: None,
+                        annotations: BTreeMap::new(),
+                    }),
+                    None => Err(GolemError::unknown("No more versions")),
+                }
+            }
+        };
+
+        let yielded = watch_latest_metadata(Duration::from_millis(1), latest)
+            .take(4)
+            .map(|result| result.map(|metadata| metadata.version))
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(yielded[..3], [Ok(0), Ok(1), Ok(2)]);
+        assert!(yielded[3].is_err());
+    }
+
+    #[test]
+    async fn stored_components_get_the_next_version() {
+        let root = tempfile::tempdir().unwrap();
+        let service = local_service(
+            root.path(),
+            Some(Arc::new(InMemoryComponentMetadataCache::new(
+                16,
+                Duration::from_secs(60),
+            ))),
+            Arc::new(CompiledComponentServiceDisabled::new()),
+            false,
+        );
+        let component_id = ComponentId::new_v4();
+
+        let first = service
+            .store(
+                &component_id,
+                ComponentType::Durable,
+                EMPTY_COMPONENT.to_vec(),
+            )
+            .await
+            .unwrap();
+        let second = service
+            .store(
+                &component_id,
+                ComponentType::Ephemeral,
+                EMPTY_COMPONENT.to_vec(),
+            )
+            .await
+            .unwrap();
+        assert_eq!((first, second), (0, 1));
+
+        let metadata = service.get_metadata(&component_id, None).await.unwrap();
+        assert_eq!(metadata.version, 1);
+        assert!(service.is_latest(&component_id, 1).await.unwrap());
+        assert!(!service.is_latest(&component_id, 0).await.unwrap());
+        assert_eq!(metadata.component_type, ComponentType::Ephemeral);
+        assert_eq!(metadata.size, EMPTY_COMPONENT.len() as u64);
+
+        let raw_metadata = service.get_raw_metadata(&component_id, 0).await.unwrap();
+        assert!(raw_metadata.exports.is_empty());
+        assert!(raw_metadata.memories.is_empty());
+
+        // The local source synthesizes the component message from the metadata
+        let proto = service.get_proto_component(&component_id, 0).await.unwrap();
+        assert_eq!(proto.versioned_component_id.unwrap().version, 0);
+        assert_eq!(proto.component_size, EMPTY_COMPONENT.len() as u64);
+        assert_eq!(
+            proto.component_type,
+            Some(golem_api_grpc::proto::golem::component::ComponentType::Durable as i32)
+        );
+        assert!(proto.component_name.is_empty());
+
+        // Reading the metadata never compiles the component
+        assert_eq!(service.compile_result(&component_id, 0), None);
+        assert_eq!(service.compile_result(&component_id, 1), None);
+
+        let rejected = service
+            .store(&component_id, ComponentType::Durable, b"not wasm".to_vec())
+            .await;
+        assert!(matches!(
+            rejected,
+            Err(GolemError::ComponentParseFailed { .. })
+        ));
+    }
+
+    #[test]
+    async fn files_resolving_to_the_same_version_are_rejected() {
+        let root = tempfile::tempdir().unwrap();
+        let component_id = ComponentId::new_v4();
+        let (path, id) = (root.path(), &component_id);
+        let find = move |forced_version| {
+            ComponentServiceLocalFileSystem::find_component_files_in(
+                path,
+                path,
+                id,
+                forced_version,
+                false,
+            )
+        };
+
+        std::fs::write(root.path().join(format!("{component_id}-1.wasm")), "").unwrap();
+        std::fs::write(root.path().join(format!("{component_id}-2.wasm")), "").unwrap();
+        assert_eq!(find(None).await.unwrap().0, 2);
+        assert_eq!(find(Some(1)).await.unwrap().0, 1);
+
+        std::fs::write(root.path().join(format!("{component_id}-01.wasm")), "").unwrap();
+        for forced_version in [None, Some(1), Some(2)] {
+            let Err(GolemError::GetLatestVersionOfComponentFailed { reason, .. }) =
+                find(forced_version).await
+            else {
+                panic!("Expected the duplicate version to be rejected");
+            };
+            assert!(reason.contains(&format!("{component_id}-1.wasm")));
+            assert!(reason.contains(&format!("{component_id}-01.wasm")));
+        }
+    }
+
+    #[test]
+    async fn stored_components_are_found_by_hash() {
+        // Empty components, the second one with a custom section
+        let first = EMPTY_COMPONENT;
+        let second = [EMPTY_COMPONENT, b"\0\x04\x03abc"].concat();
+
+        let root = tempfile::tempdir().unwrap();
+        let service = local_service(
+            root.path(),
+            None,
+            Arc::new(CompiledComponentServiceDisabled::new()),
+            false,
+        );
+        let component_id = ComponentId::new_v4();
+
+        service
+            .store(&component_id, ComponentType::Durable, first.to_vec())
+            .await
+            .unwrap();
+        service
+            .store(&component_id, ComponentType::Durable, second.clone())
+            .await
+            .unwrap();
+
+        let find = |bytes: &[u8]| service.find_version_by_hash(&component_id, sha256_hex(bytes));
+        assert_eq!(find(first).await.unwrap(), Some(0));
+        assert_eq!(find(&second).await.unwrap(), Some(1));
+        assert_eq!(find(b"other").await.unwrap(), None);
+    }
+
+    #[test]
+    async fn local_watch_yields_new_versions_when_stored() {
+        let root = tempfile::tempdir().unwrap();
+        let service = local_service(
+            root.path(),
+            None,
+            Arc::new(CompiledComponentServiceDisabled::new()),
+            false,
+        );
+        let component_id = ComponentId::new_v4();
+
+        service
+            .store(
+                &component_id,
+                ComponentType::Durable,
+                EMPTY_COMPONENT.to_vec(),
+            )
+            .await
+            .unwrap();
+
+        // Far longer than the test, so only the file watcher can yield the new version
+        let mut watch = service.watch_metadata(&component_id, Duration::from_secs(3600));
+        let first = watch.next().await.unwrap().unwrap();
+        assert_eq!(first.version, 0);
+
+        service
+            .store(
+                &component_id,
+                ComponentType::Durable,
+                EMPTY_COMPONENT.to_vec(),
+            )
+            .await
+            .unwrap();
+
+        let next = tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                // Lookups during the write of the new version may fail
+                if let Ok(metadata) = watch.next().await.unwrap() {
+                    return metadata;
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(next.version, 1);
+    }
+
+    #[test]
+    async fn missing_props_files_are_recovered_when_enabled() {
+        let root = tempfile::tempdir().unwrap();
+        let service = |recover_missing_props: bool| {
+            local_service(
+                root.path(),
+                None,
+                Arc::new(CompiledComponentServiceDisabled::new()),
+                recover_missing_props,
+            )
+        };
+        let component_id = ComponentId::new_v4();
+        service(false)
+            .store(
+                &component_id,
+                ComponentType::Ephemeral,
+                EMPTY_COMPONENT.to_vec(),
+            )
+            .await
+            .unwrap();
+        std::fs::remove_file(root.path().join(format!("{component_id}-0.json"))).unwrap();
+
+        let strict = service(false).get_metadata(&component_id, Some(0)).await;
+        assert!(matches!(
+            strict,
+            Err(GolemError::GetLatestVersionOfComponentFailed { .. })
+        ));
+
+        let metadata = service(true)
+            .get_metadata(&component_id, Some(0))
+            .await
+            .unwrap();
+        assert_eq!(metadata.version, 0);
+        assert_eq!(metadata.size, EMPTY_COMPONENT.len() as u64);
+        // The type is not known without the properties file
+        assert_eq!(metadata.component_type, ComponentType::Durable);
+        assert!(metadata.files.is_empty());
+    }
+
+    /// Counts the reads and writes of the compiled component store, which never has a component
+    #[derive(Default)]
+    struct CountingCompiledComponentService {
+        gets: AtomicUsize,
+        puts: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl CompiledComponentService for CountingCompiledComponentService {
+        async fn get(
+            &self,
+            _component_id: &ComponentId,
+            _component_version: u64,
+            _engine: &Engine,
+        ) -> Result<Option<Component>, CompiledComponentError> {
+            self.gets.fetch_add(1, Ordering::SeqCst);
+            Ok(None)
+        }
+
+        async fn put(
+            &self,
+            _component_id: &ComponentId,
+            _component_version: u64,
+            _component: &Component,
+        ) -> Result<(), GolemError> {
+            self.puts.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[test]
+    async fn forced_recompilation_bypasses_the_caches() {
+        let root = tempfile::tempdir().unwrap();
+        let compiled = Arc::new(CountingCompiledComponentService::default());
+        let service = local_service(root.path(), None, compiled.clone(), false);
+        let component_id = ComponentId::new_v4();
+        service
+            .store(
+                &component_id,
+                ComponentType::Durable,
+                EMPTY_COMPONENT.to_vec(),
+            )
+            .await
+            .unwrap();
+        let engine = Engine::default();
+        let counts = || {
+            (
+                compiled.gets.load(Ordering::SeqCst),
+                compiled.puts.load(Ordering::SeqCst),
+            )
+        };
+
+        service
+            .ensure_cached(&engine, &component_id, 0, false)
+            .await
+            .unwrap();
+        assert_eq!(counts(), (1, 1));
+        assert!(service.compile_result(&component_id, 0).is_some());
+
+        // Served from the in-memory cache
+        service
+            .ensure_cached(&engine, &component_id, 0, false)
+            .await
+            .unwrap();
+        assert_eq!(counts(), (1, 1));
+
+        // Compiled again without reading the store, and the artifact in the store replaced
+        service
+            .ensure_cached(&engine, &component_id, 0, true)
+            .await
+            .unwrap();
+        assert_eq!(counts(), (1, 2));
+        assert_eq!(
+            service.compile_result(&component_id, 0).unwrap().error,
+            None
+        );
+    }
+
+    #[test]
+    async fn compile_results_keep_the_last_compilation() {
+        let results = CompileResults::new(16, Duration::from_secs(60));
+        let component_id = ComponentId::new_v4();
+        let failure = |reason: &str| GolemError::ComponentParseFailed {
+            component_id: component_id.clone(),
+            component_version: 0,
+            reason: reason.to_string(),
+        };
+        assert_eq!(results.get(&component_id, 0), None);
+
+        results
+            .record(
+                &component_id,
+                0,
+                &Err(failure("first")),
+                Duration::from_millis(5),
+            )
+            .await;
+        results
+            .record(
+                &component_id,
+                0,
+                &Err(failure("second")),
+                Duration::from_millis(7),
+            )
+            .await;
+
+        assert_eq!(
+            results.get(&component_id, 0),
+            Some(ComponentCompileResult {
+                error: Some(failure("second").to_string()),
+                duration: Duration::from_millis(7),
+            })
+        );
+        assert_eq!(results.get(&component_id, 1), None);
+    }
+
+    #[test]
+    async fn compile_results_are_kept_without_a_component_cache() {
+        let results = CompileResults::new(0, Duration::from_secs(60));
+        let component_id = ComponentId::new_v4();
+        let failure = GolemError::ComponentParseFailed {
+            component_id: component_id.clone(),
+            component_version: 0,
+            reason: "invalid".to_string(),
+        };
+
+        results
+            .record(
+                &component_id,
+                0,
+                &Err(failure.clone()),
+                Duration::from_millis(5),
+            )
+            .await;
+
+        assert_eq!(
+            results.get(&component_id, 0),
+            Some(ComponentCompileResult {
+                error: Some(failure.to_string()),
+                duration: Duration::from_millis(5),
+            })
+        );
+    }
+
+    fn signing_key() -> (Ed25519KeyPair, PublicKey) {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let public_key = PublicKey(key_pair.public_key().as_ref().to_vec());
+        (key_pair, public_key)
+    }
+
+    #[test]
+    fn valid_signatures_are_accepted() {
+        let (key_pair, public_key) = signing_key();
+        let signature = key_pair.sign(EMPTY_COMPONENT);
+
+        assert!(verify_signature(
+            &public_key,
+            Some(signature.as_ref()),
+            EMPTY_COMPONENT,
+            &ComponentId::new_v4(),
+            1
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn tampered_components_are_rejected() {
+        let (key_pair, public_key) = signing_key();
+        let signature = key_pair.sign(EMPTY_COMPONENT);
+        let tampered = [EMPTY_COMPONENT, b"\0\x04\x03abc"].concat();
+
+        let result = verify_signature(
+            &public_key,
+            Some(signature.as_ref()),
+            &tampered,
+            &ComponentId::new_v4(),
+            1,
+        );
+
+        assert!(
+            matches!(result, Err(GolemError::ComponentSignatureInvalid { .. })),
+            "{result:?}"
+        );
+    }
+
+    #[test]
+    fn missing_signatures_are_rejected() {
+        let (_, public_key) = signing_key();
+
+        let result = verify_signature(
+            &public_key,
+            None,
+            EMPTY_COMPONENT,
+            &ComponentId::new_v4(),
+            1,
+        );
+
+        match result {
+            Err(GolemError::ComponentSignatureInvalid { reason, .. }) => {
+                assert_eq!(reason, "Missing signature")
+            }
+            other => panic!("Unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn reported_memories_are_checked_against_the_component() {
+        let component_id = ComponentId::new_v4();
+
+        assert!(check_reported_memories(
+            &component_id,
+            0,
+            &[],
+            EMPTY_COMPONENT
+        ));
+        assert!(!check_reported_memories(
+            &component_id,
+            0,
+            &[LinearMemory {
+                initial: 65536,
+                maximum: None,
+            }],
+            EMPTY_COMPONENT
+        ));
+        assert!(!check_reported_memories(&component_id, 0, &[], b"not wasm"));
+    }
+
+    #[test]
+    fn large_components_go_to_their_own_pool() {
+        let cache = ComponentCache::new(16, Duration::from_secs(60), Some(1024), 2);
+        assert_eq!(cache.admission(1024), ComponentCacheAdmission::Main);
+        assert_eq!(cache.admission(1025), ComponentCacheAdmission::Large);
+
+        let cache = ComponentCache::new(16, Duration::from_secs(60), Some(1024), 0);
+        assert_eq!(cache.admission(512), ComponentCacheAdmission::Main);
+        assert_eq!(cache.admission(2048), ComponentCacheAdmission::Bypass);
+
+        let cache = ComponentCache::new(16, Duration::from_secs(60), None, 2);
+        assert_eq!(cache.admission(u64::MAX), ComponentCacheAdmission::Main);
+    }
+
+    #[test]
+    fn zero_capacity_disables_the_main_cache() {
+        let cache = ComponentCache::new(0, Duration::from_secs(60), None, 0);
+        assert!(cache.main.is_none());
+        assert_eq!(cache.admission(512), ComponentCacheAdmission::Bypass);
+        cache.remove(&ComponentKey {
+            component_id: ComponentId::new_v4(),
+            component_version: 0,
+        });
+
+        let cache = ComponentCache::new(0, Duration::from_secs(60), Some(1024), 2);
+        assert_eq!(cache.admission(512), ComponentCacheAdmission::Bypass);
+        assert_eq!(cache.admission(2048), ComponentCacheAdmission::Large);
+    }
+
+    #[test]
+    fn sha256_hashes_are_normalized() {
+        let hash = "AB".repeat(32);
+        assert_eq!(normalize_sha256(&hash).unwrap(), "ab".repeat(32));
+        assert_eq!(
+            normalize_sha256(&format!("sha256:{hash}")).unwrap(),
+            "ab".repeat(32)
+        );
+        assert!(normalize_sha256("abc").is_err());
+        assert!(normalize_sha256(&"zz".repeat(32)).is_err());
+    }
+
+    fn grpc_component_with_unknown_file_permissions(
+        component_id: &ComponentId,
+    ) -> golem_api_grpc::proto::golem::component::Component {
+        golem_api_grpc::proto::golem::component::Component {
+            versioned_component_id: Some(
+                golem_api_grpc::proto::golem::component::VersionedComponentId {
+                    component_id: Some(component_id.clone().into()),
+                    version: 3,
+                },
+            ),
+            component_size: 1024,
+            files: vec![
+                golem_api_grpc::proto::golem::component::InitialComponentFile {
+                    key: "key".to_string(),
+                    path: "/data.txt".to_string(),
+                    permissions: 99,
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn lenient_metadata_ignores_undecodable_files() {
+        let component_id = ComponentId::new_v4();
+        let component = grpc_component_with_unknown_file_permissions(&component_id);
+
+        assert!(matches!(
+            metadata_from_grpc(&component_id, component.clone(), false),
+            Err(GrpcError::Unexpected(_))
+        ));
+
+        let metadata = metadata_from_grpc(&component_id, component, true).unwrap();
+        assert_eq!(metadata.version, 3);
+        assert_eq!(metadata.size, 1024);
+        assert!(metadata.files.is_empty());
+        assert!(metadata.exports.is_empty());
+    }
+
+    #[test]
+    fn annotations_are_read_from_grpc_and_local_properties() {
+        let component_id = ComponentId::new_v4();
+        let component = golem_api_grpc::proto::golem::component::Component {
+            versioned_component_id: Some(
+                golem_api_grpc::proto::golem::component::VersionedComponentId {
+                    component_id: Some(component_id.clone().into()),
+                    version: 1,
+                },
+            ),
+            metadata: Some(golem_api_grpc::proto::golem::component::ComponentMetadata {
+                annotations: [("team".to_string(), "payments".to_string())].into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let metadata = metadata_from_grpc(&component_id, component, false).unwrap();
+        assert_eq!(
+            metadata.annotations,
+            BTreeMap::from([("team".to_string(), "payments".to_string())])
+        );
+
+        let props = r#"{"version":1,"size":0,"memories":[],"exports":[],"componentType":"Durable","files":[],"annotations":{"cost-center":"42"}}"#;
+        let metadata: ComponentMetadata = serde_json::from_str(props).unwrap();
+        assert_eq!(
+            metadata.annotations,
+            BTreeMap::from([("cost-center".to_string(), "42".to_string())])
+        );
+
+        let props = r#"{"version":1,"size":0,"memories":[],"exports":[],"componentType":"Durable","files":[]}"#;
+        let metadata: ComponentMetadata = serde_json::from_str(props).unwrap();
+        assert!(metadata.annotations.is_empty());
+    }
+
+    #[test]
+    fn listed_versions_skip_the_broken_ones() {
+        let component_id = ComponentId::new_v4();
+        let component = |version| golem_api_grpc::proto::golem::component::Component {
+            versioned_component_id: Some(
+                golem_api_grpc::proto::golem::component::VersionedComponentId {
+                    component_id: Some(component_id.clone().into()),
+                    version,
+                },
+            ),
+            ..Default::default()
+        };
+        let components = vec![
+            component(1),
+            component(3),
+            grpc_component_with_unknown_file_permissions(&component_id),
+            golem_api_grpc::proto::golem::component::Component::default(),
+            component(0),
+        ];
+        // Every version but 1 gets an export, so only version 1 has none
+        let transformer: MetadataTransformer = Arc::new(|mut metadata: ComponentMetadata| {
+            if metadata.version != 1 {
+                metadata.exports = vec![AnalysedExport::Function(AnalysedFunction {
+                    name: "run".to_string(),
+                    parameters: vec![],
+                    results: vec![],
+                })];
+            }
+            metadata
+        });
+
+        let versions = |lenient, reject_empty_exports| {
+            versions_from_grpc(
+                &component_id,
+                components.clone(),
+                lenient,
+                &Some(transformer.clone()),
+                reject_empty_exports,
+            )
+            .into_iter()
+            .map(|metadata| metadata.version)
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(versions(false, false), vec![3, 1, 0]);
+        assert_eq!(versions(true, false), vec![3, 3, 1, 0]);
+        assert_eq!(versions(false, true), vec![3, 0]);
+    }
+
+    #[test]
+    fn metadata_is_transformed_before_it_is_checked() {
+        let component_id = ComponentId::new_v4();
+        let metadata = ComponentMetadata {
+            version: 2,
+            size: 0,
+            memories: vec![],
+            exports: vec![],
+            component_type: ComponentType::Durable,
+            files: vec![],
+            base_version: None,
+            annotations: BTreeMap::new(),
+        };
+        let transformer: MetadataTransformer = Arc::new(|mut metadata: ComponentMetadata| {
+            metadata
+                .annotations
+                .insert("team".to_string(), "payments".to_string());
+            metadata.exports = vec![AnalysedExport::Function(AnalysedFunction {
+                name: "run".to_string(),
+                parameters: vec![],
+                results: vec![],
+            })];
+            metadata
+        });
+
+        // The exports added by the transformer count for reject_empty_exports
+        let prepared = prepare_metadata(&Some(transformer), true, &component_id, metadata).unwrap();
+        assert_eq!(
+            prepared.annotations,
+            BTreeMap::from([("team".to_string(), "payments".to_string())])
+        );
+        assert_eq!(prepared.exports.len(), 1);
+        assert_eq!(prepared.version, 2);
+    }
+
+    #[test]
+    fn empty_exports_are_only_rejected_when_configured() {
+        let component_id = ComponentId::new_v4();
+        let metadata = ComponentMetadata {
+            version: 2,
+            size: 0,
+            memories: vec![],
+            exports: vec![],
+            component_type: ComponentType::Durable,
+            files: vec![],
+            base_version: None,
+            annotations: BTreeMap::new(),
+        };
+
+        assert!(prepare_metadata(&None, false, &component_id, metadata.clone()).is_ok());
+        assert!(matches!(
+            prepare_metadata(&None, true, &component_id, metadata),
+            Err(GolemError::ComponentParseFailed {
+                component_version: 2,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn lenient_metadata_requires_the_version() {
+        let component_id = ComponentId::new_v4();
+        let component = golem_api_grpc::proto::golem::component::Component {
+            versioned_component_id: None,
+            ..grpc_component_with_unknown_file_permissions(&component_id)
+        };
+
+        assert!(metadata_from_grpc(&component_id, component, true).is_err());
+    }
+
+    fn props(edit: impl FnOnce(&mut serde_json::Map<String, serde_json::Value>)) -> String {
+        let mut props = match serde_json::to_value(ComponentMetadata {
+            version: 3,
+            size: 8,
+            memories: vec![],
+            exports: vec![],
+            component_type: ComponentType::Durable,
+            files: vec![],
+            base_version: None,
+            annotations: BTreeMap::new(),
+        })
+        .unwrap()
+        {
+            serde_json::Value::Object(props) => props,
+            _ => unreachable!(),
+        };
+        edit(&mut props);
+        serde_json::Value::Object(props).to_string()
+    }
+
+    fn props_error(data: &str) -> String {
+        parse_props_file(std::path::Path::new("/components/c-3.json"), data).unwrap_err()
+    }
+
+    #[test]
+    fn props_files_are_parsed() {
+        let path = std::path::Path::new("/components/c-3.json");
+        let metadata = parse_props_file(path, &props(|_| {})).unwrap();
+        assert_eq!(metadata.version, 3);
+
+        // Unknown fields, such as from a newer executor, are ignored
+        let metadata = parse_props_file(
+            path,
+            &props(|props| {
+                props.insert("compression".to_string(), "zstd".into());
+            }),
+        )
+        .unwrap();
+        assert_eq!(metadata.size, 8);
+    }
+
+    #[test]
+    fn invalid_props_files_are_reported_with_the_reason() {
+        let error = props_error(&props(|props| {
+            props.remove("files");
+        }));
+        assert!(error.starts_with("Invalid properties file /components/c-3.json of component"));
+        assert!(error.contains("missing field `files`"), "{error}");
+
+        let error = props_error(&props(|props| {
+            props.insert("size".to_string(), "large".into());
+        }));
+        assert!(error.contains("invalid type: string \"large\""), "{error}");
+
+        let error = props_error(&props(|props| {
+            props.insert("componentType".to_string(), "Persistent".into());
+        }));
+        assert!(error.contains("unknown variant `Persistent`"), "{error}");
+
+        let error = props_error(&props(|props| {
+            props.insert("files".to_string(), serde_json::json!({}));
+        }));
+        assert!(error.contains("invalid type: map"), "{error}");
+
+        let truncated = props(|_| {});
+        let error = props_error(&truncated[..truncated.len() - 1]);
+        assert!(error.contains("EOF while parsing"), "{error}");
+
+        let error = props_error("");
+        assert!(
+            error.contains("EOF while parsing a value at line 1 column 0"),
+            "{error}"
+        );
+
+        let error = props_error("[]");
+        assert!(error.contains("invalid type: sequence"), "{error}");
+    }
 }
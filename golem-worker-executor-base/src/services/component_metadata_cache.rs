@@ -0,0 +1,459 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use golem_common::cache::{BackgroundEvictionMode, Cache, FullCacheEvictionMode, SimpleCache};
+use golem_common::model::{ComponentId, ComponentVersion};
+
+use crate::error::GolemError;
+use crate::metrics::component::record_component_metadata_cache_stats;
+use crate::services::component::ComponentMetadata;
+use crate::services::golem_config::{ComponentCacheConfig, ComponentMetadataCacheConfig};
+
+/// How often the statistics of the metadata cache are published as metrics
+const STATS_PERIOD: Duration = Duration::from_secs(60);
+
+/// Loads the metadata of a component version when it is not cached
+pub type MetadataLoad = Pin<Box<dyn Future<Output = Result<ComponentMetadata, GolemError>> + Send>>;
+
+/// Cache of the metadata of component versions.
+///
+/// The metadata of a component version never changes, so it can be shared by any number of
+/// executors, unlike the compiled components which always stay in the executor's memory.
+#[async_trait]
+pub trait ComponentMetadataCache {
+    /// Gets the cached metadata of a component version, or caches the result of `load`
+    async fn get_or_insert(
+        &self,
+        component_id: &ComponentId,
+        version: ComponentVersion,
+        load: MetadataLoad,
+    ) -> Result<ComponentMetadata, GolemError>;
+
+    async fn remove(&self, component_id: &ComponentId, version: ComponentVersion);
+
+    /// Number of cached entries, in total and by component
+    fn stats(&self) -> ComponentMetadataCacheStats;
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ComponentMetadataCacheStats {
+    pub entries: usize,
+    pub versions_per_component: HashMap<ComponentId, usize>,
+}
+
+impl ComponentMetadataCacheStats {
+    fn record(&self) {
+        record_component_metadata_cache_stats(
+            self.entries,
+            self.versions_per_component.len(),
+            self.versions_per_component
+                .values()
+                .copied()
+                .max()
+                .unwrap_or_default(),
+        );
+    }
+}
+
+/// Creates the metadata cache of the component services, or `None` when metadata caching is
+/// disabled
+pub async fn configured(
+    config: &ComponentCacheConfig,
+) -> Option<Arc<dyn ComponentMetadataCache + Send + Sync>> {
+    if !config.cache_metadata {
+        return None;
+    }
+
+    let in_memory =
+        InMemoryComponentMetadataCache::new(config.max_metadata_capacity, config.time_to_idle)
+            .with_max_versions_per_component(config.max_metadata_versions_per_component);
+    let cache: Arc<dyn ComponentMetadataCache + Send + Sync> = match &config.metadata_backend {
+        ComponentMetadataCacheConfig::InMemory => Arc::new(in_memory),
+        #[cfg(feature = "redis-metadata-cache")]
+        ComponentMetadataCacheConfig::Redis(redis_config) => {
+            tracing::info!(
+                "Sharing component metadata through Redis at {}",
+                redis_config.redis.url()
+            );
+            let pool = golem_common::redis::RedisPool::configured(&redis_config.redis)
+                .await
+                .expect("Failed to create the Redis pool of the component metadata cache");
+            Arc::new(redis::RedisComponentMetadataCache::new(
+                in_memory,
+                pool,
+                redis_config.ttl,
+            ))
+        }
+    };
+    publish_stats(Arc::downgrade(&cache));
+    Some(cache)
+}
+
+/// Periodically publishes the statistics of the cache as metrics, until the cache is dropped
+fn publish_stats(cache: Weak<dyn ComponentMetadataCache + Send + Sync>) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(STATS_PERIOD).await;
+            match cache.upgrade() {
+                Some(cache) => cache.stats().record(),
+                None => break,
+            }
+        }
+    });
+}
+
+pub struct InMemoryComponentMetadataCache {
+    cache: Cache<(ComponentId, ComponentVersion), (), ComponentMetadata, GolemError>,
+    max_versions_per_component: Option<usize>,
+    // The cached versions of each component, most recently used first. Only tracked when
+    // `max_versions_per_component` is set
+    recent_versions: Mutex<HashMap<ComponentId, VecDeque<ComponentVersion>>>,
+}
+
+impl InMemoryComponentMetadataCache {
+    pub fn new(max_capacity: usize, time_to_idle: Duration) -> Self {
+        Self {
+            cache: Cache::new(
+                Some(max_capacity),
+                FullCacheEvictionMode::LeastRecentlyUsed(1),
+                BackgroundEvictionMode::OlderThan {
+                    ttl: time_to_idle,
+                    period: Duration::from_secs(60),
+                },
+                "component_metadata",
+            ),
+            max_versions_per_component: None,
+            recent_versions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Keeps at most `max_versions` versions of each component, evicting the least recently
+    /// used ones, so that a component with many versions cannot take over the whole cache
+    pub fn with_max_versions_per_component(mut self, max_versions: Option<usize>) -> Self {
+        self.max_versions_per_component = max_versions.map(|max| max.max(1));
+        self
+    }
+
+    // Marks `version` as the most recently used one of the component, returning the versions
+    // to evict to stay within the limit
+    fn touch(
+        &self,
+        component_id: &ComponentId,
+        version: ComponentVersion,
+    ) -> Vec<ComponentVersion> {
+        let Some(max_versions) = self.max_versions_per_component else {
+            return Vec::new();
+        };
+
+        let mut recent_versions = self.recent_versions.lock().unwrap();
+        let versions = recent_versions.entry(component_id.clone()).or_default();
+        // Versions evicted by the cache itself, because they were idle or the cache was full,
+        // are no longer tracked
+        versions.retain(|v| *v != version && self.cache.contains_key(&(component_id.clone(), *v)));
+        versions.push_front(version);
+        if versions.len() > max_versions {
+            versions.split_off(max_versions).into()
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn forget(&self, component_id: &ComponentId, version: ComponentVersion) {
+        let mut recent_versions = self.recent_versions.lock().unwrap();
+        if let Some(versions) = recent_versions.get_mut(component_id) {
+            versions.retain(|v| *v != version);
+            if versions.is_empty() {
+                recent_versions.remove(component_id);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ComponentMetadataCache for InMemoryComponentMetadataCache {
+    async fn get_or_insert(
+        &self,
+        component_id: &ComponentId,
+        version: ComponentVersion,
+        load: MetadataLoad,
+    ) -> Result<ComponentMetadata, GolemError> {
+        let result = self
+            .cache
+            .get_or_insert_simple(&(component_id.clone(), version), || load)
+            .await;
+        if result.is_ok() {
+            for evicted in self.touch(component_id, version) {
+                self.cache.remove(&(component_id.clone(), evicted));
+            }
+        }
+        result
+    }
+
+    async fn remove(&self, component_id: &ComponentId, version: ComponentVersion) {
+        self.cache.remove(&(component_id.clone(), version));
+        self.forget(component_id, version);
+    }
+
+    fn stats(&self) -> ComponentMetadataCacheStats {
+        let mut stats = ComponentMetadataCacheStats::default();
+        for ((component_id, _), _) in self.cache.iter() {
+            stats.entries += 1;
+            *stats
+                .versions_per_component
+                .entry(component_id)
+                .or_default() += 1;
+        }
+        stats
+    }
+}
+
+#[cfg(feature = "redis-metadata-cache")]
+mod redis {
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use bytes::Bytes;
+    use fred::types::Expiration;
+    use golem_common::model::{ComponentId, ComponentVersion};
+    use golem_common::redis::RedisPool;
+    use tracing::warn;
+
+    use super::{
+        ComponentMetadataCache, ComponentMetadataCacheStats, InMemoryComponentMetadataCache,
+        MetadataLoad,
+    };
+    use crate::error::GolemError;
+    use crate::services::component::ComponentMetadata;
+
+    /// Shares the metadata of component versions between executors through Redis, keeping an
+    /// in-memory cache in front of it.
+    ///
+    /// Redis is only an optimisation: when it is unavailable, or holds an entry that cannot be
+    /// decoded, the metadata is loaded from the component service as without it.
+    pub struct RedisComponentMetadataCache {
+        local: InMemoryComponentMetadataCache,
+        redis: RedisPool,
+        ttl: Duration,
+    }
+
+    impl RedisComponentMetadataCache {
+        pub fn new(local: InMemoryComponentMetadataCache, redis: RedisPool, ttl: Duration) -> Self {
+            Self { local, redis, ttl }
+        }
+
+        fn key(component_id: &ComponentId, version: ComponentVersion) -> String {
+            format!("component-metadata:{component_id}:{version}")
+        }
+    }
+
+    #[async_trait]
+    impl ComponentMetadataCache for RedisComponentMetadataCache {
+        async fn get_or_insert(
+            &self,
+            component_id: &ComponentId,
+            version: ComponentVersion,
+            load: MetadataLoad,
+        ) -> Result<ComponentMetadata, GolemError> {
+            let redis = self.redis.clone();
+            let key = Self::key(component_id, version);
+            let ttl = self.ttl.as_secs().max(1) as i64;
+
+            self.local
+                .get_or_insert(
+                    component_id,
+                    version,
+                    Box::pin(async move {
+                        let api = redis.with("component_metadata_cache", "get_or_insert");
+                        match api.get::<Option<Bytes>, _>(&key).await {
+                            Ok(Some(bytes)) => match serde_json::from_slice(&bytes) {
+                                Ok(metadata) => return Ok(metadata),
+                                Err(err) => warn!("Ignoring undecodable cached {key}: {err}"),
+                            },
+                            Ok(None) => {}
+                            Err(err) => warn!("Failed to get {key} from Redis: {err}"),
+                        }
+
+                        let metadata = load.await?;
+                        match serde_json::to_vec(&metadata) {
+                            Ok(bytes) => {
+                                if let Err(err) = api
+                                    .set::<(), _, _>(
+                                        &key,
+                                        Bytes::from(bytes),
+                                        Some(Expiration::EX(ttl)),
+                                        None,
+                                        false,
+                                    )
+                                    .await
+                                {
+                                    warn!("Failed to store {key} in Redis: {err}");
+                                }
+                            }
+                            Err(err) => warn!("Failed to serialize {key}: {err}"),
+                        }
+                        Ok(metadata)
+                    }),
+                )
+                .await
+        }
+
+        async fn remove(&self, component_id: &ComponentId, version: ComponentVersion) {
+            self.local.remove(component_id, version).await;
+            let key = Self::key(component_id, version);
+            if let Err(err) = self
+                .redis
+                .with("component_metadata_cache", "remove")
+                .del::<(), _>(&key)
+                .await
+            {
+                warn!("Failed to remove {key} from Redis: {err}");
+            }
+        }
+
+        // Only the entries of this executor, Redis is shared with the others
+        fn stats(&self) -> ComponentMetadataCacheStats {
+            self.local.stats()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use golem_common::model::{ComponentId, ComponentType};
+
+    use super::{ComponentMetadataCache, InMemoryComponentMetadataCache, MetadataLoad};
+    use crate::services::component::ComponentMetadata;
+
+    fn load(version: u64, loads: Arc<AtomicUsize>) -> MetadataLoad {
+        Box::pin(async move {
+            loads.fetch_add(1, Ordering::SeqCst);
+            Ok(ComponentMetadata {
+                version,
+                size: 0,
+                memories: vec![],
+                exports: vec![],
+                component_type: ComponentType::Durable,
+                files: vec![],
+                base_version: None,
+                annotations: Default::default(),
+            })
+        })
+    }
+
+    #[test]
+    async fn in_memory_cache_loads_each_version_once() {
+        let cache = InMemoryComponentMetadataCache::new(16, Duration::from_secs(60));
+        let component_id = ComponentId::new_v4();
+        let loads = Arc::new(AtomicUsize::new(0));
+
+        for _ in 0..3 {
+            let metadata = cache
+                .get_or_insert(&component_id, 1, load(1, loads.clone()))
+                .await
+                .unwrap();
+            assert_eq!(metadata.version, 1);
+        }
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+
+        cache.remove(&component_id, 1).await;
+        cache
+            .get_or_insert(&component_id, 1, load(1, loads.clone()))
+            .await
+            .unwrap();
+        assert_eq!(loads.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    async fn in_memory_cache_keeps_the_most_recently_used_versions_per_component() {
+        let cache = InMemoryComponentMetadataCache::new(16, Duration::from_secs(60))
+            .with_max_versions_per_component(Some(2));
+        let noisy = ComponentId::new_v4();
+        let quiet = ComponentId::new_v4();
+        let loads = Arc::new(AtomicUsize::new(0));
+
+        cache
+            .get_or_insert(&quiet, 0, load(0, loads.clone()))
+            .await
+            .unwrap();
+        for version in [0, 1, 0, 2] {
+            cache
+                .get_or_insert(&noisy, version, load(version, loads.clone()))
+                .await
+                .unwrap();
+        }
+        assert_eq!(loads.load(Ordering::SeqCst), 4);
+
+        let stats = cache.stats();
+        assert_eq!(stats.entries, 3);
+        assert_eq!(stats.versions_per_component.get(&noisy), Some(&2));
+        assert_eq!(stats.versions_per_component.get(&quiet), Some(&1));
+
+        // Version 1 was the least recently used one, version 0 is still cached
+        cache
+            .get_or_insert(&noisy, 0, load(0, loads.clone()))
+            .await
+            .unwrap();
+        assert_eq!(loads.load(Ordering::SeqCst), 4);
+        cache
+            .get_or_insert(&noisy, 1, load(1, loads.clone()))
+            .await
+            .unwrap();
+        assert_eq!(loads.load(Ordering::SeqCst), 5);
+    }
+
+    #[test]
+    async fn in_memory_cache_forgets_versions_evicted_by_the_cache() {
+        let cache = InMemoryComponentMetadataCache::new(16, Duration::from_secs(60))
+            .with_max_versions_per_component(Some(2));
+        let component_id = ComponentId::new_v4();
+        let loads = Arc::new(AtomicUsize::new(0));
+
+        for version in [0, 1] {
+            cache
+                .get_or_insert(&component_id, version, load(version, loads.clone()))
+                .await
+                .unwrap();
+        }
+        // Evicted without going through the metadata cache, as the idle entries are
+        cache.cache.remove(&(component_id.clone(), 1));
+        cache
+            .get_or_insert(&component_id, 2, load(2, loads.clone()))
+            .await
+            .unwrap();
+
+        // Version 0 still fits in the limit, as version 1 is no longer counted
+        assert_eq!(
+            cache.recent_versions.lock().unwrap().get(&component_id),
+            Some(&[2, 0].into())
+        );
+        cache
+            .get_or_insert(&component_id, 0, load(0, loads.clone()))
+            .await
+            .unwrap();
+        assert_eq!(loads.load(Ordering::SeqCst), 3);
+    }
+}
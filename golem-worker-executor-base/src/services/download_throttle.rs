@@ -0,0 +1,113 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+/// Limits the combined rate of the component downloads of a source with a token bucket, so that
+/// many workers starting at once cannot saturate the network and starve the live traffic.
+///
+/// The bucket holds at most one second worth of bytes, so downloads can burst up to that after
+/// being idle. Chunks larger than the bucket are let through once the bucket got refilled, and
+/// put it in debt, delaying the following chunks accordingly.
+pub struct DownloadThrottle {
+    bytes_per_second: u64,
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    // Negative when a chunk larger than the available bytes was let through
+    available: f64,
+    last_refill: Instant,
+}
+
+impl DownloadThrottle {
+    pub fn new(bytes_per_second: u64) -> Self {
+        let bytes_per_second = bytes_per_second.max(1);
+        Self {
+            bytes_per_second,
+            bucket: Mutex::new(Bucket {
+                available: bytes_per_second as f64,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// The throttle of a source configured with the given limit, if any
+    pub fn configured(max_bytes_per_second: Option<u64>) -> Option<Arc<Self>> {
+        max_bytes_per_second.map(|limit| Arc::new(Self::new(limit)))
+    }
+
+    /// Waits until `bytes` more bytes can be downloaded. Waiting downloads are served in order,
+    /// as the bucket stays locked while waiting for it to refill.
+    pub async fn acquire(&self, bytes: usize) {
+        let rate = self.bytes_per_second as f64;
+        let mut bucket = self.bucket.lock().await;
+
+        let now = Instant::now();
+        let refilled = now.duration_since(bucket.last_refill).as_secs_f64() * rate;
+        bucket.available = (bucket.available + refilled).min(rate);
+        bucket.last_refill = now;
+
+        if bucket.available < 0.0 {
+            tokio::time::sleep(Duration::from_secs_f64(-bucket.available / rate)).await;
+            bucket.available = 0.0;
+            bucket.last_refill = Instant::now();
+        }
+        bucket.available -= bytes as f64;
+    }
+}
+
+/// Waits for `throttle` to let `bytes` more bytes through, when there is one
+pub async fn throttle(throttle: &Option<Arc<DownloadThrottle>>, bytes: usize) {
+    if let Some(throttle) = throttle {
+        throttle.acquire(bytes).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::time::Duration;
+
+    use tokio::time::Instant;
+
+    use super::DownloadThrottle;
+
+    #[test]
+    async fn bursts_up_to_one_second_of_bytes() {
+        let throttle = DownloadThrottle::new(10_000);
+
+        let start = Instant::now();
+        throttle.acquire(4_000).await;
+        throttle.acquire(6_000).await;
+        assert!(start.elapsed() < Duration::from_millis(100));
+    }
+
+    #[test]
+    async fn limits_the_rate_after_the_burst() {
+        let throttle = DownloadThrottle::new(10_000);
+
+        let start = Instant::now();
+        // The whole bucket, then 3000 bytes more
+        throttle.acquire(10_000).await;
+        throttle.acquire(3_000).await;
+        throttle.acquire(1).await;
+        assert!(start.elapsed() >= Duration::from_millis(290));
+    }
+}
@@ -14,7 +14,9 @@
 
 use anyhow::anyhow;
 use async_lock::Mutex;
-use golem_common::model::{AccountId, InitialComponentFileKey};
+use futures::future::try_join_all;
+use futures::stream::{self, StreamExt};
+use golem_common::model::{AccountId, InitialComponentFile, InitialComponentFileKey};
 use std::collections::HashMap;
 use std::path::Path;
 use std::sync::atomic::AtomicU64;
@@ -33,6 +35,12 @@ pub struct FileUseToken {
     _handle: Arc<CacheEntry>,
 }
 
+/// Initial files downloaded ahead of a worker start. They stay in the cache of the file loader
+/// as long as this is kept.
+pub struct PrefetchedFiles {
+    _tokens: Vec<FileUseToken>,
+}
+
 /// Interface for loading files and making them available to workers.
 /// This will hardlink to a temporary directory to avoid copying files between workers. Beware
 /// that hardlinking is only possible within the same filesystem.
@@ -64,6 +72,89 @@ impl FileLoader {
         })
     }
 
+    /// Checks that the content of every file exists in the blob storage, failing with
+    /// `GolemError::MissingInitialFile` otherwise, instead of only when a worker loads the file
+    pub async fn ensure_exist(
+        &self,
+        account_id: &AccountId,
+        files: &[InitialComponentFile],
+    ) -> Result<(), GolemError> {
+        let checks = files.iter().map(|file| async move {
+            let exists = self
+                .initial_component_files_service
+                .exists(account_id, &file.key)
+                .await
+                .map_err(|err| {
+                    GolemError::initial_file_download_failed(file.path.to_string(), err)
+                })?;
+
+            if exists {
+                Ok(())
+            } else {
+                Err(GolemError::missing_initial_file(
+                    file.key.to_string(),
+                    file.path.to_string(),
+                ))
+            }
+        });
+
+        try_join_all(checks).await?;
+        Ok(())
+    }
+
+    /// Downloads the files into the cache, at most `max_concurrency` at a time, so that workers
+    /// get them from there. Fails with all the files that could not be downloaded.
+    pub async fn prefetch(
+        &self,
+        account_id: &AccountId,
+        files: &[InitialComponentFile],
+        max_concurrency: usize,
+    ) -> Result<PrefetchedFiles, GolemError> {
+        let results: Vec<_> = stream::iter(files)
+            .map(|file| async move {
+                self.get_or_add_cache_entry(account_id, &file.key)
+                    .await
+                    .map(|cache_entry| FileUseToken {
+                        _handle: cache_entry,
+                    })
+                    .map_err(|err| (file.path.to_string(), err.to_string()))
+            })
+            .buffer_unordered(max_concurrency.max(1))
+            .collect()
+            .await;
+
+        let mut tokens = Vec::with_capacity(files.len());
+        let mut failures = Vec::new();
+        for result in results {
+            match result {
+                Ok(token) => tokens.push(token),
+                Err(failure) => failures.push(failure),
+            }
+        }
+
+        if failures.is_empty() {
+            Ok(PrefetchedFiles { _tokens: tokens })
+        } else {
+            let paths = failures
+                .iter()
+                .map(|(path, _)| path.as_str())
+                .collect::<Vec<_>>();
+            let reasons = failures
+                .iter()
+                .map(|(path, reason)| format!("{path}: {reason}"))
+                .collect::<Vec<_>>();
+            Err(GolemError::initial_file_download_failed(
+                paths.join(", "),
+                format!(
+                    "{} of {} files failed to download: {}",
+                    failures.len(),
+                    files.len(),
+                    reasons.join("; ")
+                ),
+            ))
+        }
+    }
+
     /// Read-only files can be safely shared between workers. Download once to cache and hardlink to target.
     /// The file will only be valid until the token is dropped.
     pub async fn get_read_only_to(
@@ -171,6 +262,7 @@ impl FileLoader {
                     target.display()
                 );
                 tokio::fs::copy(&cache_entry_path, target).await?;
+                return Ok(());
             }
         }
 
@@ -301,3 +393,109 @@ impl Drop for InitializedCacheEntry {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::sync::Arc;
+
+    use bytes::Bytes;
+    use golem_common::model::{
+        AccountId, ComponentFilePath, ComponentFilePermissions, InitialComponentFile,
+        InitialComponentFileKey,
+    };
+    use golem_service_base::service::initial_component_files::InitialComponentFilesService;
+    use golem_service_base::storage::blob::memory::InMemoryBlobStorage;
+
+    use crate::error::GolemError;
+    use crate::services::file_loader::FileLoader;
+
+    fn file(key: InitialComponentFileKey, path: &str) -> InitialComponentFile {
+        InitialComponentFile {
+            key,
+            path: ComponentFilePath::from_abs_str(path).unwrap(),
+            permissions: ComponentFilePermissions::ReadOnly,
+        }
+    }
+
+    #[test]
+    async fn prefetch_reports_all_missing_files() {
+        let files_service = Arc::new(InitialComponentFilesService::new(Arc::new(
+            InMemoryBlobStorage::new(),
+        )));
+        let account_id = AccountId {
+            value: "test-account".to_string(),
+        };
+        let stored = files_service
+            .put_if_not_exists(&account_id, &Bytes::from("hello"))
+            .await
+            .unwrap();
+        let file_loader = FileLoader::new(files_service).unwrap();
+
+        assert!(file_loader
+            .prefetch(&account_id, &[file(stored.clone(), "/hello.txt")], 2)
+            .await
+            .is_ok());
+
+        let result = file_loader
+            .prefetch(
+                &account_id,
+                &[
+                    file(stored, "/hello.txt"),
+                    file(InitialComponentFileKey("first".to_string()), "/first.txt"),
+                    file(InitialComponentFileKey("second".to_string()), "/second.txt"),
+                ],
+                2,
+            )
+            .await;
+        match result {
+            Err(GolemError::InitialComponentFileDownloadFailed { path, reason }) => {
+                assert!(path.contains("/first.txt"));
+                assert!(path.contains("/second.txt"));
+                assert!(reason.starts_with("2 of 3 files failed to download"));
+            }
+            _ => panic!("Expected the prefetch to fail"),
+        }
+    }
+
+    #[test]
+    async fn ensure_exist_reports_missing_files() {
+        let files_service = Arc::new(InitialComponentFilesService::new(Arc::new(
+            InMemoryBlobStorage::new(),
+        )));
+        let account_id = AccountId {
+            value: "test-account".to_string(),
+        };
+        let stored = files_service
+            .put_if_not_exists(&account_id, &Bytes::from("hello"))
+            .await
+            .unwrap();
+        let file_loader = FileLoader::new(files_service).unwrap();
+
+        assert!(file_loader
+            .ensure_exist(&account_id, &[file(stored.clone(), "/hello.txt")])
+            .await
+            .is_ok());
+
+        let result = file_loader
+            .ensure_exist(
+                &account_id,
+                &[
+                    file(stored, "/hello.txt"),
+                    file(
+                        InitialComponentFileKey("missing".to_string()),
+                        "/missing.txt",
+                    ),
+                ],
+            )
+            .await;
+        assert_eq!(
+            result,
+            Err(GolemError::MissingInitialFile {
+                key: "missing".to_string(),
+                path: "/missing.txt".to_string(),
+            })
+        );
+    }
+}
@@ -12,21 +12,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fmt::{Debug, Formatter};
 use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-use anyhow::Context;
+use anyhow::{bail, Context};
 use figment::providers::{Format, Toml};
 use figment::Figment;
 use golem_service_base::config::BlobStorageConfig;
 use http::Uri;
+use humantime_serde::re::humantime::format_duration;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
 use golem_common::config::{
     ConfigExample, ConfigLoader, DbSqliteConfig, HasConfigExamples, RedisConfig, RetryConfig,
 };
+use golem_common::model::ComponentId;
 use golem_common::tracing::TracingConfig;
 
 /// The shared global Golem configuration
@@ -70,10 +73,99 @@ pub struct Limits {
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ComponentCacheConfig {
+    /// Number of compiled components kept in memory. With 0, the in-memory cache is disabled to
+    /// save memory, and components are deserialized from the compiled component store (or
+    /// compiled) on every load.
     pub max_capacity: usize,
     pub max_metadata_capacity: usize,
+    /// When set, the metadata cache keeps at most this many versions of each component, evicting
+    /// its least recently used versions, so that one component cannot take over the whole cache
+    #[serde(default)]
+    pub max_metadata_versions_per_component: Option<usize>,
     #[serde(with = "humantime_serde")]
     pub time_to_idle: Duration,
+    /// When set, the resolved latest version of a component is reused for this long instead of
+    /// asking the component service every time. Within this period a newly published version
+    /// is not visible yet, so callers may observe a stale latest version.
+    #[serde(with = "humantime_serde")]
+    pub latest_version_ttl: Option<Duration>,
+    /// When set together with `latest_version_ttl`, a resolved latest version older than this is
+    /// still used until `latest_version_ttl`, while it gets resolved again in the background.
+    /// This avoids waiting for the component service whenever the latest version expires.
+    #[serde(default, with = "humantime_serde")]
+    pub latest_version_soft_ttl: Option<Duration>,
+    /// When disabled, component metadata is fetched on every lookup instead of being cached,
+    /// while compiled components are still cached. Use it only if metadata can change under a
+    /// fixed component version: every worker activation then costs an additional request to the
+    /// component service (or a read of the properties file and the WASM for the local one).
+    pub cache_metadata: bool,
+    /// On shutdown, the time the in-flight component downloads and compilations get to finish
+    /// and store their compiled component before the executor stops anyway
+    #[serde(with = "humantime_serde")]
+    pub shutdown_grace_period: Duration,
+    /// Maximum number of components compiled at the same time, further compilations wait for
+    /// a free slot. Defaults to the number of physical cores.
+    pub max_concurrent_compiles: Option<usize>,
+    /// Maximum number of initial files of a component downloaded at the same time when they
+    /// are prefetched while the component of a starting worker is loaded
+    pub max_concurrent_file_prefetches: usize,
+    /// Where cached component metadata is kept, when `cache_metadata` is enabled
+    pub metadata_backend: ComponentMetadataCacheConfig,
+    /// When enabled, the initial files of a component are checked to exist in the blob storage
+    /// whenever a worker loads its metadata, failing with `MissingInitialFile` right away instead
+    /// of when the files get materialized. Costs a blob storage lookup per file and activation.
+    #[serde(default)]
+    pub validate_initial_files: bool,
+    /// When enabled, loading the metadata of a component without any exports fails with
+    /// `ComponentParseFailed`, catching accidentally empty builds. Components only running side
+    /// effects legitimately have no exports, so this is disabled by default.
+    #[serde(default)]
+    pub reject_empty_exports: bool,
+    /// Components whose WASM is larger than this many bytes are not admitted to the main
+    /// in-memory cache, so that a few giant components cannot evict the many small hot ones
+    pub large_component_threshold: Option<u64>,
+    /// Number of components above `large_component_threshold` kept in their own in-memory cache.
+    /// With 0, they are not cached in memory and are loaded from the compiled component store
+    /// every time.
+    #[serde(default)]
+    pub large_component_capacity: usize,
+    /// When set, the WASM of the recently downloaded components is kept in memory up to this many
+    /// bytes in total, so that recompiling a component whose compiled artifact became unusable
+    /// (for example after an engine upgrade) does not download it again
+    #[serde(default)]
+    pub max_downloaded_bytes_cache_size: Option<u64>,
+}
+
+impl ComponentCacheConfig {
+    pub fn concurrent_compile_limit(&self) -> usize {
+        self.max_concurrent_compiles
+            .unwrap_or_else(|| {
+                sysinfo::System::new()
+                    .physical_core_count()
+                    .or_else(|| std::thread::available_parallelism().ok().map(|n| n.get()))
+                    .unwrap_or(1)
+            })
+            .max(1)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", content = "config")]
+pub enum ComponentMetadataCacheConfig {
+    InMemory,
+    /// Shares the metadata between all executors using the same Redis, in addition to caching
+    /// it in memory. Requires the `redis-metadata-cache` feature.
+    #[cfg(feature = "redis-metadata-cache")]
+    Redis(ComponentMetadataRedisCacheConfig),
+}
+
+#[cfg(feature = "redis-metadata-cache")]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ComponentMetadataRedisCacheConfig {
+    pub redis: RedisConfig,
+    /// How long an entry is kept in Redis after it was stored
+    #[serde(with = "humantime_serde")]
+    pub ttl: Duration,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -81,6 +173,7 @@ pub struct ComponentCacheConfig {
 pub enum ComponentServiceConfig {
     Grpc(ComponentServiceGrpcConfig),
     Local(ComponentServiceLocalConfig),
+    Oci(ComponentServiceOciConfig),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -89,12 +182,148 @@ pub struct ComponentServiceGrpcConfig {
     pub port: u16,
     pub access_token: String,
     pub retries: RetryConfig,
+    /// Overall wall-clock budget for a single retried operation (download or metadata lookup),
+    /// after which it fails even if the retry policy would allow further attempts.
+    #[serde(with = "humantime_serde")]
+    pub max_elapsed: Option<Duration>,
     pub max_component_size: usize,
+    /// When set, every downloaded component must come with a detached signature that is valid
+    /// for this key, otherwise it is rejected before compilation. The component service does not
+    /// serve signatures yet, so setting it is rejected when the configuration is validated.
+    pub verification_key: Option<PublicKey>,
+    /// Status codes retried in addition to the ones always considered transient, such as
+    /// `RESOURCE_EXHAUSTED` for a component service that rate limits its clients.
+    #[serde(default)]
+    pub retriable_status_codes: Vec<GrpcStatusCode>,
+    /// When set, only the id, version and size of a component's metadata are required, and
+    /// exports or initial files the executor cannot decode are ignored with a warning.
+    #[serde(default)]
+    pub lenient_metadata: bool,
+    /// When set, the memories reported in the metadata of a downloaded component are compared
+    /// with the ones found in its WASM, logging a warning if they differ. This detects metadata
+    /// drift on the component service, at the cost of analysing every downloaded component.
+    #[serde(default)]
+    pub verify_memories: bool,
+    /// When set, the downloads of components from the component service are throttled to this
+    /// many bytes per second in total, so that starting many workers at once does not saturate
+    /// the network at the expense of the live traffic
+    #[serde(default)]
+    pub max_download_bytes_per_second: Option<u64>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ComponentServiceLocalConfig {
     pub root: PathBuf,
+    /// Directory containing the `.json` properties files of the components. When not set, they
+    /// are expected next to the `.wasm` files in `root`.
+    pub props_root: Option<PathBuf>,
+    /// When set, every component must have a `.sig` file next to its `.wasm` file containing a
+    /// detached signature that is valid for this key.
+    pub verification_key: Option<PublicKey>,
+    /// When enabled, a component version whose `.wasm` file exists without its properties file
+    /// is still served, with metadata found by analysing the WASM: a durable component with the
+    /// initial files embedded in it, if any. A warning is logged every time it is recovered.
+    #[serde(default)]
+    pub recover_missing_props: bool,
+}
+
+/// Serves components published as OCI artifacts. The artifact of a component is stored in the
+/// `{repository}/{component_id}` repository of the registry, with a `application/wasm` layer
+/// holding the component and a metadata layer in the format of the local properties files.
+/// Its tags are mapped to component versions by the `cloud.golem.component.version` annotation.
+/// Tags named after the version they hold (`3` or `v3`) are resolved with a single manifest
+/// request, other tags only by fetching the manifests of all the tags of the repository.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ComponentServiceOciConfig {
+    pub registry: Url,
+    pub repository: String,
+    pub auth: Option<OciAuthConfig>,
+    pub retries: RetryConfig,
+    /// When set, every artifact must have a signature layer containing a detached signature of
+    /// the component that is valid for this key.
+    pub verification_key: Option<PublicKey>,
+    /// When set, the downloads from the registry are throttled to this many bytes per second in
+    /// total, as for the gRPC component service
+    #[serde(default)]
+    pub max_download_bytes_per_second: Option<u64>,
+    /// Overall wall-clock budget for a single retried operation against the registry, as for
+    /// the gRPC component service
+    #[serde(default, with = "humantime_serde")]
+    pub max_elapsed: Option<Duration>,
+    /// Blobs larger than this are rejected without downloading them
+    #[serde(default = "default_max_oci_blob_size")]
+    pub max_component_size: usize,
+}
+
+fn default_max_oci_blob_size() -> usize {
+    50 * 1024 * 1024
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct OciAuthConfig {
+    pub username: String,
+    pub password: String,
+}
+
+impl Debug for OciAuthConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OciAuthConfig")
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+/// An Ed25519 public key, configured as a hex encoded string
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PublicKey(pub Vec<u8>);
+
+impl TryFrom<String> for PublicKey {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let bytes =
+            hex::decode(value.trim()).map_err(|err| format!("Invalid public key: {err}"))?;
+        if bytes.len() != 32 {
+            return Err(format!(
+                "Invalid public key: expected 32 bytes, got {}",
+                bytes.len()
+            ));
+        }
+        Ok(PublicKey(bytes))
+    }
+}
+
+impl From<PublicKey> for String {
+    fn from(value: PublicKey) -> Self {
+        hex::encode(value.0)
+    }
+}
+
+/// A gRPC status code, configured by its name such as `RESOURCE_EXHAUSTED` or `ResourceExhausted`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct GrpcStatusCode(pub tonic::Code);
+
+impl TryFrom<String> for GrpcStatusCode {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let normalize = |name: &str| name.replace('_', "").to_lowercase();
+        let name = normalize(value.trim());
+        (0..=16)
+            .map(tonic::Code::from_i32)
+            .find(|code| normalize(&format!("{code:?}")) == name)
+            .map(GrpcStatusCode)
+            .ok_or_else(|| format!("Invalid gRPC status code: {value}"))
+    }
+}
+
+impl From<GrpcStatusCode> for String {
+    fn from(value: GrpcStatusCode) -> Self {
+        format!("{:?}", value.0)
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -105,7 +334,29 @@ pub enum CompiledComponentServiceConfig {
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct CompiledComponentServiceEnabledConfig {}
+pub struct CompiledComponentServiceEnabledConfig {
+    /// Components whose compiled artifacts are stored in a different location than the
+    /// configured blob storage. Components not listed in any override use the blob storage.
+    #[serde(default)]
+    pub overrides: Vec<CompiledComponentStoreOverride>,
+    /// Number of compiled components in a row failing to deserialize after which a store is
+    /// considered corrupted, and skipped in favour of recompiling the components
+    pub max_deserialization_failures: u32,
+    /// How long a store is skipped for after too many deserialization failures
+    #[serde(with = "humantime_serde")]
+    pub deserialization_failure_cooldown: Duration,
+    /// Compresses the stored compiled components with zstd, saving disk space at the cost of
+    /// decompressing them on every load. The decompression time is logged at debug level.
+    #[serde(default)]
+    pub compress_compiled: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CompiledComponentStoreOverride {
+    pub component_ids: Vec<ComponentId>,
+    /// Local directory used as the compilation cache of the listed components
+    pub root: PathBuf,
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CompiledComponentServiceDisabledConfig {}
@@ -154,6 +405,30 @@ impl GolemConfig {
         ))
     }
 
+    /// Checks the settings which can be parsed, but not used together
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if let ComponentServiceConfig::Grpc(config) = &self.component_service {
+            if config.verification_key.is_some() {
+                bail!(
+                    "component_service.config.verification_key is not supported by the grpc component service, which does not serve component signatures"
+                );
+            }
+        }
+        let cache = &self.component_cache;
+        match (cache.latest_version_ttl, cache.latest_version_soft_ttl) {
+            (None, Some(_)) => bail!(
+                "component_cache.latest_version_soft_ttl requires component_cache.latest_version_ttl to be set"
+            ),
+            (Some(ttl), Some(soft_ttl)) if soft_ttl >= ttl => bail!(
+                "component_cache.latest_version_soft_ttl ({}) must be shorter than component_cache.latest_version_ttl ({})",
+                format_duration(soft_ttl),
+                format_duration(ttl)
+            ),
+            _ => {}
+        }
+        Ok(())
+    }
+
     pub fn add_port_to_tracing_file_name_if_enabled(&mut self) {
         if self.tracing_file_name_with_port {
             if let Some(file_name) = &self.tracing.file_name {
@@ -168,6 +443,221 @@ impl GolemConfig {
             }
         }
     }
+
+    /// Describes the effective component service configuration in a human readable form, with
+    /// the access token redacted.
+    pub fn component_service_summary(&self) -> String {
+        let optional_duration = |duration: Option<Duration>| match duration {
+            Some(duration) => format_duration(duration).to_string(),
+            None => "none".to_string(),
+        };
+        let verification_key = |key: &Option<PublicKey>| match key {
+            Some(key) => String::from(key.clone()),
+            None => "none".to_string(),
+        };
+        let download_rate = |limit: Option<u64>| match limit {
+            Some(limit) => format!("{limit} bytes/s"),
+            None => "unlimited".to_string(),
+        };
+
+        let mut lines = Vec::new();
+        match &self.component_service {
+            ComponentServiceConfig::Grpc(config) => {
+                lines.push("source: grpc".to_string());
+                lines.push(format!("endpoint: {}", config.url()));
+                lines.push("access token: <redacted>".to_string());
+                lines.push("compression: gzip".to_string());
+                lines.push(format!("max component size: {}", config.max_component_size));
+                let retries = &config.retries;
+                lines.push(format!("retries max attempts: {}", retries.max_attempts));
+                lines.push(format!(
+                    "retries min delay: {}",
+                    format_duration(retries.min_delay)
+                ));
+                lines.push(format!(
+                    "retries max delay: {}",
+                    format_duration(retries.max_delay)
+                ));
+                lines.push(format!("retries multiplier: {}", retries.multiplier));
+                lines.push(format!(
+                    "retries max jitter factor: {}",
+                    retries
+                        .max_jitter_factor
+                        .map(|factor| factor.to_string())
+                        .unwrap_or_else(|| "none".to_string())
+                ));
+                lines.push(format!(
+                    "max elapsed: {}",
+                    optional_duration(config.max_elapsed)
+                ));
+                lines.push(format!(
+                    "verification key: {}",
+                    verification_key(&config.verification_key)
+                ));
+                lines.push(format!(
+                    "additional retriable status codes: {}",
+                    if config.retriable_status_codes.is_empty() {
+                        "none".to_string()
+                    } else {
+                        config
+                            .retriable_status_codes
+                            .iter()
+                            .map(|code| String::from(*code))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    }
+                ));
+                lines.push(format!("lenient metadata: {}", config.lenient_metadata));
+                lines.push(format!("verify memories: {}", config.verify_memories));
+                lines.push(format!(
+                    "max download rate: {}",
+                    download_rate(config.max_download_bytes_per_second)
+                ));
+            }
+            ComponentServiceConfig::Local(config) => {
+                lines.push("source: local".to_string());
+                lines.push(format!("root: {}", config.root.display()));
+                lines.push(format!(
+                    "props root: {}",
+                    config.props_root.as_ref().unwrap_or(&config.root).display()
+                ));
+                lines.push("compression: none".to_string());
+                lines.push(format!(
+                    "verification key: {}",
+                    verification_key(&config.verification_key)
+                ));
+                lines.push(format!(
+                    "recover missing props: {}",
+                    config.recover_missing_props
+                ));
+            }
+            ComponentServiceConfig::Oci(config) => {
+                lines.push("source: oci".to_string());
+                lines.push(format!("registry: {}", config.registry));
+                lines.push(format!("repository: {}", config.repository));
+                lines.push(format!(
+                    "auth: {}",
+                    match &config.auth {
+                        Some(auth) => format!("{} / <redacted>", auth.username),
+                        None => "none".to_string(),
+                    }
+                ));
+                lines.push(format!(
+                    "retries max attempts: {}",
+                    config.retries.max_attempts
+                ));
+                lines.push(format!(
+                    "max elapsed: {}",
+                    optional_duration(config.max_elapsed)
+                ));
+                lines.push(format!("max component size: {}", config.max_component_size));
+                lines.push(format!(
+                    "max download rate: {}",
+                    download_rate(config.max_download_bytes_per_second)
+                ));
+                lines.push(format!(
+                    "verification key: {}",
+                    verification_key(&config.verification_key)
+                ));
+            }
+        }
+
+        let cache = &self.component_cache;
+        lines.push(format!(
+            "cache max capacity: {}",
+            match cache.max_capacity {
+                0 => "disabled, components are loaded from the compiled component store every time"
+                    .to_string(),
+                max_capacity => max_capacity.to_string(),
+            }
+        ));
+        lines.push(format!(
+            "cache max metadata capacity: {}",
+            cache.max_metadata_capacity
+        ));
+        lines.push(format!(
+            "cache max metadata versions per component: {}",
+            cache
+                .max_metadata_versions_per_component
+                .map(|max| max.to_string())
+                .unwrap_or_else(|| "unlimited".to_string())
+        ));
+        lines.push(format!(
+            "cache time to idle: {}",
+            format_duration(cache.time_to_idle)
+        ));
+        lines.push(format!(
+            "latest version ttl: {}",
+            optional_duration(cache.latest_version_ttl)
+        ));
+        lines.push(format!(
+            "latest version soft ttl: {}",
+            optional_duration(cache.latest_version_soft_ttl)
+        ));
+        lines.push(format!(
+            "metadata cache: {}",
+            match &cache.metadata_backend {
+                ComponentMetadataCacheConfig::InMemory => "in-memory".to_string(),
+                #[cfg(feature = "redis-metadata-cache")]
+                ComponentMetadataCacheConfig::Redis(config) => format!(
+                    "redis at {}, ttl {}",
+                    config.redis.url(),
+                    format_duration(config.ttl)
+                ),
+            }
+        ));
+        lines.push(format!(
+            "max concurrent file prefetches: {}",
+            cache.max_concurrent_file_prefetches
+        ));
+        lines.push(format!(
+            "validate initial files: {}",
+            cache.validate_initial_files
+        ));
+        lines.push(format!(
+            "reject empty exports: {}",
+            cache.reject_empty_exports
+        ));
+        lines.push(format!(
+            "large components: {}",
+            match cache.large_component_threshold {
+                Some(threshold) if cache.large_component_capacity > 0 => format!(
+                    "above {threshold} bytes, cached separately up to {} components",
+                    cache.large_component_capacity
+                ),
+                Some(threshold) => format!("above {threshold} bytes, not cached in memory"),
+                None => "cached as any other component".to_string(),
+            }
+        ));
+        lines.push(format!(
+            "downloaded WASM cache: {}",
+            match cache.max_downloaded_bytes_cache_size {
+                Some(size) if size > 0 => format!("up to {size} bytes"),
+                _ => "disabled".to_string(),
+            }
+        ));
+        lines.push(format!(
+            "compiled component service: {}",
+            match &self.compiled_component_service {
+                CompiledComponentServiceConfig::Enabled(_) => "enabled",
+                CompiledComponentServiceConfig::Disabled(_) => "disabled",
+            }
+        ));
+        if let CompiledComponentServiceConfig::Enabled(config) = &self.compiled_component_service {
+            if config.compress_compiled {
+                lines.push("compiled components: stored compressed".to_string());
+            }
+            for store_override in &config.overrides {
+                lines.push(format!(
+                    "compiled component store override: {:?} for {} component(s)",
+                    store_override.root,
+                    store_override.component_ids.len()
+                ));
+            }
+        }
+
+        lines.join("\n")
+    }
 }
 
 impl ComponentServiceGrpcConfig {
@@ -371,7 +861,20 @@ impl Default for ComponentCacheConfig {
         Self {
             max_capacity: 32,
             max_metadata_capacity: 16384,
+            max_metadata_versions_per_component: None,
             time_to_idle: Duration::from_secs(12 * 60 * 60),
+            latest_version_ttl: None,
+            latest_version_soft_ttl: None,
+            cache_metadata: true,
+            shutdown_grace_period: Duration::from_secs(30),
+            max_concurrent_compiles: None,
+            max_concurrent_file_prefetches: 8,
+            metadata_backend: ComponentMetadataCacheConfig::InMemory,
+            validate_initial_files: false,
+            reject_empty_exports: false,
+            large_component_threshold: None,
+            large_component_capacity: 0,
+            max_downloaded_bytes_cache_size: None,
         }
     }
 }
@@ -389,7 +892,13 @@ impl Default for ComponentServiceGrpcConfig {
             port: 9090,
             access_token: "2a354594-7a63-4091-a46b-cc58d379f677".to_string(),
             retries: RetryConfig::max_attempts_3(),
+            max_elapsed: None,
             max_component_size: 50 * 1024 * 1024,
+            verification_key: None,
+            retriable_status_codes: vec![],
+            lenient_metadata: false,
+            verify_memories: false,
+            max_download_bytes_per_second: None,
         }
     }
 }
@@ -402,7 +911,7 @@ impl Default for CompiledComponentServiceConfig {
 
 impl CompiledComponentServiceConfig {
     pub fn enabled() -> Self {
-        Self::Enabled(CompiledComponentServiceEnabledConfig {})
+        Self::Enabled(CompiledComponentServiceEnabledConfig::default())
     }
 
     pub fn disabled() -> Self {
@@ -410,6 +919,17 @@ impl CompiledComponentServiceConfig {
     }
 }
 
+impl Default for CompiledComponentServiceEnabledConfig {
+    fn default() -> Self {
+        Self {
+            overrides: vec![],
+            max_deserialization_failures: 5,
+            deserialization_failure_cooldown: Duration::from_secs(5 * 60),
+            compress_compiled: false,
+        }
+    }
+}
+
 impl Default for ShardManagerServiceConfig {
     fn default() -> Self {
         Self::Grpc(ShardManagerServiceGrpcConfig::default())
@@ -24,10 +24,13 @@ pub mod active_workers;
 pub mod blob_store;
 pub mod compiled_component;
 pub mod component;
+pub mod component_metadata_cache;
+pub mod download_throttle;
 pub mod events;
 pub mod file_loader;
 pub mod golem_config;
 pub mod key_value;
+pub mod oci;
 pub mod oplog;
 pub mod promise;
 pub mod rpc;
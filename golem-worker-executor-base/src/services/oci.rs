@@ -0,0 +1,495 @@
+// Copyright 2024 Golem Cloud
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::header::{ACCEPT, LINK, WWW_AUTHENTICATE};
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::Deserialize;
+use tokio::sync::RwLock;
+use url::Url;
+
+use crate::services::download_throttle::{throttle, DownloadThrottle};
+use crate::services::golem_config::OciAuthConfig;
+
+pub const OCI_MANIFEST_MEDIA_TYPE: &str = "application/vnd.oci.image.manifest.v1+json";
+
+/// Minimal client for pulling artifacts through the OCI distribution API.
+///
+/// Supports anonymous access, basic authentication and the bearer token flow, where the
+/// registry's `WWW-Authenticate` challenge tells where to get a token from.
+pub struct OciRegistryClient {
+    client: reqwest::Client,
+    registry: Url,
+    auth: Option<OciAuthConfig>,
+    token: RwLock<Option<String>>,
+    // Limits the rate blobs are downloaded at
+    download_throttle: Option<Arc<DownloadThrottle>>,
+    // Blobs larger than this are rejected, without downloading more than this
+    max_blob_size: usize,
+    // Overall wall-clock limit of a retried operation against the registry
+    max_elapsed: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OciManifest {
+    #[serde(default)]
+    pub layers: Vec<OciDescriptor>,
+    #[serde(default)]
+    pub annotations: HashMap<String, String>,
+}
+
+impl OciManifest {
+    pub fn layer(&self, media_type: &str) -> Option<&OciDescriptor> {
+        self.layers
+            .iter()
+            .find(|layer| layer.media_type == media_type)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OciDescriptor {
+    pub media_type: String,
+    pub digest: String,
+    pub size: u64,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum OciError {
+    #[error("Request to the OCI registry failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("OCI registry responded with {status}: {body}")]
+    Status { status: StatusCode, body: String },
+    #[error("Failed to authenticate with the OCI registry: {0}")]
+    Auth(String),
+    #[error("Invalid OCI registry URL: {0}")]
+    InvalidUrl(String),
+    #[error("Digest mismatch of blob: expected {expected}, got {actual}")]
+    DigestMismatch { expected: String, actual: String },
+    #[error("Unsupported digest {0}, only sha256 digests are accepted")]
+    UnsupportedDigest(String),
+    #[error("Blob {digest} is larger than the limit of {max_size} bytes")]
+    BlobTooLarge { digest: String, max_size: usize },
+}
+
+impl OciError {
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            OciError::Request(_) => true,
+            OciError::Status { status, .. } => {
+                status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+            }
+            OciError::Auth(_)
+            | OciError::InvalidUrl(_)
+            | OciError::DigestMismatch { .. }
+            | OciError::UnsupportedDigest(_)
+            | OciError::BlobTooLarge { .. } => false,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TagList {
+    #[serde(default)]
+    tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    token: Option<String>,
+    access_token: Option<String>,
+}
+
+impl OciRegistryClient {
+    pub fn new(
+        registry: Url,
+        auth: Option<OciAuthConfig>,
+        download_throttle: Option<Arc<DownloadThrottle>>,
+        max_blob_size: usize,
+        max_elapsed: Option<Duration>,
+    ) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            registry,
+            auth,
+            token: RwLock::new(None),
+            download_throttle,
+            max_blob_size,
+            max_elapsed,
+        }
+    }
+
+    pub fn max_elapsed(&self) -> Option<Duration> {
+        self.max_elapsed
+    }
+
+    pub async fn list_tags(&self, repository: &str) -> Result<Vec<String>, OciError> {
+        let mut tags = Vec::new();
+        let mut url = self.url(&format!("/v2/{repository}/tags/list"))?;
+        loop {
+            let response = self.get(url.clone(), "application/json").await?;
+            let next = next_page(&url, &response);
+            let page: TagList = response.json().await?;
+            tags.extend(page.tags.unwrap_or_default());
+            match next {
+                Some(next) => url = next,
+                None => break,
+            }
+        }
+        Ok(tags)
+    }
+
+    pub async fn get_manifest(
+        &self,
+        repository: &str,
+        reference: &str,
+    ) -> Result<OciManifest, OciError> {
+        let url = self.url(&format!("/v2/{repository}/manifests/{reference}"))?;
+        let response = self.get(url, OCI_MANIFEST_MEDIA_TYPE).await?;
+        Ok(response.json().await?)
+    }
+
+    /// Downloads a blob and checks it against the sha256 digest of its descriptor. Blobs over the
+    /// size limit are rejected before downloading them if their descriptor says so, and otherwise
+    /// as soon as more than the limit was received.
+    pub async fn get_blob(
+        &self,
+        repository: &str,
+        descriptor: &OciDescriptor,
+    ) -> Result<Vec<u8>, OciError> {
+        let expected = descriptor
+            .digest
+            .strip_prefix("sha256:")
+            .filter(|digest| digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit()))
+            .ok_or_else(|| OciError::UnsupportedDigest(descriptor.digest.clone()))?;
+        let too_large = || OciError::BlobTooLarge {
+            digest: descriptor.digest.clone(),
+            max_size: self.max_blob_size,
+        };
+        if descriptor.size > self.max_blob_size as u64 {
+            return Err(too_large());
+        }
+
+        let url = self.url(&format!("/v2/{repository}/blobs/{}", descriptor.digest))?;
+        let mut response = self.get(url, &descriptor.media_type).await?;
+        let mut bytes = Vec::with_capacity(descriptor.size as usize);
+        while let Some(chunk) = response.chunk().await? {
+            if bytes.len() + chunk.len() > self.max_blob_size {
+                return Err(too_large());
+            }
+            throttle(&self.download_throttle, chunk.len()).await;
+            bytes.extend_from_slice(&chunk);
+        }
+
+        let actual = hex::encode(ring::digest::digest(&ring::digest::SHA256, &bytes));
+        if !actual.eq_ignore_ascii_case(expected) {
+            return Err(OciError::DigestMismatch {
+                expected: descriptor.digest.clone(),
+                actual: format!("sha256:{actual}"),
+            });
+        }
+
+        Ok(bytes)
+    }
+
+    fn url(&self, path: &str) -> Result<Url, OciError> {
+        self.registry
+            .join(path)
+            .map_err(|err| OciError::InvalidUrl(err.to_string()))
+    }
+
+    async fn get(&self, url: Url, accept: &str) -> Result<Response, OciError> {
+        let token = self.token.read().await.clone();
+        let response = self
+            .authorize(self.client.get(url.clone()).header(ACCEPT, accept), token)
+            .send()
+            .await?;
+
+        let response = if response.status() == StatusCode::UNAUTHORIZED {
+            match bearer_challenge(&response) {
+                Some(challenge) => {
+                    let token = self.fetch_token(&challenge).await?;
+                    *self.token.write().await = Some(token.clone());
+                    self.authorize(self.client.get(url).header(ACCEPT, accept), Some(token))
+                        .send()
+                        .await?
+                }
+                None => response,
+            }
+        } else {
+            response
+        };
+
+        if response.status().is_success() {
+            Ok(response)
+        } else {
+            Err(OciError::Status {
+                status: response.status(),
+                body: response.text().await.unwrap_or_default(),
+            })
+        }
+    }
+
+    fn authorize(&self, request: RequestBuilder, token: Option<String>) -> RequestBuilder {
+        match (token, &self.auth) {
+            (Some(token), _) => request.bearer_auth(token),
+            (None, Some(auth)) => request.basic_auth(&auth.username, Some(&auth.password)),
+            (None, None) => request,
+        }
+    }
+
+    async fn fetch_token(&self, challenge: &HashMap<String, String>) -> Result<String, OciError> {
+        let realm = challenge
+            .get("realm")
+            .ok_or_else(|| OciError::Auth("Missing realm in the bearer challenge".to_string()))?;
+        let mut url = Url::parse(realm)
+            .map_err(|err| OciError::Auth(format!("Invalid realm {realm}: {err}")))?;
+        {
+            let mut query = url.query_pairs_mut();
+            for key in ["service", "scope"] {
+                if let Some(value) = challenge.get(key) {
+                    query.append_pair(key, value);
+                }
+            }
+        }
+
+        let request = match &self.auth {
+            Some(auth) => self
+                .client
+                .get(url)
+                .basic_auth(&auth.username, Some(&auth.password)),
+            None => self.client.get(url),
+        };
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(OciError::Auth(format!(
+                "Token request failed with {}",
+                response.status()
+            )));
+        }
+
+        let token: TokenResponse = response.json().await?;
+        token
+            .token
+            .or(token.access_token)
+            .ok_or_else(|| OciError::Auth("Token response contains no token".to_string()))
+    }
+}
+
+fn bearer_challenge(response: &Response) -> Option<HashMap<String, String>> {
+    let header = response.headers().get(WWW_AUTHENTICATE)?.to_str().ok()?;
+    parse_bearer_challenge(header)
+}
+
+/// Parses a `Bearer realm="...",service="...",scope="..."` challenge into its parameters
+fn parse_bearer_challenge(header: &str) -> Option<HashMap<String, String>> {
+    let (scheme, params) = header.trim().split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case("bearer") {
+        return None;
+    }
+
+    let mut result = HashMap::new();
+    let mut rest = params.trim();
+    while !rest.is_empty() {
+        let (key, after_key) = rest.split_once('=')?;
+        let (value, after_value) = match after_key.strip_prefix('"') {
+            Some(quoted) => {
+                let end = quoted.find('"')?;
+                (&quoted[..end], &quoted[end + 1..])
+            }
+            None => match after_key.find(',') {
+                Some(end) => (&after_key[..end], &after_key[end..]),
+                None => (after_key, ""),
+            },
+        };
+        result.insert(key.trim().to_lowercase(), value.to_string());
+        rest = after_value.trim_start_matches([',', ' ']);
+    }
+    Some(result)
+}
+
+/// Follows the `Link: <...>; rel="next"` header used to paginate tag lists
+fn next_page(current: &Url, response: &Response) -> Option<Url> {
+    let link = response.headers().get(LINK)?.to_str().ok()?;
+    let (target, params) = link.split_once(';')?;
+    if !params.contains("rel=\"next\"") {
+        return None;
+    }
+    let target = target.trim().strip_prefix('<')?.strip_suffix('>')?;
+    current.join(target).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use reqwest::header::LINK;
+    use reqwest::{Response, StatusCode};
+    use url::Url;
+
+    use super::{
+        next_page, parse_bearer_challenge, OciDescriptor, OciError, OciManifest, OciRegistryClient,
+    };
+
+    fn response_with_link(link: &str) -> Response {
+        Response::from(
+            http::Response::builder()
+                .header(LINK, link)
+                .body(Vec::<u8>::new())
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn bearer_challenge_with_quoted_params() {
+        let challenge = parse_bearer_challenge(
+            r#"Bearer realm="https://ghcr.io/token",service="ghcr.io",scope="repository:golem/components:pull,push""#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            challenge.get("realm").map(String::as_str),
+            Some("https://ghcr.io/token")
+        );
+        assert_eq!(
+            challenge.get("service").map(String::as_str),
+            Some("ghcr.io")
+        );
+        assert_eq!(
+            challenge.get("scope").map(String::as_str),
+            Some("repository:golem/components:pull,push")
+        );
+    }
+
+    #[test]
+    fn non_bearer_challenge_is_ignored() {
+        assert!(parse_bearer_challenge(r#"Basic realm="registry""#).is_none());
+    }
+
+    #[test]
+    fn bearer_challenge_with_unquoted_params() {
+        let challenge =
+            parse_bearer_challenge("bearer realm=https://registry/token,service=registry").unwrap();
+
+        assert_eq!(
+            challenge.get("realm").map(String::as_str),
+            Some("https://registry/token")
+        );
+        assert_eq!(
+            challenge.get("service").map(String::as_str),
+            Some("registry")
+        );
+    }
+
+    #[test]
+    fn next_page_follows_relative_link() {
+        let current = Url::parse("https://ghcr.io/v2/golem/components/tags/list").unwrap();
+
+        let next = next_page(
+            &current,
+            &response_with_link(r#"</v2/golem/components/tags/list?last=v2&n=100>; rel="next""#),
+        );
+        assert_eq!(
+            next.unwrap().as_str(),
+            "https://ghcr.io/v2/golem/components/tags/list?last=v2&n=100"
+        );
+
+        assert!(next_page(&current, &response_with_link(r#"</other>; rel="prev""#)).is_none());
+    }
+
+    #[test]
+    fn manifest_layers_are_found_by_media_type() {
+        let manifest: OciManifest = serde_json::from_str(
+            r#"{
+                "schemaVersion": 2,
+                "layers": [
+                    { "mediaType": "application/wasm", "digest": "sha256:aa", "size": 10 },
+                    { "mediaType": "application/vnd.golem.component.metadata.v1+json", "digest": "sha256:bb", "size": 2 }
+                ],
+                "annotations": { "cloud.golem.component.version": "3" }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.layer("application/wasm").unwrap().size, 10);
+        assert_eq!(
+            manifest
+                .layer("application/vnd.golem.component.metadata.v1+json")
+                .unwrap()
+                .digest,
+            "sha256:bb"
+        );
+        assert!(manifest.layer("application/octet-stream").is_none());
+        assert_eq!(manifest.annotations["cloud.golem.component.version"], "3");
+    }
+
+    #[test]
+    fn only_transient_errors_are_retried() {
+        let status = |status: StatusCode| OciError::Status {
+            status,
+            body: String::new(),
+        };
+
+        assert!(status(StatusCode::SERVICE_UNAVAILABLE).is_retriable());
+        assert!(status(StatusCode::TOO_MANY_REQUESTS).is_retriable());
+        assert!(!status(StatusCode::NOT_FOUND).is_retriable());
+        assert!(!OciError::Auth("denied".to_string()).is_retriable());
+        assert!(!OciError::DigestMismatch {
+            expected: "sha256:aa".to_string(),
+            actual: "sha256:bb".to_string(),
+        }
+        .is_retriable());
+    }
+
+    #[test]
+    async fn blobs_are_checked_before_downloading_them() {
+        // Nothing listens on the registry, so the checks must fail before any request is made
+        let client = OciRegistryClient::new(
+            Url::parse("http://127.0.0.1:1").unwrap(),
+            None,
+            None,
+            1024,
+            None,
+        );
+        let descriptor = |digest: &str, size: u64| OciDescriptor {
+            media_type: "application/wasm".to_string(),
+            digest: digest.to_string(),
+            size,
+        };
+        let sha256 = format!("sha256:{}", "ab".repeat(32));
+
+        assert!(matches!(
+            client
+                .get_blob("components", &descriptor("md5:0123", 10))
+                .await,
+            Err(OciError::UnsupportedDigest(_))
+        ));
+        assert!(matches!(
+            client
+                .get_blob("components", &descriptor("sha256:../../other", 10))
+                .await,
+            Err(OciError::UnsupportedDigest(_))
+        ));
+        assert!(matches!(
+            client
+                .get_blob("components", &descriptor(&sha256, 2048))
+                .await,
+            Err(OciError::BlobTooLarge { max_size: 1024, .. })
+        ));
+    }
+}
@@ -186,6 +186,14 @@ impl<Ctx: WorkerCtx> Worker<Ctx> {
                 Some(worker_metadata.last_known_status.component_version),
             )
             .await?;
+        if deps.config().component_cache.validate_initial_files {
+            deps.file_loader()
+                .ensure_exist(
+                    &owned_worker_id.account_id,
+                    &initial_component_metadata.files,
+                )
+                .await?;
+        }
         let last_oplog_index = deps.oplog_service().get_last_index(&owned_worker_id).await;
         let oplog = deps
             .oplog_service()
@@ -1261,10 +1269,16 @@ impl RunningWorker {
                     target_version
                 },
             );
-        let (component, component_metadata) = parent
-            .component_service()
-            .get(&parent.engine(), &component_id, component_version)
+        // The initial files are downloaded while the component is loaded, and are kept in the
+        // file cache until the worker's file system is prepared
+        let component_service = parent.component_service();
+        let files_metadata = component_service
+            .get_metadata(&component_id, Some(component_version))
             .await?;
+        let ((component, component_metadata), _prefetched_files) = tokio::try_join!(
+            component_service.get(&parent.engine(), &component_id, component_version),
+            component_service.prefetch_files(&worker_metadata.account_id, &files_metadata),
+        )?;
 
         let context = Ctx::create(
             OwnedWorkerId::new(&worker_metadata.account_id, &worker_metadata.worker_id),
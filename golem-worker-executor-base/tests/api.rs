@@ -46,6 +46,7 @@ use golem_test_framework::dsl::{
     drain_connection, is_worker_execution_error, stdout_event_matching, stdout_events,
     worker_error_message, TestDslUnsafe,
 };
+use golem_worker_executor_base::services::compiled_component::HOST_TARGET;
 use tokio::time::sleep;
 use tonic::transport::Body;
 use tracing::{debug, info};
@@ -1559,7 +1560,7 @@ async fn trying_to_use_a_wasm_that_wasmtime_cannot_load_provides_good_error_mess
     let target_dir = cwd.join(Path::new("data/components"));
     let component_path = target_dir.join(Path::new(&format!("{component_id}-0.wasm")));
     let compiled_component_path = cwd.join(Path::new(&format!(
-        "data/blobs/compilation_cache/{component_id}/0.cwasm"
+        "data/blobs/compilation_cache/{component_id}/{HOST_TARGET}/0.cwasm"
     )));
 
     {
@@ -22,9 +22,9 @@ use golem_common::model::{
 use golem_service_base::config::{BlobStorageConfig, LocalFileSystemBlobStorageConfig};
 use golem_worker_executor_base::error::GolemError;
 use golem_worker_executor_base::services::golem_config::{
-    CompiledComponentServiceConfig, CompiledComponentServiceEnabledConfig, ComponentServiceConfig,
-    ComponentServiceLocalConfig, GolemConfig, IndexedStorageConfig, KeyValueStorageConfig,
-    MemoryConfig, ShardManagerServiceConfig, WorkerServiceGrpcConfig,
+    CompiledComponentServiceConfig, ComponentServiceConfig, ComponentServiceLocalConfig,
+    GolemConfig, IndexedStorageConfig, KeyValueStorageConfig, MemoryConfig,
+    ShardManagerServiceConfig, WorkerServiceGrpcConfig,
 };
 
 use golem_worker_executor_base::durable_host::{
@@ -315,10 +315,11 @@ pub async fn start_limited(
         http_port: context.http_port(),
         component_service: ComponentServiceConfig::Local(ComponentServiceLocalConfig {
             root: Path::new("data/components").to_path_buf(),
+            props_root: None,
+            verification_key: None,
+            recover_missing_props: false,
         }),
-        compiled_component_service: CompiledComponentServiceConfig::Enabled(
-            CompiledComponentServiceEnabledConfig {},
-        ),
+        compiled_component_service: CompiledComponentServiceConfig::enabled(),
         shard_manager_service: ShardManagerServiceConfig::SingleShard,
         public_worker_api: WorkerServiceGrpcConfig {
             host: "localhost".to_string(),
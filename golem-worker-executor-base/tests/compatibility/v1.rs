@@ -936,20 +936,13 @@ pub fn golem_error() {
             details: "invalid request".to_string(),
         }),
     };
-    let g6 = GolemError::ComponentDownloadFailed {
-        component_id: wid.component_id.clone(),
-        component_version: 0,
-        reason: "reason".to_string(),
-    };
+    let g6 = GolemError::component_download_failed(wid.component_id.clone(), 0, "reason");
     let g7 = GolemError::ComponentParseFailed {
         component_id: wid.component_id.clone(),
         component_version: 0,
         reason: "reason".to_string(),
     };
-    let g8 = GolemError::GetLatestVersionOfComponentFailed {
-        component_id: wid.component_id.clone(),
-        reason: "reason".to_string(),
-    };
+    let g8 = GolemError::get_latest_version_of_component_failed(wid.component_id.clone(), "reason");
     let g9 = GolemError::PromiseNotFound {
         promise_id: pid.clone(),
     };
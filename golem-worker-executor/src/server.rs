@@ -21,6 +21,12 @@ use golem_worker_executor_base::metrics;
 use golem_worker_executor_base::services::golem_config::make_config_loader;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    if std::env::args().any(|arg| arg == "--show-component-service-config") {
+        let config = make_config_loader().load()?;
+        println!("{}", config.component_service_summary());
+        return Ok(());
+    }
+
     match make_config_loader().load_or_dump_config() {
         Some(mut config) => {
             config.add_port_to_tracing_file_name_if_enabled();
@@ -16,8 +16,10 @@
 mod tests {
     use test_r::test;
 
+    use golem_common::config::RetryConfig;
     use golem_worker_executor_base::services::golem_config::{
-        make_config_loader, ShardManagerServiceConfig,
+        make_config_loader, ComponentServiceConfig, ComponentServiceOciConfig, OciAuthConfig,
+        PublicKey, ShardManagerServiceConfig,
     };
 
     #[test]
@@ -29,5 +31,58 @@ mod tests {
             _ => panic!("Expected shard manager service to be grpc"),
         };
         assert_eq!(shard_manager_grpc_port, 9002);
+        golem_config.validate().expect("Invalid config");
+    }
+
+    #[test]
+    pub fn grpc_component_service_rejects_verification_key() {
+        let mut golem_config = make_config_loader().load().expect("Failed to load config");
+        match &mut golem_config.component_service {
+            ComponentServiceConfig::Grpc(config) => {
+                config.verification_key = Some(PublicKey(vec![0; 32]))
+            }
+            _ => panic!("Expected component service to be grpc"),
+        }
+
+        let err = golem_config.validate().unwrap_err().to_string();
+        assert!(err.contains("verification_key is not supported by the grpc component service"));
+    }
+
+    #[test]
+    pub fn component_service_summary_redacts_access_token() {
+        let golem_config = make_config_loader().load().expect("Failed to load config");
+        let summary = golem_config.component_service_summary();
+
+        assert!(summary.contains("source: grpc"));
+        assert!(summary.contains("access token: <redacted>"));
+        assert!(!summary.contains("2a354594-7a63-4091-a46b-cc58d379f677"));
+    }
+
+    #[test]
+    pub fn component_service_summary_redacts_oci_password() {
+        let mut golem_config = make_config_loader().load().expect("Failed to load config");
+        golem_config.component_service = ComponentServiceConfig::Oci(ComponentServiceOciConfig {
+            registry: "https://ghcr.io".parse().unwrap(),
+            repository: "golem/components".to_string(),
+            auth: Some(OciAuthConfig {
+                username: "deployer".to_string(),
+                password: "secret-password".to_string(),
+            }),
+            retries: RetryConfig::default(),
+            verification_key: None,
+            max_download_bytes_per_second: Some(1024 * 1024),
+            max_elapsed: None,
+            max_component_size: 50 * 1024 * 1024,
+        });
+        let summary = golem_config.component_service_summary();
+
+        assert!(summary.contains("source: oci"));
+        assert!(summary.contains("repository: golem/components"));
+        assert!(summary.contains("auth: deployer / <redacted>"));
+        assert!(summary.contains("max download rate: 1048576 bytes/s"));
+        assert!(summary.contains("max component size: 52428800"));
+        assert!(summary.contains("verification key: none"));
+        assert!(!summary.contains("secret-password"));
+        assert!(!format!("{:?}", golem_config.component_service).contains("secret-password"));
     }
 }
@@ -1,23 +1,32 @@
 use std::future::Future;
 use std::sync::Arc;
+use std::time::Instant;
 
+use crate::app_config::{ConcurrencyLimitConfig, PreflightCacheConfig, ResponseCacheConfig};
 use crate::gateway_api_definition::http::CompiledHttpApiDefinition;
+use crate::gateway_api_deployment::ApiSiteString;
 use crate::gateway_execution::file_server_binding_handler::FileServerBindingHandler;
 use crate::gateway_rib_interpreter::DefaultRibInterpreter;
 use futures_util::FutureExt;
-use hyper::header::HOST;
-use poem::http::StatusCode;
+use hyper::header::{ACCESS_CONTROL_REQUEST_HEADERS, CONTENT_LENGTH, HOST};
+use poem::http::{Method, StatusCode};
 use poem::{Body, Endpoint, Request, Response};
 use tracing::{error, info};
 
 use crate::gateway_execution::api_definition_lookup::ApiDefinitionsLookup;
+use crate::gateway_execution::concurrency_limit::{ConcurrencyLimitKey, ConcurrencyLimiter};
+use crate::gateway_execution::conditional_request::evaluate_preconditions;
 
-use crate::gateway_binding::GatewayBindingResolver;
+use crate::gateway_binding::StaticBinding;
 use crate::gateway_execution::gateway_binding_executor::{
     DefaultGatewayBindingExecutor, GatewayBindingExecutor,
 };
+use crate::gateway_execution::gateway_binding_resolver::ResolvedBinding;
+use crate::gateway_execution::preflight_cache::{PreflightCache, PreflightCacheKey};
+use crate::gateway_execution::response_cache::{ResponseCache, ResponseCacheKey};
 use crate::gateway_execution::GatewayWorkerRequestExecutor;
-use crate::gateway_request::http_request::{ApiInputPath, InputHttpRequest};
+use crate::gateway_middleware::{CorsOriginResolver, RequestConnection};
+use crate::gateway_request::http_request::{router, ApiInputPath, InputHttpRequest};
 
 // Executes custom request with the help of worker_request_executor and definition_service
 // This is a common API projects can make use of, similar to healthcheck service
@@ -30,6 +39,11 @@ pub struct CustomHttpRequestApi<Namespace> {
     >,
     pub gateway_binding_executor:
         Arc<dyn GatewayBindingExecutor<Namespace, poem::Response> + Sync + Send>,
+    pub response_cache: Arc<ResponseCache>,
+    pub preflight_cache: Arc<PreflightCache>,
+    pub concurrency_limiter: Arc<ConcurrencyLimiter>,
+    pub max_preflight_request_headers_size: usize,
+    pub cors_origin_resolver: Option<Arc<dyn CorsOriginResolver + Sync + Send>>,
 }
 
 impl<Namespace: Clone + Send + Sync + 'static> CustomHttpRequestApi<Namespace> {
@@ -41,6 +55,10 @@ impl<Namespace: Clone + Send + Sync + 'static> CustomHttpRequestApi<Namespace> {
                 + Send,
         >,
         fileserver_binding_handler: Arc<dyn FileServerBindingHandler<Namespace> + Sync + Send>,
+        max_preflight_request_headers_size: usize,
+        preflight_cache: &PreflightCacheConfig,
+        response_cache: &ResponseCacheConfig,
+        concurrency_limit: &ConcurrencyLimitConfig,
     ) -> Self {
         let evaluator = Arc::new(DefaultRibInterpreter::from_worker_request_executor(
             worker_request_executor_service.clone(),
@@ -54,13 +72,30 @@ impl<Namespace: Clone + Send + Sync + 'static> CustomHttpRequestApi<Namespace> {
         Self {
             api_definition_lookup_service,
             gateway_binding_executor,
+            response_cache: Arc::new(ResponseCache::new(response_cache)),
+            preflight_cache: Arc::new(PreflightCache::new(preflight_cache)),
+            concurrency_limiter: Arc::new(ConcurrencyLimiter::new(concurrency_limit)),
+            max_preflight_request_headers_size,
+            cors_origin_resolver: None,
         }
     }
 
+    // Allows the origins accepted by the predicate the resolver provides for the host of a
+    // request, in addition to the origins of the CORS configurations of the host's routes
+    pub fn with_cors_origin_resolver(
+        mut self,
+        cors_origin_resolver: Arc<dyn CorsOriginResolver + Sync + Send>,
+    ) -> Self {
+        self.cors_origin_resolver = Some(cors_origin_resolver);
+        self
+    }
+
     pub async fn execute(&self, request: Request) -> Response {
+        let connection = RequestConnection::from_request(&request);
         let (req_parts, body) = request.into_parts();
         let headers = req_parts.headers;
         let uri = req_parts.uri;
+        let method = req_parts.method;
 
         let host = match headers.get(HOST).and_then(|h| h.to_str().ok()) {
             Some(host) => host.to_string(),
@@ -73,28 +108,33 @@ impl<Namespace: Clone + Send + Sync + 'static> CustomHttpRequestApi<Namespace> {
 
         info!("API request host: {}", host);
 
-        let json_request_body: serde_json::Value = if body.is_empty() {
-            serde_json::Value::Null
-        } else {
-            match body.into_json().await {
-                Ok(json_request_body) => json_request_body,
-                Err(err) => {
-                    error!("API request host: {} - error: {}", host, err);
-                    return Response::builder()
-                        .status(StatusCode::BAD_REQUEST)
-                        .body(Body::from_string("Request body parse error".to_string()));
-                }
+        if method == Method::OPTIONS {
+            let request_headers_size: usize = headers
+                .get_all(ACCESS_CONTROL_REQUEST_HEADERS)
+                .iter()
+                .map(|value| value.len())
+                .sum();
+
+            if request_headers_size > self.max_preflight_request_headers_size {
+                error!(
+                    "API request host: {} - preflight request headers too large: {} bytes",
+                    host, request_headers_size
+                );
+                return Response::builder().status(StatusCode::BAD_REQUEST).body(
+                    Body::from_string("Access-Control-Request-Headers too large".to_string()),
+                );
             }
-        };
+        }
 
-        let input_http_request = InputHttpRequest {
+        // The body is read once the route is known, as its body transform decides how to parse it
+        let mut input_http_request = InputHttpRequest {
             input_path: ApiInputPath {
                 base_path: uri.path().to_string(),
                 query_path: uri.query().map(|x| x.to_string()),
             },
             headers,
-            req_method: req_parts.method,
-            req_body: json_request_body,
+            req_method: method.clone(),
+            req_body: serde_json::Value::Null,
         };
 
         let possible_api_definitions = match self
@@ -114,15 +154,181 @@ impl<Namespace: Clone + Send + Sync + 'static> CustomHttpRequestApi<Namespace> {
             }
         };
 
-        match input_http_request
-            .resolve_worker_binding(possible_api_definitions)
+        let router = router::build_from_definitions(&possible_api_definitions);
+        let route_middlewares = input_http_request.route_middlewares(&router);
+
+        // Request hooks, such as the IP filter and the header size limit, only need the headers,
+        // so they reject requests before the body is read or transformed, and before CORS and
+        // the worker
+        if let Some(response) = route_middlewares.as_ref().and_then(|middlewares| {
+            middlewares.process_http_request(&input_http_request, &connection)
+        }) {
+            return Self::finish_response(&method, response).await;
+        }
+
+        let body_transform =
+            route_middlewares.and_then(|middlewares| middlewares.get_body_transform());
+
+        input_http_request.req_body = match &body_transform {
+            Some(body_transform)
+                if body_transform.applies_to_request(&input_http_request.headers) =>
+            {
+                match body_transform
+                    .transform_request_body(&input_http_request.headers, body)
+                    .await
+                {
+                    Ok(json_request_body) => json_request_body,
+                    Err(response) => {
+                        error!(
+                            "API request host: {} - failed to transform the request body",
+                            host
+                        );
+                        return response;
+                    }
+                }
+            }
+            _ if body.is_empty() => serde_json::Value::Null,
+            _ => match body.into_json().await {
+                Ok(json_request_body) => json_request_body,
+                Err(err) => {
+                    error!("API request host: {} - error: {}", host, err);
+                    return Response::builder()
+                        .status(StatusCode::BAD_REQUEST)
+                        .body(Body::from_string("Request body parse error".to_string()));
+                }
+            },
+        };
+
+        let response = match input_http_request
+            .resolve_worker_binding_with_router(&router)
             .await
         {
-            Ok(resolved_gateway_binding) => {
+            Ok(mut resolved_gateway_binding) => {
+                if let Some(predicate) = self
+                    .cors_origin_resolver
+                    .as_ref()
+                    .and_then(|resolver| resolver.allow_origin_predicate(&host))
+                {
+                    resolved_gateway_binding.set_allow_origin_predicate(&predicate);
+                }
+
+                let cache = match &resolved_gateway_binding.resolved_binding {
+                    ResolvedBinding::Worker(binding) | ResolvedBinding::FileServer(binding) => {
+                        binding.middlewares.get_response_cache().and_then(|config| {
+                            let transformed =
+                                body_transform.as_ref().is_some_and(|body_transform| {
+                                    body_transform.applies_to_response(&input_http_request.headers)
+                                });
+                            ResponseCacheKey::from_request(&input_http_request, &config)
+                                .map(|key| (key.with_transformed_body(transformed), config))
+                        })
+                    }
+                    ResolvedBinding::Static(_) | ResolvedBinding::AutoOptions(_) => None,
+                };
+
+                if let Some((key, _)) = &cache {
+                    if let Some(response) = self.response_cache.get(key) {
+                        let response = Self::finish_response(&method, response).await;
+                        return evaluate_preconditions(
+                            &method,
+                            &input_http_request.headers,
+                            response,
+                        );
+                    }
+                }
+
+                if let ResolvedBinding::Static(StaticBinding::HttpCorsPreflight(cors)) =
+                    &resolved_gateway_binding.resolved_binding
+                {
+                    let key = PreflightCacheKey::from_request(&input_http_request);
+                    if let Some(response) = self.preflight_cache.get(&key, cors) {
+                        return response;
+                    }
+
+                    let response: poem::Response = self
+                        .gateway_binding_executor
+                        .execute_binding(&resolved_gateway_binding)
+                        .await;
+                    return self.preflight_cache.put(&key, cors, response).await;
+                }
+
+                // Held until the response is built, a streaming body is not counted as in flight
+                let _in_flight = match &resolved_gateway_binding.resolved_binding {
+                    ResolvedBinding::Worker(binding) | ResolvedBinding::FileServer(binding) => {
+                        match binding.middlewares.get_concurrency() {
+                            Some(limit) => {
+                                // The host the API definitions were found for
+                                let key = ConcurrencyLimitKey::new(
+                                    &ApiSiteString(host.clone()),
+                                    &binding.route,
+                                    &binding.worker_detail.component_id,
+                                );
+                                match self.concurrency_limiter.acquire(&key, limit).await {
+                                    Ok(in_flight) => Some(in_flight),
+                                    Err(response) => {
+                                        return Self::finish_response(&method, response).await;
+                                    }
+                                }
+                            }
+                            None => None,
+                        }
+                    }
+                    ResolvedBinding::Static(_) | ResolvedBinding::AutoOptions(_) => None,
+                };
+
+                let server_timing = match &resolved_gateway_binding.resolved_binding {
+                    ResolvedBinding::Worker(binding) | ResolvedBinding::FileServer(binding) => {
+                        binding.middlewares.get_server_timing()
+                    }
+                    ResolvedBinding::Static(_) | ResolvedBinding::AutoOptions(_) => None,
+                };
+
+                let touches_body = match &resolved_gateway_binding.resolved_binding {
+                    ResolvedBinding::Worker(binding) | ResolvedBinding::FileServer(binding) => {
+                        binding.middlewares.touches_body()
+                    }
+                    ResolvedBinding::Static(_) | ResolvedBinding::AutoOptions(_) => false,
+                };
+
+                // Only the execution is timed, not the body transform below or the response
+                // compression of the gateway
+                let started = Instant::now();
                 let response: poem::Response = self
                     .gateway_binding_executor
                     .execute_binding(&resolved_gateway_binding)
                     .await;
+                let duration = started.elapsed();
+
+                // Header-only middlewares were applied while building the response, so unless
+                // one of the middlewares reads the body, a streaming body is passed on untouched
+                let mut response = if touches_body {
+                    let response = match &body_transform {
+                        Some(body_transform) => {
+                            let mut response = if body_transform
+                                .applies_to_response(&input_http_request.headers)
+                            {
+                                body_transform.transform_response(response).await
+                            } else {
+                                response
+                            };
+                            body_transform.add_vary(response.headers_mut());
+                            response
+                        }
+                        None => response,
+                    };
+
+                    match &cache {
+                        Some((key, config)) => self.response_cache.put(key, config, response).await,
+                        None => response,
+                    }
+                } else {
+                    response
+                };
+
+                // Added after caching, so that responses served from the cache have no timing
+                if let Some(server_timing) = &server_timing {
+                    server_timing.apply(&mut response, duration);
+                }
 
                 response
             }
@@ -134,7 +340,41 @@ impl<Namespace: Clone + Send + Sync + 'static> CustomHttpRequestApi<Namespace> {
                     .status(StatusCode::METHOD_NOT_ALLOWED)
                     .finish()
             }
+        };
+
+        let response = Self::finish_response(&method, response).await;
+        evaluate_preconditions(&method, &input_http_request.headers, response)
+    }
+
+    // Responses to HEAD requests (which may be served by a GET route with auto HEAD enabled)
+    // keep the headers of the full response, including its Content-Length, but have no body
+    pub async fn finish_response(method: &Method, mut response: Response) -> Response {
+        if method != Method::HEAD {
+            return response;
         }
+
+        let body = response.take_body();
+
+        if !response.headers().contains_key(CONTENT_LENGTH) {
+            match body.into_bytes().await {
+                Ok(bytes) => {
+                    response
+                        .headers_mut()
+                        .insert(CONTENT_LENGTH, bytes.len().into());
+                }
+                Err(err) => {
+                    error!(
+                        "Failed to read the response body of a HEAD request: {}",
+                        err
+                    );
+                    return Response::builder()
+                        .status(StatusCode::INTERNAL_SERVER_ERROR)
+                        .finish();
+                }
+            }
+        }
+
+        response
     }
 }
 
@@ -145,3 +385,47 @@ impl<Namespace: Clone + Send + Sync + 'static> Endpoint for CustomHttpRequestApi
         self.execute(req).map(Ok)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use golem_service_base::auth::DefaultNamespace;
+    use hyper::header::CONTENT_LENGTH;
+    use poem::http::Method;
+    use poem::{Body, Response};
+
+    use crate::api::CustomHttpRequestApi;
+
+    async fn finish_response(method: &Method, response: Response) -> Response {
+        CustomHttpRequestApi::<DefaultNamespace>::finish_response(method, response).await
+    }
+
+    #[test]
+    async fn keeps_the_body_of_other_methods() {
+        let response = finish_response(&Method::GET, Response::builder().body("hello")).await;
+
+        assert!(!response.headers().contains_key(CONTENT_LENGTH));
+        assert_eq!(response.into_body().into_string().await.unwrap(), "hello");
+    }
+
+    #[test]
+    async fn strips_the_body_of_head_responses_keeping_its_length() {
+        let response = finish_response(&Method::HEAD, Response::builder().body("hello")).await;
+
+        assert_eq!(response.headers().get(CONTENT_LENGTH).unwrap(), "5");
+        assert!(response.into_body().into_bytes().await.unwrap().is_empty());
+    }
+
+    #[test]
+    async fn keeps_the_content_length_of_head_responses() {
+        // Set by the route for the full body, which is not read again
+        let response = Response::builder()
+            .header(CONTENT_LENGTH, 1024)
+            .body(Body::from_string("partial".to_string()));
+        let response = finish_response(&Method::HEAD, response).await;
+
+        assert_eq!(response.headers().get(CONTENT_LENGTH).unwrap(), "1024");
+        assert!(response.into_body().into_bytes().await.unwrap().is_empty());
+    }
+}
@@ -3,6 +3,7 @@ pub use custom_http_request_api::*;
 pub use error::*;
 pub use healthcheck::*;
 pub use register_api_definition_api::*;
+pub use request_decompression::*;
 
 // Components and request data that can be reused for implementing server API endpoints
 mod common;
@@ -10,3 +11,4 @@ mod custom_http_request_api;
 mod error;
 mod healthcheck;
 mod register_api_definition_api;
+mod request_decompression;
@@ -6,7 +6,10 @@ use crate::gateway_api_deployment::ApiSite;
 use crate::gateway_binding::{
     GatewayBinding, GatewayBindingCompiled, StaticBinding, WorkerBinding, WorkerBindingCompiled,
 };
-use crate::gateway_middleware::{Cors, CorsPreflightExpr, HttpMiddleware, Middleware};
+use crate::gateway_middleware::{
+    BodyTransformConfig, CacheConfig, Cors, CorsPreflightExpr, HttpMiddleware, IpFilterConfig,
+    LocationRewriteRule, Middleware, RequireHttpsConfig, ServerTimingConfig,
+};
 use golem_api_grpc::proto::golem::apidefinition as grpc_apidefinition;
 use golem_common::model::GatewayBindingType;
 use golem_service_base::model::VersionedComponentId;
@@ -124,6 +127,33 @@ impl From<CompiledRoute> for RouteWithTypeInfo {
     }
 }
 
+// The middlewares of a route, described in the order the gateway runs them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+pub struct RouteMiddlewares {
+    pub method: MethodPattern,
+    pub path: String,
+    pub middlewares: Vec<String>,
+}
+
+impl From<CompiledRoute> for RouteMiddlewares {
+    fn from(value: CompiledRoute) -> Self {
+        let middlewares = match value.binding {
+            GatewayBindingCompiled::Worker(binding)
+            | GatewayBindingCompiled::FileServer(binding) => binding
+                .middlewares
+                .map(|middlewares| middlewares.describe())
+                .unwrap_or_default(),
+            GatewayBindingCompiled::Static(_) => vec![],
+        };
+
+        Self {
+            method: value.method,
+            path: value.path.to_string(),
+            middlewares,
+        }
+    }
+}
+
 // GatewayBindingData is a user exposed structure of GatewayBinding
 // GatewayBindingData is flattened here only to keep the REST API backward compatibility.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
@@ -186,11 +216,46 @@ impl GatewayBindingData {
         };
 
         let middleware = worker_binding.middleware.and_then(|x| {
-            x.0.iter().find_map(|m| {
-                m.get_cors().map(|c| MiddlewareData {
-                    cors: Some(c.clone()),
+            let cors = x.get_cors();
+            let auto_options = x.has_auto_options().then_some(true);
+            let response_cache = x.get_response_cache();
+            let auto_head = x.has_auto_head().then_some(true);
+            let location_rewrite = x.get_location_rewrite();
+            let max_header_size = x.get_max_header_size();
+            let body_transform = x.get_body_transform();
+            let require_https = x.get_require_https();
+            let concurrency = x.get_concurrency();
+            let ip_filter = x.get_ip_filter();
+            let server_timing = x.get_server_timing();
+
+            if cors.is_none()
+                && auto_options.is_none()
+                && response_cache.is_none()
+                && auto_head.is_none()
+                && location_rewrite.is_none()
+                && max_header_size.is_none()
+                && body_transform.is_none()
+                && require_https.is_none()
+                && concurrency.is_none()
+                && ip_filter.is_none()
+                && server_timing.is_none()
+            {
+                None
+            } else {
+                Some(MiddlewareData {
+                    cors,
+                    auto_options,
+                    response_cache,
+                    auto_head,
+                    location_rewrite,
+                    max_header_size,
+                    body_transform,
+                    require_https,
+                    concurrency,
+                    ip_filter,
+                    server_timing,
                 })
-            })
+            }
         });
 
         Ok(Self {
@@ -215,6 +280,16 @@ impl GatewayBindingData {
 #[oai(rename_all = "camelCase")]
 pub struct MiddlewareData {
     pub cors: Option<Cors>,
+    pub auto_options: Option<bool>,
+    pub response_cache: Option<CacheConfig>,
+    pub auto_head: Option<bool>,
+    pub location_rewrite: Option<Vec<LocationRewriteRule>>,
+    pub max_header_size: Option<usize>,
+    pub body_transform: Option<BodyTransformConfig>,
+    pub require_https: Option<RequireHttpsConfig>,
+    pub concurrency: Option<usize>,
+    pub ip_filter: Option<IpFilterConfig>,
+    pub server_timing: Option<ServerTimingConfig>,
 }
 
 // GolemWorkerBindingWithTypeInfo is a subset of CompiledGolemWorkerBinding
@@ -463,9 +538,52 @@ impl TryFrom<GatewayBindingData> for GatewayBinding {
 
                 let mut middlewares = Vec::new();
                 if let Some(middle_ware_daa) = gateway_binding_data.middleware {
+                    if let Some(ip_filter) = middle_ware_daa.ip_filter {
+                        middlewares.push(Middleware::http(HttpMiddleware::ip_filter(ip_filter)));
+                    }
+                    if let Some(require_https) = middle_ware_daa.require_https {
+                        middlewares.push(Middleware::http(HttpMiddleware::require_https(
+                            require_https,
+                        )));
+                    }
+                    if let Some(max_header_size) = middle_ware_daa.max_header_size {
+                        middlewares.push(Middleware::http(HttpMiddleware::max_header_size(
+                            max_header_size,
+                        )));
+                    }
                     if let Some(cors) = middle_ware_daa.cors {
                         middlewares.push(Middleware::http(HttpMiddleware::cors(cors)));
                     }
+                    if middle_ware_daa.auto_options == Some(true) {
+                        middlewares.push(Middleware::http(HttpMiddleware::auto_options()));
+                    }
+                    if let Some(response_cache) = middle_ware_daa.response_cache {
+                        middlewares.push(Middleware::http(HttpMiddleware::response_cache(
+                            response_cache,
+                        )));
+                    }
+                    if middle_ware_daa.auto_head == Some(true) {
+                        middlewares.push(Middleware::http(HttpMiddleware::auto_head()));
+                    }
+                    if let Some(location_rewrite) = middle_ware_daa.location_rewrite {
+                        middlewares.push(Middleware::http(HttpMiddleware::location_rewrite(
+                            location_rewrite,
+                        )));
+                    }
+                    if let Some(body_transform) = middle_ware_daa.body_transform {
+                        middlewares.push(Middleware::http(HttpMiddleware::body_transform(
+                            body_transform,
+                        )));
+                    }
+                    if let Some(concurrency) = middle_ware_daa.concurrency {
+                        middlewares
+                            .push(Middleware::http(HttpMiddleware::concurrency(concurrency)));
+                    }
+                    if let Some(server_timing) = middle_ware_daa.server_timing {
+                        middlewares.push(Middleware::http(HttpMiddleware::server_timing(
+                            server_timing,
+                        )));
+                    }
                 }
 
                 let worker_binding = WorkerBinding {
@@ -0,0 +1,218 @@
+use std::io::Read;
+
+use flate2::read::MultiGzDecoder;
+use futures_util::StreamExt;
+use http::header::{CONTENT_ENCODING, CONTENT_LENGTH};
+use http::{HeaderMap, HeaderValue, StatusCode};
+use poem::{Body, Endpoint, IntoResponse, Middleware, Request, Response};
+
+// Decompresses gzip request bodies (Content-Encoding: gzip) before they reach the gateway,
+// so the workers always receive the plain body. The Content-Encoding header is removed and the
+// Content-Length is updated to the decompressed size. Requests with other encodings are passed on
+// untouched
+pub struct RequestDecompression {
+    max_decompressed_size: usize,
+}
+
+impl RequestDecompression {
+    pub fn new(max_decompressed_size: usize) -> Self {
+        Self {
+            max_decompressed_size,
+        }
+    }
+}
+
+impl<E: Endpoint> Middleware<E> for RequestDecompression {
+    type Output = RequestDecompressionEndpoint<E>;
+
+    fn transform(&self, ep: E) -> Self::Output {
+        RequestDecompressionEndpoint {
+            inner: ep,
+            max_decompressed_size: self.max_decompressed_size,
+        }
+    }
+}
+
+pub struct RequestDecompressionEndpoint<E> {
+    inner: E,
+    max_decompressed_size: usize,
+}
+
+impl<E: Endpoint> Endpoint for RequestDecompressionEndpoint<E> {
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> poem::Result<Self::Output> {
+        if is_gzip_encoded(req.headers()) {
+            match decompress_gzip(req.take_body(), self.max_decompressed_size).await {
+                Ok(body) => {
+                    req.headers_mut().remove(CONTENT_ENCODING);
+                    req.headers_mut()
+                        .insert(CONTENT_LENGTH, HeaderValue::from(body.len()));
+                    req.set_body(body);
+                }
+                Err(response) => return Ok(response),
+            }
+        }
+
+        self.inner.call(req).await.map(IntoResponse::into_response)
+    }
+}
+
+fn is_gzip_encoded(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_ENCODING)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| {
+            let value = value.trim();
+            value.eq_ignore_ascii_case("gzip") || value.eq_ignore_ascii_case("x-gzip")
+        })
+        .unwrap_or(false)
+}
+
+// Both the compressed and the decompressed body are limited to max_size bytes, and the
+// decompression stops as soon as the limit is exceeded
+async fn decompress_gzip(body: Body, max_size: usize) -> Result<Vec<u8>, Response> {
+    let mut compressed = Vec::new();
+    let mut stream = body.into_bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|err| {
+            error_response(
+                StatusCode::BAD_REQUEST,
+                format!("Failed to read the request body: {err}"),
+            )
+        })?;
+        if compressed.len() + chunk.len() > max_size {
+            return Err(too_large(max_size));
+        }
+        compressed.extend_from_slice(&chunk);
+    }
+
+    let mut decompressed = Vec::new();
+    MultiGzDecoder::new(compressed.as_slice())
+        .take(max_size as u64 + 1)
+        .read_to_end(&mut decompressed)
+        .map_err(|err| {
+            error_response(
+                StatusCode::BAD_REQUEST,
+                format!("Invalid gzip request body: {err}"),
+            )
+        })?;
+
+    if decompressed.len() > max_size {
+        Err(too_large(max_size))
+    } else {
+        Ok(decompressed)
+    }
+}
+
+fn too_large(max_size: usize) -> Response {
+    error_response(
+        StatusCode::PAYLOAD_TOO_LARGE,
+        format!("Request body too large: the limit is {max_size} bytes after decompression"),
+    )
+}
+
+fn error_response(status: StatusCode, message: String) -> Response {
+    Response::builder()
+        .status(status)
+        .body(Body::from_string(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::io::Write;
+
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use http::header::{CONTENT_ENCODING, CONTENT_LENGTH};
+    use http::StatusCode;
+    use poem::test::TestClient;
+    use poem::{handler, EndpointExt, Request};
+
+    use crate::api::RequestDecompression;
+
+    #[handler]
+    async fn echo(req: &Request, body: String) -> String {
+        format!(
+            "{}|{}|{}",
+            req.headers()
+                .get(CONTENT_ENCODING)
+                .map(|value| value.to_str().unwrap())
+                .unwrap_or("none"),
+            req.headers()
+                .get(CONTENT_LENGTH)
+                .map(|value| value.to_str().unwrap())
+                .unwrap_or("none"),
+            body
+        )
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    async fn decompresses_gzip_bodies() {
+        let client = TestClient::new(echo.with(RequestDecompression::new(1024)));
+
+        let response = client
+            .post("/")
+            .header(CONTENT_ENCODING, "gzip")
+            .body(gzip(b"{\"hello\":\"world\"}"))
+            .send()
+            .await;
+
+        response.assert_status_is_ok();
+        response.assert_text("none|17|{\"hello\":\"world\"}").await;
+    }
+
+    #[test]
+    async fn keeps_other_encodings() {
+        let client = TestClient::new(echo.with(RequestDecompression::new(1024)));
+
+        let response = client
+            .post("/")
+            .header(CONTENT_ENCODING, "identity")
+            .body("plain")
+            .send()
+            .await;
+
+        response.assert_status_is_ok();
+        let text = response.0.into_body().into_string().await.unwrap();
+        assert!(text.starts_with("identity|"));
+        assert!(text.ends_with("|plain"));
+    }
+
+    #[test]
+    async fn rejects_bodies_decompressing_over_the_limit() {
+        let client = TestClient::new(echo.with(RequestDecompression::new(1024)));
+
+        // Compresses to a few dozen bytes
+        let response = client
+            .post("/")
+            .header(CONTENT_ENCODING, "gzip")
+            .body(gzip(&[b'a'; 1025]))
+            .send()
+            .await;
+
+        response.assert_status(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[test]
+    async fn rejects_invalid_gzip_bodies() {
+        let client = TestClient::new(echo.with(RequestDecompression::new(1024)));
+
+        let response = client
+            .post("/")
+            .header(CONTENT_ENCODING, "gzip")
+            .body("not gzip")
+            .send()
+            .await;
+
+        response.assert_status(StatusCode::BAD_REQUEST);
+    }
+}
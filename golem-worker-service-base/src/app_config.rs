@@ -3,6 +3,8 @@ use std::time::Duration;
 
 use golem_service_base::config::BlobStorageConfig;
 use http::Uri;
+use poem::middleware::Compression;
+use poem::web::CompressionAlgo;
 use serde::{Deserialize, Serialize};
 use url::Url;
 use uuid::Uuid;
@@ -12,6 +14,8 @@ use golem_common::config::{DbConfig, DbSqliteConfig};
 use golem_common::tracing::TracingConfig;
 use golem_service_base::service::routing_table::RoutingTableConfig;
 
+use crate::api::RequestDecompression;
+
 // The base configuration for the worker service
 // If there are extra configurations for custom services,
 // it's preferred to reuse base config.
@@ -27,6 +31,14 @@ pub struct WorkerServiceBaseConfig {
     pub routing_table: RoutingTableConfig,
     pub worker_executor_retries: RetryConfig,
     pub blob_storage: BlobStorageConfig,
+    // Maximum total size in bytes of the Access-Control-Request-Headers of a preflight (OPTIONS)
+    // request. Larger preflights are rejected with 400 before any route is resolved
+    pub max_preflight_request_headers_size: usize,
+    pub preflight_cache: PreflightCacheConfig,
+    pub response_cache: ResponseCacheConfig,
+    pub concurrency_limit: ConcurrencyLimitConfig,
+    pub request_decompression: RequestDecompressionConfig,
+    pub response_compression: ResponseCompressionConfig,
 }
 
 impl WorkerServiceBaseConfig {
@@ -57,6 +69,12 @@ impl Default for WorkerServiceBaseConfig {
                 max_jitter_factor: Some(0.15),
             },
             blob_storage: BlobStorageConfig::default(),
+            max_preflight_request_headers_size: 8192,
+            preflight_cache: PreflightCacheConfig::default(),
+            response_cache: ResponseCacheConfig::default(),
+            concurrency_limit: ConcurrencyLimitConfig::default(),
+            request_decompression: RequestDecompressionConfig::default(),
+            response_compression: ResponseCompressionConfig::default(),
         }
     }
 }
@@ -83,6 +101,116 @@ impl HasConfigExamples<WorkerServiceBaseConfig> for WorkerServiceBaseConfig {
     }
 }
 
+// Cache of the responses to CORS preflight (OPTIONS) requests, keyed by the route, the origin
+// and the requested method and headers. Entries are dropped after time_to_live, or as soon as
+// the CORS configuration of the route changes
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PreflightCacheConfig {
+    pub max_capacity: usize,
+    #[serde(with = "humantime_serde")]
+    pub time_to_live: Duration,
+}
+
+impl Default for PreflightCacheConfig {
+    fn default() -> Self {
+        Self {
+            max_capacity: 1024,
+            time_to_live: Duration::from_secs(60),
+        }
+    }
+}
+
+// Cache of the responses of the routes with the response-cache middleware, shared by all routes.
+// The least recently used entries are evicted beyond max_capacity
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ResponseCacheConfig {
+    pub max_capacity: usize,
+}
+
+impl Default for ResponseCacheConfig {
+    fn default() -> Self {
+        Self { max_capacity: 1024 }
+    }
+}
+
+// How requests over the limit of a route with the concurrency middleware are handled. They wait
+// up to max_wait for another request to finish, and are then rejected with 503 Service Unavailable
+// and a Retry-After header of retry_after. With a max_wait of 0 they are rejected immediately
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConcurrencyLimitConfig {
+    #[serde(with = "humantime_serde")]
+    pub max_wait: Duration,
+    #[serde(with = "humantime_serde")]
+    pub retry_after: Duration,
+}
+
+impl Default for ConcurrencyLimitConfig {
+    fn default() -> Self {
+        Self {
+            max_wait: Duration::ZERO,
+            retry_after: Duration::from_secs(1),
+        }
+    }
+}
+
+// Compression of the HTTP responses of the API gateway (the custom request endpoint), negotiated
+// with the Accept-Encoding header of the request. When the client accepts several of the enabled
+// algorithms, the one with the highest q-value wins
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ResponseCompressionConfig {
+    pub gzip: bool,
+    pub brotli: bool,
+}
+
+impl ResponseCompressionConfig {
+    pub fn algorithms(&self) -> Vec<CompressionAlgo> {
+        let mut algorithms = Vec::new();
+        if self.brotli {
+            algorithms.push(CompressionAlgo::BR);
+        }
+        if self.gzip {
+            algorithms.push(CompressionAlgo::GZIP);
+        }
+        algorithms
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.gzip || self.brotli
+    }
+
+    pub fn middleware(&self) -> Compression {
+        Compression::new().algorithms(self.algorithms())
+    }
+}
+
+// Decompression of the gateway's HTTP request bodies sent with a supported Content-Encoding, before
+// they reach the workers. Bodies decompressing to more than max_decompressed_size bytes are
+// rejected with 413, so a small compressed request cannot exhaust the memory of the gateway
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RequestDecompressionConfig {
+    pub gzip: bool,
+    pub max_decompressed_size: usize,
+}
+
+impl RequestDecompressionConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.gzip
+    }
+
+    pub fn middleware(&self) -> RequestDecompression {
+        RequestDecompression::new(self.max_decompressed_size)
+    }
+}
+
+impl Default for RequestDecompressionConfig {
+    fn default() -> Self {
+        Self {
+            gzip: false,
+            max_decompressed_size: 10 * 1024 * 1024,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ComponentServiceConfig {
     pub host: String,
@@ -118,3 +246,70 @@ impl Default for ComponentServiceConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use http::header::{ACCEPT_ENCODING, CONTENT_ENCODING};
+    use poem::test::TestClient;
+    use poem::{handler, EndpointExt};
+
+    use crate::app_config::ResponseCompressionConfig;
+
+    #[handler]
+    fn index() -> String {
+        "compressible ".repeat(100)
+    }
+
+    async fn content_encoding(
+        config: &ResponseCompressionConfig,
+        accept_encoding: &str,
+    ) -> Option<String> {
+        let client = TestClient::new(index.with_if(config.is_enabled(), config.middleware()));
+        let response = client
+            .get("/")
+            .header(ACCEPT_ENCODING, accept_encoding)
+            .send()
+            .await;
+        response.assert_status_is_ok();
+        response
+            .0
+            .headers()
+            .get(CONTENT_ENCODING)
+            .map(|value| value.to_str().unwrap().to_string())
+    }
+
+    #[test]
+    async fn prefers_brotli_with_higher_q_value() {
+        let config = ResponseCompressionConfig {
+            gzip: true,
+            brotli: true,
+        };
+
+        assert_eq!(
+            content_encoding(&config, "gzip;q=0.5, br;q=0.9").await,
+            Some("br".to_string())
+        );
+        assert_eq!(
+            content_encoding(&config, "gzip;q=0.9, br;q=0.5").await,
+            Some("gzip".to_string())
+        );
+    }
+
+    #[test]
+    async fn uses_only_enabled_algorithms() {
+        let gzip_only = ResponseCompressionConfig {
+            gzip: true,
+            brotli: false,
+        };
+        assert_eq!(
+            content_encoding(&gzip_only, "gzip;q=0.5, br;q=0.9").await,
+            Some("gzip".to_string())
+        );
+
+        let disabled = ResponseCompressionConfig::default();
+        assert!(!disabled.is_enabled());
+        assert_eq!(content_encoding(&disabled, "gzip, br").await, None);
+    }
+}
@@ -106,7 +106,8 @@ mod internal {
 
     use crate::gateway_binding::{GatewayBinding, ResponseMapping, StaticBinding, WorkerBinding};
     use crate::gateway_middleware::{
-        Cors, CorsPreflightExpr, HttpMiddleware, Middleware, Middlewares,
+        BodyTransformConfig, CacheConfig, Cors, CorsPreflightExpr, HttpMiddleware, IpFilterConfig,
+        LocationRewriteRule, Middleware, Middlewares, RequireHttpsConfig, ServerTimingConfig,
     };
     use golem_service_base::model::VersionedComponentId;
     use uuid::Uuid;
@@ -328,6 +329,29 @@ mod internal {
         if let Some(middleware_value) = worker_gateway_info.get("middlewares") {
             match middleware_value {
                 Value::Object(map) => {
+                    let ip_filter: Option<IpFilterConfig> = map
+                        .get("ip-filter")
+                        .map(|json_value| serde_json::from_value(json_value.clone()))
+                        .transpose()
+                        .map_err(|err| format!("Invalid schema for ip filter {}", err))?;
+
+                    if let Some(ip_filter) = ip_filter {
+                        middlewares.push(HttpMiddleware::ip_filter(ip_filter));
+                    }
+
+                    let max_header_size = map
+                        .get("max-header-size")
+                        .map(|json_value| {
+                            json_value
+                                .as_u64()
+                                .ok_or("max-header-size is not a non-negative integer")
+                        })
+                        .transpose()?;
+
+                    if let Some(max_header_size) = max_header_size {
+                        middlewares.push(HttpMiddleware::max_header_size(max_header_size as usize));
+                    }
+
                     let cors_preflight: Option<Cors> = map
                         .get("cors")
                         .map(|json_value| serde_json::from_value(json_value.clone()))
@@ -337,6 +361,87 @@ mod internal {
                     if let Some(cors_preflight) = cors_preflight {
                         middlewares.push(HttpMiddleware::cors(cors_preflight));
                     }
+
+                    let auto_options = map
+                        .get("auto-options")
+                        .map(|json_value| json_value.as_bool().ok_or("auto-options is not a bool"))
+                        .transpose()?;
+
+                    if auto_options == Some(true) {
+                        middlewares.push(HttpMiddleware::auto_options());
+                    }
+
+                    let response_cache: Option<CacheConfig> = map
+                        .get("response-cache")
+                        .map(|json_value| serde_json::from_value(json_value.clone()))
+                        .transpose()
+                        .map_err(|err| format!("Invalid schema for response cache {}", err))?;
+
+                    if let Some(response_cache) = response_cache {
+                        middlewares.push(HttpMiddleware::response_cache(response_cache));
+                    }
+
+                    let auto_head = map
+                        .get("auto-head")
+                        .map(|json_value| json_value.as_bool().ok_or("auto-head is not a bool"))
+                        .transpose()?;
+
+                    if auto_head == Some(true) {
+                        middlewares.push(HttpMiddleware::auto_head());
+                    }
+
+                    let location_rewrite: Option<Vec<LocationRewriteRule>> = map
+                        .get("location-rewrite")
+                        .map(|json_value| serde_json::from_value(json_value.clone()))
+                        .transpose()
+                        .map_err(|err| format!("Invalid schema for location rewrite {}", err))?;
+
+                    if let Some(location_rewrite) = location_rewrite {
+                        middlewares.push(HttpMiddleware::location_rewrite(location_rewrite));
+                    }
+
+                    let body_transform: Option<BodyTransformConfig> = map
+                        .get("body-transform")
+                        .map(|json_value| serde_json::from_value(json_value.clone()))
+                        .transpose()
+                        .map_err(|err| format!("Invalid schema for body transform {}", err))?;
+
+                    if let Some(body_transform) = body_transform {
+                        middlewares.push(HttpMiddleware::body_transform(body_transform));
+                    }
+
+                    let require_https: Option<RequireHttpsConfig> = map
+                        .get("require-https")
+                        .map(|json_value| serde_json::from_value(json_value.clone()))
+                        .transpose()
+                        .map_err(|err| format!("Invalid schema for require https {}", err))?;
+
+                    if let Some(require_https) = require_https {
+                        middlewares.push(HttpMiddleware::require_https(require_https));
+                    }
+
+                    let concurrency = map
+                        .get("concurrency")
+                        .map(|json_value| {
+                            json_value
+                                .as_u64()
+                                .ok_or("concurrency is not a non-negative integer")
+                        })
+                        .transpose()?;
+
+                    if let Some(concurrency) = concurrency {
+                        middlewares.push(HttpMiddleware::concurrency(concurrency as usize));
+                    }
+
+                    let server_timing: Option<ServerTimingConfig> = map
+                        .get("server-timing")
+                        .map(|json_value| serde_json::from_value(json_value.clone()))
+                        .transpose()
+                        .map_err(|err| format!("Invalid schema for server timing {}", err))?;
+
+                    if let Some(server_timing) = server_timing {
+                        middlewares.push(HttpMiddleware::server_timing(server_timing));
+                    }
                 }
                 _ => return Err(
                     "Invalid response mapping type. It should be a string representing expression"
@@ -234,13 +234,7 @@ mod internal {
 
         let middleware = worker_binding
             .middlewares
-            .iter()
-            .map(
-                |m| golem_api_grpc::proto::golem::apidefinition::Middleware {
-                    cors: m.get_cors().map(|x| x.into()),
-                },
-            )
-            .next();
+            .map(golem_api_grpc::proto::golem::apidefinition::Middleware::from);
 
         let binding_type = match binding_type {
             GatewayBindingType::Default => 0,
@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use golem_service_base::model::VersionedComponentId;
+use http::header::RETRY_AFTER;
+use http::StatusCode;
+use poem::{Body, Response};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::debug;
+
+use crate::app_config::ConcurrencyLimitConfig;
+use crate::gateway_api_deployment::ApiSiteString;
+use crate::metrics::GATEWAY_IN_FLIGHT_REQUESTS;
+
+// The requests sharing a concurrency limit: those of the same route (its method and path pattern),
+// of the same site and calling the same component
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConcurrencyLimitKey {
+    site: ApiSiteString,
+    route: String,
+    component_id: VersionedComponentId,
+}
+
+impl ConcurrencyLimitKey {
+    // The site is the one the API definitions of the request were looked up by, so the in-flight
+    // gauge only gets a label per deployed site, not per Host header a client sends
+    pub fn new(site: &ApiSiteString, route: &str, component_id: &VersionedComponentId) -> Self {
+        ConcurrencyLimitKey {
+            site: site.clone(),
+            route: route.to_string(),
+            component_id: component_id.clone(),
+        }
+    }
+}
+
+// A request counted as in flight, until it is dropped
+pub struct InFlightRequest {
+    site: ApiSiteString,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Drop for InFlightRequest {
+    fn drop(&mut self) {
+        GATEWAY_IN_FLIGHT_REQUESTS
+            .with_label_values(&[self.site.0.as_str()])
+            .dec();
+    }
+}
+
+// The semaphores of the routes with the concurrency middleware. A semaphore is replaced as soon
+// as the limit of its routes changes (for example by deploying a new version of the API
+// definition), the requests still holding a permit of the old one are not counted again. The
+// semaphores without any request in flight or waiting are dropped whenever one is added, so that
+// the routes of removed deployments do not keep theirs
+pub struct ConcurrencyLimiter {
+    config: ConcurrencyLimitConfig,
+    semaphores: Mutex<HashMap<ConcurrencyLimitKey, (usize, Arc<Semaphore>)>>,
+}
+
+impl ConcurrencyLimiter {
+    pub fn new(config: &ConcurrencyLimitConfig) -> Self {
+        Self {
+            config: config.clone(),
+            semaphores: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Waits up to the configured max_wait for one of the `limit` slots of the key. Returns the
+    // 503 Service Unavailable response to send instead if none got free in time
+    pub async fn acquire(
+        &self,
+        key: &ConcurrencyLimitKey,
+        limit: usize,
+    ) -> Result<InFlightRequest, Response> {
+        let semaphore = self.semaphore(key, limit);
+
+        let permit = match semaphore.clone().try_acquire_owned() {
+            Ok(permit) => Some(permit),
+            Err(_) if self.config.max_wait.is_zero() => None,
+            Err(_) => tokio::time::timeout(self.config.max_wait, semaphore.acquire_owned())
+                .await
+                .ok()
+                .and_then(|permit| permit.ok()),
+        };
+
+        match permit {
+            Some(permit) => {
+                GATEWAY_IN_FLIGHT_REQUESTS
+                    .with_label_values(&[key.site.0.as_str()])
+                    .inc();
+                Ok(InFlightRequest {
+                    site: key.site.clone(),
+                    _permit: permit,
+                })
+            }
+            None => {
+                debug!(
+                    "Rejecting request to {}, {limit} requests are already in flight",
+                    key.site
+                );
+                Err(self.service_unavailable())
+            }
+        }
+    }
+
+    fn semaphore(&self, key: &ConcurrencyLimitKey, limit: usize) -> Arc<Semaphore> {
+        let mut semaphores = self.semaphores.lock().unwrap();
+        match semaphores.get(key) {
+            Some((current_limit, semaphore)) if *current_limit == limit => semaphore.clone(),
+            _ => {
+                // Permits and waiters hold a reference to their semaphore
+                semaphores.retain(|_, (_, semaphore)| Arc::strong_count(semaphore) > 1);
+                let semaphore = Arc::new(Semaphore::new(limit));
+                semaphores.insert(key.clone(), (limit, semaphore.clone()));
+                semaphore
+            }
+        }
+    }
+
+    fn service_unavailable(&self) -> Response {
+        // Retry-After is in whole seconds, rounded up so that clients do not retry too early
+        let retry_after = self.config.retry_after.as_secs_f64().ceil().max(1.0) as u64;
+        Response::builder()
+            .status(StatusCode::SERVICE_UNAVAILABLE)
+            .header(RETRY_AFTER, retry_after)
+            .body(Body::from_string(
+                "Too many requests in flight, retry later".to_string(),
+            ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::*;
+    use golem_common::model::ComponentId;
+    use std::time::{Duration, Instant};
+
+    fn key(site: &str) -> ConcurrencyLimitKey {
+        route_key(site, "GET /items")
+    }
+
+    fn route_key(site: &str, route: &str) -> ConcurrencyLimitKey {
+        let component_id = VersionedComponentId {
+            component_id: ComponentId(uuid::Uuid::nil()),
+            version: 0,
+        };
+        ConcurrencyLimitKey::new(&ApiSiteString(site.to_string()), route, &component_id)
+    }
+
+    fn limiter(max_wait: Duration) -> ConcurrencyLimiter {
+        ConcurrencyLimiter::new(&ConcurrencyLimitConfig {
+            max_wait,
+            retry_after: Duration::from_millis(1500),
+        })
+    }
+
+    #[test]
+    async fn rejects_requests_over_the_limit() {
+        let limiter = limiter(Duration::ZERO);
+        let key = key("example.com");
+
+        let first = limiter.acquire(&key, 2).await.ok().unwrap();
+        let _second = limiter.acquire(&key, 2).await.ok().unwrap();
+
+        let rejected = limiter.acquire(&key, 2).await.err().unwrap();
+        assert_eq!(rejected.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(rejected.headers().get(RETRY_AFTER).unwrap(), "2");
+
+        // Other sites have their own limit
+        assert!(limiter.acquire(&self::key("other.com"), 2).await.is_ok());
+
+        drop(first);
+        assert!(limiter.acquire(&key, 2).await.is_ok());
+    }
+
+    #[test]
+    async fn queued_requests_wait_up_to_max_wait() {
+        let limiter = Arc::new(limiter(Duration::from_millis(200)));
+        let key = key("example.com");
+
+        let in_flight = limiter.acquire(&key, 1).await.ok().unwrap();
+
+        let start = Instant::now();
+        assert!(limiter.acquire(&key, 1).await.is_err());
+        assert!(start.elapsed() >= Duration::from_millis(200));
+
+        let queued = {
+            let limiter = limiter.clone();
+            let key = key.clone();
+            tokio::spawn(async move { limiter.acquire(&key, 1).await.is_ok() })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        drop(in_flight);
+        assert!(queued.await.unwrap());
+    }
+
+    #[test]
+    async fn changing_the_limit_replaces_the_semaphore() {
+        let limiter = limiter(Duration::ZERO);
+        let key = key("example.com");
+
+        let _in_flight = limiter.acquire(&key, 1).await.ok().unwrap();
+        assert!(limiter.acquire(&key, 1).await.is_err());
+        assert!(limiter.acquire(&key, 2).await.is_ok());
+    }
+
+    #[test]
+    async fn routes_have_their_own_limit() {
+        let limiter = limiter(Duration::ZERO);
+        let items = route_key("example.com", "GET /items");
+        let orders = route_key("example.com", "POST /orders");
+
+        let _items_in_flight = limiter.acquire(&items, 1).await.ok().unwrap();
+        let _orders_in_flight = limiter.acquire(&orders, 2).await.ok().unwrap();
+
+        // The limit of one route does not replace the semaphore of the other
+        assert!(limiter.acquire(&items, 1).await.is_err());
+        assert!(limiter.acquire(&orders, 2).await.is_ok());
+        assert!(limiter.acquire(&items, 1).await.is_err());
+    }
+
+    #[test]
+    async fn idle_semaphores_are_dropped() {
+        let limiter = limiter(Duration::ZERO);
+        let items = route_key("example.com", "GET /items");
+        let orders = route_key("example.com", "POST /orders");
+        let users = route_key("example.com", "GET /users");
+
+        let items_in_flight = limiter.acquire(&items, 1).await.ok().unwrap();
+        drop(limiter.acquire(&orders, 1).await.ok().unwrap());
+        let _users_in_flight = limiter.acquire(&users, 1).await.ok().unwrap();
+
+        // The route without requests in flight lost its semaphore when the last one was added
+        {
+            let semaphores = limiter.semaphores.lock().unwrap();
+            assert!(semaphores.contains_key(&items));
+            assert!(!semaphores.contains_key(&orders));
+            assert!(semaphores.contains_key(&users));
+        }
+
+        // Requests in flight still count after the semaphore of their route is dropped
+        assert!(limiter.acquire(&items, 1).await.is_err());
+        drop(items_in_flight);
+        assert!(limiter.acquire(&items, 1).await.is_ok());
+    }
+}
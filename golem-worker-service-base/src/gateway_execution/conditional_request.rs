@@ -0,0 +1,300 @@
+use http::header::{
+    CACHE_CONTROL, CONTENT_LOCATION, DATE, ETAG, EXPIRES, IF_MATCH, IF_NONE_MATCH, VARY,
+};
+use http::{HeaderMap, Method, StatusCode};
+use poem::Response;
+
+// Headers a 304 Not Modified response keeps from the full response (RFC 7232, section 4.1)
+const NOT_MODIFIED_HEADERS: [http::HeaderName; 6] =
+    [CACHE_CONTROL, CONTENT_LOCATION, DATE, ETAG, EXPIRES, VARY];
+
+// An entity tag of an ETag, If-Match or If-None-Match header (RFC 7232, section 2.3)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntityTag {
+    pub weak: bool,
+    // Without the quotes
+    pub opaque_tag: String,
+}
+
+impl EntityTag {
+    pub fn parse(value: &str) -> Option<Self> {
+        match parse_entity_tag(value.trim()) {
+            Some((tag, "")) => Some(tag),
+            _ => None,
+        }
+    }
+
+    // Both tags are strong and have the same opaque tag. Used for If-Match
+    pub fn strong_eq(&self, other: &EntityTag) -> bool {
+        !self.weak && !other.weak && self.opaque_tag == other.opaque_tag
+    }
+
+    // The opaque tags are the same, whether the tags are weak or not. Used for If-None-Match
+    pub fn weak_eq(&self, other: &EntityTag) -> bool {
+        self.opaque_tag == other.opaque_tag
+    }
+}
+
+// Parses an entity tag at the start of `input`, returning it with the rest of the input
+fn parse_entity_tag(input: &str) -> Option<(EntityTag, &str)> {
+    let (weak, input) = match input.strip_prefix("W/") {
+        Some(rest) => (true, rest),
+        None => (false, input),
+    };
+    let input = input.strip_prefix('"')?;
+    let end = input.find('"')?;
+    let opaque_tag = &input[..end];
+
+    // etagc = %x21 / %x23-7E / obs-text
+    if opaque_tag.bytes().any(|byte| byte < 0x21 || byte == 0x7f) {
+        return None;
+    }
+
+    Some((
+        EntityTag {
+            weak,
+            opaque_tag: opaque_tag.to_string(),
+        },
+        &input[end + 1..],
+    ))
+}
+
+// The value of an If-Match or If-None-Match header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntityTagCondition {
+    // `*`, matching any current representation
+    Any,
+    Tags(Vec<EntityTag>),
+}
+
+impl EntityTagCondition {
+    // Collects all the (comma separated) values of the header. Returns None if the header is
+    // missing, or if any of its values is invalid, in which case the condition is ignored
+    pub fn from_headers(headers: &HeaderMap, name: &http::HeaderName) -> Option<Self> {
+        let mut tags = Vec::new();
+        for value in headers.get_all(name) {
+            let value = value.to_str().ok()?.trim();
+            if value == "*" {
+                return Some(EntityTagCondition::Any);
+            }
+
+            let mut rest = value;
+            loop {
+                rest = rest.trim_start_matches([' ', '\t', ',']);
+                if rest.is_empty() {
+                    break;
+                }
+                let (tag, remaining) = parse_entity_tag(rest)?;
+                tags.push(tag);
+                rest = remaining;
+            }
+        }
+
+        (!tags.is_empty()).then_some(EntityTagCondition::Tags(tags))
+    }
+
+    fn matches(
+        &self,
+        current: Option<&EntityTag>,
+        eq: impl Fn(&EntityTag, &EntityTag) -> bool,
+    ) -> bool {
+        match self {
+            EntityTagCondition::Any => true,
+            EntityTagCondition::Tags(tags) => {
+                current.is_some_and(|current| tags.iter().any(|tag| eq(tag, current)))
+            }
+        }
+    }
+}
+
+// Evaluates the If-Match and If-None-Match preconditions of a GET or HEAD request against the
+// ETag of the response, replacing it with 412 Precondition Failed or 304 Not Modified. Other
+// methods are passed on unchanged: their preconditions must be checked by the worker before it
+// makes any change, the gateway only sees the response. Like any precondition, they are
+// ignored for responses other than 2xx (RFC 7232, section 5)
+pub fn evaluate_preconditions(
+    method: &Method,
+    request_headers: &HeaderMap,
+    response: Response,
+) -> Response {
+    if (method != Method::GET && method != Method::HEAD) || !response.status().is_success() {
+        return response;
+    }
+
+    let current = response
+        .headers()
+        .get(ETAG)
+        .and_then(|value| value.to_str().ok())
+        .and_then(EntityTag::parse);
+
+    if let Some(if_match) = EntityTagCondition::from_headers(request_headers, &IF_MATCH) {
+        if !if_match.matches(current.as_ref(), EntityTag::strong_eq) {
+            return Response::builder()
+                .status(StatusCode::PRECONDITION_FAILED)
+                .finish();
+        }
+    }
+
+    match EntityTagCondition::from_headers(request_headers, &IF_NONE_MATCH) {
+        Some(if_none_match) if if_none_match.matches(current.as_ref(), EntityTag::weak_eq) => {
+            not_modified(&response)
+        }
+        _ => response,
+    }
+}
+
+fn not_modified(response: &Response) -> Response {
+    let mut not_modified = Response::builder()
+        .status(StatusCode::NOT_MODIFIED)
+        .finish();
+    for name in NOT_MODIFIED_HEADERS {
+        for value in response.headers().get_all(&name) {
+            not_modified
+                .headers_mut()
+                .append(name.clone(), value.clone());
+        }
+    }
+    not_modified
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::*;
+    use http::HeaderValue;
+
+    fn tag(value: &str) -> EntityTag {
+        EntityTag::parse(value).unwrap()
+    }
+
+    fn ok_response(etag: &'static str) -> Response {
+        Response::builder()
+            .status(StatusCode::OK)
+            .header(ETAG, etag)
+            .header(CACHE_CONTROL, "max-age=60")
+            .body("body")
+    }
+
+    fn request_headers(name: http::HeaderName, value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn entity_tags_are_parsed() {
+        assert_eq!(
+            EntityTag::parse("\"xyzzy\""),
+            Some(EntityTag {
+                weak: false,
+                opaque_tag: "xyzzy".to_string()
+            })
+        );
+        assert_eq!(
+            EntityTag::parse("W/\"xyzzy\""),
+            Some(EntityTag {
+                weak: true,
+                opaque_tag: "xyzzy".to_string()
+            })
+        );
+        assert_eq!(EntityTag::parse("\"\"").unwrap().opaque_tag, "");
+        assert_eq!(EntityTag::parse("xyzzy"), None);
+        assert_eq!(EntityTag::parse("w/\"xyzzy\""), None);
+        assert_eq!(EntityTag::parse("\"xyzzy\" trailing"), None);
+    }
+
+    // The comparison examples of RFC 7232, section 2.3.2
+    #[test]
+    fn weak_weak_comparison() {
+        assert!(!tag("W/\"1\"").strong_eq(&tag("W/\"1\"")));
+        assert!(tag("W/\"1\"").weak_eq(&tag("W/\"1\"")));
+        assert!(!tag("W/\"1\"").weak_eq(&tag("W/\"2\"")));
+    }
+
+    #[test]
+    fn weak_strong_comparison() {
+        assert!(!tag("W/\"1\"").strong_eq(&tag("\"1\"")));
+        assert!(!tag("\"1\"").strong_eq(&tag("W/\"1\"")));
+        assert!(tag("W/\"1\"").weak_eq(&tag("\"1\"")));
+        assert!(tag("\"1\"").weak_eq(&tag("W/\"1\"")));
+    }
+
+    #[test]
+    fn strong_strong_comparison() {
+        assert!(tag("\"1\"").strong_eq(&tag("\"1\"")));
+        assert!(tag("\"1\"").weak_eq(&tag("\"1\"")));
+        assert!(!tag("\"1\"").strong_eq(&tag("\"2\"")));
+        assert!(!tag("\"1\"").weak_eq(&tag("\"2\"")));
+    }
+
+    #[test]
+    fn conditions_are_lists_of_tags() {
+        let mut headers = request_headers(IF_NONE_MATCH, "\"a,b\", W/\"c\"");
+        headers.append(IF_NONE_MATCH, HeaderValue::from_static("\"d\""));
+
+        assert_eq!(
+            EntityTagCondition::from_headers(&headers, &IF_NONE_MATCH),
+            Some(EntityTagCondition::Tags(vec![
+                tag("\"a,b\""),
+                tag("W/\"c\""),
+                tag("\"d\"")
+            ]))
+        );
+        assert_eq!(
+            EntityTagCondition::from_headers(&request_headers(IF_MATCH, "*"), &IF_MATCH),
+            Some(EntityTagCondition::Any)
+        );
+        assert_eq!(
+            EntityTagCondition::from_headers(&request_headers(IF_MATCH, "\"a\" b"), &IF_MATCH),
+            None
+        );
+        assert_eq!(
+            EntityTagCondition::from_headers(&HeaderMap::new(), &IF_MATCH),
+            None
+        );
+    }
+
+    #[test]
+    fn if_none_match_uses_the_weak_comparison() {
+        let headers = request_headers(IF_NONE_MATCH, "W/\"1\"");
+
+        let response = evaluate_preconditions(&Method::GET, &headers, ok_response("\"1\""));
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers().get(ETAG).unwrap(), "\"1\"");
+        assert_eq!(response.headers().get(CACHE_CONTROL).unwrap(), "max-age=60");
+
+        let response = evaluate_preconditions(&Method::GET, &headers, ok_response("W/\"2\""));
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn if_match_uses_the_strong_comparison() {
+        let headers = request_headers(IF_MATCH, "\"1\"");
+
+        let response = evaluate_preconditions(&Method::GET, &headers, ok_response("\"1\""));
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = evaluate_preconditions(&Method::GET, &headers, ok_response("W/\"1\""));
+        assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+
+        let headers = request_headers(IF_MATCH, "*");
+        let response = evaluate_preconditions(&Method::HEAD, &headers, ok_response("W/\"1\""));
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn preconditions_only_apply_to_successful_safe_requests() {
+        let headers = request_headers(IF_NONE_MATCH, "*");
+
+        let response = evaluate_preconditions(&Method::POST, &headers, ok_response("\"1\""));
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let not_found = Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .header(ETAG, "\"1\"")
+            .finish();
+        let response = evaluate_preconditions(&Method::GET, &headers, not_found);
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::gateway_binding::{
-    GatewayRequestDetails, ResolvedBinding, ResolvedGatewayBinding, ResolvedWorkerBinding,
-    RibInputTypeMismatch, RibInputValueResolver, StaticBinding,
+    AllowedMethods, GatewayRequestDetails, ResolvedBinding, ResolvedGatewayBinding,
+    ResolvedWorkerBinding, RibInputTypeMismatch, RibInputValueResolver, StaticBinding,
 };
 use crate::gateway_execution::file_server_binding_handler::{
     FileServerBindingHandler, FileServerBindingResult,
@@ -21,7 +21,8 @@ pub trait GatewayBindingExecutor<Namespace, Response> {
         EvaluationError: ToResponse<Response>,
         RibInputTypeMismatch: ToResponse<Response>,
         FileServerBindingResult: ToResponse<Response>,
-        CorsPreflight: ToResponse<Response>;
+        CorsPreflight: ToResponse<Response>,
+        AllowedMethods: ToResponse<Response>;
 }
 
 pub struct DefaultGatewayBindingExecutor<Namespace> {
@@ -168,6 +169,7 @@ impl<N: Send + Sync, R: Debug + Send + Sync> GatewayBindingExecutor<N, R>
         RibInputTypeMismatch: ToResponse<R>,
         FileServerBindingResult: ToResponse<R>,
         CorsPreflight: ToResponse<R>,
+        AllowedMethods: ToResponse<R>,
     {
         match &binding.resolved_binding {
             ResolvedBinding::Worker(resolved_binding) => {
@@ -183,6 +185,9 @@ impl<N: Send + Sync, R: Debug + Send + Sync> GatewayBindingExecutor<N, R>
                     .clone()
                     .to_response(&binding.request_details, &Middlewares::default())
             }
+            ResolvedBinding::AutoOptions(allowed_methods) => allowed_methods
+                .clone()
+                .to_response(&binding.request_details, &Middlewares::default()),
         }
     }
 }
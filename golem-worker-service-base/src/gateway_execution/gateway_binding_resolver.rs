@@ -1,16 +1,18 @@
 use crate::gateway_api_definition::http::{CompiledHttpApiDefinition, VarInfo};
 use crate::gateway_binding::{GatewayBindingCompiled, StaticBinding};
-use crate::gateway_binding::{GatewayRequestDetails, ResponseMappingCompiled};
-use crate::gateway_execution::router::RouterPattern;
+use crate::gateway_binding::{GatewayRequestDetails, HttpRequestDetails, ResponseMappingCompiled};
+use crate::gateway_execution::router::{Router, RouterPattern};
+use crate::gateway_request::http_request::router::RouteEntry;
 use crate::gateway_request::http_request::{router, InputHttpRequest};
 use async_trait::async_trait;
 use golem_common::model::IdempotencyKey;
 use golem_service_base::model::VersionedComponentId;
+use http::Method;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::fmt::Display;
 
-use crate::gateway_middleware::Middlewares;
+use crate::gateway_middleware::{Middlewares, OriginPredicate};
 
 // Every type of request (example: InputHttpRequest (which corresponds to a Route)) can have an instance of this resolver,
 // which will resolve the gateway binding equired for that request.
@@ -48,6 +50,22 @@ pub enum ResolvedBinding<Namespace> {
     Static(StaticBinding),
     Worker(ResolvedWorkerBinding<Namespace>),
     FileServer(ResolvedWorkerBinding<Namespace>),
+    AutoOptions(AllowedMethods),
+}
+
+// The methods that have a route for a given path, used to answer
+// OPTIONS requests of routes with the auto-options middleware
+#[derive(Clone, Debug, PartialEq)]
+pub struct AllowedMethods(pub Vec<Method>);
+
+impl AllowedMethods {
+    pub fn to_header_value(&self) -> String {
+        self.0
+            .iter()
+            .map(|method| method.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -89,6 +107,8 @@ pub struct ResolvedWorkerBinding<Namespace> {
     pub compiled_response_mapping: ResponseMappingCompiled,
     pub namespace: Namespace,
     pub middlewares: Middlewares,
+    // The method and path pattern of the route the binding belongs to
+    pub route: String,
 }
 
 impl<Namespace> ResolvedGatewayBinding<Namespace> {
@@ -100,6 +120,21 @@ impl<Namespace> ResolvedGatewayBinding<Namespace> {
             _ => None,
         }
     }
+
+    // Allows the origins accepted by `predicate` in the CORS configurations of the binding,
+    // both of a preflight binding and of the CORS middleware of a worker or file server binding
+    pub fn set_allow_origin_predicate(&mut self, predicate: &OriginPredicate) {
+        match &mut self.resolved_binding {
+            ResolvedBinding::Static(StaticBinding::HttpCorsPreflight(cors)) => {
+                cors.set_allow_origin_predicate(predicate.clone())
+            }
+            ResolvedBinding::Worker(binding) | ResolvedBinding::FileServer(binding) => {
+                binding.middlewares.set_allow_origin_predicate(predicate)
+            }
+            ResolvedBinding::AutoOptions(_) => {}
+        }
+    }
+
     pub fn from_static_binding(
         request_details: &GatewayRequestDetails,
         static_binding: &StaticBinding,
@@ -129,27 +164,49 @@ impl<Namespace: Clone + Send + Sync + 'static>
         &self,
         compiled_api_definitions: Vec<CompiledHttpApiDefinition<Namespace>>,
     ) -> Result<ResolvedGatewayBinding<Namespace>, GatewayBindingResolverError> {
-        let compiled_routes = compiled_api_definitions
-            .iter()
-            .flat_map(|x| x.routes.iter().map(|y| (x.namespace.clone(), y.clone())))
-            .collect::<Vec<_>>();
+        let router = router::build_from_definitions(&compiled_api_definitions);
+        self.resolve_worker_binding_with_router(&router).await
+    }
+}
 
+impl InputHttpRequest {
+    // Resolves the binding with a router already built from the API definitions
+    pub async fn resolve_worker_binding_with_router<Namespace: Clone + Send + Sync + 'static>(
+        &self,
+        router: &Router<RouteEntry<Namespace>>,
+    ) -> Result<ResolvedGatewayBinding<Namespace>, GatewayBindingResolverError> {
         let api_request = self;
-        let router = router::build(compiled_routes);
 
         let path: Vec<&str> = RouterPattern::split(&api_request.input_path.base_path).collect();
         let request_query_variables = self.input_path.query_components().unwrap_or_default();
         let request_body = &self.req_body;
         let headers = &self.headers;
 
-        let router::RouteEntry {
+        let route_entry = match router.check_path(&api_request.req_method, &path) {
+            Some(route_entry) => route_entry,
+            None if api_request.req_method == Method::HEAD => {
+                internal::get_auto_head_route_entry(router, &path)
+                    .ok_or("Failed to resolve route")?
+            }
+            None if api_request.req_method == Method::OPTIONS => {
+                let allowed_methods = internal::get_auto_options_allowed_methods(router, &path)
+                    .ok_or("Failed to resolve route")?;
+
+                return Ok(ResolvedGatewayBinding {
+                    request_details: GatewayRequestDetails::Http(HttpRequestDetails::empty()),
+                    resolved_binding: ResolvedBinding::AutoOptions(allowed_methods),
+                });
+            }
+            None => return Err("Failed to resolve route".into()),
+        };
+
+        let RouteEntry {
             path_params,
             query_params,
             namespace,
             binding,
-        } = router
-            .check_path(&api_request.req_method, &path)
-            .ok_or("Failed to resolve route")?;
+            route,
+        } = route_entry;
 
         let zipped_path_params: HashMap<VarInfo, &str> = {
             path_params
@@ -165,13 +222,15 @@ impl<Namespace: Clone + Send + Sync + 'static>
             request_body,
             headers,
         )
-        .map_err(|err| format!("Failed to fetch input request details {}", err.join(", ")))?;
+        .map_err(|err| format!("Failed to fetch input request details {}", err.join(", ")))?
+        .with_request_method(&api_request.req_method);
 
         match binding {
             GatewayBindingCompiled::FileServer(worker_binding) => internal::get_resolved_binding(
                 worker_binding,
                 &http_request_details,
                 namespace,
+                route,
                 headers,
             )
             .await
@@ -183,6 +242,7 @@ impl<Namespace: Clone + Send + Sync + 'static>
                 worker_binding,
                 &http_request_details,
                 namespace,
+                route,
                 headers,
             )
             .await
@@ -197,18 +257,83 @@ impl<Namespace: Clone + Send + Sync + 'static>
     }
 }
 
+pub(crate) use internal::get_auto_head_route_entry;
+
 mod internal {
     use crate::gateway_binding::{
-        GatewayBindingResolverError, GatewayRequestDetails, ResolvedWorkerBinding,
-        RibInputValueResolver, WorkerBindingCompiled, WorkerDetail,
+        AllowedMethods, GatewayBindingCompiled, GatewayBindingResolverError, GatewayRequestDetails,
+        ResolvedWorkerBinding, RibInputValueResolver, WorkerBindingCompiled, WorkerDetail,
     };
+    use crate::gateway_execution::router::Router;
+    use crate::gateway_request::http_request::router::RouteEntry;
     use golem_common::model::IdempotencyKey;
-    use http::HeaderMap;
+    use http::{HeaderMap, Method};
+
+    fn has_auto_head<Namespace>(route_entry: &RouteEntry<Namespace>) -> bool {
+        match &route_entry.binding {
+            GatewayBindingCompiled::Worker(binding)
+            | GatewayBindingCompiled::FileServer(binding) => binding
+                .middlewares
+                .as_ref()
+                .is_some_and(|middlewares| middlewares.has_auto_head()),
+            GatewayBindingCompiled::Static(_) => false,
+        }
+    }
+
+    // A HEAD request without an explicit route is served by the GET route of the same path,
+    // if that route enabled auto HEAD. The body is stripped by the gateway after execution
+    pub fn get_auto_head_route_entry<'a, Namespace>(
+        router: &'a Router<RouteEntry<Namespace>>,
+        path: &[&str],
+    ) -> Option<&'a RouteEntry<Namespace>> {
+        router
+            .check_path(&Method::GET, path)
+            .filter(|route_entry| has_auto_head(route_entry))
+    }
+
+    // Auto OPTIONS responses are only given if at least one route of the path enabled it
+    pub fn get_auto_options_allowed_methods<Namespace>(
+        router: &Router<RouteEntry<Namespace>>,
+        path: &[&str],
+    ) -> Option<AllowedMethods> {
+        let routes = router.check_path_all_methods(path);
+
+        let enabled = routes
+            .iter()
+            .any(|(_, route_entry)| match &route_entry.binding {
+                GatewayBindingCompiled::Worker(binding)
+                | GatewayBindingCompiled::FileServer(binding) => binding
+                    .middlewares
+                    .as_ref()
+                    .is_some_and(|middlewares| middlewares.has_auto_options()),
+                GatewayBindingCompiled::Static(_) => false,
+            });
+
+        if enabled {
+            let auto_head = routes
+                .iter()
+                .any(|(method, route_entry)| **method == Method::GET && has_auto_head(route_entry));
+
+            let mut methods: Vec<Method> = routes
+                .into_iter()
+                .map(|(method, _)| method.clone())
+                .chain(std::iter::once(Method::OPTIONS))
+                .chain(auto_head.then_some(Method::HEAD))
+                .collect();
+            methods.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+            methods.dedup();
+
+            Some(AllowedMethods(methods))
+        } else {
+            None
+        }
+    }
 
     pub async fn get_resolved_binding<Namespace: Clone>(
         binding: &WorkerBindingCompiled,
         http_request_details: &GatewayRequestDetails,
         namespace: &Namespace,
+        route: &str,
         headers: &HeaderMap,
     ) -> Result<ResolvedWorkerBinding<Namespace>, GatewayBindingResolverError> {
         let worker_name_opt = if let Some(worker_name_compiled) = &binding.worker_name_compiled {
@@ -281,6 +406,7 @@ mod internal {
             compiled_response_mapping: binding.response_compiled.clone(),
             namespace: namespace.clone(),
             middlewares: binding.middlewares.clone().unwrap_or_default(),
+            route: route.to_string(),
         };
 
         Ok(resolved_binding)
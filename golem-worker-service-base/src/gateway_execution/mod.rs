@@ -2,11 +2,15 @@ use golem_common::model::{ComponentId, IdempotencyKey};
 use golem_wasm_rpc::protobuf::type_annotated_value::TypeAnnotatedValue;
 
 pub mod api_definition_lookup;
+pub mod concurrency_limit;
+pub mod conditional_request;
 pub mod file_server_binding_handler;
 pub mod gateway_binding_executor;
 pub mod gateway_binding_resolver;
 mod gateway_worker_request_executor;
 mod http_content_type_mapper;
+pub mod preflight_cache;
+pub mod response_cache;
 pub mod rib_input_value_resolver;
 pub mod router;
 pub mod to_response;
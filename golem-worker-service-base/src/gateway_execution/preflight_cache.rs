@@ -0,0 +1,209 @@
+use std::time::{Duration, Instant};
+
+use golem_common::cache::{BackgroundEvictionMode, Cache, FullCacheEvictionMode, SimpleCache};
+use http::header::{ACCESS_CONTROL_REQUEST_HEADERS, ACCESS_CONTROL_REQUEST_METHOD, HOST, ORIGIN};
+use http::{HeaderMap, StatusCode};
+use poem::Response;
+use tracing::debug;
+
+use crate::app_config::PreflightCacheConfig;
+use crate::gateway_middleware::Cors;
+use crate::gateway_request::http_request::InputHttpRequest;
+
+// Identifies a preflight (OPTIONS) request of a route. The response only depends on
+// these and on the CORS configuration of the route, which is stored with the cached response
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PreflightCacheKey {
+    host: String,
+    path: String,
+    origin: Option<String>,
+    request_method: Option<String>,
+    request_headers: Option<String>,
+}
+
+impl PreflightCacheKey {
+    pub fn from_request(request: &InputHttpRequest) -> Self {
+        PreflightCacheKey {
+            host: header_value(&request.headers, HOST.as_str()).unwrap_or_default(),
+            path: request.input_path.base_path.clone(),
+            origin: header_value(&request.headers, ORIGIN.as_str()),
+            request_method: header_value(&request.headers, ACCESS_CONTROL_REQUEST_METHOD.as_str()),
+            request_headers: header_value(
+                &request.headers,
+                ACCESS_CONTROL_REQUEST_HEADERS.as_str(),
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CachedPreflight {
+    cors: Cors,
+    status: StatusCode,
+    headers: HeaderMap,
+    cached_at: Instant,
+}
+
+// Responses of the cors-preflight bindings, so that the CORS headers are not computed again
+// for every preflight request. A cached response is dropped as soon as the CORS configuration
+// of its route changes (for example by deploying a new version of the API definition)
+pub struct PreflightCache {
+    cache: Cache<PreflightCacheKey, (), CachedPreflight, ()>,
+    time_to_live: Duration,
+}
+
+impl PreflightCache {
+    pub fn new(config: &PreflightCacheConfig) -> Self {
+        Self {
+            cache: Cache::new(
+                Some(config.max_capacity),
+                FullCacheEvictionMode::LeastRecentlyUsed(1),
+                BackgroundEvictionMode::OlderThan {
+                    ttl: config.time_to_live,
+                    period: Duration::from_secs(60),
+                },
+                "gateway_preflight_cache",
+            ),
+            time_to_live: config.time_to_live,
+        }
+    }
+
+    pub fn get(&self, key: &PreflightCacheKey, cors: &Cors) -> Option<Response> {
+        match self.cache.try_get(key) {
+            Some(cached)
+                if cached.cached_at.elapsed() < self.time_to_live && &cached.cors == cors =>
+            {
+                debug!("Serving preflight response for {} from the cache", key.path);
+                let mut response = Response::builder().status(cached.status).finish();
+                response.headers_mut().extend(cached.headers);
+                Some(response)
+            }
+            Some(_) => {
+                self.cache.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    // Stores the computed preflight response, and returns it. Preflight responses have no body
+    pub async fn put(&self, key: &PreflightCacheKey, cors: &Cors, response: Response) -> Response {
+        if !response.status().is_success() {
+            return response;
+        }
+
+        let cached = CachedPreflight {
+            cors: cors.clone(),
+            status: response.status(),
+            headers: response.headers().clone(),
+            cached_at: Instant::now(),
+        };
+
+        let _ = self
+            .cache
+            .get_or_insert_simple(key, || Box::pin(async move { Ok(cached) }))
+            .await;
+
+        response
+    }
+}
+
+fn header_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::*;
+    use crate::gateway_request::http_request::ApiInputPath;
+    use http::header::ACCESS_CONTROL_ALLOW_ORIGIN;
+    use http::{HeaderValue, Method};
+
+    fn request(origin: &str) -> InputHttpRequest {
+        let mut headers = HeaderMap::new();
+        headers.insert(HOST, HeaderValue::from_static("localhost"));
+        headers.insert(ORIGIN, HeaderValue::from_str(origin).unwrap());
+        headers.insert(
+            ACCESS_CONTROL_REQUEST_METHOD,
+            HeaderValue::from_static("POST"),
+        );
+
+        InputHttpRequest {
+            input_path: ApiInputPath {
+                base_path: "/items".to_string(),
+                query_path: None,
+            },
+            headers,
+            req_method: Method::OPTIONS,
+            req_body: serde_json::Value::Null,
+        }
+    }
+
+    fn preflight_response(allow_origin: &str) -> Response {
+        Response::builder()
+            .status(StatusCode::OK)
+            .header(ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin)
+            .finish()
+    }
+
+    fn cache() -> PreflightCache {
+        PreflightCache::new(&PreflightCacheConfig {
+            max_capacity: 16,
+            time_to_live: Duration::from_secs(60),
+        })
+    }
+
+    #[test]
+    async fn preflight_responses_are_cached_per_origin() {
+        let cache = cache();
+        let cors = Cors::default();
+        let key = PreflightCacheKey::from_request(&request("https://a.example.com"));
+
+        assert!(cache.get(&key, &cors).is_none());
+        cache.put(&key, &cors, preflight_response("*")).await;
+
+        let response = cache.get(&key, &cors).unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+            "*"
+        );
+
+        let other_origin = PreflightCacheKey::from_request(&request("https://b.example.com"));
+        assert!(cache.get(&other_origin, &cors).is_none());
+    }
+
+    #[test]
+    async fn changed_cors_configuration_invalidates_the_cached_response() {
+        let cache = cache();
+        let cors = Cors::default();
+        let key = PreflightCacheKey::from_request(&request("https://a.example.com"));
+        cache.put(&key, &cors, preflight_response("*")).await;
+
+        let mut updated_cors = Cors::default();
+        updated_cors
+            .set_allow_origin("https://a.example.com")
+            .unwrap();
+
+        assert!(cache.get(&key, &updated_cors).is_none());
+        assert!(cache.get(&key, &cors).is_none());
+    }
+
+    #[test]
+    async fn expired_preflight_responses_are_not_served() {
+        let cache = PreflightCache::new(&PreflightCacheConfig {
+            max_capacity: 16,
+            time_to_live: Duration::ZERO,
+        });
+        let cors = Cors::default();
+        let key = PreflightCacheKey::from_request(&request("https://a.example.com"));
+        cache.put(&key, &cors, preflight_response("*")).await;
+
+        assert!(cache.get(&key, &cors).is_none());
+    }
+}
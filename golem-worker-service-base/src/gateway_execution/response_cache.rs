@@ -0,0 +1,416 @@
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use golem_common::cache::{BackgroundEvictionMode, Cache, FullCacheEvictionMode, SimpleCache};
+use http::header::{
+    AUTHORIZATION, CACHE_CONTROL, CONTENT_LENGTH, COOKIE, HOST, ORIGIN, SET_COOKIE,
+};
+use http::{HeaderMap, Method, StatusCode};
+use poem::Response;
+use tracing::debug;
+
+use crate::app_config::ResponseCacheConfig;
+use crate::gateway_middleware::CacheConfig;
+use crate::gateway_request::http_request::InputHttpRequest;
+
+// Identifies a cacheable request. Besides the configured query parameters and headers,
+// the origin is always part of the key, as CORS headers of a cached response depend on it,
+// and so is whether the body transform of the route converts the response.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResponseCacheKey {
+    method: Method,
+    host: String,
+    path: String,
+    origin: Option<String>,
+    query_params: Vec<(String, Option<String>)>,
+    headers: Vec<(String, Option<String>)>,
+    transformed: bool,
+}
+
+impl ResponseCacheKey {
+    // Returns None if the request must not be served from (or stored in) the cache
+    pub fn from_request(request: &InputHttpRequest, config: &CacheConfig) -> Option<Self> {
+        if request.req_method != Method::GET && request.req_method != Method::HEAD {
+            return None;
+        }
+
+        if has_cache_directive(&request.headers, "no-store") {
+            return None;
+        }
+
+        // The key does not identify the user, so responses to requests with credentials are
+        // never shared through the cache
+        if request.headers.contains_key(AUTHORIZATION) || request.headers.contains_key(COOKIE) {
+            return None;
+        }
+
+        let query_components = request.input_path.query_components().unwrap_or_default();
+
+        let query_params = config
+            .query_params
+            .iter()
+            .map(|name| (name.clone(), query_components.get(name).cloned()))
+            .collect();
+
+        let headers = config
+            .headers
+            .iter()
+            .map(|name| {
+                let name = name.to_lowercase();
+                let value = header_value(&request.headers, name.as_str());
+                (name, value)
+            })
+            .collect();
+
+        Some(ResponseCacheKey {
+            method: request.req_method.clone(),
+            host: header_value(&request.headers, HOST.as_str()).unwrap_or_default(),
+            path: request.input_path.base_path.clone(),
+            origin: header_value(&request.headers, ORIGIN.as_str()),
+            query_params,
+            headers,
+            transformed: false,
+        })
+    }
+
+    // Keeps the converted and the original representation of a response apart
+    pub fn with_transformed_body(self, transformed: bool) -> Self {
+        ResponseCacheKey {
+            transformed,
+            ..self
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CachedResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+    cached_at: Instant,
+    ttl: Duration,
+}
+
+impl CachedResponse {
+    fn to_response(&self) -> Response {
+        let mut response = Response::builder()
+            .status(self.status)
+            .body(self.body.clone());
+        response.headers_mut().extend(self.headers.clone());
+        response
+    }
+}
+
+// Responses of routes with the response-cache middleware. A response served from
+// here never reaches the worker.
+pub struct ResponseCache {
+    cache: Cache<ResponseCacheKey, (), CachedResponse, ()>,
+}
+
+impl ResponseCache {
+    pub fn new(config: &ResponseCacheConfig) -> Self {
+        let max_capacity = config.max_capacity;
+        Self {
+            cache: Cache::new(
+                Some(max_capacity),
+                FullCacheEvictionMode::LeastRecentlyUsed(1),
+                BackgroundEvictionMode::LeastRecentlyUsed {
+                    count: max_capacity,
+                    period: Duration::from_secs(60),
+                },
+                "gateway_response_cache",
+            ),
+        }
+    }
+
+    pub fn get(&self, key: &ResponseCacheKey) -> Option<Response> {
+        match self.cache.try_get(key) {
+            Some(cached) if cached.cached_at.elapsed() < cached.ttl => {
+                debug!("Serving response for {} from the response cache", key.path);
+                Some(cached.to_response())
+            }
+            Some(_) => {
+                self.cache.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    // Stores the response if it is cacheable, and returns it (rebuilt if its body had to be read)
+    pub async fn put(
+        &self,
+        key: &ResponseCacheKey,
+        config: &CacheConfig,
+        response: Response,
+    ) -> Response {
+        if !is_cacheable(&response, config) {
+            return response;
+        }
+
+        let (parts, body) = response.into_parts();
+
+        let body = match body.into_bytes().await {
+            Ok(body) => body,
+            Err(err) => {
+                return Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(format!("Failed to read response body: {}", err));
+            }
+        };
+
+        let cached = CachedResponse {
+            status: parts.status,
+            headers: parts.headers,
+            body,
+            cached_at: Instant::now(),
+            ttl: Duration::from_secs(config.ttl_seconds),
+        };
+
+        if cached.body.len() as u64 <= config.max_entry_size {
+            let value = cached.clone();
+            let _ = self
+                .cache
+                .get_or_insert_simple(key, || Box::pin(async move { Ok(value) }))
+                .await;
+        }
+
+        cached.to_response()
+    }
+}
+
+// Responses setting cookies, or meant for a single user, are never cached
+fn is_cacheable(response: &Response, config: &CacheConfig) -> bool {
+    if !response.status().is_success()
+        || response.headers().contains_key(SET_COOKIE)
+        || has_cache_directive(response.headers(), "no-store")
+        || has_cache_directive(response.headers(), "private")
+    {
+        return false;
+    }
+
+    // Avoid buffering bodies that are known to be too large up front
+    let content_length = header_value(response.headers(), CONTENT_LENGTH.as_str())
+        .and_then(|length| length.parse::<u64>().ok());
+
+    match content_length {
+        Some(length) => length <= config.max_entry_size,
+        None => true,
+    }
+}
+
+// Whether Cache-Control has the directive, with or without an argument (as in private="Set-Cookie")
+fn has_cache_directive(headers: &HeaderMap, name: &str) -> bool {
+    headers.get_all(CACHE_CONTROL).iter().any(|value| {
+        value.to_str().is_ok_and(|value| {
+            value.split(',').any(|directive| {
+                let directive = directive.trim();
+                let directive_name = directive
+                    .split_once('=')
+                    .map_or(directive, |(directive_name, _)| directive_name);
+                directive_name.trim().eq_ignore_ascii_case(name)
+            })
+        })
+    })
+}
+
+fn header_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::*;
+    use crate::gateway_request::http_request::ApiInputPath;
+    use http::HeaderValue;
+
+    fn config() -> CacheConfig {
+        CacheConfig {
+            ttl_seconds: 60,
+            max_entry_size: 16,
+            query_params: vec!["page".to_string()],
+            headers: vec!["Accept-Language".to_string()],
+        }
+    }
+
+    fn request(method: Method, query: Option<&str>, headers: &[(&str, &str)]) -> InputHttpRequest {
+        let mut header_map = HeaderMap::new();
+        header_map.insert(HOST, HeaderValue::from_static("localhost"));
+        for (name, value) in headers {
+            header_map.insert(
+                http::HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                HeaderValue::from_str(value).unwrap(),
+            );
+        }
+
+        InputHttpRequest {
+            input_path: ApiInputPath {
+                base_path: "/items".to_string(),
+                query_path: query.map(|q| q.to_string()),
+            },
+            headers: header_map,
+            req_method: method,
+            req_body: serde_json::Value::Null,
+        }
+    }
+
+    #[test]
+    fn only_safe_methods_are_cached() {
+        let config = config();
+
+        assert!(
+            ResponseCacheKey::from_request(&request(Method::GET, None, &[]), &config).is_some()
+        );
+        assert!(
+            ResponseCacheKey::from_request(&request(Method::HEAD, None, &[]), &config).is_some()
+        );
+        assert!(
+            ResponseCacheKey::from_request(&request(Method::POST, None, &[]), &config).is_none()
+        );
+    }
+
+    #[test]
+    fn request_no_store_bypasses_cache() {
+        let request = request(
+            Method::GET,
+            None,
+            &[("cache-control", "max-age=0, no-store")],
+        );
+
+        assert!(ResponseCacheKey::from_request(&request, &config()).is_none());
+    }
+
+    #[test]
+    fn key_includes_only_selected_query_params_and_headers() {
+        let config = config();
+
+        let key = |query, headers| {
+            ResponseCacheKey::from_request(&request(Method::GET, query, headers), &config).unwrap()
+        };
+
+        assert_eq!(
+            key(Some("page=1&sort=asc"), &[("x-request-id", "a")]),
+            key(Some("page=1&sort=desc"), &[("x-request-id", "b")])
+        );
+        assert_ne!(key(Some("page=1"), &[]), key(Some("page=2"), &[]));
+        assert_ne!(
+            key(None, &[("accept-language", "en")]),
+            key(None, &[("accept-language", "de")])
+        );
+    }
+
+    #[test]
+    fn key_includes_whether_the_body_is_transformed() {
+        let key =
+            ResponseCacheKey::from_request(&request(Method::GET, None, &[]), &config()).unwrap();
+
+        assert_ne!(
+            key.clone().with_transformed_body(true),
+            key.clone().with_transformed_body(false)
+        );
+        assert_eq!(key.clone().with_transformed_body(false), key);
+    }
+
+    #[test]
+    async fn cached_response_is_served_until_ttl_expires() {
+        let cache = ResponseCache::new(&ResponseCacheConfig { max_capacity: 16 });
+        let key =
+            ResponseCacheKey::from_request(&request(Method::GET, None, &[]), &config()).unwrap();
+
+        let response = cache
+            .put(&key, &config(), Response::builder().body("hello"))
+            .await;
+        assert_eq!(response.into_body().into_string().await.unwrap(), "hello");
+
+        let cached = cache.get(&key).unwrap();
+        assert_eq!(cached.into_body().into_string().await.unwrap(), "hello");
+
+        let expired = CacheConfig {
+            ttl_seconds: 0,
+            ..config()
+        };
+        let other_key =
+            ResponseCacheKey::from_request(&request(Method::HEAD, None, &[]), &config()).unwrap();
+        let _ = cache
+            .put(&other_key, &expired, Response::builder().body("hello"))
+            .await;
+        assert!(cache.get(&other_key).is_none());
+    }
+
+    #[test]
+    async fn uncacheable_responses_are_not_stored() {
+        let cache = ResponseCache::new(&ResponseCacheConfig { max_capacity: 16 });
+        let config = config();
+        let key = |query| {
+            ResponseCacheKey::from_request(&request(Method::GET, Some(query), &[]), &config)
+                .unwrap()
+        };
+
+        let _ = cache
+            .put(
+                &key("page=1"),
+                &config,
+                Response::builder()
+                    .status(StatusCode::NOT_FOUND)
+                    .body("missing"),
+            )
+            .await;
+        let _ = cache
+            .put(
+                &key("page=2"),
+                &config,
+                Response::builder()
+                    .header(CACHE_CONTROL, "no-store")
+                    .body("private"),
+            )
+            .await;
+        let _ = cache
+            .put(
+                &key("page=3"),
+                &config,
+                Response::builder().body("a body longer than the maximum entry size"),
+            )
+            .await;
+
+        let _ = cache
+            .put(
+                &key("page=4"),
+                &config,
+                Response::builder()
+                    .header(SET_COOKIE, "session=abc")
+                    .body("session"),
+            )
+            .await;
+        let _ = cache
+            .put(
+                &key("page=5"),
+                &config,
+                Response::builder()
+                    .header(CACHE_CONTROL, "private=\"x-user\", max-age=60")
+                    .body("private"),
+            )
+            .await;
+
+        assert!(cache.get(&key("page=1")).is_none());
+        assert!(cache.get(&key("page=2")).is_none());
+        assert!(cache.get(&key("page=3")).is_none());
+        assert!(cache.get(&key("page=4")).is_none());
+        assert!(cache.get(&key("page=5")).is_none());
+    }
+
+    #[test]
+    fn requests_with_credentials_bypass_cache() {
+        let config = config();
+        let key =
+            |headers| ResponseCacheKey::from_request(&request(Method::GET, None, headers), &config);
+
+        assert!(key(&[("authorization", "Bearer token")]).is_none());
+        assert!(key(&[("cookie", "session=abc")]).is_none());
+        assert!(key(&[("cache-control", "max-age=60")]).is_some());
+    }
+}
@@ -33,6 +33,14 @@ impl<T> Router<T> {
         let result = node.matches(path)?;
         Some(result)
     }
+
+    /// Returns the route data of every method that has a route matching the given path.
+    pub fn check_path_all_methods(&self, path: &[&str]) -> Vec<(&Method, &T)> {
+        self.tree
+            .iter()
+            .filter_map(|(method, node)| node.matches(path).map(|data| (method, data)))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -60,4 +68,23 @@ mod tests {
 
         assert_eq!(router.check_path(&Method::POST, &["api"]), Some(&1));
     }
+
+    #[test]
+    fn test_router_all_methods() {
+        let mut router = Router::new();
+
+        router.add_route(Method::GET, vec![RouterPattern::literal("test")], 1);
+        router.add_route(Method::POST, vec![RouterPattern::literal("test")], 2);
+        router.add_route(
+            Method::PUT,
+            vec![RouterPattern::literal("test"), RouterPattern::Variable],
+            3,
+        );
+
+        let mut result = router.check_path_all_methods(&["test"]);
+        result.sort_by_key(|(_, data)| **data);
+
+        assert_eq!(result, vec![(&Method::GET, &1), (&Method::POST, &2)]);
+        assert!(router.check_path_all_methods(&["api"]).is_empty());
+    }
 }
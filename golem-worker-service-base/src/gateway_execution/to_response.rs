@@ -1,9 +1,9 @@
 use crate::api::WorkerApiBaseError;
-use crate::gateway_binding::{GatewayRequestDetails, RibInputTypeMismatch};
+use crate::gateway_binding::{AllowedMethods, GatewayRequestDetails, RibInputTypeMismatch};
 use crate::gateway_execution::file_server_binding_handler::{
     FileServerBindingError, FileServerBindingResult,
 };
-use crate::gateway_middleware::{Cors as CorsPreflight, Middlewares};
+use crate::gateway_middleware::{insert_cors_header, Cors as CorsPreflight, Middlewares};
 use crate::gateway_rib_interpreter::EvaluationError;
 use http::header::*;
 use http::StatusCode;
@@ -18,7 +18,7 @@ pub trait ToResponse<A> {
 impl ToResponse<poem::Response> for FileServerBindingResult {
     fn to_response(
         self,
-        _request_details: &GatewayRequestDetails,
+        request_details: &GatewayRequestDetails,
         middlewares: &Middlewares,
     ) -> poem::Response {
         let mut response = match self {
@@ -37,51 +37,97 @@ impl ToResponse<poem::Response> for FileServerBindingResult {
             }
         };
 
-        middlewares.transform_http_response(&mut response);
+        middlewares.transform_http_response(request_details, &mut response);
         response
     }
 }
 
-// Preflight (OPTIONS) response that will consist of all configured CORS headers
-impl ToResponse<poem::Response> for CorsPreflight {
+// Auto OPTIONS response listing the methods that have a route for the requested path
+impl ToResponse<poem::Response> for AllowedMethods {
     fn to_response(
         self,
         _request_details: &GatewayRequestDetails,
         _middlewares: &Middlewares,
+    ) -> poem::Response {
+        let mut response = poem::Response::builder()
+            .status(StatusCode::NO_CONTENT)
+            .finish();
+
+        response
+            .headers_mut()
+            .insert(ALLOW, self.to_header_value().parse().unwrap());
+
+        response
+    }
+}
+
+// Preflight (OPTIONS) response that will consist of all configured CORS headers. If there is
+// a policy for the requested method, its origins and headers are used instead, and an origin
+// not allowed by it gets no CORS headers, rejecting the preflight. Otherwise the methods with
+// a policy are not listed as allowed. The allowed headers may be reflected from
+// Access-Control-Request-Headers instead
+impl ToResponse<poem::Response> for CorsPreflight {
+    fn to_response(
+        self,
+        request_details: &GatewayRequestDetails,
+        _middlewares: &Middlewares,
     ) -> poem::Response {
         let mut response = poem::Response::builder().status(StatusCode::OK).finish();
 
-        response.headers_mut().insert(
-            ACCESS_CONTROL_ALLOW_ORIGIN,
-            self.get_allow_origin().clone().parse().unwrap(),
-        );
-        response.headers_mut().insert(
-            ACCESS_CONTROL_ALLOW_METHODS,
-            self.get_allow_methods().clone().parse().unwrap(),
-        );
-        response.headers_mut().insert(
-            ACCESS_CONTROL_ALLOW_HEADERS,
-            self.get_allow_headers().clone().parse().unwrap(),
-        );
+        let (origin, request_method, request_headers) = match request_details {
+            GatewayRequestDetails::Http(http) => (
+                http.get_origin_header(),
+                http.get_access_control_request_method_header(),
+                http.get_access_control_request_headers_header(),
+            ),
+        };
 
-        if let Some(expose_headers) = &self.get_expose_headers() {
-            response.headers_mut().insert(
-                ACCESS_CONTROL_EXPOSE_HEADERS,
-                expose_headers.clone().parse().unwrap(),
-            );
+        if !self.allows_origin(origin.as_deref()) {
+            return response;
+        }
+
+        let (allow_origin, allow_methods, allow_headers) =
+            match self.get_method_policy(request_method.as_deref()) {
+                Some(policy) => match policy.get_allow_origin_for(origin.as_deref()) {
+                    Some(allow_origin) => (
+                        allow_origin,
+                        policy.method.clone(),
+                        policy
+                            .allow_headers
+                            .clone()
+                            .unwrap_or_else(|| self.get_allow_headers()),
+                    ),
+                    None => return response,
+                },
+                None => (
+                    self.get_allow_origin_for(origin.as_deref()),
+                    self.get_allow_methods_without_policies(),
+                    self.get_allow_headers(),
+                ),
+            };
+
+        insert_cors_header(&mut response, ACCESS_CONTROL_ALLOW_ORIGIN, &allow_origin);
+        insert_cors_header(&mut response, ACCESS_CONTROL_ALLOW_METHODS, &allow_methods);
+        if let Some(allow_headers) =
+            self.get_allow_headers_for(allow_headers, request_headers.as_deref())
+        {
+            insert_cors_header(&mut response, ACCESS_CONTROL_ALLOW_HEADERS, &allow_headers);
+        }
+
+        if let Some(expose_headers) = &self.get_expose_headers_for_origin(origin.as_deref()) {
+            insert_cors_header(&mut response, ACCESS_CONTROL_EXPOSE_HEADERS, expose_headers);
         }
 
         if let Some(allow_credentials) = self.get_allow_credentials() {
-            response.headers_mut().insert(
+            insert_cors_header(
+                &mut response,
                 ACCESS_CONTROL_ALLOW_CREDENTIALS,
-                allow_credentials.to_string().parse().unwrap(),
+                &allow_credentials.to_string(),
             );
         }
 
         if let Some(max_age) = self.get_max_age() {
-            response
-                .headers_mut()
-                .insert(ACCESS_CONTROL_MAX_AGE, max_age.to_string().parse().unwrap());
+            insert_cors_header(&mut response, ACCESS_CONTROL_MAX_AGE, &max_age.to_string());
         }
 
         response
@@ -111,14 +157,14 @@ impl ToResponse<poem::Response> for RibResult {
 impl ToResponse<poem::Response> for RibInputTypeMismatch {
     fn to_response(
         self,
-        _request_details: &GatewayRequestDetails,
+        request_details: &GatewayRequestDetails,
         middlewares: &Middlewares,
     ) -> poem::Response {
         let mut response = poem::Response::builder()
             .status(StatusCode::BAD_REQUEST)
             .body(Body::from_string(format!("Error {}", self.0).to_string()));
 
-        middlewares.transform_http_response(&mut response);
+        middlewares.transform_http_response(request_details, &mut response);
         response
     }
 }
@@ -126,14 +172,14 @@ impl ToResponse<poem::Response> for RibInputTypeMismatch {
 impl ToResponse<poem::Response> for EvaluationError {
     fn to_response(
         self,
-        _request_details: &GatewayRequestDetails,
+        request_details: &GatewayRequestDetails,
         middlewares: &Middlewares,
     ) -> poem::Response {
         let mut response = poem::Response::builder()
             .status(StatusCode::INTERNAL_SERVER_ERROR)
             .body(Body::from_string(format!("Error {}", self).to_string()));
 
-        middlewares.transform_http_response(&mut response);
+        middlewares.transform_http_response(request_details, &mut response);
 
         response
     }
@@ -142,14 +188,14 @@ impl ToResponse<poem::Response> for EvaluationError {
 impl ToResponse<poem::Response> for String {
     fn to_response(
         self,
-        _request_details: &GatewayRequestDetails,
+        request_details: &GatewayRequestDetails,
         middlewares: &Middlewares,
     ) -> poem::Response {
         let mut response = poem::Response::builder()
             .status(StatusCode::INTERNAL_SERVER_ERROR)
             .body(Body::from_string(self.to_string()));
 
-        middlewares.transform_http_response(&mut response);
+        middlewares.transform_http_response(request_details, &mut response);
         response
     }
 }
@@ -252,7 +298,7 @@ mod internal {
                 }
             };
 
-            middleware.transform_http_response(&mut response);
+            middleware.transform_http_response(request_details, &mut response);
             response
         }
     }
@@ -265,12 +311,20 @@ mod test {
     use golem_wasm_rpc::protobuf::{NameTypePair, NameValuePair, TypedRecord};
     use test_r::test;
 
-    use crate::gateway_binding::{GatewayRequestDetails, HttpRequestDetails};
+    use crate::gateway_binding::{GatewayRequestDetails, HttpRequestDetails, StaticBinding};
+    use crate::gateway_execution::gateway_binding_resolver::{
+        ResolvedBinding, ResolvedGatewayBinding,
+    };
     use crate::gateway_execution::to_response::ToResponse;
-    use crate::gateway_middleware::Middlewares;
-    use http::header::CONTENT_TYPE;
-    use http::StatusCode;
+    use crate::gateway_middleware::{Cors, CorsMethodPolicy, Middlewares, OriginPredicate};
+    use http::header::{
+        ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN,
+        ACCESS_CONTROL_REQUEST_HEADERS, ACCESS_CONTROL_REQUEST_METHOD, CONTENT_TYPE, ORIGIN,
+    };
+    use http::{HeaderMap, HeaderValue, StatusCode};
     use rib::RibResult;
+    use std::collections::HashMap;
+    use std::sync::Arc;
 
     fn create_record(values: Vec<(String, TypeAnnotatedValue)>) -> TypeAnnotatedValue {
         let mut name_type_pairs = vec![];
@@ -339,6 +393,146 @@ mod test {
         assert_eq!(status, expected_status);
     }
 
+    fn preflight_request(origin: &str, method: &str) -> GatewayRequestDetails {
+        let mut headers = HeaderMap::new();
+        headers.insert(ORIGIN, HeaderValue::from_str(origin).unwrap());
+        headers.insert(
+            ACCESS_CONTROL_REQUEST_METHOD,
+            HeaderValue::from_str(method).unwrap(),
+        );
+        GatewayRequestDetails::from(
+            &HashMap::new(),
+            &HashMap::new(),
+            &[],
+            &serde_json::Value::Null,
+            &headers,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn preflight_reflects_origins_allowed_by_the_resolved_predicate() {
+        let request = preflight_request("https://acme.tenant.example.com", "GET");
+        let mut cors = Cors::default();
+        cors.set_allow_origin("https://app.example.com").unwrap();
+
+        let mut binding = ResolvedGatewayBinding::<()>::from_static_binding(
+            &request,
+            &StaticBinding::from_http_cors(cors),
+        );
+        binding.set_allow_origin_predicate(&OriginPredicate::new(Arc::new(|origin: &str| {
+            origin.ends_with(".tenant.example.com")
+        })));
+        let ResolvedBinding::Static(StaticBinding::HttpCorsPreflight(cors)) =
+            binding.resolved_binding
+        else {
+            panic!("Expected a preflight binding")
+        };
+
+        let response = cors.to_response(&request, &Middlewares::default());
+        assert_eq!(
+            response.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+            "https://acme.tenant.example.com"
+        );
+    }
+
+    fn cors_with_delete_policy() -> Cors {
+        let mut cors = Cors::default();
+        cors.set_method_policies(vec![CorsMethodPolicy {
+            method: "DELETE".to_string(),
+            allow_origins: vec!["https://admin.example.com".to_string()],
+            allow_headers: Some("Authorization".to_string()),
+        }])
+        .unwrap();
+        cors
+    }
+
+    #[test]
+    fn preflight_of_allowed_method() {
+        let response = cors_with_delete_policy().to_response(
+            &preflight_request("https://admin.example.com", "DELETE"),
+            &Middlewares::default(),
+        );
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let headers = response.headers();
+        assert_eq!(
+            headers.get(ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+            "https://admin.example.com"
+        );
+        assert_eq!(headers.get(ACCESS_CONTROL_ALLOW_METHODS).unwrap(), "DELETE");
+        assert_eq!(
+            headers.get(ACCESS_CONTROL_ALLOW_HEADERS).unwrap(),
+            "Authorization"
+        );
+    }
+
+    #[test]
+    fn preflight_of_method_not_allowed_for_the_origin() {
+        let response = cors_with_delete_policy().to_response(
+            &preflight_request("https://app.example.com", "DELETE"),
+            &Middlewares::default(),
+        );
+
+        let headers = response.headers();
+        assert!(!headers.contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+        assert!(!headers.contains_key(ACCESS_CONTROL_ALLOW_METHODS));
+        assert!(!headers.contains_key(ACCESS_CONTROL_ALLOW_HEADERS));
+    }
+
+    #[test]
+    fn preflight_of_method_without_policy() {
+        let response = cors_with_delete_policy().to_response(
+            &preflight_request("https://app.example.com", "GET"),
+            &Middlewares::default(),
+        );
+
+        let headers = response.headers();
+        assert_eq!(headers.get(ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(), "*");
+        assert_eq!(
+            headers.get(ACCESS_CONTROL_ALLOW_METHODS).unwrap(),
+            "GET, POST, PUT, OPTIONS"
+        );
+    }
+
+    #[test]
+    fn preflight_reflecting_request_headers() {
+        let mut cors = cors_with_delete_policy();
+        cors.set_reflect_request_headers(true);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            ORIGIN,
+            HeaderValue::from_static("https://admin.example.com"),
+        );
+        headers.insert(
+            ACCESS_CONTROL_REQUEST_METHOD,
+            HeaderValue::from_static("DELETE"),
+        );
+        headers.insert(
+            ACCESS_CONTROL_REQUEST_HEADERS,
+            HeaderValue::from_static("authorization, x-custom"),
+        );
+        let request = GatewayRequestDetails::from(
+            &HashMap::new(),
+            &HashMap::new(),
+            &[],
+            &serde_json::Value::Null,
+            &headers,
+        )
+        .unwrap();
+
+        let response = cors.to_response(&request, &Middlewares::default());
+
+        assert_eq!(
+            response
+                .headers()
+                .get(ACCESS_CONTROL_ALLOW_HEADERS)
+                .unwrap(),
+            "authorization"
+        );
+    }
+
     #[test]
     async fn test_evaluation_result_to_response_with_no_http_specifics() {
         let evaluation_result: RibResult =
@@ -0,0 +1,727 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use futures_util::StreamExt;
+use http::header::{ACCEPT, CONTENT_LENGTH, CONTENT_TYPE, VARY};
+use http::{HeaderMap, HeaderValue, StatusCode};
+use poem::Body;
+use poem_openapi::{Enum, Object};
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tracing::debug;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct BodyTransformConfig {
+    pub transformer: BodyTransformerKind,
+    // Media types of the bodies to convert, such as `application/xml`. Converted responses
+    // get the first one as their content type
+    pub content_types: Vec<String>,
+    // Bodies are buffered for the conversion up to this many bytes. Larger requests are rejected
+    // with 413 Payload Too Large, and larger responses are passed on without conversion
+    pub max_body_size: u64,
+}
+
+// The built-in body transformers, each selecting an implementation of `BodyTransformer`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "kebab-case")]
+#[oai(rename_all = "kebab-case")]
+pub enum BodyTransformerKind {
+    XmlJson,
+}
+
+impl BodyTransformerKind {
+    pub fn transformer(&self) -> &'static (dyn BodyTransformer + Send + Sync) {
+        match self {
+            BodyTransformerKind::XmlJson => &XmlJsonTransformer,
+        }
+    }
+}
+
+impl Display for BodyTransformerKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BodyTransformerKind::XmlJson => write!(f, "xml-json"),
+        }
+    }
+}
+
+impl FromStr for BodyTransformerKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "xml-json" => Ok(BodyTransformerKind::XmlJson),
+            _ => Err(format!("Unknown body transformer {s}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum BodyTransformConfigError {
+    #[error("A body transform needs at least one content type")]
+    NoContentTypes,
+    #[error("Invalid content type {value:?} of the body transform")]
+    InvalidContentType { value: String },
+    #[error("The maximum body size of a body transform must be greater than 0")]
+    ZeroMaxBodySize,
+}
+
+// Converts request bodies of another format to the JSON the worker expects, and the JSON
+// responses of the worker back to that format
+pub trait BodyTransformer {
+    fn to_json(&self, body: &[u8]) -> Result<Value, String>;
+
+    fn from_json(&self, value: &Value) -> Result<Vec<u8>, String>;
+}
+
+impl BodyTransformConfig {
+    pub fn transformer(&self) -> &'static (dyn BodyTransformer + Send + Sync) {
+        self.transformer.transformer()
+    }
+
+    pub fn validate(&self) -> Result<(), BodyTransformConfigError> {
+        if self.content_types.is_empty() {
+            return Err(BodyTransformConfigError::NoContentTypes);
+        }
+
+        if let Some(invalid) = self
+            .content_types
+            .iter()
+            .find(|content_type| http::HeaderValue::from_str(content_type).is_err())
+        {
+            return Err(BodyTransformConfigError::InvalidContentType {
+                value: invalid.clone(),
+            });
+        }
+
+        if self.max_body_size == 0 {
+            return Err(BodyTransformConfigError::ZeroMaxBodySize);
+        }
+
+        Ok(())
+    }
+
+    // Whether the request body has one of the configured content types
+    pub fn applies_to_request(&self, headers: &HeaderMap) -> bool {
+        headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|content_type| self.matches(content_type))
+    }
+
+    // Whether the client sent or accepts one of the configured content types, and so
+    // expects the JSON response of the worker to be converted
+    pub fn applies_to_response(&self, request_headers: &HeaderMap) -> bool {
+        self.applies_to_request(request_headers)
+            || request_headers
+                .get_all(ACCEPT)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .flat_map(|value| value.split(','))
+                .any(|media_type| self.matches(media_type))
+    }
+
+    // Reads and converts the request body, or returns the response rejecting the request
+    pub async fn transform_request_body(
+        &self,
+        headers: &HeaderMap,
+        body: Body,
+    ) -> Result<Value, poem::Response> {
+        let content_length = headers
+            .get(CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok());
+
+        if content_length.is_some_and(|length| length > self.max_body_size) {
+            return Err(self.too_large());
+        }
+
+        // Read chunk by chunk, so that a body without a Content-Length is not buffered beyond
+        // the limit either
+        let mut bytes = Vec::new();
+        let mut stream = body.into_bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|err| {
+                poem::Response::builder()
+                    .status(StatusCode::BAD_REQUEST)
+                    .body(format!("Failed to read request body: {err}"))
+            })?;
+            if (bytes.len() + chunk.len()) as u64 > self.max_body_size {
+                return Err(self.too_large());
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        self.transformer().to_json(&bytes).map_err(|err| {
+            poem::Response::builder()
+                .status(StatusCode::BAD_REQUEST)
+                .body(format!("Failed to convert request body: {err}"))
+        })
+    }
+
+    // Converts a JSON response. Other responses, and the ones too large to buffer, are
+    // returned unchanged (rebuilt if their body had to be read)
+    pub async fn transform_response(&self, response: poem::Response) -> poem::Response {
+        if !response.content_type().is_some_and(is_json) {
+            return response;
+        }
+
+        let content_length = response
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok());
+
+        if content_length.is_some_and(|length| length > self.max_body_size) {
+            return response;
+        }
+
+        let (mut parts, body) = response.into_parts();
+
+        // Read chunk by chunk, so that a body without a Content-Length is not buffered beyond
+        // the limit. A larger body is passed on with the chunks read so far put back in front
+        let mut bytes = Vec::new();
+        let mut stream = body.into_bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(err) => {
+                    return poem::Response::builder()
+                        .status(StatusCode::INTERNAL_SERVER_ERROR)
+                        .body(format!("Failed to read response body: {}", err));
+                }
+            };
+            if (bytes.len() + chunk.len()) as u64 > self.max_body_size {
+                let read = futures_util::stream::iter([Ok(bytes::Bytes::from(bytes)), Ok(chunk)]);
+                return poem::Response::from_parts(
+                    parts,
+                    Body::from_bytes_stream(read.chain(stream)),
+                );
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        let converted = serde_json::from_slice(&bytes)
+            .map_err(|err| err.to_string())
+            .and_then(|value| self.transformer().from_json(&value))
+            .inspect_err(|err| debug!("Not converting the response body: {}", err))
+            .ok();
+
+        match (converted, self.content_types.first()) {
+            (Some(converted), Some(content_type)) => match content_type.parse() {
+                Ok(content_type) => {
+                    parts.headers.insert(CONTENT_TYPE, content_type);
+                    parts.headers.remove(CONTENT_LENGTH);
+                    poem::Response::from_parts(parts, Body::from(converted))
+                }
+                Err(_) => poem::Response::from_parts(parts, Body::from(bytes)),
+            },
+            _ => poem::Response::from_parts(parts, Body::from(bytes)),
+        }
+    }
+
+    // Responses of routes with a body transform depend on the content type and the accepted
+    // types of the request, which caches in between must take into account
+    pub fn add_vary(&self, headers: &mut HeaderMap) {
+        headers.append(VARY, HeaderValue::from_static("Accept, Content-Type"));
+    }
+
+    fn matches(&self, media_type: &str) -> bool {
+        let media_type = essence(media_type);
+        self.content_types
+            .iter()
+            .any(|content_type| essence(content_type).eq_ignore_ascii_case(media_type))
+    }
+
+    fn too_large(&self) -> poem::Response {
+        poem::Response::builder()
+            .status(StatusCode::PAYLOAD_TOO_LARGE)
+            .body(format!(
+                "Request body too large to convert, the limit is {} bytes",
+                self.max_body_size
+            ))
+    }
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::apidefinition::BodyTransform> for BodyTransformConfig {
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::apidefinition::BodyTransform,
+    ) -> Result<Self, Self::Error> {
+        Ok(BodyTransformConfig {
+            transformer: value.transformer.parse()?,
+            content_types: value.content_types,
+            max_body_size: value.max_body_size,
+        })
+    }
+}
+
+impl From<BodyTransformConfig> for golem_api_grpc::proto::golem::apidefinition::BodyTransform {
+    fn from(value: BodyTransformConfig) -> Self {
+        golem_api_grpc::proto::golem::apidefinition::BodyTransform {
+            transformer: value.transformer.to_string(),
+            content_types: value.content_types,
+            max_body_size: value.max_body_size,
+        }
+    }
+}
+
+// The media type without its parameters, such as `application/xml` for `application/xml; charset=utf-8`
+fn essence(media_type: &str) -> &str {
+    media_type.split(';').next().unwrap_or_default().trim()
+}
+
+fn is_json(content_type: &str) -> bool {
+    let media_type = essence(content_type).to_ascii_lowercase();
+    media_type == "application/json" || media_type.ends_with("+json")
+}
+
+// Converts between XML and JSON with the usual conventions: the root element becomes an object
+// with a single field named after it, child elements become fields (repeated ones an array),
+// attributes become fields prefixed with `@`, and the text of elements that have child elements
+// or attributes becomes the `#text` field. Text is always kept as a string.
+//
+// JSON values without a single root field (such as arrays) are written in a `response` element
+pub struct XmlJsonTransformer;
+
+const TEXT_FIELD: &str = "#text";
+const ATTRIBUTE_PREFIX: char = '@';
+const DEFAULT_ROOT: &str = "response";
+
+impl BodyTransformer for XmlJsonTransformer {
+    fn to_json(&self, body: &[u8]) -> Result<Value, String> {
+        let mut reader = Reader::from_reader(body);
+        reader.config_mut().trim_text(true);
+
+        let mut open: Vec<XmlElement> = Vec::new();
+        let mut root: Option<(String, Value)> = None;
+
+        loop {
+            match reader
+                .read_event()
+                .map_err(|err| format!("Invalid XML: {err}"))?
+            {
+                Event::Start(start) => open.push(XmlElement::new(&start)?),
+                Event::Empty(start) => {
+                    XmlElement::new(&start)?.close(&mut open, &mut root)?;
+                }
+                Event::End(_) => {
+                    let element = open.pop().ok_or("Unexpected closing tag")?;
+                    element.close(&mut open, &mut root)?;
+                }
+                Event::Text(text) => {
+                    if let Some(element) = open.last_mut() {
+                        let text = text
+                            .unescape()
+                            .map_err(|err| format!("Invalid XML: {err}"))?;
+                        element.text.push_str(&text);
+                    }
+                }
+                Event::CData(data) => {
+                    if let Some(element) = open.last_mut() {
+                        let text = std::str::from_utf8(&data)
+                            .map_err(|err| format!("Invalid XML: {err}"))?;
+                        element.text.push_str(text);
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        if !open.is_empty() {
+            return Err("Invalid XML: unclosed element".to_string());
+        }
+
+        let (name, value) = root.ok_or("Invalid XML: no root element")?;
+        Ok(Value::Object(Map::from_iter([(name, value)])))
+    }
+
+    fn from_json(&self, value: &Value) -> Result<Vec<u8>, String> {
+        let mut writer = Writer::new(Vec::new());
+
+        match value {
+            Value::Object(fields) if fields.len() == 1 => {
+                let (name, value) = fields.iter().next().unwrap();
+                if value.is_array() || name.starts_with(ATTRIBUTE_PREFIX) || name == TEXT_FIELD {
+                    write_element(&mut writer, DEFAULT_ROOT, value)?;
+                } else {
+                    write_element(&mut writer, name, value)?;
+                }
+            }
+            _ => write_element(&mut writer, DEFAULT_ROOT, value)?,
+        }
+
+        Ok(writer.into_inner())
+    }
+}
+
+struct XmlElement {
+    name: String,
+    fields: Map<String, Value>,
+    text: String,
+}
+
+impl XmlElement {
+    fn new(start: &BytesStart) -> Result<Self, String> {
+        let name = std::str::from_utf8(start.name().as_ref())
+            .map_err(|err| format!("Invalid XML: {err}"))?
+            .to_string();
+
+        let mut fields = Map::new();
+        for attribute in start.attributes() {
+            let attribute = attribute.map_err(|err| format!("Invalid XML: {err}"))?;
+            let key = std::str::from_utf8(attribute.key.as_ref())
+                .map_err(|err| format!("Invalid XML: {err}"))?;
+            let value = attribute
+                .unescape_value()
+                .map_err(|err| format!("Invalid XML: {err}"))?;
+            fields.insert(
+                format!("{ATTRIBUTE_PREFIX}{key}"),
+                Value::String(value.to_string()),
+            );
+        }
+
+        Ok(XmlElement {
+            name,
+            fields,
+            text: String::new(),
+        })
+    }
+
+    // Adds the element to its parent, or makes it the root if it has none
+    fn close(
+        self,
+        open: &mut [XmlElement],
+        root: &mut Option<(String, Value)>,
+    ) -> Result<(), String> {
+        let XmlElement {
+            name,
+            mut fields,
+            text,
+        } = self;
+
+        let value = if fields.is_empty() {
+            if text.is_empty() {
+                Value::Null
+            } else {
+                Value::String(text)
+            }
+        } else {
+            if !text.is_empty() {
+                fields.insert(TEXT_FIELD.to_string(), Value::String(text));
+            }
+            Value::Object(fields)
+        };
+
+        match open.last_mut() {
+            Some(parent) => {
+                match parent.fields.get_mut(&name) {
+                    Some(Value::Array(values)) => values.push(value),
+                    Some(existing) => *existing = Value::Array(vec![existing.take(), value]),
+                    None => {
+                        parent.fields.insert(name, value);
+                    }
+                }
+                Ok(())
+            }
+            None if root.is_some() => Err("Invalid XML: multiple root elements".to_string()),
+            None => {
+                *root = Some((name, value));
+                Ok(())
+            }
+        }
+    }
+}
+
+fn write_element(writer: &mut Writer<Vec<u8>>, name: &str, value: &Value) -> Result<(), String> {
+    check_name(name)?;
+    match value {
+        // Arrays are written as repeated elements
+        Value::Array(values) => {
+            for value in values {
+                write_element(writer, name, value)?;
+            }
+        }
+        Value::Object(fields) => {
+            let mut start = BytesStart::new(name);
+            for (key, value) in fields {
+                if let Some(attribute) = key.strip_prefix(ATTRIBUTE_PREFIX) {
+                    check_name(attribute)?;
+                    start.push_attribute((attribute, text_of(value).as_str()));
+                }
+            }
+            write_event(writer, Event::Start(start))?;
+            for (key, value) in fields {
+                if key == TEXT_FIELD {
+                    write_event(writer, Event::Text(BytesText::new(&text_of(value))))?;
+                } else if !key.starts_with(ATTRIBUTE_PREFIX) {
+                    write_element(writer, key, value)?;
+                }
+            }
+            write_event(writer, Event::End(BytesEnd::new(name)))?;
+        }
+        Value::Null => write_event(writer, Event::Empty(BytesStart::new(name)))?,
+        _ => {
+            write_event(writer, Event::Start(BytesStart::new(name)))?;
+            write_event(writer, Event::Text(BytesText::new(&text_of(value))))?;
+            write_event(writer, Event::End(BytesEnd::new(name)))?;
+        }
+    }
+    Ok(())
+}
+
+// JSON keys become element and attribute names, which are written as they are. Only names
+// starting with a letter or `_`, and consisting of letters, digits, `_`, `-` and `.` are
+// accepted, so that a key can not inject markup
+fn check_name(name: &str) -> Result<(), String> {
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|first| first.is_alphabetic() || first == '_')
+        && chars.all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'));
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("{name:?} is not a valid XML name"))
+    }
+}
+
+fn write_event(writer: &mut Writer<Vec<u8>>, event: Event) -> Result<(), String> {
+    writer
+        .write_event(event)
+        .map_err(|err| format!("Failed to write XML: {err}"))
+}
+
+fn text_of(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use http::header::{ACCEPT, CONTENT_TYPE};
+    use http::{HeaderMap, HeaderValue, StatusCode};
+    use poem::Body;
+    use serde_json::json;
+
+    use crate::gateway_middleware::{
+        BodyTransformConfig, BodyTransformConfigError, BodyTransformer, BodyTransformerKind,
+        XmlJsonTransformer,
+    };
+
+    fn config(max_body_size: u64) -> BodyTransformConfig {
+        BodyTransformConfig {
+            transformer: BodyTransformerKind::XmlJson,
+            content_types: vec!["application/xml".to_string(), "text/xml".to_string()],
+            max_body_size,
+        }
+    }
+
+    fn headers(name: http::HeaderName, value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn converts_xml_to_json() {
+        let json = XmlJsonTransformer
+            .to_json(
+                br#"<?xml version="1.0"?>
+                <order id="42">
+                    <item>apple</item>
+                    <item>pear</item>
+                    <note lang="en">fresh &amp; ripe</note>
+                    <gift/>
+                </order>"#,
+            )
+            .unwrap();
+
+        assert_eq!(
+            json,
+            json!({
+                "order": {
+                    "@id": "42",
+                    "item": ["apple", "pear"],
+                    "note": { "@lang": "en", "#text": "fresh & ripe" },
+                    "gift": null
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_xml() {
+        assert!(XmlJsonTransformer
+            .to_json(b"<order><item></order>")
+            .is_err());
+        assert!(XmlJsonTransformer.to_json(b"<a/><b/>").is_err());
+        assert!(XmlJsonTransformer.to_json(b"").is_err());
+    }
+
+    #[test]
+    fn converts_json_to_xml() {
+        let xml = XmlJsonTransformer
+            .from_json(&json!({
+                "order": {
+                    "@id": "42",
+                    "item": ["apple", "pear"],
+                    "note": "fresh & ripe",
+                    "total": 3.5
+                }
+            }))
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(xml).unwrap(),
+            r#"<order id="42"><item>apple</item><item>pear</item><note>fresh &amp; ripe</note><total>3.5</total></order>"#
+        );
+    }
+
+    #[test]
+    fn wraps_json_without_a_single_root_field() {
+        let xml = XmlJsonTransformer.from_json(&json!([1, 2])).unwrap();
+
+        assert_eq!(
+            String::from_utf8(xml).unwrap(),
+            "<response>1</response><response>2</response>"
+        );
+    }
+
+    #[test]
+    fn validates_config() {
+        assert!(config(1024).validate().is_ok());
+        assert_eq!(
+            config(0).validate(),
+            Err(BodyTransformConfigError::ZeroMaxBodySize)
+        );
+        assert_eq!(
+            BodyTransformConfig {
+                content_types: vec![],
+                ..config(1024)
+            }
+            .validate(),
+            Err(BodyTransformConfigError::NoContentTypes)
+        );
+    }
+
+    #[test]
+    fn matches_configured_content_types() {
+        let config = config(1024);
+
+        assert!(config.applies_to_request(&headers(CONTENT_TYPE, "application/xml; charset=utf-8")));
+        assert!(!config.applies_to_request(&headers(CONTENT_TYPE, "application/json")));
+        assert!(config.applies_to_response(&headers(ACCEPT, "application/json, text/xml")));
+        assert!(!config.applies_to_response(&headers(ACCEPT, "application/json")));
+    }
+
+    #[test]
+    async fn rejects_request_bodies_over_the_limit() {
+        let body = "<order><item>apple</item></order>";
+        let headers = headers(CONTENT_TYPE, "application/xml");
+
+        let json = config(1024)
+            .transform_request_body(&headers, Body::from_string(body.to_string()))
+            .await
+            .unwrap();
+        assert_eq!(json, json!({ "order": { "item": "apple" } }));
+
+        let response = config(8)
+            .transform_request_body(&headers, Body::from_string(body.to_string()))
+            .await
+            .unwrap_err();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[test]
+    async fn stops_reading_streamed_request_bodies_at_the_limit() {
+        // No Content-Length, and a body failing after the limit, which is never read
+        let chunks = futures_util::stream::iter(vec![
+            Ok(bytes::Bytes::from("<order>")),
+            Ok(bytes::Bytes::from("<item>apple</item>")),
+            Err(std::io::Error::other("not to be read")),
+        ]);
+
+        let response = config(16)
+            .transform_request_body(
+                &headers(CONTENT_TYPE, "application/xml"),
+                Body::from_bytes_stream(chunks),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[test]
+    async fn converts_json_responses_only() {
+        let json_response = poem::Response::builder()
+            .content_type("application/json")
+            .body(r#"{"order":{"item":"apple"}}"#);
+        let response = config(1024).transform_response(json_response).await;
+        assert_eq!(response.content_type(), Some("application/xml"));
+        assert_eq!(
+            response.into_body().into_string().await.unwrap(),
+            "<order><item>apple</item></order>"
+        );
+
+        let text_response = poem::Response::builder()
+            .content_type("text/plain")
+            .body("apple");
+        let response = config(1024).transform_response(text_response).await;
+        assert_eq!(response.content_type(), Some("text/plain"));
+        assert_eq!(response.into_body().into_string().await.unwrap(), "apple");
+
+        let large_response = poem::Response::builder()
+            .content_type("application/json")
+            .body(r#"{"order":{"item":"apple"}}"#);
+        let response = config(8).transform_response(large_response).await;
+        assert_eq!(response.content_type(), Some("application/json"));
+    }
+
+    #[test]
+    async fn passes_on_streamed_responses_over_the_limit() {
+        // No Content-Length, so the limit is only noticed while reading
+        let chunks = futures_util::stream::iter(vec![
+            Ok::<_, std::io::Error>(bytes::Bytes::from(r#"{"order":"#)),
+            Ok(bytes::Bytes::from(r#"{"item":"apple"}}"#)),
+        ]);
+        let response = poem::Response::builder()
+            .content_type("application/json")
+            .body(Body::from_bytes_stream(chunks));
+
+        let response = config(16).transform_response(response).await;
+        assert_eq!(response.content_type(), Some("application/json"));
+        assert_eq!(
+            response.into_body().into_string().await.unwrap(),
+            r#"{"order":{"item":"apple"}}"#
+        );
+    }
+
+    #[test]
+    fn rejects_keys_that_are_not_xml_names() {
+        assert!(XmlJsonTransformer
+            .from_json(&json!({ "order": { "a><script/><b": 1 } }))
+            .is_err());
+        assert!(XmlJsonTransformer
+            .from_json(&json!({ "order": { "@id\"=\"": 1 } }))
+            .is_err());
+        assert!(XmlJsonTransformer
+            .from_json(&json!({ "1st": "apple" }))
+            .is_err());
+        assert!(XmlJsonTransformer
+            .from_json(&json!({ "order-item": { "_id.v2": 1 } }))
+            .is_ok());
+    }
+}
@@ -1,7 +1,13 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex};
+
 use http::header::*;
-use poem_openapi::Object;
+use http::{HeaderValue, Uri};
+use poem_openapi::{Enum, Object};
 use rib::{Expr, GetLiteralValue, RibInput, TypeName};
 use serde::{Deserialize, Serialize};
+use tracing::warn;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
 #[serde(rename_all = "camelCase")]
@@ -13,6 +19,176 @@ pub struct Cors {
     expose_headers: Option<String>,
     allow_credentials: Option<bool>,
     max_age: Option<u64>,
+    // Expose headers for specific origins, taking precedence over `expose_headers`.
+    // The first entry matching the request's origin wins.
+    expose_headers_by_origin: Option<Vec<CorsOriginExposeHeaders>>,
+    mode: Option<CorsMode>,
+    // If true, CORS headers are only added to responses of cross-origin requests,
+    // and left out of same-origin ones (whose Origin matches the Host of the request)
+    only_cross_origin: Option<bool>,
+    // If true, requests with the opaque `null` origin (sandboxed iframes, `file://` pages,
+    // some redirects) get CORS headers, with `null` reflected as the allowed origin.
+    // By default they get none
+    allow_null_origin: Option<bool>,
+    // Policies of specific methods, taking precedence over `allow_origin` and `allow_headers`
+    // in preflight requests for the method (given by Access-Control-Request-Method).
+    // The first entry for the method wins.
+    method_policies: Option<Vec<CorsMethodPolicy>>,
+    // If true, preflight responses allow the headers of the request's
+    // Access-Control-Request-Headers which are in `allow_headers` (or all of them if it is `*`),
+    // instead of listing `allow_headers`. Unlike `*`, reflected headers also work with credentials
+    reflect_request_headers: Option<bool>,
+    // Origins allowed in addition to `allow_origin`, decided by code rather than configuration,
+    // such as by a lookup per tenant. Not part of the API definition
+    #[serde(skip)]
+    #[oai(skip)]
+    allow_origin_predicate: Option<OriginPredicate>,
+}
+
+// Decides whether an origin is allowed, getting the value of the request's Origin header. It is
+// evaluated on the hot path, for every request with an Origin header that is not allowed by
+// `allow_origin` already, so it must be fast and must never block: a decision needing I/O, such
+// as a database lookup, has to be served from memory, for example by wrapping the predicate with
+// `OriginPredicate::memoized`, or by consulting a cache refreshed in the background
+#[derive(Clone)]
+pub struct OriginPredicate(Arc<dyn Fn(&str) -> bool + Send + Sync>);
+
+impl OriginPredicate {
+    pub fn new(predicate: Arc<dyn Fn(&str) -> bool + Send + Sync>) -> Self {
+        OriginPredicate(predicate)
+    }
+
+    // Remembers the decision of `predicate` for each origin, so that it is only evaluated once
+    // per origin. At most `max_entries` decisions are kept; when full, all of them are dropped,
+    // bounding the memory an attacker sending arbitrary origins can make it use
+    pub fn memoized(
+        predicate: Arc<dyn Fn(&str) -> bool + Send + Sync>,
+        max_entries: usize,
+    ) -> Self {
+        let decisions: Mutex<HashMap<String, bool>> = Mutex::new(HashMap::new());
+        OriginPredicate(Arc::new(move |origin: &str| {
+            if let Some(allowed) = decisions.lock().unwrap().get(origin) {
+                return *allowed;
+            }
+
+            // Not holding the lock while the predicate runs
+            let allowed = predicate(origin);
+
+            let mut decisions = decisions.lock().unwrap();
+            if decisions.len() >= max_entries {
+                decisions.clear();
+            }
+            if max_entries > 0 {
+                decisions.insert(origin.to_string(), allowed);
+            }
+            allowed
+        }))
+    }
+
+    pub fn allows(&self, origin: &str) -> bool {
+        (self.0)(origin)
+    }
+}
+
+// Provides the origin predicate for the CORS configurations of the routes of a host, such as one
+// allowing the origins registered by the tenant owning the host. The predicate is code rather than
+// part of the API definitions, so the gateway gets it from a resolver injected with
+// `CustomHttpRequestApi::with_cors_origin_resolver`. It is called for every request of the host,
+// so it should return the same (memoized) predicate every time, which also keeps the preflight
+// cache of the host effective
+pub trait CorsOriginResolver {
+    fn allow_origin_predicate(&self, host: &str) -> Option<OriginPredicate>;
+}
+
+impl Debug for OriginPredicate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "OriginPredicate")
+    }
+}
+
+// Predicates are equal only if they are the same instance
+impl PartialEq for OriginPredicate {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+// `AllowAll` is the explicit opt-in to permissive CORS, such as for internal-only gateways:
+// any origin, method and header is allowed, and credentials are never allowed, regardless of the
+// other fields of the configuration
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "kebab-case")]
+#[oai(rename_all = "kebab-case")]
+pub enum CorsMode {
+    #[default]
+    Explicit,
+    AllowAll,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct CorsOriginExposeHeaders {
+    // Either an exact origin, or a pattern in which `*` matches any sequence of characters,
+    // such as `https://*.example.com`
+    pub origin: String,
+    pub expose_headers: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct CorsMethodPolicy {
+    pub method: String,
+    // Origins allowed to use the method, or `*` for any origin
+    pub allow_origins: Vec<String>,
+    // Headers allowed with the method, defaults to `allow_headers`
+    pub allow_headers: Option<String>,
+}
+
+impl CorsMethodPolicy {
+    pub fn matches(&self, method: &str) -> bool {
+        self.method.trim().eq_ignore_ascii_case(method.trim())
+    }
+
+    // The Access-Control-Allow-Origin of a preflight request from the given origin, or `None`
+    // if the origin may not use the method. An allowed origin is reflected, as only one origin
+    // can be given in the header
+    pub fn get_allow_origin_for(&self, origin: Option<&str>) -> Option<String> {
+        if self.allow_origins.iter().any(|allowed| allowed == "*") {
+            return Some("*".to_string());
+        }
+
+        origin
+            .filter(|origin| self.allow_origins.iter().any(|allowed| allowed == origin))
+            .map(|origin| origin.to_string())
+    }
+}
+
+impl CorsOriginExposeHeaders {
+    pub fn matches(&self, origin: &str) -> bool {
+        let mut parts = self.origin.split('*');
+        let first = parts.next().unwrap_or_default();
+
+        let Some(mut rest) = origin.strip_prefix(first) else {
+            return false;
+        };
+
+        let mut parts: Vec<&str> = parts.collect();
+
+        match parts.pop() {
+            None => rest.is_empty(),
+            Some(last) => {
+                for part in parts {
+                    match rest.find(part) {
+                        Some(index) => rest = &rest[index + part.len()..],
+                        None => return false,
+                    }
+                }
+                rest.ends_with(last)
+            }
+        }
+    }
 }
 
 impl Default for Cors {
@@ -24,35 +200,351 @@ impl Default for Cors {
             expose_headers: None,
             max_age: None,
             allow_credentials: None,
+            expose_headers_by_origin: None,
+            mode: None,
+            only_cross_origin: None,
+            allow_null_origin: None,
+            method_policies: None,
+            reflect_request_headers: None,
+            allow_origin_predicate: None,
         }
     }
 }
 
+// A CORS configuration that would fail (or panic) when applied to a response
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum CorsConfigError {
+    #[error(
+        "Invalid value for {field} in CORS configuration: {value:?} is not a valid header value"
+    )]
+    InvalidHeaderValue { field: &'static str, value: String },
+    #[error("Invalid origin in CORS configuration: {origin:?} must be '*' or an absolute URI such as https://example.com")]
+    InvalidOrigin { origin: String },
+    #[error("CORS configuration allows credentials for any origin ('*'), which browsers reject as it would let any website make authenticated requests on behalf of the user; specify an explicit origin, or use the allow-all mode if the gateway really should be open to any origin without credentials")]
+    CredentialsWithWildcardOrigin,
+    #[error("CORS configuration uses 'null' as allowed origin. The null origin is shared by every sandboxed iframe and local file, so allowing it lets any untrusted page (including an attacker's sandboxed iframe) read the responses; if this is really needed, use allow_null_origin instead, which keeps null origins opt-in and separate from the allowed origin")]
+    NullAllowOrigin,
+    #[error("Invalid method {method:?} in CORS method policies")]
+    InvalidMethod { method: String },
+    #[error("CORS method policy for {method} allows no origins")]
+    NoOriginsForMethod { method: String },
+    #[error("Conflicting CORS expose headers for origin {origin:?}: {first:?} and {second:?}")]
+    ConflictingExposeHeaders {
+        origin: String,
+        first: String,
+        second: String,
+    },
+}
+
 impl Cors {
+    // Checks all the invariants the gateway relies on when applying CORS headers to responses,
+    // so a misconfiguration is reported when the configuration is registered
+    pub fn validate(&self) -> Result<(), CorsConfigError> {
+        let allow_origin = self.get_allow_origin();
+        Self::validate_header_value("allow_origin", &allow_origin)?;
+        if is_null_origin(&allow_origin) {
+            return Err(CorsConfigError::NullAllowOrigin);
+        }
+        if allow_origin != "*" {
+            Self::validate_origin(&allow_origin)?;
+        }
+
+        Self::validate_header_value("allow_methods", &self.get_allow_methods())?;
+        Self::validate_header_value("allow_headers", &self.get_allow_headers())?;
+
+        if let Some(expose_headers) = &self.expose_headers {
+            Self::validate_header_value("expose_headers", expose_headers)?;
+        }
+
+        if self.get_allow_credentials() == Some(true) && allow_origin == "*" {
+            return Err(CorsConfigError::CredentialsWithWildcardOrigin);
+        }
+
+        for policy in self.method_policies.iter().flatten() {
+            if !VALID_METHODS.contains(&policy.method.trim().to_uppercase().as_str()) {
+                return Err(CorsConfigError::InvalidMethod {
+                    method: policy.method.clone(),
+                });
+            }
+            if policy.allow_origins.is_empty() {
+                return Err(CorsConfigError::NoOriginsForMethod {
+                    method: policy.method.clone(),
+                });
+            }
+            for origin in &policy.allow_origins {
+                if origin == "*" {
+                    if self.get_allow_credentials() == Some(true) {
+                        return Err(CorsConfigError::CredentialsWithWildcardOrigin);
+                    }
+                } else if is_null_origin(origin) {
+                    return Err(CorsConfigError::NullAllowOrigin);
+                } else {
+                    Self::validate_origin(origin)?;
+                }
+            }
+            if let Some(allow_headers) = &policy.allow_headers {
+                Self::validate_header_value("method_policies", allow_headers)?;
+            }
+        }
+
+        let entries = self.expose_headers_by_origin.iter().flatten();
+        for (idx, entry) in entries.clone().enumerate() {
+            if !entry.origin.contains('*') {
+                Self::validate_origin(&entry.origin)?;
+            }
+            Self::validate_header_value("expose_headers_by_origin", &entry.expose_headers)?;
+
+            if let Some(previous) = entries
+                .clone()
+                .take(idx)
+                .find(|previous| previous.origin == entry.origin)
+            {
+                if previous.expose_headers != entry.expose_headers {
+                    return Err(CorsConfigError::ConflictingExposeHeaders {
+                        origin: entry.origin.clone(),
+                        first: previous.expose_headers.clone(),
+                        second: entry.expose_headers.clone(),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_header_value(field: &'static str, value: &str) -> Result<(), CorsConfigError> {
+        HeaderValue::from_str(value)
+            .map(|_| ())
+            .map_err(|_| CorsConfigError::InvalidHeaderValue {
+                field,
+                value: value.to_string(),
+            })
+    }
+
+    fn validate_origin(origin: &str) -> Result<(), CorsConfigError> {
+        match origin.parse::<Uri>() {
+            Ok(uri) if uri.scheme().is_some() && uri.host().is_some() => Ok(()),
+            _ => Err(CorsConfigError::InvalidOrigin {
+                origin: origin.to_string(),
+            }),
+        }
+    }
+
+    pub fn allow_all() -> Cors {
+        Cors {
+            mode: Some(CorsMode::AllowAll),
+            ..Cors::default()
+        }
+    }
+
+    pub fn get_mode(&self) -> CorsMode {
+        self.mode.unwrap_or_default()
+    }
+
+    fn is_allow_all(&self) -> bool {
+        self.get_mode() == CorsMode::AllowAll
+    }
+
     pub fn get_allow_origin(&self) -> String {
-        self.allow_origin.clone()
+        if self.is_allow_all() {
+            "*".to_string()
+        } else {
+            self.allow_origin.clone()
+        }
+    }
+
+    // The Access-Control-Allow-Origin for a request with the given Origin header. An allowed
+    // `null` origin is reflected, as it never matches an explicit origin, and so is an origin
+    // allowed by the origin predicate
+    pub fn get_allow_origin_for(&self, origin: Option<&str>) -> String {
+        let allow_origin = self.get_allow_origin();
+        match origin {
+            Some(origin) if is_null_origin(origin) && allow_origin != "*" => "null".to_string(),
+            Some(origin)
+                if allow_origin != "*"
+                    && allow_origin != origin
+                    && self.is_allowed_by_predicate(origin) =>
+            {
+                origin.to_string()
+            }
+            _ => allow_origin,
+        }
+    }
+
+    fn is_allowed_by_predicate(&self, origin: &str) -> bool {
+        self.allow_origin_predicate
+            .as_ref()
+            .is_some_and(|predicate| predicate.allows(origin))
+    }
+
+    pub fn get_allow_origin_predicate(&self) -> Option<OriginPredicate> {
+        self.allow_origin_predicate.clone()
     }
 
     pub fn get_allow_methods(&self) -> String {
-        self.allow_methods.clone()
+        if self.is_allow_all() {
+            "*".to_string()
+        } else {
+            self.allow_methods.clone()
+        }
+    }
+
+    // The allowed methods of preflight requests for methods without a policy. Methods governed by
+    // a policy are left out, as they are only allowed for the origins of their policy
+    pub fn get_allow_methods_without_policies(&self) -> String {
+        let allow_methods = self.get_allow_methods();
+        if self.is_allow_all() || self.method_policies.is_none() {
+            return allow_methods;
+        }
+
+        allow_methods
+            .split(',')
+            .map(str::trim)
+            .filter(|method| !method.is_empty())
+            .filter(|method| {
+                !self
+                    .method_policies
+                    .iter()
+                    .flatten()
+                    .any(|policy| policy.matches(method))
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
     }
 
     pub fn get_allow_headers(&self) -> String {
-        self.allow_headers.clone()
+        if self.is_allow_all() {
+            "*".to_string()
+        } else {
+            self.allow_headers.clone()
+        }
+    }
+
+    pub fn get_reflect_request_headers(&self) -> bool {
+        self.reflect_request_headers.unwrap_or(false)
+    }
+
+    // The Access-Control-Allow-Headers of a preflight request with the given
+    // Access-Control-Request-Headers, where `allow_headers` is the configured list (of the method
+    // policy, if any). When reflecting, it is the allowed subset of the requested headers, in the
+    // requested order, or `None` if no requested header is allowed
+    pub fn get_allow_headers_for(
+        &self,
+        allow_headers: String,
+        request_headers: Option<&str>,
+    ) -> Option<String> {
+        if !self.get_reflect_request_headers() {
+            return Some(allow_headers);
+        }
+
+        let allowed: Vec<&str> = allow_headers
+            .split(',')
+            .map(|header| header.trim())
+            .collect();
+        let any_allowed = allowed.contains(&"*");
+
+        let reflected: Vec<&str> = request_headers
+            .unwrap_or_default()
+            .split(',')
+            .map(|header| header.trim())
+            .filter(|header| !header.is_empty())
+            .filter(|header| {
+                any_allowed
+                    || allowed
+                        .iter()
+                        .any(|allowed| allowed.eq_ignore_ascii_case(header))
+            })
+            .collect();
+
+        (!reflected.is_empty()).then(|| reflected.join(", "))
     }
 
     pub fn get_expose_headers(&self) -> Option<String> {
         self.expose_headers.clone()
     }
 
+    pub fn get_expose_headers_by_origin(&self) -> Option<Vec<CorsOriginExposeHeaders>> {
+        self.expose_headers_by_origin.clone()
+    }
+
+    // Expose headers for the given request origin, falling back to `expose_headers`
+    // if there is no origin specific mapping
+    pub fn get_expose_headers_for_origin(&self, origin: Option<&str>) -> Option<String> {
+        origin
+            .and_then(|origin| {
+                self.expose_headers_by_origin
+                    .iter()
+                    .flatten()
+                    .find(|entry| entry.matches(origin))
+                    .map(|entry| entry.expose_headers.clone())
+            })
+            .or_else(|| self.expose_headers.clone())
+    }
+
     pub fn get_allow_credentials(&self) -> Option<bool> {
-        self.allow_credentials
+        if self.is_allow_all() {
+            None
+        } else {
+            self.allow_credentials
+        }
     }
 
     pub fn get_max_age(&self) -> Option<u64> {
         self.max_age
     }
 
+    pub fn get_method_policies(&self) -> Option<Vec<CorsMethodPolicy>> {
+        self.method_policies.clone()
+    }
+
+    // The policy for preflight requests of the given method, if there is one. Method policies
+    // do not apply in the allow-all mode
+    pub fn get_method_policy(&self, request_method: Option<&str>) -> Option<&CorsMethodPolicy> {
+        if self.is_allow_all() {
+            return None;
+        }
+
+        request_method.and_then(|method| {
+            self.method_policies
+                .iter()
+                .flatten()
+                .find(|policy| policy.matches(method))
+        })
+    }
+
+    pub fn get_only_cross_origin(&self) -> bool {
+        self.only_cross_origin.unwrap_or(false)
+    }
+
+    pub fn get_allow_null_origin(&self) -> bool {
+        self.allow_null_origin.unwrap_or(false)
+    }
+
+    // Requests with the `null` origin get no CORS headers at all unless explicitly allowed
+    pub fn allows_origin(&self, origin: Option<&str>) -> bool {
+        origin.map_or(true, |origin| {
+            !is_null_origin(origin) || self.get_allow_null_origin()
+        })
+    }
+
+    // Whether CORS headers are to be added to the response of a request with the given
+    // Origin and Host headers. Requests without an Origin header are never cross-origin
+    pub fn applies_to(&self, origin: Option<&str>, host: Option<&str>) -> bool {
+        if !self.allows_origin(origin) {
+            return false;
+        }
+
+        if !self.get_only_cross_origin() {
+            return true;
+        }
+
+        match (origin, host) {
+            (None, _) => false,
+            (Some(origin), Some(host)) => !is_same_origin(origin, host),
+            (Some(_), None) => true,
+        }
+    }
+
     pub fn from_parameters(
         allow_origin: Option<String>,
         allow_methods: Option<String>,
@@ -153,14 +645,13 @@ impl Cors {
     }
 
     pub fn set_allow_methods(&mut self, allow_methods: &str) -> Result<(), String> {
-        let valid_methods = [
-            "GET", "POST", "PUT", "DELETE", "OPTIONS", "PATCH", "HEAD", "TRACE", "CONNECT",
-        ];
         let methods: Vec<&str> = allow_methods.split(',').collect();
 
-        if methods
-            .into_iter()
-            .all(|m| valid_methods.contains(&m.trim().to_uppercase().as_str()))
+        // `*` is the wildcard used by the allow-all mode
+        if allow_methods.trim() == "*"
+            || methods
+                .into_iter()
+                .all(|m| VALID_METHODS.contains(&m.trim().to_uppercase().as_str()))
         {
             self.allow_methods = allow_methods.to_string();
             Ok(())
@@ -178,6 +669,40 @@ impl Cors {
         }
     }
 
+    pub fn set_expose_headers_by_origin(
+        &mut self,
+        expose_headers_by_origin: Vec<CorsOriginExposeHeaders>,
+    ) -> Result<(), String> {
+        for entry in &expose_headers_by_origin {
+            if entry.origin.is_empty() {
+                return Err("origin in expose_headers_by_origin cannot be empty.".to_string());
+            }
+            if entry.expose_headers.is_empty() {
+                return Err(format!(
+                    "expose_headers for origin {} cannot be empty.",
+                    entry.origin
+                ));
+            }
+        }
+
+        self.expose_headers_by_origin = Some(expose_headers_by_origin);
+        Ok(())
+    }
+
+    pub fn set_method_policies(
+        &mut self,
+        method_policies: Vec<CorsMethodPolicy>,
+    ) -> Result<(), String> {
+        for policy in &method_policies {
+            if policy.method.trim().is_empty() {
+                return Err("method in method_policies cannot be empty.".to_string());
+            }
+        }
+
+        self.method_policies = Some(method_policies);
+        Ok(())
+    }
+
     pub fn set_allow_credentials(&mut self, allow_credentials: bool) {
         self.allow_credentials = Some(allow_credentials);
     }
@@ -185,6 +710,78 @@ impl Cors {
     pub fn set_max_age(&mut self, max_age: u64) {
         self.max_age = Some(max_age);
     }
+
+    pub fn set_mode(&mut self, mode: CorsMode) {
+        self.mode = Some(mode);
+    }
+
+    pub fn set_only_cross_origin(&mut self, only_cross_origin: bool) {
+        self.only_cross_origin = Some(only_cross_origin);
+    }
+
+    pub fn set_allow_null_origin(&mut self, allow_null_origin: bool) {
+        self.allow_null_origin = Some(allow_null_origin);
+    }
+
+    pub fn set_reflect_request_headers(&mut self, reflect_request_headers: bool) {
+        self.reflect_request_headers = Some(reflect_request_headers);
+    }
+
+    // Allows the origins accepted by `predicate` in addition to `allow_origin`, see
+    // `OriginPredicate` for the requirements on it
+    pub fn set_allow_origin_predicate(&mut self, predicate: OriginPredicate) {
+        self.allow_origin_predicate = Some(predicate);
+    }
+
+    pub fn with_allow_origin_predicate(mut self, predicate: OriginPredicate) -> Cors {
+        self.allow_origin_predicate = Some(predicate);
+        self
+    }
+}
+
+const VALID_METHODS: [&str; 9] = [
+    "GET", "POST", "PUT", "DELETE", "OPTIONS", "PATCH", "HEAD", "TRACE", "CONNECT",
+];
+
+fn is_null_origin(origin: &str) -> bool {
+    origin.trim().eq_ignore_ascii_case("null")
+}
+
+// Compares the authority of the Origin header with the Host header, ignoring the default port
+// of the origin's scheme. The scheme itself is not compared, as TLS may be terminated in front
+// of the gateway
+fn is_same_origin(origin: &str, host: &str) -> bool {
+    let Ok(origin) = origin.parse::<Uri>() else {
+        return false;
+    };
+    let (Some(scheme), Some(origin_host)) = (origin.scheme_str(), origin.host()) else {
+        return false;
+    };
+    let Ok(host) = format!("{scheme}://{host}").parse::<Uri>() else {
+        return false;
+    };
+
+    let default_port = match scheme {
+        "http" => Some(80),
+        "https" => Some(443),
+        _ => None,
+    };
+    let port = |uri: &Uri| uri.port_u16().or(default_port);
+
+    host.host()
+        .is_some_and(|host| host.eq_ignore_ascii_case(origin_host))
+        && port(&host) == port(&origin)
+}
+
+// Values are validated when the API definition is registered, so this only skips the
+// headers of configurations that bypassed the validation instead of failing the request
+pub(crate) fn insert_cors_header(response: &mut poem::Response, name: HeaderName, value: &str) {
+    match HeaderValue::from_str(value) {
+        Ok(value) => {
+            response.headers_mut().insert(name, value);
+        }
+        Err(_) => warn!("Skipping invalid CORS header {name}: {value:?}"),
+    }
 }
 
 impl TryFrom<golem_api_grpc::proto::golem::apidefinition::CorsPreflight> for Cors {
@@ -200,6 +797,39 @@ impl TryFrom<golem_api_grpc::proto::golem::apidefinition::CorsPreflight> for Cor
             expose_headers: value.expose_headers,
             max_age: value.max_age,
             allow_credentials: value.allow_credentials,
+            mode: value.allow_all.map(|allow_all| {
+                if allow_all {
+                    CorsMode::AllowAll
+                } else {
+                    CorsMode::Explicit
+                }
+            }),
+            only_cross_origin: value.only_cross_origin,
+            allow_null_origin: value.allow_null_origin,
+            method_policies: if value.method_policies.is_empty() {
+                None
+            } else {
+                Some(
+                    value
+                        .method_policies
+                        .into_iter()
+                        .map(CorsMethodPolicy::from)
+                        .collect(),
+                )
+            },
+            expose_headers_by_origin: if value.expose_headers_by_origin.is_empty() {
+                None
+            } else {
+                Some(
+                    value
+                        .expose_headers_by_origin
+                        .into_iter()
+                        .map(CorsOriginExposeHeaders::from)
+                        .collect(),
+                )
+            },
+            reflect_request_headers: value.reflect_request_headers,
+            allow_origin_predicate: None,
         })
     }
 }
@@ -213,6 +843,64 @@ impl From<Cors> for golem_api_grpc::proto::golem::apidefinition::CorsPreflight {
             expose_headers: value.expose_headers,
             max_age: value.max_age,
             allow_credentials: value.allow_credentials,
+            expose_headers_by_origin: value
+                .expose_headers_by_origin
+                .unwrap_or_default()
+                .into_iter()
+                .map(|entry| entry.into())
+                .collect(),
+            allow_all: value.mode.map(|mode| mode == CorsMode::AllowAll),
+            only_cross_origin: value.only_cross_origin,
+            allow_null_origin: value.allow_null_origin,
+            method_policies: value
+                .method_policies
+                .unwrap_or_default()
+                .into_iter()
+                .map(|policy| policy.into())
+                .collect(),
+            reflect_request_headers: value.reflect_request_headers,
+        }
+    }
+}
+
+impl From<golem_api_grpc::proto::golem::apidefinition::CorsMethodPolicy> for CorsMethodPolicy {
+    fn from(value: golem_api_grpc::proto::golem::apidefinition::CorsMethodPolicy) -> Self {
+        CorsMethodPolicy {
+            method: value.method,
+            allow_origins: value.allow_origins,
+            allow_headers: value.allow_headers,
+        }
+    }
+}
+
+impl From<CorsMethodPolicy> for golem_api_grpc::proto::golem::apidefinition::CorsMethodPolicy {
+    fn from(value: CorsMethodPolicy) -> Self {
+        golem_api_grpc::proto::golem::apidefinition::CorsMethodPolicy {
+            method: value.method,
+            allow_origins: value.allow_origins,
+            allow_headers: value.allow_headers,
+        }
+    }
+}
+
+impl From<golem_api_grpc::proto::golem::apidefinition::CorsOriginExposeHeaders>
+    for CorsOriginExposeHeaders
+{
+    fn from(value: golem_api_grpc::proto::golem::apidefinition::CorsOriginExposeHeaders) -> Self {
+        CorsOriginExposeHeaders {
+            origin: value.origin,
+            expose_headers: value.expose_headers,
+        }
+    }
+}
+
+impl From<CorsOriginExposeHeaders>
+    for golem_api_grpc::proto::golem::apidefinition::CorsOriginExposeHeaders
+{
+    fn from(value: CorsOriginExposeHeaders) -> Self {
+        golem_api_grpc::proto::golem::apidefinition::CorsOriginExposeHeaders {
+            origin: value.origin,
+            expose_headers: value.expose_headers,
         }
     }
 }
@@ -224,19 +912,19 @@ impl CorsPreflightExpr {
         let mut cors_parameters = vec![
             (
                 ACCESS_CONTROL_ALLOW_ORIGIN.to_string(),
-                Expr::literal(cors.allow_origin.as_str()),
+                Expr::literal(cors.get_allow_origin().as_str()),
             ),
             (
                 ACCESS_CONTROL_ALLOW_METHODS.to_string(),
-                Expr::literal(cors.allow_methods.as_str()),
+                Expr::literal(cors.get_allow_methods().as_str()),
             ),
             (
                 ACCESS_CONTROL_ALLOW_HEADERS.to_string(),
-                Expr::literal(cors.allow_headers.as_str()),
+                Expr::literal(cors.get_allow_headers().as_str()),
             ),
         ];
 
-        if let Some(allow_credentials) = &cors.allow_credentials {
+        if let Some(allow_credentials) = &cors.get_allow_credentials() {
             cors_parameters.push((
                 ACCESS_CONTROL_ALLOW_CREDENTIALS.to_string(),
                 Expr::literal(allow_credentials.to_string().as_str()),
@@ -302,3 +990,432 @@ mod internal {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use crate::gateway_middleware::{
+        Cors, CorsConfigError, CorsMethodPolicy, CorsMode, CorsOriginExposeHeaders, OriginPredicate,
+    };
+
+    fn cors_with_expose_headers_by_origin() -> Cors {
+        let mut cors = Cors::default();
+        cors.set_expose_headers("X-Default").unwrap();
+        cors.set_expose_headers_by_origin(vec![
+            CorsOriginExposeHeaders {
+                origin: "https://partner.com".to_string(),
+                expose_headers: "X-Default, X-Partner".to_string(),
+            },
+            CorsOriginExposeHeaders {
+                origin: "https://*.example.com".to_string(),
+                expose_headers: "X-Default, X-Example".to_string(),
+            },
+        ])
+        .unwrap();
+        cors
+    }
+
+    #[test]
+    fn expose_headers_for_exact_origin() {
+        let cors = cors_with_expose_headers_by_origin();
+
+        assert_eq!(
+            cors.get_expose_headers_for_origin(Some("https://partner.com")),
+            Some("X-Default, X-Partner".to_string())
+        );
+    }
+
+    #[test]
+    fn expose_headers_for_origin_pattern() {
+        let cors = cors_with_expose_headers_by_origin();
+
+        assert_eq!(
+            cors.get_expose_headers_for_origin(Some("https://api.example.com")),
+            Some("X-Default, X-Example".to_string())
+        );
+        assert_eq!(
+            cors.get_expose_headers_for_origin(Some("https://example.com")),
+            Some("X-Default".to_string())
+        );
+    }
+
+    #[test]
+    fn expose_headers_fall_back_to_default() {
+        let cors = cors_with_expose_headers_by_origin();
+
+        assert_eq!(
+            cors.get_expose_headers_for_origin(Some("https://partner.com.evil.com")),
+            Some("X-Default".to_string())
+        );
+        assert_eq!(
+            cors.get_expose_headers_for_origin(None),
+            Some("X-Default".to_string())
+        );
+    }
+
+    #[test]
+    fn valid_cors_configurations() {
+        assert_eq!(Cors::default().validate(), Ok(()));
+        assert_eq!(cors_with_expose_headers_by_origin().validate(), Ok(()));
+
+        let mut cors = Cors::default();
+        cors.set_allow_origin("https://example.com:8080").unwrap();
+        cors.set_allow_credentials(true);
+        assert_eq!(cors.validate(), Ok(()));
+    }
+
+    #[test]
+    fn invalid_header_value() {
+        let mut cors = Cors::default();
+        cors.set_allow_headers("Content-Type\nX-Injected: 1")
+            .unwrap();
+
+        assert!(matches!(
+            cors.validate(),
+            Err(CorsConfigError::InvalidHeaderValue {
+                field: "allow_headers",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn invalid_allow_origin() {
+        let mut cors = Cors::default();
+        cors.set_allow_origin("apple.com").unwrap();
+
+        assert_eq!(
+            cors.validate(),
+            Err(CorsConfigError::InvalidOrigin {
+                origin: "apple.com".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_origin_in_expose_headers_by_origin() {
+        let mut cors = Cors::default();
+        cors.set_expose_headers_by_origin(vec![CorsOriginExposeHeaders {
+            origin: "not an origin".to_string(),
+            expose_headers: "X-Partner".to_string(),
+        }])
+        .unwrap();
+
+        assert_eq!(
+            cors.validate(),
+            Err(CorsConfigError::InvalidOrigin {
+                origin: "not an origin".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn credentials_with_wildcard_origin() {
+        let mut cors = Cors::default();
+        cors.set_allow_credentials(true);
+
+        assert_eq!(
+            cors.validate(),
+            Err(CorsConfigError::CredentialsWithWildcardOrigin)
+        );
+    }
+
+    #[test]
+    fn allow_all_mode() {
+        let mut cors = Cors::default();
+        cors.set_allow_origin("https://example.com").unwrap();
+        cors.set_allow_credentials(true);
+        cors.set_mode(CorsMode::AllowAll);
+
+        assert_eq!(cors.validate(), Ok(()));
+        assert_eq!(cors.get_allow_origin(), "*");
+        assert_eq!(cors.get_allow_methods(), "*");
+        assert_eq!(cors.get_allow_headers(), "*");
+        assert_eq!(cors.get_allow_credentials(), None);
+        assert_eq!(Cors::allow_all().get_mode(), CorsMode::AllowAll);
+    }
+
+    #[test]
+    fn allow_all_mode_proto_roundtrip() {
+        let cors = Cors::allow_all();
+        let proto: golem_api_grpc::proto::golem::apidefinition::CorsPreflight = cors.clone().into();
+
+        assert_eq!(Cors::try_from(proto), Ok(cors));
+    }
+
+    #[test]
+    fn conflicting_expose_headers_by_origin() {
+        let mut cors = Cors::default();
+        cors.set_expose_headers_by_origin(vec![
+            CorsOriginExposeHeaders {
+                origin: "https://partner.com".to_string(),
+                expose_headers: "X-A".to_string(),
+            },
+            CorsOriginExposeHeaders {
+                origin: "https://partner.com".to_string(),
+                expose_headers: "X-B".to_string(),
+            },
+        ])
+        .unwrap();
+
+        assert_eq!(
+            cors.validate(),
+            Err(CorsConfigError::ConflictingExposeHeaders {
+                origin: "https://partner.com".to_string(),
+                first: "X-A".to_string(),
+                second: "X-B".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn applies_to_all_requests_by_default() {
+        let cors = Cors::default();
+
+        assert!(cors.applies_to(Some("https://example.com"), Some("example.com")));
+        assert!(cors.applies_to(None, Some("example.com")));
+    }
+
+    #[test]
+    fn only_cross_origin() {
+        let mut cors = Cors::default();
+        cors.set_only_cross_origin(true);
+
+        assert!(!cors.applies_to(Some("https://example.com"), Some("example.com")));
+        assert!(!cors.applies_to(Some("https://example.com"), Some("example.com:443")));
+        assert!(!cors.applies_to(Some("http://Example.com:8080"), Some("example.com:8080")));
+        assert!(!cors.applies_to(None, Some("example.com")));
+
+        assert!(cors.applies_to(Some("https://app.example.com"), Some("example.com")));
+        assert!(cors.applies_to(Some("https://example.com"), Some("example.com:8443")));
+        assert!(cors.applies_to(Some("null"), Some("example.com")));
+        assert!(cors.applies_to(Some("https://example.com"), None));
+    }
+
+    #[test]
+    fn null_origin_is_not_allowed_by_default() {
+        let cors = Cors::default();
+
+        assert!(!cors.applies_to(Some("null"), Some("example.com")));
+        assert!(!Cors::allow_all().applies_to(Some("null"), Some("example.com")));
+        assert!(cors.applies_to(Some("https://example.com"), Some("example.com")));
+    }
+
+    #[test]
+    fn allow_null_origin() {
+        let mut cors = Cors::default();
+        cors.set_allow_origin("https://example.com").unwrap();
+        cors.set_allow_null_origin(true);
+
+        assert!(cors.applies_to(Some("null"), Some("example.com")));
+        assert_eq!(cors.get_allow_origin_for(Some("null")), "null");
+        assert_eq!(
+            cors.get_allow_origin_for(Some("https://other.com")),
+            "https://example.com"
+        );
+        assert_eq!(Cors::default().get_allow_origin_for(Some("null")), "*");
+    }
+
+    fn cors_with_method_policies() -> Cors {
+        let mut cors = Cors::default();
+        cors.set_method_policies(vec![
+            CorsMethodPolicy {
+                method: "GET".to_string(),
+                allow_origins: vec!["*".to_string()],
+                allow_headers: None,
+            },
+            CorsMethodPolicy {
+                method: "DELETE".to_string(),
+                allow_origins: vec!["https://admin.example.com".to_string()],
+                allow_headers: Some("Authorization".to_string()),
+            },
+        ])
+        .unwrap();
+        cors
+    }
+
+    #[test]
+    fn method_policies() {
+        let cors = cors_with_method_policies();
+        assert_eq!(cors.validate(), Ok(()));
+
+        let get = cors.get_method_policy(Some("get")).unwrap();
+        assert_eq!(
+            get.get_allow_origin_for(Some("https://app.example.com")),
+            Some("*".to_string())
+        );
+
+        let delete = cors.get_method_policy(Some("DELETE")).unwrap();
+        assert_eq!(
+            delete.get_allow_origin_for(Some("https://admin.example.com")),
+            Some("https://admin.example.com".to_string())
+        );
+        assert_eq!(
+            delete.get_allow_origin_for(Some("https://app.example.com")),
+            None
+        );
+        assert_eq!(delete.get_allow_origin_for(None), None);
+
+        assert!(cors.get_method_policy(Some("POST")).is_none());
+        assert!(cors.get_method_policy(None).is_none());
+
+        let mut allow_all = cors_with_method_policies();
+        allow_all.set_mode(CorsMode::AllowAll);
+        assert!(allow_all.get_method_policy(Some("DELETE")).is_none());
+    }
+
+    #[test]
+    fn invalid_method_policies() {
+        let mut cors = Cors::default();
+        cors.set_method_policies(vec![CorsMethodPolicy {
+            method: "FETCH".to_string(),
+            allow_origins: vec!["*".to_string()],
+            allow_headers: None,
+        }])
+        .unwrap();
+        assert_eq!(
+            cors.validate(),
+            Err(CorsConfigError::InvalidMethod {
+                method: "FETCH".to_string()
+            })
+        );
+
+        cors.set_method_policies(vec![CorsMethodPolicy {
+            method: "DELETE".to_string(),
+            allow_origins: vec![],
+            allow_headers: None,
+        }])
+        .unwrap();
+        assert_eq!(
+            cors.validate(),
+            Err(CorsConfigError::NoOriginsForMethod {
+                method: "DELETE".to_string()
+            })
+        );
+
+        cors.set_method_policies(vec![CorsMethodPolicy {
+            method: "DELETE".to_string(),
+            allow_origins: vec!["admin.example.com".to_string()],
+            allow_headers: None,
+        }])
+        .unwrap();
+        assert_eq!(
+            cors.validate(),
+            Err(CorsConfigError::InvalidOrigin {
+                origin: "admin.example.com".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn method_policies_proto_roundtrip() {
+        let cors = cors_with_method_policies();
+        let proto: golem_api_grpc::proto::golem::apidefinition::CorsPreflight = cors.clone().into();
+
+        assert_eq!(Cors::try_from(proto), Ok(cors));
+    }
+
+    #[test]
+    fn null_allow_origin() {
+        let mut cors = Cors::default();
+        cors.set_allow_origin("null").unwrap();
+
+        assert_eq!(cors.validate(), Err(CorsConfigError::NullAllowOrigin));
+    }
+
+    #[test]
+    fn reflected_request_headers_are_filtered() {
+        let mut cors = Cors::default();
+        cors.set_allow_headers("Content-Type, Authorization, X-Request-Id")
+            .unwrap();
+        cors.set_reflect_request_headers(true);
+
+        assert_eq!(
+            cors.get_allow_headers_for(
+                cors.get_allow_headers(),
+                Some("x-request-id, x-tracking,authorization")
+            ),
+            Some("x-request-id, authorization".to_string())
+        );
+        assert_eq!(
+            cors.get_allow_headers_for(cors.get_allow_headers(), Some("X-Tracking")),
+            None
+        );
+        assert_eq!(
+            cors.get_allow_headers_for(cors.get_allow_headers(), None),
+            None
+        );
+    }
+
+    #[test]
+    fn reflected_request_headers_with_wildcard() {
+        let mut cors = Cors::default();
+        cors.set_allow_headers("*").unwrap();
+        cors.set_reflect_request_headers(true);
+
+        assert_eq!(
+            cors.get_allow_headers_for(cors.get_allow_headers(), Some("X-Custom-1, X-Custom-2")),
+            Some("X-Custom-1, X-Custom-2".to_string())
+        );
+    }
+
+    #[test]
+    fn request_headers_are_not_reflected_by_default() {
+        let cors = Cors::default();
+
+        assert_eq!(
+            cors.get_allow_headers_for(cors.get_allow_headers(), Some("X-Custom")),
+            Some("Content-Type, Authorization".to_string())
+        );
+    }
+
+    #[test]
+    fn origins_allowed_by_the_predicate_are_reflected() {
+        let mut cors = Cors::default();
+        cors.set_allow_origin("https://app.example.com").unwrap();
+        cors.set_allow_origin_predicate(OriginPredicate::new(Arc::new(|origin: &str| {
+            origin.ends_with(".tenant.example.com")
+        })));
+
+        assert_eq!(
+            cors.get_allow_origin_for(Some("https://acme.tenant.example.com")),
+            "https://acme.tenant.example.com"
+        );
+        assert_eq!(
+            cors.get_allow_origin_for(Some("https://app.example.com")),
+            "https://app.example.com"
+        );
+        assert_eq!(
+            cors.get_allow_origin_for(Some("https://evil.com")),
+            "https://app.example.com"
+        );
+    }
+
+    #[test]
+    fn memoized_origin_predicate_is_evaluated_once_per_origin() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let calls_clone = calls.clone();
+        let predicate = OriginPredicate::memoized(
+            Arc::new(move |origin: &str| {
+                calls_clone.fetch_add(1, Ordering::SeqCst);
+                origin == "https://acme.com"
+            }),
+            2,
+        );
+
+        assert!(predicate.allows("https://acme.com"));
+        assert!(predicate.allows("https://acme.com"));
+        assert!(!predicate.allows("https://evil.com"));
+        assert!(!predicate.allows("https://evil.com"));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // Full, so the decisions are dropped to make room for the new one
+        assert!(!predicate.allows("https://other.com"));
+        assert!(predicate.allows("https://acme.com"));
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+}
@@ -1,11 +1,58 @@
-use crate::gateway_middleware::http::cors::Cors;
+use crate::gateway_binding::GatewayRequestDetails;
+use crate::gateway_middleware::http::body_transform::BodyTransformConfig;
+use crate::gateway_middleware::http::cors::{insert_cors_header, Cors};
+use crate::gateway_middleware::http::ip_filter::{IpFilter, IpFilterConfig};
+use crate::gateway_middleware::http::location_rewrite::{rewrite_location, LocationRewriteRule};
+use crate::gateway_middleware::http::require_https::{
+    RequestConnection, RequireHttps, RequireHttpsConfig,
+};
+use crate::gateway_middleware::http::response_cache::CacheConfig;
+use crate::gateway_middleware::http::server_timing::ServerTimingConfig;
+use crate::gateway_request::http_request::InputHttpRequest;
 use http::header::{
     ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_EXPOSE_HEADERS,
+    LOCATION,
 };
+use http::{HeaderMap, StatusCode};
+use poem::Body;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum HttpMiddleware {
     Cors(Cors),
+    // Answers OPTIONS requests on the route's path with the methods it supports,
+    // even if no explicit OPTIONS route (such as a CORS preflight) is defined
+    AutoOptions,
+    // Answers HEAD requests on the route's path by executing its GET route and
+    // stripping the body, keeping the headers (including Content-Length) of the GET response
+    AutoHead,
+    // Serves successful responses to GET and HEAD requests from a cache, without
+    // calling the worker again until the configured TTL expires
+    ResponseCache(CacheConfig),
+    // Rewrites the Location header of redirect responses from internal URLs of the workers
+    // to the public ones, using the first matching rule
+    LocationRewrite(Vec<LocationRewriteRule>),
+    // Rejects requests whose headers (names and values) add up to more than the given
+    // number of bytes with 431 Request Header Fields Too Large, before any other middleware runs
+    MaxHeaderSize(usize),
+    // Converts request bodies of the configured content types (such as XML) to the JSON
+    // passed to the worker, and the JSON responses of the worker back. Bodies are buffered
+    // up to the configured size for the conversion
+    BodyTransform(BodyTransformConfig),
+    // Redirects requests which did not arrive over HTTPS to their https URL, or rejects them
+    // with 403 Forbidden, before any other middleware runs. Behind a proxy terminating TLS,
+    // the X-Forwarded-Proto header of the configured trusted proxies is used instead
+    RequireHttps(RequireHttps),
+    // Limits the number of requests of the route being executed at the same time. Requests over
+    // the limit wait up to the max_wait of the gateway's concurrency_limit configuration for
+    // another request to finish, and are then rejected with 503 Service Unavailable
+    Concurrency(usize),
+    // Rejects requests of clients not allowed by their IP address with 403 Forbidden, before
+    // any other middleware runs. Behind a proxy, the X-Forwarded-For header of the configured
+    // trusted proxies is used to find the address of the client
+    IpFilter(IpFilter),
+    // Adds the duration of the worker execution to the response as X-Response-Time and
+    // Server-Timing headers, with configurable names
+    ServerTiming(ServerTimingConfig),
 }
 
 impl HttpMiddleware {
@@ -13,36 +60,529 @@ impl HttpMiddleware {
         HttpMiddleware::Cors(cors)
     }
 
-    pub fn transform_response(&self, response: &mut poem::Response) {
+    pub fn auto_options() -> Self {
+        HttpMiddleware::AutoOptions
+    }
+
+    pub fn auto_head() -> Self {
+        HttpMiddleware::AutoHead
+    }
+
+    pub fn response_cache(config: CacheConfig) -> Self {
+        HttpMiddleware::ResponseCache(config)
+    }
+
+    pub fn location_rewrite(rules: Vec<LocationRewriteRule>) -> Self {
+        HttpMiddleware::LocationRewrite(rules)
+    }
+
+    pub fn max_header_size(max_size: usize) -> Self {
+        HttpMiddleware::MaxHeaderSize(max_size)
+    }
+
+    pub fn body_transform(config: BodyTransformConfig) -> Self {
+        HttpMiddleware::BodyTransform(config)
+    }
+
+    pub fn require_https(config: RequireHttpsConfig) -> Self {
+        HttpMiddleware::RequireHttps(config.into())
+    }
+
+    pub fn concurrency(limit: usize) -> Self {
+        HttpMiddleware::Concurrency(limit)
+    }
+
+    pub fn ip_filter(config: IpFilterConfig) -> Self {
+        HttpMiddleware::IpFilter(config.into())
+    }
+
+    pub fn server_timing(config: ServerTimingConfig) -> Self {
+        HttpMiddleware::ServerTiming(config)
+    }
+
+    // The key of the middleware in the OpenAPI extension of a route
+    pub fn name(&self) -> &'static str {
+        match self {
+            HttpMiddleware::Cors(_) => "cors",
+            HttpMiddleware::AutoOptions => "auto-options",
+            HttpMiddleware::AutoHead => "auto-head",
+            HttpMiddleware::ResponseCache(_) => "response-cache",
+            HttpMiddleware::LocationRewrite(_) => "location-rewrite",
+            HttpMiddleware::MaxHeaderSize(_) => "max-header-size",
+            HttpMiddleware::BodyTransform(_) => "body-transform",
+            HttpMiddleware::RequireHttps(_) => "require-https",
+            HttpMiddleware::Concurrency(_) => "concurrency",
+            HttpMiddleware::IpFilter(_) => "ip-filter",
+            HttpMiddleware::ServerTiming(_) => "server-timing",
+        }
+    }
+
+    // Whether the middleware needs the whole response body (which materializes a streaming body),
+    // as opposed to only updating the response headers. Header-only middlewares are applied
+    // in `transform_response` and never read the body, so the gateway can keep streaming
+    pub fn touches_body(&self) -> bool {
+        match self {
+            HttpMiddleware::Cors(_) => false,
+            HttpMiddleware::AutoOptions => false,
+            HttpMiddleware::AutoHead => false,
+            // Reads the full body to store it in the cache
+            HttpMiddleware::ResponseCache(_) => true,
+            HttpMiddleware::LocationRewrite(_) => false,
+            HttpMiddleware::MaxHeaderSize(_) => false,
+            // Reads the full request and response bodies to convert them
+            HttpMiddleware::BodyTransform(_) => true,
+            HttpMiddleware::RequireHttps(_) => false,
+            HttpMiddleware::Concurrency(_) => false,
+            HttpMiddleware::IpFilter(_) => false,
+            HttpMiddleware::ServerTiming(_) => false,
+        }
+    }
+
+    // Checks the incoming request before it reaches the worker. Returns the response
+    // to send instead if the middleware rejects the request
+    pub fn process_request(
+        &self,
+        request: &InputHttpRequest,
+        connection: &RequestConnection,
+    ) -> Option<poem::Response> {
+        match self {
+            HttpMiddleware::MaxHeaderSize(max_size) => {
+                Self::check_header_size(&request.headers, *max_size)
+            }
+            HttpMiddleware::RequireHttps(require_https) => require_https.process_request(
+                &request.headers,
+                &request.input_path.path_and_query(),
+                connection,
+            ),
+            HttpMiddleware::IpFilter(filter) => {
+                filter.process_request(&request.headers, connection)
+            }
+            HttpMiddleware::Cors(_)
+            | HttpMiddleware::AutoOptions
+            | HttpMiddleware::AutoHead
+            | HttpMiddleware::ResponseCache(_)
+            | HttpMiddleware::LocationRewrite(_)
+            | HttpMiddleware::BodyTransform(_) => None,
+            // Wrap the execution of the worker, which is done by the gateway
+            HttpMiddleware::Concurrency(_) | HttpMiddleware::ServerTiming(_) => None,
+        }
+    }
+
+    // Applies header-only transformations. Middlewares that touch the body
+    // are handled by the gateway outside of this pipeline
+    pub fn transform_response(
+        &self,
+        request_details: &GatewayRequestDetails,
+        response: &mut poem::Response,
+    ) {
         match self {
             // if CORS is applied as a middleware, we need to return a response with specific CORS headers
             HttpMiddleware::Cors(cors) => {
-                Self::apply_cors(response, cors);
+                let (origin, host, method) = match request_details {
+                    GatewayRequestDetails::Http(http) => (
+                        http.get_origin_header(),
+                        http.get_host_header(),
+                        http.get_request_method().map(|method| method.to_string()),
+                    ),
+                };
+                if cors.applies_to(origin.as_deref(), host.as_deref()) {
+                    Self::apply_cors(response, cors, origin.as_deref(), method.as_deref());
+                }
             }
+            HttpMiddleware::AutoOptions => {}
+            HttpMiddleware::AutoHead => {}
+            HttpMiddleware::ResponseCache(_) => {}
+            HttpMiddleware::LocationRewrite(rules) => Self::apply_location_rewrite(response, rules),
+            HttpMiddleware::MaxHeaderSize(_) => {}
+            HttpMiddleware::BodyTransform(_) => {}
+            HttpMiddleware::RequireHttps(_) => {}
+            HttpMiddleware::Concurrency(_) => {}
+            HttpMiddleware::IpFilter(_) => {}
+            // Added by the gateway, which measures the execution of the worker
+            HttpMiddleware::ServerTiming(_) => {}
         }
     }
 
-    fn apply_cors(response: &mut poem::Response, cors: &Cors) {
-        response.headers_mut().insert(
-            ACCESS_CONTROL_ALLOW_ORIGIN,
-            // hot path, and this unwrap will not fail unless we bypassed it during configuration
-            cors.get_allow_origin().clone().parse().unwrap(),
-        );
+    fn check_header_size(headers: &HeaderMap, max_size: usize) -> Option<poem::Response> {
+        let size: usize = headers
+            .iter()
+            .map(|(name, value)| name.as_str().len() + value.len())
+            .sum();
+
+        (size > max_size).then(|| {
+            poem::Response::builder()
+                .status(StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE)
+                .body(Body::from_string(format!(
+                    "Request headers too large: {size} bytes, the limit is {max_size} bytes"
+                )))
+        })
+    }
+
+    fn apply_location_rewrite(response: &mut poem::Response, rules: &[LocationRewriteRule]) {
+        if !response.status().is_redirection() {
+            return;
+        }
+
+        let rewritten = response
+            .headers()
+            .get(LOCATION)
+            .and_then(|location| location.to_str().ok())
+            .and_then(|location| rewrite_location(rules, location))
+            .and_then(|location| location.parse().ok());
+
+        if let Some(location) = rewritten {
+            response.headers_mut().insert(LOCATION, location);
+        }
+    }
+
+    // A method with a policy only gets CORS headers for the origins allowed by the policy, the
+    // same as its preflight requests
+    fn apply_cors(
+        response: &mut poem::Response,
+        cors: &Cors,
+        origin: Option<&str>,
+        method: Option<&str>,
+    ) {
+        let allow_origin = match cors.get_method_policy(method) {
+            Some(policy) => match policy.get_allow_origin_for(origin) {
+                Some(allow_origin) => allow_origin,
+                None => return,
+            },
+            None => cors.get_allow_origin_for(origin),
+        };
+
+        insert_cors_header(response, ACCESS_CONTROL_ALLOW_ORIGIN, &allow_origin);
 
         if let Some(allow_credentials) = &cors.get_allow_credentials() {
-            response.headers_mut().insert(
+            insert_cors_header(
+                response,
                 ACCESS_CONTROL_ALLOW_CREDENTIALS,
-                // hot path, and this unwrap will not fail unless we bypassed it during configuration
-                allow_credentials.to_string().clone().parse().unwrap(),
+                &allow_credentials.to_string(),
             );
         }
 
-        if let Some(expose_headers) = &cors.get_expose_headers() {
-            response.headers_mut().insert(
-                ACCESS_CONTROL_EXPOSE_HEADERS,
-                // hot path, and this unwrap will not fail unless we bypassed it during configuration
-                expose_headers.clone().parse().unwrap(),
-            );
+        if let Some(expose_headers) = &cors.get_expose_headers_for_origin(origin) {
+            insert_cors_header(response, ACCESS_CONTROL_EXPOSE_HEADERS, expose_headers);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::gateway_binding::{GatewayRequestDetails, HttpRequestDetails};
+    use crate::gateway_middleware::{
+        BodyTransformConfig, BodyTransformerKind, CacheConfig, Cors, CorsMethodPolicy,
+        HttpMiddleware, HttpsPolicy, IpFilterConfig, LocationRewriteRule, Middleware, Middlewares,
+        RequestConnection, RequireHttpsConfig, ServerTimingConfig,
+    };
+    use crate::gateway_request::http_request::{ApiInputPath, InputHttpRequest};
+    use futures_util::stream;
+    use http::header::{
+        ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_ORIGIN, LOCATION, ORIGIN,
+    };
+    use http::{HeaderMap, HeaderValue, Method, StatusCode};
+    use poem::Body;
+    use std::collections::HashMap;
+
+    fn request(headers: HeaderMap) -> InputHttpRequest {
+        InputHttpRequest {
+            input_path: ApiInputPath {
+                base_path: "/items".to_string(),
+                query_path: None,
+            },
+            headers,
+            req_method: Method::GET,
+            req_body: serde_json::Value::Null,
         }
     }
+
+    #[test]
+    fn header_only_middlewares_do_not_touch_body() {
+        assert!(!HttpMiddleware::cors(Cors::default()).touches_body());
+        assert!(!HttpMiddleware::auto_options().touches_body());
+        assert!(!HttpMiddleware::auto_head().touches_body());
+        assert!(!HttpMiddleware::location_rewrite(vec![]).touches_body());
+        assert!(!HttpMiddleware::max_header_size(8192).touches_body());
+        assert!(!HttpMiddleware::concurrency(16).touches_body());
+        assert!(!HttpMiddleware::server_timing(ServerTimingConfig::default()).touches_body());
+        assert!(!HttpMiddleware::require_https(RequireHttpsConfig {
+            policy: HttpsPolicy::Redirect,
+            trusted_proxies: vec![],
+        })
+        .touches_body());
+        assert!(HttpMiddleware::response_cache(CacheConfig {
+            ttl_seconds: 60,
+            max_entry_size: 1024,
+            query_params: vec![],
+            headers: vec![],
+        })
+        .touches_body());
+        assert!(HttpMiddleware::body_transform(BodyTransformConfig {
+            transformer: BodyTransformerKind::XmlJson,
+            content_types: vec!["application/xml".to_string()],
+            max_body_size: 1024,
+        })
+        .touches_body());
+    }
+
+    #[test]
+    async fn cors_keeps_streaming_body() {
+        let middlewares = Middlewares(vec![Middleware::cors(&Cors::default())]);
+        assert!(!middlewares.touches_body());
+
+        let chunks = stream::iter(vec![
+            Ok::<_, std::io::Error>(bytes::Bytes::from("hello ")),
+            Ok(bytes::Bytes::from("world")),
+        ]);
+        let mut response = poem::Response::builder().body(Body::from_bytes_stream(chunks));
+
+        middlewares.transform_http_response(
+            &GatewayRequestDetails::Http(HttpRequestDetails::empty()),
+            &mut response,
+        );
+
+        assert!(response.headers().contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+        assert_eq!(
+            response.into_body().into_string().await.unwrap(),
+            "hello world"
+        );
+    }
+
+    #[test]
+    fn cors_skips_invalid_header_values() {
+        // Deserialized without validation, as definitions stored before it was added may be
+        let cors: Cors = serde_json::from_value(serde_json::json!({
+            "allowOrigin": "https://app.example.com\n",
+            "allowMethods": "GET",
+            "allowHeaders": "Content-Type",
+            "allowCredentials": true
+        }))
+        .unwrap();
+        let middlewares = Middlewares(vec![Middleware::cors(&cors)]);
+
+        let mut response = poem::Response::builder().finish();
+        middlewares.transform_http_response(
+            &GatewayRequestDetails::Http(HttpRequestDetails::empty()),
+            &mut response,
+        );
+
+        assert!(!response.headers().contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+        assert_eq!(
+            response
+                .headers()
+                .get(ACCESS_CONTROL_ALLOW_CREDENTIALS)
+                .unwrap(),
+            "true"
+        );
+    }
+
+    #[test]
+    fn cors_of_method_with_policy() {
+        let mut cors = Cors::default();
+        cors.set_method_policies(vec![CorsMethodPolicy {
+            method: "DELETE".to_string(),
+            allow_origins: vec!["https://admin.example.com".to_string()],
+            allow_headers: None,
+        }])
+        .unwrap();
+        let middlewares = Middlewares(vec![Middleware::cors(&cors)]);
+
+        let request_details = |origin: &str, method: Method| {
+            let mut headers = HeaderMap::new();
+            headers.insert(ORIGIN, HeaderValue::from_str(origin).unwrap());
+            GatewayRequestDetails::from(
+                &HashMap::new(),
+                &HashMap::new(),
+                &[],
+                &serde_json::Value::Null,
+                &headers,
+            )
+            .unwrap()
+            .with_request_method(&method)
+        };
+
+        let mut allowed = poem::Response::builder().finish();
+        middlewares.transform_http_response(
+            &request_details("https://admin.example.com", Method::DELETE),
+            &mut allowed,
+        );
+        assert_eq!(
+            allowed.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+            "https://admin.example.com"
+        );
+
+        let mut rejected = poem::Response::builder().finish();
+        middlewares.transform_http_response(
+            &request_details("https://app.example.com", Method::DELETE),
+            &mut rejected,
+        );
+        assert!(!rejected.headers().contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+
+        let mut without_policy = poem::Response::builder().finish();
+        middlewares.transform_http_response(
+            &request_details("https://app.example.com", Method::GET),
+            &mut without_policy,
+        );
+        assert_eq!(
+            without_policy
+                .headers()
+                .get(ACCESS_CONTROL_ALLOW_ORIGIN)
+                .unwrap(),
+            "*"
+        );
+    }
+
+    #[test]
+    fn location_rewrite_only_applies_to_redirects() {
+        let middleware = HttpMiddleware::location_rewrite(vec![LocationRewriteRule {
+            from: "/api".to_string(),
+            to: "https://example.com/public-api".to_string(),
+        }]);
+        let request_details = GatewayRequestDetails::Http(HttpRequestDetails::empty());
+
+        let mut redirect = poem::Response::builder()
+            .status(StatusCode::FOUND)
+            .header(LOCATION, "/api/items/1")
+            .finish();
+        middleware.transform_response(&request_details, &mut redirect);
+        assert_eq!(
+            redirect.headers().get(LOCATION).unwrap(),
+            "https://example.com/public-api/items/1"
+        );
+
+        let mut created = poem::Response::builder()
+            .status(StatusCode::CREATED)
+            .header(LOCATION, "/api/items/1")
+            .finish();
+        middleware.transform_response(&request_details, &mut created);
+        assert_eq!(created.headers().get(LOCATION).unwrap(), "/api/items/1");
+    }
+
+    #[test]
+    fn max_header_size_rejects_large_headers() {
+        let mut headers = HeaderMap::new();
+        // 4 bytes of name and 16 bytes of value
+        headers.insert("host", HeaderValue::from_static("example.com:9006"));
+
+        let request = request(headers);
+        let connection = RequestConnection::default();
+
+        assert!(HttpMiddleware::max_header_size(20)
+            .process_request(&request, &connection)
+            .is_none());
+
+        let response = HttpMiddleware::max_header_size(19)
+            .process_request(&request, &connection)
+            .unwrap();
+        assert_eq!(
+            response.status(),
+            StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE
+        );
+    }
+
+    #[test]
+    fn max_header_size_runs_before_other_middlewares() {
+        let middlewares = Middlewares(vec![
+            Middleware::cors(&Cors::default()),
+            Middleware::http(HttpMiddleware::max_header_size(1)),
+        ]);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("host", HeaderValue::from_static("example.com"));
+
+        let response = middlewares
+            .process_http_request(&request(headers), &RequestConnection::default())
+            .unwrap();
+        assert_eq!(
+            response.status(),
+            StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE
+        );
+        assert!(!response.headers().contains_key(ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+
+    #[test]
+    fn require_https_runs_before_the_header_size_limit() {
+        let middlewares = Middlewares(vec![
+            Middleware::http(HttpMiddleware::max_header_size(1)),
+            Middleware::http(HttpMiddleware::require_https(RequireHttpsConfig {
+                policy: HttpsPolicy::Redirect,
+                trusted_proxies: vec![],
+            })),
+        ]);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("host", HeaderValue::from_static("example.com"));
+        let request = request(headers);
+
+        let response = middlewares
+            .process_http_request(&request, &RequestConnection::default())
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+        assert_eq!(
+            response.headers().get(LOCATION).unwrap(),
+            "https://example.com/items"
+        );
+
+        let https = RequestConnection {
+            https: true,
+            peer: None,
+        };
+        let response = middlewares.process_http_request(&request, &https).unwrap();
+        assert_eq!(
+            response.status(),
+            StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE
+        );
+    }
+
+    #[test]
+    fn describe_lists_middlewares_in_execution_order() {
+        let middlewares = Middlewares(vec![
+            Middleware::http(HttpMiddleware::location_rewrite(vec![])),
+            Middleware::cors(&Cors::default()),
+            Middleware::http(HttpMiddleware::response_cache(CacheConfig {
+                ttl_seconds: 60,
+                max_entry_size: 1024,
+                query_params: vec![],
+                headers: vec![],
+            })),
+            Middleware::http(HttpMiddleware::auto_head()),
+            Middleware::http(HttpMiddleware::max_header_size(8192)),
+            Middleware::http(HttpMiddleware::require_https(RequireHttpsConfig {
+                policy: HttpsPolicy::Reject,
+                trusted_proxies: vec![],
+            })),
+            Middleware::http(HttpMiddleware::body_transform(BodyTransformConfig {
+                transformer: BodyTransformerKind::XmlJson,
+                content_types: vec!["application/xml".to_string()],
+                max_body_size: 2048,
+            })),
+            Middleware::http(HttpMiddleware::concurrency(16)),
+            Middleware::http(HttpMiddleware::ip_filter(IpFilterConfig {
+                allow: vec!["10.0.0.0/8".to_string()],
+                deny: vec![],
+                trusted_proxies: vec![],
+            })),
+            Middleware::http(HttpMiddleware::server_timing(ServerTimingConfig::default())),
+        ]);
+
+        assert_eq!(
+            middlewares.describe(),
+            vec![
+                "request: body-transform (xml-json, up to 2048 bytes)",
+                "routing: auto-head",
+                "request: ip-filter (1 allowed, 0 denied)",
+                "request: require-https (reject)",
+                "request: max-header-size (8192 bytes)",
+                "request: response-cache lookup (ttl 60s)",
+                "request: concurrency (up to 16 in flight)",
+                "response: location-rewrite (0 rules)",
+                "response: cors",
+                "response: body-transform (xml-json)",
+                "response: response-cache store (up to 1024 bytes)",
+                "response: server-timing (app)",
+            ]
+        );
+    }
 }
@@ -0,0 +1,451 @@
+use std::fmt::Display;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use http::{HeaderMap, StatusCode};
+use poem::Body;
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+
+use crate::gateway_middleware::http::require_https::RequestConnection;
+
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+
+// Allows or denies requests by the IP address of the client. The lists contain IPv4 or IPv6
+// CIDRs (such as 10.0.0.0/8 or 2001:db8::/32), or single addresses. A client matching the deny
+// list is rejected with 403 Forbidden, even if it also matches the allow list. If the allow list
+// is not empty, clients not matching it are rejected as well.
+//
+// The client address is the address of the connection, unless that is one of the trusted
+// proxies, in which case X-Forwarded-For is read from the right, skipping the trusted proxies,
+// and the first other address is the client. The entries on its left could have been sent by
+// the client itself, so only list proxies which append the address they got the request from
+// to X-Forwarded-For, otherwise clients can spoof their address through the header.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct IpFilterConfig {
+    #[serde(default)]
+    #[oai(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    #[oai(default)]
+    pub deny: Vec<String>,
+    // CIDRs or addresses of the proxies in front of the gateway whose X-Forwarded-For is trusted
+    #[serde(default)]
+    #[oai(default)]
+    pub trusted_proxies: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum IpFilterConfigError {
+    #[error("Invalid IP filter entry {value:?}, expected an IP address or CIDR")]
+    InvalidCidr { value: String },
+    #[error("The IP filter has neither allowed nor denied addresses")]
+    NoRules,
+}
+
+// An IPv4 or IPv6 network, such as 10.0.0.0/8. A single address is a network of its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpCidr {
+    address: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpCidr {
+    pub fn contains(&self, address: &IpAddr) -> bool {
+        match (self.address, address.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(address)) => {
+                prefix_matches(&network.octets(), &address.octets(), self.prefix_len)
+            }
+            (IpAddr::V6(network), IpAddr::V6(address)) => {
+                prefix_matches(&network.octets(), &address.octets(), self.prefix_len)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn prefix_matches(network: &[u8], address: &[u8], prefix_len: u8) -> bool {
+    let full_bytes = (prefix_len / 8) as usize;
+    let remaining_bits = prefix_len % 8;
+
+    if network[..full_bytes] != address[..full_bytes] {
+        return false;
+    }
+    if remaining_bits == 0 {
+        return true;
+    }
+    let mask = 0xffu8 << (8 - remaining_bits);
+    network[full_bytes] & mask == address[full_bytes] & mask
+}
+
+impl FromStr for IpCidr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (address, prefix_len) = match s.trim().split_once('/') {
+            Some((address, prefix_len)) => (address, Some(prefix_len)),
+            None => (s.trim(), None),
+        };
+
+        let address = address
+            .parse::<IpAddr>()
+            .map_err(|_| format!("Invalid IP address in {s}"))?
+            .to_canonical();
+        let max_prefix_len = match address {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        let prefix_len = match prefix_len {
+            Some(prefix_len) => prefix_len
+                .parse::<u8>()
+                .ok()
+                .filter(|prefix_len| *prefix_len <= max_prefix_len)
+                .ok_or_else(|| format!("Invalid prefix length in {s}"))?,
+            None => max_prefix_len,
+        };
+
+        Ok(IpCidr {
+            address,
+            prefix_len,
+        })
+    }
+}
+
+impl Display for IpCidr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.address, self.prefix_len)
+    }
+}
+
+// The proxies in front of the gateway whose forwarding headers (X-Forwarded-For and
+// X-Forwarded-Proto) are trusted, given as CIDRs or single addresses. Shared by the middlewares
+// reading such headers, so that they agree on which proxies are trusted
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TrustedProxies(Vec<IpCidr>);
+
+impl TrustedProxies {
+    // Skips invalid entries, like the lists of the IP filter
+    pub fn new(values: &[String]) -> Self {
+        TrustedProxies(parse_cidrs(values))
+    }
+
+    // The first entry which is neither an IP address nor a CIDR
+    pub fn find_invalid(values: &[String]) -> Option<&String> {
+        values.iter().find(|value| value.parse::<IpCidr>().is_err())
+    }
+
+    pub fn contains(&self, address: &IpAddr) -> bool {
+        self.0.iter().any(|cidr| cidr.contains(address))
+    }
+}
+
+impl IpFilterConfig {
+    pub fn validate(&self) -> Result<(), IpFilterConfigError> {
+        if self.allow.is_empty() && self.deny.is_empty() {
+            return Err(IpFilterConfigError::NoRules);
+        }
+
+        match self
+            .allow
+            .iter()
+            .chain(&self.deny)
+            .find(|value| value.parse::<IpCidr>().is_err())
+            .or_else(|| TrustedProxies::find_invalid(&self.trusted_proxies))
+        {
+            Some(invalid) => Err(IpFilterConfigError::InvalidCidr {
+                value: invalid.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+// An IP filter with its lists parsed once, as run by the gateway for every request
+#[derive(Debug, Clone, PartialEq)]
+pub struct IpFilter {
+    config: IpFilterConfig,
+    allow: Vec<IpCidr>,
+    deny: Vec<IpCidr>,
+    trusted_proxies: TrustedProxies,
+}
+
+impl IpFilter {
+    pub fn config(&self) -> &IpFilterConfig {
+        &self.config
+    }
+
+    // The address of the client, taken from X-Forwarded-For if the request came from a trusted
+    // proxy. None if the address of the connection is not known, or if X-Forwarded-For has an
+    // invalid entry before the first address not of a trusted proxy (reading from the right), as
+    // the client cannot be identified then
+    pub fn client_address(
+        &self,
+        headers: &HeaderMap,
+        connection: &RequestConnection,
+    ) -> Option<IpAddr> {
+        let peer = connection.peer?.to_canonical();
+        if !self.is_trusted(&peer) {
+            return Some(peer);
+        }
+
+        let forwarded: Vec<&str> = headers
+            .get_all(X_FORWARDED_FOR)
+            .iter()
+            .map(|value| value.to_str().ok())
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .flat_map(|value| value.split(','))
+            .collect();
+
+        // If every forwarded address is a trusted proxy, the leftmost one is the client
+        let mut client = peer;
+        for address in forwarded.iter().rev() {
+            client = address.trim().parse::<IpAddr>().ok()?.to_canonical();
+            if !self.is_trusted(&client) {
+                break;
+            }
+        }
+        Some(client)
+    }
+
+    pub fn is_allowed(&self, address: &IpAddr) -> bool {
+        let denied = self.deny.iter().any(|cidr| cidr.contains(address));
+        let allowed = self.allow.is_empty() || self.allow.iter().any(|cidr| cidr.contains(address));
+
+        allowed && !denied
+    }
+
+    fn is_trusted(&self, address: &IpAddr) -> bool {
+        self.trusted_proxies.contains(address)
+    }
+
+    // Returns the response rejecting the request if its client is not allowed. Clients whose
+    // address is not known are rejected as well
+    pub fn process_request(
+        &self,
+        headers: &HeaderMap,
+        connection: &RequestConnection,
+    ) -> Option<poem::Response> {
+        let allowed = self
+            .client_address(headers, connection)
+            .is_some_and(|address| self.is_allowed(&address));
+
+        (!allowed).then(|| {
+            poem::Response::builder()
+                .status(StatusCode::FORBIDDEN)
+                .body(Body::from_string(
+                    "Access denied for the client address".to_string(),
+                ))
+        })
+    }
+}
+
+impl From<IpFilterConfig> for IpFilter {
+    fn from(config: IpFilterConfig) -> Self {
+        IpFilter {
+            allow: parse_cidrs(&config.allow),
+            deny: parse_cidrs(&config.deny),
+            trusted_proxies: TrustedProxies::new(&config.trusted_proxies),
+            config,
+        }
+    }
+}
+
+// Invalid entries are rejected when the API definition is validated, and skipped here
+fn parse_cidrs(values: &[String]) -> Vec<IpCidr> {
+    values
+        .iter()
+        .filter_map(|value| value.parse().ok())
+        .collect()
+}
+
+impl From<golem_api_grpc::proto::golem::apidefinition::IpFilter> for IpFilterConfig {
+    fn from(value: golem_api_grpc::proto::golem::apidefinition::IpFilter) -> Self {
+        IpFilterConfig {
+            allow: value.allow,
+            deny: value.deny,
+            trusted_proxies: value.trusted_proxies,
+        }
+    }
+}
+
+impl From<IpFilterConfig> for golem_api_grpc::proto::golem::apidefinition::IpFilter {
+    fn from(value: IpFilterConfig) -> Self {
+        golem_api_grpc::proto::golem::apidefinition::IpFilter {
+            allow: value.allow,
+            deny: value.deny,
+            trusted_proxies: value.trusted_proxies,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::*;
+    use http::HeaderValue;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    fn config(allow: &[&str], deny: &[&str]) -> IpFilterConfig {
+        IpFilterConfig {
+            allow: strings(allow),
+            deny: strings(deny),
+            trusted_proxies: strings(&["10.0.0.1", "fd00::/8"]),
+        }
+    }
+
+    fn filter(allow: &[&str], deny: &[&str]) -> IpFilter {
+        config(allow, deny).into()
+    }
+
+    fn connection(peer: &str) -> RequestConnection {
+        RequestConnection {
+            https: false,
+            peer: Some(peer.parse().unwrap()),
+        }
+    }
+
+    fn forwarded_for(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(X_FORWARDED_FOR, HeaderValue::from_static(value));
+        headers
+    }
+
+    fn address(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn cidrs_match_ipv4_and_ipv6_addresses() {
+        let cidr: IpCidr = "192.168.0.0/23".parse().unwrap();
+        assert!(cidr.contains(&address("192.168.1.200")));
+        assert!(!cidr.contains(&address("192.168.2.1")));
+        // IPv4-mapped IPv6 addresses are IPv4 addresses
+        assert!(cidr.contains(&address("::ffff:192.168.0.1")));
+
+        let cidr: IpCidr = "2001:db8::/32".parse().unwrap();
+        assert!(cidr.contains(&address("2001:db8:1::1")));
+        assert!(!cidr.contains(&address("2001:db9::1")));
+        assert!(!cidr.contains(&address("192.168.0.1")));
+
+        let single: IpCidr = "10.0.0.1".parse().unwrap();
+        assert_eq!(single.to_string(), "10.0.0.1/32");
+        assert!(single.contains(&address("10.0.0.1")));
+        assert!(!single.contains(&address("10.0.0.2")));
+
+        let any: IpCidr = "0.0.0.0/0".parse().unwrap();
+        assert!(any.contains(&address("203.0.113.7")));
+
+        assert!("10.0.0.0/33".parse::<IpCidr>().is_err());
+        assert!("2001:db8::/129".parse::<IpCidr>().is_err());
+        assert!("example.com/8".parse::<IpCidr>().is_err());
+    }
+
+    #[test]
+    fn deny_list_wins_over_allow_list() {
+        let config = filter(&["192.168.0.0/16"], &["192.168.1.0/24"]);
+
+        assert!(config
+            .process_request(&HeaderMap::new(), &connection("192.168.2.1"))
+            .is_none());
+
+        let denied = config
+            .process_request(&HeaderMap::new(), &connection("192.168.1.1"))
+            .unwrap();
+        assert_eq!(denied.status(), StatusCode::FORBIDDEN);
+
+        let not_allowed = config
+            .process_request(&HeaderMap::new(), &connection("203.0.113.7"))
+            .unwrap();
+        assert_eq!(not_allowed.status(), StatusCode::FORBIDDEN);
+
+        // Without an allow list, every client not denied is allowed
+        let config = self::filter(&[], &["2001:db8::/32"]);
+        assert!(config
+            .process_request(&HeaderMap::new(), &connection("2001:db9::1"))
+            .is_none());
+        assert!(config
+            .process_request(&HeaderMap::new(), &connection("2001:db8::1"))
+            .is_some());
+        assert!(config
+            .process_request(&HeaderMap::new(), &RequestConnection::default())
+            .is_some());
+    }
+
+    #[test]
+    fn forwarded_for_is_only_trusted_from_trusted_proxies() {
+        let config = filter(&["192.168.0.0/16"], &[]);
+        let headers = forwarded_for("192.168.0.1");
+
+        assert_eq!(
+            config.client_address(&headers, &connection("10.0.0.1")),
+            Some(address("192.168.0.1"))
+        );
+        assert_eq!(
+            config.client_address(&headers, &connection("203.0.113.7")),
+            Some(address("203.0.113.7"))
+        );
+        assert!(config
+            .process_request(&headers, &connection("203.0.113.7"))
+            .is_some());
+    }
+
+    #[test]
+    fn forwarded_for_is_read_from_the_right() {
+        let config = filter(&["192.168.0.0/16"], &[]);
+
+        // The client claims to be 192.168.0.1, the trusted proxies saw 203.0.113.7
+        let headers = forwarded_for("192.168.0.1, 203.0.113.7, fd00::2");
+        assert_eq!(
+            config.client_address(&headers, &connection("10.0.0.1")),
+            Some(address("203.0.113.7"))
+        );
+        assert!(config
+            .process_request(&headers, &connection("10.0.0.1"))
+            .is_some());
+
+        let headers = forwarded_for("10.0.0.1");
+        assert_eq!(
+            config.client_address(&headers, &connection("fd00::1")),
+            Some(address("10.0.0.1"))
+        );
+
+        // The client cannot be identified past an invalid entry, and is rejected
+        let headers = forwarded_for("192.168.0.1, unknown");
+        assert_eq!(
+            config.client_address(&headers, &connection("10.0.0.1")),
+            None
+        );
+        assert!(config
+            .process_request(&headers, &connection("10.0.0.1"))
+            .is_some());
+
+        // Invalid entries on the left of the client were not added by a trusted proxy
+        let headers = forwarded_for("unknown, 203.0.113.7, fd00::2");
+        assert_eq!(
+            config.client_address(&headers, &connection("10.0.0.1")),
+            Some(address("203.0.113.7"))
+        );
+    }
+
+    #[test]
+    fn entries_must_be_addresses_or_cidrs() {
+        assert!(config(&["192.168.0.0/16"], &["::1"]).validate().is_ok());
+        assert_eq!(
+            config(&[], &[]).validate(),
+            Err(IpFilterConfigError::NoRules)
+        );
+        assert_eq!(
+            config(&["192.168.0.0/16"], &["localhost"]).validate(),
+            Err(IpFilterConfigError::InvalidCidr {
+                value: "localhost".to_string()
+            })
+        );
+    }
+}
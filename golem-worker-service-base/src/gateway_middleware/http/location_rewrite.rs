@@ -0,0 +1,167 @@
+use http::Uri;
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct LocationRewriteRule {
+    // Prefix of the Location header to rewrite. Either an internal URL such as
+    // `http://worker.internal:8080/api`, or a path such as `/api` matching relative locations
+    pub from: String,
+    // The public URL (or path) replacing `from`, such as `https://example.com/public-api`
+    pub to: String,
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum LocationRewriteConfigError {
+    #[error("Invalid location rewrite rule {value:?}: it must be a path starting with '/' or an absolute URL such as https://example.com")]
+    InvalidPrefix { value: String },
+}
+
+impl LocationRewriteRule {
+    pub fn validate(&self) -> Result<(), LocationRewriteConfigError> {
+        Self::validate_prefix(&self.from)?;
+        Self::validate_prefix(&self.to)
+    }
+
+    fn validate_prefix(value: &str) -> Result<(), LocationRewriteConfigError> {
+        let valid = if is_path(value) {
+            true
+        } else {
+            matches!(value.parse::<Uri>(), Ok(uri) if uri.scheme().is_some() && uri.host().is_some())
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(LocationRewriteConfigError::InvalidPrefix {
+                value: value.to_string(),
+            })
+        }
+    }
+
+    // Matches `from` only on a boundary of the location, so that `http://internal` does not
+    // rewrite `http://internal.example.com`, and `/api` does not rewrite `/apis`
+    fn rewrite(&self, location: &str) -> Option<String> {
+        let rest = location.strip_prefix(&self.from)?;
+        let on_boundary =
+            rest.is_empty() || self.from.ends_with('/') || rest.starts_with(['/', '?', '#']);
+
+        on_boundary.then(|| format!("{}{}", self.to, rest))
+    }
+}
+
+// Rewrites the Location header of a redirect with the first matching rule. Locations not
+// matching any rule, such as redirects to external sites, are kept as they are
+pub fn rewrite_location(rules: &[LocationRewriteRule], location: &str) -> Option<String> {
+    // Protocol-relative locations (`//host/path`) are absolute, and never match path rules
+    let location_is_path = is_path(location);
+
+    rules
+        .iter()
+        .filter(|rule| is_path(&rule.from) == location_is_path)
+        .find_map(|rule| rule.rewrite(location))
+}
+
+fn is_path(value: &str) -> bool {
+    value.starts_with('/') && !value.starts_with("//")
+}
+
+impl From<golem_api_grpc::proto::golem::apidefinition::LocationRewriteRule>
+    for LocationRewriteRule
+{
+    fn from(value: golem_api_grpc::proto::golem::apidefinition::LocationRewriteRule) -> Self {
+        LocationRewriteRule {
+            from: value.from,
+            to: value.to,
+        }
+    }
+}
+
+impl From<LocationRewriteRule>
+    for golem_api_grpc::proto::golem::apidefinition::LocationRewriteRule
+{
+    fn from(value: LocationRewriteRule) -> Self {
+        golem_api_grpc::proto::golem::apidefinition::LocationRewriteRule {
+            from: value.from,
+            to: value.to,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use crate::gateway_middleware::{
+        rewrite_location, LocationRewriteConfigError, LocationRewriteRule,
+    };
+
+    fn rules() -> Vec<LocationRewriteRule> {
+        vec![
+            LocationRewriteRule {
+                from: "http://worker.internal:8080/api".to_string(),
+                to: "https://example.com/public-api".to_string(),
+            },
+            LocationRewriteRule {
+                from: "/api".to_string(),
+                to: "/public-api".to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn rewrites_internal_url() {
+        assert_eq!(
+            rewrite_location(&rules(), "http://worker.internal:8080/api/items/1?x=1"),
+            Some("https://example.com/public-api/items/1?x=1".to_string())
+        );
+        assert_eq!(
+            rewrite_location(&rules(), "http://worker.internal:8080/api"),
+            Some("https://example.com/public-api".to_string())
+        );
+    }
+
+    #[test]
+    fn rewrites_relative_location() {
+        assert_eq!(
+            rewrite_location(&rules(), "/api/items/1"),
+            Some("/public-api/items/1".to_string())
+        );
+    }
+
+    #[test]
+    fn keeps_locations_not_matching_on_a_boundary() {
+        assert_eq!(rewrite_location(&rules(), "/apis/items"), None);
+        assert_eq!(
+            rewrite_location(&rules(), "http://worker.internal:8080/api-v2"),
+            None
+        );
+    }
+
+    #[test]
+    fn keeps_external_locations() {
+        assert_eq!(
+            rewrite_location(&rules(), "https://other.com/api/items"),
+            None
+        );
+        assert_eq!(rewrite_location(&rules(), "//other.com/api/items"), None);
+    }
+
+    #[test]
+    fn validates_rules() {
+        assert!(rules().iter().all(|rule| rule.validate().is_ok()));
+
+        let rule = LocationRewriteRule {
+            from: "worker.internal/api".to_string(),
+            to: "/public-api".to_string(),
+        };
+        assert_eq!(
+            rule.validate(),
+            Err(LocationRewriteConfigError::InvalidPrefix {
+                value: "worker.internal/api".to_string()
+            })
+        );
+    }
+}
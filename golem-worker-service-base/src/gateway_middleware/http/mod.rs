@@ -1,5 +1,17 @@
+pub use body_transform::*;
 pub use cors::*;
 pub use http_middleware::*;
+pub use ip_filter::*;
+pub use location_rewrite::*;
+pub use require_https::*;
+pub use response_cache::*;
+pub use server_timing::*;
 
+mod body_transform;
 mod cors;
 mod http_middleware;
+mod ip_filter;
+mod location_rewrite;
+mod require_https;
+mod response_cache;
+mod server_timing;
@@ -0,0 +1,311 @@
+use std::fmt::Display;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use http::header::HOST;
+use http::uri::Authority;
+use http::{HeaderMap, StatusCode};
+use poem::Body;
+use poem_openapi::{Enum, Object};
+use serde::{Deserialize, Serialize};
+
+use crate::gateway_middleware::http::ip_filter::TrustedProxies;
+
+const X_FORWARDED_PROTO: &str = "x-forwarded-proto";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct RequireHttpsConfig {
+    pub policy: HttpsPolicy,
+    // CIDRs or addresses of the proxies in front of the gateway which terminate TLS. Only their
+    // X-Forwarded-Proto header is trusted, so that other clients cannot claim to use HTTPS
+    #[serde(default)]
+    #[oai(default)]
+    pub trusted_proxies: Vec<String>,
+}
+
+// What the gateway answers to requests which did not arrive over HTTPS
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "kebab-case")]
+#[oai(rename_all = "kebab-case")]
+pub enum HttpsPolicy {
+    // 301 Moved Permanently to the same URL with the https scheme
+    Redirect,
+    // 403 Forbidden
+    Reject,
+}
+
+impl Display for HttpsPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HttpsPolicy::Redirect => write!(f, "redirect"),
+            HttpsPolicy::Reject => write!(f, "reject"),
+        }
+    }
+}
+
+impl FromStr for HttpsPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "redirect" => Ok(HttpsPolicy::Redirect),
+            "reject" => Ok(HttpsPolicy::Reject),
+            _ => Err(format!("Unknown HTTPS policy {s}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum RequireHttpsConfigError {
+    #[error("Invalid trusted proxy {value:?}, expected an IP address or CIDR")]
+    InvalidTrustedProxy { value: String },
+}
+
+// How the request reached the gateway, as opposed to what its headers claim
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RequestConnection {
+    // Whether the connection of the client to the gateway itself uses TLS
+    pub https: bool,
+    pub peer: Option<IpAddr>,
+}
+
+impl RequestConnection {
+    pub fn from_request(request: &poem::Request) -> Self {
+        RequestConnection {
+            https: request.scheme() == &http::uri::Scheme::HTTPS,
+            peer: request
+                .remote_addr()
+                .as_socket_addr()
+                .map(|address| address.ip()),
+        }
+    }
+}
+
+impl RequireHttpsConfig {
+    pub fn validate(&self) -> Result<(), RequireHttpsConfigError> {
+        match TrustedProxies::find_invalid(&self.trusted_proxies) {
+            Some(invalid) => Err(RequireHttpsConfigError::InvalidTrustedProxy {
+                value: invalid.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+// The HTTPS requirement with its trusted proxies parsed once, as run by the gateway for every
+// request
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequireHttps {
+    config: RequireHttpsConfig,
+    trusted_proxies: TrustedProxies,
+}
+
+impl RequireHttps {
+    pub fn config(&self) -> &RequireHttpsConfig {
+        &self.config
+    }
+
+    // Whether the client used HTTPS, either to connect to the gateway, or to connect to a
+    // trusted proxy which reports it in X-Forwarded-Proto. Only the last value is read, the one
+    // added by the trusted proxy itself, as the ones before it could have been sent by the client
+    pub fn is_https(&self, headers: &HeaderMap, connection: &RequestConnection) -> bool {
+        if connection.https {
+            return true;
+        }
+
+        let trusted = connection
+            .peer
+            .is_some_and(|peer| self.trusted_proxies.contains(&peer));
+
+        trusted
+            && headers
+                .get_all(X_FORWARDED_PROTO)
+                .iter()
+                .last()
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.rsplit(',').next())
+                .is_some_and(|proto| proto.trim().eq_ignore_ascii_case("https"))
+    }
+
+    // Returns the response redirecting or rejecting the request if it did not use HTTPS
+    pub fn process_request(
+        &self,
+        headers: &HeaderMap,
+        path_and_query: &str,
+        connection: &RequestConnection,
+    ) -> Option<poem::Response> {
+        if self.is_https(headers, connection) {
+            return None;
+        }
+
+        let redirect = match self.config.policy {
+            HttpsPolicy::Redirect => https_location(headers, path_and_query),
+            HttpsPolicy::Reject => None,
+        };
+
+        Some(match redirect {
+            Some(location) => poem::Response::builder()
+                .status(StatusCode::MOVED_PERMANENTLY)
+                .header(http::header::LOCATION, location)
+                .finish(),
+            // Also when there is no valid host to redirect to
+            None => poem::Response::builder()
+                .status(StatusCode::FORBIDDEN)
+                .body(Body::from_string("HTTPS is required".to_string())),
+        })
+    }
+}
+
+// The https URL of the request, on the default port, as the port of the plain HTTP
+// listener is not the HTTPS one
+fn https_location(headers: &HeaderMap, path_and_query: &str) -> Option<String> {
+    let authority = headers
+        .get(HOST)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<Authority>().ok())?;
+
+    Some(format!("https://{}{}", authority.host(), path_and_query))
+}
+
+impl From<RequireHttpsConfig> for RequireHttps {
+    fn from(config: RequireHttpsConfig) -> Self {
+        RequireHttps {
+            trusted_proxies: TrustedProxies::new(&config.trusted_proxies),
+            config,
+        }
+    }
+}
+
+impl TryFrom<golem_api_grpc::proto::golem::apidefinition::RequireHttps> for RequireHttpsConfig {
+    type Error = String;
+
+    fn try_from(
+        value: golem_api_grpc::proto::golem::apidefinition::RequireHttps,
+    ) -> Result<Self, Self::Error> {
+        Ok(RequireHttpsConfig {
+            policy: value.policy.parse()?,
+            trusted_proxies: value.trusted_proxies,
+        })
+    }
+}
+
+impl From<RequireHttpsConfig> for golem_api_grpc::proto::golem::apidefinition::RequireHttps {
+    fn from(value: RequireHttpsConfig) -> Self {
+        golem_api_grpc::proto::golem::apidefinition::RequireHttps {
+            policy: value.policy.to_string(),
+            trusted_proxies: value.trusted_proxies,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::*;
+    use http::header::LOCATION;
+    use http::HeaderValue;
+
+    fn config(policy: HttpsPolicy) -> RequireHttpsConfig {
+        RequireHttpsConfig {
+            policy,
+            trusted_proxies: vec!["10.0.0.1".to_string(), "fd00::/8".to_string()],
+        }
+    }
+
+    fn require_https(policy: HttpsPolicy) -> RequireHttps {
+        config(policy).into()
+    }
+
+    fn headers(forwarded_proto: Option<&'static str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(HOST, HeaderValue::from_static("example.com:9006"));
+        if let Some(forwarded_proto) = forwarded_proto {
+            headers.insert(X_FORWARDED_PROTO, HeaderValue::from_static(forwarded_proto));
+        }
+        headers
+    }
+
+    fn connection(https: bool, peer: &str) -> RequestConnection {
+        RequestConnection {
+            https,
+            peer: Some(peer.parse().unwrap()),
+        }
+    }
+
+    #[test]
+    fn https_connections_pass() {
+        assert!(require_https(HttpsPolicy::Reject)
+            .process_request(&headers(None), "/items", &connection(true, "192.0.2.1"))
+            .is_none());
+    }
+
+    #[test]
+    fn http_requests_are_redirected_to_https() {
+        let response = require_https(HttpsPolicy::Redirect)
+            .process_request(
+                &headers(None),
+                "/items?page=2",
+                &connection(false, "192.0.2.1"),
+            )
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+        assert_eq!(
+            response.headers().get(LOCATION).unwrap(),
+            "https://example.com/items?page=2"
+        );
+    }
+
+    #[test]
+    fn http_requests_are_rejected() {
+        let response = require_https(HttpsPolicy::Reject)
+            .process_request(&headers(None), "/items", &connection(false, "192.0.2.1"))
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn forwarded_proto_is_only_trusted_from_trusted_proxies() {
+        let require_https = require_https(HttpsPolicy::Reject);
+
+        assert!(require_https.is_https(&headers(Some("https")), &connection(false, "10.0.0.1")));
+        assert!(require_https.is_https(&headers(Some("https")), &connection(false, "fd00::1")));
+        assert!(!require_https.is_https(&headers(Some("http")), &connection(false, "10.0.0.1")));
+        assert!(!require_https.is_https(&headers(Some("https")), &connection(false, "192.0.2.1")));
+        assert!(!require_https.is_https(&headers(Some("https")), &RequestConnection::default()));
+    }
+
+    #[test]
+    fn only_the_last_forwarded_proto_is_trusted() {
+        let require_https = require_https(HttpsPolicy::Reject);
+        let trusted = connection(false, "10.0.0.1");
+
+        // The client sent https, the trusted proxy appended the protocol it actually used
+        assert!(!require_https.is_https(&headers(Some("https, http")), &trusted));
+        assert!(require_https.is_https(&headers(Some("http, HTTPS")), &trusted));
+
+        let mut appended = headers(Some("https"));
+        appended.append(X_FORWARDED_PROTO, HeaderValue::from_static("http"));
+        assert!(!require_https.is_https(&appended, &trusted));
+    }
+
+    #[test]
+    fn trusted_proxies_must_be_ip_addresses_or_cidrs() {
+        assert!(config(HttpsPolicy::Redirect).validate().is_ok());
+
+        let config = RequireHttpsConfig {
+            policy: HttpsPolicy::Redirect,
+            trusted_proxies: vec!["10.0.0.0/8".to_string(), "proxy.local".to_string()],
+        };
+        assert_eq!(
+            config.validate(),
+            Err(RequireHttpsConfigError::InvalidTrustedProxy {
+                value: "proxy.local".to_string()
+            })
+        );
+    }
+}
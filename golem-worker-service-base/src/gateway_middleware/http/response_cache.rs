@@ -0,0 +1,42 @@
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct CacheConfig {
+    // How long a successful response is served from the cache
+    pub ttl_seconds: u64,
+    // Responses with a larger body (in bytes) are never cached
+    pub max_entry_size: u64,
+    // Query parameters that are part of the cache key, in addition to the method and the path
+    #[serde(default)]
+    #[oai(default)]
+    pub query_params: Vec<String>,
+    // Request headers that are part of the cache key, in addition to the method and the path
+    #[serde(default)]
+    #[oai(default)]
+    pub headers: Vec<String>,
+}
+
+impl From<golem_api_grpc::proto::golem::apidefinition::ResponseCache> for CacheConfig {
+    fn from(value: golem_api_grpc::proto::golem::apidefinition::ResponseCache) -> Self {
+        CacheConfig {
+            ttl_seconds: value.ttl_seconds,
+            max_entry_size: value.max_entry_size,
+            query_params: value.query_params,
+            headers: value.headers,
+        }
+    }
+}
+
+impl From<CacheConfig> for golem_api_grpc::proto::golem::apidefinition::ResponseCache {
+    fn from(value: CacheConfig) -> Self {
+        golem_api_grpc::proto::golem::apidefinition::ResponseCache {
+            ttl_seconds: value.ttl_seconds,
+            max_entry_size: value.max_entry_size,
+            query_params: value.query_params,
+            headers: value.headers,
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use std::time::Duration;
+
+use http::header::HeaderName;
+use http::HeaderValue;
+use poem_openapi::Object;
+use serde::{Deserialize, Serialize};
+
+const SERVER_TIMING: &str = "server-timing";
+const DEFAULT_METRIC: &str = "app";
+const DEFAULT_RESPONSE_TIME_HEADER: &str = "x-response-time";
+
+// Adds the time the gateway spent executing the route to the response, in milliseconds, both as
+// `X-Response-Time: 12.345ms` and as `Server-Timing: app;dur=12.345`. The duration covers the
+// worker (or file server) only: it starts once the request passed the other middlewares and
+// ends before the response body is transformed, cached or compressed
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, Object)]
+#[serde(rename_all = "camelCase")]
+#[oai(rename_all = "camelCase")]
+pub struct ServerTimingConfig {
+    // The name of the metric in the Server-Timing header, `app` by default
+    pub metric: Option<String>,
+    // The header with the duration, X-Response-Time by default
+    pub response_time_header: Option<String>,
+    // Whether the Server-Timing header is added as well, true by default
+    pub server_timing: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ServerTimingConfigError {
+    #[error("Invalid Server-Timing metric name {value:?}")]
+    InvalidMetric { value: String },
+    #[error("Invalid response time header {value:?}")]
+    InvalidResponseTimeHeader { value: String },
+}
+
+impl ServerTimingConfig {
+    pub fn validate(&self) -> Result<(), ServerTimingConfigError> {
+        if let Some(metric) = &self.metric {
+            if !is_token(metric) {
+                return Err(ServerTimingConfigError::InvalidMetric {
+                    value: metric.clone(),
+                });
+            }
+        }
+
+        if let Some(header) = &self.response_time_header {
+            if HeaderName::try_from(header.as_str()).is_err() {
+                return Err(ServerTimingConfigError::InvalidResponseTimeHeader {
+                    value: header.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn metric(&self) -> &str {
+        self.metric.as_deref().unwrap_or(DEFAULT_METRIC)
+    }
+
+    pub fn response_time_header(&self) -> &str {
+        self.response_time_header
+            .as_deref()
+            .unwrap_or(DEFAULT_RESPONSE_TIME_HEADER)
+    }
+
+    pub fn adds_server_timing(&self) -> bool {
+        self.server_timing.unwrap_or(true)
+    }
+
+    // A response time header set by the worker is kept. Server-Timing can list several metrics,
+    // so the metric of the gateway is added next to those of the worker
+    pub fn apply(&self, response: &mut poem::Response, duration: Duration) {
+        let millis = format!("{:.3}", duration.as_secs_f64() * 1000.0);
+
+        if let Ok(header) = HeaderName::try_from(self.response_time_header()) {
+            if !response.headers().contains_key(&header) {
+                if let Ok(value) = HeaderValue::try_from(format!("{millis}ms")) {
+                    response.headers_mut().insert(header, value);
+                }
+            }
+        }
+
+        if self.adds_server_timing() {
+            if let Ok(value) = HeaderValue::try_from(format!("{};dur={millis}", self.metric())) {
+                response.headers_mut().append(SERVER_TIMING, value);
+            }
+        }
+    }
+}
+
+// The characters allowed in HTTP tokens, such as the metric names of Server-Timing
+fn is_token(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c))
+}
+
+impl From<golem_api_grpc::proto::golem::apidefinition::ServerTiming> for ServerTimingConfig {
+    fn from(value: golem_api_grpc::proto::golem::apidefinition::ServerTiming) -> Self {
+        ServerTimingConfig {
+            metric: value.metric,
+            response_time_header: value.response_time_header,
+            server_timing: value.server_timing,
+        }
+    }
+}
+
+impl From<ServerTimingConfig> for golem_api_grpc::proto::golem::apidefinition::ServerTiming {
+    fn from(value: ServerTimingConfig) -> Self {
+        golem_api_grpc::proto::golem::apidefinition::ServerTiming {
+            metric: value.metric,
+            response_time_header: value.response_time_header,
+            server_timing: value.server_timing,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_r::test;
+
+    use super::*;
+
+    fn header_values(response: &poem::Response, name: &str) -> Vec<String> {
+        response
+            .headers()
+            .get_all(name)
+            .iter()
+            .map(|value| value.to_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn adds_both_headers_by_default() {
+        let mut response = poem::Response::builder().finish();
+        ServerTimingConfig::default().apply(&mut response, Duration::from_micros(12345));
+
+        assert_eq!(
+            header_values(&response, "x-response-time"),
+            vec!["12.345ms"]
+        );
+        assert_eq!(
+            header_values(&response, SERVER_TIMING),
+            vec!["app;dur=12.345"]
+        );
+    }
+
+    #[test]
+    fn names_are_configurable() {
+        let config = ServerTimingConfig {
+            metric: Some("worker".to_string()),
+            response_time_header: Some("X-Gateway-Time".to_string()),
+            server_timing: None,
+        };
+        let mut response = poem::Response::builder()
+            .header(SERVER_TIMING, "db;dur=3")
+            .finish();
+        config.apply(&mut response, Duration::from_millis(5));
+
+        assert_eq!(header_values(&response, "x-gateway-time"), vec!["5.000ms"]);
+        assert!(response.headers().get("x-response-time").is_none());
+        // The metrics of the worker are kept
+        assert_eq!(
+            header_values(&response, SERVER_TIMING),
+            vec!["db;dur=3", "worker;dur=5.000"]
+        );
+    }
+
+    #[test]
+    fn server_timing_is_optional() {
+        let config = ServerTimingConfig {
+            server_timing: Some(false),
+            ..ServerTimingConfig::default()
+        };
+        let mut response = poem::Response::builder()
+            .header("x-response-time", "1ms")
+            .finish();
+        config.apply(&mut response, Duration::from_millis(5));
+
+        assert_eq!(header_values(&response, "x-response-time"), vec!["1ms"]);
+        assert!(response.headers().get(SERVER_TIMING).is_none());
+    }
+
+    #[test]
+    fn names_must_be_valid() {
+        assert!(ServerTimingConfig::default().validate().is_ok());
+        assert_eq!(
+            ServerTimingConfig {
+                metric: Some("app;dur=1".to_string()),
+                ..ServerTimingConfig::default()
+            }
+            .validate(),
+            Err(ServerTimingConfigError::InvalidMetric {
+                value: "app;dur=1".to_string()
+            })
+        );
+        assert_eq!(
+            ServerTimingConfig {
+                response_time_header: Some("X Response Time".to_string()),
+                ..ServerTimingConfig::default()
+            }
+            .validate(),
+            Err(ServerTimingConfigError::InvalidResponseTimeHeader {
+                value: "X Response Time".to_string()
+            })
+        );
+    }
+}
@@ -1,6 +1,9 @@
 pub use http::*;
 mod http;
 
+use crate::gateway_binding::GatewayRequestDetails;
+use crate::gateway_request::http_request::InputHttpRequest;
+
 // A set of middleware can exist in a binding.
 // These middlewares will be processed in a sequential order.
 // The information contained in each middleware is made available to
@@ -25,12 +28,140 @@ impl Middlewares {
             .collect()
     }
 
-    pub fn transform_http_response(&self, response: &mut poem::Response) {
+    pub fn transform_http_response(
+        &self,
+        request_details: &GatewayRequestDetails,
+        response: &mut poem::Response,
+    ) {
         for middleware in self.http_middlewares() {
-            middleware.transform_response(response);
+            if !middleware.touches_body() {
+                middleware.transform_response(request_details, response);
+            }
         }
     }
 
+    // Runs the request hooks of the middlewares, the IP filter, the HTTPS requirement and then the
+    // header size limit first, so that such requests are rejected before CORS or any other processing
+    pub fn process_http_request(
+        &self,
+        request: &InputHttpRequest,
+        connection: &RequestConnection,
+    ) -> Option<poem::Response> {
+        let mut middlewares = self.http_middlewares();
+        // A stable sort, the others keep their order
+        middlewares.sort_by_key(|middleware| match middleware {
+            HttpMiddleware::IpFilter(_) => 0,
+            HttpMiddleware::RequireHttps(_) => 1,
+            HttpMiddleware::MaxHeaderSize(_) => 2,
+            _ => 3,
+        });
+
+        middlewares
+            .iter()
+            .find_map(|middleware| middleware.process_request(request, connection))
+    }
+
+    // If false, the response body can be streamed to the client as it is produced
+    pub fn touches_body(&self) -> bool {
+        self.http_middlewares()
+            .iter()
+            .any(|middleware| middleware.touches_body())
+    }
+
+    // Lists the middlewares in the order the gateway runs them, each prefixed by the phase it
+    // runs in: "request" before the worker is called, "routing" when the router answers
+    // the request by itself, and "response" on the way back. Meant for debugging the
+    // middleware setup of a route, so the descriptions are not stable
+    pub fn describe(&self) -> Vec<String> {
+        let middlewares = self.http_middlewares();
+        let mut description = Vec::new();
+
+        if let Some(body_transform) = self.get_body_transform() {
+            description.push(format!(
+                "request: body-transform ({}, up to {} bytes)",
+                body_transform.transformer, body_transform.max_body_size
+            ));
+        }
+
+        for middleware in &middlewares {
+            if matches!(
+                middleware,
+                HttpMiddleware::AutoOptions | HttpMiddleware::AutoHead
+            ) {
+                description.push(format!("routing: {}", middleware.name()));
+            }
+        }
+
+        if let Some(ip_filter) = self.get_ip_filter() {
+            description.push(format!(
+                "request: ip-filter ({} allowed, {} denied)",
+                ip_filter.allow.len(),
+                ip_filter.deny.len()
+            ));
+        }
+
+        if let Some(require_https) = self.get_require_https() {
+            description.push(format!("request: require-https ({})", require_https.policy));
+        }
+
+        if let Some(max_size) = self.get_max_header_size() {
+            description.push(format!("request: max-header-size ({max_size} bytes)"));
+        }
+
+        if let Some(response_cache) = self.get_response_cache() {
+            description.push(format!(
+                "request: response-cache lookup (ttl {}s)",
+                response_cache.ttl_seconds
+            ));
+        }
+
+        if let Some(limit) = self.get_concurrency() {
+            description.push(format!("request: concurrency (up to {limit} in flight)"));
+        }
+
+        for middleware in &middlewares {
+            match middleware {
+                HttpMiddleware::Cors(_) => description.push("response: cors".to_string()),
+                HttpMiddleware::LocationRewrite(rules) => description.push(format!(
+                    "response: location-rewrite ({} rules)",
+                    rules.len()
+                )),
+                HttpMiddleware::AutoOptions
+                | HttpMiddleware::AutoHead
+                | HttpMiddleware::ResponseCache(_)
+                | HttpMiddleware::MaxHeaderSize(_)
+                | HttpMiddleware::BodyTransform(_)
+                | HttpMiddleware::RequireHttps(_)
+                | HttpMiddleware::Concurrency(_)
+                | HttpMiddleware::IpFilter(_)
+                | HttpMiddleware::ServerTiming(_) => {}
+            }
+        }
+
+        if let Some(body_transform) = self.get_body_transform() {
+            description.push(format!(
+                "response: body-transform ({})",
+                body_transform.transformer
+            ));
+        }
+
+        if let Some(response_cache) = self.get_response_cache() {
+            description.push(format!(
+                "response: response-cache store (up to {} bytes)",
+                response_cache.max_entry_size
+            ));
+        }
+
+        if let Some(server_timing) = self.get_server_timing() {
+            description.push(format!(
+                "response: server-timing ({})",
+                server_timing.metric()
+            ));
+        }
+
+        description
+    }
+
     pub fn add(&mut self, middleware: Middleware) {
         self.0.push(middleware);
     }
@@ -38,6 +169,88 @@ impl Middlewares {
     pub fn get_cors(&self) -> Option<Cors> {
         self.0.iter().find_map(|m| m.get_cors())
     }
+
+    // Allows the origins accepted by `predicate` in the CORS configuration, if there is one
+    pub fn set_allow_origin_predicate(&mut self, predicate: &OriginPredicate) {
+        for middleware in self.0.iter_mut() {
+            match middleware {
+                Middleware::Http(HttpMiddleware::Cors(cors)) => {
+                    cors.set_allow_origin_predicate(predicate.clone())
+                }
+                Middleware::Http(_) => {}
+            }
+        }
+    }
+
+    pub fn get_response_cache(&self) -> Option<CacheConfig> {
+        self.0.iter().find_map(|m| match m {
+            Middleware::Http(HttpMiddleware::ResponseCache(config)) => Some(config.clone()),
+            Middleware::Http(_) => None,
+        })
+    }
+
+    pub fn get_location_rewrite(&self) -> Option<Vec<LocationRewriteRule>> {
+        self.0.iter().find_map(|m| match m {
+            Middleware::Http(HttpMiddleware::LocationRewrite(rules)) => Some(rules.clone()),
+            Middleware::Http(_) => None,
+        })
+    }
+
+    pub fn get_max_header_size(&self) -> Option<usize> {
+        self.0.iter().find_map(|m| match m {
+            Middleware::Http(HttpMiddleware::MaxHeaderSize(max_size)) => Some(*max_size),
+            Middleware::Http(_) => None,
+        })
+    }
+
+    pub fn get_body_transform(&self) -> Option<BodyTransformConfig> {
+        self.0.iter().find_map(|m| match m {
+            Middleware::Http(HttpMiddleware::BodyTransform(config)) => Some(config.clone()),
+            Middleware::Http(_) => None,
+        })
+    }
+
+    pub fn get_require_https(&self) -> Option<RequireHttpsConfig> {
+        self.0.iter().find_map(|m| match m {
+            Middleware::Http(HttpMiddleware::RequireHttps(require_https)) => {
+                Some(require_https.config().clone())
+            }
+            Middleware::Http(_) => None,
+        })
+    }
+
+    pub fn get_concurrency(&self) -> Option<usize> {
+        self.0.iter().find_map(|m| match m {
+            Middleware::Http(HttpMiddleware::Concurrency(limit)) => Some(*limit),
+            Middleware::Http(_) => None,
+        })
+    }
+
+    pub fn get_ip_filter(&self) -> Option<IpFilterConfig> {
+        self.0.iter().find_map(|m| match m {
+            Middleware::Http(HttpMiddleware::IpFilter(filter)) => Some(filter.config().clone()),
+            Middleware::Http(_) => None,
+        })
+    }
+
+    pub fn get_server_timing(&self) -> Option<ServerTimingConfig> {
+        self.0.iter().find_map(|m| match m {
+            Middleware::Http(HttpMiddleware::ServerTiming(config)) => Some(config.clone()),
+            Middleware::Http(_) => None,
+        })
+    }
+
+    pub fn has_auto_options(&self) -> bool {
+        self.0
+            .iter()
+            .any(|m| matches!(m, Middleware::Http(HttpMiddleware::AutoOptions)))
+    }
+
+    pub fn has_auto_head(&self) -> bool {
+        self.0
+            .iter()
+            .any(|m| matches!(m, Middleware::Http(HttpMiddleware::AutoHead)))
+    }
 }
 
 // A middleware will not add, remove or update the input to worker what-so-ever,
@@ -61,6 +274,7 @@ impl Middleware {
     pub fn get_cors(&self) -> Option<Cors> {
         match self {
             Middleware::Http(HttpMiddleware::Cors(cors)) => Some(cors.clone()),
+            Middleware::Http(_) => None,
         }
     }
 
@@ -76,10 +290,60 @@ impl TryFrom<golem_api_grpc::proto::golem::apidefinition::Middleware> for Middle
         value: golem_api_grpc::proto::golem::apidefinition::Middleware,
     ) -> Result<Self, Self::Error> {
         let mut middlewares = Vec::new();
+        if let Some(ip_filter) = value.ip_filter {
+            middlewares.push(Middleware::http(HttpMiddleware::ip_filter(
+                ip_filter.into(),
+            )));
+        }
+        if let Some(max_header_size) = value.max_header_size {
+            middlewares.push(Middleware::http(HttpMiddleware::max_header_size(
+                max_header_size as usize,
+            )));
+        }
         if let Some(cors) = value.cors {
             let cors = Cors::try_from(cors)?;
             middlewares.push(Middleware::http(HttpMiddleware::cors(cors)));
         }
+        if value.auto_options == Some(true) {
+            middlewares.push(Middleware::http(HttpMiddleware::auto_options()));
+        }
+        if value.auto_head == Some(true) {
+            middlewares.push(Middleware::http(HttpMiddleware::auto_head()));
+        }
+        if let Some(response_cache) = value.response_cache {
+            middlewares.push(Middleware::http(HttpMiddleware::response_cache(
+                response_cache.into(),
+            )));
+        }
+        if !value.location_rewrite.is_empty() {
+            middlewares.push(Middleware::http(HttpMiddleware::location_rewrite(
+                value
+                    .location_rewrite
+                    .into_iter()
+                    .map(LocationRewriteRule::from)
+                    .collect(),
+            )));
+        }
+        if let Some(body_transform) = value.body_transform {
+            middlewares.push(Middleware::http(HttpMiddleware::body_transform(
+                BodyTransformConfig::try_from(body_transform)?,
+            )));
+        }
+        if let Some(require_https) = value.require_https {
+            middlewares.push(Middleware::http(HttpMiddleware::require_https(
+                RequireHttpsConfig::try_from(require_https)?,
+            )));
+        }
+        if let Some(concurrency) = value.concurrency {
+            middlewares.push(Middleware::http(HttpMiddleware::concurrency(
+                concurrency as usize,
+            )));
+        }
+        if let Some(server_timing) = value.server_timing {
+            middlewares.push(Middleware::http(HttpMiddleware::server_timing(
+                server_timing.into(),
+            )));
+        }
         Ok(Middlewares(middlewares))
     }
 }
@@ -88,6 +352,21 @@ impl From<Middlewares> for golem_api_grpc::proto::golem::apidefinition::Middlewa
     fn from(value: Middlewares) -> Self {
         golem_api_grpc::proto::golem::apidefinition::Middleware {
             cors: value.0.iter().find_map(|m| m.get_cors().map(|c| c.into())),
+            auto_options: value.has_auto_options().then_some(true),
+            response_cache: value.get_response_cache().map(|c| c.into()),
+            auto_head: value.has_auto_head().then_some(true),
+            location_rewrite: value
+                .get_location_rewrite()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|rule| rule.into())
+                .collect(),
+            max_header_size: value.get_max_header_size().map(|size| size as u64),
+            body_transform: value.get_body_transform().map(|config| config.into()),
+            require_https: value.get_require_https().map(|config| config.into()),
+            concurrency: value.get_concurrency().map(|limit| limit as u64),
+            ip_filter: value.get_ip_filter().map(|config| config.into()),
+            server_timing: value.get_server_timing().map(|config| config.into()),
         }
     }
 }
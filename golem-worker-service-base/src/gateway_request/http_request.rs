@@ -1,6 +1,10 @@
 use std::collections::HashMap;
 
 use crate::gateway_api_deployment::ApiSiteString;
+use crate::gateway_binding::GatewayBindingCompiled;
+use crate::gateway_execution::gateway_binding_resolver::get_auto_head_route_entry;
+use crate::gateway_execution::router::{Router, RouterPattern};
+use crate::gateway_middleware::Middlewares;
 use hyper::http::{HeaderMap, Method};
 use serde_json::Value;
 
@@ -19,6 +23,30 @@ impl InputHttpRequest {
             .and_then(|host| host.to_str().ok())
             .map(|host_str| ApiSiteString(host_str.to_string()))
     }
+
+    // The middlewares of the route matching the request, looked up before the binding is
+    // resolved, as some of them (such as the body transform) decide how to read the body, and
+    // the request hooks run before the body is read. A HEAD request served by a GET route
+    // with auto HEAD gets the middlewares of that route. The router is the one the binding is
+    // resolved with afterwards, so that it is only built once per request
+    pub fn route_middlewares<Namespace>(
+        &self,
+        router: &Router<router::RouteEntry<Namespace>>,
+    ) -> Option<Middlewares> {
+        let path: Vec<&str> = RouterPattern::split(&self.input_path.base_path).collect();
+
+        let route_entry = match router.check_path(&self.req_method, &path) {
+            Some(route_entry) => route_entry,
+            None if self.req_method == Method::HEAD => get_auto_head_route_entry(router, &path)?,
+            None => return None,
+        };
+
+        match &route_entry.binding {
+            GatewayBindingCompiled::Worker(binding)
+            | GatewayBindingCompiled::FileServer(binding) => binding.middlewares.clone(),
+            GatewayBindingCompiled::Static(_) => None,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -28,6 +56,14 @@ pub struct ApiInputPath {
 }
 
 impl ApiInputPath {
+    // The path with the query, as in the request line
+    pub fn path_and_query(&self) -> String {
+        match &self.query_path {
+            Some(query_path) => format!("{}?{}", self.base_path, query_path),
+            None => self.base_path.clone(),
+        }
+    }
+
     // Return the value of each query variable in a HashMap
     pub fn query_components(&self) -> Option<HashMap<String, String>> {
         if let Some(query_path) = self.query_path.clone() {
@@ -49,7 +85,7 @@ impl ApiInputPath {
 }
 
 pub mod router {
-    use crate::gateway_api_definition::http::CompiledRoute;
+    use crate::gateway_api_definition::http::{CompiledHttpApiDefinition, CompiledRoute};
     use crate::gateway_api_definition::http::{PathPattern, QueryInfo, VarInfo};
     use crate::gateway_binding::GatewayBindingCompiled;
     use crate::gateway_execution::router::{Router, RouterPattern};
@@ -61,6 +97,19 @@ pub mod router {
         pub query_params: Vec<QueryInfo>,
         pub namespace: Namespace,
         pub binding: GatewayBindingCompiled,
+        // The method and path pattern of the route, such as `GET /items/{id}`
+        pub route: String,
+    }
+
+    pub fn build_from_definitions<Namespace: Clone>(
+        compiled_api_definitions: &[CompiledHttpApiDefinition<Namespace>],
+    ) -> Router<RouteEntry<Namespace>> {
+        build(
+            compiled_api_definitions
+                .iter()
+                .flat_map(|x| x.routes.iter().map(|y| (x.namespace.clone(), y.clone())))
+                .collect(),
+        )
     }
 
     pub fn build<Namespace>(
@@ -69,9 +118,10 @@ pub mod router {
         let mut router = Router::new();
 
         for (namespace, route) in routes {
-            let method = route.method.into();
+            let method: hyper::Method = route.method.into();
             let path = route.path;
             let binding = route.binding;
+            let route = format!("{method} {path}");
 
             let path_params = path
                 .path_patterns
@@ -88,6 +138,7 @@ pub mod router {
                 query_params: path.query_params,
                 namespace,
                 binding,
+                route,
             };
 
             let path: Vec<RouterPattern> = path
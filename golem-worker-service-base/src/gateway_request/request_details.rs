@@ -1,6 +1,6 @@
 use crate::gateway_api_definition::http::{QueryInfo, VarInfo};
 
-use http::HeaderMap;
+use http::{HeaderMap, Method};
 use serde_json::Value;
 use std::collections::HashMap;
 
@@ -25,6 +25,16 @@ impl GatewayRequestDetails {
         )?))
     }
 
+    // Records the method of the request, which is not part of the details exposed to Rib
+    pub fn with_request_method(self, method: &Method) -> Self {
+        match self {
+            GatewayRequestDetails::Http(http) => GatewayRequestDetails::Http(HttpRequestDetails {
+                request_method: Some(method.clone()),
+                ..http
+            }),
+        }
+    }
+
     pub fn as_json(&self) -> Value {
         match self {
             GatewayRequestDetails::Http(http_request_details) => {
@@ -70,6 +80,7 @@ pub struct HttpRequestDetails {
     pub request_body: RequestBody,
     pub request_query_values: RequestQueryValues,
     pub request_header_values: RequestHeaderValues,
+    pub request_method: Option<Method>,
 }
 
 impl HttpRequestDetails {
@@ -79,6 +90,7 @@ impl HttpRequestDetails {
             request_body: RequestBody(Value::Null),
             request_query_values: RequestQueryValues(JsonKeyValues::default()),
             request_header_values: RequestHeaderValues(JsonKeyValues::default()),
+            request_method: None,
         }
     }
 
@@ -91,6 +103,46 @@ impl HttpRequestDetails {
             .and_then(|field| field.value.as_str().map(|x| x.to_string()))
     }
 
+    pub fn get_origin_header(&self) -> Option<String> {
+        self.request_header_values
+            .0
+            .fields
+            .iter()
+            .find(|field| field.name == http::header::ORIGIN.to_string())
+            .and_then(|field| field.value.as_str().map(|x| x.to_string()))
+    }
+
+    pub fn get_access_control_request_method_header(&self) -> Option<String> {
+        self.request_header_values
+            .0
+            .fields
+            .iter()
+            .find(|field| field.name == http::header::ACCESS_CONTROL_REQUEST_METHOD.to_string())
+            .and_then(|field| field.value.as_str().map(|x| x.to_string()))
+    }
+
+    pub fn get_access_control_request_headers_header(&self) -> Option<String> {
+        self.request_header_values
+            .0
+            .fields
+            .iter()
+            .find(|field| field.name == http::header::ACCESS_CONTROL_REQUEST_HEADERS.to_string())
+            .and_then(|field| field.value.as_str().map(|x| x.to_string()))
+    }
+
+    pub fn get_request_method(&self) -> Option<&Method> {
+        self.request_method.as_ref()
+    }
+
+    pub fn get_host_header(&self) -> Option<String> {
+        self.request_header_values
+            .0
+            .fields
+            .iter()
+            .find(|field| field.name == http::header::HOST.to_string())
+            .and_then(|field| field.value.as_str().map(|x| x.to_string()))
+    }
+
     fn from_input_http_request(
         path_params: &HashMap<VarInfo, &str>,
         query_variable_values: &HashMap<String, String>,
@@ -108,6 +160,7 @@ impl HttpRequestDetails {
             request_body,
             request_query_values: query_params,
             request_header_values: header_params,
+            request_method: None,
         })
     }
 }
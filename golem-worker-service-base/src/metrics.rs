@@ -20,6 +20,13 @@ lazy_static! {
     static ref VERSION_INFO: IntCounterVec =
         register_int_counter_vec!("version_info", "Version info of the server", &["version"])
             .unwrap();
+    // Requests of the routes with the concurrency middleware currently being executed
+    pub static ref GATEWAY_IN_FLIGHT_REQUESTS: IntGaugeVec = register_int_gauge_vec!(
+        "gateway_in_flight_requests",
+        "Number of in-flight requests of the routes with a concurrency limit",
+        &["host"]
+    )
+    .unwrap();
 }
 
 pub fn register_all() -> Registry {
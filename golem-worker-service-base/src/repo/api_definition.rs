@@ -129,6 +129,9 @@ pub trait ApiDefinitionRepo {
         namespace: &str,
         id: &str,
     ) -> Result<Vec<ApiDefinitionRecord>, RepoError>;
+
+    // All definitions of all namespaces, used to check the stored definitions on startup
+    async fn get_all_records(&self) -> Result<Vec<ApiDefinitionRecord>, RepoError>;
 }
 
 pub struct LoggedApiDefinitionRepo<Repo: ApiDefinitionRepo> {
@@ -225,6 +228,11 @@ impl<Repo: ApiDefinitionRepo + Sync> ApiDefinitionRepo for LoggedApiDefinitionRe
         let result = self.repo.get_all_versions(namespace, id).await;
         Self::logged_with_id("get_all_versions", namespace, id, result)
     }
+
+    async fn get_all_records(&self) -> Result<Vec<ApiDefinitionRecord>, RepoError> {
+        let result = self.repo.get_all_records().await;
+        Self::logged_with_id("get_all_records", "*", "*", result)
+    }
 }
 
 pub struct DbApiDefinitionRepo<DB: Database> {
@@ -421,6 +429,26 @@ impl ApiDefinitionRepo for DbApiDefinitionRepo<sqlx::Postgres> {
             .await
             .map_err(|e| e.into())
     }
+
+    #[when(sqlx::Postgres -> get_all_records)]
+    async fn get_all_records_postgres(&self) -> Result<Vec<ApiDefinitionRecord>, RepoError> {
+        sqlx::query_as::<_, ApiDefinitionRecord>(
+            "SELECT namespace, id, version, draft, data, created_at::timestamptz FROM api_definitions",
+        )
+        .fetch_all(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
+
+    #[when(sqlx::Sqlite -> get_all_records)]
+    async fn get_all_records_sqlite(&self) -> Result<Vec<ApiDefinitionRecord>, RepoError> {
+        sqlx::query_as::<_, ApiDefinitionRecord>(
+            "SELECT namespace, id, version, draft, data, created_at FROM api_definitions",
+        )
+        .fetch_all(self.db_pool.deref())
+        .await
+        .map_err(|e| e.into())
+    }
 }
 
 pub mod record_data_serde {
@@ -8,6 +8,7 @@ use serde::{Deserialize, Serialize};
 use crate::gateway_api_definition::http::{HttpApiDefinition, MethodPattern, Route};
 
 use crate::gateway_execution::router::{Router, RouterPattern};
+use crate::gateway_middleware::Middlewares;
 use crate::service::gateway::api_definition_transformer::ApiDefTransformationError;
 use crate::service::gateway::api_definition_validator::{
     ApiDefinitionValidatorService, ValidationErrors,
@@ -66,7 +67,15 @@ impl ApiDefinitionValidatorService<HttpApiDefinition, RouteValidationError>
         api: &HttpApiDefinition,
         _components: &[Component],
     ) -> Result<(), ValidationErrors<RouteValidationError>> {
-        let errors = unique_routes(api.routes.as_slice());
+        let mut errors = unique_routes(api.routes.as_slice());
+        errors.extend(invalid_cors(api.routes.as_slice()));
+        errors.extend(invalid_location_rewrites(api.routes.as_slice()));
+        errors.extend(invalid_max_header_sizes(api.routes.as_slice()));
+        errors.extend(invalid_body_transforms(api.routes.as_slice()));
+        errors.extend(invalid_require_https(api.routes.as_slice()));
+        errors.extend(invalid_concurrency_limits(api.routes.as_slice()));
+        errors.extend(invalid_ip_filters(api.routes.as_slice()));
+        errors.extend(invalid_server_timings(api.routes.as_slice()));
 
         if errors.is_empty() {
             Ok(())
@@ -108,36 +117,166 @@ fn unique_routes(routes: &[Route]) -> Vec<RouteValidationError> {
     errors
 }
 
+// Also used on startup, to report the stored definitions registered before the CORS
+// configurations were validated
+pub fn invalid_cors(routes: &[Route]) -> Vec<RouteValidationError> {
+    let mut errors: Vec<RouteValidationError> = routes
+        .iter()
+        .filter_map(|route| {
+            route
+                .cors_preflight_binding()
+                .and_then(|cors| cors.validate().err())
+                .map(|err| route_error(route, err.to_string()))
+        })
+        .collect();
+    errors.extend(route_errors(routes, |middlewares| {
+        middlewares
+            .get_cors()
+            .and_then(|cors| cors.validate().err())
+            .map(|err| err.to_string())
+    }));
+    errors
+}
+
+fn invalid_location_rewrites(routes: &[Route]) -> Vec<RouteValidationError> {
+    route_errors(routes, |middlewares| {
+        middlewares
+            .get_location_rewrite()
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|rule| rule.validate().err())
+            .map(|err| err.to_string())
+            .collect::<Vec<_>>()
+    })
+}
+
+fn invalid_max_header_sizes(routes: &[Route]) -> Vec<RouteValidationError> {
+    route_errors(routes, |middlewares| {
+        (middlewares.get_max_header_size() == Some(0))
+            .then(|| "The maximum header size must be greater than 0".to_string())
+    })
+}
+
+fn invalid_body_transforms(routes: &[Route]) -> Vec<RouteValidationError> {
+    route_errors(routes, |middlewares| {
+        middlewares
+            .get_body_transform()
+            .and_then(|config| config.validate().err())
+            .map(|err| err.to_string())
+    })
+}
+
+fn invalid_require_https(routes: &[Route]) -> Vec<RouteValidationError> {
+    route_errors(routes, |middlewares| {
+        middlewares
+            .get_require_https()
+            .and_then(|config| config.validate().err())
+            .map(|err| err.to_string())
+    })
+}
+
+fn invalid_ip_filters(routes: &[Route]) -> Vec<RouteValidationError> {
+    route_errors(routes, |middlewares| {
+        middlewares
+            .get_ip_filter()
+            .and_then(|config| config.validate().err())
+            .map(|err| err.to_string())
+    })
+}
+
+fn invalid_server_timings(routes: &[Route]) -> Vec<RouteValidationError> {
+    route_errors(routes, |middlewares| {
+        middlewares
+            .get_server_timing()
+            .and_then(|config| config.validate().err())
+            .map(|err| err.to_string())
+    })
+}
+
+fn invalid_concurrency_limits(routes: &[Route]) -> Vec<RouteValidationError> {
+    route_errors(routes, |middlewares| {
+        (middlewares.get_concurrency() == Some(0))
+            .then(|| "The concurrency limit must be greater than 0".to_string())
+    })
+}
+
+// The errors `check` finds in the middlewares of each route's worker binding, as errors of the route
+fn route_errors<F, I>(routes: &[Route], check: F) -> Vec<RouteValidationError>
+where
+    F: Fn(&Middlewares) -> I,
+    I: IntoIterator<Item = String>,
+{
+    routes
+        .iter()
+        .flat_map(|route| {
+            route
+                .binding
+                .get_worker_binding()
+                .and_then(|binding| binding.middleware)
+                .map(|middlewares| check(&middlewares).into_iter().collect::<Vec<_>>())
+                .unwrap_or_default()
+                .into_iter()
+                .map(|detail| route_error(route, detail))
+        })
+        .collect()
+}
+
+fn route_error(route: &Route, detail: String) -> RouteValidationError {
+    RouteValidationError {
+        method: route.method.clone(),
+        path: route.path.to_string(),
+        component: route.binding.get_worker_binding().map(|w| w.component_id),
+        detail,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use test_r::test;
 
     use crate::gateway_api_definition::http::{MethodPattern, Route};
     use crate::gateway_binding::{GatewayBinding, ResponseMapping};
-    use crate::service::gateway::http_api_definition_validator::unique_routes;
+    use crate::gateway_middleware::{
+        HttpMiddleware, HttpsPolicy, IpFilterConfig, Middleware, Middlewares, RequireHttpsConfig,
+        ServerTimingConfig,
+    };
+    use crate::service::gateway::http_api_definition_validator::{
+        invalid_concurrency_limits, invalid_ip_filters, invalid_max_header_sizes,
+        invalid_require_https, invalid_server_timings, unique_routes,
+    };
     use golem_common::model::ComponentId;
     use golem_service_base::model::VersionedComponentId;
     use rib::Expr;
 
-    #[test]
-    fn test_unique_routes() {
-        fn make_route(method: MethodPattern, path: &str) -> Route {
-            Route {
-                method,
-                path: crate::gateway_api_definition::http::AllPathPatterns::parse(path).unwrap(),
-                binding: GatewayBinding::Default(crate::gateway_binding::WorkerBinding {
-                    component_id: VersionedComponentId {
-                        component_id: ComponentId::new_v4(),
-                        version: 1,
-                    },
-                    worker_name: Some(Expr::identifier("request")),
-                    idempotency_key: None,
-                    response_mapping: ResponseMapping(Expr::literal("sample")),
-                    middleware: None,
+    fn make_route(method: MethodPattern, path: &str) -> Route {
+        make_route_with_middlewares(method, path, None)
+    }
+
+    fn make_route_with_middlewares(
+        method: MethodPattern,
+        path: &str,
+        middlewares: Option<Vec<HttpMiddleware>>,
+    ) -> Route {
+        Route {
+            method,
+            path: crate::gateway_api_definition::http::AllPathPatterns::parse(path).unwrap(),
+            binding: GatewayBinding::Default(crate::gateway_binding::WorkerBinding {
+                component_id: VersionedComponentId {
+                    component_id: ComponentId::new_v4(),
+                    version: 1,
+                },
+                worker_name: Some(Expr::identifier("request")),
+                idempotency_key: None,
+                response_mapping: ResponseMapping(Expr::literal("sample")),
+                middleware: middlewares.map(|middlewares| {
+                    Middlewares(middlewares.into_iter().map(Middleware::http).collect())
                 }),
-            }
+            }),
         }
+    }
 
+    #[test]
+    fn test_unique_routes() {
         let paths = &[
             "/users/{id}/posts/{post_id}",
             "/users/{id}/posts/{post_id}/comments/{comment_id}",
@@ -167,4 +306,80 @@ mod tests {
         assert!(errors.len() == 1);
         assert!(errors[0].detail.contains(paths[0]), "Received: {errors:?}");
     }
+
+    #[test]
+    fn middleware_errors_are_reported_for_their_route() {
+        let routes = vec![
+            make_route_with_middlewares(
+                MethodPattern::Get,
+                "/invalid",
+                Some(vec![
+                    HttpMiddleware::max_header_size(0),
+                    HttpMiddleware::concurrency(0),
+                ]),
+            ),
+            make_route_with_middlewares(
+                MethodPattern::Get,
+                "/valid",
+                Some(vec![
+                    HttpMiddleware::max_header_size(8192),
+                    HttpMiddleware::concurrency(4),
+                ]),
+            ),
+            make_route(MethodPattern::Get, "/none"),
+        ];
+
+        let max_header_size_errors = invalid_max_header_sizes(&routes);
+        assert_eq!(max_header_size_errors.len(), 1);
+        assert_eq!(max_header_size_errors[0].path, "/invalid");
+        assert_eq!(
+            max_header_size_errors[0].component,
+            routes[0]
+                .binding
+                .get_worker_binding()
+                .map(|w| w.component_id)
+        );
+
+        let concurrency_errors = invalid_concurrency_limits(&routes);
+        assert_eq!(concurrency_errors.len(), 1);
+        assert_eq!(concurrency_errors[0].path, "/invalid");
+        assert!(
+            concurrency_errors[0].detail.contains("concurrency"),
+            "Received: {concurrency_errors:?}"
+        );
+    }
+
+    #[test]
+    fn invalid_middleware_configs_are_reported() {
+        let routes = vec![make_route_with_middlewares(
+            MethodPattern::Post,
+            "/users/{id}",
+            Some(vec![
+                HttpMiddleware::ip_filter(IpFilterConfig {
+                    allow: vec!["10.0.0.0/33".to_string()],
+                    deny: vec![],
+                    trusted_proxies: vec![],
+                }),
+                HttpMiddleware::require_https(RequireHttpsConfig {
+                    policy: HttpsPolicy::Reject,
+                    trusted_proxies: vec!["proxy".to_string()],
+                }),
+                HttpMiddleware::server_timing(ServerTimingConfig {
+                    metric: Some("not a token".to_string()),
+                    response_time_header: None,
+                    server_timing: None,
+                }),
+            ]),
+        )];
+
+        for errors in [
+            invalid_ip_filters(&routes),
+            invalid_require_https(&routes),
+            invalid_server_timings(&routes),
+        ] {
+            assert_eq!(errors.len(), 1, "Received: {errors:?}");
+            assert_eq!(errors[0].method, MethodPattern::Post);
+            assert_eq!(errors[0].path, "/users/{id}");
+        }
+    }
 }
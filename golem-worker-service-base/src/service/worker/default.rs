@@ -41,7 +41,8 @@ use golem_common::model::{
 };
 use golem_service_base::model::{Component, GolemError};
 use golem_service_base::model::{
-    GetOplogResponse, GolemErrorUnknown, PublicOplogEntryWithIndex, ResourceLimits, WorkerMetadata,
+    ExecutorCompileStatus, GetOplogResponse, GolemErrorUnknown, PublicOplogEntryWithIndex,
+    ResourceLimits, WorkerMetadata,
 };
 use golem_service_base::service::routing_table::{HasRoutingTableService, RoutingTableService};
 use golem_wasm_ast::analysis::AnalysedFunctionResult;
@@ -261,6 +262,28 @@ pub trait WorkerService<AuthCtx> {
         metadata: WorkerRequestMetadata,
         auth_ctx: &AuthCtx,
     ) -> WorkerResult<Pin<Box<dyn Stream<Item = WorkerResult<Bytes>> + Send + 'static>>>;
+
+    /// Gets the result of the last compilation of a component version from every worker
+    /// executor
+    async fn get_compile_status(
+        &self,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<ExecutorCompileStatus>>;
+
+    /// Loads a component version on every worker executor, compiling it where it is not cached
+    /// yet, and returns the result of its last compilation on each of them. With
+    /// `force_recompile`, every executor compiles it again and overwrites its compiled artifact.
+    async fn warm_component(
+        &self,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+        force_recompile: bool,
+        metadata: WorkerRequestMetadata,
+        auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<ExecutorCompileStatus>>;
 }
 
 pub struct TypedResult {
@@ -1155,6 +1178,102 @@ where
 
         Ok(Box::pin(stream))
     }
+
+    async fn get_compile_status(
+        &self,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+        _metadata: WorkerRequestMetadata,
+        _auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<ExecutorCompileStatus>> {
+        let component_id = component_id.clone();
+        let result = self.call_worker_executor(
+            AllExecutors,
+            "get_component_compile_status",
+            move |worker_executor_client| {
+                let component_id: golem_api_grpc::proto::golem::component::ComponentId =
+                    component_id.clone().into();
+
+                Box::pin(
+                    worker_executor_client.get_component_compile_status(
+                        workerexecutor::v1::GetComponentCompileStatusRequest {
+                            component_id: Some(component_id),
+                            component_version,
+                        }
+                    )
+                )
+            },
+            |responses| {
+                responses.into_iter().map(|response| {
+                    match response.into_inner() {
+                        workerexecutor::v1::GetComponentCompileStatusResponse {
+                            result:
+                            Some(workerexecutor::v1::get_component_compile_status_response::Result::Success(status)),
+                        } => Ok(status.into()),
+                        workerexecutor::v1::GetComponentCompileStatusResponse {
+                            result:
+                            Some(workerexecutor::v1::get_component_compile_status_response::Result::Failure(err)),
+                        } => Err(err.into()),
+                        workerexecutor::v1::GetComponentCompileStatusResponse { .. } => {
+                            Err("Empty response".into())
+                        }
+                    }
+                }).collect::<Result<Vec<_>, ResponseMapResult>>()
+            },
+            WorkerServiceError::InternalCallError,
+        ).await?;
+
+        Ok(result)
+    }
+
+    async fn warm_component(
+        &self,
+        component_id: &ComponentId,
+        component_version: ComponentVersion,
+        force_recompile: bool,
+        _metadata: WorkerRequestMetadata,
+        _auth_ctx: &AuthCtx,
+    ) -> WorkerResult<Vec<ExecutorCompileStatus>> {
+        let component_id = component_id.clone();
+        let result = self.call_worker_executor(
+            AllExecutors,
+            "warm_component",
+            move |worker_executor_client| {
+                let component_id: golem_api_grpc::proto::golem::component::ComponentId =
+                    component_id.clone().into();
+
+                Box::pin(
+                    worker_executor_client.warm_component(
+                        workerexecutor::v1::WarmComponentRequest {
+                            component_id: Some(component_id),
+                            component_version,
+                            force_recompile,
+                        }
+                    )
+                )
+            },
+            |responses| {
+                responses.into_iter().map(|response| {
+                    match response.into_inner() {
+                        workerexecutor::v1::WarmComponentResponse {
+                            result:
+                            Some(workerexecutor::v1::warm_component_response::Result::Success(status)),
+                        } => Ok(status.into()),
+                        workerexecutor::v1::WarmComponentResponse {
+                            result:
+                            Some(workerexecutor::v1::warm_component_response::Result::Failure(err)),
+                        } => Err(err.into()),
+                        workerexecutor::v1::WarmComponentResponse { .. } => {
+                            Err("Empty response".into())
+                        }
+                    }
+                }).collect::<Result<Vec<_>, ResponseMapResult>>()
+            },
+            WorkerServiceError::InternalCallError,
+        ).await?;
+
+        Ok(result)
+    }
 }
 
 impl<AuthCtx> WorkerServiceDefault<AuthCtx>
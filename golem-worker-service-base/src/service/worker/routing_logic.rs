@@ -313,6 +313,7 @@ pub enum ResponseMapResult {
         shard_ids: HashSet<ShardId>,
     },
     ShardingNotReady,
+    ShuttingDown,
     Other(WorkerServiceError),
 }
 
@@ -327,6 +328,7 @@ impl From<GolemError> for ResponseMapResult {
                 shard_ids,
             },
             GolemError::ShardingNotReady(_) => ResponseMapResult::ShardingNotReady,
+            GolemError::ShuttingDown(_) => ResponseMapResult::ShuttingDown,
             other => ResponseMapResult::Other(other.into()),
         }
     }
@@ -410,6 +412,9 @@ impl<T: HasRoutingTableService + HasWorkerExecutorClients + Send + Sync> Routing
                             Err(error @ ResponseMapResult::ShardingNotReady) => {
                                 retry.retry(self, &error, &pod).await
                             }
+                            Err(error @ ResponseMapResult::ShuttingDown) => {
+                                retry.retry(self, &error, &pod).await
+                            }
                             Err(ResponseMapResult::Other(error)) => {
                                 retry.non_retryable_error(error, &pod)
                             }
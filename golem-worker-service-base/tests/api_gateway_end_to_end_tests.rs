@@ -32,6 +32,13 @@ async fn execute(
     api_request: &InputHttpRequest,
     api_specification: &HttpApiDefinition,
 ) -> TestResponse {
+    TestResponse::from_live_response(execute_live(api_request, api_specification).await).await
+}
+
+async fn execute_live(
+    api_request: &InputHttpRequest,
+    api_specification: &HttpApiDefinition,
+) -> poem::Response {
     let mut api_specification = api_specification.clone();
     api_specification.transform().unwrap();
 
@@ -52,8 +59,7 @@ async fn execute(
         internal::get_test_file_server_binding_handler(),
     );
 
-    let poem_response: poem::Response = test_executor.execute_binding(&resolved_route).await;
-    TestResponse::from_live_response(poem_response).await
+    test_executor.execute_binding(&resolved_route).await
 }
 
 #[test]
@@ -236,6 +242,98 @@ async fn test_end_to_end_api_gateway_cors_with_preflight_and_actual_request() {
     );
 }
 
+#[test]
+async fn test_end_to_end_api_gateway_auto_options() {
+    let empty_headers = HeaderMap::new();
+    let preflight_request =
+        get_preflight_api_request("foo/1", None, &empty_headers, serde_json::Value::Null);
+
+    let worker_name = r#"
+      let id: u64 = request.path.user-id;
+      "shopping-cart-${id}"
+    "#;
+
+    let response_mapping = r#"
+      let response = golem:it/api.{get-cart-contents}("a", "b");
+      response
+    "#;
+
+    let api_specification: HttpApiDefinition = get_api_spec_worker_binding_with_auto_options(
+        "foo/{user-id}",
+        worker_name,
+        response_mapping,
+    );
+
+    let test_response = execute(&preflight_request, &api_specification).await;
+
+    assert_eq!(test_response.get_status(), 204);
+    assert_eq!(
+        test_response.get_allow_header(),
+        Some("GET, OPTIONS".to_string())
+    );
+}
+
+#[test]
+async fn test_end_to_end_api_gateway_auto_head() {
+    let empty_headers = HeaderMap::new();
+    let get_request = get_api_request("foo/1", None, &empty_headers, serde_json::Value::Null);
+    let mut head_request = get_request.clone();
+    head_request.req_method = Method::HEAD;
+
+    let preflight_request =
+        get_preflight_api_request("foo/1", None, &empty_headers, serde_json::Value::Null);
+
+    let worker_name = r#"
+      let id: u64 = request.path.user-id;
+      "shopping-cart-${id}"
+    "#;
+
+    let response_mapping = r#"
+      let response = golem:it/api.{get-cart-contents}("a", "b");
+      response
+    "#;
+
+    let api_specification: HttpApiDefinition =
+        get_api_spec_worker_binding_with_auto_head("foo/{user-id}", worker_name, response_mapping);
+
+    // The HEAD request is executed by the GET route, the body is only stripped by the gateway
+    let head_response = execute(&head_request, &api_specification).await;
+    let preflight_response = execute(&preflight_request, &api_specification).await;
+
+    assert_eq!(
+        head_response.get_worker_name(),
+        Some("shopping-cart-1".to_string())
+    );
+    assert_eq!(
+        preflight_response.get_allow_header(),
+        Some("GET, HEAD, OPTIONS".to_string())
+    );
+
+    let get_body = execute_live(&get_request, &api_specification)
+        .await
+        .into_body()
+        .into_bytes()
+        .await
+        .unwrap();
+    let head_response = api::CustomHttpRequestApi::<DefaultNamespace>::finish_response(
+        &Method::HEAD,
+        execute_live(&head_request, &api_specification).await,
+    )
+    .await;
+
+    assert_eq!(head_response.status(), http::StatusCode::OK);
+    assert_eq!(
+        head_response.headers().get(http::header::CONTENT_LENGTH),
+        Some(&HeaderValue::from(get_body.len()))
+    );
+    assert!(head_response
+        .into_body()
+        .into_bytes()
+        .await
+        .unwrap()
+        .is_empty());
+}
+
 #[test]
 async fn test_end_to_end_api_gateway_with_request_path_and_query_lookup() {
     let empty_headers = HeaderMap::new();
@@ -653,6 +751,81 @@ fn get_api_spec_worker_binding(
     HttpApiDefinition::new(core_request, create_at)
 }
 
+fn get_api_spec_worker_binding_with_auto_options(
+    path_pattern: &str,
+    worker_name: &str,
+    rib_expression: &str,
+) -> HttpApiDefinition {
+    let yaml_string = format!(
+        r#"
+          id: users-api
+          version: 0.0.1
+          createdAt: 2024-08-21T07:42:15.696Z
+          routes:
+          - method: Get
+            path: {}
+            binding:
+              type: wit-worker
+              componentId:
+                componentId: 0b6d9cd8-f373-4e29-8a5a-548e61b868a5
+                version: 0
+              workerName: '{}'
+              response: '${{{}}}'
+              middleware:
+                autoOptions: true
+
+        "#,
+        path_pattern, worker_name, rib_expression
+    );
+
+    let http_api_definition_request: api::HttpApiDefinitionRequest =
+        serde_yaml::from_str(yaml_string.as_str()).unwrap();
+
+    let core_request: gateway_api_definition::http::HttpApiDefinitionRequest =
+        http_api_definition_request.try_into().unwrap();
+
+    let create_at: DateTime<Utc> = "2024-08-21T07:42:15.696Z".parse().unwrap();
+    HttpApiDefinition::new(core_request, create_at)
+}
+
+fn get_api_spec_worker_binding_with_auto_head(
+    path_pattern: &str,
+    worker_name: &str,
+    rib_expression: &str,
+) -> HttpApiDefinition {
+    let yaml_string = format!(
+        r#"
+          id: users-api
+          version: 0.0.1
+          createdAt: 2024-08-21T07:42:15.696Z
+          routes:
+          - method: Get
+            path: {}
+            binding:
+              type: wit-worker
+              componentId:
+                componentId: 0b6d9cd8-f373-4e29-8a5a-548e61b868a5
+                version: 0
+              workerName: '{}'
+              response: '${{{}}}'
+              middleware:
+                autoOptions: true
+                autoHead: true
+
+        "#,
+        path_pattern, worker_name, rib_expression
+    );
+
+    let http_api_definition_request: api::HttpApiDefinitionRequest =
+        serde_yaml::from_str(yaml_string.as_str()).unwrap();
+
+    let core_request: gateway_api_definition::http::HttpApiDefinitionRequest =
+        http_api_definition_request.try_into().unwrap();
+
+    let create_at: DateTime<Utc> = "2024-08-21T07:42:15.696Z".parse().unwrap();
+    HttpApiDefinition::new(core_request, create_at)
+}
+
 fn get_api_spec_cors_preflight_binding_default_response(path_pattern: &str) -> HttpApiDefinition {
     let yaml_string = format!(
         r#"
@@ -846,7 +1019,7 @@ mod internal {
     use http::header::{
         ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_HEADERS,
         ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_EXPOSE_HEADERS,
-        ACCESS_CONTROL_MAX_AGE,
+        ACCESS_CONTROL_MAX_AGE, ALLOW,
     };
     use rib::RibResult;
     use serde_json::Value;
@@ -896,6 +1069,9 @@ mod internal {
         cors_header_allow_methods: Option<String>,
         cors_header_allow_headers: Option<String>,
         cors_header_max_age: Option<u64>,
+        // Present only for auto OPTIONS responses
+        allow_header: Option<String>,
+        status: u16,
     }
 
     impl TestResponse {
@@ -926,6 +1102,10 @@ mod internal {
                 .get(ACCESS_CONTROL_ALLOW_CREDENTIALS)
                 .map(|x| x.to_str().unwrap().parse::<bool>().unwrap());
 
+            let allow_header = headers.get(ALLOW).map(|x| x.to_str().unwrap().to_string());
+
+            let status = response.status().as_u16();
+
             let bytes = response.into_body().into_bytes().await.ok();
 
             if let Some(bytes) = bytes {
@@ -953,6 +1133,8 @@ mod internal {
                     cors_header_allow_methods: allow_methods,
                     cors_header_allow_headers: allow_headers,
                     cors_header_max_age: max_age,
+                    allow_header,
+                    status,
                 }
             } else {
                 TestResponse {
@@ -965,6 +1147,8 @@ mod internal {
                     cors_header_allow_methods: allow_methods,
                     cors_header_allow_headers: allow_headers,
                     cors_header_max_age: max_age,
+                    allow_header,
+                    status,
                 }
             }
         }
@@ -993,6 +1177,14 @@ mod internal {
             self.cors_header_expose_headers.clone()
         }
 
+        pub fn get_allow_header(&self) -> Option<String> {
+            self.allow_header.clone()
+        }
+
+        pub fn get_status(&self) -> u16 {
+            self.status
+        }
+
         pub fn get_worker_name(&self) -> Option<String> {
             self.worker_name.clone()
         }
@@ -146,6 +146,9 @@ impl TestComponentService {
                 exports: Self::get_metadata(),
                 producers: vec![],
                 memories: vec![],
+                world: None,
+                analysis_warnings: vec![],
+                annotations: Default::default(),
             },
             created_at: Some(Utc::now()),
             component_type: None,
@@ -236,6 +239,7 @@ async fn test_services(
     test_delete_non_existing(definition_service.clone()).await;
     test_deployment(definition_service.clone(), deployment_service.clone()).await;
     test_deployment_conflict(definition_service.clone(), deployment_service.clone()).await;
+    test_repo_get_all_records(api_definition_repo.clone()).await;
 }
 
 async fn test_deployment(
@@ -720,6 +724,43 @@ async fn test_definition_crud(
     assert!(definitions.is_empty());
 }
 
+async fn test_repo_get_all_records(
+    api_definition_repo: Arc<dyn api_definition::ApiDefinitionRepo + Sync + Send>,
+) {
+    let id = Uuid::new_v4().to_string();
+    let record = |namespace: &str| api_definition::ApiDefinitionRecord {
+        namespace: namespace.to_string(),
+        id: id.clone(),
+        version: "0.0.1".to_string(),
+        draft: true,
+        data: vec![],
+        created_at: Utc::now(),
+    };
+
+    api_definition_repo.create(&record("ns1")).await.unwrap();
+    api_definition_repo.create(&record("ns2")).await.unwrap();
+
+    let mut namespaces: Vec<String> = api_definition_repo
+        .get_all_records()
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|r| r.id == id)
+        .map(|r| r.namespace)
+        .collect();
+    namespaces.sort();
+    assert_eq!(namespaces, vec!["ns1".to_string(), "ns2".to_string()]);
+
+    api_definition_repo
+        .delete("ns1", &id, "0.0.1")
+        .await
+        .unwrap();
+    api_definition_repo
+        .delete("ns2", &id, "0.0.1")
+        .await
+        .unwrap();
+}
+
 async fn test_delete_non_existing(
     definition_service: Arc<
         dyn ApiDefinitionService<EmptyAuthCtx, DefaultNamespace, RouteValidationError>
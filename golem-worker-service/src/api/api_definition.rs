@@ -5,6 +5,7 @@ use golem_service_base::auth::{DefaultNamespace, EmptyAuthCtx};
 use golem_worker_service_base::api::ApiEndpointError;
 use golem_worker_service_base::api::HttpApiDefinitionRequest;
 use golem_worker_service_base::api::HttpApiDefinitionWithTypeInfo;
+use golem_worker_service_base::api::RouteMiddlewares;
 use golem_worker_service_base::gateway_api_definition::http::CompiledHttpApiDefinition;
 use golem_worker_service_base::gateway_api_definition::http::HttpApiDefinitionRequest as CoreHttpApiDefinitionRequest;
 use golem_worker_service_base::gateway_api_definition::http::OpenApiDefinitionRequest;
@@ -198,6 +199,57 @@ impl RegisterApiDefinitionApi {
         record.result(response)
     }
 
+    /// Get the middlewares of an API definition
+    ///
+    /// Lists the middlewares of each route of the API definition in the order the gateway runs them,
+    /// each prefixed by the phase it runs in. Meant for debugging, the descriptions are not stable.
+    #[oai(
+        path = "/:id/:version/middlewares",
+        method = "get",
+        operation_id = "get_definition_middlewares"
+    )]
+    async fn get_middlewares(
+        &self,
+        id: Path<ApiDefinitionId>,
+        version: Path<ApiVersion>,
+    ) -> Result<Json<Vec<RouteMiddlewares>>, ApiEndpointError> {
+        let record = recorded_http_api_request!(
+            "get_definition_middlewares",
+            api_definition_id = id.0.to_string(),
+            version = version.0.to_string()
+        );
+
+        let response = {
+            let api_definition_id = id.0;
+
+            let api_version = version.0;
+
+            let data = self
+                .definition_service
+                .get(
+                    &api_definition_id,
+                    &api_version,
+                    &DefaultNamespace::default(),
+                    &EmptyAuthCtx::default(),
+                )
+                .instrument(record.span.clone())
+                .await?;
+
+            let definition = data.ok_or(ApiEndpointError::not_found(safe(format!(
+                "Can't find api definition with id {api_definition_id}, and version {api_version}"
+            ))))?;
+
+            let result = definition
+                .routes
+                .into_iter()
+                .map(RouteMiddlewares::from)
+                .collect();
+            Ok(Json(result))
+        };
+
+        record.result(response)
+    }
+
     /// Delete an API definition
     ///
     /// Deletes an API definition by its API definition ID and version.
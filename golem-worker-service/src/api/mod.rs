@@ -7,6 +7,9 @@ use crate::api::worker::WorkerApi;
 use crate::service::Services;
 use golem_worker_service_base::api::CustomHttpRequestApi;
 use golem_worker_service_base::api::HealthcheckApi;
+use golem_worker_service_base::app_config::{
+    ConcurrencyLimitConfig, PreflightCacheConfig, ResponseCacheConfig,
+};
 use poem::endpoint::PrometheusExporter;
 use poem::{get, EndpointExt, Route};
 use poem_openapi::OpenApiService;
@@ -41,11 +44,21 @@ pub fn combined_routes(prometheus_registry: Arc<Registry>, services: &Services)
         )
 }
 
-pub fn custom_request_route(services: Services) -> Route {
+pub fn custom_request_route(
+    services: Services,
+    max_preflight_request_headers_size: usize,
+    preflight_cache: &PreflightCacheConfig,
+    response_cache: &ResponseCacheConfig,
+    concurrency_limit: &ConcurrencyLimitConfig,
+) -> Route {
     let custom_request_executor = CustomHttpRequestApi::new(
         services.worker_to_http_service,
         services.http_definition_lookup_service,
         services.fileserver_binding_handler,
+        max_preflight_request_headers_size,
+        preflight_cache,
+        response_cache,
+        concurrency_limit,
     );
 
     Route::new().nest("/", custom_request_executor)
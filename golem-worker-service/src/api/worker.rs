@@ -765,6 +765,86 @@ impl WorkerApi {
 
         record.result(response)
     }
+
+    /// Get the compile status of a component version on each worker executor
+    ///
+    /// Returns the result of the last compilation of the component version on every worker
+    /// executor: whether it succeeded, the error if it failed, and how long the compilation took.
+    /// Executors which did not compile the version report no result.
+    #[oai(
+        path = "/:component_id/versions/:version/compile-status",
+        method = "get",
+        operation_id = "get_component_compile_status"
+    )]
+    async fn get_component_compile_status(
+        &self,
+        component_id: Path<ComponentId>,
+        version: Path<u64>,
+    ) -> Result<Json<ComponentCompileStatusResponse>> {
+        let record = recorded_http_api_request!(
+            "get_component_compile_status",
+            component_id = component_id.0.to_string(),
+            version = version.0.to_string()
+        );
+
+        let response = self
+            .worker_service
+            .get_compile_status(
+                &component_id.0,
+                version.0,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|executors| Json(ComponentCompileStatusResponse { executors }));
+
+        record.result(response)
+    }
+
+    /// Warm up a component version on each worker executor
+    ///
+    /// Loads the component version on every worker executor, compiling it where it is not cached
+    /// yet, and returns the result of its last compilation on each of them. With
+    /// `force-recompile`, the executors compile it from its WASM even if it is cached, and
+    /// overwrite the compiled artifact in the compiled component store, which replaces an artifact
+    /// suspected to be corrupt.
+    #[oai(
+        path = "/:component_id/versions/:version/warm",
+        method = "post",
+        operation_id = "warm_component"
+    )]
+    async fn warm_component(
+        &self,
+        component_id: Path<ComponentId>,
+        version: Path<u64>,
+        #[oai(name = "force-recompile")] force_recompile: Query<Option<bool>>,
+    ) -> Result<Json<ComponentCompileStatusResponse>> {
+        let force_recompile = force_recompile.0.unwrap_or(false);
+        let record = recorded_http_api_request!(
+            "warm_component",
+            component_id = component_id.0.to_string(),
+            version = version.0.to_string(),
+            force_recompile = force_recompile
+        );
+
+        let response = self
+            .worker_service
+            .warm_component(
+                &component_id.0,
+                version.0,
+                force_recompile,
+                empty_worker_metadata(),
+                &EmptyAuthCtx::default(),
+            )
+            .instrument(record.span.clone())
+            .await
+            .map_err(|e| e.into())
+            .map(|executors| Json(ComponentCompileStatusResponse { executors }));
+
+        record.result(response)
+    }
 }
 
 fn make_worker_id(
@@ -1242,6 +1242,17 @@ fn error_to_status(error: GrpcWorkerError) -> Status {
                 worker_execution_error::Error::FileSystemError(_) => {
                     "Failed accessing worker filesystem".to_string()
                 }
+                worker_execution_error::Error::ComponentSignatureInvalid(err) => format!(
+                    "Component Signature Invalid: Component ID = {:?}, Version: {}, Reason: {}",
+                    err.component_id, err.component_version, err.reason
+                ),
+                worker_execution_error::Error::ShuttingDown(_) => {
+                    "Worker Executor Shutting Down".to_string()
+                }
+                worker_execution_error::Error::MissingInitialFile(err) => format!(
+                    "Missing Initial File: Key = {}, Path: {}",
+                    err.key, err.path
+                ),
             };
             Status::internal(message)
         }
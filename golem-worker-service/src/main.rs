@@ -79,11 +79,29 @@ pub async fn app(
     let http_service1 = services.clone();
     let http_service2 = services.clone();
     let grpc_services = services.clone();
+    let gateway_compression = config.response_compression.clone();
+    let gateway_decompression = config.request_decompression.clone();
 
     let custom_request_server = tokio::spawn(async move {
-        let route = api::custom_request_route(http_service1)
-            .with(OpenTelemetryMetrics::new())
-            .with(Tracing);
+        let route = api::custom_request_route(
+            http_service1,
+            config.max_preflight_request_headers_size,
+            &config.preflight_cache,
+            &config.response_cache,
+            &config.concurrency_limit,
+        )
+        .with_if(
+            gateway_compression.is_enabled(),
+            gateway_compression.middleware(),
+        )
+        // Wraps the response compression so it runs first, as the compression middleware
+        // would otherwise decompress the request bodies itself, without a limit
+        .with_if(
+            gateway_decompression.is_enabled(),
+            gateway_decompression.middleware(),
+        )
+        .with(OpenTelemetryMetrics::new())
+        .with(Tracing);
 
         poem::Server::new(TcpListener::bind(("0.0.0.0", config.custom_request_port)))
             .name("gateway")
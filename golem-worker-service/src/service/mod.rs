@@ -29,7 +29,7 @@ use golem_worker_service_base::service::gateway::api_definition::{
 };
 use golem_worker_service_base::service::gateway::api_definition_validator::ApiDefinitionValidatorService;
 use golem_worker_service_base::service::gateway::http_api_definition_validator::{
-    HttpApiDefinitionValidator, RouteValidationError,
+    invalid_cors, HttpApiDefinitionValidator, RouteValidationError,
 };
 use golem_worker_service_base::service::worker::WorkerServiceDefault;
 
@@ -46,6 +46,7 @@ use golem_worker_service_base::service::gateway::api_deployment::{
 use std::sync::Arc;
 use std::time::Duration;
 use tonic::codec::CompressionEncoding;
+use tracing::error;
 
 #[derive(Clone)]
 pub struct Services {
@@ -150,6 +151,8 @@ impl Services {
             }
         };
 
+        report_invalid_stored_cors(api_definition_repo.as_ref()).await?;
+
         let blob_storage: Arc<dyn BlobStorage + Sync + Send> = match &config.blob_storage {
             BlobStorageConfig::S3(config) => Arc::new(
                 golem_service_base::storage::blob::s3::S3BlobStorage::new(config.clone()).await,
@@ -222,3 +225,42 @@ impl Services {
         })
     }
 }
+
+// Definitions registered before their CORS configurations were validated may be invalid.
+// The gateway skips the invalid headers of these, so they are only reported here to be fixed
+async fn report_invalid_stored_cors(
+    api_definition_repo: &(dyn api_definition::ApiDefinitionRepo + Sync + Send),
+) -> Result<(), String> {
+    let records = api_definition_repo
+        .get_all_records()
+        .await
+        .map_err(|e| format!("Failed to load API definitions: {e}"))?;
+
+    for record in records {
+        let (namespace, id, version) = (
+            record.namespace.clone(),
+            record.id.clone(),
+            record.version.clone(),
+        );
+        match HttpApiDefinition::try_from(record) {
+            Ok(definition) => {
+                for error in invalid_cors(&definition.routes) {
+                    error!(
+                        namespace = %namespace,
+                        api_definition_id = %id,
+                        version = %version,
+                        "Invalid CORS configuration in stored API definition: {error}"
+                    );
+                }
+            }
+            Err(e) => error!(
+                namespace = %namespace,
+                api_definition_id = %id,
+                version = %version,
+                "Failed to read stored API definition: {e}"
+            ),
+        }
+    }
+
+    Ok(())
+}